@@ -0,0 +1,166 @@
+//!
+//! Soft affinity between processes
+//!
+//! Processes spawned with the same [AffinityGroup] are preferentially run by the same worker, so
+//! that tightly-coupled processes (e.g. a producer and its consumer) share its caches.
+//!
+//! The affinity is soft: the group is placed on a worker the first time one of its processes is
+//! scheduled, and its processes are then queued on a run queue dedicated to the group's worker,
+//! which the other workers only steal from once they don't have anything else to run.
+use lightproc::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+// The worker of a group that wasn't placed yet.
+const UNPLACED: usize = usize::MAX;
+
+///
+/// A labelled group of processes which should preferentially run on the same worker.
+///
+/// # Example
+/// ```rust
+/// use bastion_executor::affinity::AffinityGroup;
+/// use bastion_executor::prelude::*;
+/// use lightproc::prelude::*;
+///
+/// let group = AffinityGroup::new("pipeline-a");
+///
+/// let producer = spawn_with_affinity(async { 42 }, ProcStack::default(), &group);
+/// let consumer = spawn_with_affinity(async { 24 }, ProcStack::default(), &group);
+///
+/// run(
+///     async {
+///         producer.await;
+///         consumer.await;
+///     },
+///     ProcStack::default(),
+/// );
+///
+/// assert_eq!(group.stats().scheduled(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AffinityGroup {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    label: String,
+    worker: AtomicUsize,
+    scheduled: AtomicUsize,
+    on_worker: AtomicUsize,
+    moved: AtomicUsize,
+}
+
+///
+/// Placement statistics of an [AffinityGroup]'s processes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AffinityStats {
+    worker: Option<usize>,
+    scheduled: usize,
+    on_worker: usize,
+    moved: usize,
+}
+
+///
+/// Process queued on the run queue dedicated to its group's worker.
+#[derive(Debug)]
+pub(crate) struct AffineProc {
+    pub(crate) proc: LightProc,
+    pub(crate) group: AffinityGroup,
+}
+
+impl AffinityGroup {
+    ///
+    /// Creates a new group with the given label, which isn't placed yet.
+    ///
+    /// The processes are only grouped with those spawned with a clone of the same group: the label
+    /// is only used to tell the groups apart (e.g. in logs).
+    pub fn new(label: &str) -> Self {
+        let inner = Inner {
+            label: label.to_string(),
+            worker: AtomicUsize::new(UNPLACED),
+            scheduled: AtomicUsize::new(0),
+            on_worker: AtomicUsize::new(0),
+            moved: AtomicUsize::new(0),
+        };
+
+        AffinityGroup {
+            inner: Arc::new(inner),
+        }
+    }
+
+    ///
+    /// Returns the label of the group.
+    pub fn label(&self) -> &str {
+        &self.inner.label
+    }
+
+    ///
+    /// Returns the placement statistics of the group's processes.
+    pub fn stats(&self) -> AffinityStats {
+        let worker = match self.inner.worker.load(Ordering::SeqCst) {
+            UNPLACED => None,
+            worker => Some(worker),
+        };
+
+        AffinityStats {
+            worker,
+            scheduled: self.inner.scheduled.load(Ordering::SeqCst),
+            on_worker: self.inner.on_worker.load(Ordering::SeqCst),
+            moved: self.inner.moved.load(Ordering::SeqCst),
+        }
+    }
+
+    ///
+    /// Returns the worker of the group, placing it on `worker` if it wasn't placed yet.
+    pub(crate) fn place(&self, worker: usize) -> usize {
+        match self.inner.worker.compare_exchange(
+            UNPLACED,
+            worker,
+            Ordering::SeqCst,
+            Ordering::SeqCst,
+        ) {
+            Ok(_) => worker,
+            Err(placed) => placed,
+        }
+    }
+
+    pub(crate) fn scheduled(&self) {
+        self.inner.scheduled.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub(crate) fn ran_on(&self, worker: usize) {
+        if self.inner.worker.load(Ordering::SeqCst) == worker {
+            self.inner.on_worker.fetch_add(1, Ordering::SeqCst);
+        } else {
+            self.inner.moved.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+impl AffinityStats {
+    ///
+    /// Returns the worker the group was placed on, if one of its processes was scheduled.
+    pub fn worker(&self) -> Option<usize> {
+        self.worker
+    }
+
+    ///
+    /// Returns the number of times the group's processes were scheduled.
+    pub fn scheduled(&self) -> usize {
+        self.scheduled
+    }
+
+    ///
+    /// Returns the number of times the group's processes were fetched by the group's worker.
+    pub fn on_worker(&self) -> usize {
+        self.on_worker
+    }
+
+    ///
+    /// Returns the number of times the group's processes were stolen by another worker.
+    pub fn moved(&self) -> usize {
+        self.moved
+    }
+}
@@ -0,0 +1,302 @@
+//!
+//! Deterministic scheduling of the processes
+//!
+//! Once enabled (before the pool is first used), a single worker runs all the processes, picking
+//! the next one among those ready to run using a pseudo-random generator seeded with the given
+//! seed. Running the same workload with the same seed then reproduces the same interleaving of
+//! processes, which helps reproducing the race conditions depending on it.
+//!
+//! The processes woken outside of the worker (by the threads which aren't part of the pool,
+//! blocking processes or timers) are only run once none of the processes woken by the worker
+//! are ready to run anymore and none of the other threads [hold] the scheduler, all of those
+//! woken since then being picked among using the same generator. The threads which wake several
+//! processes at once (e.g. to launch them) hold the scheduler until they're done, so that the
+//! interleaving is reproduced as long as the other threads interact with the processes in the
+//! same order, once those are waiting.
+//!
+//! Time is virtualized: once none of the processes are ready to run anymore, none were woken
+//! outside of the worker and the scheduler isn't held, the clock returned by [now] is advanced
+//! to the earliest deadline registered with [wake_at], waking the processes waiting for it
+//! right away. The timers which don't use this clock (e.g. those of `futures-timer`) keep on
+//! firing after their real delay.
+//!
+//! # Example
+//! ```rust
+//! use bastion_executor::deterministic;
+//! use bastion_executor::prelude::*;
+//! use lightproc::prelude::*;
+//!
+//! // Has to be done before the pool is used, otherwise the pool isn't deterministic.
+//! if deterministic::enable(42) {
+//!     assert_eq!(deterministic::seed(), Some(42));
+//! }
+//!
+//! let handle = spawn(async { 42 }, ProcStack::default());
+//! run(
+//!     async {
+//!         handle.await;
+//!     },
+//!     ProcStack::default(),
+//! );
+//! ```
+use crate::pool;
+use lazy_static::lazy_static;
+use lightproc::prelude::*;
+use std::collections::BTreeMap;
+use std::fmt::{self, Debug, Formatter};
+use std::mem;
+use std::sync::Mutex;
+use std::task::Waker;
+use std::time::{Duration, Instant};
+
+lazy_static! {
+    // The seed to use once the pool starts, and whether it started.
+    static ref MODE: Mutex<Mode> = Mutex::new(Mode::default());
+    // When the virtual clock started, and how far it was advanced since then.
+    static ref EPOCH: Instant = Instant::now();
+    static ref ADVANCED: Mutex<Duration> = Mutex::new(Duration::default());
+}
+
+#[derive(Debug, Default)]
+struct Mode {
+    seed: Option<u64>,
+    started: bool,
+}
+
+///
+/// Makes the pool schedule its processes deterministically using the given seed, returning
+/// whether it will, which is only the case if the pool wasn't used yet (and if no other seed
+/// was given).
+pub fn enable(seed: u64) -> bool {
+    let mut mode = MODE.lock().unwrap();
+    if mode.started {
+        return mode.seed == Some(seed);
+    }
+
+    mode.seed = Some(seed);
+    true
+}
+
+///
+/// Returns the seed with which the pool schedules its processes, if it does so
+/// deterministically.
+pub fn seed() -> Option<u64> {
+    MODE.lock().unwrap().seed
+}
+
+///
+/// Returns the current time, which is virtual if the pool schedules its processes
+/// deterministically (see the [module-level documentation](index.html)).
+pub fn now() -> Instant {
+    if seed().is_none() {
+        return Instant::now();
+    }
+
+    *EPOCH + *ADVANCED.lock().unwrap()
+}
+
+///
+/// Wakes `waker` once the virtual clock reaches `deadline`, returning whether it will, which is
+/// only the case if the pool schedules its processes deterministically.
+///
+/// Registering the same waker again replaces its previous deadline.
+pub fn wake_at(deadline: Instant, waker: Waker) -> bool {
+    let pool = pool::get();
+    match &pool.deterministic {
+        Some(scheduler) => {
+            scheduler.wake_at(deadline, waker);
+            pool.sleepers.notify_one();
+            true
+        }
+        None => false,
+    }
+}
+
+///
+/// Returns a pseudo-random number drawn from the generator picking the processes to run, if the
+/// pool schedules its processes deterministically, so that the processes relying on it behave
+/// the same way every time their interleaving is reproduced.
+pub fn random() -> Option<u64> {
+    seed()?;
+    pool::get().deterministic.as_ref().map(Scheduler::random)
+}
+
+///
+/// Holds the processes woken outside of the worker until the returned guard is dropped, if the
+/// pool schedules its processes deterministically, so that the processes woken by the current
+/// thread in the meantime are all picked among once it is done waking them.
+///
+/// The current thread mustn't wait for a process to run while holding the scheduler, as the
+/// process might only be woken once the scheduler is released.
+pub fn hold() -> Hold {
+    if seed().is_none() {
+        return Hold(false);
+    }
+
+    match &pool::get().deterministic {
+        Some(scheduler) => {
+            scheduler.inner.lock().unwrap().holds += 1;
+            Hold(true)
+        }
+        None => Hold(false),
+    }
+}
+
+///
+/// A guard returned by [hold], releasing the scheduler once dropped.
+#[must_use = "the scheduler is released as soon as the guard is dropped"]
+#[derive(Debug)]
+pub struct Hold(bool);
+
+impl Drop for Hold {
+    fn drop(&mut self) {
+        if !self.0 {
+            return;
+        }
+
+        let pool = pool::get();
+        if let Some(scheduler) = &pool.deterministic {
+            scheduler.inner.lock().unwrap().holds -= 1;
+            pool.sleepers.notify_one();
+        }
+    }
+}
+
+///
+/// Advances the virtual clock to `deadline`, unless it already reached it.
+fn advance(deadline: Instant) {
+    let offset = deadline.saturating_duration_since(*EPOCH);
+    let mut advanced = ADVANCED.lock().unwrap();
+    if offset > *advanced {
+        *advanced = offset;
+    }
+}
+
+///
+/// Marks the pool as started, returning the seed it has to use from now on.
+pub(crate) fn start() -> Option<u64> {
+    let mut mode = MODE.lock().unwrap();
+    mode.started = true;
+    mode.seed
+}
+
+///
+/// The processes to run on the pool's single worker, when it is deterministic.
+pub(crate) struct Scheduler {
+    inner: Mutex<Inner>,
+}
+
+struct Inner {
+    rng: Rng,
+    // The processes to run, picked using `rng`.
+    ready: Vec<LightProc>,
+    // The processes woken outside of the worker, which are moved to `ready` once nothing else
+    // is and the scheduler isn't held anymore.
+    woken: Vec<LightProc>,
+    // How many guards returned by `hold` are alive.
+    holds: usize,
+    // The wakers to wake once the virtual clock reaches their deadline.
+    timers: BTreeMap<Instant, Vec<Waker>>,
+}
+
+impl Scheduler {
+    pub(crate) fn new(seed: u64) -> Self {
+        let inner = Inner {
+            rng: Rng::new(seed),
+            ready: Vec::new(),
+            woken: Vec::new(),
+            holds: 0,
+            timers: BTreeMap::new(),
+        };
+
+        Scheduler {
+            inner: Mutex::new(inner),
+        }
+    }
+
+    pub(crate) fn schedule(&self, proc: LightProc, from_worker: bool) {
+        let mut inner = self.inner.lock().unwrap();
+        if from_worker {
+            inner.ready.push(proc);
+        } else {
+            inner.woken.push(proc);
+        }
+    }
+
+    pub(crate) fn wake_at(&self, deadline: Instant, waker: Waker) {
+        let mut inner = self.inner.lock().unwrap();
+        for wakers in inner.timers.values_mut() {
+            wakers.retain(|registered| !registered.will_wake(&waker));
+        }
+
+        inner.timers.retain(|_, wakers| !wakers.is_empty());
+        inner.timers.entry(deadline).or_default().push(waker);
+    }
+
+    pub(crate) fn random(&self) -> u64 {
+        self.inner.lock().unwrap().rng.next()
+    }
+
+    pub(crate) fn fetch(&self) -> Option<LightProc> {
+        let mut inner = self.inner.lock().unwrap();
+        loop {
+            let len = inner.ready.len();
+            if len > 0 {
+                let picked = inner.rng.next() % len as u64;
+                return Some(inner.ready.swap_remove(picked as usize));
+            }
+
+            // Another thread is still waking processes.
+            if inner.holds > 0 {
+                return None;
+            }
+
+            if !inner.woken.is_empty() {
+                inner.ready = mem::take(&mut inner.woken);
+                continue;
+            }
+
+            // Nothing is left to run until the earliest timer expires.
+            let (deadline, wakers) = inner.timers.pop_first()?;
+            advance(deadline);
+            drop(inner);
+            for waker in wakers {
+                waker.wake();
+            }
+
+            inner = self.inner.lock().unwrap();
+        }
+    }
+}
+
+impl Debug for Scheduler {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        let inner = self.inner.lock().unwrap();
+        fmt.debug_struct("Scheduler")
+            .field("ready", &inner.ready.len())
+            .field("woken", &inner.woken.len())
+            .field("timers", &inner.timers.len())
+            .finish()
+    }
+}
+
+///
+/// A xorshift64* generator, seeded using splitmix64 (so that close seeds give unrelated
+/// sequences, and a zero seed doesn't get the generator stuck).
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        Rng((z ^ (z >> 31)) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+}
@@ -32,8 +32,10 @@
 #[macro_use]
 mod macros;
 
+pub mod affinity;
 pub mod allocator;
 pub mod blocking;
+pub mod deterministic;
 pub mod distributor;
 pub mod load_balancer;
 pub mod placement;
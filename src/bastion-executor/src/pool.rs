@@ -4,6 +4,8 @@
 //! Pool management and tracking belongs here.
 //! We spawn futures onto the pool with [spawn] method of global run queue or
 //! with corresponding [Worker]'s spawn method.
+use crate::affinity::{AffineProc, AffinityGroup};
+use crate::deterministic::Scheduler;
 use crate::distributor::Distributor;
 use crate::run_queue::{Injector, Stealer};
 use crate::sleepers::Sleepers;
@@ -11,6 +13,10 @@ use crate::worker;
 use lazy_static::lazy_static;
 use lightproc::prelude::*;
 use std::future::Future;
+#[cfg(feature = "testing")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "testing")]
+use std::sync::Arc;
 
 ///
 /// Spawn a process (which contains future + process stack) onto the executor from the global level.
@@ -45,6 +51,99 @@ where
     self::get().spawn(future, stack)
 }
 
+///
+/// Spawn a process (which contains future + process stack) onto the executor from the global level,
+/// scheduling it on the queue dedicated to its [ProcClass].
+///
+/// # Example
+/// ```rust
+/// use bastion_executor::prelude::*;
+/// use lightproc::prelude::*;
+///
+/// let stack = ProcStack::default();
+///
+/// let handle = spawn_with_class(
+///     async {
+///         println!("managing other procs...");
+///     },
+///     stack.clone(),
+///     ProcClass::Management,
+/// );
+///
+/// run(
+///     async {
+///         handle.await;
+///     },
+///     stack.clone(),
+/// );
+/// ```
+pub fn spawn_with_class<F, T>(future: F, stack: ProcStack, class: ProcClass) -> RecoverableHandle<T>
+where
+    F: Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    self::get().spawn_with_class(future, stack, class)
+}
+
+///
+/// Spawn a process (which contains future + process stack) onto the executor from the global level,
+/// preferentially running it on the same worker as the other processes of its [AffinityGroup].
+///
+/// # Example
+/// ```rust
+/// use bastion_executor::affinity::AffinityGroup;
+/// use bastion_executor::prelude::*;
+/// use lightproc::prelude::*;
+///
+/// let stack = ProcStack::default();
+/// let group = AffinityGroup::new("pipeline-a");
+///
+/// let handle = spawn_with_affinity(
+///     async {
+///         println!("running next to the other procs of the group...");
+///     },
+///     stack.clone(),
+///     &group,
+/// );
+///
+/// run(
+///     async {
+///         handle.await;
+///     },
+///     stack.clone(),
+/// );
+/// ```
+pub fn spawn_with_affinity<F, T>(
+    future: F,
+    stack: ProcStack,
+    group: &AffinityGroup,
+) -> RecoverableHandle<T>
+where
+    F: Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    self::get().spawn_with_affinity(future, stack, group)
+}
+
+///
+/// Class of a process, telling the executor which of its queues the process is scheduled on.
+///
+/// Management and timer processes are expected to be short-lived. They are kept on small
+/// dedicated queues, which workers service before their own run queues, up to a bounded
+/// share, so that a burst of them doesn't delay other workloads for long.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcClass {
+    ///
+    /// Processes running the actual workloads, scheduled on the workers' run queues.
+    Exec,
+    ///
+    /// Processes managing other processes (launching, tearing down, supervising...).
+    Management,
+    ///
+    /// Processes waiting for timers to fire.
+    Timer,
+}
+
 ///
 /// Pool that global run queue, stealers of the workers, and parked threads.
 #[derive(Debug)]
@@ -53,17 +152,70 @@ pub struct Pool {
     /// Global run queue implementation
     pub(crate) injector: Injector<LightProc>,
     ///
+    /// Run queue dedicated to management processes
+    pub(crate) management: Injector<LightProc>,
+    ///
+    /// Run queue dedicated to timer processes
+    pub(crate) timers: Injector<LightProc>,
+    ///
+    /// Run queues dedicated to the processes of the affinity groups placed on each worker
+    pub(crate) affine: Vec<Injector<AffineProc>>,
+    ///
     /// Stealers of the workers
     pub(crate) stealers: Vec<Stealer<LightProc>>,
     ///
     /// Container of parked threads
     pub(crate) sleepers: Sleepers,
+    ///
+    /// Processes of the pool's single worker, if it schedules them deterministically
+    pub(crate) deterministic: Option<Scheduler>,
+    ///
+    /// Number of processes spawned on the pool whose future wasn't dropped yet
+    #[cfg(feature = "testing")]
+    pub(crate) live: Arc<AtomicUsize>,
+}
+
+#[cfg(feature = "testing")]
+///
+/// Counts a process as live until its future is dropped (once it completed, panicked or
+/// was cancelled).
+struct Live(Arc<AtomicUsize>);
+
+#[cfg(feature = "testing")]
+impl Live {
+    fn new(live: &Arc<AtomicUsize>) -> Self {
+        live.fetch_add(1, Ordering::SeqCst);
+        Live(live.clone())
+    }
+}
+
+#[cfg(feature = "testing")]
+impl Drop for Live {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 
 impl Pool {
     ///
     /// Spawn a process (which contains future + process stack) onto the executor via [Pool] interface.
     pub fn spawn<F, T>(&self, future: F, stack: ProcStack) -> RecoverableHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.spawn_with_class(future, stack, ProcClass::Exec)
+    }
+
+    ///
+    /// Spawn a process (which contains future + process stack) onto the executor via [Pool] interface,
+    /// scheduling it on the queue dedicated to its [ProcClass].
+    pub fn spawn_with_class<F, T>(
+        &self,
+        future: F,
+        stack: ProcStack,
+        class: ProcClass,
+    ) -> RecoverableHandle<T>
     where
         F: Future<Output = T> + Send + 'static,
         T: Send + 'static,
@@ -71,11 +223,66 @@ impl Pool {
         // Log this `spawn` operation.
         let _child_id = stack.get_pid() as u64;
         let _parent_id = worker::get_proc_stack(|t| t.get_pid() as u64).unwrap_or(0);
+        let future = self.counted(future);
 
-        let (task, handle) = LightProc::recoverable(future, worker::schedule, stack);
+        let (task, handle) = match class {
+            ProcClass::Exec => LightProc::recoverable(future, worker::schedule, stack),
+            ProcClass::Management => {
+                LightProc::recoverable(future, worker::schedule_management, stack)
+            }
+            ProcClass::Timer => LightProc::recoverable(future, worker::schedule_timer, stack),
+        };
         task.schedule();
         handle
     }
+
+    ///
+    /// Spawn a process (which contains future + process stack) onto the executor via [Pool] interface,
+    /// preferentially running it on the same worker as the other processes of its [AffinityGroup].
+    pub fn spawn_with_affinity<F, T>(
+        &self,
+        future: F,
+        stack: ProcStack,
+        group: &AffinityGroup,
+    ) -> RecoverableHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let group = group.clone();
+        let schedule = move |proc| worker::schedule_affine(proc, &group);
+        let future = self.counted(future);
+
+        let (task, handle) = LightProc::recoverable(future, schedule, stack);
+        task.schedule();
+        handle
+    }
+
+    #[cfg(feature = "testing")]
+    ///
+    /// Returns the number of processes spawned on the pool that are still live, i.e. whose
+    /// future wasn't dropped yet because it didn't complete, panic or get cancelled (or
+    /// because the executor didn't run it again since it was cancelled).
+    pub fn live_procs(&self) -> usize {
+        self.live.load(Ordering::SeqCst)
+    }
+
+    #[cfg(feature = "testing")]
+    fn counted<F>(&self, future: F) -> impl Future<Output = F::Output>
+    where
+        F: Future,
+    {
+        let live = Live::new(&self.live);
+        async move {
+            let _live = live;
+            future.await
+        }
+    }
+
+    #[cfg(not(feature = "testing"))]
+    fn counted<F>(&self, future: F) -> F {
+        future
+    }
 }
 
 ///
@@ -84,13 +291,25 @@ impl Pool {
 pub fn get() -> &'static Pool {
     lazy_static! {
         static ref POOL: Pool = {
-            let distributor = Distributor::new();
+            let seed = crate::deterministic::start();
+            let mut distributor = Distributor::new();
+            if seed.is_some() {
+                distributor.cores.truncate(1);
+            }
+
             let stealers = distributor.assign();
+            let affine = stealers.iter().map(|_| Injector::new()).collect();
 
             Pool {
                 injector: Injector::new(),
+                management: Injector::new(),
+                timers: Injector::new(),
+                affine,
                 stealers,
                 sleepers: Sleepers::new(),
+                deterministic: seed.map(Scheduler::new),
+                #[cfg(feature = "testing")]
+                live: Arc::new(AtomicUsize::new(0)),
             }
         };
     }
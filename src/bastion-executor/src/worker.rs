@@ -3,9 +3,10 @@
 //!
 //! This worker implementation relies on worker run queue statistics which are hold in the pinned global memory
 //! where workload distribution calculated and amended to their own local queues.
+use crate::affinity::{AffineProc, AffinityGroup};
 use crate::load_balancer;
 use crate::pool::{self, Pool};
-use crate::run_queue::{Steal, Worker};
+use crate::run_queue::{Injector, Steal, Worker};
 use lightproc::prelude::*;
 use load_balancer::SmpStats;
 use std::cell::{Cell, UnsafeCell};
@@ -59,7 +60,51 @@ thread_local! {
     static QUEUE: UnsafeCell<Option<Worker<LightProc>>> = UnsafeCell::new(None);
 }
 
+///
+/// Maximum number of management and timer processes a worker runs in a row
+/// before fetching a process from the regular run queues again.
+const PRIORITIZED_SHARE: usize = 4;
+
+thread_local! {
+    static PRIORITIZED_STREAK: Cell<usize> = const { Cell::new(0) };
+}
+
+///
+/// Affinity of the threads which aren't workers.
+const NO_AFFINITY: usize = usize::MAX;
+
+thread_local! {
+    static AFFINITY: Cell<usize> = const { Cell::new(NO_AFFINITY) };
+}
+
+///
+/// Returns whether the current thread is one of the pool's workers, which must not be blocked
+/// waiting for another process (that it might have to run itself).
+pub fn is_worker() -> bool {
+    AFFINITY.with(|affinity| affinity.get() != NO_AFFINITY)
+}
+
+///
+/// Schedules the process on the deterministic scheduler, if the pool uses one, returning it
+/// otherwise.
+fn schedule_deterministic(proc: LightProc) -> Option<LightProc> {
+    let pool = pool::get();
+    match &pool.deterministic {
+        Some(scheduler) => {
+            scheduler.schedule(proc, is_worker());
+            pool.sleepers.notify_one();
+            None
+        }
+        None => Some(proc),
+    }
+}
+
 pub(crate) fn schedule(proc: LightProc) {
+    let proc = match schedule_deterministic(proc) {
+        Some(proc) => proc,
+        None => return,
+    };
+
     QUEUE.with(|queue| {
         let local = unsafe { (*queue.get()).as_ref() };
 
@@ -72,18 +117,132 @@ pub(crate) fn schedule(proc: LightProc) {
     pool::get().sleepers.notify_one();
 }
 
+///
+/// Schedules the process on the run queue dedicated to its affinity group's worker, placing the
+/// group on the current worker (or on the least loaded one, outside of the workers) if it wasn't
+/// placed yet.
+pub(crate) fn schedule_affine(proc: LightProc, group: &AffinityGroup) {
+    let pool = pool::get();
+    if pool.deterministic.is_some() {
+        return schedule(proc);
+    }
+
+    let current = AFFINITY.with(Cell::get);
+    let worker = match current {
+        NO_AFFINITY => {
+            let sorted_load = load_balancer::stats().get_sorted_load();
+            let least_loaded = sorted_load.last().map_or(0, |(core, _)| *core);
+            group.place(least_loaded)
+        }
+        current => group.place(current),
+    };
+
+    let queue = match pool.affine.get(worker) {
+        Some(queue) => queue,
+        None => return schedule(proc),
+    };
+
+    group.scheduled();
+    let group = group.clone();
+    queue.push(AffineProc { proc, group });
+
+    // The current worker fetches the process once the one it is running yields, so waking
+    // another one would only make it steal the process.
+    if worker != current {
+        pool.sleepers.notify_one();
+    }
+}
+
+pub(crate) fn schedule_management(proc: LightProc) {
+    let proc = match schedule_deterministic(proc) {
+        Some(proc) => proc,
+        None => return,
+    };
+
+    pool::get().management.push(proc);
+    pool::get().sleepers.notify_one();
+}
+
+pub(crate) fn schedule_timer(proc: LightProc) {
+    let proc = match schedule_deterministic(proc) {
+        Some(proc) => proc,
+        None => return,
+    };
+
+    pool::get().timers.push(proc);
+    pool::get().sleepers.notify_one();
+}
+
 ///
 /// Fetch the process from the run queue.
 /// Does the work of work-stealing if process doesn't exist in the local run queue.
+///
+/// Management and timer processes are fetched first, unless this worker
+/// already ran its share of them in a row.
+///
+/// If the pool schedules its processes deterministically, they are fetched from its
+/// deterministic scheduler.
 pub fn fetch_proc(affinity: usize) -> Option<LightProc> {
     let pool = pool::get();
+    if let Some(scheduler) = &pool.deterministic {
+        return scheduler.fetch();
+    }
 
-    QUEUE.with(|queue| {
-        let local = unsafe { (*queue.get()).as_ref().unwrap() };
-        local.pop().or_else(|| affine_steal(pool, local, affinity))
+    PRIORITIZED_STREAK.with(|streak| {
+        if streak.get() < PRIORITIZED_SHARE {
+            if let Some(proc) = prioritized_steal(pool) {
+                streak.set(streak.get() + 1);
+                return Some(proc);
+            }
+        }
+
+        streak.set(0);
+        QUEUE.with(|queue| {
+            let local = unsafe { (*queue.get()).as_ref().unwrap() };
+            local
+                .pop()
+                .or_else(|| {
+                    pool.affine
+                        .get(affinity)
+                        .and_then(|q| affine_pop(q, affinity))
+                })
+                .or_else(|| affine_steal(pool, local, affinity))
+                // Procs of the affinity groups placed on other workers are only stolen
+                // once there isn't anything else to run.
+                .or_else(|| {
+                    pool.affine
+                        .iter()
+                        .enumerate()
+                        .filter(|(worker, _)| *worker != affinity)
+                        .find_map(|(_, q)| affine_pop(q, affinity))
+                })
+        })
     })
 }
 
+///
+/// Fetch a process from the run queue dedicated to the affinity groups of a worker, counting
+/// it as run by the worker with the given affinity.
+fn affine_pop(queue: &Injector<AffineProc>, affinity: usize) -> Option<LightProc> {
+    iter::repeat_with(|| queue.steal())
+        .find(|s| !s.is_retry())
+        .and_then(|s| s.success())
+        .map(|affine| {
+            affine.group.ran_on(affinity);
+            affine.proc
+        })
+}
+
+fn prioritized_steal(pool: &Pool) -> Option<LightProc> {
+    fn steal(injector: &Injector<LightProc>) -> Option<LightProc> {
+        iter::repeat_with(|| injector.steal())
+            .find(|s| !s.is_retry())
+            .and_then(|s| s.success())
+    }
+
+    steal(&pool.timers).or_else(|| steal(&pool.management))
+}
+
 fn affine_steal(pool: &Pool, local: &Worker<LightProc>, affinity: usize) -> Option<LightProc> {
     let load_mean = load_balancer::stats().mean();
     // Pop a task from the local queue, if not empty.
@@ -93,35 +252,45 @@ fn affine_steal(pool: &Pool, local: &Worker<LightProc>, affinity: usize) -> Opti
             let core_vec = load_balancer::stats().get_sorted_load();
 
             // First try to get procs from global queue
-            pool.injector.steal_batch_and_pop(&local).or_else(|| {
-                match core_vec.get(0) {
-                    Some((core, _)) => {
-                        // If affinity is the one with the highest let other's do the stealing
-                        if *core == affinity {
-                            Steal::Retry
-                        } else {
-                            // Try iterating through biggest to smallest
-                            core_vec
-                                .iter()
-                                .map(|s| {
-                                    // Steal the mean amount to balance all queues considering incoming workloads
-                                    // Otherwise do an ignorant steal (which is going to be useless)
-                                    if load_mean > 0 {
-                                        pool.stealers
-                                            .get(s.0)
-                                            .unwrap()
-                                            .steal_batch_and_pop_with_amount(&local, load_mean)
-                                    } else {
-                                        pool.stealers.get(s.0).unwrap().steal_batch_and_pop(&local)
-                                        // TODO: Set evacuation flag in thread_local
-                                    }
-                                })
-                                .collect()
+            pool.injector
+                .steal_batch_and_pop(&local)
+                .or_else(|| {
+                    match core_vec.get(0) {
+                        Some((core, _)) => {
+                            // If affinity is the one with the highest let other's do the stealing
+                            // (retrying here would make this worker spin while every queue is
+                            // empty, instead of going to sleep).
+                            if *core == affinity {
+                                Steal::Empty
+                            } else {
+                                // Try iterating through biggest to smallest
+                                core_vec
+                                    .iter()
+                                    .map(|s| {
+                                        // Steal the mean amount to balance all queues considering incoming workloads
+                                        // Otherwise do an ignorant steal (which is going to be useless)
+                                        if load_mean > 0 {
+                                            pool.stealers
+                                                .get(s.0)
+                                                .unwrap()
+                                                .steal_batch_and_pop_with_amount(&local, load_mean)
+                                        } else {
+                                            pool.stealers
+                                                .get(s.0)
+                                                .unwrap()
+                                                .steal_batch_and_pop(&local)
+                                            // TODO: Set evacuation flag in thread_local
+                                        }
+                                    })
+                                    .collect()
+                            }
                         }
+                        _ => Steal::Empty,
                     }
-                    _ => Steal::Retry,
-                }
-            })
+                })
+                // Nothing else to do, the share doesn't matter anymore. This also
+                // needs to be checked while retrying, as we might spin here.
+                .or_else(|| prioritized_steal(pool).map_or(Steal::Empty, Steal::Success))
         })
         // Loop while no task was stolen and any steal operation needs to be retried.
         .find(|s| !s.is_retry())
@@ -136,6 +305,7 @@ pub(crate) fn stats_generator(affinity: usize, local: &Worker<LightProc>) {
 
 pub(crate) fn main_loop(affinity: usize, local: Worker<LightProc>) {
     QUEUE.with(|queue| unsafe { *queue.get() = Some(local) });
+    AFFINITY.with(|current| current.set(affinity));
 
     loop {
         QUEUE.with(|queue| {
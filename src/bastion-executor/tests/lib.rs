@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use bastion_executor::{placement, pool};
+    use bastion_executor::{placement, pool, worker};
 
     #[test]
     fn affinity_replacement() {
@@ -12,4 +12,41 @@ mod tests {
     fn pool_check() {
         pool::get();
     }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn live_procs() {
+        use bastion_executor::prelude::*;
+        use lightproc::proc_stack::ProcStack;
+        use std::thread;
+        use std::time::{Duration, Instant};
+
+        let pool = pool::get();
+        let live = pool.live_procs();
+        let handle = spawn(futures::future::pending::<()>(), ProcStack::default());
+        assert_eq!(pool.live_procs(), live + 1);
+
+        // The proc is only dropped once the executor runs it again.
+        handle.cancel();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while pool.live_procs() != live {
+            assert!(Instant::now() < deadline, "Timed out.");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn timer_procs() {
+        use bastion_executor::pool::ProcClass;
+        use bastion_executor::run::run;
+        use lightproc::proc_stack::ProcStack;
+
+        let handle = pool::spawn_with_class(async { 42 }, ProcStack::default(), ProcClass::Timer);
+        assert_eq!(run(handle, ProcStack::default()), Some(42));
+    }
+
+    #[test]
+    fn not_a_worker() {
+        assert!(!worker::is_worker());
+    }
 }
@@ -0,0 +1,86 @@
+#![feature(test)]
+
+extern crate test;
+
+use bastion::prelude::*;
+use std::sync::Once;
+use test::Bencher;
+
+static START: Once = Once::new();
+
+// Answers the questions it receives with their content.
+fn pong(children: Children) -> Children {
+    children.with_exec(|ctx: BastionContext| async move {
+        loop {
+            msg! { ctx.recv().await?,
+                msg: u64 =!> {
+                    answer!(ctx, msg).unwrap();
+                };
+                _: _ => ();
+            }
+        }
+    })
+}
+
+// Forwards the questions it receives to `pong`'s element before
+// answering them with its answer.
+fn ping(children: Children, pong: ChildRef) -> Children {
+    children.with_exec(move |ctx: BastionContext| {
+        let pong = pong.clone();
+        async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    msg: u64 =!> {
+                        let answer = pong.ask_anonymously(msg).unwrap().await?;
+                        msg! { answer,
+                            msg: u64 => {
+                                answer!(ctx, msg).unwrap();
+                            };
+                            _: _ => ();
+                        }
+                    };
+                    _: _ => ();
+                }
+            }
+        }
+    })
+}
+
+// Creates a `ping` and a `pong` group, in the affinity group
+// labelled `affinity` if there's one.
+fn ping_pong(affinity: Option<&'static str>) -> ChildRef {
+    let with_affinity = move |children: Children| match affinity {
+        Some(label) => children.with_affinity_group(label),
+        None => children,
+    };
+
+    let pong = Bastion::children(move |children| pong(with_affinity(children))).unwrap();
+    let pong = pong.elems()[0].clone();
+    let ping = Bastion::children(move |children| ping(with_affinity(children), pong)).unwrap();
+
+    ping.elems()[0].clone()
+}
+
+fn bench_ping_pong(b: &mut Bencher, affinity: Option<&'static str>) {
+    START.call_once(|| {
+        Bastion::init();
+        Bastion::start();
+    });
+
+    let elem = ping_pong(affinity);
+
+    b.iter(|| {
+        let answer = elem.ask_anonymously(42u64).unwrap();
+        run!(answer).unwrap();
+    });
+}
+
+#[bench]
+fn ping_pong_without_affinity(b: &mut Bencher) {
+    bench_ping_pong(b, None);
+}
+
+#[bench]
+fn ping_pong_with_affinity(b: &mut Bencher) {
+    bench_ping_pong(b, Some("ping-pong"));
+}
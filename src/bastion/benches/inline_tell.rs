@@ -0,0 +1,74 @@
+#![feature(test)]
+
+extern crate test;
+
+use bastion::prelude::*;
+use std::sync::Once;
+use test::Bencher;
+
+static START: Once = Once::new();
+
+const PINGS: u64 = 100;
+
+#[derive(Debug, Clone, Copy)]
+// As small as a `u64`, but boxed when it is told.
+struct Boxed(u64);
+
+// Answers a question once it was told `PINGS` messages built by
+// `ping`, telling itself the next one each time it receives one.
+fn ping_pong<M: Message>(ping: fn(u64) -> M, pinged: fn(&Msg) -> Option<u64>) -> ChildRef {
+    let children = Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| async move {
+            loop {
+                let question = ctx.recv().await?;
+                ctx.current().tell_anonymously(ping(0)).unwrap();
+                loop {
+                    let (msg, _) = ctx.recv().await?.extract();
+                    match pinged(&msg) {
+                        Some(n) if n == PINGS => break,
+                        Some(n) => ctx.current().tell_anonymously(ping(n + 1)).unwrap(),
+                        None => (),
+                    }
+                }
+
+                msg! { question,
+                    _msg: () =!> {
+                        answer!(ctx, ()).unwrap();
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    children.elems()[0].clone()
+}
+
+fn bench_ping_pong(b: &mut Bencher, elem: ChildRef) {
+    b.iter(|| {
+        let answer = elem.ask_anonymously(()).unwrap();
+        run!(answer).unwrap();
+    });
+}
+
+fn init_start() {
+    START.call_once(|| {
+        Bastion::init();
+        Bastion::start();
+    });
+}
+
+#[bench]
+fn inline_u64(b: &mut Bencher) {
+    init_start();
+    let elem = ping_pong(|n| n, |msg| msg.peek::<u64>().copied());
+    bench_ping_pong(b, elem);
+}
+
+#[bench]
+fn boxed_u64(b: &mut Bencher) {
+    init_start();
+    let elem = ping_pong(Boxed, |msg| msg.peek::<Boxed>().map(|boxed| boxed.0));
+    bench_ping_pong(b, elem);
+}
@@ -0,0 +1,78 @@
+#![feature(test)]
+
+extern crate test;
+
+use bastion::prelude::*;
+use futures_timer::Delay;
+use std::sync::Once;
+use std::time::Duration;
+use test::Bencher;
+
+static START: Once = Once::new();
+
+// Number of elements continuously panicking (and thus being
+// restarted) while the latency of a healthy element is measured.
+const STORM_REDUNDANCY: usize = 8;
+// How long each of those elements lives before panicking.
+const STORM_LIFETIME: Duration = Duration::from_millis(1);
+
+fn healthy_children() -> ChildrenRef {
+    Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    msg: u64 =!> {
+                        answer!(ctx, msg).unwrap();
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+    })
+    .unwrap()
+}
+
+fn start_restart_storm() {
+    Bastion::children(|children| {
+        children
+            .with_redundancy(STORM_REDUNDANCY)
+            .with_exec(|_: BastionContext| async move {
+                Delay::new(STORM_LIFETIME).await;
+                panic!("Restart storm.");
+            })
+    })
+    .unwrap();
+}
+
+#[bench]
+fn ask_latency(b: &mut Bencher) {
+    START.call_once(|| {
+        Bastion::init();
+        Bastion::start();
+    });
+
+    let healthy = healthy_children();
+    let elem = healthy.elems()[0].clone();
+
+    b.iter(|| {
+        let answer = elem.ask_anonymously(42u64).unwrap();
+        run!(answer).unwrap();
+    });
+}
+
+#[bench]
+fn ask_latency_during_restart_storm(b: &mut Bencher) {
+    START.call_once(|| {
+        Bastion::init();
+        Bastion::start();
+    });
+
+    let healthy = healthy_children();
+    let elem = healthy.elems()[0].clone();
+    start_restart_storm();
+
+    b.iter(|| {
+        let answer = elem.ask_anonymously(42u64).unwrap();
+        run!(answer).unwrap();
+    });
+}
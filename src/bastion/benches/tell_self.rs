@@ -0,0 +1,71 @@
+#![feature(test)]
+
+extern crate test;
+
+use bastion::prelude::*;
+use std::sync::Once;
+use test::Bencher;
+
+static START: Once = Once::new();
+
+const LOOPS: u64 = 100;
+
+// Loops `LOOPS` times through its mailbox when it is asked a
+// question, telling itself the next step using `tell` before
+// answering it.
+fn looping(tell: fn(&BastionContext, u64)) -> ChildRef {
+    let children = Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| async move {
+            loop {
+                let question = ctx.recv().await?;
+                tell(&ctx, 0);
+                loop {
+                    msg! { ctx.recv().await?,
+                        step: u64 => {
+                            if step == LOOPS {
+                                break;
+                            }
+
+                            tell(&ctx, step + 1);
+                        };
+                        _: _ => ();
+                    }
+                }
+
+                msg! { question,
+                    _msg: () =!> {
+                        answer!(ctx, ()).unwrap();
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    children.elems()[0].clone()
+}
+
+fn bench_looping(b: &mut Bencher, tell: fn(&BastionContext, u64)) {
+    START.call_once(|| {
+        Bastion::init();
+        Bastion::start();
+    });
+
+    let elem = looping(tell);
+
+    b.iter(|| {
+        let answer = elem.ask_anonymously(()).unwrap();
+        run!(answer).unwrap();
+    });
+}
+
+#[bench]
+fn tell_self(b: &mut Bencher) {
+    bench_looping(b, |ctx, step| ctx.tell_self(step).unwrap());
+}
+
+#[bench]
+fn tell_through_child_ref(b: &mut Bencher) {
+    bench_looping(b, |ctx, step| ctx.current().tell_anonymously(step).unwrap());
+}
@@ -26,7 +26,9 @@ fn sp(supervisor: Supervisor) -> Supervisor {
             println!("(sp      ) after_stop");
         });
 
-    let children_ref = supervisor.children_ref(sp_ch);
+    let children_ref = supervisor
+        .children_ref(sp_ch)
+        .expect("Couldn't create the children group.");
 
     supervisor
         .supervisor(|sp| sp_sp(sp, children_ref))
@@ -1,4 +1,6 @@
-use bastion::prelude::*;
+// This example only uses the functions and types exported by
+// `prelude_v2`, without any of Bastion's macros.
+use bastion::prelude_v2::*;
 
 #[macro_use]
 extern crate log;
@@ -18,11 +20,8 @@ fn fib(n: usize) -> usize {
 // in the real world
 fn deserialize_into_fib_command(message: String) -> (String, usize) {
     let arguments: Vec<&str> = message.split(' ').collect();
-    let command = arguments
-        .first()
-        .map(|s| s.to_string())
-        .unwrap_or(String::new());
-    let number = usize::from_str_radix(arguments.get(1).unwrap_or(&"0"), 10).unwrap_or(0);
+    let command = arguments.first().map(|s| s.to_string()).unwrap_or_default();
+    let number = arguments.get(1).unwrap_or(&"0").parse().unwrap_or(0);
     (command, number)
 }
 
@@ -30,35 +29,46 @@ fn deserialize_into_fib_command(message: String) -> (String, usize) {
 // A child will wait for a message, and try to process it.
 async fn fib_child_task(ctx: BastionContext) -> Result<(), ()> {
     loop {
-        // This macro is weird.
-        // Bear with me as I tell you more
-        // about the variants in the match statements below.
-        // so the first line means "wait for the next message", and then lets match against it
-        msg! { ctx.recv().await?,
-            // =!> refer to messages that can be replied to.
-            // In order to reply to a message, we use the answer! macro
+        // This waits for the next message, and returns it as a
+        // `TypedMsg` if it's a `String`, or as it was received
+        // otherwise.
+        match ctx.recv_typed::<String>().await {
+            // `Ask`s are messages that can be replied to.
+            // In order to reply to a message, we use its sender
             // and pass ctx as parameter, so that bastion knows where to send the reply
-            request: String =!> {
-                let (command, number) = deserialize_into_fib_command(request);
+            Ok((TypedMsg::Ask { msg, sender }, _)) => {
+                let (command, number) = deserialize_into_fib_command(msg);
                 if command == "fib" {
-                    answer!(ctx, format!("{}", fib(number))).expect("couldn't reply :(");
+                    sender
+                        .answer(&ctx, format!("{}", fib(number)))
+                        .expect("couldn't reply :(");
                 } else {
-                    answer!(ctx, "I'm sorry I didn't understand the task I was supposed to do").expect("couldn't reply :(");
+                    sender
+                        .answer(
+                            &ctx,
+                            "I'm sorry I didn't understand the task I was supposed to do",
+                        )
+                        .expect("couldn't reply :(");
                 }
-            };
-            // ref <name> are broadcasts.
-            // each child in a children group receives a broadcast and can process it.
-            ref broadcast: String => {
+            }
+            // `Broadcast`s are shared by each child in a children group,
+            // which receives a broadcast and can process it.
+            Ok((TypedMsg::Broadcast(broadcast), _)) => {
                 info!("received broadcast: {:?}", *broadcast);
-            };
-            // <name> (without the ref keyword) are messages that have a unique recipient.
-            message: String => {
+            }
+            // `Tell`s are messages that have a unique recipient.
+            Ok((TypedMsg::Tell(message), _)) => {
                 info!("someone told me something: {}", message);
-            };
-            // <name> that have the `_` type are catch alls
-            unknown:_ => {
-                error!("uh oh, I received a message I didn't understand\n {:?}", unknown);
-            };
+            }
+            // The messages of other types are returned as they were
+            // received.
+            Err(unknown) => {
+                let (unknown, _) = unknown.extract();
+                error!(
+                    "uh oh, I received a message I didn't understand: {}",
+                    unknown.type_name()
+                );
+            }
         }
     }
 }
@@ -70,15 +80,13 @@ async fn request(child: &ChildRef, body: String) -> std::io::Result<String> {
     let answer = child
         .ask_anonymously(body)
         .expect("couldn't perform request");
-    Ok(msg! { answer.await.expect("couldn't receive answer"),
-        reply: String => {
-            reply.to_string()
-        };
-        unknown:_ => {
-            error!("uh oh, I received a message I didn't understand: {:?}", unknown);
-            "".to_string()
-        };
-    })
+    match answer.await_typed::<String>().await {
+        Ok(reply) => Ok(reply),
+        Err(err) => {
+            error!("uh oh, I received an answer I didn't understand: {}", err);
+            Ok("".to_string())
+        }
+    }
 }
 
 // RUST_LOG=info cargo run --example fibonacci
@@ -109,10 +117,10 @@ fn main() {
             .expect("Couldn't whisper to child.");
 
         let now = std::time::Instant::now();
-        // by using run!, we are blocking.
-        // we could have used spawn! instead,
+        // by using run, we are blocking.
+        // we could have used spawn instead,
         // to run everything in parallel.
-        let fib_reply = run!(request(child, format!("fib {}", fib_to_compute)))
+        let fib_reply = run(request(child, format!("fib {}", fib_to_compute)))
             .expect("send_command_to_child failed");
 
         println!(
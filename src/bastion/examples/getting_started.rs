@@ -118,7 +118,7 @@ fn main() {
         .expect("Couldn't send the message.");
     let _ = async {
         // ...until the child eventually answers back...
-        let _answer: Result<SignedMessage, ()> = answer.await;
+        let _answer: Result<SignedMessage, AnswerError> = answer.await;
     };
 
     // ...and then even stop or kill it...
@@ -1,4 +1,6 @@
-use bastion::prelude::*;
+// This example only uses the functions and types exported by
+// `prelude_v2`, without any of the macros.
+use bastion::prelude_v2::*;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
@@ -35,24 +37,16 @@ fn main() {
                     .ask(&ctx.current().addr(), "Hello World!")
                     .expect("Couldn't send the message.");
 
-                msg! { ctx.recv().await?,
-                    msg: &'static str =!> {
-                        println!(r#"msg == "Hello World!" => {}"#, msg == "Hello World!"); // true
-                        let _ = answer!(ctx, "Goodbye!");
-                    };
-                    // This won't happen because this example
-                    // only "asks" a `&'static str`...
-                    _: _ => ();
+                // The messages of other types would be rejected, but
+                // this example only "asks" a `&'static str`...
+                if let (TypedMsg::Ask { msg, sender }, _) = ctx.recv_msg::<&'static str>().await {
+                    println!(r#"msg == "Hello World!" => {}"#, msg == "Hello World!"); // true
+                    let _ = sender.answer(&ctx, "Goodbye!");
                 }
 
-                msg! { answer.await?,
-                    msg: &'static str => {
-                        println!(r#"msg == "Goodbye!" => {}"#, msg == "Goodbye!"); // true
-                    };
-                    // This won't happen because this example
-                    // only answers a `&'static str`...
-                    _: _ => ();
-                }
+                // ...and answers a `&'static str`.
+                let msg: &'static str = answer.await_typed().await?;
+                println!(r#"msg == "Goodbye!" => {}"#, msg == "Goodbye!"); // true
 
                 // Panicking will restart the children group.
                 panic!("Oh no!");
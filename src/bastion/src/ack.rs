@@ -0,0 +1,182 @@
+//!
+//! Acknowledgements allow to know once every element of a
+//! children group applied a message broadcasted using
+//! [`ChildrenRef::broadcast_acked`], rather than once they
+//! received it, by calling [`BastionContext::ack`] with the
+//! [`AckToken`] it was sent with.
+//!
+//! [`ChildrenRef::broadcast_acked`]: ../children_ref/struct.ChildrenRef.html#method.broadcast_acked
+//! [`BastionContext::ack`]: ../context/struct.BastionContext.html#method.ack
+//! [`AckToken`]: struct.AckToken.html
+use crate::child_ref::ChildRef;
+use crate::context::BastionId;
+use crate::errors::SendErrorKind;
+use crate::reactor::{self, Sleep};
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures::future::{self, Either};
+use futures::StreamExt;
+use fxhash::FxHashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+/// The token a message broadcasted using
+/// [`ChildrenRef::broadcast_acked`] is sent with (see
+/// [`SignedMessage::ack_token`]), which the elements pass to
+/// [`BastionContext::ack`] once they applied the message.
+///
+/// [`ChildrenRef::broadcast_acked`]: ../children_ref/struct.ChildrenRef.html#method.broadcast_acked
+/// [`SignedMessage::ack_token`]: ../envelope/struct.SignedMessage.html#method.ack_token
+/// [`BastionContext::ack`]: ../context/struct.BastionContext.html#method.ack
+pub struct AckToken(Arc<Acks>);
+
+#[derive(Debug)]
+struct Acks {
+    acked: Mutex<FxHashSet<BastionId>>,
+    // Notifies the tracker waiting for the acknowledgements.
+    sender: UnboundedSender<()>,
+    // Whether the tracker stopped waiting, the acknowledgements
+    // being ignored from then on.
+    done: AtomicBool,
+}
+
+#[derive(Debug)]
+// Waits for the acknowledgements sent using its token, which stop
+// being tracked once it is dropped.
+pub(crate) struct AckTracker {
+    token: AckToken,
+    notified: UnboundedReceiver<()>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// The elements which acknowledged a message broadcasted using
+/// [`ChildrenRef::broadcast_acked`], and those which didn't
+/// before its timeout expired, when it resolved.
+///
+/// [`ChildrenRef::broadcast_acked`]: ../children_ref/struct.ChildrenRef.html#method.broadcast_acked
+pub struct AckReport {
+    acked: Vec<BastionId>,
+    pending: Vec<BastionId>,
+    dead: Vec<BastionId>,
+    send: Option<SendErrorKind>,
+}
+
+impl AckToken {
+    pub(crate) fn ack(&self, id: &BastionId) {
+        if self.is_done() {
+            return;
+        }
+
+        // FIXME: panics?
+        self.0.acked.lock().unwrap().insert(id.clone());
+        self.0.sender.unbounded_send(()).ok();
+    }
+
+    pub(crate) fn is_acked(&self, id: &BastionId) -> bool {
+        // FIXME: panics?
+        self.0.acked.lock().unwrap().contains(id)
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        self.0.done.load(Ordering::SeqCst)
+    }
+}
+
+impl AckTracker {
+    pub(crate) fn new() -> Self {
+        let (sender, notified) = mpsc::unbounded();
+        let acks = Acks {
+            acked: Mutex::default(),
+            sender,
+            done: AtomicBool::new(false),
+        };
+
+        AckTracker {
+            token: AckToken(Arc::new(acks)),
+            notified,
+        }
+    }
+
+    pub(crate) fn token(&self) -> &AckToken {
+        &self.token
+    }
+
+    // Waits until all of `elems` acknowledged the message or the
+    // timeout expired.
+    pub(crate) async fn wait(mut self, elems: Vec<ChildRef>, timeout: Duration) -> AckReport {
+        let mut expired = Sleep::new(reactor::now() + timeout);
+        while !elems.iter().all(|elem| self.token.is_acked(elem.id())) {
+            match future::select(self.notified.next(), &mut expired).await {
+                Either::Left((Some(()), _)) => (),
+                Either::Left((None, _)) => unreachable!(),
+                Either::Right(_) => break,
+            }
+        }
+
+        AckReport::new(&elems, &self.token, None)
+    }
+}
+
+impl Drop for AckTracker {
+    fn drop(&mut self) {
+        self.token.0.done.store(true, Ordering::SeqCst);
+    }
+}
+
+impl AckReport {
+    // Sorts `elems` depending on whether they acknowledged the
+    // message sent with `token` and are still running.
+    pub(crate) fn new(elems: &[ChildRef], token: &AckToken, send: Option<SendErrorKind>) -> Self {
+        let mut report = AckReport {
+            acked: Vec::new(),
+            pending: Vec::new(),
+            dead: Vec::new(),
+            send,
+        };
+
+        for elem in elems {
+            let id = elem.id().clone();
+            if token.is_acked(&id) {
+                report.acked.push(id);
+            } else if elem.sender().is_closed() {
+                report.dead.push(id);
+            } else {
+                report.pending.push(id);
+            }
+        }
+
+        report
+    }
+
+    /// Returns whether every element acknowledged the message.
+    pub fn is_complete(&self) -> bool {
+        self.pending.is_empty() && self.dead.is_empty() && self.send.is_none()
+    }
+
+    /// Returns the identifiers of the elements which acknowledged
+    /// the message (including those which did once restarted).
+    pub fn acked(&self) -> &[BastionId] {
+        &self.acked
+    }
+
+    /// Returns the identifiers of the elements which are running
+    /// but didn't acknowledge the message before the timeout
+    /// expired.
+    pub fn pending(&self) -> &[BastionId] {
+        &self.pending
+    }
+
+    /// Returns the identifiers of the elements which stopped
+    /// without acknowledging the message (or were being restarted
+    /// once the timeout expired).
+    pub fn dead(&self) -> &[BastionId] {
+        &self.dead
+    }
+
+    /// Returns why the message couldn't be sent to the group, if
+    /// it couldn't (e.g. because the group stopped).
+    pub fn send_error(&self) -> Option<&SendErrorKind> {
+        self.send.as_ref()
+    }
+}
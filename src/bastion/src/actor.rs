@@ -0,0 +1,302 @@
+//!
+//! A trait-based API to define the elements of a children group
+//! as actors handling one message at a time, instead of futures
+//! looping over the messages they receive (see
+//! [`Children::with_actor`]).
+//!
+//! [`Children::with_actor`]: ../children/struct.Children.html#method.with_actor
+use crate::context::BastionContext;
+use crate::dead_letters::{self, DeadLetterReason, DroppedMsg};
+use crate::envelope::SignedMessage;
+use crate::errors::ActorError;
+use crate::message::{Message, Msg};
+use crate::reactor::{self, Sleep};
+use futures::future::{self, Either};
+use std::future::Future;
+use std::time::Duration;
+
+/// An actor whose state lives in the `struct` implementing this
+/// trait, run by the elements of a children group that was given
+/// a closure creating it (see [`Children::with_actor`]).
+///
+/// Its methods can be implemented as `async fn`s, as long as the
+/// futures they return are `Send`.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// struct Counter {
+///     count: u64,
+/// }
+///
+/// impl Actor for Counter {
+///     async fn handle(&mut self, _: &BastionContext, msg: SignedMessage) -> Result<Reply, ActorError> {
+///         msg! { msg,
+///             // Told an amount to increment the counter by...
+///             by: u64 => self.count += by;
+///             // ...or asked for the current count.
+///             _question: &'static str => return Ok(Reply::new(self.count));
+///             _: _ => ();
+///         }
+///
+///         Ok(Reply::none())
+///     }
+/// }
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// Bastion::children(|children| children.with_actor(|| Counter { count: 0 }))
+///     .expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`Children::with_actor`]: ../children/struct.Children.html#method.with_actor
+pub trait Actor: Send + 'static {
+    /// Called once the element running the actor started (or was
+    /// restarted), before it handles any message.
+    ///
+    /// If it returns an error, the element faults (and is restarted
+    /// depending on its supervisor's strategy). By default, this
+    /// does nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of the element running the actor.
+    fn started(
+        &mut self,
+        ctx: &BastionContext,
+    ) -> impl Future<Output = Result<(), ActorError>> + Send {
+        let _ = ctx;
+        async { Ok(()) }
+    }
+
+    /// Handles a message received by the element running the
+    /// actor, which can be matched using [`msg!`], returning the
+    /// [`Reply`] answering it if it was asked (which is ignored if
+    /// it was told or broadcasted).
+    ///
+    /// The message is handed over without its answer's sender, so
+    /// the `=!>` arms of [`msg!`] don't match it and the replies
+    /// are only sent by the element once this method returned: a
+    /// message asked that isn't given an answer makes its sender
+    /// get an error.
+    ///
+    /// If it returns an error, the element faults (and is restarted
+    /// depending on its supervisor's strategy).
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of the element running the actor.
+    /// * `msg` - The message that was received.
+    ///
+    /// [`msg!`]: ../macro.msg.html
+    /// [`Reply`]: struct.Reply.html
+    fn handle(
+        &mut self,
+        ctx: &BastionContext,
+        msg: SignedMessage,
+    ) -> impl Future<Output = Result<Reply, ActorError>> + Send;
+
+    /// Called once the element running the actor was asked to stop
+    /// and handled the messages left in its mailbox, or once one of
+    /// the actor's methods returned an error (before the element
+    /// faults), right before the actor is dropped.
+    ///
+    /// It isn't called if the element is killed or panicked, since
+    /// its future is then dropped without being polled anymore. By
+    /// default, this does nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of the element running the actor.
+    fn stopped(&mut self, ctx: &BastionContext) -> impl Future<Output = ()> + Send {
+        let _ = ctx;
+        async {}
+    }
+}
+
+#[derive(Debug, Default)]
+/// The answer an [`Actor`] returns from [`Actor::handle`], which is
+/// sent to the sender of the message it handled if it was asked.
+///
+/// [`Actor`]: trait.Actor.html
+/// [`Actor::handle`]: trait.Actor.html#tymethod.handle
+pub struct Reply(Option<Msg>);
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// What happens to a message that an actor took too long to handle
+/// once its handling was cancelled (see
+/// [`Children::with_message_timeout`]).
+///
+/// [`Children::with_message_timeout`]: ../children/struct.Children.html#method.with_message_timeout
+pub enum MessageTimeoutAction {
+    /// The message is sent to the dead letters with
+    /// [`DeadLetterReason::TimedOut`], and the actor goes on
+    /// handling the next message.
+    ///
+    /// [`DeadLetterReason::TimedOut`]: ../dead_letters/enum.DeadLetterReason.html#variant.TimedOut
+    SkipAndDeadLetter,
+    /// The message is sent to the dead letters like with
+    /// `SkipAndDeadLetter`, and the element faults (and is restarted
+    /// depending on its supervisor's strategy).
+    FaultElement,
+    /// The message is handled again, at most `attempts` more times,
+    /// before being skipped like with `SkipAndDeadLetter`.
+    ///
+    /// A message can't be handled again if the actor took it out of
+    /// the [`SignedMessage`] it was handed (e.g. by matching it
+    /// using [`msg!`] without `ref`), since it is then dropped along
+    /// with its handling: it is skipped right away.
+    ///
+    /// [`SignedMessage`]: ../prelude/struct.SignedMessage.html
+    /// [`msg!`]: ../macro.msg.html
+    Retry {
+        /// How many times a message is handled again.
+        attempts: usize,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+// How long an actor can take to handle a message, and what happens
+// once it took too long.
+pub(crate) struct MessageTimeout {
+    timeout: Duration,
+    action: MessageTimeoutAction,
+}
+
+impl Reply {
+    /// Creates a reply answering `answer`.
+    ///
+    /// # Arguments
+    ///
+    /// * `answer` - The answer to send.
+    pub fn new<M: Message>(answer: M) -> Self {
+        Reply(Some(Msg::tell(answer)))
+    }
+
+    /// Creates a reply which doesn't answer anything.
+    pub fn none() -> Self {
+        Reply(None)
+    }
+}
+
+// Runs `actor` until the element is asked to stop or an error is
+// returned by one of its methods, calling its `stopped` method
+// either way.
+pub(crate) async fn run<A: Actor>(mut actor: A, ctx: BastionContext) -> Result<(), ActorError> {
+    let res = run_actor(&mut actor, &ctx).await;
+    actor.stopped(&ctx).await;
+    res
+}
+
+async fn run_actor<A: Actor>(actor: &mut A, ctx: &BastionContext) -> Result<(), ActorError> {
+    actor.started(ctx).await?;
+    let shutdown = ctx.shutdown_token();
+    loop {
+        // The messages left in the mailbox are handled before
+        // stopping.
+        let mut msg = match future::select(Box::pin(ctx.recv()), shutdown.clone()).await {
+            Either::Left((msg, _)) => msg?,
+            Either::Right(((), _)) => {
+                ctx.stop_waiting().await;
+                return Ok(());
+            }
+        };
+
+        let sender = msg.msg.take_sender();
+        let reply = match ctx.message_timeout() {
+            Some(timeout) => handle_within(actor, ctx, msg, timeout).await?,
+            None => actor.handle(ctx, msg).await?,
+        };
+
+        if let (Some(sender), Reply(Some(answer))) = (sender, reply) {
+            if sender.send_msg(answer, ctx.signature()).is_err() {
+                debug!(
+                    "BastionContext({}): The answer's recipient stopped waiting for it.",
+                    ctx.current().id()
+                );
+            }
+        }
+    }
+}
+
+// Lets `actor` handle `msg` until it is done or `timeout` expires,
+// applying its action each time it does.
+async fn handle_within<A: Actor>(
+    actor: &mut A,
+    ctx: &BastionContext,
+    msg: SignedMessage,
+    timeout: MessageTimeout,
+) -> Result<Reply, ActorError> {
+    let dropped = DroppedMsg::new(&msg);
+    let type_name = msg.msg.type_name();
+    let correlation_id = msg.msg.provenance_entry().correlation_id().copied();
+    let mut attempts = match timeout.action {
+        MessageTimeoutAction::Retry { attempts } => attempts,
+        _ => 0,
+    };
+
+    let mut msg = Some(msg);
+    while let Some(SignedMessage { msg: handled, sign }) = msg.take() {
+        // The message is kept until its handling is done, to be
+        // handled again if it timed out.
+        let (lent, reclaim) = handled.lend();
+        let lent = SignedMessage {
+            msg: lent,
+            sign: sign.clone(),
+        };
+
+        let started = reactor::now();
+        let handling = Box::pin(actor.handle(ctx, lent));
+        let expired = Sleep::new(started + timeout.timeout);
+        if let Either::Left((res, _)) = future::select(handling, expired).await {
+            return res;
+        }
+
+        let reason = DeadLetterReason::TimedOut {
+            type_name,
+            correlation_id,
+            elapsed: reactor::now().saturating_duration_since(started),
+        };
+        if attempts > 0 {
+            msg = reclaim.reclaim().map(|msg| SignedMessage { msg, sign });
+        }
+
+        if msg.is_some() {
+            debug!(
+                "BastionContext({}): Retrying message ({:?}).",
+                ctx.current().id(),
+                reason
+            );
+            attempts -= 1;
+            continue;
+        }
+
+        warn!(
+            "BastionContext({}): Skipping message ({:?}).",
+            ctx.current().id(),
+            reason
+        );
+        dead_letters::bounce_dropped(dropped, Some(ctx.current().id()), reason);
+        if timeout.action == MessageTimeoutAction::FaultElement {
+            return Err(ActorError::new(reason));
+        }
+
+        return Ok(Reply::none());
+    }
+
+    Ok(Reply::none())
+}
+
+impl MessageTimeout {
+    pub(crate) fn new(timeout: Duration, action: MessageTimeoutAction) -> Self {
+        MessageTimeout { timeout, action }
+    }
+}
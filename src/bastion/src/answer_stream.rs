@@ -0,0 +1,137 @@
+//!
+//! Streamed answers allow an element to reply several times to
+//! a message sent using [`ChildRef::ask_stream`] (e.g. to send
+//! the partial results of a long-running query), using the
+//! [`AnswerStreamSender`] it was sent with, before closing the
+//! [`AnswerStream`] its sender receives them from.
+//!
+//! [`ChildRef::ask_stream`]: ../child_ref/struct.ChildRef.html#method.ask_stream
+//! [`AnswerStreamSender`]: struct.AnswerStreamSender.html
+//! [`AnswerStream`]: struct.AnswerStream.html
+use crate::errors::{AnswerError, SendError};
+use crate::message::{Message, Msg};
+use futures::channel::mpsc::{self, Receiver, Sender};
+use futures::future;
+use futures::Stream;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+// The number of replies that can be waiting to be received from a
+// stream before `AnswerStreamSender::reply` waits for some of them
+// to be.
+const BUFFER: usize = 16;
+
+#[derive(Debug)]
+/// The handle a message sent using [`ChildRef::ask_stream`] is
+/// sent with, allowing its recipient to reply to it several times
+/// (see [`SignedMessage::take_stream_sender`]).
+///
+/// If it is dropped without being closed (e.g. because the
+/// element faulted), the [`AnswerStream`] ends with an
+/// [`AnswerError::Unanswered`].
+///
+/// [`ChildRef::ask_stream`]: ../child_ref/struct.ChildRef.html#method.ask_stream
+/// [`SignedMessage::take_stream_sender`]: ../envelope/struct.SignedMessage.html#method.take_stream_sender
+/// [`AnswerStream`]: struct.AnswerStream.html
+/// [`AnswerError::Unanswered`]: ../errors/enum.AnswerError.html#variant.Unanswered
+pub struct AnswerStreamSender {
+    sender: Sender<Msg>,
+    closed: Arc<AtomicBool>,
+}
+
+#[derive(Debug)]
+/// A [`Stream`] of the replies to a message sent using
+/// [`ChildRef::ask_stream`], which ends once its recipient closed
+/// the [`AnswerStreamSender`] it was sent with, or with an
+/// [`AnswerError::Unanswered`] if it dropped it without closing it.
+///
+/// [`Stream`]: https://docs.rs/futures/0.3/futures/stream/trait.Stream.html
+/// [`ChildRef::ask_stream`]: ../child_ref/struct.ChildRef.html#method.ask_stream
+/// [`AnswerStreamSender`]: struct.AnswerStreamSender.html
+/// [`AnswerError::Unanswered`]: ../errors/enum.AnswerError.html#variant.Unanswered
+pub struct AnswerStream {
+    recver: Receiver<Msg>,
+    closed: Arc<AtomicBool>,
+    done: bool,
+}
+
+// Returns a sender of replies and the stream receiving them.
+pub(crate) fn channel() -> (AnswerStreamSender, AnswerStream) {
+    let (sender, recver) = mpsc::channel(BUFFER);
+    let closed = Arc::new(AtomicBool::new(false));
+
+    let sender = AnswerStreamSender {
+        sender,
+        closed: closed.clone(),
+    };
+    let stream = AnswerStream {
+        recver,
+        closed,
+        done: false,
+    };
+
+    (sender, stream)
+}
+
+impl AnswerStreamSender {
+    /// Sends a reply to the [`AnswerStream`] of the message this
+    /// sender was sent with, waiting until it is receiving them if
+    /// 16 replies weren't received yet.
+    ///
+    /// This method returns `()` if it succeeded, or a
+    /// [`SendError`](../errors/struct.SendError.html) carrying the
+    /// reply otherwise (if the stream was dropped).
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The reply to send.
+    ///
+    /// [`AnswerStream`]: struct.AnswerStream.html
+    pub async fn reply<M: Message>(&mut self, msg: M) -> Result<(), SendError<M>> {
+        debug!("{:?}: Replying: {:?}", self, msg);
+        let sender = &mut self.sender;
+        if future::poll_fn(|ctx| sender.poll_ready(ctx)).await.is_err() {
+            return Err(SendError::stopped(msg));
+        }
+
+        self.sender.try_send(Msg::tell(msg)).map_err(|err| {
+            // FIXME: panics?
+            SendError::stopped(err.into_inner().try_unwrap().unwrap())
+        })
+    }
+
+    /// Ends the [`AnswerStream`] of the message this sender was
+    /// sent with, once the replies sent beforehand are received.
+    ///
+    /// [`AnswerStream`]: struct.AnswerStream.html
+    pub fn close(self) {
+        debug!("{:?}: Closing.", self);
+        self.closed.store(true, Ordering::SeqCst);
+    }
+}
+
+impl Stream for AnswerStream {
+    type Item = Result<Msg, AnswerError>;
+
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Option<Self::Item>> {
+        let stream = self.get_mut();
+        if stream.done {
+            return Poll::Ready(None);
+        }
+
+        match Pin::new(&mut stream.recver).poll_next(ctx) {
+            Poll::Ready(Some(msg)) => Poll::Ready(Some(Ok(msg))),
+            Poll::Ready(None) => {
+                stream.done = true;
+                if stream.closed.load(Ordering::SeqCst) {
+                    Poll::Ready(None)
+                } else {
+                    Poll::Ready(Some(Err(AnswerError::Unanswered)))
+                }
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
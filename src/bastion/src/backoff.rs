@@ -0,0 +1,161 @@
+//!
+//! Back-offs delaying the restarts of the children groups'
+//! elements that keep faulting (see [`Children::with_restart_backoff`]
+//! and [`Supervisor::with_restart_backoff`]), so that an element
+//! depending on an unavailable resource doesn't hammer it.
+//!
+//! [`Children::with_restart_backoff`]: ../children/struct.Children.html#method.with_restart_backoff
+//! [`Supervisor::with_restart_backoff`]: ../supervisor/struct.Supervisor.html#method.with_restart_backoff
+use bastion_executor::deterministic;
+use std::time::Duration;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq)]
+/// How long the restart of an element that faulted is delayed,
+/// depending on how many times it faulted in a row.
+///
+/// The first restart is delayed by `min`, and each of the
+/// following consecutive ones by twice the previous delay, up to
+/// `max`. The element's restarts aren't consecutive anymore once
+/// it ran for longer than the back-off's reset threshold (see
+/// [`with_reset_after`]) before faulting, in which case its next
+/// restart is delayed by `min` again.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// # use std::time::Duration;
+/// #
+/// // Waits 100ms, 200ms, 400ms... up to 10s between the restarts,
+/// // shortening each delay by up to 10%.
+/// let backoff = Backoff::exponential(
+///     Duration::from_millis(100),
+///     Duration::from_secs(10),
+///     0.1,
+/// )
+/// .with_reset_after(Duration::from_secs(60));
+/// ```
+///
+/// [`with_reset_after`]: #method.with_reset_after
+pub struct Backoff {
+    min: Duration,
+    max: Duration,
+    jitter: f64,
+    reset_after: Duration,
+}
+
+impl Backoff {
+    /// Creates a back-off doubling the delay of each consecutive
+    /// restart, from `min` up to `max`, whose reset threshold is
+    /// `max` (see [`with_reset_after`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - The delay of the first restart.
+    /// * `max` - The maximum delay of a restart.
+    /// * `jitter` - The fraction of each delay (between `0.0` and
+    ///     `1.0`) by which it is randomly shortened, so that the
+    ///     elements faulting together aren't restarted together.
+    ///
+    /// [`with_reset_after`]: #method.with_reset_after
+    pub fn exponential(min: Duration, max: Duration, jitter: f64) -> Self {
+        let jitter = if jitter.is_nan() {
+            0.0
+        } else {
+            jitter.clamp(0.0, 1.0)
+        };
+
+        Backoff {
+            min,
+            max: max.max(min),
+            jitter,
+            reset_after: max.max(min),
+        }
+    }
+
+    /// Sets for how long an element must run before faulting for
+    /// its next restart to be delayed by the back-off's minimum
+    /// delay again.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - How long an element must run for its
+    ///     restarts not to be consecutive anymore.
+    pub fn with_reset_after(mut self, threshold: Duration) -> Self {
+        self.reset_after = threshold;
+        self
+    }
+
+    /// Returns the delay of the first restart.
+    pub fn min(&self) -> Duration {
+        self.min
+    }
+
+    /// Returns the maximum delay of a restart.
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// Returns the fraction of each delay by which it is randomly
+    /// shortened.
+    pub fn jitter(&self) -> f64 {
+        self.jitter
+    }
+
+    /// Returns for how long an element must run for its restarts
+    /// not to be consecutive anymore.
+    pub fn reset_after(&self) -> Duration {
+        self.reset_after
+    }
+
+    /// Returns the delay of an element's restart after it was
+    /// already restarted `attempt` times in a row, before applying
+    /// the jitter.
+    ///
+    /// # Arguments
+    ///
+    /// * `attempt` - The number of consecutive restarts that
+    ///     happened before this one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// let backoff = Backoff::exponential(
+    ///     Duration::from_millis(100),
+    ///     Duration::from_millis(300),
+    ///     0.0,
+    /// );
+    ///
+    /// assert_eq!(backoff.delay(0), Duration::from_millis(100));
+    /// assert_eq!(backoff.delay(1), Duration::from_millis(200));
+    /// assert_eq!(backoff.delay(2), Duration::from_millis(300));
+    /// ```
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.checked_pow(attempt).unwrap_or(u32::MAX);
+        self.min
+            .checked_mul(factor)
+            .map_or(self.max, |delay| delay.min(self.max))
+    }
+
+    // Returns the delay of an element's restart after it was
+    // restarted `attempt` times in a row, shortened by a random
+    // fraction of the jitter (drawn from the seeded generator of the
+    // deterministic scheduler, if it is used).
+    pub(crate) fn jittered_delay(&self, attempt: u32) -> Duration {
+        let delay = self.delay(attempt);
+        if self.jitter == 0.0 {
+            return delay;
+        }
+
+        let random = deterministic::random().unwrap_or_else(|| Uuid::new_v4().as_u128() as u64);
+        let random = random as f64 / u64::MAX as f64;
+        delay.mul_f64(1.0 - self.jitter * random)
+    }
+}
+
+// The jitter is never NaN.
+impl Eq for Backoff {}
@@ -1,17 +1,43 @@
 use crate::broadcast::{Broadcast, Parent};
+use crate::child_ref::ChildRef;
 use crate::children::Children;
-use crate::children_ref::ChildrenRef;
-use crate::config::Config;
+use crate::children_ref::{ChildrenRef, GroupState};
+use crate::config::{Config, ConfigChange, ReloadError, RuntimeConfig};
 use crate::context::{BastionContext, BastionId};
+use crate::correlation::ReplyBroker;
 use crate::envelope::Envelope;
+use crate::errors::{BuilderError, SendError};
+use crate::event::Event;
+use crate::health::Health;
+use crate::history::TerminatedGroup;
+use crate::idle::{self, IdleCriteria};
 use crate::message::{BastionMessage, Message};
+use crate::panic_hook;
 use crate::path::BastionPathElement;
+use crate::reactor;
+#[cfg(feature = "signals")]
+use crate::signals::{self, ShutdownConfig};
+use crate::state_cell::StateCell;
 use crate::supervisor::{Supervisor, SupervisorRef};
 use crate::system::SYSTEM;
+#[cfg(feature = "introspection")]
+use crate::tree::{self, TreeSnapshot};
+use crate::typed::TypedChildrenRef;
 
+use bastion_executor::deterministic;
+use bastion_executor::pool::{self, ProcClass};
+use bastion_executor::run::run;
 use core::future::Future;
+#[cfg(feature = "introspection")]
+use futures::stream;
+use futures::stream::Stream;
+#[cfg(feature = "introspection")]
+use futures_timer::Delay;
+use lightproc::prelude::*;
 
 use std::fmt::{self, Debug, Formatter};
+#[cfg(feature = "introspection")]
+use std::time::Duration;
 
 /// A `struct` allowing to access the system's API to initialize it,
 /// start, stop and kill it and to create new supervisors and top-level
@@ -125,7 +151,7 @@ use std::fmt::{self, Debug, Formatter};
 ///     let answer: Answer = child.ask_anonymously("A message containing data.").expect("Couldn't send the message.");
 ///     # async {
 ///     // ...until the child eventually answers back...
-///     let answer: Result<SignedMessage, ()> = answer.await;
+///     let answer: Result<SignedMessage, AnswerError> = answer.await;
 ///     # };
 ///
 ///     // ...and then even stop or kill it...
@@ -185,6 +211,17 @@ impl Bastion {
     /// `Bastion::init_with` at least once before using any of
     /// bastion's features.**
     ///
+    /// Once the system stopped (or while it is stopping, in which
+    /// case this method blocks until it stopped), calling this
+    /// method again initializes a new system, without any of the
+    /// previous system's supervisors, children groups or names
+    /// registered with [`Children::with_name`].
+    ///
+    /// Note that elements can only be restarted after panicking
+    /// if panics unwind: when built with `panic = "abort"`, a
+    /// panicking element aborts the whole process (a warning is
+    /// logged when initializing the system in that case).
+    ///
     /// # Arguments
     ///
     /// * `config` - The configuration used to initialize the system.
@@ -210,15 +247,28 @@ impl Bastion {
     ///
     /// [`Config`]: struct.Config.html
     /// [`Bastion::init`]: #method.init
+    /// [`Children::with_name`]: children/struct.Children.html#method.with_name
     pub fn init_with(config: Config) {
         debug!("Bastion: Initializing with config: {:?}", config);
-        if config.backtraces().is_hide() {
-            debug!("Bastion: Hiding backtraces.");
-            std::panic::set_hook(Box::new(|_| ()));
+        if cfg!(panic = "abort") {
+            warn!("Bastion: Panics abort the process, so panicking elements won't be restarted.");
+        }
+
+        if let Some(seed) = config.deterministic_seed() {
+            if !deterministic::enable(seed) {
+                warn!(
+                    "Bastion: The executor was already used, so its scheduler won't use seed {}.",
+                    seed
+                );
+            }
         }
 
-        // NOTE: this is just to make sure that SYSTEM has been initialized by lazy_static
-        SYSTEM.sender().is_closed();
+        SYSTEM.init(&config);
+        #[cfg(feature = "signals")]
+        signals::reset();
+        // Once the previous system stopped and restored the hook
+        // it replaced.
+        panic_hook::install(config.hook_order(), config.backtraces().is_hide());
     }
 
     /// Creates a new [`Supervisor`], passes it through the specified
@@ -226,7 +276,7 @@ impl Bastion {
     /// start supervising children.
     ///
     /// This method returns a [`SupervisorRef`] referencing the newly
-    /// created supervisor if it succeeded, or `Err(())`
+    /// created supervisor if it succeeded, or a [`BuilderError`]
     /// otherwise.
     ///
     /// # Arguments
@@ -256,11 +306,16 @@ impl Bastion {
     ///
     /// [`Supervisor`]: supervisor/struct.Supervisor.html
     /// [`SupervisorRef`]: supervisor/struct.SupervisorRef.html
-    pub fn supervisor<S>(init: S) -> Result<SupervisorRef, ()>
+    /// [`BuilderError`]: errors/enum.BuilderError.html
+    pub fn supervisor<S>(init: S) -> Result<SupervisorRef, BuilderError>
     where
         S: FnOnce(Supervisor) -> Supervisor,
     {
         debug!("Bastion: Creating supervisor.");
+        SYSTEM.check_running()?;
+        // The groups are all launched before any of their elements
+        // runs.
+        let _hold = deterministic::hold();
         let parent = Parent::system();
         let bcast = Broadcast::new(parent, BastionPathElement::Supervisor(BastionId::new()));
 
@@ -269,12 +324,28 @@ impl Bastion {
         let supervisor = init(supervisor);
         debug!("Supervisor({}): Initialized.", supervisor.id());
         let supervisor_ref = supervisor.as_ref();
+        let builder_error = supervisor.builder_error();
 
         debug!("Bastion: Deploying Supervisor({}).", supervisor.id());
         let msg = BastionMessage::deploy_supervisor(supervisor);
         let envelope = Envelope::new(msg, SYSTEM.path().clone(), SYSTEM.sender().clone());
         trace!("Bastion: Sending envelope: {:?}", envelope);
-        SYSTEM.sender().unbounded_send(envelope).map_err(|_| ())?;
+        SYSTEM
+            .sender()
+            .unbounded_send(envelope)
+            .map_err(|_| BuilderError::ParentStopped)?;
+
+        if let Some(err) = builder_error {
+            // The groups that were deployed are stopped along with
+            // the supervisor.
+            warn!(
+                "Bastion: Stopping Supervisor({}): {}",
+                supervisor_ref.id(),
+                err
+            );
+            supervisor_ref.stop().ok();
+            return Err(err);
+        }
 
         Ok(supervisor_ref)
     }
@@ -284,8 +355,12 @@ impl Bastion {
     /// supervisor for it to start supervising it.
     ///
     /// This methods returns a [`ChildrenRef`] referencing the newly
-    /// created children group it it succeeded, or `Err(())`
-    /// otherwise.
+    /// created children group it it succeeded, or a [`BuilderError`]
+    /// otherwise (e.g. [`BuilderError::NotInitialized`] before the
+    /// system was initialized, [`BuilderError::ShuttingDown`] once
+    /// it was asked to stop, until it is initialized again, or
+    /// [`BuilderError::Conflict`] if some of the group's settings
+    /// conflict with each other).
     ///
     /// Note that the "system supervisor" is a supervisor created
     /// by the system at startup.
@@ -327,23 +402,99 @@ impl Bastion {
     ///
     /// [`Children`]: children/struct.Children.html
     /// [`ChildrenRef`]: children/struct.ChildrenRef.html
-    pub fn children<C>(init: C) -> Result<ChildrenRef, ()>
+    /// [`BuilderError`]: errors/enum.BuilderError.html
+    /// [`BuilderError::NotInitialized`]: errors/enum.BuilderError.html#variant.NotInitialized
+    /// [`BuilderError::ShuttingDown`]: errors/enum.BuilderError.html#variant.ShuttingDown
+    /// [`BuilderError::Conflict`]: errors/enum.BuilderError.html#variant.Conflict
+    pub fn children<C>(init: C) -> Result<ChildrenRef, BuilderError>
     where
         C: FnOnce(Children) -> Children,
     {
         debug!("Bastion: Creating children group.");
+        SYSTEM.check_running()?;
         SYSTEM.supervisor().children(init)
     }
 
+    /// Creates a new [`Children`] whose elements only accept
+    /// messages of type `M`, passes it through the specified `init`
+    /// closure and then sends it to the system's default supervisor,
+    /// like [`Bastion::children`] does.
+    ///
+    /// The group's accepted types are set to `(M,)` once `init`
+    /// returned (see [`Children::with_accepted_types`]), and its
+    /// elements can retrieve the messages using
+    /// [`BastionContext::recv_msg`].
+    ///
+    /// This methods returns a [`TypedChildrenRef`] referencing the
+    /// newly created children group, whose methods only take
+    /// messages of type `M`, if it succeeded, or a [`BuilderError`]
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `init` - The closure taking the new [`Children`] as an
+    ///     argument and returning it once configured.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let typed_ref: TypedChildrenRef<u64> = Bastion::typed_children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             loop {
+    ///                 if let (TypedMsg::Ask { msg, sender }, _) = ctx.recv_msg::<u64>().await {
+    ///                     sender.answer(&ctx, msg * 2).unwrap();
+    ///                 }
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// // This wouldn't compile:
+    /// // typed_ref.tell_one("A message containing data.");
+    /// typed_ref.tell_one(42).expect("Couldn't send the message.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children`]: children/struct.Children.html
+    /// [`Bastion::children`]: #method.children
+    /// [`Children::with_accepted_types`]: children/struct.Children.html#method.with_accepted_types
+    /// [`BastionContext::recv_msg`]: context/struct.BastionContext.html#method.recv_msg
+    /// [`TypedChildrenRef`]: typed/struct.TypedChildrenRef.html
+    /// [`BuilderError`]: errors/enum.BuilderError.html
+    pub fn typed_children<M, C>(init: C) -> Result<TypedChildrenRef<M>, BuilderError>
+    where
+        M: Message,
+        C: FnOnce(Children) -> Children,
+    {
+        debug!("Bastion: Creating typed children group.");
+        SYSTEM.check_running()?;
+        SYSTEM.supervisor().typed_children(init)
+    }
+
     /// Creates a new [`Children`] which will have the given closure
     /// as action and then sends it to the system's default supervisor.
     ///
-    /// This method returns a [`ChildrenRef`] referencing the newly created children
-    /// if the creation was successful, otherwise returns an `Err(())`.
+    /// This method returns a [`ChildRef`] referencing the only element
+    /// of the newly created children if the creation was successful,
+    /// otherwise returns a [`BuilderError`].
     ///
     /// Internally this method uses the [`Bastion::children`] and [`Children::with_exec`] methods
     /// to create a new children.
     ///
+    /// The group only has one element, which is restarted like the
+    /// other groups of the system supervisor are (see
+    /// [`Config::with_root_restart_window`]).
+    ///
     /// # Arguments
     /// * `action` - The closure which gets executed by the child.
     ///
@@ -355,7 +506,7 @@ impl Bastion {
     /// # fn main() {
     ///     # Bastion::init();
     ///     #
-    /// let children_ref: ChildrenRef = Bastion::spawn(|ctx: BastionContext| {
+    /// let child_ref: ChildRef = Bastion::spawn(|ctx: BastionContext| {
     ///     async move {
     ///         // ...
     ///         Ok(())
@@ -371,21 +522,24 @@ impl Bastion {
     /// [`Children::with_exec`]: children/struct.Children.html#method.with_exec
     /// [`Bastion::children`]: #method.children
     /// [`Children`]: children/struct.Children.html
-    /// [`ChildrenRef`]: children/struct.ChildrenRef.html
-    pub fn spawn<I, F>(action: I) -> Result<ChildrenRef, ()>
+    /// [`BuilderError`]: errors/enum.BuilderError.html
+    /// [`Config::with_root_restart_window`]: struct.Config.html#method.with_root_restart_window
+    /// [`ChildRef`]: child_ref/struct.ChildRef.html
+    pub fn spawn<I, F>(action: I) -> Result<ChildRef, BuilderError>
     where
         I: Fn(BastionContext) -> F + Send + Sync + 'static,
         F: Future<Output = Result<(), ()>> + Send + 'static,
     {
-        Bastion::children(|ch| ch.with_redundancy(1).with_exec(action))
+        let children_ref = Bastion::children(|ch| ch.with_redundancy(1).with_exec(action))?;
+        Ok(children_ref.elems()[0].clone())
     }
 
     /// Sends a message to the system which will then send it to all
     /// the root-level supervisors and their supervised children and
     /// supervisors, etc.
     ///
-    /// This method returns `()` if it succeeded, or `Err(msg)`
-    /// otherwise.
+    /// This method returns `()` if it succeeded, or a [`SendError`]
+    /// carrying the message otherwise.
     ///
     /// # Arguments
     ///
@@ -425,7 +579,9 @@ impl Bastion {
     ///     # Bastion::block_until_stopped();
     /// # }
     /// ```
-    pub fn broadcast<M: Message>(msg: M) -> Result<(), M> {
+    ///
+    /// [`SendError`]: errors/struct.SendError.html
+    pub fn broadcast<M: Message>(msg: M) -> Result<(), SendError<M>> {
         debug!("Bastion: Broadcasting message: {:?}", msg);
         let msg = BastionMessage::broadcast(msg);
         let envelope = Envelope::from_dead_letters(msg);
@@ -434,7 +590,7 @@ impl Bastion {
         SYSTEM
             .sender()
             .unbounded_send(envelope)
-            .map_err(|err| err.into_inner().into_msg().unwrap())
+            .map_err(|err| SendError::stopped(err.into_inner().into_msg().unwrap()))
     }
 
     /// Sends a message to the system to tell it to start
@@ -461,6 +617,11 @@ impl Bastion {
     /// ```
     pub fn start() {
         debug!("Bastion: Starting.");
+        if !SYSTEM.is_initialized() {
+            warn!("Bastion: Not starting the system: it isn't initialized.");
+            return;
+        }
+
         let msg = BastionMessage::start();
         let envelope = Envelope::from_dead_letters(msg);
         trace!("Bastion: Sending envelope: {:?}", envelope);
@@ -492,6 +653,11 @@ impl Bastion {
     /// ```
     pub fn stop() {
         debug!("Bastion: Stopping.");
+        if !SYSTEM.is_initialized() {
+            return;
+        }
+
+        SYSTEM.notify_stopping();
         let msg = BastionMessage::stop();
         let envelope = Envelope::from_dead_letters(msg);
         trace!("Bastion: Sending envelope: {:?}", envelope);
@@ -499,6 +665,101 @@ impl Bastion {
         SYSTEM.sender().unbounded_send(envelope).ok();
     }
 
+    /// Stops the system (as [`Bastion::stop`] does) once none of
+    /// the elements of its children groups handled or received a
+    /// message for the criteria's [`quiescent_for`] window, except
+    /// the ones of the groups named in its [`ignore_groups`].
+    ///
+    /// An element is idle while it waits for a message and there
+    /// isn't any left in its mailbox, so that
+    /// [`Bastion::block_until_stopped`] only returns once every
+    /// message was handled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// fn main() {
+    ///     Bastion::init();
+    ///
+    ///     // Spawn children and supervisors, and send them the
+    ///     // messages they have to handle...
+    ///
+    ///     Bastion::start();
+    ///     Bastion::stop_when_idle(IdleCriteria {
+    ///         quiescent_for: Duration::from_millis(100),
+    ///         ignore_groups: vec!["tickers".to_string()],
+    ///     });
+    ///
+    ///     Bastion::block_until_stopped();
+    /// }
+    /// ```
+    ///
+    /// [`Bastion::stop`]: #method.stop
+    /// [`Bastion::block_until_stopped`]: #method.block_until_stopped
+    /// [`quiescent_for`]: struct.IdleCriteria.html#structfield.quiescent_for
+    /// [`ignore_groups`]: struct.IdleCriteria.html#structfield.ignore_groups
+    pub fn stop_when_idle(criteria: IdleCriteria) {
+        debug!("Bastion: Stopping once idle: {:?}", criteria);
+        if SYSTEM.check_running().is_err() {
+            return;
+        }
+
+        let stack = ProcStack::default();
+        pool::spawn_with_class(idle::stop_when_idle(criteria), stack, ProcClass::Management);
+    }
+
+    /// Installs handlers of the `SIGTERM` and `SIGINT` signals which
+    /// stop the system gracefully once one of them is received.
+    ///
+    /// The children groups are stopped first, their elements
+    /// handling the messages left in their mailboxes (as
+    /// [`ChildrenRef::stop_with_timeout`] does), and then the system
+    /// (as [`Bastion::stop`] does). The system is killed (as
+    /// [`Bastion::kill`] does) if it didn't stop before the config's
+    /// deadline or if another signal is received meanwhile, and
+    /// [`Bastion::block_until_stopped`] returns once it stopped.
+    ///
+    /// Calling this method again replaces the config used once a
+    /// signal is received. This only works on Unix, and does nothing
+    /// but warn on other platforms.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - How to stop the system once a signal is
+    ///     received.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// fn main() {
+    ///     Bastion::init();
+    ///
+    ///     // Spawn children and supervisors...
+    ///
+    ///     Bastion::start();
+    ///     Bastion::handle_signals(ShutdownConfig::new().with_deadline(Duration::from_secs(10)));
+    ///
+    ///     # Bastion::stop();
+    ///     Bastion::block_until_stopped();
+    /// }
+    /// ```
+    ///
+    /// [`ChildrenRef::stop_with_timeout`]: children_ref/struct.ChildrenRef.html#method.stop_with_timeout
+    /// [`Bastion::stop`]: #method.stop
+    /// [`Bastion::kill`]: #method.kill
+    /// [`Bastion::block_until_stopped`]: #method.block_until_stopped
+    #[cfg(feature = "signals")]
+    pub fn handle_signals(config: ShutdownConfig) {
+        debug!("Bastion: Handling the signals: {:?}", config);
+        signals::install(config);
+    }
+
     /// Sends a message to the system to tell it to kill every
     /// running children groups and supervisors
     ///
@@ -522,6 +783,11 @@ impl Bastion {
     /// ```
     pub fn kill() {
         debug!("Bastion: Killing.");
+        if !SYSTEM.is_initialized() {
+            return;
+        }
+
+        SYSTEM.notify_stopping();
         let msg = BastionMessage::kill();
         let envelope = Envelope::from_dead_letters(msg);
         trace!("Bastion: Sending envelope: {:?}", envelope);
@@ -535,12 +801,255 @@ impl Bastion {
             system.cancel();
         }
 
+        SYSTEM.states().drop_all();
         SYSTEM.notify_stopped();
     }
 
+    /// Returns the [`ReplyBroker`] where the replies to messages
+    /// sent using [`ChildRef::tell_correlated`] are sent to.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let broker: &ReplyBroker = Bastion::reply_broker();
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ReplyBroker`]: correlation/struct.ReplyBroker.html
+    /// [`ChildRef::tell_correlated`]: child_ref/struct.ChildRef.html#method.tell_correlated
+    pub fn reply_broker() -> &'static ReplyBroker {
+        SYSTEM.reply_broker()
+    }
+
+    /// Creates a [`StateCell`] holding a state of type `T` owned by
+    /// the system, or returns the one that was already created for
+    /// this type (`init` being dropped).
+    ///
+    /// The state is constructed using `init` the first time it is
+    /// accessed, either using [`StateCell::get`] or from an element
+    /// using [`BastionContext::global`], and is dropped while the
+    /// system stops, once every children group stopped (so that
+    /// its `Drop` implementation can still flush it somewhere).
+    /// Once the system was initialized again, a new cell has to
+    /// be created, constructing a fresh state.
+    ///
+    /// # Arguments
+    ///
+    /// * `init` - The closure constructing the state.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// #[derive(Debug, Default)]
+    /// struct Requests(AtomicUsize);
+    ///
+    /// let requests = Bastion::state_cell(Requests::default);
+    ///
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| async move {
+    ///         let requests = ctx.global::<Requests>().unwrap();
+    ///         requests.0.fetch_add(1, Ordering::SeqCst);
+    ///
+    ///         Ok(())
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    ///
+    /// // Once the system stopped...
+    /// assert_eq!(requests.get().unwrap_err(), StateError::Dropped);
+    /// # }
+    /// ```
+    ///
+    /// [`StateCell`]: state_cell/struct.StateCell.html
+    /// [`StateCell::get`]: state_cell/struct.StateCell.html#method.get
+    /// [`BastionContext::global`]: context/struct.BastionContext.html#method.global
+    pub fn state_cell<T, I>(init: I) -> StateCell<T>
+    where
+        T: Send + Sync + 'static,
+        I: FnOnce() -> T + Send + 'static,
+    {
+        let states = SYSTEM.states();
+        states.register(init);
+        StateCell::new(states.clone())
+    }
+
+    /// Returns a [`ChildrenRef`] referencing the running children
+    /// group that was created with the given name using
+    /// [`Children::with_name`], if there is one.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the children group.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_name("workers")
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// // Anywhere else in the program...
+    /// let workers = Bastion::children_of("workers").expect("Couldn't find the children group.");
+    /// workers.broadcast("Hello!").expect("Couldn't send the message.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef`]: children/struct.ChildrenRef.html
+    /// [`Children::with_name`]: children/struct.Children.html#method.with_name
+    pub fn children_of(name: &str) -> Option<ChildrenRef> {
+        SYSTEM.registry().get(name)
+    }
+
+    /// Returns the summaries of the children groups that stopped,
+    /// were killed or faulted, oldest first, if the system keeps a
+    /// history of them (see [`Config::with_group_history`]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// use bastion::children_ref::GroupState;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    /// let config = Config::new().with_group_history(100, Duration::from_secs(60 * 60));
+    /// Bastion::init_with(config);
+    ///     # Bastion::start();
+    ///
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_name("tenant-42")
+    /// }).expect("Couldn't create the children group.");
+    /// run!(children_ref.stop_and_wait()).expect("Couldn't stop the group.");
+    ///
+    /// // Later, once the group can't be found anymore...
+    /// assert!(Bastion::children_of("tenant-42").is_none());
+    /// let history = Bastion::terminated_groups();
+    /// let group = history.iter().find(|group| group.name() == Some("tenant-42")).unwrap();
+    /// assert_eq!(group.state(), GroupState::Stopped);
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Config::with_group_history`]: struct.Config.html#method.with_group_history
+    pub fn terminated_groups() -> Vec<TerminatedGroup> {
+        SYSTEM.history().entries()
+    }
+
+    /// Returns a stream yielding the diagnostic [`Event`]s emitted
+    /// by the system after this method was called.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// use futures::StreamExt;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let mut events = Bastion::events();
+    ///
+    /// spawn!(async move {
+    ///     while let Some(event) = events.next().await {
+    ///         println!("{:?}", event);
+    ///     }
+    /// });
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Event`]: event/enum.Event.html
+    pub fn events() -> impl Stream<Item = Event> + Unpin {
+        SYSTEM.events().subscribe()
+    }
+
+    /// Returns a future resolving once the system is stopped (either
+    /// by calling [`Bastion::stop`] or [`Bastion::kill`]), after
+    /// every children group stopped running, including those whose
+    /// elements faulted while being stopped.
+    ///
+    /// The future resolves right away if the system isn't
+    /// initialized or already stopped. Unlike
+    /// [`Bastion::block_until_stopped`], it can be awaited along
+    /// with other futures (e.g. the ones triggering the shutdown
+    /// of a server).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    /// use futures::prelude::*;
+    ///
+    /// fn main() {
+    ///     Bastion::init();
+    ///     Bastion::start();
+    ///
+    ///     # Bastion::stop();
+    ///     run!(async {
+    ///         let shutdown = future::pending::<()>();
+    ///         futures::select! {
+    ///             _ = Bastion::stopped().fuse() => {
+    ///                 // The system was stopped...
+    ///             }
+    ///             _ = shutdown.fuse() => Bastion::stop(),
+    ///         }
+    ///     });
+    /// }
+    /// ```
+    ///
+    /// [`Bastion::stop`]: #method.stop
+    /// [`Bastion::kill`]: #method.kill
+    /// [`Bastion::block_until_stopped`]: #method.block_until_stopped
+    pub fn stopped() -> impl Future<Output = ()> {
+        let stopped = if SYSTEM.is_initialized() {
+            SYSTEM.on_stopped()
+        } else {
+            None
+        };
+
+        async move {
+            if let Some(stopped) = stopped {
+                stopped.await.ok();
+            }
+        }
+    }
+
     /// Blocks the current thread until the system is stopped
     /// (either by calling [`Bastion::stop()`] or
-    /// [`Bastion::kill`]).
+    /// [`Bastion::kill`]), like awaiting [`Bastion::stopped`]
+    /// does.
     ///
     /// # Example
     ///
@@ -565,9 +1074,332 @@ impl Bastion {
     ///
     /// [`Bastion::stop()`]: #method.stop
     /// [`Bastion::kill()`]: #method.kill
+    /// [`Bastion::stopped`]: #method.stopped
     pub fn block_until_stopped() {
         debug!("Bastion: Blocking until system is stopped.");
-        SYSTEM.wait_until_stopped();
+        run(Bastion::stopped(), ProcStack::default());
+    }
+
+    /// Returns whether the system is degraded, because the system
+    /// supervisor restarted more elements than its window allows and
+    /// was configured using [`Config::on_root_exhaustion`] to degrade
+    /// the system in that case.
+    ///
+    /// The system stays degraded until [`Bastion::recover`] is called.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// if Bastion::is_degraded() {
+    ///     // Only the kept children groups are running...
+    /// }
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Config::on_root_exhaustion`]: struct.Config.html#method.on_root_exhaustion
+    /// [`Bastion::recover`]: #method.recover
+    pub fn is_degraded() -> bool {
+        SYSTEM.is_initialized() && SYSTEM.is_degraded()
+    }
+
+    /// Makes the system stop being degraded (see
+    /// [`Bastion::is_degraded`]), deploying again the children groups
+    /// that were stopped when it was degraded.
+    ///
+    /// The groups are created with their previous identifiers and
+    /// definitions (their closure, redundancy, name, callbacks...)
+    /// but with new elements, so the [`ChildrenRef`]s and
+    /// [`ChildRef`]s referencing them before can't be used anymore:
+    /// they should be retrieved again, e.g. using
+    /// [`Bastion::children_of`]. The elements of the groups using
+    /// stable identifiers (see [`Children::with_stable_ids`]) take
+    /// the identifiers of the ones they replace instead, whose
+    /// `ChildRef`s reach them.
+    ///
+    /// This method does nothing if the system isn't degraded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// if Bastion::is_degraded() {
+    ///     // Once the cause of the faults was fixed...
+    ///     Bastion::recover();
+    /// }
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Bastion::is_degraded`]: #method.is_degraded
+    /// [`ChildrenRef`]: children/struct.ChildrenRef.html
+    /// [`ChildRef`]: child_ref/struct.ChildRef.html
+    /// [`Bastion::children_of`]: #method.children_of
+    /// [`Children::with_stable_ids`]: children/struct.Children.html#method.with_stable_ids
+    pub fn recover() {
+        debug!("Bastion: Recovering.");
+        if !SYSTEM.is_initialized() || !SYSTEM.notify_recovered() {
+            debug!("Bastion: Not recovering the system: it isn't degraded.");
+            return;
+        }
+
+        let msg = BastionMessage::recover();
+        let envelope = Envelope::from_dead_letters(msg);
+        trace!("Bastion: Sending envelope: {:?}", envelope);
+        // FIXME: Err(Error)
+        SYSTEM.sender().unbounded_send(envelope).ok();
+    }
+
+    /// Replaces the runtime settings of the system's configuration
+    /// (see [`Config::with_runtime`]) without restarting it,
+    /// emitting an [`Event::ConfigReloaded`] listing the settings
+    /// that changed, which are also returned.
+    ///
+    /// The new settings only apply to what happens from now on:
+    /// e.g. the messages asked before keep waiting for their answer
+    /// with the default timeout they were asked with (see
+    /// [`RuntimeConfig::with_default_ask_timeout`]).
+    ///
+    /// This method returns an error if the system isn't
+    /// initialized, or if it is stopping or stopped.
+    ///
+    /// # Arguments
+    ///
+    /// * `runtime` - The new runtime settings, replacing all the
+    ///     previous ones.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    /// let runtime = RuntimeConfig::new().with_default_ask_timeout(Duration::from_secs(1));
+    /// let changes = Bastion::reload_config(runtime).expect("Couldn't reload the configuration.");
+    /// assert_eq!(changes[0].field(), "default_ask_timeout");
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Config::with_runtime`]: struct.Config.html#method.with_runtime
+    /// [`Event::ConfigReloaded`]: event/enum.Event.html#variant.ConfigReloaded
+    /// [`RuntimeConfig::with_default_ask_timeout`]: struct.RuntimeConfig.html#method.with_default_ask_timeout
+    pub fn reload_config(runtime: RuntimeConfig) -> Result<Vec<ConfigChange>, ReloadError> {
+        debug!("Bastion: Reloading the configuration: {:?}", runtime);
+        SYSTEM.check_running().map_err(|err| match err {
+            BuilderError::NotInitialized => ReloadError::NotInitialized,
+            _ => ReloadError::ShuttingDown,
+        })?;
+
+        let changes = SYSTEM.reload(runtime);
+        info!("Bastion: Reloaded the configuration: {:?}", changes);
+        let event = Event::ConfigReloaded {
+            changes: changes.clone(),
+        };
+        SYSTEM.events().emit(event);
+
+        Ok(changes)
+    }
+
+    /// Reloads the runtime settings of `config` like
+    /// [`Bastion::reload_config`] does, after checking that its
+    /// other settings (which can only be set when the system is
+    /// initialized) are the same as those of the configuration
+    /// the system was initialized with.
+    ///
+    /// This method returns [`ReloadError::ConstructionTime`],
+    /// listing the settings that differ, without reloading
+    /// anything otherwise. Note that the dead letters' serializers
+    /// and handler are only compared by the types they serialize
+    /// and whether a handler is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration to reload the runtime
+    ///     settings of.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    /// let runtime = RuntimeConfig::new().with_default_ask_timeout(Duration::from_secs(1));
+    /// let config = Config::new().hide_backtraces().with_runtime(runtime);
+    /// assert_eq!(
+    ///     Bastion::reload_with(config),
+    ///     Err(ReloadError::ConstructionTime { fields: vec!["backtraces"] }),
+    /// );
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Bastion::reload_config`]: #method.reload_config
+    /// [`ReloadError::ConstructionTime`]: enum.ReloadError.html#variant.ConstructionTime
+    pub fn reload_with(config: Config) -> Result<Vec<ConfigChange>, ReloadError> {
+        if SYSTEM.is_initialized() {
+            let fields = SYSTEM.config().construction_diff(&config);
+            if !fields.is_empty() {
+                debug!("Bastion: Not reloading the configuration: {:?}", fields);
+                return Err(ReloadError::ConstructionTime { fields });
+            }
+        }
+
+        Bastion::reload_config(config.runtime().clone())
+    }
+
+    /// Returns the health of the system: whether it is degraded,
+    /// and the health of its utilities (the group receiving the
+    /// dead letters and the reactor, which wakes the elements
+    /// sleeping, see [`BastionContext::sleep`]).
+    ///
+    /// The utilities are supervised by a dedicated supervisor of
+    /// the system, which always restarts them (waiting longer each
+    /// time) and stops them after the other supervisors.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    /// let health = Bastion::health();
+    /// if health.reactor().restarts() > 0 {
+    ///     // The elements' timers were registered again...
+    /// }
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`BastionContext::sleep`]: context/struct.BastionContext.html#method.sleep
+    pub fn health() -> Health {
+        let dead_letters = if SYSTEM.is_initialized() {
+            SYSTEM.dead_letters().state()
+        } else {
+            GroupState::Stopped
+        };
+
+        Health {
+            degraded: Bastion::is_degraded(),
+            dead_letters,
+            reactor: reactor::health(),
+        }
+    }
+
+    /// Returns a snapshot of the supervision tree: the supervisors
+    /// currently running, along with the children groups they
+    /// supervise and their elements, which can be exported using
+    /// [`TreeSnapshot::to_dot`] or [`TreeSnapshot::to_json`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    /// Bastion::children(|children| children.with_name("workers"))
+    ///     .expect("Couldn't create the children group.");
+    ///
+    /// let tree = Bastion::tree();
+    /// println!("{}", tree.to_dot());
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`TreeSnapshot::to_dot`]: tree/struct.TreeSnapshot.html#method.to_dot
+    /// [`TreeSnapshot::to_json`]: tree/struct.TreeSnapshot.html#method.to_json
+    #[cfg(feature = "introspection")]
+    pub fn tree() -> TreeSnapshot {
+        tree::snapshot()
+    }
+
+    /// Returns a stream yielding a snapshot of the supervision tree
+    /// (see [`Bastion::tree`]) right away and then every `interval`.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - How long to wait between the snapshots.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// use futures::StreamExt;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let mut snapshots = Bastion::tree_watch(Duration::from_secs(1));
+    ///
+    /// spawn!(async move {
+    ///     while let Some(tree) = snapshots.next().await {
+    ///         println!("{}", tree.to_json());
+    ///     }
+    /// });
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Bastion::tree`]: #method.tree
+    #[cfg(feature = "introspection")]
+    pub fn tree_watch(interval: Duration) -> impl Stream<Item = TreeSnapshot> + Unpin {
+        let snapshots = stream::unfold(true, move |first| async move {
+            if !first {
+                Delay::new(interval).await;
+            }
+
+            Some((tree::snapshot(), false))
+        });
+
+        Box::pin(snapshots)
+    }
+
+    #[doc(hidden)]
+    // Makes the reactor fault for it to be restarted, to test that
+    // the elements' timers outlive its restarts.
+    pub fn fault_reactor() {
+        reactor::fault();
     }
 }
 
@@ -0,0 +1,240 @@
+//!
+//! Batches of messages, accumulated by a children group and
+//! delivered to its elements at once (see
+//! [`Children::with_batching`]).
+//!
+//! [`Children::with_batching`]: ../children/struct.Children.html#method.with_batching
+use crate::dead_letters::{self, DeadLetterReason};
+use crate::envelope::Envelope;
+use crate::errors::SendErrorKind;
+use crate::message::{BastionMessage, Message};
+use crate::reactor::{self, Sleep};
+use crate::warmup::Router;
+use bastion_executor::pool::{self, ProcClass};
+use lightproc::proc_stack::ProcStack;
+use std::any::{Any, TypeId};
+use std::fmt::{self, Debug, Formatter};
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// The messages of the type batched by a children group (see
+/// [`Children::with_batching`]), in the order they were sent to
+/// it.
+///
+/// [`Children::with_batching`]: ../children/struct.Children.html#method.with_batching
+pub struct Batch<T>(Vec<T>);
+
+#[derive(Clone)]
+// The batch of messages a group accumulates, shared by the group
+// and its `ChildrenRef`s.
+pub(crate) struct Batcher(Arc<BatcherInner>);
+
+struct BatcherInner {
+    type_id: TypeId,
+    type_name: &'static str,
+    max_items: usize,
+    max_delay: Duration,
+    routing: Router,
+    // Routes the items (a `Vec` of the batched type) to one of the
+    // group's elements.
+    deliver: fn(Box<dyn Any + Send>, &Router),
+    pending: Mutex<Pending>,
+}
+
+#[derive(Default)]
+struct Pending {
+    // A `Vec` of the batched type, unless the batch is empty.
+    items: Option<Box<dyn Any + Send>>,
+    // Incremented each time a batch is delivered, so that the
+    // delayed delivery of a batch is skipped if it already was.
+    delivered: u64,
+}
+
+impl<T> Batch<T> {
+    /// Returns the messages of the batch, consuming it.
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T> Deref for Batch<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<T> IntoIterator for Batch<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl Batcher {
+    pub(crate) fn new<T: Message>(max_items: usize, max_delay: Duration, routing: Router) -> Self {
+        let inner = BatcherInner {
+            type_id: TypeId::of::<T>(),
+            type_name: std::any::type_name::<T>(),
+            max_items: max_items.max(1),
+            max_delay,
+            routing,
+            deliver: deliver::<T>,
+            pending: Mutex::new(Pending::default()),
+        };
+
+        Batcher(Arc::new(inner))
+    }
+
+    // Returns an empty batcher with the same configuration, which
+    // routes its batches to the elements of a respawned group.
+    pub(crate) fn respawn(&self, routing: Router) -> Self {
+        let inner = BatcherInner {
+            type_id: self.0.type_id,
+            type_name: self.0.type_name,
+            max_items: self.0.max_items,
+            max_delay: self.0.max_delay,
+            routing,
+            deliver: self.0.deliver,
+            pending: Mutex::new(Pending::default()),
+        };
+
+        Batcher(Arc::new(inner))
+    }
+
+    // Returns the id and the name of the batched type.
+    pub(crate) fn batched(&self) -> (TypeId, &'static str) {
+        (self.0.type_id, self.0.type_name)
+    }
+
+    // Adds the message to the pending batch, delivering it once
+    // it's full, or returns it if it isn't of the batched type.
+    pub(crate) fn push<M: Message>(&self, msg: M) -> Result<(), M> {
+        if TypeId::of::<M>() != self.0.type_id {
+            return Err(msg);
+        }
+
+        // FIXME: panics?
+        let mut pending = self.0.pending.lock().unwrap();
+        let items = pending
+            .items
+            .get_or_insert_with(|| Box::new(Vec::<M>::new()))
+            .downcast_mut::<Vec<M>>()
+            .unwrap();
+        items.push(msg);
+
+        let len = items.len();
+        if len >= self.0.max_items {
+            let items = pending.take();
+            drop(pending);
+            self.deliver(items);
+        } else if len == 1 {
+            self.deliver_after(pending.delivered);
+        }
+
+        Ok(())
+    }
+
+    // Delivers the pending batch, if it isn't empty.
+    pub(crate) fn flush(&self) {
+        // FIXME: panics?
+        let items = self.0.pending.lock().unwrap().take();
+        self.deliver(items);
+    }
+
+    // Delivers the pending batch once the maximum delay elapsed,
+    // unless it was delivered before.
+    fn deliver_after(&self, delivered: u64) {
+        let batcher = self.clone();
+        let deadline = reactor::now() + self.0.max_delay;
+        let delayed = async move {
+            Sleep::new(deadline).await;
+
+            // FIXME: panics?
+            let mut pending = batcher.0.pending.lock().unwrap();
+            if pending.delivered == delivered {
+                let items = pending.take();
+                drop(pending);
+                batcher.deliver(items);
+            }
+        };
+
+        pool::spawn_with_class(delayed, ProcStack::default(), ProcClass::Timer);
+    }
+
+    fn deliver(&self, items: Option<Box<dyn Any + Send>>) {
+        if let Some(items) = items {
+            (self.0.deliver)(items, &self.0.routing);
+        }
+    }
+}
+
+impl Pending {
+    fn take(&mut self) -> Option<Box<dyn Any + Send>> {
+        let items = self.items.take();
+        if items.is_some() {
+            self.delivered += 1;
+        }
+
+        items
+    }
+}
+
+impl Debug for Batcher {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Batcher")
+            .field("type_name", &self.0.type_name)
+            .field("max_items", &self.0.max_items)
+            .field("max_delay", &self.0.max_delay)
+            .finish()
+    }
+}
+
+fn deliver<T: Message>(items: Box<dyn Any + Send>, routing: &Router) {
+    let mut batch = match items.downcast::<Vec<T>>() {
+        Ok(items) => Some(Batch(*items)),
+        Err(_) => unreachable!(),
+    };
+
+    debug!("Batcher: Delivering a batch: {:?}", batch);
+    // NOTE: the batches aren't of the type of a key function.
+    let routed = routing.route(None, || {
+        let msg = BastionMessage::tell(batch.take().unwrap());
+        Envelope::from_dead_letters(msg)
+    });
+
+    match routed {
+        Ok(Some(child)) => {
+            if let Err(err) = child.tell_unchecked(batch.take().unwrap()) {
+                warn!("Batcher: Couldn't deliver a batch: {}", err);
+                let reason = match err.kind() {
+                    SendErrorKind::MailboxFull => DeadLetterReason::MailboxFull,
+                    _ => DeadLetterReason::RecipientStopped,
+                };
+                let env = Envelope::from_dead_letters(BastionMessage::tell(err.into_inner_msg()));
+                dead_letters::bounce(env, Some(child.id()), reason);
+            }
+        }
+        // The batch was queued...
+        Ok(None) if batch.is_none() => (),
+        // ...or dropped because the queue was full.
+        Ok(None) => {
+            let env = Envelope::from_dead_letters(BastionMessage::tell(batch.take().unwrap()));
+            dead_letters::bounce(env, None, DeadLetterReason::MailboxFull);
+        }
+        Err(kind) => {
+            warn!("Batcher: Couldn't deliver a batch: {}", kind);
+            let reason = match kind {
+                SendErrorKind::MailboxFull => DeadLetterReason::MailboxFull,
+                _ => DeadLetterReason::RecipientStopped,
+            };
+            let env = Envelope::from_dead_letters(BastionMessage::tell(batch.take().unwrap()));
+            dead_letters::bounce(env, None, reason);
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//!
+//! A bridge allowing synchronous code (e.g. legacy modules called
+//! from deep inside non-async code) to ask a message to an element
+//! and wait for its answer, without being restructured into async.
+//!
+//! [`ask_blocking`] blocks the calling thread, which is safe from
+//! any thread except the executor's workers running the elements:
+//! blocking one of them could prevent the element from answering,
+//! so it is refused with [`BridgeError::WouldBlockExecutor`].
+//!
+//! [`ask_blocking`]: fn.ask_blocking.html
+//! [`BridgeError::WouldBlockExecutor`]: enum.BridgeError.html#variant.WouldBlockExecutor
+use crate::child_ref::ChildRef;
+use crate::errors::SendErrorKind;
+use crate::message::{Message, Msg};
+use bastion_executor::worker;
+use futures::task::{self, ArcWake};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// The reasons why [`ask_blocking`] couldn't return an answer.
+///
+/// [`ask_blocking`]: fn.ask_blocking.html
+pub enum BridgeError {
+    /// It was called from one of the executor's worker threads,
+    /// which must not be blocked (e.g. from an element's future).
+    /// [`ChildRef::ask_anonymously`] can be used and its answer
+    /// awaited instead.
+    ///
+    /// [`ChildRef::ask_anonymously`]: ../child_ref/struct.ChildRef.html#method.ask_anonymously
+    WouldBlockExecutor,
+    /// The message couldn't be sent, for the reason this variant
+    /// carries (the message is dropped).
+    Send(SendErrorKind),
+    /// The message was dropped without being answered (e.g.
+    /// because its recipient stopped).
+    Unanswered,
+    /// The message wasn't answered before the timeout expired.
+    TimedOut,
+}
+
+#[derive(Debug, Default)]
+// Wakes the thread blocked on the answer once it can be polled
+// again.
+struct Signal {
+    woken: Mutex<bool>,
+    cvar: Condvar,
+}
+
+/// Sends a message to the element `child` is referencing, like
+/// [`ChildRef::ask_anonymously`] does, and blocks the current
+/// thread until the element answers it or until `timeout` expires.
+///
+/// This function returns the answer if it was received in time, or
+/// a [`BridgeError`] otherwise (including right away, without
+/// sending the message, if it is called from one of the executor's
+/// worker threads).
+///
+/// # Arguments
+///
+/// * `child` - The element to ask the message to.
+/// * `msg` - The message to ask.
+/// * `timeout` - How long to wait for the answer.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// use bastion::blocking_bridge;
+/// use std::time::Duration;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     # Bastion::start();
+///     #
+/// let children_ref = Bastion::children(|children| {
+///     children.with_exec(|ctx: BastionContext| async move {
+///         loop {
+///             msg! { ctx.recv().await?,
+///                 n: u64 =!> {
+///                     answer!(ctx, n * 2).unwrap();
+///                 };
+///                 _: _ => ();
+///             }
+///         }
+///     })
+/// }).expect("Couldn't create the children group.");
+///
+/// // From synchronous code that isn't run by the executor...
+/// let child_ref = &children_ref.elems()[0];
+/// let answer = blocking_bridge::ask_blocking(child_ref, 21u64, Duration::from_secs(1))
+///     .expect("Didn't receive an answer.");
+/// assert_eq!(answer.downcast::<u64>().unwrap(), 42);
+///     #
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`ChildRef::ask_anonymously`]: ../child_ref/struct.ChildRef.html#method.ask_anonymously
+/// [`BridgeError`]: enum.BridgeError.html
+pub fn ask_blocking<M: Message>(
+    child: &ChildRef,
+    msg: M,
+    timeout: Duration,
+) -> Result<Msg, BridgeError> {
+    if worker::is_worker() {
+        warn!(
+            "ChildRef({}): Refusing to block an executor's worker thread.",
+            child.id()
+        );
+        return Err(BridgeError::WouldBlockExecutor);
+    }
+
+    let deadline = Instant::now() + timeout;
+    let mut answer = child
+        .ask_anonymously(msg)
+        .map_err(|err| BridgeError::Send(err.kind()))?;
+
+    match block_on(&mut answer, deadline) {
+        Some(answer) => {
+            let (msg, _) = answer.map_err(|_| BridgeError::Unanswered)?.extract();
+            Ok(msg)
+        }
+        None => Err(BridgeError::TimedOut),
+    }
+}
+
+// Blocks the current thread until `fut` resolves, or returns `None`
+// once the deadline is reached.
+pub(crate) fn block_on<F: Future + Unpin>(fut: &mut F, deadline: Instant) -> Option<F::Output> {
+    let signal = Arc::new(Signal::default());
+    let waker = task::waker(signal.clone());
+    let mut ctx = Context::from_waker(&waker);
+
+    loop {
+        if let Poll::Ready(output) = Pin::new(&mut *fut).poll(&mut ctx) {
+            return Some(output);
+        }
+
+        let mut woken = signal.woken.lock().unwrap();
+        while !*woken {
+            let now = Instant::now();
+            if now >= deadline {
+                return None;
+            }
+
+            woken = signal.cvar.wait_timeout(woken, deadline - now).unwrap().0;
+        }
+
+        *woken = false;
+    }
+}
+
+impl ArcWake for Signal {
+    fn wake_by_ref(signal: &Arc<Self>) {
+        *signal.woken.lock().unwrap() = true;
+        signal.cvar.notify_one();
+    }
+}
+
+impl Display for BridgeError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            BridgeError::WouldBlockExecutor => fmt.write_str(
+                "couldn't wait for the answer: it would block one of the executor's worker threads",
+            ),
+            BridgeError::Send(kind) => write!(fmt, "couldn't send the message: {}", kind),
+            BridgeError::Unanswered => {
+                fmt.write_str("the message was dropped without being answered")
+            }
+            BridgeError::TimedOut => {
+                fmt.write_str("the message wasn't answered before the timeout")
+            }
+        }
+    }
+}
+
+impl Error for BridgeError {}
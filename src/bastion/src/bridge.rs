@@ -0,0 +1,540 @@
+//!
+//! Bridges converting external event sources (e.g. a channel, a
+//! socket or a file being watched) into messages told to a
+//! children group, from a supervised element of their own.
+//!
+//! [`from_stream`] pulls the items of a [`Stream`] and
+//! [`from_blocking_iter`] the ones of an [`Iterator`] whose items
+//! are retrieved by blocking (from a dedicated thread, so that the
+//! executor's workers aren't blocked). Each item is mapped to a
+//! message and told to one of the target group's elements (see
+//! [`ChildrenRef::tell_one`]), only pulling the next item once it
+//! was. What the bridge does once its source is exhausted is set
+//! using [`Bridge::with_exhaustion`].
+//!
+//! [`from_stream`]: fn.from_stream.html
+//! [`from_blocking_iter`]: fn.from_blocking_iter.html
+//! [`Stream`]: https://docs.rs/futures/0.3/futures/stream/trait.Stream.html
+//! [`Iterator`]: https://doc.rust-lang.org/std/iter/trait.Iterator.html
+//! [`ChildrenRef::tell_one`]: ../children_ref/struct.ChildrenRef.html#method.tell_one
+//! [`Bridge::with_exhaustion`]: struct.Bridge.html#method.with_exhaustion
+#[cfg(feature = "supervision")]
+use crate::backoff::Backoff;
+use crate::bastion::Bastion;
+use crate::children::Children;
+use crate::children_ref::ChildrenRef;
+use crate::context::{BastionContext, BastionId};
+use crate::dead_letters::{self, DeadLetterReason};
+use crate::envelope::Envelope;
+use crate::errors::{BuilderError, SendErrorKind};
+use crate::message::{BastionMessage, Message};
+use crate::tree;
+use futures::channel::mpsc;
+use futures::executor;
+use futures::prelude::*;
+use futures::stream::BoxStream;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::mpsc as std_mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long a bridge waits before trying again to tell a message
+/// that couldn't be told.
+pub const DEFAULT_RETRY_DELAY: Duration = Duration::from_millis(10);
+/// How long a bridge waits for its target group to be relaunched
+/// once it terminated, before stopping.
+pub const DEFAULT_TARGET_GRACE: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, PartialEq)]
+/// What a [`Bridge`] does once its source is exhausted.
+///
+/// [`Bridge`]: struct.Bridge.html
+pub enum Exhaustion {
+    /// The bridge's element stops (which is the default).
+    Stop,
+    /// A [`SourceExhausted`] message is broadcasted to the target
+    /// group, and the bridge's element stops.
+    ///
+    /// [`SourceExhausted`]: struct.SourceExhausted.html
+    NotifyTarget,
+    #[cfg(feature = "supervision")]
+    /// The source is created again once the back-off's delay
+    /// passed, which grows each time the source is exhausted in a
+    /// row (i.e. each time it is exhausted before the back-off's
+    /// reset threshold passed since it was created).
+    Restart(Backoff),
+}
+
+impl Default for Exhaustion {
+    fn default() -> Self {
+        Exhaustion::Stop
+    }
+}
+
+#[derive(Debug, Clone)]
+/// The message broadcasted to the target group of a bridge whose
+/// source was exhausted, if the bridge was configured with
+/// [`Exhaustion::NotifyTarget`].
+///
+/// [`Exhaustion::NotifyTarget`]: enum.Exhaustion.html#variant.NotifyTarget
+pub struct SourceExhausted {
+    bridge: BastionId,
+}
+
+impl SourceExhausted {
+    /// Returns the identifier of the bridge's children group.
+    pub fn bridge(&self) -> &BastionId {
+        &self.bridge
+    }
+}
+
+/// A bridge telling the messages mapped from the items of an
+/// external source to a children group, as created by
+/// [`from_stream`] or [`from_blocking_iter`].
+///
+/// The bridge runs as the single element of a children group,
+/// created using [`build`] or configured using [`configure`], which
+/// creates the source when it is started (and again if it is
+/// restarted).
+///
+/// While the target group's elements can't receive a message
+/// (e.g. because their mailboxes are full, or because they are
+/// being restarted), the bridge waits and tries again without
+/// pulling the next item. If the target group terminates, the
+/// bridge tells the messages to the group that replaced it if it
+/// was restarted by its supervisor, or sends the message to the
+/// dead letters and stops if it wasn't relaunched in time (see
+/// [`with_target_grace`]).
+///
+/// [`from_stream`]: fn.from_stream.html
+/// [`from_blocking_iter`]: fn.from_blocking_iter.html
+/// [`build`]: #method.build
+/// [`configure`]: #method.configure
+/// [`with_target_grace`]: #method.with_target_grace
+pub struct Bridge<M: Message> {
+    source: Arc<dyn Fn() -> BoxStream<'static, M> + Send + Sync>,
+    target: ChildrenRef,
+    exhaustion: Exhaustion,
+    retry_delay: Duration,
+    target_grace: Duration,
+}
+
+/// Creates a [`Bridge`] telling the messages returned by `map` for
+/// each item of the stream returned by `factory` to one of the
+/// elements of `target`.
+///
+/// # Arguments
+///
+/// * `factory` - The closure creating the stream, called each time
+///     the bridge is started.
+/// * `target` - The children group to tell the messages to.
+/// * `map` - The closure mapping each item to a message.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// use bastion::bridge;
+/// use futures::channel::mpsc;
+/// use futures::prelude::*;
+/// use std::sync::{Arc, Mutex};
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     # Bastion::start();
+///     #
+///     # let target = Bastion::children(|children| children).unwrap();
+/// let (sender, recver) = mpsc::unbounded::<u64>();
+/// let recver = Arc::new(Mutex::new(Some(recver)));
+///
+/// // The receiver can only be bridged once.
+/// bridge::from_stream(
+///     move || stream::iter(recver.lock().unwrap().take()).flatten(),
+///     target,
+///     |n| n * 2,
+/// )
+/// .build()
+/// .expect("Couldn't create the bridge.");
+///
+/// sender.unbounded_send(21).unwrap();
+///     #
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`Bridge`]: struct.Bridge.html
+pub fn from_stream<SF, S, F, M>(factory: SF, target: ChildrenRef, map: F) -> Bridge<M>
+where
+    SF: Fn() -> S + Send + Sync + 'static,
+    S: Stream + Send + 'static,
+    F: Fn(S::Item) -> M + Send + Sync + 'static,
+    M: Message,
+{
+    let map = Arc::new(map);
+    let source = move || {
+        let map = map.clone();
+        factory().map(move |item| map(item)).boxed()
+    };
+
+    Bridge::new(Arc::new(source), target)
+}
+
+/// Creates a [`Bridge`] telling the messages returned by `map` for
+/// each item of the iterator returned by `factory` to one of the
+/// elements of `target`.
+///
+/// The iterators are created and iterated over by a thread
+/// dedicated to the bridge, spawned when it is created, which
+/// retrieves each item once the previous one was told. Once the
+/// bridge stops or is restarted, the thread stops iterating after
+/// retrieving the next item (or once the iterator is exhausted),
+/// and it stops once the bridge is dropped.
+///
+/// This function returns the [`Bridge`] if its thread could be
+/// spawned, or [`BuilderError::ThreadSpawn`] otherwise.
+///
+/// # Arguments
+///
+/// * `factory` - The closure creating the iterator, called (from
+///     the bridge's thread) each time the bridge is started.
+/// * `target` - The children group to tell the messages to.
+/// * `map` - The closure mapping each item to a message.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// use bastion::bridge::{self, Bridge};
+/// use std::io::{self, BufRead};
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     # Bastion::start();
+///     #
+///     # let target = Bastion::children(|children| children).unwrap();
+/// // Tells each line read from the standard input...
+/// bridge::from_blocking_iter(
+///     || io::stdin().lock().lines().map_while(Result::ok),
+///     target,
+///     |line| line,
+/// )
+/// .and_then(Bridge::build)
+/// .expect("Couldn't create the bridge.");
+///     #
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`Bridge`]: struct.Bridge.html
+/// [`BuilderError::ThreadSpawn`]: ../errors/enum.BuilderError.html#variant.ThreadSpawn
+pub fn from_blocking_iter<IF, I, F, M>(
+    factory: IF,
+    target: ChildrenRef,
+    map: F,
+) -> Result<Bridge<M>, BuilderError>
+where
+    IF: Fn() -> I + Send + Sync + 'static,
+    I: Iterator,
+    I::Item: Send + 'static,
+    F: Fn(I::Item) -> M + Send + Sync + 'static,
+    M: Message,
+{
+    // Each time the bridge is started, it sends the sender of the
+    // items of a new iterator to the bridge's thread.
+    let (starts, started) = std_mpsc::channel::<mpsc::Sender<I::Item>>();
+    thread::Builder::new()
+        .name("bastion-bridge".to_string())
+        .spawn(move || {
+            for mut sender in started {
+                for item in factory() {
+                    // The bridge stopped or was restarted.
+                    if executor::block_on(sender.send(item)).is_err() {
+                        break;
+                    }
+                }
+            }
+        })
+        .map_err(|err| {
+            error!("Bridge: Couldn't spawn the bridge's thread: {}", err);
+            BuilderError::ThreadSpawn
+        })?;
+
+    let source = move || {
+        // Only one item is retrieved ahead of the one being told.
+        let (sender, recver) = mpsc::channel(0);
+        starts.send(sender).ok();
+        recver
+    };
+
+    Ok(from_stream(source, target, map))
+}
+
+impl<M: Message> Bridge<M> {
+    fn new(
+        source: Arc<dyn Fn() -> BoxStream<'static, M> + Send + Sync>,
+        target: ChildrenRef,
+    ) -> Self {
+        Bridge {
+            source,
+            target,
+            exhaustion: Exhaustion::default(),
+            retry_delay: DEFAULT_RETRY_DELAY,
+            target_grace: DEFAULT_TARGET_GRACE,
+        }
+    }
+
+    /// Sets what the bridge does once its source is exhausted.
+    ///
+    /// The default is [`Exhaustion::Stop`].
+    ///
+    /// # Arguments
+    ///
+    /// * `exhaustion` - What the bridge does once its source is
+    ///     exhausted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// use bastion::bridge::{self, Exhaustion};
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let target = Bastion::children(|children| children).unwrap();
+    /// // Polls the source again, waiting 100ms, 200ms... up to 1s
+    /// // each time it is exhausted.
+    /// let backoff = Backoff::exponential(
+    ///     Duration::from_millis(100),
+    ///     Duration::from_secs(1),
+    ///     0.0,
+    /// );
+    /// bridge::from_blocking_iter(|| 0..10u64, target, |n| n)
+    ///     .expect("Couldn't create the bridge.")
+    ///     .with_exhaustion(Exhaustion::Restart(backoff))
+    ///     .build()
+    ///     .expect("Couldn't create the bridge.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Exhaustion::Stop`]: enum.Exhaustion.html#variant.Stop
+    pub fn with_exhaustion(mut self, exhaustion: Exhaustion) -> Self {
+        self.exhaustion = exhaustion;
+        self
+    }
+
+    /// Sets how long the bridge waits before trying again to tell a
+    /// message that the target group's elements couldn't receive.
+    ///
+    /// The default is [`DEFAULT_RETRY_DELAY`].
+    ///
+    /// # Arguments
+    ///
+    /// * `delay` - How long to wait before trying again.
+    ///
+    /// [`DEFAULT_RETRY_DELAY`]: constant.DEFAULT_RETRY_DELAY.html
+    pub fn with_retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
+    /// Sets how long the bridge waits for its target group to be
+    /// relaunched by its supervisor once it terminated, before
+    /// sending the message it was telling to the dead letters and
+    /// stopping.
+    ///
+    /// The default is [`DEFAULT_TARGET_GRACE`].
+    ///
+    /// # Arguments
+    ///
+    /// * `grace` - How long to wait for the target group.
+    ///
+    /// [`DEFAULT_TARGET_GRACE`]: constant.DEFAULT_TARGET_GRACE.html
+    pub fn with_target_grace(mut self, grace: Duration) -> Self {
+        self.target_grace = grace;
+        self
+    }
+
+    /// Configures `children` to run the bridge as its single
+    /// element, e.g. to supervise it using a supervisor created by
+    /// the user.
+    ///
+    /// # Arguments
+    ///
+    /// * `children` - The children group to configure.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// use bastion::bridge;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let target = Bastion::children(|children| children).unwrap();
+    /// let bridge = bridge::from_blocking_iter(|| 0..10u64, target, |n| n)
+    ///     .expect("Couldn't create the bridge.");
+    ///
+    /// Bastion::supervisor(|sp| sp.children(|children| bridge.configure(children)))
+    ///     .expect("Couldn't create the supervisor.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn configure(self, children: Children) -> Children {
+        let bridge = Arc::new(self);
+        children
+            .with_redundancy(1)
+            .with_exec(move |ctx: BastionContext| bridge.clone().run(ctx))
+    }
+
+    /// Creates a children group supervised by the system supervisor
+    /// running the bridge as its single element (see [`configure`]).
+    ///
+    /// This method returns a [`ChildrenRef`] referencing the
+    /// bridge's group if it succeeded, or a [`BuilderError`]
+    /// otherwise.
+    ///
+    /// [`configure`]: #method.configure
+    /// [`ChildrenRef`]: ../children_ref/struct.ChildrenRef.html
+    /// [`BuilderError`]: ../errors/enum.BuilderError.html
+    pub fn build(self) -> Result<ChildrenRef, BuilderError> {
+        Bastion::children(|children| self.configure(children))
+    }
+
+    async fn run(self: Arc<Self>, ctx: BastionContext) -> Result<(), ()> {
+        let mut target = self.target.clone();
+        // The number of times the source was exhausted in a row.
+        #[cfg(feature = "supervision")]
+        let mut attempt = 0;
+        loop {
+            #[cfg(feature = "supervision")]
+            let created = Instant::now();
+            let mut source = (self.source)();
+            while let Some(msg) = source.next().await {
+                if self.forward(&ctx, &mut target, msg).await.is_err() {
+                    return Ok(());
+                }
+            }
+
+            debug!("Bridge({}): Source exhausted.", ctx.current().id());
+            match &self.exhaustion {
+                Exhaustion::Stop => return Ok(()),
+                Exhaustion::NotifyTarget => {
+                    let notification = SourceExhausted {
+                        bridge: ctx.parent().id().clone(),
+                    };
+                    self.resolve(&mut target);
+                    target.broadcast(notification).ok();
+                    return Ok(());
+                }
+                #[cfg(feature = "supervision")]
+                Exhaustion::Restart(backoff) => {
+                    if created.elapsed() >= backoff.reset_after() {
+                        attempt = 0;
+                    }
+                    let delay = backoff.jittered_delay(attempt);
+                    attempt = attempt.saturating_add(1);
+                    debug!(
+                        "Bridge({}): Creating the source again in {:?}.",
+                        ctx.current().id(),
+                        delay
+                    );
+                    ctx.sleep(delay).await;
+                }
+            }
+        }
+    }
+
+    // Tells the message to one of the target group's elements,
+    // waiting until one of them can receive it. Returns an error if
+    // the target group terminated without being relaunched in time.
+    async fn forward(
+        &self,
+        ctx: &BastionContext,
+        target: &mut ChildrenRef,
+        mut msg: M,
+    ) -> Result<(), ()> {
+        let mut terminated = None;
+        loop {
+            if self.resolve(target) {
+                match target.tell_one(msg) {
+                    Ok(()) => return Ok(()),
+                    Err(err) => {
+                        if let SendErrorKind::TypeNotAccepted { .. } = err.kind() {
+                            warn!(
+                                "Bridge({}): Dropping message not accepted by Children({}): {:?}",
+                                ctx.current().id(),
+                                target.id(),
+                                err.msg()
+                            );
+                            return Ok(());
+                        }
+
+                        trace!(
+                            "Bridge({}): Couldn't tell message ({:?}), trying again.",
+                            ctx.current().id(),
+                            err.kind()
+                        );
+                        msg = err.into_inner_msg();
+                        terminated = None;
+                    }
+                }
+            } else {
+                let since = *terminated.get_or_insert_with(Instant::now);
+                if since.elapsed() >= self.target_grace {
+                    warn!(
+                        "Bridge({}): Children({}) terminated, stopping.",
+                        ctx.current().id(),
+                        target.id()
+                    );
+                    let env = Envelope::new_with_sign(BastionMessage::tell(msg), ctx.signature());
+                    let reason = DeadLetterReason::RecipientStopped;
+                    dead_letters::bounce(env, Some(target.id()), reason);
+                    return Err(());
+                }
+            }
+
+            ctx.sleep(self.retry_delay).await;
+        }
+    }
+
+    // Replaces `target` by the group that replaced it if it was
+    // restarted. Returns whether the target group didn't terminate.
+    fn resolve(&self, target: &mut ChildrenRef) -> bool {
+        if !target.state().is_terminal() {
+            return true;
+        }
+
+        match tree::group(target.id()) {
+            Some(relaunched) if !relaunched.state().is_terminal() => {
+                debug!("Bridge: Children({}) was relaunched.", target.id());
+                *target = relaunched;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<M: Message> Debug for Bridge<M> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Bridge")
+            .field("target", &self.target.id())
+            .field("exhaustion", &self.exhaustion)
+            .field("retry_delay", &self.retry_delay)
+            .field("target_grace", &self.target_grace)
+            .finish()
+    }
+}
@@ -1,5 +1,6 @@
 use crate::children_ref::ChildrenRef;
 use crate::context::BastionId;
+use crate::dead_letters::{self, DeadLetterReason};
 use crate::envelope::Envelope;
 use crate::message::BastionMessage;
 use crate::path::{BastionPath, BastionPathElement};
@@ -137,6 +138,16 @@ impl Broadcast {
         self.clear_children();
     }
 
+    // Stops every child but this one, which stays registered.
+    pub(crate) fn stop_children_except(&mut self, id: &BastionId) {
+        let kept = self.children.remove(id);
+        self.stop_children();
+
+        if let Some(kept) = kept {
+            self.children.insert(id.clone(), kept);
+        }
+    }
+
     pub(crate) fn kill_child(&mut self, id: &BastionId) {
         let msg = BastionMessage::kill();
         let env = Envelope::new(msg, self.path.clone(), self.sender.clone());
@@ -162,6 +173,15 @@ impl Broadcast {
         self.send_parent(env).ok();
     }
 
+    pub(crate) fn killed(&mut self) {
+        self.kill_children();
+
+        let msg = BastionMessage::killed(self.id().clone());
+        let env = Envelope::new(msg, self.path.clone(), self.sender.clone());
+        // FIXME: Err(msg)
+        self.send_parent(env).ok();
+    }
+
     pub(crate) fn faulted(&mut self) {
         self.kill_children();
 
@@ -184,15 +204,43 @@ impl Broadcast {
     }
 
     pub(crate) fn send_children(&self, env: Envelope) {
-        for child in self.children.values() {
+        for (id, child) in &self.children {
             // FIXME: Err(Error) if None
             if let Some(env) = env.try_clone() {
-                // FIXME: handle errors
-                child.unbounded_send(env).ok();
+                if let Err(err) = child.unbounded_send(env) {
+                    let reason = DeadLetterReason::RecipientStopped;
+                    dead_letters::bounce(err.into_inner(), Some(id), reason);
+                }
             }
         }
     }
 
+    // Sends the envelope to every child but this one.
+    pub(crate) fn send_children_except(&mut self, id: &BastionId, env: Envelope) {
+        let kept = self.children.remove(id);
+        self.send_children(env);
+
+        if let Some(kept) = kept {
+            self.children.insert(id.clone(), kept);
+        }
+    }
+
+    pub(crate) fn close(&mut self) {
+        self.recver.close();
+    }
+
+    // Closes the channel and returns the messages it still
+    // buffers.
+    pub(crate) fn drain(&mut self) -> Vec<Envelope> {
+        self.recver.close();
+        let mut envs = Vec::new();
+        while let Ok(env) = self.recver.try_recv() {
+            envs.push(env);
+        }
+
+        envs
+    }
+
     pub(crate) fn send_self(&self, env: Envelope) {
         // FIXME: handle errors
         self.sender.unbounded_send(env).ok();
@@ -232,7 +280,7 @@ impl Parent {
         }
     }
 
-    fn send(&self, env: Envelope) -> Result<(), Envelope> {
+    pub(crate) fn send(&self, env: Envelope) -> Result<(), Envelope> {
         match self {
             // FIXME
             Parent::None => unimplemented!(),
@@ -1,3 +1,4 @@
+use crate::errors::{ExecError, PanicReport};
 use std::fmt::{self, Debug, Formatter};
 use std::sync::Arc;
 
@@ -10,6 +11,11 @@ pub(crate) enum CallbackType {
     BeforeStart,
 }
 
+// The method called with the error returned by an element.
+type AfterError = Arc<dyn Fn(&ExecError) + Send + Sync>;
+// The method called with the report of an element's panic.
+type AfterPanic = Arc<dyn Fn(&PanicReport) + Send + Sync>;
+
 #[derive(Default, Clone)]
 /// A set of methods that will get called at different states of
 /// a [`Supervisor`] or [`Children`] life.
@@ -52,6 +58,8 @@ pub struct Callbacks {
     before_restart: Option<Arc<dyn Fn() + Send + Sync>>,
     after_restart: Option<Arc<dyn Fn() + Send + Sync>>,
     after_stop: Option<Arc<dyn Fn() + Send + Sync>>,
+    after_error: Option<AfterError>,
+    after_panic: Option<AfterPanic>,
 }
 
 impl Callbacks {
@@ -349,6 +357,119 @@ impl Callbacks {
         self
     }
 
+    /// Sets the method that will get called with the error returned
+    /// by the future of an element of a [`Children`], right before
+    /// the callback set using [`with_before_restart`] is.
+    ///
+    /// This method isn't called for a [`Supervisor`], nor when an
+    /// element panicked.
+    ///
+    /// # Arguments
+    ///
+    /// * `after_error` - The method that will get called.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     let callbacks = Callbacks::new()
+    ///         .with_after_error(|err: &ExecError| println!("Element faulted: {:?}", err));
+    ///
+    ///     children
+    ///         .with_exec_err(|ctx| {
+    ///             async move {
+    ///                 // ...
+    ///
+    ///                 // This will make the element fault...
+    ///                 Err("Something went wrong.")
+    ///             }
+    ///             // -- Element faulted: "Something went wrong."
+    ///         })
+    ///         .with_callbacks(callbacks)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Supervisor`]: supervisor/struct.Supervisor.html
+    /// [`Children`]: children/struct.Children.html
+    /// [`with_before_restart`]: #method.with_before_restart
+    pub fn with_after_error<C>(mut self, after_error: C) -> Self
+    where
+        C: Fn(&ExecError) + Send + Sync + 'static,
+    {
+        let after_error = Arc::new(after_error);
+        self.after_error = Some(after_error);
+        self
+    }
+
+    /// Sets the method that will get called with the report of
+    /// the panic of a [`Children`]'s element (its message, where
+    /// it panicked and its backtrace), before the callback set
+    /// using [`with_before_restart`] (or [`with_after_stop`]).
+    ///
+    /// This method isn't called for a [`Supervisor`], nor when an
+    /// element's future returned an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `after_panic` - The method that will get called.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     let callbacks = Callbacks::new()
+    ///         .with_after_panic(|panic: &PanicReport| println!("Element {}", panic))
+    ///         .with_before_restart(|| println!("Element restarting."));
+    ///
+    ///     children
+    ///         .with_exec(|ctx| {
+    ///             async move {
+    ///                 // ...
+    ///
+    ///                 // This will make the element fault...
+    ///                 panic!("Something went wrong.");
+    ///                 # Ok(())
+    ///             }
+    ///             // -- Element panicked at src/main.rs:12:17: Something went wrong.
+    ///             // -- Element restarting.
+    ///         })
+    ///         .with_callbacks(callbacks)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Supervisor`]: supervisor/struct.Supervisor.html
+    /// [`Children`]: children/struct.Children.html
+    /// [`with_before_restart`]: #method.with_before_restart
+    /// [`with_after_stop`]: #method.with_after_stop
+    pub fn with_after_panic<C>(mut self, after_panic: C) -> Self
+    where
+        C: Fn(&PanicReport) + Send + Sync + 'static,
+    {
+        let after_panic = Arc::new(after_panic);
+        self.after_panic = Some(after_panic);
+        self
+    }
+
     /// Returns whether a callback was defined using [`with_before_start`].
     ///
     /// # Example
@@ -429,6 +550,46 @@ impl Callbacks {
         self.after_stop.is_some()
     }
 
+    /// Returns whether a callback was defined using [`with_after_error`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    /// let callbacks = Callbacks::new()
+    ///     .with_after_error(|err| println!("Element faulted: {:?}", err));
+    ///
+    /// assert!(callbacks.has_after_error());
+    /// # }
+    /// ```
+    ///
+    /// [`with_after_error`]: #method.with_after_error
+    pub fn has_after_error(&self) -> bool {
+        self.after_error.is_some()
+    }
+
+    /// Returns whether a callback was defined using [`with_after_panic`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    /// let callbacks = Callbacks::new()
+    ///     .with_after_panic(|panic| println!("Element {}", panic));
+    ///
+    /// assert!(callbacks.has_after_panic());
+    /// # }
+    /// ```
+    ///
+    /// [`with_after_panic`]: #method.with_after_panic
+    pub fn has_after_panic(&self) -> bool {
+        self.after_panic.is_some()
+    }
+
     pub(crate) fn before_start(&self) {
         if let Some(before_start) = &self.before_start {
             before_start()
@@ -456,15 +617,29 @@ impl Callbacks {
             after_stop()
         }
     }
+
+    pub(crate) fn after_error(&self, err: &ExecError) {
+        if let Some(after_error) = &self.after_error {
+            after_error(err)
+        }
+    }
+
+    pub(crate) fn after_panic(&self, panic: &PanicReport) {
+        if let Some(after_panic) = &self.after_panic {
+            after_panic(panic)
+        }
+    }
 }
 
 impl Debug for Callbacks {
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
         fmt.debug_struct("Callbacks")
             .field("before_start", &self.before_start.is_some())
-            .field("before_restart", &self.before_start.is_some())
-            .field("after_restart", &self.before_start.is_some())
-            .field("after_stop", &self.before_start.is_some())
+            .field("before_restart", &self.before_restart.is_some())
+            .field("after_restart", &self.after_restart.is_some())
+            .field("after_stop", &self.after_stop.is_some())
+            .field("after_error", &self.after_error.is_some())
+            .field("after_panic", &self.after_panic.is_some())
             .finish()
     }
 }
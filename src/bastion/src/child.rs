@@ -3,24 +3,52 @@
 use crate::broadcast::Broadcast;
 use crate::callbacks::{CallbackType, Callbacks};
 use crate::child_ref::ChildRef;
-use crate::context::{BastionContext, BastionId, ContextState};
-use crate::envelope::Envelope;
-use crate::message::BastionMessage;
+use crate::children_ref::{MigrationError, MigrationReport};
+use crate::context::{BastionContext, BastionId, CancellationToken, ContextEvent, ContextState};
+use crate::dead_letters::{self, DeadLetterReason};
+use crate::envelope::{Envelope, SignedMessage};
+use crate::errors::{ExecError, PanicReport};
+use crate::event::FaultReason;
+use crate::flight_recorder::FlightRecorder;
+use crate::idle::GroupActivity;
+use crate::instrument::{ExecInfo, ExecWrapper};
+use crate::launch::LaunchPermit;
+use crate::lifeline::{Lifeline, ORPHAN_TIMEOUT};
+use crate::message::{BastionMessage, Priority};
+use crate::panic_hook;
+use crate::provenance::Tracker;
+use crate::reactor::{self, Sleep};
 use crate::system::SYSTEM;
+use crate::warmup::{Router, WarmupExec};
+use crate::watch::Termination;
+use bastion_executor::affinity::AffinityGroup;
 use bastion_executor::pool;
+use futures::channel::mpsc::UnboundedSender;
+use futures::future::{self, AbortHandle, Abortable, BoxFuture};
 use futures::pending;
 use futures::poll;
 use futures::prelude::*;
 use lightproc::prelude::*;
 use lightproc::proc_state::EmptyProcState;
 use qutex::Qutex;
+use std::any::Any;
 use std::fmt::{self, Debug, Formatter};
 use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
 use std::pin::Pin;
+use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
+use std::time::Instant;
 
 pub(crate) struct Init(pub(crate) Box<dyn Fn(BastionContext) -> Exec + Send + Sync>);
-pub(crate) struct Exec(Pin<Box<dyn Future<Output = Result<(), ()>> + Send>>);
+pub(crate) struct Exec(Pin<Box<dyn Future<Output = Result<(), ExecError>> + Send>>);
+
+// The closure set with `Children::with_async_init`, returning the
+// state passed to the closure set with
+// `StatefulChildren::with_exec`.
+pub(crate) type AsyncInit<S> =
+    Arc<dyn Fn(BastionContext) -> BoxFuture<'static, Result<S, ExecError>> + Send + Sync>;
+pub(crate) type ExecWithState<S> = Arc<dyn Fn(S, BastionContext) -> Exec + Send + Sync>;
 
 #[derive(Debug)]
 pub(crate) struct Child {
@@ -41,17 +69,70 @@ pub(crate) struct Child {
     pre_start_msgs: Vec<Envelope>,
     // A shortcut for accessing to this actor by others.
     child_ref: ChildRef,
+    // The token given to the child's context, cancelled once
+    // the child is dropped.
+    token: CancellationToken,
     started: bool,
+    // Whether the child was launched to replace a faulted one,
+    // in which case `after_restart` is called instead of
+    // `before_start`.
+    restarted: bool,
+    // Whether the callback matching the way the child
+    // terminated was already called.
+    terminated: bool,
+    // Whether the child's future is being polled, which is
+    // only the case when it is dropped if it panicked.
+    polling: bool,
+    // The report of the panic of the child's future, set before
+    // resuming it to be sent along with the fault.
+    panicked: Arc<Mutex<Option<PanicReport>>>,
+    // Whether the child was asked to stop once it is done
+    // handling its messages.
+    finishing: bool,
+    // The affinity group of the child's group, if it has one.
+    affinity: Option<AffinityGroup>,
+    // The provenance recorded by the child, if its group records
+    // it.
+    provenance: Option<Tracker>,
+    // The messages handled last by the child, if its group enables
+    // its flight recorder.
+    recorder: Option<FlightRecorder>,
+    // The activity of the child's group, which the child is
+    // counted as busy in until it is idle.
+    activity: Option<GroupActivity>,
+    idle: bool,
+    // The priority of the messages asked to the child without one.
+    ask_priority: Priority,
+    // The warmup run by the child once started, until it is warm
+    // and starts executing its future.
+    warmup: Option<WarmupExec>,
+    // The elements of the child's group that routed messages are
+    // sent to, which the child joins once it is warm.
+    routing: Option<Router>,
+    // Dropped along with the child, after its future and once
+    // its callbacks were called, to let its group know about it.
+    alive: Option<UnboundedSender<()>>,
+    // The permit the child holds until it is warm, if it was
+    // launched along with its group.
+    launch: Option<LaunchPermit>,
+    // The lifeline of the child's group, and the key its proc is
+    // tracked with by it once launched.
+    lifeline: Option<Lifeline>,
+    tracked: Option<usize>,
+    // When the child is killed if it didn't finish handling its
+    // messages, once its group's task was dropped.
+    orphaned: Option<Sleep>,
 }
 
 impl Init {
-    pub(crate) fn new<C, F>(init: C) -> Self
+    pub(crate) fn new<C, F, E>(init: C) -> Self
     where
         C: Fn(BastionContext) -> F + Send + Sync + 'static,
-        F: Future<Output = Result<(), ()>> + Send + 'static,
+        F: Future<Output = Result<(), E>> + Send + 'static,
+        E: Debug + Send + 'static,
     {
         let init = Box::new(move |ctx: BastionContext| {
-            let fut = init(ctx);
+            let fut = init(ctx).map_err(ExecError::new);
             let exec = Box::pin(fut);
 
             Exec(exec)
@@ -59,6 +140,47 @@ impl Init {
 
         Init(init)
     }
+
+    // Returns an init whose futures first wait for the state
+    // returned by `init` (faulting if it returns an error) and
+    // then execute the future returned by `exec` with it.
+    pub(crate) fn with_state<S: Send + 'static>(
+        init: AsyncInit<S>,
+        exec: ExecWithState<S>,
+    ) -> Self {
+        let init = Box::new(move |ctx: BastionContext| {
+            let init = init.clone();
+            let exec = exec.clone();
+            // The element is only initialized once it got its state.
+            let launch = ctx.launch_permit();
+            let fut = async move {
+                let state = init(ctx.duplicate()).await?;
+                drop(launch);
+                exec(state, ctx).await
+            };
+
+            Exec(Box::pin(fut))
+        });
+
+        Init(init)
+    }
+}
+
+pub(crate) fn async_init<I, F, S, E>(init: I) -> AsyncInit<S>
+where
+    I: Fn(BastionContext) -> F + Send + Sync + 'static,
+    F: Future<Output = Result<S, E>> + Send + 'static,
+    E: Debug + Send + 'static,
+{
+    Arc::new(move |ctx| init(ctx).map_err(ExecError::new).boxed())
+}
+
+pub(crate) fn exec_with_state<I, F, S>(exec: I) -> ExecWithState<S>
+where
+    I: Fn(S, BastionContext) -> F + Send + Sync + 'static,
+    F: Future<Output = Result<(), ()>> + Send + 'static,
+{
+    Arc::new(move |state, ctx| Exec(Box::pin(exec(state, ctx).map_err(ExecError::new))))
 }
 
 impl Child {
@@ -68,10 +190,29 @@ impl Child {
         bcast: Broadcast,
         state: Qutex<Pin<Box<ContextState>>>,
         child_ref: ChildRef,
+        token: CancellationToken,
     ) -> Self {
         debug!("Child({}): Initializing.", bcast.id());
         let pre_start_msgs = Vec::new();
         let started = false;
+        let restarted = false;
+        let terminated = false;
+        let polling = false;
+        let panicked = Arc::new(Mutex::new(None));
+        let finishing = false;
+        let affinity = None;
+        let provenance = None;
+        let recorder = None;
+        let activity = None;
+        let idle = false;
+        let ask_priority = Priority::default();
+        let warmup = None;
+        let routing = None;
+        let alive = None;
+        let launch = None;
+        let lifeline = None;
+        let tracked = None;
+        let orphaned = None;
 
         Child {
             bcast,
@@ -80,10 +221,96 @@ impl Child {
             state,
             pre_start_msgs,
             child_ref,
+            token,
             started,
+            restarted,
+            terminated,
+            polling,
+            panicked,
+            finishing,
+            affinity,
+            provenance,
+            recorder,
+            activity,
+            idle,
+            ask_priority,
+            warmup,
+            routing,
+            alive,
+            launch,
+            lifeline,
+            tracked,
+            orphaned,
         }
     }
 
+    /// Marks the child as replacing a faulted one.
+    pub(crate) fn restarted(mut self) -> Self {
+        self.restarted = true;
+        self
+    }
+
+    /// Sets the affinity group the child is spawned with.
+    pub(crate) fn with_affinity(mut self, affinity: Option<AffinityGroup>) -> Self {
+        self.affinity = affinity;
+        self
+    }
+
+    /// Makes the child record the provenance of the messages it
+    /// sends, with chains of at most `max_depth` entries.
+    pub(crate) fn with_provenance(mut self, max_depth: Option<usize>) -> Self {
+        self.provenance = max_depth.map(Tracker::new);
+        self
+    }
+
+    /// Makes the child record the last `capacity` messages it
+    /// handles, to report them if it faults.
+    pub(crate) fn with_flight_recorder(mut self, capacity: Option<usize>) -> Self {
+        self.recorder = capacity.map(FlightRecorder::new);
+        self
+    }
+
+    /// Sets the activity of the child's group, where the child is
+    /// counted as busy until it is idle.
+    pub(crate) fn with_activity(mut self, activity: GroupActivity) -> Self {
+        activity.busy();
+        self.activity = Some(activity);
+        self
+    }
+
+    /// Sets the priority of the messages asked to the child without
+    /// one.
+    pub(crate) fn with_ask_priority(mut self, priority: Priority) -> Self {
+        self.ask_priority = priority;
+        self
+    }
+
+    /// Sets the warmup the child runs once started, and the routing
+    /// it joins once warm.
+    pub(crate) fn with_warmup(mut self, warmup: Option<WarmupExec>, routing: Router) -> Self {
+        self.warmup = warmup;
+        self.routing = Some(routing);
+        self
+    }
+
+    /// Sets the sender dropped along with the child.
+    pub(crate) fn with_alive(mut self, alive: Option<UnboundedSender<()>>) -> Self {
+        self.alive = alive;
+        self
+    }
+
+    /// Sets the permit the child holds until it is warm.
+    pub(crate) fn with_launch_permit(mut self, launch: LaunchPermit) -> Self {
+        self.launch = Some(launch);
+        self
+    }
+
+    /// Sets the lifeline of the child's group.
+    pub(crate) fn with_lifeline(mut self, lifeline: Lifeline) -> Self {
+        self.lifeline = Some(lifeline);
+        self
+    }
+
     fn stack(&self) -> ProcStack {
         trace!("Child({}): Creating ProcStack.", self.id());
         let id = self.bcast.id().clone();
@@ -94,10 +321,13 @@ impl Child {
 
         let parent_inner = self.bcast.parent().clone().into_children();
         let child_ref_inner = self.child_ref.clone();
+        let provenance = self.provenance.clone();
+        let recorder = self.recorder.clone();
+        let panicked = self.panicked.clone();
 
         // FIXME: with_pid
         ProcStack::default().with_after_panic(move |_state: &mut EmptyProcState| {
-            warn!("Child({}): Panicked.", id);
+            debug!("Child({}): Panicked.", id);
 
             if let Some(parent) = &parent_inner {
                 let used_dispatchers = parent.dispatchers();
@@ -106,7 +336,24 @@ impl Child {
             }
 
             let id = id.clone();
-            let msg = BastionMessage::restart_required(id, parent.id().clone());
+            let provenance = provenance
+                .as_ref()
+                .map(Tracker::provenance)
+                .unwrap_or_default();
+            let records = recorder
+                .as_ref()
+                .map(FlightRecorder::records)
+                .unwrap_or_default();
+            // FIXME: panics?
+            let error = panicked.lock().unwrap().take().map(ExecError::panicked);
+            let msg = BastionMessage::restart_required(
+                id,
+                parent.id().clone(),
+                FaultReason::Panicked,
+                error,
+                provenance,
+                records,
+            );
             let env = Envelope::new(msg, path.clone(), sender.clone());
             // TODO: handle errors
             parent.send(env).ok();
@@ -120,18 +367,61 @@ impl Child {
     fn stopped(&mut self) {
         debug!("Child({}): Stopped.", self.id());
         self.remove_from_dispatchers();
+        self.bounce_unhandled();
         self.bcast.stopped();
+        self.child_ref.watchers().terminated(Termination::Stopped);
     }
 
-    fn faulted(&mut self) {
+    // Sends the messages the element received but won't handle
+    // anymore to the dead letters.
+    fn bounce_unhandled(&mut self) {
+        let id = self.id().clone();
+        let reason = DeadLetterReason::RecipientStopped;
+        for env in self.bcast.drain() {
+            dead_letters::bounce(env, Some(&id), reason);
+        }
+
+        // The element's future was dropped, so nothing should be
+        // holding its state anymore.
+        if let Some(Ok(mut guard)) = self.state.clone().lock_async().now_or_never() {
+            let mut state = guard.as_mut();
+            for SignedMessage { msg, sign } in state.take_messages() {
+                let env = Envelope::new_with_sign(BastionMessage::Message(msg), sign);
+                dead_letters::bounce(env, Some(&id), reason);
+            }
+        }
+    }
+
+    fn faulted(&mut self, error: ExecError) {
         debug!("Child({}): Faulted.", self.id());
         self.remove_from_dispatchers();
+        self.callbacks.after_error(&error);
+        self.terminate_with(CallbackType::BeforeRestart);
+        let termination = Termination::Faulted(FaultReason::Errored);
+        self.child_ref.watchers().terminated(termination);
 
         let parent = self.bcast.parent().clone().into_children().unwrap();
         let path = self.bcast.path().clone();
         let sender = self.bcast.sender().clone();
 
-        let msg = BastionMessage::restart_required(self.id().clone(), parent.id().clone());
+        let provenance = self
+            .provenance
+            .as_ref()
+            .map(Tracker::provenance)
+            .unwrap_or_default();
+        let records = self
+            .recorder
+            .as_ref()
+            .map(FlightRecorder::records)
+            .unwrap_or_default();
+        let msg = BastionMessage::restart_required(
+            self.id().clone(),
+            parent.id().clone(),
+            FaultReason::Errored,
+            Some(error),
+            provenance,
+            records,
+        );
         let env = Envelope::new(msg, path.clone(), sender.clone());
         // TODO: handle errors
         parent.send(env).ok();
@@ -147,16 +437,42 @@ impl Child {
                 msg: BastionMessage::Stop,
                 ..
             } => {
+                self.token.shut_down();
                 self.stopped();
-                self.callbacks.after_stop();
+                self.terminate_with(CallbackType::AfterStop);
                 return Err(());
             }
+            // The child keeps running its future until it waits for
+            // a message while its mailbox is empty.
+            Envelope {
+                msg: BastionMessage::StopWithin(_),
+                ..
+            } => {
+                debug!("Child({}): Finishing.", self.id());
+                self.token.shut_down();
+                self.finishing = true;
+            }
+            // The child's future is told to stop on its own.
+            Envelope {
+                msg: BastionMessage::Quiesce(deadline),
+                ..
+            } => {
+                debug!("Child({}): Quiescing.", self.id());
+                self.token.shut_down();
+                let mut guard = self.state.clone().lock_async().await.map_err(|_| ())?;
+                let event = ContextEvent::Quiesce {
+                    deadline: Instant::now() + deadline,
+                };
+                guard.as_mut().push_event(event);
+            }
             Envelope {
                 msg: BastionMessage::Kill,
                 ..
             } => {
+                self.token.shut_down();
+                self.child_ref.watchers().terminated(Termination::Killed);
                 self.stopped();
-                self.callbacks.before_restart();
+                self.terminate_with(CallbackType::AfterStop);
                 return Err(());
             }
             // FIXME
@@ -164,20 +480,32 @@ impl Child {
                 msg: BastionMessage::Deploy(_),
                 ..
             } => unimplemented!(),
-            // FIXME
+            // Elements don't have any element to prune or launch.
             Envelope {
                 msg: BastionMessage::Prune { .. },
                 ..
-            } => unimplemented!(),
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::CancelTask(_),
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Scale { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::SetRouting { .. },
+                ..
+            } => unreachable!(),
             Envelope {
                 msg: BastionMessage::ApplyCallback(callback_type),
                 ..
             } => self.apply_callback(callback_type),
-            // FIXME
+            // Elements don't supervise anything.
             Envelope {
                 msg: BastionMessage::SuperviseWith(_),
                 ..
-            } => unimplemented!(),
+            } => unreachable!(),
             Envelope {
                 msg: BastionMessage::InstantiatedChild { .. },
                 ..
@@ -185,11 +513,22 @@ impl Child {
             Envelope {
                 msg: BastionMessage::Message(msg),
                 sign,
+                slot,
             } => {
                 debug!("Child({}): Received a message: {:?}", self.id(), msg);
                 let mut guard = self.state.clone().lock_async().await.map_err(|_| ())?;
                 let mut state = guard.as_mut();
-                state.push_message(msg, sign);
+                let msg = msg.or_ask_priority(self.ask_priority);
+                let dropped = state.push_message(msg, sign, slot);
+                drop(guard);
+                if let Some(activity) = &self.activity {
+                    activity.touch();
+                }
+
+                for SignedMessage { msg, sign } in dropped {
+                    let env = Envelope::new_with_sign(BastionMessage::Message(msg), sign);
+                    dead_letters::bounce(env, Some(self.id()), DeadLetterReason::MailboxFull);
+                }
             }
             Envelope {
                 msg: BastionMessage::RestartRequired { .. },
@@ -199,6 +538,14 @@ impl Child {
                 msg: BastionMessage::RestartSubtree,
                 ..
             } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Degrade { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Recover,
+                ..
+            } => unreachable!(),
             Envelope {
                 msg: BastionMessage::RestoreChild { .. },
                 ..
@@ -228,11 +575,121 @@ impl Child {
                 msg: BastionMessage::Faulted { .. },
                 ..
             } => unimplemented!(),
+            Envelope {
+                msg: BastionMessage::MigrateChild { to, sender },
+                ..
+            } => {
+                let report = self.migrate(&to).await;
+                let migrated = report.is_ok();
+                // The element keeps on running as part of its group,
+                // with the messages that weren't transferred.
+                if let Err(err) = &report {
+                    warn!(
+                        "Child({}): Couldn't migrate to Child({}): {}",
+                        self.id(),
+                        to.id(),
+                        err
+                    );
+                }
+
+                if let Err(report) = sender.send(report) {
+                    debug!(
+                        "Child({}): The migration's report wasn't waited for: {:?}",
+                        self.id(),
+                        report
+                    );
+                }
+
+                if migrated {
+                    self.stopped();
+                    self.terminate_with(CallbackType::AfterStop);
+                    return Err(());
+                }
+            }
         }
 
         Ok(())
     }
 
+    async fn migrate(&mut self, to: &ChildRef) -> Result<MigrationReport, MigrationError> {
+        debug!("Child({}): Migrating to Child({}).", self.id(), to.id());
+        let mut guard = self
+            .state
+            .clone()
+            .lock_async()
+            .await
+            .map_err(|_| MigrationError::SourceStopped)?;
+        let mut state = guard.as_mut();
+
+        let mut msgs = state.take_messages();
+        let mut transferred = 0;
+        let mut dead_lettered = 0;
+        let mut dropped = 0;
+        while let Some(SignedMessage { msg, sign }) = msgs.pop_front() {
+            let env = Envelope::new_with_sign(BastionMessage::Message(msg), sign);
+            if let Err(env) = to.send(env) {
+                warn!(
+                    "Child({}): Child({}) stopped while migrating.",
+                    self.id(),
+                    to.id()
+                );
+                if let Envelope {
+                    msg: BastionMessage::Message(msg),
+                    sign,
+                    ..
+                } = env
+                {
+                    msgs.push_front(SignedMessage::new(msg, sign));
+                }
+                state.restore_messages(msgs);
+
+                return Err(MigrationError::TargetStopped);
+            }
+
+            transferred += 1;
+        }
+
+        // NOTE: closing the mailbox makes every racing sender get its
+        //      message back, while everything that was already
+        //      accepted is still buffered and forwarded below. The
+        //      mailbox's stream only ends once the messages that were
+        //      accepted but not yet pushed are received too.
+        self.bcast.close();
+        while let Some(env) = self.bcast.next().await {
+            match env {
+                Envelope {
+                    msg: BastionMessage::Message(_),
+                    ..
+                } => match to.send(env) {
+                    Ok(()) => transferred += 1,
+                    Err(env) => {
+                        let reason = DeadLetterReason::RecipientStopped;
+                        dead_letters::bounce(env, Some(to.id()), reason);
+                        dead_lettered += 1;
+                    }
+                },
+                env => {
+                    debug!(
+                        "Child({}): Dropping message while migrating: {:?}",
+                        self.id(),
+                        env
+                    );
+                    dropped += 1;
+                }
+            }
+        }
+
+        debug!(
+            "Child({}): Migrated {} messages to Child({}) ({} dead-lettered, {} dropped).",
+            self.id(),
+            transferred,
+            to.id(),
+            dead_lettered,
+            dropped
+        );
+        Ok(MigrationReport::new(transferred, dead_lettered, dropped))
+    }
+
     async fn initialize(&mut self) -> Result<(), ()> {
         trace!(
             "Child({}): Received a new message (started=false): {:?}",
@@ -240,7 +697,11 @@ impl Child {
             BastionMessage::Start
         );
         debug!("Child({}): Starting.", self.id());
-        self.callbacks.before_start();
+        if self.restarted {
+            self.callbacks.after_restart();
+        } else {
+            self.callbacks.before_start();
+        }
         self.started = true;
 
         let msgs = self.pre_start_msgs.drain(..).collect::<Vec<_>>();
@@ -269,9 +730,21 @@ impl Child {
         }
     }
 
+    // Calls the callback matching the way the child terminated,
+    // unless the child never started or one was already called.
+    fn terminate_with(&mut self, callback_type: CallbackType) {
+        if self.started && !self.terminated {
+            self.terminated = true;
+            self.apply_callback(callback_type);
+        }
+    }
+
     async fn run(mut self) {
         debug!("Child({}): Launched.", self.id());
-        self.register_in_dispatchers();
+        // Children without a warmup are warm right away.
+        if self.warmup.is_none() {
+            self.warmed();
+        }
 
         loop {
             match poll!(&mut self.bcast.next()) {
@@ -315,34 +788,197 @@ impl Child {
                 Poll::Pending => (),
             }
 
+            if self.supervise_orphan().await.is_err() {
+                return;
+            }
+
             if !self.started {
                 pending!();
 
                 continue;
             }
 
-            match poll!(&mut self.exec) {
+            if self.warmup.is_some() {
+                self.polling = true;
+                let poll = poll!(self.warmup.as_mut().unwrap());
+                self.polling = false;
+
+                if poll.is_pending() {
+                    pending!();
+
+                    continue;
+                }
+
+                debug!("Child({}): Warmed up.", self.id());
+                self.warmup = None;
+                self.warmed();
+            }
+
+            self.polling = true;
+            // The messages sent by the future record the message it
+            // is handling.
+            let entered = self.provenance.as_ref().map(Tracker::enter);
+            let recording = self.recorder.as_ref().map(FlightRecorder::enter);
+            let poll = poll!(AssertUnwindSafe(&mut self.exec).catch_unwind());
+            drop(recording);
+            drop(entered);
+            let poll = match poll {
+                Poll::Ready(Ok(done)) => Poll::Ready(done),
+                Poll::Ready(Err(payload)) => self.panicked(payload),
+                Poll::Pending => Poll::Pending,
+            };
+            self.polling = false;
+
+            match poll {
                 Poll::Ready(Ok(())) => {
                     debug!(
                         "Child({}): The future finished executing successfully.",
                         self.id()
                     );
-                    return self.stopped();
+                    self.stopped();
+                    return self.terminate_with(CallbackType::AfterStop);
                 }
-                Poll::Ready(Err(())) => {
-                    warn!("Child({}): The future returned an error.", self.id());
-                    return self.faulted();
+                Poll::Ready(Err(error)) => {
+                    debug!(
+                        "Child({}): The future returned an error: {:?}",
+                        self.id(),
+                        error
+                    );
+                    return self.faulted(error);
                 }
                 Poll::Pending => (),
             }
 
+            let idle = self.is_idle();
+            self.set_idle(idle);
+            if self.finishing && idle {
+                debug!("Child({}): Finished handling its messages.", self.id());
+                self.stopped();
+                return self.terminate_with(CallbackType::AfterStop);
+            }
+
+            // NOTE: both the mailbox and the exec future registered
+            //      this task's waker when they were polled above, so
+            //      the child is only polled again once one of them
+            //      can make progress.
             pending!();
         }
     }
 
-    pub(crate) fn launch(self) -> RecoverableHandle<()> {
+    // Stops the child once its group's task was dropped without
+    // stopping it (e.g. because its proc was cancelled), as it
+    // can't be supervised anymore: it finishes handling its
+    // messages first, and is killed if it didn't within
+    // `ORPHAN_TIMEOUT`.
+    async fn supervise_orphan(&mut self) -> Result<(), ()> {
+        if self.orphaned.is_none() {
+            let cut = match &mut self.lifeline {
+                Some(lifeline) => future::poll_fn(move |ctx| lifeline.poll_cut(ctx)),
+                None => return Ok(()),
+            };
+            if poll!(cut).is_pending() {
+                return Ok(());
+            }
+
+            warn!(
+                "Child({}): Orphaned: its group's task was dropped.",
+                self.id()
+            );
+            if !self.started {
+                self.stopped();
+                self.terminate_with(CallbackType::AfterStop);
+                return Err(());
+            }
+
+            self.token.shut_down();
+            self.finishing = true;
+            self.orphaned = Some(Sleep::new(reactor::now() + ORPHAN_TIMEOUT));
+        }
+
+        if let Some(timeout) = &mut self.orphaned {
+            if poll!(timeout).is_ready() {
+                warn!(
+                    "Child({}): Killed: orphaned and still running after {:?}.",
+                    self.id(),
+                    ORPHAN_TIMEOUT
+                );
+                self.child_ref.watchers().terminated(Termination::Killed);
+                self.stopped();
+                self.terminate_with(CallbackType::AfterStop);
+                return Err(());
+            }
+        }
+
+        Ok(())
+    }
+
+    // Reports the panic of the child's future before resuming it,
+    // the child then being dropped and restarted.
+    fn panicked(&self, payload: Box<dyn Any + Send>) -> ! {
+        let (location, backtrace) = panic_hook::take_captured();
+        let report = PanicReport::new(&*payload, location, backtrace);
+        debug!("Child({}): The future {}.", self.id(), report);
+        self.callbacks.after_panic(&report);
+        // FIXME: panics?
+        *self.panicked.lock().unwrap() = Some(report);
+
+        panic::resume_unwind(payload)
+    }
+
+    // Whether the child's future is waiting for a message while
+    // there isn't any left to retrieve.
+    fn is_idle(&self) -> bool {
+        match self.state.clone().lock_async().now_or_never() {
+            Some(Ok(guard)) => guard.is_idle(),
+            // The future is holding its state.
+            _ => false,
+        }
+    }
+
+    // Lets the child's group know whether the child became idle or
+    // busy.
+    fn set_idle(&mut self, idle: bool) {
+        if idle == self.idle {
+            return;
+        }
+
+        self.idle = idle;
+        if let Some(activity) = &self.activity {
+            if idle {
+                activity.idle();
+            } else {
+                activity.busy();
+            }
+        }
+    }
+
+    pub(crate) fn launch(mut self) -> RecoverableHandle<()> {
         let stack = self.stack();
-        pool::spawn(self.run(), stack)
+        // The group's supervisor cancels the child's proc if it
+        // didn't stop on its own once the group's task was dropped.
+        let (handle, registration) = AbortHandle::new_pair();
+        if let Some(lifeline) = &self.lifeline {
+            self.tracked = Some(lifeline.track(self.id().clone(), handle));
+        }
+
+        let affinity = self.affinity.clone();
+        let run = Abortable::new(self.run(), registration).map(|_| ());
+        match affinity {
+            Some(affinity) => pool::spawn_with_affinity(run, stack, &affinity),
+            None => pool::spawn(run, stack),
+        }
+    }
+
+    // Lets the child be sent routed messages (and the ones routed
+    // to its group while none of its elements was warm).
+    fn warmed(&mut self) {
+        self.launch.take();
+        self.register_in_dispatchers();
+        if let Some(routing) = &self.routing {
+            for env in routing.warmed(&self.child_ref) {
+                self.bcast.send_self(env);
+            }
+        }
     }
 
     /// Adds the actor into each registry declared in the parent node.
@@ -370,8 +1006,61 @@ impl Child {
     }
 }
 
+impl Drop for Child {
+    fn drop(&mut self) {
+        // The child terminated (or its proc was cancelled or
+        // panicked), whatever the reason.
+        self.token.cancel();
+        if let (Some(lifeline), Some(key)) = (&self.lifeline, self.tracked) {
+            lifeline.untrack(key);
+        }
+        self.set_idle(true);
+        if let Some(routing) = &self.routing {
+            routing.cooled(self.id());
+        }
+
+        // The proc was cancelled (e.g. when the group was killed
+        // or the element pruned) or panicked.
+        if self.polling {
+            self.terminate_with(CallbackType::BeforeRestart);
+        } else {
+            self.terminate_with(CallbackType::AfterStop);
+        }
+
+        // The watchers are only notified here if the child didn't
+        // stop or fault before being dropped.
+        let termination = if self.polling {
+            Termination::Faulted(FaultReason::Panicked)
+        } else {
+            Termination::Killed
+        };
+        self.child_ref.watchers().terminated(termination);
+    }
+}
+
+impl Exec {
+    // Wraps the future using `wrapper`, keeping the error it
+    // returns to report it if the wrapping future returns it.
+    pub(crate) fn wrap(self, wrapper: &ExecWrapper, info: ExecInfo) -> Self {
+        let error = Arc::new(Mutex::new(None));
+        let kept = error.clone();
+        let exec = self.0.map_err(move |err| {
+            // FIXME: panics?
+            *kept.lock().unwrap() = Some(err);
+        });
+
+        let wrapped = wrapper.wrap(info, Box::pin(exec)).map_err(move |()| {
+            // FIXME: panics?
+            let err = error.lock().unwrap().take();
+            err.unwrap_or_else(|| ExecError::new(()))
+        });
+
+        Exec(Box::pin(wrapped))
+    }
+}
+
 impl Future for Exec {
-    type Output = Result<(), ()>;
+    type Output = Result<(), ExecError>;
 
     fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
         Pin::new(&mut self.get_mut().0).poll(ctx)
@@ -380,7 +1069,7 @@ impl Future for Exec {
 
 impl Default for Init {
     fn default() -> Self {
-        Init::new(|_| async { Ok(()) })
+        Init::new(|_| async { Ok::<(), ()>(()) })
     }
 }
 
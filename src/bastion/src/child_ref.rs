@@ -1,34 +1,191 @@
 //!
 //! Allows users to communicate with Child through the mailboxes.
+use crate::answer_stream::AnswerStream;
 use crate::broadcast::Sender;
 use crate::context::BastionId;
+use crate::correlation::{Correlated, CorrelationId};
+use crate::dead_letters::{self, DeadLetterReason};
 use crate::envelope::{Envelope, RefAddr};
-use crate::message::{Answer, BastionMessage, Message};
+use crate::errors::{SendError, SendErrorKind, ShutdownError};
+use crate::mailbox::{Mailbox, OverflowPolicy, PendingAnswers};
+use crate::message::{AcceptedTypes, Answer, AnswerTimeout, BastionMessage, Message, Priority};
+use crate::metrics::{ChildCounters, ChildStats};
 use crate::path::BastionPath;
+use crate::schedule::{self, ScheduleHandle};
+use crate::system::SYSTEM;
+use crate::typed::TypedChildRef;
+use crate::watch::{Watcher, Watchers};
+use futures::future;
 use std::cmp::{Eq, PartialEq};
 use std::fmt::Debug;
 use std::hash::{Hash, Hasher};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
+use std::task::Poll;
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 /// A "reference" to an element of a children group, allowing to
 /// communicate with it.
 pub struct ChildRef {
     id: BastionId,
-    sender: Sender,
+    sender: SharedSender,
     path: Arc<BastionPath>,
+    accepted_types: AcceptedTypes,
+    mailbox: Option<Arc<Mailbox>>,
+    // The questions asked to the child that are waiting for its
+    // answer, if their number is bounded.
+    pending_answers: Option<Arc<PendingAnswers>>,
+    counters: Arc<ChildCounters>,
+    // Notified once the element terminates (which a restarted
+    // element doesn't share).
+    watchers: Arc<Watchers>,
 }
 
+#[derive(Debug, Clone)]
+// The sender of the element's current incarnation, shared by the
+// `ChildRef`s referencing it and replaced when it is restarted, so
+// that they keep on reaching it (it is read each time a message
+// is sent to the element, but only written when it restarts).
+pub(crate) struct SharedSender(Arc<RwLock<Sender>>);
+
 impl ChildRef {
     pub(crate) fn new(id: BastionId, sender: Sender, path: Arc<BastionPath>) -> ChildRef {
-        ChildRef { id, sender, path }
+        let sender = SharedSender(Arc::new(RwLock::new(sender)));
+        let accepted_types = AcceptedTypes::default();
+        let mailbox = None;
+        let pending_answers = None;
+        let counters = Arc::default();
+        let watchers = Arc::default();
+
+        ChildRef {
+            id,
+            sender,
+            path,
+            accepted_types,
+            mailbox,
+            pending_answers,
+            counters,
+            watchers,
+        }
+    }
+
+    pub(crate) fn with_accepted_types(mut self, accepted_types: AcceptedTypes) -> Self {
+        self.accepted_types = accepted_types;
+        self
+    }
+
+    // Makes this `ChildRef` share the sender of the `ChildRef`s
+    // referencing the previous incarnation of the element, after
+    // replacing it with its own.
+    pub(crate) fn with_shared_sender(mut self, shared: &SharedSender) -> Self {
+        shared.replace(self.sender());
+        self.sender = shared.clone();
+        self
+    }
+
+    pub(crate) fn shared_sender(&self) -> &SharedSender {
+        &self.sender
+    }
+
+    pub(crate) fn accepted_types(&self) -> &AcceptedTypes {
+        &self.accepted_types
+    }
+
+    pub(crate) fn with_mailbox(mut self, mailbox: Option<Arc<Mailbox>>) -> Self {
+        self.mailbox = mailbox;
+        self
+    }
+
+    pub(crate) fn mailbox(&self) -> Option<&Arc<Mailbox>> {
+        self.mailbox.as_ref()
+    }
+
+    pub(crate) fn with_pending_answers(
+        mut self,
+        pending_answers: Option<Arc<PendingAnswers>>,
+    ) -> Self {
+        self.pending_answers = pending_answers;
+        self
+    }
+
+    pub(crate) fn pending_answers(&self) -> Option<&Arc<PendingAnswers>> {
+        self.pending_answers.as_ref()
+    }
+
+    pub(crate) fn with_counters(mut self, counters: Arc<ChildCounters>) -> Self {
+        self.counters = counters;
+        self
+    }
+
+    pub(crate) fn counters(&self) -> &Arc<ChildCounters> {
+        &self.counters
+    }
+
+    pub(crate) fn with_watchers(mut self, watchers: Arc<Watchers>) -> Self {
+        self.watchers = watchers;
+        self
+    }
+
+    pub(crate) fn watchers(&self) -> &Arc<Watchers> {
+        &self.watchers
+    }
+
+    /// Returns a snapshot of the run-time statistics of the
+    /// children group element this `ChildRef` is referencing: the
+    /// number of messages waiting in its mailbox, of messages it
+    /// processed, of questions waiting for its answer and of times
+    /// it was restarted.
+    ///
+    /// Reading them doesn't wait for the element, so they can be
+    /// polled to make scaling decisions (see
+    /// [`ChildrenRef::stats`] for the statistics of a whole group).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    ///     # let child_ref = &children_ref.elems()[0];
+    /// let stats: ChildStats = child_ref.stats();
+    /// if stats.mailbox_len() > 1_000 {
+    ///     // Scale the group up...
+    /// }
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef::stats`]: ../children_ref/struct.ChildrenRef.html#method.stats
+    pub fn stats(&self) -> ChildStats {
+        let pending_answers = self
+            .pending_answers
+            .as_ref()
+            .map_or(0, |pending| pending.len());
+        self.counters.stats(&self.id, pending_answers)
+    }
+
+    /// Returns the number of messages sent to the children group
+    /// element this `ChildRef` is referencing that it didn't
+    /// retrieve yet (see [`stats`]).
+    ///
+    /// [`stats`]: #method.stats
+    pub fn mailbox_len(&self) -> usize {
+        self.counters.mailbox_len()
     }
 
     /// Returns the identifier of the children group element this
     /// `ChildRef` is referencing.
     ///
-    /// Note that the children group element's identifier is reset
-    /// when it is restarted.
+    /// Note that the children group element's identifier is kept
+    /// when it is restarted, but not when its group is deployed
+    /// again unless the group uses stable identifiers (see
+    /// [`Children::with_stable_ids`]).
     ///
     /// # Example
     ///
@@ -53,16 +210,39 @@ impl ChildRef {
     ///     # Bastion::block_until_stopped();
     /// # }
     /// ```
+    ///
+    /// [`Children::with_stable_ids`]: ../children/struct.Children.html#method.with_stable_ids
     pub fn id(&self) -> &BastionId {
         &self.id
     }
 
+    /// Returns a [`TypedChildRef`] wrapping this `ChildRef`, whose
+    /// methods only take messages of type `M`, if the element it is
+    /// referencing only accepts messages of this type (see
+    /// [`Bastion::typed_children`]), or `None` otherwise.
+    ///
+    /// [`TypedChildRef`]: ../typed/struct.TypedChildRef.html
+    /// [`Bastion::typed_children`]: ../struct.Bastion.html#method.typed_children
+    pub fn typed<M: Message>(&self) -> Option<TypedChildRef<M>> {
+        if !self.accepted_types.only::<M>() {
+            return None;
+        }
+
+        Some(TypedChildRef::new(self.clone()))
+    }
+
     /// Sends a message to the child this `ChildRef` is referencing.
     /// This message is intended to be used outside of Bastion context when
     /// there is no way for receiver to identify message sender
     ///
-    /// This method returns `()` if it succeeded, or `Err(msg)`
-    /// otherwise.
+    /// This method returns `()` if it succeeded, or a
+    /// [`SendError`](../errors/struct.SendError.html) carrying the message otherwise.
+    /// If the child's group only accepts some message types (see
+    /// [`Children::with_accepted_types`]), a message of another type
+    /// is rejected with [`SendErrorKind::TypeNotAccepted`] instead of
+    /// being sent. If the child's mailbox is bounded and full (see
+    /// [`Children::with_mailbox_capacity`]), what happens to the
+    /// message depends on its group's [`OverflowPolicy`].
     ///
     /// # Argument
     ///
@@ -108,12 +288,285 @@ impl ChildRef {
     ///     # Bastion::block_until_stopped();
     /// # }
     /// ```
-    pub fn tell_anonymously<M: Message>(&self, msg: M) -> Result<(), M> {
+    ///
+    /// [`Children::with_accepted_types`]: ../children/struct.Children.html#method.with_accepted_types
+    /// [`SendErrorKind::TypeNotAccepted`]: ../errors/enum.SendErrorKind.html#variant.TypeNotAccepted
+    /// [`Children::with_mailbox_capacity`]: ../children/struct.Children.html#method.with_mailbox_capacity
+    /// [`OverflowPolicy`]: ../mailbox/enum.OverflowPolicy.html
+    pub fn tell_anonymously<M: Message>(&self, msg: M) -> Result<(), SendError<M>> {
+        if let Err(kind) = self.accepted_types.check::<M>() {
+            return Err(SendError::new(kind, msg));
+        }
+
+        self.tell_unchecked(msg)
+    }
+
+    /// Sends a message to the child this `ChildRef` is referencing
+    /// once `delay` elapsed, like [`tell_anonymously`] does, the
+    /// system's reactor waking the timer once it expires (which can
+    /// be a few milliseconds late).
+    ///
+    /// The message is sent to the dead letters instead if the child
+    /// stops or is killed before (see [`watch`]) or can't receive it
+    /// once the delay elapsed, and dropped if the returned
+    /// [`ScheduleHandle`] is cancelled before. If the child faults
+    /// and is restarted before, the message is sent to the
+    /// restarted child.
+    ///
+    /// This method returns a [`ScheduleHandle`] if the child's
+    /// group accepts messages of this type, or a
+    /// [`SendError`](../errors/struct.SendError.html) carrying the
+    /// message otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    /// * `delay` - How long to wait before sending it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    ///     # let child_ref = &children_ref.elems()[0];
+    /// let handle = child_ref
+    ///     .tell_after("timeout", Duration::from_secs(30))
+    ///     .expect("Couldn't schedule the message.");
+    /// // The message isn't needed anymore...
+    /// handle.cancel();
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`tell_anonymously`]: #method.tell_anonymously
+    /// [`watch`]: #method.watch
+    /// [`ScheduleHandle`]: ../schedule/struct.ScheduleHandle.html
+    pub fn tell_after<M: Message>(
+        &self,
+        msg: M,
+        delay: Duration,
+    ) -> Result<ScheduleHandle, SendError<M>> {
+        debug!(
+            "ChildRef({}): Telling message after {:?}: {:?}",
+            self.id(),
+            delay,
+            msg
+        );
+        if let Err(kind) = self.accepted_types.check::<M>() {
+            return Err(SendError::new(kind, msg));
+        }
+
+        Ok(schedule::tell_after(self.clone(), msg, delay))
+    }
+
+    /// Sends a message to the child this `ChildRef` is referencing,
+    /// like [`tell_anonymously`] does, but without checking whether
+    /// its group accepts messages of this type (e.g. because the
+    /// message's type is only known at runtime).
+    ///
+    /// This method returns `()` if it succeeded, or a
+    /// [`SendError`](../errors/struct.SendError.html) carrying the message otherwise.
+    ///
+    /// # Argument
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// [`tell_anonymously`]: #method.tell_anonymously
+    pub fn tell_unchecked<M: Message>(&self, msg: M) -> Result<(), SendError<M>> {
         debug!("ChildRef({}): Telling message: {:?}", self.id(), msg);
         let msg = BastionMessage::tell(msg);
         let env = Envelope::from_dead_letters(msg);
         // FIXME: panics?
-        self.send(env).map_err(|env| env.into_msg().unwrap())
+        self.send_bounded(env)
+            .map_err(|(kind, env)| SendError::new(kind, env.into_msg().unwrap()))
+    }
+
+    /// Sends a message to the child this `ChildRef` is referencing,
+    /// like [`tell_anonymously`] does, but waiting until its mailbox
+    /// has room for it if it is full and its group's overflow policy
+    /// is [`OverflowPolicy::Block`] (see
+    /// [`Children::with_mailbox_capacity`]).
+    ///
+    /// This method returns a [`Future`] resolving to `()` once the
+    /// message was sent, or to a
+    /// [`SendError`](../errors/struct.SendError.html) carrying the
+    /// message otherwise. With another overflow policy, it resolves
+    /// right away to what [`tell_anonymously`] returns.
+    ///
+    /// # Argument
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children
+    ///         .with_mailbox_capacity(16)
+    ///         .with_overflow_policy(OverflowPolicy::Block)
+    ///         .with_exec(|ctx: BastionContext| async move {
+    ///             loop {
+    ///                 ctx.recv().await?;
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// let child_ref = &children_ref.elems()[0];
+    /// run!(async {
+    ///     for n in 0..64u64 {
+    ///         child_ref.tell_async(n).await.expect("Couldn't send the message.");
+    ///     }
+    /// });
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`tell_anonymously`]: #method.tell_anonymously
+    /// [`OverflowPolicy::Block`]: ../mailbox/enum.OverflowPolicy.html#variant.Block
+    /// [`Children::with_mailbox_capacity`]: ../children/struct.Children.html#method.with_mailbox_capacity
+    /// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+    pub async fn tell_async<M: Message>(&self, msg: M) -> Result<(), SendError<M>> {
+        let mailbox = match &self.mailbox {
+            Some(mailbox) if mailbox.policy() == OverflowPolicy::Block => mailbox,
+            _ => return self.tell_anonymously(msg),
+        };
+
+        if let Err(kind) = self.accepted_types.check::<M>() {
+            return Err(SendError::new(kind, msg));
+        }
+
+        debug!("ChildRef({}): Telling message: {:?}", self.id(), msg);
+        let msg = BastionMessage::tell(msg);
+        let mut env = Envelope::from_dead_letters(msg);
+        // The element's mailbox isn't emptied anymore once it
+        // stopped.
+        env.slot = future::poll_fn(|ctx| {
+            if self.sender().is_closed() {
+                return Poll::Ready(None);
+            }
+
+            mailbox.poll_reserve(ctx).map(Some)
+        })
+        .await;
+
+        // FIXME: panics?
+        self.send(env)
+            .map_err(|env| SendError::stopped(env.into_msg().unwrap()))
+    }
+
+    /// Sends a message to the child this `ChildRef` is referencing,
+    /// along with a new [`CorrelationId`] that the child can use to
+    /// send a reply to the global [`ReplyBroker`], using
+    /// [`BastionContext::reply_correlated`].
+    ///
+    /// The child receives the message as a [`Correlated`] message.
+    /// Like with [`tell_anonymously`], the child can't identify the
+    /// message's sender.
+    ///
+    /// This method returns the [`CorrelationId`] that can be used
+    /// to wait for the reply using [`ReplyBroker::wait`] if it
+    /// succeeded, or a [`SendError`](../errors/struct.SendError.html)
+    /// carrying the message otherwise (including if too many
+    /// correlation ids are already waiting for a reply).
+    ///
+    /// # Argument
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref =
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             msg! { ctx.recv().await?,
+    ///                 req: Correlated<&'static str> => {
+    ///                     assert_eq!(*req.msg(), "Ping");
+    ///                     ctx.reply_correlated(req.id(), "Pong").ok();
+    ///                 };
+    ///                 _: _ => ();
+    ///             }
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    ///     # Bastion::start();
+    ///     # let child_ref = &children_ref.elems()[0];
+    /// let id: CorrelationId = child_ref
+    ///     .tell_correlated("Ping")
+    ///     .expect("Couldn't send the message.");
+    ///
+    /// let reply = run!(Bastion::reply_broker().wait(&id, Duration::from_secs(1)));
+    /// msg! { reply.expect("Didn't receive a reply."),
+    ///     msg: &'static str => {
+    ///         assert_eq!(msg, "Pong");
+    ///     };
+    ///     _: _ => ();
+    /// }
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`CorrelationId`]: ../correlation/struct.CorrelationId.html
+    /// [`ReplyBroker`]: ../correlation/struct.ReplyBroker.html
+    /// [`ReplyBroker::wait`]: ../correlation/struct.ReplyBroker.html#method.wait
+    /// [`BastionContext::reply_correlated`]: ../context/struct.BastionContext.html#method.reply_correlated
+    /// [`Correlated`]: ../correlation/struct.Correlated.html
+    /// [`tell_anonymously`]: #method.tell_anonymously
+    pub fn tell_correlated<M: Message>(&self, msg: M) -> Result<CorrelationId, SendError<M>> {
+        if let Err(kind) = self.accepted_types.check::<M>() {
+            return Err(SendError::new(kind, msg));
+        }
+
+        debug!(
+            "ChildRef({}): Telling correlated message: {:?}",
+            self.id(),
+            msg
+        );
+        let broker = SYSTEM.reply_broker();
+        let id = match broker.register() {
+            Some(id) => id,
+            None => return Err(SendError::new(SendErrorKind::Full, msg)),
+        };
+
+        let msg = BastionMessage::tell_correlated(id, msg);
+        let env = Envelope::from_dead_letters(msg);
+        match self.send_bounded(env) {
+            Ok(()) => Ok(id),
+            Err((kind, env)) => {
+                broker.unregister(&id);
+                // FIXME: panics?
+                let correlated: Correlated<M> = env.into_msg().unwrap();
+                Err(SendError::new(kind, correlated.into_msg()))
+            }
+        }
     }
 
     /// Sends a message to the child this `ChildRef` is referencing,
@@ -121,8 +574,13 @@ impl ChildRef {
     /// This message is intended to be used outside of Bastion context when
     /// there is no way for receiver to identify message sender
     ///
-    /// This method returns [`Answer`](../message/struct.Answer.html) if it succeeded, or `Err(msg)`
-    /// otherwise.
+    /// This method returns [`Answer`](../message/struct.Answer.html) if it succeeded, or a
+    /// [`SendError`](../errors/struct.SendError.html) carrying the message otherwise.
+    /// Like with [`tell_anonymously`](#method.tell_anonymously), a
+    /// message of a type the child's group doesn't accept is rejected,
+    /// and so is a message asked while too many questions are waiting
+    /// for the child's answer (see
+    /// [`Children::with_max_pending_answers`]).
     ///
     /// # Argument
     ///
@@ -194,21 +652,381 @@ impl ChildRef {
     /// ```
     ///
     /// [`Answer`]: message/struct.Answer.html
-    pub fn ask_anonymously<M: Message>(&self, msg: M) -> Result<Answer, M> {
+    pub fn ask_anonymously<M: Message>(&self, msg: M) -> Result<Answer, SendError<M>> {
+        if let Err(kind) = self.accepted_types.check::<M>() {
+            return Err(SendError::new(kind, msg));
+        }
+
+        self.ask_unchecked(msg)
+    }
+
+    /// Sends a message to the child this `ChildRef` is referencing,
+    /// allowing it to reply to it several times (e.g. to send the
+    /// partial results of a long-running query) using the
+    /// [`AnswerStreamSender`] it is sent with, which it can take
+    /// using [`SignedMessage::take_stream_sender`].
+    ///
+    /// Unlike the messages sent using [`ask_anonymously`], the
+    /// message is received as if it was told (it can't be answered
+    /// using [`answer!`]).
+    ///
+    /// This method returns the [`AnswerStream`] receiving the
+    /// replies if it succeeded, or a
+    /// [`SendError`](../errors/struct.SendError.html) carrying the
+    /// message otherwise.
+    ///
+    /// # Argument
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use futures::StreamExt;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             loop {
+    ///                 let mut msg = ctx.recv().await?;
+    ///                 // The sender needs to be taken before matching
+    ///                 // the message...
+    ///                 let sender = msg.take_stream_sender();
+    ///                 msg! { msg,
+    ///                     rows: u64 => {
+    ///                         if let Some(mut sender) = sender {
+    ///                             for row in 0..rows {
+    ///                                 sender.reply(row).await.ok();
+    ///                             }
+    ///
+    ///                             // ...and closed once every reply was sent.
+    ///                             sender.close();
+    ///                         }
+    ///                     };
+    ///                     _: _ => ();
+    ///                 }
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///
+    /// let replies = children_ref.elems()[0]
+    ///     .ask_stream(3u64)
+    ///     .expect("Couldn't send the message.");
+    /// let rows = run!(replies.collect::<Vec<_>>());
+    /// assert_eq!(rows.len(), 3);
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`AnswerStreamSender`]: ../answer_stream/struct.AnswerStreamSender.html
+    /// [`SignedMessage::take_stream_sender`]: ../envelope/struct.SignedMessage.html#method.take_stream_sender
+    /// [`ask_anonymously`]: #method.ask_anonymously
+    /// [`answer!`]: ../macro.answer.html
+    /// [`AnswerStream`]: ../answer_stream/struct.AnswerStream.html
+    pub fn ask_stream<M: Message>(&self, msg: M) -> Result<AnswerStream, SendError<M>> {
+        debug!(
+            "ChildRef({}): Asking message to stream: {:?}",
+            self.id(),
+            msg
+        );
+        if let Err(kind) = self.accepted_types.check::<M>() {
+            return Err(SendError::new(kind, msg));
+        }
+
+        let (msg, stream) = BastionMessage::ask_stream(msg);
+        let env = Envelope::from_dead_letters(msg);
+        // FIXME: panics?
+        self.send_bounded(env)
+            .map_err(|(kind, env)| SendError::new(kind, env.into_msg().unwrap()))?;
+
+        Ok(stream)
+    }
+
+    /// Sends a message to the child this `ChildRef` is referencing,
+    /// allowing it to answer like [`ask_anonymously`] does, but
+    /// waiting until one of the questions waiting for the child's
+    /// answer was answered if there are too many of them (see
+    /// [`Children::with_max_pending_answers`]).
+    ///
+    /// This method returns a [`Future`] resolving to an [`Answer`]
+    /// once the message was sent, or to a
+    /// [`SendError`](../errors/struct.SendError.html) carrying the
+    /// message otherwise.
+    ///
+    /// # Argument
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children
+    ///         .with_max_pending_answers(16)
+    ///         .with_exec(|ctx: BastionContext| async move {
+    ///             loop {
+    ///                 msg! { ctx.recv().await?,
+    ///                     n: u64 =!> {
+    ///                         answer!(ctx, n * 2).ok();
+    ///                     };
+    ///                     _: _ => ();
+    ///                 }
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// let child_ref = &children_ref.elems()[0];
+    /// let answers = run!(async {
+    ///     let mut answers = Vec::new();
+    ///     for n in 0..64u64 {
+    ///         answers.push(child_ref.ask_async(n).await.expect("Couldn't send the message."));
+    ///     }
+    ///
+    ///     answers
+    /// });
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ask_anonymously`]: #method.ask_anonymously
+    /// [`Children::with_max_pending_answers`]: ../children/struct.Children.html#method.with_max_pending_answers
+    /// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+    /// [`Answer`]: ../message/struct.Answer.html
+    pub async fn ask_async<M: Message>(&self, msg: M) -> Result<Answer, SendError<M>> {
+        let pending_answers = match &self.pending_answers {
+            Some(pending_answers) => pending_answers,
+            None => return self.ask_anonymously(msg),
+        };
+
+        if let Err(kind) = self.accepted_types.check::<M>() {
+            return Err(SendError::new(kind, msg));
+        }
+
         debug!("ChildRef({}): Asking message: {:?}", self.id(), msg);
         let (msg, answer) = BastionMessage::ask(msg);
+        // No question is answered anymore once the element stopped.
+        let slot = future::poll_fn(|ctx| {
+            if self.sender().is_closed() {
+                return Poll::Ready(None);
+            }
+
+            pending_answers.poll_reserve(ctx).map(Some)
+        })
+        .await;
+
+        let msg = match slot {
+            Some(slot) => msg.with_answer_slot(slot),
+            // FIXME: panics?
+            None => return Err(SendError::stopped(msg.into_msg().unwrap())),
+        };
+
         let env = Envelope::from_dead_letters(msg);
         // FIXME: panics?
-        self.send(env).map_err(|env| env.into_msg().unwrap())?;
+        self.send_bounded(env)
+            .map_err(|(kind, env)| SendError::new(kind, env.into_msg().unwrap()))?;
 
         Ok(answer)
     }
 
+    /// Sends a message to the child this `ChildRef` is referencing,
+    /// allowing it to answer before the timeout expires, like
+    /// [`ask_anonymously`] does.
+    ///
+    /// This method returns an [`AnswerTimeout`] if it succeeded,
+    /// resolving to [`AnswerError::TimedOut`] if the child didn't
+    /// answer in time (see [`Answer::timeout`]), or a
+    /// [`SendError`](../errors/struct.SendError.html) carrying the
+    /// message otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    /// * `timeout` - How long to wait for the answer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             loop {
+    ///                 msg! { ctx.recv().await?,
+    ///                     msg: &'static str =!> {
+    ///                         answer!(ctx, msg).ok();
+    ///                     };
+    ///                     _: _ => ();
+    ///                 }
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     # Bastion::start();
+    ///
+    /// let child_ref = &children_ref.elems()[0];
+    /// let answer = child_ref
+    ///     .ask_timeout("hello", Duration::from_secs(1))
+    ///     .expect("Couldn't send the message.");
+    ///
+    /// msg! { run!(answer).expect("Couldn't receive the answer in time."),
+    ///     msg: &'static str => {
+    ///         assert_eq!(msg, "hello");
+    ///     };
+    ///     _: _ => ();
+    /// }
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ask_anonymously`]: #method.ask_anonymously
+    /// [`AnswerTimeout`]: ../message/struct.AnswerTimeout.html
+    /// [`AnswerError::TimedOut`]: ../errors/enum.AnswerError.html#variant.TimedOut
+    /// [`Answer::timeout`]: ../message/struct.Answer.html#method.timeout
+    pub fn ask_timeout<M: Message>(
+        &self,
+        msg: M,
+        timeout: Duration,
+    ) -> Result<AnswerTimeout, SendError<M>> {
+        self.ask_anonymously(msg)
+            .map(|answer| answer.timeout(timeout))
+    }
+
+    /// Sends a message to the child this `ChildRef` is referencing,
+    /// allowing it to answer, like [`ask_anonymously`] does, but
+    /// landing in the lane of its mailbox matching `priority`
+    /// instead of the one its group's [`with_ask_priority`]
+    /// defaults to.
+    ///
+    /// A message asked with [`Priority::High`] is retrieved by the
+    /// child before the `Normal` ones it received before it, and
+    /// its answer inherits the same priority.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    /// * `priority` - The priority of the message and its answer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             loop {
+    ///                 msg! { ctx.recv().await?,
+    ///                     msg: &'static str =!> {
+    ///                         answer!(ctx, msg).ok();
+    ///                     };
+    ///                     _: _ => ();
+    ///                 }
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     # Bastion::start();
+    ///
+    /// let child_ref = &children_ref.elems()[0];
+    /// let answer = child_ref
+    ///     .ask_with_priority("hello", Priority::High)
+    ///     .expect("Couldn't send the message.");
+    ///
+    /// let (answer, _) = run!(answer).expect("Couldn't receive the answer.").extract();
+    /// assert_eq!(answer.priority(), Priority::High);
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ask_anonymously`]: #method.ask_anonymously
+    /// [`with_ask_priority`]: ../children/struct.Children.html#method.with_ask_priority
+    /// [`Priority::High`]: ../message/enum.Priority.html#variant.High
+    pub fn ask_with_priority<M: Message>(
+        &self,
+        msg: M,
+        priority: Priority,
+    ) -> Result<Answer, SendError<M>> {
+        if let Err(kind) = self.accepted_types.check::<M>() {
+            return Err(SendError::new(kind, msg));
+        }
+
+        debug!(
+            "ChildRef({}): Asking message with priority {:?}: {:?}",
+            self.id(),
+            priority,
+            msg
+        );
+        let (msg, answer) = BastionMessage::ask_with_priority(msg, priority);
+        self.send_asked(msg)?;
+
+        Ok(answer)
+    }
+
+    // Asks a message without checking whether the child's group
+    // accepts messages of this type.
+    pub(crate) fn ask_unchecked<M: Message>(&self, msg: M) -> Result<Answer, SendError<M>> {
+        debug!("ChildRef({}): Asking message: {:?}", self.id(), msg);
+        let (msg, answer) = BastionMessage::ask(msg);
+        self.send_asked(msg)?;
+
+        Ok(answer)
+    }
+
+    fn send_asked<M: Message>(&self, msg: BastionMessage) -> Result<(), SendError<M>> {
+        let msg = match &self.pending_answers {
+            Some(pending_answers) => match pending_answers.reserve() {
+                Some(slot) => msg.with_answer_slot(slot),
+                None => {
+                    debug!(
+                        "ChildRef({}): Too many questions waiting for an answer.",
+                        self.id()
+                    );
+                    let kind = SendErrorKind::TooManyPending;
+                    // FIXME: panics?
+                    return Err(SendError::new(kind, msg.into_msg().unwrap()));
+                }
+            },
+            None => msg,
+        };
+
+        let env = Envelope::from_dead_letters(msg);
+        // FIXME: panics?
+        self.send_bounded(env)
+            .map_err(|(kind, env)| SendError::new(kind, env.into_msg().unwrap()))
+    }
+
     /// Sends a message to the child this `ChildRef` is referencing
     /// to tell it to stop its execution.
     ///
-    /// This method returns `()` if it succeeded, or `Err(())`
-    /// otherwise.
+    /// This method returns `()` if it succeeded, or a
+    /// [`ShutdownError`](../errors/enum.ShutdownError.html) otherwise.
     ///
     /// # Example
     ///
@@ -227,18 +1045,18 @@ impl ChildRef {
     ///     # Bastion::block_until_stopped();
     /// # }
     /// ```
-    pub fn stop(&self) -> Result<(), ()> {
+    pub fn stop(&self) -> Result<(), ShutdownError> {
         debug!("ChildRef({}): Stopping.", self.id);
         let msg = BastionMessage::stop();
         let env = Envelope::from_dead_letters(msg);
-        self.send(env).map_err(|_| ())
+        self.send(env).map_err(|_| ShutdownError::AlreadyStopped)
     }
 
     /// Sends a message to the child this `ChildRef` is referencing
     /// to tell it to suicide.
     ///
-    /// This method returns `()` if it succeeded, or `Err(())`
-    /// otherwise.
+    /// This method returns `()` if it succeeded, or a
+    /// [`ShutdownError`](../errors/enum.ShutdownError.html) otherwise.
     ///
     /// # Example
     ///
@@ -257,27 +1075,108 @@ impl ChildRef {
     ///     # Bastion::block_until_stopped();
     /// # }
     /// ```
-    pub fn kill(&self) -> Result<(), ()> {
+    pub fn kill(&self) -> Result<(), ShutdownError> {
         debug!("ChildRef({}): Killing.", self.id());
         let msg = BastionMessage::kill();
         let env = Envelope::from_dead_letters(msg);
-        self.send(env).map_err(|_| ())
+        self.send(env).map_err(|_| ShutdownError::AlreadyStopped)
+    }
+
+    /// Returns a [`Watcher`] resolving to how the child this
+    /// `ChildRef` is referencing terminated (see [`Termination`])
+    /// once it stopped, faulted or was killed, or right away if it
+    /// already did.
+    ///
+    /// If the child faults and is restarted, the watcher still
+    /// resolves (to [`Termination::Faulted`]): the restarted child
+    /// can be watched using the `ChildRef`s referencing it after
+    /// its restart (e.g. retrieved again using
+    /// [`ChildrenRef::elems`]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    /// let backend = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| async move {
+    ///         // Handle the messages forwarded by the proxy...
+    ///         ctx.recv().await?;
+    ///         Ok(())
+    ///     })
+    /// })
+    /// .expect("Couldn't create the children group.");
+    /// let backend = backend.elems()[0].clone();
+    ///
+    /// Bastion::children(|children| {
+    ///     children.with_exec(move |ctx: BastionContext| {
+    ///         let backend_ref = backend.clone();
+    ///         async move {
+    ///             // The proxy stops once its backend terminated.
+    ///             let termination = backend_ref.watch().await;
+    ///             # drop(ctx);
+    ///             println!("The backend terminated: {:?}", termination);
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// })
+    /// .expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Watcher`]: ../watch/struct.Watcher.html
+    /// [`Termination`]: ../watch/enum.Termination.html
+    /// [`Termination::Faulted`]: ../watch/enum.Termination.html#variant.Faulted
+    /// [`ChildrenRef::elems`]: ../children_ref/struct.ChildrenRef.html#method.elems
+    pub fn watch(&self) -> Watcher {
+        debug!("ChildRef({}): Watching.", self.id());
+        self.watchers.watch()
     }
 
     /// Returns [`RefAddr`] for the child
     pub fn addr(&self) -> RefAddr {
-        RefAddr::new(self.path.clone(), self.sender.clone())
+        RefAddr::new(self.path.clone(), self.sender())
+            .with_pending_answers(self.pending_answers.clone())
     }
 
     pub(crate) fn send(&self, env: Envelope) -> Result<(), Envelope> {
         trace!("ChildRef({}): Sending message: {:?}", self.id(), env);
-        self.sender
+        self.sender()
             .unbounded_send(env)
             .map_err(|err| err.into_inner())
     }
 
-    pub(crate) fn sender(&self) -> &Sender {
-        &self.sender
+    // Sends a message taking a place in the child's mailbox if it
+    // is bounded, applying its overflow policy if it is full.
+    pub(crate) fn send_bounded(&self, mut env: Envelope) -> Result<(), (SendErrorKind, Envelope)> {
+        if let Some(mailbox) = &self.mailbox {
+            match mailbox.reserve() {
+                Some(slot) => env.slot = Some(slot),
+                None if mailbox.policy() == OverflowPolicy::DropNewest => {
+                    debug!(
+                        "ChildRef({}): Dropping message (mailbox full): {:?}",
+                        self.id(),
+                        env
+                    );
+                    dead_letters::bounce(env, Some(self.id()), DeadLetterReason::MailboxFull);
+                    return Ok(());
+                }
+                None => return Err((SendErrorKind::MailboxFull, env)),
+            }
+        }
+
+        self.send(env).map_err(|env| (SendErrorKind::Stopped, env))
+    }
+
+    pub(crate) fn sender(&self) -> Sender {
+        // FIXME: panics?
+        self.sender.0.read().unwrap().clone()
     }
 
     /// Returns the [`BastionPath`] of the child
@@ -286,6 +1185,13 @@ impl ChildRef {
     }
 }
 
+impl SharedSender {
+    fn replace(&self, sender: Sender) {
+        // FIXME: panics?
+        *self.0.write().unwrap() = sender;
+    }
+}
+
 impl PartialEq for ChildRef {
     fn eq(&self, other: &Self) -> bool {
         self.id == other.id
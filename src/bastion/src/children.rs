@@ -1,29 +1,81 @@
 //!
 //! Children are a group of child supervised under a supervisor
-use crate::broadcast::{Broadcast, Parent, Sender};
-use crate::callbacks::{CallbackType, Callbacks};
-use crate::child::{Child, Init};
+use crate::ack::AckToken;
+use crate::actor::{self, Actor, MessageTimeout, MessageTimeoutAction};
+#[cfg(feature = "supervision")]
+use crate::backoff::Backoff;
+use crate::batch::Batcher;
+use crate::broadcast::{Broadcast, Parent};
+use crate::callbacks::Callbacks;
+use crate::child::{self, AsyncInit, Child, Exec, Init};
 use crate::child_ref::ChildRef;
-use crate::children_ref::ChildrenRef;
-use crate::context::{BastionContext, BastionId, ContextState};
+use crate::children_ref::{ChildrenRef, GroupPolicies, GroupState, GroupStateCell};
+use crate::context::{BastionContext, BastionId, CancellationToken, ContextState, NIL_ID};
+use crate::dead_letters::{self, DeadLetterReason};
 use crate::dispatcher::Dispatcher;
-use crate::envelope::Envelope;
-use crate::message::BastionMessage;
+use crate::envelope::{Envelope, SignedMessage};
+use crate::errors::{BuilderConflict, BuilderError, ExecError};
+use crate::event::{Event, FaultReason};
+use crate::flight_recorder::FlightRecord;
+use crate::history::GroupRecord;
+use crate::idle::GroupActivity;
+use crate::instrument::ExecInfo;
+use crate::launch::{LaunchPermit, LaunchProgress};
+use crate::lifeline::{Anchor, Lifeline};
+use crate::mailbox::{Mailbox, OverflowPolicy, PendingAnswers};
+use crate::message::{AcceptedTypes, BastionMessage, Deployment, Message, MessageTypes, Priority};
+use crate::metrics::GroupMetrics;
 use crate::path::BastionPathElement;
+use crate::preset::SupervisionPreset;
+use crate::provenance::{self, Provenance};
+use crate::reactor::{self, Sleep};
+#[cfg(feature = "autoscaling")]
+use crate::resizer::{Pressure, Resize, Resizer};
+use crate::routing::{Routing, RoutingError, RoutingKey};
+use crate::supervisor::{GroupStrategy, Inherited, SupervisionStrategy};
 use crate::system::SYSTEM;
-use bastion_executor::pool;
+use crate::tree;
+use crate::warmup::{Router, Warmup, WarmupGate};
+use bastion_executor::affinity::AffinityGroup;
+use bastion_executor::pool::{self, ProcClass};
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures::channel::oneshot;
 use futures::pending;
 use futures::poll;
 use futures::prelude::*;
 use futures::stream::FuturesOrdered;
-use fxhash::FxHashMap;
+use futures_timer::Delay;
+use fxhash::{FxHashMap, FxHashSet};
 use lightproc::prelude::*;
 use qutex::Qutex;
-use std::fmt::Debug;
+use std::any::type_name;
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Formatter};
 use std::future::Future;
+use std::mem;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::task::Poll;
+use std::time::{Duration, Instant};
+
+// The default number of messages a children group can receive
+// before being started without emitting an event.
+pub(crate) const DEFAULT_PRE_START_WATERMARK: usize = 1_000;
+// The number of messages received before a children group was
+// started that it replays before yielding.
+const PRE_START_REPLAY_CHUNK: usize = 1_000;
+// The default number of questions asked to an element that can be
+// waiting for its answer.
+const DEFAULT_MAX_PENDING_ANSWERS: usize = 4_096;
+// How long the elements of a children group that is asked to stop
+// have to do so on their own before being killed, by default.
+pub(crate) const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+// How long the elements of a children group still running once the
+// deadline it was quiesced with expired have to stop on their own
+// (e.g. if they are waiting for a message) before being killed.
+const QUIESCE_STOP_GRACE: Duration = Duration::from_millis(50);
 
 #[derive(Debug)]
 /// A children group that will contain a defined number of
@@ -38,6 +90,12 @@ use std::task::Poll;
 /// children group and eventually some of its other children,
 /// depending on its [`SupervisionStrategy`]).
 ///
+/// The methods configuring the group only record its settings,
+/// which are validated and applied once the closure configuring
+/// it returned, whichever order they were called in. Creating the
+/// group fails with [`BuilderError::Conflict`] if some of them
+/// conflict with each other.
+///
 /// # Example
 ///
 /// ```rust
@@ -68,27 +126,272 @@ use std::task::Poll;
 /// # }
 /// ```
 ///
+/// Since the group is moved into the closure and has to be
+/// returned by it, it can't be kept to be configured once it was
+/// created:
+///
+/// ```compile_fail
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// let mut retained = None;
+/// Bastion::children(|children| {
+///     retained = Some(children);
+///     children
+/// }).expect("Couldn't create the children group.");
+///
+/// retained.unwrap().with_redundancy(4);
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
 /// [`with_redundancy`]: #method.with_redundancy
 /// [`with_exec`]: #method.with_exec
 /// [`SupervisionStrategy`]: supervisor/enum.SupervisionStrategy.html
+/// [`BuilderError::Conflict`]: ../errors/enum.BuilderError.html#variant.Conflict
 pub struct Children {
     bcast: Broadcast,
     // The currently launched elements of the group.
-    launched: FxHashMap<BastionId, (Sender, RecoverableHandle<()>)>,
+    launched: FxHashMap<BastionId, (ChildRef, RecoverableHandle<()>, CancellationToken)>,
     // The closure returning the future that will be used by
     // every element of the group.
     init: Init,
+    // The closures of the elements that were deployed with
+    // their own (used when restarting them).
+    elem_inits: FxHashMap<BastionId, Init>,
+    // The index of each element in the group (see
+    // `BastionContext::elem_index`).
+    elem_indices: FxHashMap<BastionId, usize>,
+    // The closure combining the ones set with `with_async_init`
+    // and `StatefulChildren::with_exec`, which replaces `init` once
+    // the group is finalized.
+    stateful_init: Option<Init>,
     redundancy: usize,
+    // The name the group can be retrieved with using
+    // `Bastion::children_of`.
+    name: Option<String>,
+    // The strategy used to restart the group's elements when one
+    // of them faults, instead of its supervisor's, shared with the
+    // supervisor so that `ChildrenRef::strategy` can change it.
+    supervision_strategy: GroupStrategy,
     // The callbacks called at the group's different lifecycle
     // events.
     callbacks: Callbacks,
     // Messages that were received before the group was
-    // started. Those will be "replayed" once a start message
-    // is received.
-    pre_start_msgs: Vec<Envelope>,
-    started: bool,
+    // started. Those will be "replayed" in chunks once a start
+    // message is received, followed by the messages received
+    // while replaying them.
+    pre_start_msgs: VecDeque<Envelope>,
+    // The number of messages received before the group was
+    // started above which an event is emitted.
+    pre_start_watermark: Inherited<usize>,
+    // Whether the event was already emitted.
+    pre_start_exceeded: bool,
+    // Shared with the group's `ChildrenRef`s.
+    started: Arc<AtomicBool>,
     // List of dispatchers attached to each actor in the group.
     dispatchers: Vec<Arc<Box<Dispatcher>>>,
+    // The group the elements should send their output to, when
+    // this group is a pipeline's stage.
+    next_stage: Option<ChildrenRef>,
+    // The group's state, shared with its `ChildrenRef`s.
+    state: GroupStateCell,
+    // The group's resolved policies, shared with its `ChildrenRef`s.
+    policies: Arc<Mutex<GroupPolicies>>,
+    // The number of times each element was asked to be
+    // restarted.
+    restarts: FxHashMap<BastionId, usize>,
+    // The back-off delaying the restarts of the group's elements,
+    // if any, the number of times each element was restarted in a
+    // row along with when it was last relaunched (according to the
+    // reactor's clock), and the timers of the delayed restarts.
+    #[cfg(feature = "supervision")]
+    restart_backoff: Inherited<Option<Backoff>>,
+    #[cfg(feature = "supervision")]
+    backoff_attempts: FxHashMap<BastionId, (u32, Instant)>,
+    delayed_restarts: FxHashMap<BastionId, RecoverableHandle<()>>,
+    // The elements waiting for their supervisor to restart
+    // (or drop) them.
+    restarting: FxHashSet<BastionId>,
+    // The elements whose handle already resolved, used to notice
+    // those which didn't report that they stopped or faulted.
+    finished: FxHashSet<BastionId>,
+    // The metrics registered by the elements, shared with the
+    // group's `ChildrenRef`s.
+    metrics: GroupMetrics,
+    // The message types the group's `ChildRef`s and `ChildrenRef`s
+    // let be sent.
+    accepted_types: AcceptedTypes,
+    // The affinity group the group's elements are spawned with.
+    affinity: Option<AffinityGroup>,
+    // The capacity of the elements' mailboxes, if they are bounded,
+    // and what happens to the messages sent once they are full.
+    mailbox_capacity: Option<usize>,
+    overflow_policy: OverflowPolicy,
+    // Whether the overflow policy was set using
+    // `with_overflow_policy` (rather than by a preset).
+    overflow_policy_set: bool,
+    // How long the elements have to drain their mailboxes when the
+    // group is stopped, and whether the restarted ones handle the
+    // messages their previous incarnation didn't.
+    drain_timeout: Duration,
+    requeue_on_restart: bool,
+    // The number of questions asked to an element that can be
+    // waiting for its answer.
+    max_pending_answers: usize,
+    // The maximum depth of the provenance chains recorded by the
+    // elements, if they record them.
+    provenance_depth: Option<usize>,
+    // The number of messages recorded by the flight recorders of
+    // the group's elements, if it enables them.
+    flight_recorder: Option<usize>,
+    // The priority of the messages asked to the group's elements
+    // without one.
+    ask_priority: Priority,
+    // How long the group's actors can take to handle a message, and
+    // what happens once one took too long.
+    message_timeout: Option<MessageTimeout>,
+    // The activity of the group's elements, watched by the system
+    // once they are launched.
+    activity: GroupActivity,
+    // The warmup run by the elements before executing their future,
+    // the fraction of them that must be warm for the group to be
+    // ready, and the warm elements routed messages are sent to.
+    warmup: Option<Warmup>,
+    ready_fraction: f64,
+    routing: Router,
+    // The batch of messages of the type the group batches, if it
+    // batches messages.
+    batcher: Option<Batcher>,
+    // A sender cloned by every element and only dropped once
+    // their future was (and their callbacks were called), so
+    // that the group can wait for them when it is stopped (it is
+    // replaced every time it did, for the group to wait for the
+    // elements launched if it is relaunched).
+    alive: UnboundedSender<()>,
+    dropped: UnboundedReceiver<()>,
+    // Dropped along with the group, cutting the lifeline shared
+    // with its elements and its supervisor for them to know when
+    // its task was dropped without it stopping its elements.
+    _anchor: Anchor,
+    lifeline: Lifeline,
+    // Whether the elements' futures are wrapped using the wrapper
+    // set with `Config::exec_wrapper`, which the system's own
+    // groups aren't.
+    wrapped: bool,
+    // The maximum number of elements initializing concurrently, if
+    // any, the elements queued until fewer are, and the elements
+    // holding a permit (see `with_launch_concurrency`).
+    launch_concurrency: Option<usize>,
+    queued: VecDeque<QueuedElem>,
+    initializing: FxHashSet<BastionId>,
+    // Sent the id of the elements once their permit is dropped.
+    initialized_sender: UnboundedSender<BastionId>,
+    initialized: UnboundedReceiver<BastionId>,
+    // Shared with the group's `ChildrenRef`s.
+    launch: LaunchProgress,
+    // The name of the preset applied to the group, if any.
+    preset: Option<&'static str>,
+    // The group's settings, resolved once it is finalized and
+    // shared with its `ChildrenRef`s.
+    config: Arc<Mutex<GroupConfig>>,
+    // The messages broadcasted using `ChildrenRef::broadcast_acked`
+    // whose acknowledgements are still awaited, sent again to the
+    // elements restarted before acknowledging them.
+    sticky: Vec<(AckToken, Envelope)>,
+    // Whether the group's elements keep their ids when the group is
+    // respawned, along with the `ChildRef` of the element launched
+    // last with each index.
+    stable_ids: bool,
+    slots: FxHashMap<usize, ChildRef>,
+    // The resizer scaling the group, if it has one, along with the
+    // timer of its next sample and the elements it is stopping.
+    #[cfg(feature = "autoscaling")]
+    resizer: Option<Resizer>,
+    #[cfg(feature = "autoscaling")]
+    resize_tick: Option<Sleep>,
+    #[cfg(feature = "autoscaling")]
+    resizing: FxHashSet<BastionId>,
+    // What the group's entry in the system's history will
+    // summarize once it terminated.
+    record: GroupRecord,
+}
+
+#[derive(Debug)]
+// An element that isn't launched yet, but whose `ChildRef`s can
+// already be sent messages.
+struct QueuedElem {
+    index: usize,
+    bcast: Broadcast,
+    child_ref: ChildRef,
+}
+
+/// A children group's builder, returned by
+/// [`Children::with_async_init`], whose elements are initialized
+/// with a state of type `S` before executing the future returned by
+/// the closure set with [`with_exec`], to which it is passed.
+///
+/// The group is configured again once this closure is set, so it
+/// can't be created with an exec closure that doesn't take the type
+/// of state returned by the async init closure (or without an exec
+/// closure at all):
+///
+/// ```compile_fail
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// Bastion::children(|children| {
+///     children
+///         .with_async_init(|_ctx: BastionContext| async { Ok::<_, ()>(0u8) })
+///         .with_exec(|_state: String, _ctx: BastionContext| async { Ok(()) })
+/// }).expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`Children::with_async_init`]: struct.Children.html#method.with_async_init
+/// [`with_exec`]: #method.with_exec
+pub struct StatefulChildren<S> {
+    children: Children,
+    init: AsyncInit<S>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The settings of a children group, as returned by
+/// [`ChildrenRef::config`] once they were validated and resolved
+/// (after the closure configuring the group returned).
+///
+/// The policies that the group can inherit from its supervisor
+/// are returned by [`ChildrenRef::policies`] instead.
+///
+/// [`ChildrenRef::config`]: ../children_ref/struct.ChildrenRef.html#method.config
+/// [`ChildrenRef::policies`]: ../children_ref/struct.ChildrenRef.html#method.policies
+pub struct GroupConfig {
+    name: Option<String>,
+    redundancy: usize,
+    stateful: bool,
+    accepted_types: Option<Vec<&'static str>>,
+    mailbox_capacity: Option<usize>,
+    overflow_policy: OverflowPolicy,
+    max_pending_answers: usize,
+    provenance_depth: Option<usize>,
+    flight_recorder: Option<usize>,
+    ask_priority: Priority,
+    batched_type: Option<&'static str>,
+    warmup: bool,
+    ready_fraction: f64,
+    launch_concurrency: Option<usize>,
+    preset: Option<&'static str>,
 }
 
 impl Children {
@@ -96,21 +399,145 @@ impl Children {
         debug!("Children({}): Initializing.", bcast.id());
         let launched = FxHashMap::default();
         let init = Init::default();
+        let elem_inits = FxHashMap::default();
+        let elem_indices = FxHashMap::default();
+        let stateful_init = None;
         let redundancy = 1;
+        let name = None;
+        let supervision_strategy = GroupStrategy::default();
         let callbacks = Callbacks::new();
-        let pre_start_msgs = Vec::new();
-        let started = false;
+        let pre_start_msgs = VecDeque::new();
+        let pre_start_watermark = Inherited::new(DEFAULT_PRE_START_WATERMARK);
+        let pre_start_exceeded = false;
+        let started = Arc::new(AtomicBool::new(false));
         let dispatchers = Vec::new();
+        let next_stage = None;
+        let state = GroupStateCell::new();
+        let policies = Arc::new(Mutex::new(GroupPolicies::default()));
+        let restarts = FxHashMap::default();
+        #[cfg(feature = "supervision")]
+        let restart_backoff = Inherited::new(None);
+        #[cfg(feature = "supervision")]
+        let backoff_attempts = FxHashMap::default();
+        let delayed_restarts = FxHashMap::default();
+        let restarting = FxHashSet::default();
+        let finished = FxHashSet::default();
+        let metrics = GroupMetrics::new();
+        let accepted_types = AcceptedTypes::default();
+        let affinity = None;
+        // The group uses its supervisor's default mailbox unless it
+        // sets its own.
+        let default_mailbox = bcast
+            .parent()
+            .clone()
+            .into_supervisor()
+            .and_then(|supervisor| supervisor.default_mailbox());
+        let mailbox_capacity = default_mailbox.map(|(capacity, _)| capacity);
+        let overflow_policy = default_mailbox
+            .map(|(_, policy)| policy)
+            .unwrap_or_default();
+        let overflow_policy_set = false;
+        let drain_timeout = DEFAULT_DRAIN_TIMEOUT;
+        let requeue_on_restart = true;
+        let max_pending_answers = DEFAULT_MAX_PENDING_ANSWERS;
+        let provenance_depth = None;
+        let flight_recorder = None;
+        let ask_priority = Priority::default();
+        let message_timeout = None;
+        let activity = GroupActivity::default();
+        let warmup = None;
+        let ready_fraction = 1.0;
+        let routing = Router::new();
+        let batcher = None;
+        let (alive, dropped) = mpsc::unbounded();
+        let (_anchor, lifeline) = Lifeline::new();
+        let wrapped = true;
+        let launch_concurrency = None;
+        let queued = VecDeque::new();
+        let initializing = FxHashSet::default();
+        let (initialized_sender, initialized) = mpsc::unbounded();
+        let launch = LaunchProgress::default();
+        let sticky = Vec::new();
+        let stable_ids = false;
+        let slots = FxHashMap::default();
+        let preset = None;
+        let config = Arc::new(Mutex::new(GroupConfig::default()));
+        #[cfg(feature = "autoscaling")]
+        let resizer = None;
+        #[cfg(feature = "autoscaling")]
+        let resize_tick = None;
+        #[cfg(feature = "autoscaling")]
+        let resizing = FxHashSet::default();
+        let record = GroupRecord::new();
 
         Children {
             bcast,
             launched,
             init,
+            elem_inits,
+            elem_indices,
+            stateful_init,
             redundancy,
+            name,
+            supervision_strategy,
             callbacks,
             pre_start_msgs,
+            pre_start_watermark,
+            pre_start_exceeded,
             started,
             dispatchers,
+            next_stage,
+            state,
+            policies,
+            restarts,
+            #[cfg(feature = "supervision")]
+            restart_backoff,
+            #[cfg(feature = "supervision")]
+            backoff_attempts,
+            delayed_restarts,
+            restarting,
+            finished,
+            metrics,
+            accepted_types,
+            affinity,
+            mailbox_capacity,
+            overflow_policy,
+            overflow_policy_set,
+            drain_timeout,
+            requeue_on_restart,
+            max_pending_answers,
+            provenance_depth,
+            flight_recorder,
+            ask_priority,
+            message_timeout,
+            activity,
+            warmup,
+            ready_fraction,
+            routing,
+            batcher,
+            alive,
+            dropped,
+            _anchor,
+            lifeline,
+            wrapped,
+            launch_concurrency,
+            queued,
+            initializing,
+            initialized_sender,
+            initialized,
+            launch,
+            preset,
+            config,
+            sticky,
+            stable_ids,
+            slots,
+            #[cfg(feature = "autoscaling")]
+            resizer,
+            #[cfg(feature = "autoscaling")]
+            resize_tick,
+            #[cfg(feature = "autoscaling")]
+            resizing,
+            record,
         }
     }
 
@@ -152,8 +579,8 @@ impl Children {
         &self.bcast
     }
 
-    pub(crate) fn callbacks(&self) -> &Callbacks {
-        &self.callbacks
+    pub(crate) fn lifeline(&self) -> &Lifeline {
+        &self.lifeline
     }
 
     pub(crate) fn as_ref(&self) -> ChildrenRef {
@@ -167,11 +594,29 @@ impl Children {
         let sender = self.bcast.sender().clone();
         let path = self.bcast.path().clone();
 
-        let mut children = Vec::with_capacity(self.launched.len());
-        for (id, (sender, _)) in &self.launched {
+        let mut children = Vec::with_capacity(self.launched.len() + self.queued.len());
+        let queued = self
+            .queued
+            .iter()
+            .map(|elem| (elem.child_ref.id(), &elem.child_ref));
+        let launched = self
+            .launched
+            .iter()
+            .map(|(id, (child_ref, _, _))| (id, child_ref));
+        // The elements the group's resizer is stopping aren't sent
+        // new messages anymore.
+        #[cfg(feature = "autoscaling")]
+        let launched = launched.filter(|(id, _)| !self.resizing.contains(*id));
+        for (id, child_ref) in launched.chain(queued) {
             trace!("Children({}): Creating new ChildRef({}).", self.id(), id);
             // TODO: clone or ref?
-            let child = ChildRef::new(id.clone(), sender.clone(), path.clone());
+            let child = ChildRef::new(id.clone(), child_ref.sender(), path.clone())
+                .with_shared_sender(child_ref.shared_sender())
+                .with_accepted_types(self.accepted_types.clone())
+                .with_mailbox(child_ref.mailbox().cloned())
+                .with_pending_answers(child_ref.pending_answers().cloned())
+                .with_counters(child_ref.counters().clone())
+                .with_watchers(child_ref.watchers().clone());
             children.push(child);
         }
 
@@ -181,7 +626,32 @@ impl Children {
             .map(|dispatcher| dispatcher.dispatcher_type())
             .collect();
 
-        ChildrenRef::new(id, sender, path, children, dispatchers)
+        let started = self.started.clone();
+        let state = self.state.clone();
+        let policies = self.policies.clone();
+        let metrics = self.metrics.clone();
+        let accepted_types = self.accepted_types.clone();
+        let affinity = self.affinity.clone();
+        let routing = self.routing.clone();
+        let batcher = self.batcher.clone();
+
+        ChildrenRef::new(
+            id,
+            sender,
+            path,
+            children,
+            dispatchers,
+            started,
+            state,
+            policies,
+            metrics,
+            accepted_types,
+            affinity,
+            routing,
+            batcher,
+            self.launch.clone(),
+        )
+        .with_config(self.config.clone())
     }
 
     /// Sets the closure taking a [`BastionContext`] and returning a
@@ -192,7 +662,8 @@ impl Children {
     /// pass it to the `init` closure and poll the returned future until
     /// it stops, panics or another element of the group stops or panics.
     ///
-    /// The returned future's output should be `Result<(), ()>`.
+    /// The returned future's output should be `Result<(), ()>`. Use
+    /// [`with_exec_err`] to return a more informative error instead.
     ///
     /// # Arguments
     ///
@@ -227,6 +698,8 @@ impl Children {
     ///     # Bastion::block_until_stopped();
     /// # }
     /// ```
+    ///
+    /// [`with_exec_err`]: #method.with_exec_err
     pub fn with_exec<I, F>(mut self, init: I) -> Self
     where
         I: Fn(BastionContext) -> F + Send + Sync + 'static,
@@ -237,6 +710,262 @@ impl Children {
         self
     }
 
+    /// Sets the closure taking a [`BastionContext`] and returning a
+    /// [`Future`] that will be used by every element of this children
+    /// group, like [`with_exec`] does, but whose output can be
+    /// `Result<(), E>` for any error type `E` implementing [`Debug`].
+    ///
+    /// When the future returns an error, the element faults like
+    /// with [`with_exec`], and the error is logged by its supervisor
+    /// and passed to the callback set using
+    /// [`Callbacks::with_after_error`].
+    ///
+    /// Note that `E` can't be inferred from the `?` operator alone,
+    /// as it converts the errors into any type they can be
+    /// converted into.
+    ///
+    /// # Arguments
+    ///
+    /// * `init` - The closure taking a [`BastionContext`] and returning
+    ///     a [`Future`] that will be used by every element of this
+    ///     children group.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// #[derive(Debug)]
+    /// enum JobError {
+    ///     NoMessage,
+    /// }
+    ///
+    /// Bastion::children(|children| {
+    ///     children.with_exec_err(|ctx| {
+    ///         async move {
+    ///             if ctx.try_recv().await.is_none() {
+    ///                 return Err(JobError::NoMessage);
+    ///             }
+    ///
+    ///             Ok(())
+    ///
+    ///             // If `Err(JobError::NoMessage)` was returned, the
+    ///             // supervisor would log it and restart the element.
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`with_exec`]: #method.with_exec
+    /// [`Debug`]: https://doc.rust-lang.org/std/fmt/trait.Debug.html
+    /// [`Callbacks::with_after_error`]: ../struct.Callbacks.html#method.with_after_error
+    pub fn with_exec_err<I, F, E>(mut self, init: I) -> Self
+    where
+        I: Fn(BastionContext) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), E>> + Send + 'static,
+        E: Debug + Send + 'static,
+    {
+        trace!("Children({}): Setting exec closure.", self.id());
+        self.init = Init::new(init);
+        self
+    }
+
+    /// Sets the closure creating the [`Actor`] that every element of
+    /// this children group will run, handling the messages it
+    /// receives one at a time (and answering them with the
+    /// [`Reply`] it returns if they were asked) instead of
+    /// executing a future.
+    ///
+    /// The closure is called again each time an element is
+    /// restarted, for it to run a new actor. Like when using
+    /// [`with_exec_err`], the errors returned by the actor's methods
+    /// make the element fault (and be restarted depending on its
+    /// supervisor's strategy).
+    ///
+    /// This method replaces the closure set with [`with_exec`] (or
+    /// [`with_exec_err`]), and vice versa.
+    ///
+    /// # Arguments
+    ///
+    /// * `factory` - The closure returning the actor run by an
+    ///     element each time it is started.
+    ///
+    /// See [`Actor`] for an example.
+    ///
+    /// [`Actor`]: ../actor/trait.Actor.html
+    /// [`Reply`]: ../actor/struct.Reply.html
+    /// [`with_exec`]: #method.with_exec
+    /// [`with_exec_err`]: #method.with_exec_err
+    pub fn with_actor<F, A>(self, factory: F) -> Self
+    where
+        F: Fn() -> A + Send + Sync + 'static,
+        A: Actor,
+    {
+        trace!("Children({}): Setting actor factory.", self.id());
+        self.with_exec_err(move |ctx| actor::run(factory(), ctx))
+    }
+
+    /// Sets how long the [`Actor`]s run by this children group's
+    /// elements (see [`with_actor`]) can take to handle each of the
+    /// messages they receive, independently of how long they run.
+    ///
+    /// The handling of a message that took longer than `timeout`
+    /// is cancelled (without cancelling the element, which can go
+    /// on handling the next messages) and `on_timeout` is applied
+    /// to it. The messages skipped once they timed out are sent to
+    /// the dead letters with [`DeadLetterReason::TimedOut`], along
+    /// with their type and how long they were handled. By default,
+    /// the messages can take as long as they need.
+    ///
+    /// This doesn't apply to the elements executing futures (set
+    /// using [`with_exec`]), which retrieve the messages they
+    /// handle themselves.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long an actor can take to handle a message.
+    /// * `on_timeout` - What happens to a message once its handling
+    ///     was cancelled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// struct Fetcher;
+    ///
+    /// impl Actor for Fetcher {
+    ///     async fn handle(&mut self, ctx: &BastionContext, msg: SignedMessage) -> Result<Reply, ActorError> {
+    ///         // Fetch something from a remote that might not answer...
+    ///         # drop(msg);
+    ///         Ok(Reply::none())
+    ///     }
+    /// }
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_actor(|| Fetcher)
+    ///         .with_message_timeout(
+    ///             Duration::from_secs(5),
+    ///             MessageTimeoutAction::SkipAndDeadLetter,
+    ///         )
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Actor`]: ../actor/trait.Actor.html
+    /// [`with_actor`]: #method.with_actor
+    /// [`with_exec`]: #method.with_exec
+    /// [`DeadLetterReason::TimedOut`]: ../dead_letters/enum.DeadLetterReason.html#variant.TimedOut
+    pub fn with_message_timeout(
+        mut self,
+        timeout: Duration,
+        on_timeout: MessageTimeoutAction,
+    ) -> Self {
+        trace!(
+            "Children({}): Setting message timeout: {:?} ({:?}).",
+            self.id(),
+            timeout,
+            on_timeout
+        );
+        self.message_timeout = Some(MessageTimeout::new(timeout, on_timeout));
+        self
+    }
+
+    /// Sets the closure taking a [`BastionContext`] and returning a
+    /// [`Future`] that every element of this children group waits
+    /// for once started, before executing the future returned by
+    /// the closure set with [`StatefulChildren::with_exec`], to
+    /// which its output is passed.
+    ///
+    /// If the future returns an error, the element faults like if
+    /// the future it executes did (and is restarted depending on
+    /// its supervisor's strategy), without executing it. The
+    /// future is waited for again each time the element is
+    /// restarted, after the callbacks called before it starts
+    /// (see [`Callbacks::with_before_start`] and
+    /// [`Callbacks::with_after_restart`]).
+    ///
+    /// This method returns a [`StatefulChildren`], whose
+    /// [`with_exec`] method sets the closure taking the state and
+    /// gives the group back. Both closures replace the closure set
+    /// with [`with_exec`] (or [`with_exec_err`]), whichever order
+    /// the methods are called in.
+    ///
+    /// # Arguments
+    ///
+    /// * `init` - The closure taking a [`BastionContext`] and
+    ///     returning a [`Future`] resolving to the state passed
+    ///     to the closure set with [`StatefulChildren::with_exec`],
+    ///     or to an error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// # struct Connection;
+    /// # async fn connect() -> Result<Connection, String> { Ok(Connection) }
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_async_init(|_ctx: BastionContext| async move {
+    ///             // If the connection can't be opened, the element
+    ///             // faults and its supervisor restarts it.
+    ///             connect().await
+    ///         })
+    ///         .with_exec(|conn: Connection, ctx: BastionContext| async move {
+    ///             loop {
+    ///                 let msg = ctx.recv().await?;
+    ///                 // Handle the message using `conn`...
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`StatefulChildren`]: struct.StatefulChildren.html
+    /// [`StatefulChildren::with_exec`]: struct.StatefulChildren.html#method.with_exec
+    /// [`with_exec`]: #method.with_exec
+    /// [`with_exec_err`]: #method.with_exec_err
+    /// [`Callbacks::with_before_start`]: ../struct.Callbacks.html#method.with_before_start
+    /// [`Callbacks::with_after_restart`]: ../struct.Callbacks.html#method.with_after_restart
+    pub fn with_async_init<I, F, S, E>(self, init: I) -> StatefulChildren<S>
+    where
+        I: Fn(BastionContext) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<S, E>> + Send + 'static,
+        S: Send + 'static,
+        E: Debug + Send + 'static,
+    {
+        trace!("Children({}): Setting async init closure.", self.id());
+        StatefulChildren {
+            children: self,
+            init: child::async_init(init),
+        }
+    }
+
     /// Sets the number of number of elements this children group will
     /// contain. Each element will call the closure passed in
     /// [`with_exec`] and run the returned future until it stops,
@@ -321,6 +1050,12 @@ impl Children {
     /// Sets the callbacks that will get called at this children group's
     /// different lifecycle events.
     ///
+    /// The callbacks are called for each of the group's elements, from
+    /// the element's own process: `before_start` before its future is
+    /// first polled, `after_stop` once it finished executing, was stopped
+    /// or was killed, and `before_restart` before it gets restarted
+    /// because it faulted or panicked.
+    ///
     /// See [`Callbacks`]'s documentation for more information about the
     /// different callbacks available.
     ///
@@ -371,45 +1106,1608 @@ impl Children {
         self
     }
 
-    async fn kill(&mut self) {
-        debug!("Children({}): Killing.", self.id());
-        self.bcast.kill_children();
-
-        let mut children = FuturesOrdered::new();
-        for (_, (_, launched)) in self.launched.drain() {
-            launched.cancel();
-
-            children.push(launched);
-        }
-
-        children
-            .for_each_concurrent(None, |_| async {
-                trace!("Children({}): Unknown child stopped.", self.id());
-            })
-            .await;
-    }
-
-    fn stopped(&mut self) {
-        debug!("Children({}): Stopped.", self.id());
-        self.remove_dispatchers();
-        self.bcast.stopped();
+    /// Sets the number of messages that this children group can
+    /// receive before being started (messages which are kept
+    /// until it is) above which an [`Event::PreStartBufferExceeded`]
+    /// is emitted.
+    ///
+    /// The event is only emitted once per group. The default
+    /// watermark is the one set by the nearest supervisor using
+    /// [`Supervisor::with_default_pre_start_watermark`], or `1000`
+    /// messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `watermark` - The number of messages above which the event
+    ///   is emitted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_pre_start_watermark(100)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Event::PreStartBufferExceeded`]: ../event/enum.Event.html#variant.PreStartBufferExceeded
+    /// [`Supervisor::with_default_pre_start_watermark`]: ../supervisor/struct.Supervisor.html#method.with_default_pre_start_watermark
+    pub fn with_pre_start_watermark(mut self, watermark: usize) -> Self {
+        trace!(
+            "Children({}): Setting pre-start watermark: {}",
+            self.id(),
+            watermark
+        );
+        self.pre_start_watermark.set(watermark);
+        self
     }
 
-    fn faulted(&mut self) {
-        debug!("Children({}): Faulted.", self.id());
-        self.remove_dispatchers();
-        self.bcast.faulted();
+    #[cfg(feature = "supervision")]
+    /// Sets the back-off delaying the restarts of this children
+    /// group's elements when they keep faulting (e.g. because they
+    /// depend on an unavailable database), so that each consecutive
+    /// restart of an element waits longer before relaunching it
+    /// (see [`Backoff`]).
+    ///
+    /// The back-off replaces the delays of the supervisor's restart
+    /// strategy (see [`ActorRestartStrategy`]), and its delayed
+    /// restarts are cancelled if the group is stopped or killed
+    /// meanwhile. The default back-off is the one set by the
+    /// nearest supervisor using [`Supervisor::with_restart_backoff`],
+    /// and the restarts aren't delayed otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `backoff` - The back-off delaying the restarts of the
+    ///     group's elements.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_restart_backoff(
+    ///         Backoff::exponential(Duration::from_millis(100), Duration::from_secs(30), 0.1)
+    ///             // The restarts that happen after a minute of running
+    ///             // are delayed by 100ms again.
+    ///             .with_reset_after(Duration::from_secs(60)),
+    ///     )
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Backoff`]: ../backoff/struct.Backoff.html
+    /// [`ActorRestartStrategy`]: ../supervisor/enum.ActorRestartStrategy.html
+    /// [`Supervisor::with_restart_backoff`]: ../supervisor/struct.Supervisor.html#method.with_restart_backoff
+    pub fn with_restart_backoff(mut self, backoff: Backoff) -> Self {
+        trace!(
+            "Children({}): Setting restart back-off: {:?}",
+            self.id(),
+            backoff
+        );
+        self.restart_backoff.set(Some(backoff));
+        self
     }
 
-    async fn kill_children(&mut self) -> Result<(), ()> {
+    /// Sets the only message types this children group accepts,
+    /// as a tuple of up to eight types (e.g. `(A,)` or `(A, B, C)`).
+    ///
+    /// Sending a message of another type to the group using its
+    /// [`ChildrenRef`] or to one of its elements using their
+    /// [`ChildRef`] then fails right away with
+    /// [`SendErrorKind::TypeNotAccepted`], instead of the message
+    /// being received and only matching a catch-all of [`msg!`].
+    /// [`ChildRef::tell_unchecked`] sends a message without this
+    /// check. By default, the group accepts any message.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_accepted_types::<(u64, &'static str)>()
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// let err = children_ref.broadcast(1.5f64).unwrap_err();
+    /// assert!(matches!(err.kind(), SendErrorKind::TypeNotAccepted { .. }));
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef`]: ../children_ref/struct.ChildrenRef.html
+    /// [`ChildRef`]: ../child_ref/struct.ChildRef.html
+    /// [`SendErrorKind::TypeNotAccepted`]: ../errors/enum.SendErrorKind.html#variant.TypeNotAccepted
+    /// [`msg!`]: ../macro.msg.html
+    /// [`ChildRef::tell_unchecked`]: ../child_ref/struct.ChildRef.html#method.tell_unchecked
+    pub fn with_accepted_types<T: MessageTypes>(mut self) -> Self {
+        trace!("Children({}): Setting accepted message types.", self.id());
+        self.accepted_types = AcceptedTypes::new::<T>();
+        self
+    }
+
+    /// Sets the capacity of the mailboxes of this children group's
+    /// elements, which is the number of messages sent to each of
+    /// them using their [`ChildRef`] that they can hold until they
+    /// retrieve them.
+    ///
+    /// Once an element's mailbox is full, what happens to the
+    /// messages sent to it depends on the group's overflow policy
+    /// (see [`with_overflow_policy`]). The messages broadcasted to
+    /// the whole group using its [`ChildrenRef`] don't count toward
+    /// this capacity. By default, the group uses the default
+    /// mailbox of its supervisor (see
+    /// [`Supervisor::with_default_mailbox`]), or the mailboxes are
+    /// unbounded.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The capacity of the elements' mailboxes, or
+    ///     `1` if it is `0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_mailbox_capacity(1024)
+    ///         .with_overflow_policy(OverflowPolicy::DropOldest)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildRef`]: ../child_ref/struct.ChildRef.html
+    /// [`ChildrenRef`]: ../children_ref/struct.ChildrenRef.html
+    /// [`with_overflow_policy`]: #method.with_overflow_policy
+    /// [`Supervisor::with_default_mailbox`]: ../supervisor/struct.Supervisor.html#method.with_default_mailbox
+    pub fn with_mailbox_capacity(mut self, capacity: usize) -> Self {
+        trace!(
+            "Children({}): Setting mailbox capacity: {}",
+            self.id(),
+            capacity
+        );
+        self.mailbox_capacity = Some(capacity.max(1));
+        self
+    }
+
+    /// Sets what happens to the messages sent to an element of this
+    /// children group whose mailbox is full, if its capacity was set
+    /// using [`with_mailbox_capacity`].
+    ///
+    /// By default, sending them fails with
+    /// [`SendErrorKind::MailboxFull`] ([`OverflowPolicy::Fail`]).
+    /// Creating the group fails with
+    /// [`BuilderConflict::OverflowWithoutCapacity`] if the
+    /// mailboxes are unbounded.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - What happens to the messages sent to a full
+    ///     mailbox.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_mailbox_capacity(1024)
+    ///         .with_overflow_policy(OverflowPolicy::Block)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`with_mailbox_capacity`]: #method.with_mailbox_capacity
+    /// [`SendErrorKind::MailboxFull`]: ../errors/enum.SendErrorKind.html#variant.MailboxFull
+    /// [`OverflowPolicy::Fail`]: ../mailbox/enum.OverflowPolicy.html#variant.Fail
+    /// [`BuilderConflict::OverflowWithoutCapacity`]: ../errors/enum.BuilderConflict.html#variant.OverflowWithoutCapacity
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        trace!(
+            "Children({}): Setting overflow policy: {:?}",
+            self.id(),
+            policy
+        );
+        self.overflow_policy = policy;
+        self.overflow_policy_set = true;
+        self
+    }
+
+    /// Sets how long this children group's elements are given to
+    /// drain their mailboxes when the group is asked to stop (e.g.
+    /// using [`ChildrenRef::stop`]), before the ones still running
+    /// are killed.
+    ///
+    /// The elements' shutdown tokens are triggered as soon as the
+    /// group is asked to stop, and the ones waiting for messages
+    /// stop once their mailboxes are empty. The default timeout is
+    /// five seconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long the elements have to stop on their
+    ///     own.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_drain_timeout(Duration::from_secs(30))
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef::stop`]: ../children_ref/struct.ChildrenRef.html#method.stop
+    pub fn with_drain_timeout(mut self, timeout: Duration) -> Self {
+        trace!(
+            "Children({}): Setting drain timeout: {:?}",
+            self.id(),
+            timeout
+        );
+        self.drain_timeout = timeout;
+        self
+    }
+
+    /// Sets whether the elements of this children group that are
+    /// restarted after faulting handle the messages that their
+    /// previous incarnation received but didn't handle, or whether
+    /// those are sent to the dead letters (with
+    /// [`DeadLetterReason::RecipientStopped`]).
+    ///
+    /// The messages are requeued by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `requeue` - Whether the restarted elements handle the
+    ///     messages their previous incarnation didn't.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     // The requests are stale once the element restarted.
+    ///     children.with_requeue_on_restart(false)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`DeadLetterReason::RecipientStopped`]: ../dead_letters/enum.DeadLetterReason.html#variant.RecipientStopped
+    pub fn with_requeue_on_restart(mut self, requeue: bool) -> Self {
+        trace!(
+            "Children({}): Setting requeue on restart: {}",
+            self.id(),
+            requeue
+        );
+        self.requeue_on_restart = requeue;
+        self
+    }
+
+    /// Applies the policies of a [`SupervisionPreset`] applying to
+    /// the children groups: its restart back-off, its pre-start
+    /// watermark, its mailbox capacity (making the elements'
+    /// mailboxes unbounded if it doesn't have one), its overflow
+    /// policy, its drain timeout and whether the restarted elements
+    /// handle the messages their previous incarnation didn't.
+    ///
+    /// The preset's name is recorded in the group's policies (see
+    /// [`ChildrenRef::policies`]). The group's restart strategy
+    /// and window are the ones of its supervisor, to which the
+    /// preset can be applied too (see [`Supervisor::apply_preset`]).
+    /// Calling this group's other methods afterwards overrides
+    /// the preset's policies.
+    ///
+    /// # Arguments
+    ///
+    /// * `preset` - The preset to apply.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .apply_preset(SupervisionPreset::best_effort_telemetry())
+    ///         // Keeps more messages than the preset does.
+    ///         .with_mailbox_capacity(1024)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`SupervisionPreset`]: ../preset/struct.SupervisionPreset.html
+    /// [`ChildrenRef::policies`]: ../children_ref/struct.ChildrenRef.html#method.policies
+    /// [`Supervisor::apply_preset`]: ../supervisor/struct.Supervisor.html#method.apply_preset
+    pub fn apply_preset(mut self, preset: SupervisionPreset) -> Self {
+        trace!("Children({}): Applying preset: {:?}", self.id(), preset);
+        #[cfg(feature = "supervision")]
+        self.restart_backoff.set(preset.restart_backoff().cloned());
+        self.pre_start_watermark.set(preset.pre_start_watermark());
+        self.mailbox_capacity = preset.mailbox_capacity();
+        self.overflow_policy = preset.overflow_policy();
+        self.overflow_policy_set = false;
+        self.drain_timeout = preset.drain_timeout();
+        self.requeue_on_restart = preset.requeues_on_restart();
+        self.preset = Some(preset.name());
+        self
+    }
+
+    /// Sets the number of questions asked to an element of this
+    /// children group (using [`ChildRef::ask_anonymously`] or the
+    /// like) that can be waiting for its answer, `4096` by default.
+    ///
+    /// Once an element has as many of them, asking it a question
+    /// fails with [`SendErrorKind::TooManyPending`] (or waits until
+    /// one of them is answered, using [`ChildRef::ask_async`]).
+    /// A question stops waiting once the element answered it or
+    /// dropped the sender of its answer.
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - The number of questions that can be waiting for an
+    ///     element's answer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_max_pending_answers(256)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildRef::ask_anonymously`]: ../child_ref/struct.ChildRef.html#method.ask_anonymously
+    /// [`ChildRef::ask_async`]: ../child_ref/struct.ChildRef.html#method.ask_async
+    /// [`SendErrorKind::TooManyPending`]: ../errors/enum.SendErrorKind.html#variant.TooManyPending
+    pub fn with_max_pending_answers(mut self, max: usize) -> Self {
+        trace!(
+            "Children({}): Setting the maximum pending answers: {}",
+            self.id(),
+            max
+        );
+        self.max_pending_answers = max.max(1);
+        self
+    }
+
+    /// Makes this children group's elements record the provenance
+    /// of the messages they send while handling a message: the
+    /// type name and correlation id of the message they retrieved
+    /// last, followed by the provenance of this message, if its
+    /// sender recorded it. The chains are bounded to 8 entries
+    /// (see [`with_provenance_depth`]).
+    ///
+    /// The provenance of a message can be retrieved using
+    /// [`Msg::provenance`], and the provenance of the message an
+    /// element was handling when it faulted is reported along with
+    /// its fault. By default, the provenance isn't recorded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_provenance()
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`with_provenance_depth`]: #method.with_provenance_depth
+    /// [`Msg::provenance`]: ../message/struct.Msg.html#method.provenance
+    pub fn with_provenance(self) -> Self {
+        self.with_provenance_depth(provenance::DEFAULT_MAX_DEPTH)
+    }
+
+    /// Makes this children group's elements record the provenance
+    /// of the messages they send, like [`with_provenance`] does,
+    /// but with chains of at most `depth` entries (the oldest ones
+    /// being dropped and the chain marked as truncated).
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - The maximum number of entries of the chains.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_provenance_depth(16)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`with_provenance`]: #method.with_provenance
+    pub fn with_provenance_depth(mut self, depth: usize) -> Self {
+        trace!(
+            "Children({}): Recording the provenance: depth={}",
+            self.id(),
+            depth
+        );
+        self.provenance_depth = Some(depth.max(1));
+        self
+    }
+
+    /// Makes each of this children group's elements keep a flight
+    /// recorder of the last `capacity` messages it handled (at
+    /// least one): their type name, when the element started
+    /// handling them, for how long and whether it was done with
+    /// them or faulted while handling them.
+    ///
+    /// The records of an element that faulted are reported along
+    /// with its fault (see [`Event::ElemsFaulted`]), without
+    /// enabling the logs. A record is written each time the
+    /// element retrieves a message, into slots allocated
+    /// beforehand so that recording stays cheap. By default, the
+    /// elements don't keep any flight recorder, and 32 records are
+    /// a reasonable capacity.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The number of records the flight recorder
+    ///     of each element keeps.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_flight_recorder(32)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Event::ElemsFaulted`]: ../event/enum.Event.html#variant.ElemsFaulted
+    pub fn with_flight_recorder(mut self, capacity: usize) -> Self {
+        trace!(
+            "Children({}): Enabling the flight recorder: capacity={}",
+            self.id(),
+            capacity
+        );
+        self.flight_recorder = Some(capacity.max(1));
+        self
+    }
+
+    /// Sets the lane of their mailbox the messages asked to this
+    /// children group's elements land in, unless they are asked
+    /// with another priority (see [`ChildRef::ask_with_priority`]).
+    ///
+    /// By default, the messages are asked with
+    /// [`Priority::Normal`], which makes them wait behind the
+    /// messages the elements received before them.
+    ///
+    /// # Arguments
+    ///
+    /// * `priority` - The priority of the messages asked to the
+    ///     elements without one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_ask_priority(Priority::High)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildRef::ask_with_priority`]: ../child_ref/struct.ChildRef.html#method.ask_with_priority
+    /// [`Priority::Normal`]: ../message/enum.Priority.html#variant.Normal
+    pub fn with_ask_priority(mut self, priority: Priority) -> Self {
+        trace!(
+            "Children({}): Setting the ask priority: {:?}",
+            self.id(),
+            priority
+        );
+        self.ask_priority = priority;
+        self
+    }
+
+    /// Makes this children group's elements keep their identifiers
+    /// when the group itself is deployed again after being stopped
+    /// (e.g. when the system recovers from being degraded, see
+    /// [`Bastion::recover`]), if `stable` is `true`.
+    ///
+    /// The elements already keep their identifiers when they are
+    /// restarted by their supervisor. With stable identifiers, each
+    /// index of the group (from `0` to its redundancy) also keeps
+    /// its identifier once it was first launched, which is given
+    /// to the element launched with this index when the group is
+    /// deployed again. Only the channel it receives its messages
+    /// with is created again, and the [`ChildRef`]s referencing the
+    /// element it replaces reach it, like those referencing a
+    /// restarted element do (sharing their [`ChildStats`]).
+    ///
+    /// The group's elements are still never launched with the
+    /// identifier of another running element: the elements added
+    /// to the group since it was launched (e.g. by its resizer or
+    /// using [`ChildrenRef::deploy_elem`]) get new identifiers,
+    /// which are kept for their indices if the group is deployed
+    /// again. By default, the group's elements get new identifiers
+    /// when it is.
+    ///
+    /// # Arguments
+    ///
+    /// * `stable` - Whether the elements keep their identifiers
+    ///     when the group is deployed again.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_redundancy(4)
+    ///         .with_stable_ids(true)
+    ///         .with_exec(|ctx: BastionContext| async move {
+    ///             // The element's id is the same as the one of the
+    ///             // element it replaced, if the group was deployed
+    ///             // again...
+    ///             let id: &BastionId = ctx.current().id();
+    ///             # drop(id);
+    ///             # Ok(())
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Bastion::recover`]: ../struct.Bastion.html#method.recover
+    /// [`ChildRef`]: ../child_ref/struct.ChildRef.html
+    /// [`ChildStats`]: ../metrics/struct.ChildStats.html
+    /// [`ChildrenRef::deploy_elem`]: ../children_ref/struct.ChildrenRef.html#method.deploy_elem
+    pub fn with_stable_ids(mut self, stable: bool) -> Self {
+        trace!("Children({}): Setting stable ids: {}", self.id(), stable);
+        self.stable_ids = stable;
+        self
+    }
+
+    /// Makes this children group accumulate the messages of type `T`
+    /// sent to it using [`ChildrenRef::tell_one`] and deliver them
+    /// to one of its elements as a single [`Batch`], in the order
+    /// they were sent, once it holds `max_items` of them or
+    /// `max_delay` after it received the first one.
+    ///
+    /// The pending batch is also delivered when the group is
+    /// stopped or quiesced, before its elements are asked to finish
+    /// handling their messages (it is lost if the group is killed).
+    /// Only the messages sent using [`ChildrenRef::tell_one`] are
+    /// batched: the ones that are broadcasted or asked (e.g. using
+    /// [`ChildrenRef::ask_one`], which answers each of them) and
+    /// the messages of other types are delivered on their own. A
+    /// batch that can't be delivered is sent to the dead letters.
+    /// By default, the group
+    /// doesn't batch messages. Creating the group fails with
+    /// [`BuilderConflict::BatchedTypeNotAccepted`] if `T` isn't
+    /// one of the types it accepts (see [`with_accepted_types`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `max_items` - The number of messages a batch holds once
+    ///     it's delivered.
+    /// * `max_delay` - How long a batch is kept before being
+    ///     delivered, even if it isn't full.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children
+    ///         .with_batching::<u64>(100, Duration::from_millis(10))
+    ///         .with_exec(|ctx: BastionContext| async move {
+    ///             loop {
+    ///                 msg! { ctx.recv().await?,
+    ///                     batch: Batch<u64> => {
+    ///                         // Write the rows at once...
+    ///                         # drop(batch);
+    ///                     };
+    ///                     _: _ => ();
+    ///                 }
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// for row in 0..1_000u64 {
+    ///     children_ref.tell_one(row).expect("Couldn't send the message.");
+    /// }
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef::tell_one`]: ../children_ref/struct.ChildrenRef.html#method.tell_one
+    /// [`Batch`]: ../batch/struct.Batch.html
+    /// [`ChildrenRef::ask_one`]: ../children_ref/struct.ChildrenRef.html#method.ask_one
+    /// [`BuilderConflict::BatchedTypeNotAccepted`]: ../errors/enum.BuilderConflict.html#variant.BatchedTypeNotAccepted
+    /// [`with_accepted_types`]: #method.with_accepted_types
+    pub fn with_batching<T: Message>(mut self, max_items: usize, max_delay: Duration) -> Self {
+        trace!(
+            "Children({}): Batching the messages of type {} ({}, {:?}).",
+            self.id(),
+            std::any::type_name::<T>(),
+            max_items,
+            max_delay
+        );
+        self.batcher = Some(Batcher::new::<T>(
+            max_items,
+            max_delay,
+            self.routing.clone(),
+        ));
+        self
+    }
+
+    /// Sets the warmup that this children group's elements run once
+    /// started, before executing their future: the closure is called
+    /// with each element's context and the returned future is polled
+    /// until the element is warm, as decided by `gate`.
+    ///
+    /// Until then, the element isn't sent routed messages (using
+    /// [`ChildrenRef::tell_one`] or [`ChildrenRef::ask_one`], or
+    /// dispatched by the group's dispatchers), which are routed to
+    /// the elements that already are. The messages sent to it
+    /// directly are kept until it is, and shouldn't be retrieved by
+    /// the warmup. By default, the elements don't warm up.
+    ///
+    /// # Arguments
+    ///
+    /// * `warmup` - The closure returning the elements' warmup.
+    /// * `gate` - When the elements are considered warm.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_redundancy(4)
+    ///         .with_warmup(
+    ///             |ctx: BastionContext| async move {
+    ///                 // Send synthetic traffic to fill the caches...
+    ///             },
+    ///             WarmupGate::Duration(Duration::from_secs(5)),
+    ///         )
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef::tell_one`]: ../children_ref/struct.ChildrenRef.html#method.tell_one
+    /// [`ChildrenRef::ask_one`]: ../children_ref/struct.ChildrenRef.html#method.ask_one
+    pub fn with_warmup<I, F>(mut self, warmup: I, gate: WarmupGate) -> Self
+    where
+        I: Fn(BastionContext) -> F + Send + Sync + 'static,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        trace!("Children({}): Setting warmup: {:?}", self.id(), gate);
+        self.warmup = Some(Warmup::new(warmup, gate));
+        self
+    }
+
+    /// Sets the fraction of this children group's elements that
+    /// must be warm for the group to be ready (see
+    /// [`ChildrenRef::is_ready`]), between `0.0` and `1.0`.
+    ///
+    /// By default, all the group's elements must be warm.
+    ///
+    /// # Arguments
+    ///
+    /// * `fraction` - The fraction of elements that must be warm.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     // The group is ready once 3 of its elements are warm...
+    ///     children.with_redundancy(4).with_ready_fraction(0.75)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef::is_ready`]: ../children_ref/struct.ChildrenRef.html#method.is_ready
+    pub fn with_ready_fraction(mut self, fraction: f64) -> Self {
+        trace!(
+            "Children({}): Setting ready fraction: {}",
+            self.id(),
+            fraction
+        );
+        self.ready_fraction = fraction.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets how this children group routes the messages sent using
+    /// [`ChildrenRef::tell_one`] or [`ChildrenRef::ask_one`] to its
+    /// warm elements, which can be changed once the group started
+    /// using [`ChildrenRef::set_routing`].
+    ///
+    /// Creating the group fails with
+    /// [`BuilderConflict::MissingRoutingKey`] if the messages are
+    /// routed by key (using [`Routing::HashBy`]) without a key
+    /// function being set using [`with_routing_key`]. By default,
+    /// the messages are sent to each warm element in turn.
+    ///
+    /// # Arguments
+    ///
+    /// * `routing` - How the routed messages are routed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_redundancy(4)
+    ///         .with_routing(Routing::LeastLoaded)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef::tell_one`]: ../children_ref/struct.ChildrenRef.html#method.tell_one
+    /// [`ChildrenRef::ask_one`]: ../children_ref/struct.ChildrenRef.html#method.ask_one
+    /// [`ChildrenRef::set_routing`]: ../children_ref/struct.ChildrenRef.html#method.set_routing
+    /// [`BuilderConflict::MissingRoutingKey`]: ../errors/enum.BuilderConflict.html#variant.MissingRoutingKey
+    /// [`Routing::HashBy`]: ../routing/enum.Routing.html#variant.HashBy
+    /// [`with_routing_key`]: #method.with_routing_key
+    pub fn with_routing(self, routing: Routing) -> Self {
+        trace!("Children({}): Setting routing: {:?}", self.id(), routing);
+        self.routing.set_mode(routing);
+        self
+    }
+
+    /// Sets the function returning the key of the routed messages
+    /// of type `M` while this children group routes them by key
+    /// (see [`Routing::HashBy`]), the messages with the same key
+    /// being sent to the same warm element.
+    ///
+    /// The group can only route its messages by key, whether using
+    /// [`with_routing`] or [`ChildrenRef::set_routing`], once it has
+    /// a key function.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The function returning the key of a message.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// # #[derive(Debug)]
+    /// struct Order {
+    ///     customer: u64,
+    /// }
+    ///
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_redundancy(4)
+    ///         // The orders of a customer are handled in turn...
+    ///         .with_routing_key(|order: &Order| order.customer)
+    ///         .with_routing(Routing::HashBy)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Routing::HashBy`]: ../routing/enum.Routing.html#variant.HashBy
+    /// [`with_routing`]: #method.with_routing
+    /// [`ChildrenRef::set_routing`]: ../children_ref/struct.ChildrenRef.html#method.set_routing
+    pub fn with_routing_key<M, F>(self, key: F) -> Self
+    where
+        M: Message,
+        F: Fn(&M) -> u64 + Send + Sync + 'static,
+    {
+        trace!(
+            "Children({}): Setting routing key of: {}",
+            self.id(),
+            type_name::<M>()
+        );
+        self.routing.set_key(RoutingKey::new(key));
+        self
+    }
+
+    /// Sets the maximum number of this children group's elements
+    /// that are initializing concurrently when it is launched (or
+    /// restarted, or scaled up).
+    ///
+    /// An element is initializing until it is warm (see
+    /// [`with_warmup`]) and the closure set with [`with_async_init`]
+    /// returned its state (if the group has one). The other elements
+    /// are launched in waves, as the previous ones are initialized,
+    /// while the group keeps handling the messages it receives. The
+    /// messages sent to the elements that aren't launched yet are
+    /// kept until they are, and the tree snapshots (see
+    /// [`Bastion::tree`]) show how many elements were launched and
+    /// initialized.
+    ///
+    /// By default, all the elements are launched at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `concurrency` - The maximum number of elements
+    ///     initializing concurrently (at least `1`).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// # struct Connection;
+    /// # async fn connect() -> Result<Connection, String> { Ok(Connection) }
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_redundancy(1000)
+    ///         // At most 10 elements open their connection at once.
+    ///         .with_launch_concurrency(10)
+    ///         .with_async_init(|_ctx: BastionContext| async move { connect().await })
+    ///         .with_exec(|conn: Connection, ctx: BastionContext| async move {
+    ///             loop {
+    ///                 let msg = ctx.recv().await?;
+    ///                 // Handle the message using `conn`...
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`with_warmup`]: #method.with_warmup
+    /// [`with_async_init`]: #method.with_async_init
+    /// [`Bastion::tree`]: ../struct.Bastion.html#method.tree
+    pub fn with_launch_concurrency(mut self, concurrency: usize) -> Self {
+        trace!(
+            "Children({}): Setting launch concurrency: {}",
+            self.id(),
+            concurrency
+        );
+        self.launch_concurrency = Some(concurrency.max(1));
+        self
+    }
+
+    #[cfg(feature = "autoscaling")]
+    /// Sets the resizer scaling this children group up and down
+    /// once it started, depending on the pressure on its elements'
+    /// mailboxes (see [`Resizer`]). The group's redundancy is then
+    /// only the number of elements it is launched with.
+    ///
+    /// The resizer launches an element at a time while the group
+    /// is under pressure and stops an idle element at a time
+    /// otherwise, which handles the messages it is sent in the
+    /// meantime before stopping. It keeps the group's elements
+    /// within the resizer's bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `resizer` - The resizer scaling the group.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_resizer(
+    ///             Resizer::new()
+    ///                 .lower(1)
+    ///                 .upper(16)
+    ///                 .scale_up_when(|pressure| pressure.avg_mailbox > 100),
+    ///         )
+    ///         .with_exec(|ctx: BastionContext| async move {
+    ///             loop {
+    ///                 let msg = ctx.recv().await?;
+    ///                 // Handle the message...
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Resizer`]: ../resizer/struct.Resizer.html
+    pub fn with_resizer(mut self, resizer: Resizer) -> Self {
+        trace!("Children({}): Setting resizer: {:?}", self.id(), resizer);
+        self.resizer = Some(resizer);
+        self
+    }
+
+    /// Sets the affinity group this children group's elements are
+    /// spawned with, so that the executor preferentially runs them
+    /// on the same worker as the other elements of every group with
+    /// the same label (e.g. a pipeline's stages asking each other).
+    ///
+    /// The affinity is soft: the elements are still run by other
+    /// workers when their worker is busy and the others are idle.
+    /// Use [`ChildrenRef::affinity_stats`] to see how they were
+    /// placed (the affinity groups are placed again once the system
+    /// is initialized again). By default, the group doesn't have an
+    /// affinity group.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The label of the affinity group.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let producers = Bastion::children(|children| {
+    ///     children.with_affinity_group("pipeline")
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// let consumers = Bastion::children(|children| {
+    ///     children.with_affinity_group("pipeline")
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef::affinity_stats`]: ../children_ref/struct.ChildrenRef.html#method.affinity_stats
+    pub fn with_affinity_group<L: Into<String>>(mut self, label: L) -> Self {
+        let label = label.into();
+        trace!("Children({}): Setting affinity group: {}", self.id(), label);
+        self.affinity = Some(SYSTEM.affinity_group(&label));
+        self
+    }
+
+    /// Sets the name this children group can be retrieved with from
+    /// anywhere in the program, using [`Bastion::children_of`].
+    ///
+    /// The group is registered with this name when it is created and
+    /// unregistered once it stopped, was killed or faulted. Creating
+    /// a group with the name of a group that is still registered
+    /// fails with [`BuilderError::NameTaken`].
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the children group.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_name("workers")
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// let workers: Option<ChildrenRef> = Bastion::children_of("workers");
+    /// assert!(workers.is_some());
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Bastion::children_of`]: ../struct.Bastion.html#method.children_of
+    /// [`BuilderError::NameTaken`]: ../errors/enum.BuilderError.html#variant.NameTaken
+    pub fn with_name<N: Into<String>>(mut self, name: N) -> Self {
+        let name = name.into();
+        trace!("Children({}): Setting name: {:?}", self.id(), name);
+        self.name = Some(name);
+        self
+    }
+
+    pub(crate) fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Sets the strategy the group's supervisor should use when
+    /// one of the group's elements faults, instead of its own
+    /// [`SupervisionStrategy`], but only for the group's elements:
+    ///
+    /// - `OneForOne` only restarts the faulted element, leaving the
+    ///   other elements untouched.
+    /// - `OneForAll` restarts all the group's elements.
+    /// - `RestForOne` restarts the faulted element and the ones
+    ///   that were launched after it.
+    ///
+    /// The other supervised elements of the supervisor are never
+    /// restarted. The restarted elements are still counted by the
+    /// supervisor's [`RestartStrategy`].
+    ///
+    /// A restarted element keeps the messages it received but
+    /// didn't retrieve yet, which are redelivered to it once it
+    /// restarted. The [`ChildRef`]s referencing it before it was
+    /// restarted can't be used to send messages to it anymore.
+    ///
+    /// # Arguments
+    ///
+    /// * `strategy` - The strategy to restart the group's
+    ///     elements with.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::supervisor(|sp| {
+    ///     sp.with_strategy(SupervisionStrategy::OneForAll)
+    ///         .children(|children| {
+    ///             // Only the faulted element of this group is
+    ///             // restarted...
+    ///             children
+    ///                 .with_redundancy(4)
+    ///                 .with_supervision_strategy(SupervisionStrategy::OneForOne)
+    ///         })
+    ///         // ...while all the elements are restarted when an
+    ///         // element of this group faults.
+    ///         .children(|children| children)
+    /// }).expect("Couldn't create the supervisor.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`SupervisionStrategy`]: ../supervisor/enum.SupervisionStrategy.html
+    /// [`RestartStrategy`]: ../supervisor/struct.RestartStrategy.html
+    /// [`ChildRef`]: ../child_ref/struct.ChildRef.html
+    pub fn with_supervision_strategy(self, strategy: SupervisionStrategy) -> Self {
+        trace!(
+            "Children({}): Setting supervision strategy: {:?}",
+            self.id(),
+            strategy
+        );
+        // FIXME: panics?
+        *self.supervision_strategy.lock().unwrap() = Some(strategy);
+        self
+    }
+
+    pub(crate) fn supervision_strategy(&self) -> &GroupStrategy {
+        &self.supervision_strategy
+    }
+
+    // Whether the group delays the restarts of its elements itself,
+    // once its policies are resolved.
+    #[cfg(feature = "supervision")]
+    pub(crate) fn has_restart_backoff(&self) -> bool {
+        self.restart_backoff.get().is_some()
+    }
+
+    // Resolves the group's policies once it is deployed, using
+    // those of its supervisor for the ones it didn't set.
+    pub(crate) fn resolve_policies(&mut self, defaults: GroupPolicies) {
+        self.pre_start_watermark
+            .inherit(&defaults.pre_start_watermark());
+        #[cfg(feature = "supervision")]
+        self.restart_backoff
+            .inherit(&defaults.restart_backoff().cloned());
+
+        let policies = GroupPolicies::new(
+            defaults.restart_strategy().clone(),
+            *self.pre_start_watermark.get(),
+        );
+        #[cfg(feature = "supervision")]
+        let policies = policies
+            .with_restart_window(defaults.restart_window())
+            .with_restart_backoff(self.restart_backoff.get().clone());
+        let policies = policies
+            .with_mailbox(self.mailbox_capacity, self.overflow_policy)
+            .with_drain(self.drain_timeout, self.requeue_on_restart)
+            .with_preset(self.preset.or_else(|| defaults.preset()));
+        debug!("Children({}): Resolved policies: {:?}", self.id(), policies);
+        *self.policies.lock().unwrap() = policies;
+    }
+
+    // Creates a group with the same definition as this stopped one
+    // (its closure, redundancy, name, callbacks...), but without any
+    // of its elements nor of the ones added to it since.
+    pub(crate) fn respawn(self, bcast: Broadcast) -> Self {
+        debug!("Children({}): Respawning.", self.id());
+        let mut children = Children::new(bcast);
+        children.init = self.init;
+        children.redundancy = self.redundancy;
+        children.name = self.name;
+        children.supervision_strategy = self.supervision_strategy;
+        children.callbacks = self.callbacks;
+        children.pre_start_watermark = self.pre_start_watermark;
+        #[cfg(feature = "supervision")]
+        {
+            children.restart_backoff = self.restart_backoff;
+        }
+        children.dispatchers = self.dispatchers;
+        children.next_stage = self.next_stage;
+        children.accepted_types = self.accepted_types;
+        children.affinity = self.affinity;
+        children.mailbox_capacity = self.mailbox_capacity;
+        children.overflow_policy = self.overflow_policy;
+        children.drain_timeout = self.drain_timeout;
+        children.requeue_on_restart = self.requeue_on_restart;
+        children.provenance_depth = self.provenance_depth;
+        children.flight_recorder = self.flight_recorder;
+        children.ask_priority = self.ask_priority;
+        children.message_timeout = self.message_timeout;
+        children.warmup = self.warmup;
+        children.ready_fraction = self.ready_fraction;
+        children.launch_concurrency = self.launch_concurrency;
+        children.preset = self.preset;
+        children.config = self.config;
+        children.stable_ids = self.stable_ids;
+        children.slots = self.slots;
+        #[cfg(feature = "autoscaling")]
+        {
+            children.resizer = self.resizer;
+        }
+        children.batcher = self
+            .batcher
+            .map(|batcher| batcher.respawn(children.routing.clone()));
+
+        children
+    }
+
+    // Validates the settings recorded by the group's builder
+    // methods and resolves them, once the closure configuring the
+    // group returned and before its elements are launched.
+    pub(crate) fn finalize(&mut self) -> Result<(), BuilderError> {
+        self.conflict().map_err(BuilderError::Conflict)?;
+        let stateful = self.stateful_init.is_some();
+        if let Some(init) = self.stateful_init.take() {
+            self.init = init;
+        }
+
+        let config = GroupConfig {
+            name: self.name.clone(),
+            redundancy: self.redundancy,
+            stateful,
+            accepted_types: self.accepted_types.names(),
+            mailbox_capacity: self.mailbox_capacity,
+            overflow_policy: self.overflow_policy,
+            max_pending_answers: self.max_pending_answers,
+            provenance_depth: self.provenance_depth,
+            flight_recorder: self.flight_recorder,
+            ask_priority: self.ask_priority,
+            batched_type: self.batcher.as_ref().map(|batcher| batcher.batched().1),
+            warmup: self.warmup.is_some(),
+            ready_fraction: self.ready_fraction,
+            launch_concurrency: self.launch_concurrency,
+            preset: self.preset,
+        };
+        debug!("Children({}): Finalized: {:?}", self.id(), config);
+        *self.config.lock().unwrap() = config;
+
+        Ok(())
+    }
+
+    fn conflict(&self) -> Result<(), BuilderConflict> {
+        if self.overflow_policy_set && self.mailbox_capacity.is_none() {
+            return Err(BuilderConflict::OverflowWithoutCapacity);
+        }
+
+        if let Some(batcher) = &self.batcher {
+            let (id, batched) = batcher.batched();
+            if !self.accepted_types.accepts(id) {
+                return Err(BuilderConflict::BatchedTypeNotAccepted { batched });
+            }
+        }
+
+        if self.routing.mode() == Routing::HashBy && !self.routing.has_key() {
+            return Err(BuilderConflict::MissingRoutingKey);
+        }
+
+        Ok(())
+    }
+
+    // Reserves the group's name (if it has one) before its elements
+    // are launched.
+    pub(crate) fn reserve_name(&self) -> Result<(), BuilderError> {
+        match &self.name {
+            Some(name) => SYSTEM.registry().reserve(name, self.id()),
+            None => Ok(()),
+        }
+    }
+
+    // Makes the group retrievable by its reserved name and part of
+    // the tree snapshots, once its elements were launched.
+    pub(crate) fn register(&self) {
+        let children_ref = self.as_ref();
+        tree::register_group(
+            self.id(),
+            self.bcast.parent(),
+            self.name.as_ref(),
+            children_ref.clone(),
+        );
+        if let Some(name) = &self.name {
+            SYSTEM.registry().register(name, children_ref);
+        }
+    }
+
+    fn unregister(&self) {
+        tree::unregister(self.id());
+        if let Some(name) = &self.name {
+            SYSTEM.registry().unregister(name, self.id());
+        }
+    }
+
+    #[cfg(feature = "patterns")]
+    pub(crate) fn with_next_stage(mut self, next_stage: ChildrenRef) -> Self {
+        trace!(
+            "Children({}): Setting next stage: {}",
+            self.id(),
+            next_stage.id()
+        );
+        self.next_stage = Some(next_stage);
+        self
+    }
+
+    async fn kill(&mut self) {
+        debug!("Children({}): Killing.", self.id());
+        self.queued.clear();
+        self.cancel_delayed_restarts();
+        self.bcast.kill_children();
+
+        let mut children = FuturesOrdered::new();
+        for (id, (child_ref, launched, token)) in self.launched.drain() {
+            self.metrics.terminate(&id);
+            self.record.terminated(&child_ref.stats());
+            launched.cancel();
+            // The element's future won't be polled again, so it
+            // can't cancel its token itself.
+            token.cancel();
+
+            children.push_back(launched);
+        }
+
+        children
+            .for_each_concurrent(None, |_| async {
+                trace!("Children({}): Unknown child stopped.", self.id());
+            })
+            .await;
+
+        // The handles resolve as soon as the elements' procs are
+        // cancelled, but their futures are only dropped once the
+        // executor runs them again.
+        self.wait_for_dropped().await;
+    }
+
+    // Waits for the futures of the elements launched until now to
+    // be dropped, then lets the group wait for the ones launched
+    // afterwards the next time it stops.
+    async fn wait_for_dropped(&mut self) {
+        let (alive, dropped) = mpsc::unbounded();
+        self.alive = alive;
+        let mut dropped = mem::replace(&mut self.dropped, dropped);
+        while dropped.next().await.is_some() {}
+    }
+
+    fn stopped(&mut self) {
+        debug!("Children({}): Stopped.", self.id());
+        self.remove_dispatchers();
+        self.unregister();
+        self.record_history(GroupState::Stopped);
+        self.bcast.stopped();
+        self.state.set(GroupState::Stopped);
+    }
+
+    fn killed(&mut self) {
+        debug!("Children({}): Killed.", self.id());
+        self.remove_dispatchers();
+        self.unregister();
+        self.record_history(GroupState::Killed);
+        self.bcast.killed();
+        self.state.set(GroupState::Killed);
+    }
+
+    fn faulted(&mut self) {
+        debug!("Children({}): Faulted.", self.id());
+        self.remove_dispatchers();
+        self.unregister();
+        self.record_history(GroupState::Faulted);
+        self.bcast.faulted();
+        self.state.set(GroupState::Faulted);
+    }
+
+    // Adds the group to the system's history of the groups that
+    // terminated, if it keeps one, before its termination is
+    // published (for it to be found once the group is waited for).
+    fn record_history(&mut self, state: GroupState) {
+        let history = SYSTEM.history();
+        if history.is_enabled() {
+            let id = self.bcast.id().clone();
+            history.record(self.record.finish(id, self.name.clone(), state));
+        }
+    }
+
+    async fn kill_children(&mut self) -> Result<(), ()> {
+        self.state.set(GroupState::Stopping);
         self.kill().await;
         self.stopped();
         Err(())
     }
 
+    // Delivers the batch the group accumulated before its elements
+    // are stopped, if it batches messages.
+    fn flush_batch(&self) {
+        if let Some(batcher) = &self.batcher {
+            debug!("Children({}): Flushing the pending batch.", self.id());
+            batcher.flush();
+        }
+    }
+
     async fn stop_children(&mut self) -> Result<(), ()> {
-        self.kill().await;
-        self.stopped();
+        self.stop_children_within(self.drain_timeout).await
+    }
+
+    async fn stop_children_within(&mut self, timeout: Duration) -> Result<(), ()> {
+        debug!("Children({}): Stopping within {:?}.", self.id(), timeout);
+        self.state.set(GroupState::Stopping);
+        let running = self.drain_running();
+        self.finish_children(running, timeout).await
+    }
+
+    async fn quiesce_children(&mut self, deadline: Duration) -> Result<(), ()> {
+        debug!("Children({}): Quiescing within {:?}.", self.id(), deadline);
+        let started = Instant::now();
+        self.state.set(GroupState::Quiescing);
+        let mut running = self.drain_running();
+        for (_, token) in &running {
+            token.shut_down();
+        }
+
+        // The elements are told to finish what they are doing and
+        // to stop on their own...
+        let msg = BastionMessage::quiesce(deadline);
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        self.bcast.send_children(env);
+        wait_for_elems(&mut running, deadline).await;
+
+        // ...before the ones still running are stopped.
+        if !running.is_empty() {
+            debug!(
+                "Children({}): Stopping {} element(s) that didn't quiesce in time.",
+                self.id(),
+                running.len()
+            );
+            self.state.set(GroupState::Stopping);
+        }
+
+        // They are only left with what remains of the deadline.
+        let remaining = deadline.saturating_sub(started.elapsed());
+        let remaining = remaining.max(QUIESCE_STOP_GRACE);
+        self.finish_children(running, remaining).await
+    }
+
+    // Drops the elements that weren't launched yet and returns
+    // the running ones, which aren't tracked by the group anymore.
+    fn drain_running(&mut self) -> Vec<(RecoverableHandle<()>, CancellationToken)> {
+        for elem in self.queued.drain(..) {
+            self.bcast.unregister(elem.child_ref.id());
+        }
+
+        self.cancel_delayed_restarts();
+
+        let mut running = Vec::new();
+        for (id, (child_ref, launched, token)) in self.launched.drain() {
+            self.metrics.terminate(&id);
+            self.record.terminated(&child_ref.stats());
+            running.push((launched, token));
+        }
+
+        running
+    }
+
+    // Stops the `running` elements once they are done handling
+    // their messages, killing the ones still running once
+    // `timeout` expired.
+    async fn finish_children(
+        &mut self,
+        mut running: Vec<(RecoverableHandle<()>, CancellationToken)>,
+        timeout: Duration,
+    ) -> Result<(), ()> {
+        // The elements' shutdown tokens are triggered right away,
+        // even for the ones that are busy with their futures.
+        for (_, token) in &running {
+            token.shut_down();
+        }
+
+        let msg = BastionMessage::stop_within(timeout);
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        self.bcast.send_children(env);
+
+        // The elements stop on their own once they are done
+        // handling their messages...
+        wait_for_elems(&mut running, timeout).await;
+
+        // ...or get killed if they didn't in time.
+        let killed = !running.is_empty();
+        if killed {
+            warn!(
+                "Children({}): Killing {} element(s) that didn't stop in time.",
+                self.id(),
+                running.len()
+            );
+            self.bcast.kill_children();
+            for (launched, token) in running {
+                launched.cancel();
+                token.cancel();
+                launched.await;
+            }
+        }
+
+        self.wait_for_dropped().await;
+
+        if killed {
+            self.killed();
+        } else {
+            self.stopped();
+        }
+
         Err(())
     }
 
@@ -418,6 +2716,8 @@ impl Children {
         if self.launched.contains_key(&id) {
             debug!("Children({}): Child({}) stopped.", self.id(), id);
             self.drop_child(id);
+            #[cfg(feature = "autoscaling")]
+            self.resizing.remove(id);
 
             let msg = BastionMessage::finished_child(id.clone(), self.bcast.id().clone());
             let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
@@ -440,28 +2740,206 @@ impl Children {
         Ok(())
     }
 
-    fn request_restarting_child(&mut self, id: &BastionId, parent_id: &BastionId) {
+    async fn prune_child(&mut self, id: &BastionId) {
+        let (child_ref, launched, token) = match self.launched.remove(id) {
+            Some(launched) => launched,
+            None => {
+                debug!(
+                    "Children({}): Ignoring the pruning of unknown Child({}).",
+                    self.id(),
+                    id
+                );
+                return;
+            }
+        };
+
+        debug!("Children({}): Pruning Child({}).", self.id(), id);
+        self.record.retired(&child_ref.stats());
+        // This also drops the element's sender.
+        self.bcast.kill_child(id);
+        launched.cancel();
+        token.cancel();
+        launched.await;
+
+        self.elem_inits.remove(id);
+        self.elem_indices.remove(id);
+        self.restarts.remove(id);
+        self.cancel_delayed_restart(id);
+        self.metrics.terminate(id);
+        // An element pruned while it was waiting to be restarted
+        // won't be restored.
+        if self.restarting.remove(id) && self.restarting.is_empty() {
+            self.state.set(GroupState::Running);
+        }
+
+        let msg = BastionMessage::finished_child(id.clone(), self.bcast.id().clone());
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        self.bcast.send_parent(env).ok();
+    }
+
+    fn request_restarting_child(
+        &mut self,
+        id: &BastionId,
+        parent_id: &BastionId,
+        reason: FaultReason,
+        error: Option<ExecError>,
+        provenance: Provenance,
+        records: Vec<FlightRecord>,
+    ) {
         if parent_id == self.bcast.id() && self.launched.contains_key(id) {
+            self.record.faulted(reason);
+            let attempt = self.restarts.entry(id.clone()).or_insert(0);
+            *attempt += 1;
+            let attempt = *attempt;
+
+            self.restarting.insert(id.clone());
+            self.state.set(GroupState::Restarting { attempt });
+
             let parent_id = self.bcast.id().clone();
-            let msg = BastionMessage::restart_required(id.clone(), parent_id);
+            let msg = BastionMessage::restart_required(
+                id.clone(),
+                parent_id,
+                reason,
+                error,
+                provenance,
+                records,
+            );
             let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
-            self.bcast.send_parent(env).ok();
+
+            let delay = match self.restart_delay(id) {
+                Some(delay) => delay,
+                None => {
+                    self.bcast.send_parent(env).ok();
+                    return;
+                }
+            };
+
+            debug!(
+                "Children({}): Delaying the restart of Child({}) by {:?}.",
+                self.id(),
+                id,
+                delay
+            );
+            let parent = self.bcast.parent().clone();
+            let deadline = reactor::now() + delay;
+            let delayed = async move {
+                Sleep::new(deadline).await;
+                parent.send(env).ok();
+            };
+
+            // The timer is cancelled if the group stops or the
+            // element is dropped before it fires.
+            let delayed = pool::spawn_with_class(delayed, ProcStack::default(), ProcClass::Timer);
+            if let Some(previous) = self.delayed_restarts.insert(id.clone(), delayed) {
+                previous.cancel();
+            }
+        }
+    }
+
+    // Returns how long the restart of the element with this id
+    // should be delayed by the group's back-off, if it has one,
+    // counting the restart as consecutive to the previous one
+    // unless the element ran for longer than the back-off's reset
+    // threshold since it was relaunched.
+    #[cfg(feature = "supervision")]
+    fn restart_delay(&mut self, id: &BastionId) -> Option<Duration> {
+        let backoff = self.restart_backoff.get().as_ref()?;
+        let now = reactor::now();
+        let attempt = match self.backoff_attempts.get(id) {
+            Some((attempt, relaunched))
+                if now.saturating_duration_since(*relaunched) < backoff.reset_after() =>
+            {
+                *attempt
+            }
+            _ => 0,
+        };
+
+        let delay = backoff.jittered_delay(attempt);
+        let attempts = (attempt.saturating_add(1), now + delay);
+        self.backoff_attempts.insert(id.clone(), attempts);
+
+        Some(delay)
+    }
+
+    #[cfg(not(feature = "supervision"))]
+    fn restart_delay(&mut self, _: &BastionId) -> Option<Duration> {
+        None
+    }
+
+    // Forgets the restarts of the element with this id, cancelling
+    // its delayed restart if it is waiting for one.
+    fn cancel_delayed_restart(&mut self, id: &BastionId) {
+        #[cfg(feature = "supervision")]
+        self.backoff_attempts.remove(id);
+        if let Some(delayed) = self.delayed_restarts.remove(id) {
+            delayed.cancel();
+        }
+    }
+
+    fn cancel_delayed_restarts(&mut self) {
+        for (_, delayed) in self.delayed_restarts.drain() {
+            delayed.cancel();
         }
     }
 
     fn restart_child(&mut self, old_id: &BastionId, old_state: &Qutex<Pin<Box<ContextState>>>) {
+        // The element might have been pruned in the meantime...
+        let (old_ref, old_launched, old_token) = match self.launched.remove(old_id) {
+            Some(launched) => launched,
+            None => {
+                debug!(
+                    "Children({}): Not restoring pruned Child({}).",
+                    self.id(),
+                    old_id
+                );
+                return;
+            }
+        };
+        // ...or still be running, when its supervisor restarts
+        // all the elements of the group (or more) along with the
+        // faulted one.
+        old_launched.cancel();
+        old_token.cancel();
+
+        self.delayed_restarts.remove(old_id);
+        #[cfg(feature = "supervision")]
+        {
+            if let Some((_, relaunched)) = self.backoff_attempts.get_mut(old_id) {
+                *relaunched = reactor::now();
+            }
+        }
+
+        if !self.requeue_on_restart {
+            drop_messages(old_id, old_state);
+        }
+
         let parent = Parent::children(self.as_ref());
         let bcast = Broadcast::new(parent, BastionPathElement::Child(old_id.clone()));
 
         let id = bcast.id().clone();
         let sender = bcast.sender().clone();
         let path = bcast.path().clone();
-        let child_ref = ChildRef::new(id.clone(), sender.clone(), path);
+        // The messages of the state the element is restored with
+        // still take their place in its mailbox, its counters
+        // outlive its restarts (unlike its watchers, which were
+        // notified that it faulted), and the `ChildRef`s referencing
+        // it keep on reaching it.
+        old_ref.counters().restarted();
+        let child_ref = ChildRef::new(id.clone(), sender.clone(), path)
+            .with_shared_sender(old_ref.shared_sender())
+            .with_accepted_types(self.accepted_types.clone())
+            .with_mailbox(old_ref.mailbox().cloned())
+            .with_pending_answers(old_ref.pending_answers().cloned())
+            .with_counters(old_ref.counters().clone());
 
         let children = self.as_ref();
         let supervisor = self.bcast.parent().clone().into_supervisor();
 
-        let state = Qutex::new(Box::pin(ContextState::new()));
+        // The restarted element's context needs to use the state
+        // it is restored with, or it wouldn't see the messages the
+        // element receives.
+        let state = old_state.clone();
+        let token = CancellationToken::new();
 
         let ctx = BastionContext::new(
             id.clone(),
@@ -469,8 +2947,18 @@ impl Children {
             children,
             supervisor,
             state.clone(),
-        );
-        let exec = (self.init.0)(ctx);
+            token.clone(),
+            self.next_stage.clone(),
+        )
+        .with_index(self.elem_indices.get(old_id).copied().unwrap_or_default())
+        .with_message_timeout(self.message_timeout);
+        let warmup = self
+            .warmup
+            .as_ref()
+            .map(|warmup| warmup.exec(ctx.duplicate()));
+        let init = self.elem_inits.get(old_id).unwrap_or(&self.init);
+        let exec = (init.0)(ctx);
+        let exec = self.wrap_exec(exec, &id, child_ref.counters().restarts());
 
         self.bcast.register(&bcast);
 
@@ -478,17 +2966,41 @@ impl Children {
         let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
         self.bcast.send_child(&id, env);
 
-        let msg = BastionMessage::apply_callback(CallbackType::AfterRestart);
-        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
-        self.bcast.send_child(&id, env);
-
         let msg = BastionMessage::start();
         let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
         self.bcast.send_child(&id, env);
 
+        // The messages whose acknowledgement is still awaited are
+        // sent again if the previous incarnation didn't acknowledge
+        // them.
+        self.sticky.retain(|(token, _)| !token.is_done());
+        for (token, env) in &self.sticky {
+            if !token.is_acked(old_id) {
+                if let Some(env) = env.try_clone() {
+                    self.bcast.send_child(&id, env);
+                }
+            }
+        }
+
         debug!("Children({}): Restarting Child({}).", self.id(), bcast.id());
         let callbacks = self.callbacks.clone();
-        let child = Child::new(exec, callbacks, bcast, state, child_ref);
+        let child = Child::new(
+            exec,
+            callbacks,
+            bcast,
+            state,
+            child_ref.clone(),
+            token.clone(),
+        )
+        .restarted()
+        .with_affinity(self.affinity.clone())
+        .with_provenance(self.provenance_depth)
+        .with_flight_recorder(self.flight_recorder)
+        .with_activity(self.activity.clone())
+        .with_ask_priority(self.ask_priority)
+        .with_warmup(warmup, self.routing.clone())
+        .with_alive(Some(self.alive.clone()))
+        .with_lifeline(self.lifeline.clone());
         debug!(
             "Children({}): Launching faulted Child({}).",
             self.id(),
@@ -496,7 +3008,27 @@ impl Children {
         );
         let id = child.id().clone();
         let launched = child.launch();
-        self.launched.insert(id, (sender, launched));
+        self.launched.insert(id, (child_ref, launched, token));
+
+        self.finished.remove(old_id);
+        self.restarting.remove(old_id);
+        if self.restarting.is_empty() {
+            self.state.set(GroupState::Running);
+        }
+    }
+
+    fn drop_faulted_child(&mut self, id: &BastionId) {
+        self.drop_child(id);
+        self.restarts.remove(id);
+        self.cancel_delayed_restart(id);
+        self.restarting.remove(id);
+
+        if self.launched.is_empty() {
+            warn!("Children({}): All the elements were dropped.", self.id());
+            self.state.set(GroupState::Faulted);
+        } else if self.restarting.is_empty() {
+            self.state.set(GroupState::Running);
+        }
     }
 
     fn drop_child(&mut self, id: &BastionId) {
@@ -505,7 +3037,41 @@ impl Children {
             self.id(),
             id,
         );
-        self.launched.remove_entry(id);
+        if let Some((_, (child_ref, _, _))) = self.launched.remove_entry(id) {
+            self.record.retired(&child_ref.stats());
+        }
+        self.elem_inits.remove(id);
+        self.elem_indices.remove(id);
+        self.metrics.terminate(id);
+    }
+
+    fn deploy(&mut self, deployment: Deployment) {
+        match deployment {
+            Deployment::Child { init, sender } => {
+                let index = self.free_index();
+                let child_ref = self.launch_elem(index, Some(init));
+
+                // The group was already started, so the element
+                // needs to be started too.
+                let msg = BastionMessage::start();
+                let env =
+                    Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+                self.bcast.send_child(child_ref.id(), env);
+
+                // The sender might not wait for the answer anymore.
+                sender.send(child_ref).ok();
+            }
+            Deployment::Supervisor(supervisor) => warn!(
+                "Children({}): Ignoring the deployment of Supervisor({}).",
+                self.id(),
+                supervisor.id()
+            ),
+            Deployment::Children(children) => warn!(
+                "Children({}): Ignoring the deployment of Children({}).",
+                self.id(),
+                children.id()
+            ),
+        }
     }
 
     async fn handle(&mut self, envelope: Envelope) -> Result<(), ()> {
@@ -517,26 +3083,69 @@ impl Children {
             Envelope {
                 msg: BastionMessage::Stop,
                 ..
-            } => self.stop_children().await?,
+            } => {
+                self.flush_batch();
+                self.stop_children().await?
+            }
+            Envelope {
+                msg: BastionMessage::StopWithin(timeout),
+                ..
+            } => {
+                self.flush_batch();
+                self.stop_children_within(timeout).await?
+            }
+            Envelope {
+                msg: BastionMessage::Quiesce(deadline),
+                ..
+            } => {
+                self.flush_batch();
+                self.quiesce_children(deadline).await?
+            }
             Envelope {
                 msg: BastionMessage::Kill,
                 ..
             } => self.kill_children().await?,
-            // FIXME
             Envelope {
-                msg: BastionMessage::Deploy(_),
+                msg: BastionMessage::Deploy(deployment),
+                ..
+            } => self.deploy(*deployment),
+            Envelope {
+                msg: BastionMessage::Prune { id },
                 ..
-            } => unimplemented!(),
-            // FIXME
+            } => self.prune_child(&id).await,
+            // The group's task is cancelled by its supervisor, which
+            // holds its handle.
             Envelope {
-                msg: BastionMessage::Prune { .. },
+                msg: BastionMessage::CancelTask(id),
                 ..
-            } => unimplemented!(),
-            // FIXME
+            } => {
+                let msg = BastionMessage::cancel_task(id);
+                let env =
+                    Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+                self.bcast.send_parent(env).ok();
+            }
+            Envelope {
+                msg: BastionMessage::Scale { redundancy, sender },
+                ..
+            } => self.scale(redundancy, sender).await,
+            Envelope {
+                msg: BastionMessage::SetRouting { routing, sender },
+                ..
+            } => {
+                let set = self.set_routing(routing);
+                // The sender stopped waiting for the answer.
+                sender.send(set).ok();
+            }
+            // The group's faulted elements are restarted by its
+            // supervisor, using this strategy from now on.
             Envelope {
-                msg: BastionMessage::SuperviseWith(_),
+                msg: BastionMessage::SuperviseWith(strategy),
                 ..
-            } => unimplemented!(),
+            } => {
+                debug!("Children({}): Setting strategy: {:?}", self.id(), strategy);
+                // FIXME: panics?
+                *self.supervision_strategy.lock().unwrap() = Some(strategy);
+            }
             Envelope {
                 msg: BastionMessage::ApplyCallback { .. },
                 ..
@@ -545,6 +3154,17 @@ impl Children {
                 msg: BastionMessage::InstantiatedChild { .. },
                 ..
             } => unreachable!(),
+            // The dead letters are told to the dead-letters group,
+            // whose only element handles them.
+            Envelope {
+                msg: BastionMessage::Message(ref message),
+                ..
+            } if self.id() == &NIL_ID && !message.is_broadcast() => {
+                if let Some((child, _, _)) = self.launched.values().next() {
+                    // FIXME: handle errors
+                    child.send(envelope).ok();
+                }
+            }
             Envelope {
                 msg: BastionMessage::Message(ref message),
                 ..
@@ -554,12 +3174,27 @@ impl Children {
                     self.id(),
                     message
                 );
+                if let Some(token) = message.ack_token() {
+                    self.sticky.retain(|(token, _)| !token.is_done());
+                    if let Some(env) = envelope.try_clone() {
+                        self.sticky.push((token, env));
+                    }
+                }
+
                 self.bcast.send_children(envelope);
             }
             Envelope {
-                msg: BastionMessage::RestartRequired { id, parent_id },
+                msg:
+                    BastionMessage::RestartRequired {
+                        id,
+                        parent_id,
+                        reason,
+                        error,
+                        provenance,
+                        records,
+                    },
                 ..
-            } => self.request_restarting_child(&id, &parent_id),
+            } => self.request_restarting_child(&id, &parent_id, reason, error, provenance, records),
             Envelope {
                 msg: BastionMessage::FinishedChild { .. },
                 ..
@@ -568,6 +3203,16 @@ impl Children {
                 msg: BastionMessage::RestartSubtree,
                 ..
             } => unreachable!(),
+            // Only meant for the supervisors, which also send those
+            // to the groups they supervise.
+            Envelope {
+                msg: BastionMessage::Degrade { .. },
+                ..
+            } => (),
+            Envelope {
+                msg: BastionMessage::Recover,
+                ..
+            } => (),
             Envelope {
                 msg: BastionMessage::RestoreChild { id, state },
                 ..
@@ -575,60 +3220,150 @@ impl Children {
             Envelope {
                 msg: BastionMessage::DropChild { id },
                 ..
-            } => self.drop_child(&id),
+            } => self.drop_faulted_child(&id),
             Envelope {
                 msg: BastionMessage::SetState { .. },
                 ..
             } => unreachable!(),
             Envelope {
-                msg: BastionMessage::Stopped { id },
+                msg: BastionMessage::Stopped { id, .. },
                 ..
             } => self.handle_stopped_child(&id).await?,
             Envelope {
                 msg: BastionMessage::Faulted { id },
                 ..
             } => self.handle_faulted_child(&id).await?,
+            Envelope {
+                msg: BastionMessage::MigrateChild { .. },
+                ..
+            } => unreachable!(),
         }
 
         Ok(())
     }
 
-    async fn initialize(&mut self) -> Result<(), ()> {
+    fn initialize(&mut self) {
         trace!(
             "Children({}): Received a new message (started=false): {:?}",
             self.id(),
             BastionMessage::Start
         );
         debug!("Children({}): Starting.", self.id());
-        self.started = true;
+        self.started.store(true, Ordering::SeqCst);
+        self.state.set(GroupState::Running);
 
         let msg = BastionMessage::start();
         let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
         self.bcast.send_children(env);
 
-        let msgs = self.pre_start_msgs.drain(..).collect::<Vec<_>>();
-        self.pre_start_msgs.shrink_to_fit();
-
         debug!(
-            "Children({}): Replaying messages received before starting.",
-            self.id()
+            "Children({}): Replaying {} messages received before starting.",
+            self.id(),
+            self.pre_start_msgs.len()
         );
-        for msg in msgs {
-            trace!("Children({}): Replaying message: {:?}", self.id(), msg);
-            if self.handle(msg).await.is_err() {
-                return Err(());
+    }
+
+    // Replays a chunk of the messages received before the group
+    // was started and then yields, so that a huge backlog can't
+    // delay the handling of the other messages (e.g. `Kill`).
+    async fn replay_pre_start_msgs(&mut self) -> Result<(), ()> {
+        // The messages received in the meantime are handled right
+        // away, except those to broadcast to the elements (which
+        // need to be broadcasted after the backlog).
+        for _ in 0..PRE_START_REPLAY_CHUNK {
+            match poll!(&mut self.bcast.next()) {
+                Poll::Ready(Some(
+                    env @ Envelope {
+                        msg: BastionMessage::Message(_),
+                        ..
+                    },
+                )) => self.pre_start_msgs.push_back(env),
+                Poll::Ready(Some(env)) => self.handle(env).await?,
+                Poll::Ready(None) => unreachable!(),
+                Poll::Pending => break,
             }
         }
 
+        for _ in 0..PRE_START_REPLAY_CHUNK {
+            let msg = match self.pre_start_msgs.pop_front() {
+                Some(msg) => msg,
+                None => break,
+            };
+
+            trace!("Children({}): Replaying message: {:?}", self.id(), msg);
+            self.handle(msg).await?;
+        }
+
+        if self.pre_start_msgs.is_empty() {
+            self.pre_start_msgs.shrink_to_fit();
+            return Ok(());
+        }
+
+        let mut yielded = false;
+        future::poll_fn(|ctx| {
+            if yielded {
+                return Poll::Ready(());
+            }
+
+            yielded = true;
+            ctx.waker().wake_by_ref();
+            Poll::Pending
+        })
+        .await;
+
         Ok(())
     }
 
+    fn buffer_pre_start_msg(&mut self, msg: Envelope) {
+        self.pre_start_msgs.push_back(msg);
+
+        let buffered = self.pre_start_msgs.len();
+        let watermark = *self.pre_start_watermark.get();
+        if !self.pre_start_exceeded && buffered > watermark {
+            warn!(
+                "Children({}): {} messages were received before starting (watermark: {}).",
+                self.id(),
+                buffered,
+                watermark
+            );
+            self.pre_start_exceeded = true;
+
+            let event = Event::PreStartBufferExceeded {
+                group: self.id().clone(),
+                buffered,
+                watermark,
+            };
+            SYSTEM.events().emit(event);
+        }
+    }
+
     async fn run(mut self) -> Self {
         debug!("Children({}): Launched.", self.id());
 
         loop {
-            for (_, launched) in self.launched.values_mut() {
-                let _ = poll!(launched);
+            for (id, (_, launched, _)) in self.launched.iter_mut() {
+                if poll!(launched).is_ready() {
+                    self.finished.insert(id.clone());
+                }
+            }
+
+            #[cfg(feature = "autoscaling")]
+            self.poll_resizer().await;
+
+            let mut initialized = false;
+            while let Poll::Ready(Some(id)) = poll!(self.initialized.next()) {
+                initialized |= self.initializing.remove(&id);
+            }
+            if initialized {
+                self.launch_queued();
+            }
+
+            if self.started.load(Ordering::SeqCst) && !self.pre_start_msgs.is_empty() {
+                if self.replay_pre_start_msgs().await.is_err() {
+                    return self;
+                }
+
+                continue;
             }
 
             match poll!(&mut self.bcast.next()) {
@@ -636,18 +3371,14 @@ impl Children {
                 Poll::Ready(Some(Envelope {
                     msg: BastionMessage::Start,
                     ..
-                })) => {
-                    if self.initialize().await.is_err() {
-                        return self;
-                    }
-                }
-                Poll::Ready(Some(msg)) if !self.started => {
+                })) => self.initialize(),
+                Poll::Ready(Some(msg)) if !self.started.load(Ordering::SeqCst) => {
                     trace!(
                         "Children({}): Received a new message (started=false): {:?}",
                         self.id(),
                         msg
                     );
-                    self.pre_start_msgs.push(msg);
+                    self.buffer_pre_start_msg(msg);
                 }
                 Poll::Ready(Some(msg)) => {
                     trace!(
@@ -663,62 +3394,487 @@ impl Children {
                 //      `Receiver` of the same channel, this would only be
                 //      possible if the channel was closed, which never happens.
                 Poll::Ready(None) => unreachable!(),
-                Poll::Pending => pending!(),
+                Poll::Pending => {
+                    // The elements report that they stopped or
+                    // faulted before their handle resolves, so
+                    // their reports were all handled by now.
+                    self.fault_unreported_elems();
+                    // NOTE: the mailbox and the elements' handles
+                    //      registered this task's waker when they were
+                    //      polled, so the group is only polled again
+                    //      once one of them can make progress.
+                    pending!()
+                }
+            }
+        }
+    }
+
+    // Elements whose handle resolved without them reporting that
+    // they stopped or faulted (e.g. because the hook called after
+    // a panic didn't run) would otherwise never be restarted nor
+    // dropped, so they are considered faulted.
+    fn fault_unreported_elems(&mut self) {
+        let launched = &self.launched;
+        self.finished.retain(|id| launched.contains_key(id));
+
+        let unreported = self
+            .finished
+            .iter()
+            .filter(|id| !self.restarting.contains(*id))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for id in unreported {
+            warn!(
+                "Children({}): Child({}) finished without reporting it.",
+                self.id(),
+                id
+            );
+            let parent_id = self.bcast.id().clone();
+            self.request_restarting_child(
+                &id,
+                &parent_id,
+                FaultReason::Unreported,
+                None,
+                Provenance::default(),
+                Vec::new(),
+            );
+        }
+    }
+
+    // Launches or prunes elements until the group has `redundancy`
+    // of them, pruning the ones with the highest indexes first.
+    fn set_routing(&self, routing: Routing) -> Result<(), RoutingError> {
+        debug!("Children({}): Setting routing: {:?}", self.id(), routing);
+        match &routing {
+            Routing::HashBy if !self.routing.has_key() => return Err(RoutingError::MissingKey),
+            Routing::Pinned(id) if !self.launched.contains_key(id) => {
+                return Err(RoutingError::UnknownElement(id.clone()))
+            }
+            _ => (),
+        }
+
+        self.routing.set_mode(routing);
+        Ok(())
+    }
+
+    async fn scale(&mut self, redundancy: usize, sender: oneshot::Sender<ChildrenRef>) {
+        let launched = self.launched.len();
+        debug!(
+            "Children({}): Scaling from {} to {} elements.",
+            self.id(),
+            launched,
+            redundancy
+        );
+        let launched = launched + self.queued.len();
+        self.queue_started_elems(redundancy.saturating_sub(launched));
+
+        let queued = self
+            .queued
+            .iter()
+            .map(|elem| (elem.index, elem.child_ref.id()));
+        let mut surplus = self
+            .elem_indices
+            .iter()
+            .map(|(id, index)| (*index, id))
+            .chain(queued)
+            .map(|(index, id)| (index, id.clone()))
+            .collect::<Vec<_>>();
+        surplus.sort_unstable_by_key(|(index, _)| Reverse(*index));
+        surplus.truncate(launched.saturating_sub(redundancy));
+        for (_, id) in surplus {
+            match self
+                .queued
+                .iter()
+                .position(|elem| elem.child_ref.id() == &id)
+            {
+                Some(position) => {
+                    self.queued.remove(position);
+                    self.bcast.unregister(&id);
+                }
+                None => self.prune_child(&id).await,
+            }
+        }
+
+        // The group is launched again with this redundancy if its
+        // supervisor restarts it.
+        self.redundancy = redundancy;
+        self.launch_queued();
+        self.configure_routing();
+
+        self.register();
+        // The sender might not wait for the answer anymore.
+        sender.send(self.as_ref()).ok();
+    }
+
+    // Queues `count` new elements, started once they are launched
+    // (because the group already is).
+    fn queue_started_elems(&mut self, count: usize) {
+        for _ in 0..count {
+            let index = self.free_index();
+            let elem = self.queue_elem(index);
+
+            let msg = BastionMessage::start();
+            let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+            self.bcast.send_child(elem.child_ref.id(), env);
+            self.queued.push_back(elem);
+        }
+    }
+
+    fn configure_routing(&self) {
+        self.routing.configure(
+            self.redundancy,
+            self.ready_fraction,
+            self.mailbox_capacity,
+            self.overflow_policy,
+        );
+    }
+
+    #[cfg(feature = "autoscaling")]
+    // Resizes the group each time its resizer's interval elapsed,
+    // once it was started.
+    async fn poll_resizer(&mut self) {
+        let interval = match &self.resizer {
+            Some(resizer) if self.started.load(Ordering::SeqCst) => resizer.interval(),
+            _ => return,
+        };
+
+        loop {
+            let tick = self
+                .resize_tick
+                .get_or_insert_with(|| Sleep::new(reactor::now() + interval));
+            // NOTE: this registers the group's waker, for it to be
+            //      polled again once the interval elapsed.
+            if poll!(tick).is_pending() {
+                return;
+            }
+
+            self.resize_tick = None;
+            self.resize();
+        }
+    }
+
+    #[cfg(feature = "autoscaling")]
+    // Samples the pressure on the group's elements, and launches
+    // or stops elements if its resizer asks to.
+    fn resize(&mut self) {
+        let resizer = match &self.resizer {
+            Some(resizer) => resizer,
+            None => return,
+        };
+
+        // The elements the resizer stopped might have been
+        // restarted (and thus replaced) since.
+        let launched = &self.launched;
+        self.resizing.retain(|id| launched.contains_key(id));
+
+        let resizing = &self.resizing;
+        let elems = launched
+            .iter()
+            .filter(|(id, _)| !resizing.contains(*id))
+            .map(|(_, (child_ref, _, _))| {
+                let counters = child_ref.counters();
+                (counters.mailbox_len(), counters.is_idle())
+            });
+        let pressure = Pressure::sample(elems, self.queued.len());
+        trace!("Children({}): Sampled pressure: {:?}", self.id(), pressure);
+
+        match resizer.resize(&pressure) {
+            Some(Resize::Up(redundancy)) => {
+                debug!(
+                    "Children({}): Resizing from {} to {} elements.",
+                    self.id(),
+                    pressure.elems,
+                    redundancy
+                );
+                self.queue_started_elems(redundancy - pressure.elems);
+                self.redundancy = redundancy;
+                self.launch_queued();
+                self.configure_routing();
+                self.register();
             }
+            Some(Resize::Down) => self.stop_idle_elem(pressure.elems, resizer.interval()),
+            None => (),
         }
     }
 
+    #[cfg(feature = "autoscaling")]
+    // Stops the idle element with the highest index, which finishes
+    // handling the messages it might be sent in the meantime before
+    // stopping, so that the group ends up with `elems - 1` elements.
+    fn stop_idle_elem(&mut self, elems: usize, timeout: Duration) {
+        let resizing = &self.resizing;
+        let idle = self
+            .launched
+            .iter()
+            .filter(|(id, (child_ref, _, _))| {
+                !resizing.contains(*id) && child_ref.counters().is_idle()
+            })
+            .filter_map(|(id, _)| Some((*self.elem_indices.get(id)?, id.clone())))
+            .max_by_key(|(index, _)| *index);
+        let id = match idle {
+            Some((_, id)) => id,
+            None => return,
+        };
+
+        debug!(
+            "Children({}): Resizing from {} to {} elements: stopping Child({}).",
+            self.id(),
+            elems,
+            elems - 1,
+            id
+        );
+        let msg = BastionMessage::stop_within(timeout);
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        self.bcast.send_child(&id, env);
+        self.resizing.insert(id);
+
+        self.redundancy = elems - 1;
+        self.configure_routing();
+        self.register();
+    }
+
+    // The lowest index that none of the group's elements uses, for
+    // an element taking the place of one that was dropped (if any).
+    fn free_index(&self) -> usize {
+        let used = self
+            .elem_indices
+            .values()
+            .chain(self.queued.iter().map(|elem| &elem.index))
+            .collect::<FxHashSet<_>>();
+        (0..).find(|index| !used.contains(index)).unwrap()
+    }
+
     pub(crate) fn launch_elems(&mut self) {
         debug!("Children({}): Launching elements.", self.id());
-        for _ in 0..self.redundancy {
-            let parent = Parent::children(self.as_ref());
-            let bcast = Broadcast::new(parent, BastionPathElement::Child(BastionId::new()));
+        self.configure_routing();
+        self.activity = GroupActivity::register(self.name.clone());
+        for index in 0..self.redundancy {
+            // The elements of a respawned group take the place of
+            // those which had the same indices (which stopped, so
+            // their ids aren't in `launched` anymore).
+            let slot = self.slots.get(&index).cloned();
+            let elem = self.queue_elem_in(index, slot);
+            self.queued.push_back(elem);
+        }
 
-            // TODO: clone or ref?
-            let id = bcast.id().clone();
-            let sender = bcast.sender().clone();
-            let path = bcast.path().clone();
-            let child_ref = ChildRef::new(id.clone(), sender.clone(), path);
+        self.launch_queued();
+    }
 
-            let children = self.as_ref();
-            let supervisor = self.bcast.parent().clone().into_supervisor();
+    // Launches the queued elements until as many elements as the
+    // group's launch concurrency are initializing.
+    fn launch_queued(&mut self) {
+        while !self.queued.is_empty() {
+            if let Some(concurrency) = self.launch_concurrency {
+                if self.initializing.len() >= concurrency {
+                    break;
+                }
+            }
 
-            let state = Qutex::new(Box::pin(ContextState::new()));
+            let elem = self.queued.pop_front().unwrap();
+            let launch =
+                LaunchPermit::new(elem.child_ref.id().clone(), self.initialized_sender.clone());
+            self.initializing.insert(elem.child_ref.id().clone());
+            self.spawn_elem(elem, None, Some(launch));
+        }
 
-            let ctx = BastionContext::new(
-                id.clone(),
-                child_ref.clone(),
-                children,
-                supervisor,
-                state.clone(),
-            );
-            let exec = (self.init.0)(ctx);
+        // The elements which stopped since they were launched are
+        // still counted.
+        let launched = self
+            .redundancy
+            .saturating_sub(self.queued.len())
+            .max(self.launched.len());
+        let initializing = self.initializing.len();
+        self.launch
+            .set(launched, launched.saturating_sub(initializing));
+        trace!(
+            "Children({}): Launched {} elements ({} initializing, {} queued).",
+            self.id(),
+            launched,
+            initializing,
+            self.queued.len()
+        );
+    }
 
-            let parent_id = self.bcast.id().clone();
-            let msg = BastionMessage::instantiated_child(parent_id, id.clone(), state.clone());
-            let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
-            self.bcast.send_parent(env).ok();
+    // Launches a new element with this index, running the future
+    // returned by `init`, or by the group's closure if `None`.
+    fn launch_elem(&mut self, index: usize, init: Option<Init>) -> ChildRef {
+        let elem = self.queue_elem(index);
+        self.spawn_elem(elem, init, None)
+    }
 
-            self.bcast.register(&bcast);
+    // Creates the broadcast and a `ChildRef` of a new element with
+    // this index, which is launched using `spawn_elem`.
+    fn queue_elem(&mut self, index: usize) -> QueuedElem {
+        self.queue_elem_in(index, None)
+    }
 
-            debug!(
-                "Children({}): Initializing Child({}).",
-                self.id(),
-                bcast.id()
-            );
-            let callbacks = self.callbacks.clone();
-            let child = Child::new(exec, callbacks, bcast, state, child_ref);
-            debug!("Children({}): Launching Child({}).", self.id(), child.id());
-            let id = child.id().clone();
-            let launched = child.launch();
-            self.launched.insert(id, (sender, launched));
+    // Like `queue_elem`, but for an element taking the place of the
+    // one `slot` references, if the group uses stable ids.
+    fn queue_elem_in(&mut self, index: usize, slot: Option<ChildRef>) -> QueuedElem {
+        let slot = slot.filter(|_| self.stable_ids);
+        let elem_id = match &slot {
+            Some(slot) => slot.id().clone(),
+            None => BastionId::new(),
+        };
+        let parent = Parent::children(self.as_ref());
+        let bcast = Broadcast::new(parent, BastionPathElement::Child(elem_id));
+
+        // TODO: clone or ref?
+        let id = bcast.id().clone();
+        let sender = bcast.sender().clone();
+        let path = bcast.path().clone();
+        let child_ref = match slot {
+            // Like when an element is restarted, the `ChildRef`s
+            // referencing the element it replaces reach it.
+            Some(slot) => ChildRef::new(id, sender, path)
+                .with_shared_sender(slot.shared_sender())
+                .with_accepted_types(self.accepted_types.clone())
+                .with_mailbox(slot.mailbox().cloned())
+                .with_pending_answers(slot.pending_answers().cloned())
+                .with_counters(slot.counters().clone()),
+            None => {
+                let mailbox = self
+                    .mailbox_capacity
+                    .map(|capacity| Mailbox::new(capacity, self.overflow_policy));
+                let pending_answers = PendingAnswers::new(self.max_pending_answers);
+                ChildRef::new(id, sender, path)
+                    .with_accepted_types(self.accepted_types.clone())
+                    .with_mailbox(mailbox)
+                    .with_pending_answers(Some(pending_answers))
+            }
+        };
+        if self.stable_ids {
+            self.slots.insert(index, child_ref.clone());
+        }
+
+        // The element receives the messages broadcasted to the
+        // group before it is launched.
+        self.bcast.register(&bcast);
+
+        QueuedElem {
+            index,
+            bcast,
+            child_ref,
+        }
+    }
+
+    fn spawn_elem(
+        &mut self,
+        elem: QueuedElem,
+        init: Option<Init>,
+        launch: Option<LaunchPermit>,
+    ) -> ChildRef {
+        let QueuedElem {
+            index,
+            bcast,
+            child_ref,
+        } = elem;
+        let id = child_ref.id().clone();
+
+        let children = self.as_ref();
+        let supervisor = self.bcast.parent().clone().into_supervisor();
+
+        let state = Qutex::new(Box::pin(ContextState::new(child_ref.counters().clone())));
+        let token = CancellationToken::new();
+
+        let ctx = BastionContext::new(
+            id.clone(),
+            child_ref.clone(),
+            children,
+            supervisor,
+            state.clone(),
+            token.clone(),
+            self.next_stage.clone(),
+        )
+        .with_index(index)
+        .with_message_timeout(self.message_timeout);
+        let ctx = match &launch {
+            Some(launch) => ctx.with_launch_permit(launch.downgrade()),
+            None => ctx,
+        };
+        self.elem_indices.insert(id.clone(), index);
+        let warmup = self
+            .warmup
+            .as_ref()
+            .map(|warmup| warmup.exec(ctx.duplicate()));
+        let exec = match init {
+            Some(init) => {
+                let exec = (init.0)(ctx);
+                self.elem_inits.insert(id.clone(), init);
+                exec
+            }
+            None => (self.init.0)(ctx),
+        };
+        let exec = self.wrap_exec(exec, &id, 0);
+
+        let parent_id = self.bcast.id().clone();
+        let msg = BastionMessage::instantiated_child(parent_id, id.clone(), state.clone());
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        self.bcast.send_parent(env).ok();
+
+        debug!(
+            "Children({}): Initializing Child({}).",
+            self.id(),
+            bcast.id()
+        );
+        let callbacks = self.callbacks.clone();
+        let child = Child::new(
+            exec,
+            callbacks,
+            bcast,
+            state,
+            child_ref.clone(),
+            token.clone(),
+        )
+        .with_affinity(self.affinity.clone())
+        .with_provenance(self.provenance_depth)
+        .with_flight_recorder(self.flight_recorder)
+        .with_activity(self.activity.clone())
+        .with_ask_priority(self.ask_priority)
+        .with_warmup(warmup, self.routing.clone())
+        .with_alive(Some(self.alive.clone()))
+        .with_lifeline(self.lifeline.clone());
+        let child = match launch {
+            Some(launch) => child.with_launch_permit(launch),
+            None => child,
+        };
+        debug!("Children({}): Launching Child({}).", self.id(), child.id());
+        let id = child.id().clone();
+        let launched = child.launch();
+        self.launched
+            .insert(id, (child_ref.clone(), launched, token));
+
+        child_ref
+    }
+
+    // Makes the group's elements execute their futures without
+    // wrapping them using the wrapper set with `Config::exec_wrapper`.
+    pub(crate) fn unwrapped(mut self) -> Self {
+        self.wrapped = false;
+        self
+    }
+
+    // Wraps the future of the element with this id using the
+    // wrapper set with `Config::exec_wrapper`, if there is one.
+    fn wrap_exec(&self, exec: Exec, id: &BastionId, generation: usize) -> Exec {
+        if !self.wrapped {
+            return exec;
+        }
+
+        match SYSTEM.config().wrapper() {
+            Some(wrapper) => {
+                let group = self.name.clone();
+                exec.wrap(wrapper, ExecInfo::new(group, id.clone(), generation))
+            }
+            None => exec,
         }
     }
 
     pub(crate) fn launch(self) -> RecoverableHandle<Self> {
         debug!("Children({}): Launching.", self.id());
         let stack = self.stack();
-        pool::spawn(self.run(), stack)
+        pool::spawn_with_class(self.run(), stack, ProcClass::Management)
     }
 
     /// Registers all declared local dispatchers in the global dispatcher.
@@ -739,3 +3895,239 @@ impl Children {
         }
     }
 }
+
+// Waits for the `running` elements to stop, or for `timeout` to
+// expire, removing the ones which stopped.
+async fn wait_for_elems(
+    running: &mut Vec<(RecoverableHandle<()>, CancellationToken)>,
+    timeout: Duration,
+) {
+    let mut delay = Delay::new(timeout);
+    future::poll_fn(|cx| {
+        running.retain_mut(|(launched, _)| Pin::new(launched).poll(cx).is_pending());
+        if running.is_empty() || Pin::new(&mut delay).poll(cx).is_ready() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+// Sends the messages that the element `id` received but didn't
+// handle to the dead letters before it is restarted, when its group
+// doesn't requeue them. Its future was dropped or cancelled, so
+// nothing should be holding its state anymore (the messages are
+// kept if something still does).
+fn drop_messages(id: &BastionId, state: &Qutex<Pin<Box<ContextState>>>) {
+    if let Some(Ok(mut guard)) = state.clone().lock_async().now_or_never() {
+        let mut state = guard.as_mut();
+        let msgs = state.take_messages();
+        debug!(
+            "Children: Dropping {} message(s) of restarted Child({}).",
+            msgs.len(),
+            id
+        );
+        for SignedMessage { msg, sign } in msgs {
+            let env = Envelope::new_with_sign(BastionMessage::Message(msg), sign);
+            dead_letters::bounce(env, Some(id), DeadLetterReason::RecipientStopped);
+        }
+    }
+}
+
+impl<S: Send + 'static> StatefulChildren<S> {
+    /// Sets the closure taking the state returned by the future
+    /// returned by the closure set with
+    /// [`Children::with_async_init`] and a [`BastionContext`], and
+    /// returning a [`Future`] that will be used by every element of
+    /// this children group (like [`Children::with_exec`] does), once
+    /// it waited for its state.
+    ///
+    /// This method gives the group back, to be configured further
+    /// or returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `exec` - The closure taking the element's state and a
+    ///     [`BastionContext`] and returning a [`Future`] that will
+    ///     be used by every element of this children group.
+    ///
+    /// See [`Children::with_async_init`] for an example.
+    ///
+    /// [`Children::with_async_init`]: struct.Children.html#method.with_async_init
+    /// [`Children::with_exec`]: struct.Children.html#method.with_exec
+    pub fn with_exec<I, F>(self, exec: I) -> Children
+    where
+        I: Fn(S, BastionContext) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        let mut children = self.children;
+        trace!(
+            "Children({}): Setting exec closure with state.",
+            children.id()
+        );
+        let exec = child::exec_with_state(exec);
+        children.stateful_init = Some(Init::with_state(self.init, exec));
+        children
+    }
+}
+
+impl<S> Debug for StatefulChildren<S> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("StatefulChildren")
+            .field("children", &self.children)
+            .finish()
+    }
+}
+
+impl GroupConfig {
+    /// Returns the name of the children group (see
+    /// [`Children::with_name`]), if it has one.
+    ///
+    /// [`Children::with_name`]: struct.Children.html#method.with_name
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the number of elements the children group is
+    /// launched with (see [`Children::with_redundancy`]).
+    ///
+    /// [`Children::with_redundancy`]: struct.Children.html#method.with_redundancy
+    pub fn redundancy(&self) -> usize {
+        self.redundancy
+    }
+
+    /// Returns whether the elements of the children group are
+    /// initialized using [`Children::with_async_init`].
+    ///
+    /// [`Children::with_async_init`]: struct.Children.html#method.with_async_init
+    pub fn is_stateful(&self) -> bool {
+        self.stateful
+    }
+
+    /// Returns the names of the message types the children group
+    /// accepts (see [`Children::with_accepted_types`]), or `None`
+    /// if it accepts any.
+    ///
+    /// [`Children::with_accepted_types`]: struct.Children.html#method.with_accepted_types
+    pub fn accepted_types(&self) -> Option<&[&'static str]> {
+        self.accepted_types.as_deref()
+    }
+
+    /// Returns the capacity of the elements' mailboxes (see
+    /// [`Children::with_mailbox_capacity`]), or `None` if they
+    /// are unbounded.
+    ///
+    /// [`Children::with_mailbox_capacity`]: struct.Children.html#method.with_mailbox_capacity
+    pub fn mailbox_capacity(&self) -> Option<usize> {
+        self.mailbox_capacity
+    }
+
+    /// Returns what happens to the messages sent to a full
+    /// mailbox (see [`Children::with_overflow_policy`]).
+    ///
+    /// [`Children::with_overflow_policy`]: struct.Children.html#method.with_overflow_policy
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        self.overflow_policy
+    }
+
+    /// Returns the number of questions asked to an element that
+    /// can be waiting for its answer (see
+    /// [`Children::with_max_pending_answers`]).
+    ///
+    /// [`Children::with_max_pending_answers`]: struct.Children.html#method.with_max_pending_answers
+    pub fn max_pending_answers(&self) -> usize {
+        self.max_pending_answers
+    }
+
+    /// Returns the maximum depth of the provenance chains the
+    /// elements record (see [`Children::with_provenance_depth`]),
+    /// or `None` if they don't record them.
+    ///
+    /// [`Children::with_provenance_depth`]: struct.Children.html#method.with_provenance_depth
+    pub fn provenance_depth(&self) -> Option<usize> {
+        self.provenance_depth
+    }
+
+    /// Returns the number of messages recorded by the elements'
+    /// flight recorders (see [`Children::with_flight_recorder`]),
+    /// or `None` if they don't have one.
+    ///
+    /// [`Children::with_flight_recorder`]: struct.Children.html#method.with_flight_recorder
+    pub fn flight_recorder(&self) -> Option<usize> {
+        self.flight_recorder
+    }
+
+    /// Returns the priority of the messages asked without one
+    /// (see [`Children::with_ask_priority`]).
+    ///
+    /// [`Children::with_ask_priority`]: struct.Children.html#method.with_ask_priority
+    pub fn ask_priority(&self) -> Priority {
+        self.ask_priority
+    }
+
+    /// Returns the name of the type of the messages batched by
+    /// the children group (see [`Children::with_batching`]), if
+    /// it batches messages.
+    ///
+    /// [`Children::with_batching`]: struct.Children.html#method.with_batching
+    pub fn batched_type(&self) -> Option<&'static str> {
+        self.batched_type
+    }
+
+    /// Returns whether the elements warm up before executing
+    /// their future (see [`Children::with_warmup`]).
+    ///
+    /// [`Children::with_warmup`]: struct.Children.html#method.with_warmup
+    pub fn has_warmup(&self) -> bool {
+        self.warmup
+    }
+
+    /// Returns the fraction of the elements that must be warm for
+    /// the children group to be ready (see
+    /// [`Children::with_ready_fraction`]).
+    ///
+    /// [`Children::with_ready_fraction`]: struct.Children.html#method.with_ready_fraction
+    pub fn ready_fraction(&self) -> f64 {
+        self.ready_fraction
+    }
+
+    /// Returns the maximum number of elements initializing
+    /// concurrently (see [`Children::with_launch_concurrency`]),
+    /// if there is one.
+    ///
+    /// [`Children::with_launch_concurrency`]: struct.Children.html#method.with_launch_concurrency
+    pub fn launch_concurrency(&self) -> Option<usize> {
+        self.launch_concurrency
+    }
+
+    /// Returns the name of the preset applied to the children
+    /// group (see [`Children::apply_preset`]), if any.
+    ///
+    /// [`Children::apply_preset`]: struct.Children.html#method.apply_preset
+    pub fn preset(&self) -> Option<&'static str> {
+        self.preset
+    }
+}
+
+impl Default for GroupConfig {
+    fn default() -> Self {
+        GroupConfig {
+            name: None,
+            redundancy: 1,
+            stateful: false,
+            accepted_types: None,
+            mailbox_capacity: None,
+            overflow_policy: OverflowPolicy::default(),
+            max_pending_answers: DEFAULT_MAX_PENDING_ANSWERS,
+            provenance_depth: None,
+            flight_recorder: None,
+            ask_priority: Priority::default(),
+            batched_type: None,
+            warmup: false,
+            ready_fraction: 1.0,
+            launch_concurrency: None,
+            preset: None,
+        }
+    }
+}
@@ -1,15 +1,42 @@
 //!
 //! Allows users to communicate with children through the mailboxes.
+use crate::ack::{AckReport, AckTracker};
+#[cfg(feature = "supervision")]
+use crate::backoff::Backoff;
+use crate::batch::Batcher;
 use crate::broadcast::Sender;
+use crate::child::Init;
 use crate::child_ref::ChildRef;
-use crate::context::BastionId;
+use crate::children::{GroupConfig, DEFAULT_DRAIN_TIMEOUT, DEFAULT_PRE_START_WATERMARK};
+use crate::context::{BastionContext, BastionId};
 use crate::dispatcher::DispatcherType;
 use crate::envelope::Envelope;
-use crate::message::{BastionMessage, Message};
+use crate::errors::{BuilderError, SendError, SendErrorKind, ShutdownError};
+use crate::launch::LaunchProgress;
+use crate::mailbox::OverflowPolicy;
+use crate::message::{AcceptedTypes, Answer, BastionMessage, Message, Msg};
+use crate::metrics::{GroupMetrics, GroupStats};
 use crate::path::BastionPath;
+use crate::routing::{Routing, RoutingError};
+use crate::supervisor::{RestartStrategy, SupervisionStrategy};
+use crate::warmup::Router;
+use bastion_executor::affinity::AffinityGroup;
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures::channel::oneshot::{self, Receiver};
+use futures::future::{self, Either};
+use futures::stream::FuturesUnordered;
+use futures::{Stream, StreamExt};
+use futures_timer::Delay;
+use std::any::Any;
 use std::cmp::{Eq, PartialEq};
-use std::fmt::Debug;
-use std::sync::Arc;
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub use bastion_executor::affinity::AffinityStats;
 
 #[derive(Debug, Clone)]
 /// A "reference" to a children group, allowing to communicate
@@ -20,15 +47,278 @@ pub struct ChildrenRef {
     path: Arc<BastionPath>,
     children: Vec<ChildRef>,
     dispatchers: Vec<DispatcherType>,
+    started: Arc<AtomicBool>,
+    state: GroupStateCell,
+    policies: Arc<Mutex<GroupPolicies>>,
+    metrics: GroupMetrics,
+    accepted_types: AcceptedTypes,
+    affinity: Option<AffinityGroup>,
+    routing: Router,
+    batcher: Option<Batcher>,
+    // Only read by the tree's snapshots.
+    #[cfg_attr(not(feature = "introspection"), allow(dead_code))]
+    launch: LaunchProgress,
+    config: Arc<Mutex<GroupConfig>>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// What happened to a message sent using
+/// [`ChildrenRef::tell_outcome`].
+///
+/// [`ChildrenRef::tell_outcome`]: struct.ChildrenRef.html#method.tell_outcome
+pub enum SendOutcome {
+    /// The children group wasn't started yet, so the message
+    /// will be kept until it is.
+    Buffered,
+    /// The children group was started, so the message will be
+    /// broadcasted to its elements.
+    Delivered,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// The state of a children group, as returned by
+/// [`ChildrenRef::state`] and [`ChildrenRef::state_changes`].
+///
+/// [`ChildrenRef::state`]: struct.ChildrenRef.html#method.state
+/// [`ChildrenRef::state_changes`]: struct.ChildrenRef.html#method.state_changes
+pub enum GroupState {
+    /// The group was created but wasn't started yet (the
+    /// messages it receives are kept until it is).
+    Dormant,
+    /// The group was started and none of its elements is
+    /// waiting to be restarted.
+    Running,
+    /// An element of the group faulted and is waiting for its
+    /// supervisor to restart it.
+    Restarting {
+        /// The number of times the element was asked to be
+        /// restarted, including this time.
+        attempt: usize,
+    },
+    /// The group was asked to be quiesced (see
+    /// [`ChildrenRef::quiesce`]) and is waiting for its elements
+    /// to stop on their own.
+    ///
+    /// [`ChildrenRef::quiesce`]: struct.ChildrenRef.html#method.quiesce
+    Quiescing,
+    /// The group received a message asking it to stop (or to be
+    /// killed) and is stopping its elements.
+    Stopping,
+    /// The group and all its elements stopped.
+    Stopped,
+    /// The group was asked to stop within a timeout (see
+    /// [`ChildrenRef::stop_with_timeout`]) and killed the
+    /// elements which didn't stop in time.
+    ///
+    /// [`ChildrenRef::stop_with_timeout`]: struct.ChildrenRef.html#method.stop_with_timeout
+    Killed,
+    /// The group faulted or all its elements reached their
+    /// supervisor's restart limits.
+    Faulted,
+}
+
+#[derive(Debug, Clone)]
+// The state of a children group, shared between the group and
+// its `ChildrenRef`s, and the subscribers to its transitions.
+pub(crate) struct GroupStateCell(Arc<Mutex<GroupStateInner>>);
+
+#[derive(Debug)]
+struct GroupStateInner {
+    state: GroupState,
+    subscribers: Vec<UnboundedSender<GroupState>>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// The policies applying to a children group, as returned by
+/// [`ChildrenRef::policies`].
+///
+/// The policies that the group doesn't set itself are inherited
+/// from the nearest supervisor setting them.
+///
+/// [`ChildrenRef::policies`]: struct.ChildrenRef.html#method.policies
+pub struct GroupPolicies {
+    restart_strategy: RestartStrategy,
+    #[cfg(feature = "supervision")]
+    restart_window: Option<(usize, Duration)>,
+    #[cfg(feature = "supervision")]
+    restart_backoff: Option<Backoff>,
+    pre_start_watermark: usize,
+    mailbox_capacity: Option<usize>,
+    overflow_policy: OverflowPolicy,
+    drain_timeout: Duration,
+    requeue_on_restart: bool,
+    preset: Option<&'static str>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// A report returned once an element of a children group was
+/// successfully migrated using [`ChildrenRef::migrate_elem`].
+///
+/// [`ChildrenRef::migrate_elem`]: struct.ChildrenRef.html#method.migrate_elem
+pub struct MigrationReport {
+    transferred: usize,
+    dead_lettered: usize,
+    dropped: usize,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// The reasons why [`ChildrenRef::migrate_elem`] can fail.
+///
+/// [`ChildrenRef::migrate_elem`]: struct.ChildrenRef.html#method.migrate_elem
+pub enum MigrationError {
+    /// The element isn't (or isn't anymore) part of the
+    /// children group.
+    UnknownElement(BastionId),
+    /// The source and the target of the migration are the
+    /// same element.
+    SameElement,
+    /// The source element stopped before being migrated.
+    SourceStopped,
+    /// The target element stopped before receiving the
+    /// source element's mailbox. The messages that couldn't
+    /// be transferred stay in the source element's mailbox.
+    TargetStopped,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// The error returned by [`ChildrenRef::ask_reduce`] when some
+/// elements didn't answer, carrying the answers reduced so far.
+///
+/// [`ChildrenRef::ask_reduce`]: struct.ChildrenRef.html#method.ask_reduce
+pub struct ReduceError<R> {
+    partial: R,
+    answered: usize,
+    unanswered: usize,
+    timed_out: usize,
+    send: Option<SendErrorKind>,
+}
+
+impl MigrationReport {
+    pub(crate) fn new(transferred: usize, dead_lettered: usize, dropped: usize) -> Self {
+        MigrationReport {
+            transferred,
+            dead_lettered,
+            dropped,
+        }
+    }
+
+    /// Returns the number of pending messages that were moved
+    /// from the source element's mailbox to the target element's
+    /// one.
+    pub fn transferred(&self) -> usize {
+        self.transferred
+    }
+
+    /// Returns the number of messages that were still arriving in
+    /// the source element's mailbox while it was migrated and that
+    /// were sent to the dead letters because the target element
+    /// stopped in the meantime.
+    pub fn dead_lettered(&self) -> usize {
+        self.dead_lettered
+    }
+
+    /// Returns the number of control messages (e.g. a request to
+    /// stop) that were addressed to the source element while it was
+    /// migrated and that were dropped instead of being transferred.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+}
+
+impl Display for MigrationError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            MigrationError::UnknownElement(id) => write!(fmt, "unknown element: {}", id),
+            MigrationError::SameElement => fmt.write_str("can't migrate an element to itself"),
+            MigrationError::SourceStopped => fmt.write_str("the source element stopped"),
+            MigrationError::TargetStopped => fmt.write_str("the target element stopped"),
+        }
+    }
+}
+
+impl Error for MigrationError {}
+
+impl<R> ReduceError<R> {
+    /// Returns the result of reducing the answers that were
+    /// received.
+    pub fn partial(&self) -> &R {
+        &self.partial
+    }
+
+    /// Returns the result of reducing the answers that were
+    /// received, consuming the error.
+    pub fn into_partial(self) -> R {
+        self.partial
+    }
+
+    /// Returns the number of elements whose answer was reduced.
+    pub fn answered(&self) -> usize {
+        self.answered
+    }
+
+    /// Returns the number of elements that dropped the message
+    /// without answering it (e.g. because they stopped or
+    /// faulted).
+    pub fn unanswered(&self) -> usize {
+        self.unanswered
+    }
+
+    /// Returns the number of elements that didn't answer before
+    /// the timeout expired, whose answers are discarded.
+    pub fn timed_out(&self) -> usize {
+        self.timed_out
+    }
+
+    /// Returns why the message couldn't be sent to the group, if
+    /// it couldn't (e.g. because the group doesn't have any
+    /// element).
+    pub fn send_error(&self) -> Option<&SendErrorKind> {
+        self.send.as_ref()
+    }
+
+    pub(crate) fn map<T>(self, f: impl FnOnce(R) -> T) -> ReduceError<T> {
+        ReduceError {
+            partial: f(self.partial),
+            answered: self.answered,
+            unanswered: self.unanswered,
+            timed_out: self.timed_out,
+            send: self.send,
+        }
+    }
+}
+
+impl<R> Display for ReduceError<R> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match &self.send {
+            Some(kind) => write!(fmt, "couldn't send the message: {}", kind),
+            None => write!(
+                fmt,
+                "{} elements answered, {} didn't and {} timed out",
+                self.answered, self.unanswered, self.timed_out
+            ),
+        }
+    }
 }
 
+impl<R: Debug> Error for ReduceError<R> {}
+
 impl ChildrenRef {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         id: BastionId,
         sender: Sender,
         path: Arc<BastionPath>,
         children: Vec<ChildRef>,
         dispatchers: Vec<DispatcherType>,
+        started: Arc<AtomicBool>,
+        state: GroupStateCell,
+        policies: Arc<Mutex<GroupPolicies>>,
+        metrics: GroupMetrics,
+        accepted_types: AcceptedTypes,
+        affinity: Option<AffinityGroup>,
+        routing: Router,
+        batcher: Option<Batcher>,
+        launch: LaunchProgress,
     ) -> Self {
         ChildrenRef {
             id,
@@ -36,9 +326,24 @@ impl ChildrenRef {
             path,
             children,
             dispatchers,
+            started,
+            state,
+            policies,
+            metrics,
+            accepted_types,
+            affinity,
+            routing,
+            batcher,
+            launch,
+            config: Arc::default(),
         }
     }
 
+    pub(crate) fn with_config(mut self, config: Arc<Mutex<GroupConfig>>) -> Self {
+        self.config = config;
+        self
+    }
+
     /// Returns the identifier of the children group this `ChildrenRef`
     /// is referencing.
     ///
@@ -69,6 +374,242 @@ impl ChildrenRef {
         &self.id
     }
 
+    /// Returns the current state of the children group this
+    /// `ChildrenRef` is referencing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// use bastion::children_ref::GroupState;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    /// if children_ref.state() == GroupState::Running {
+    ///     // ...
+    /// }
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn state(&self) -> GroupState {
+        self.state.get()
+    }
+
+    /// Returns the policies applying to the children group this
+    /// `ChildrenRef` is referencing, including those it inherits
+    /// from its supervisors.
+    ///
+    /// The inherited policies are only resolved once the group
+    /// was deployed by its supervisor, before which the group's
+    /// own and the default ones are returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    /// let policies = children_ref.policies();
+    /// println!("Pre-start watermark: {}", policies.pre_start_watermark());
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn policies(&self) -> GroupPolicies {
+        self.policies.lock().unwrap().clone()
+    }
+
+    /// Returns the settings of the children group this
+    /// `ChildrenRef` is referencing, as they were resolved once
+    /// the closure configuring it returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_redundancy(4).with_mailbox_capacity(16)
+    /// })
+    /// .expect("Couldn't create the children group.");
+    ///
+    /// let config = children_ref.config();
+    /// assert_eq!(config.redundancy(), 4);
+    /// assert_eq!(config.mailbox_capacity(), Some(16));
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn config(&self) -> GroupConfig {
+        self.config.lock().unwrap().clone()
+    }
+
+    /// Returns a stream yielding the current state of the children
+    /// group this `ChildrenRef` is referencing, followed by each
+    /// of its state transitions.
+    ///
+    /// The stream ends once the group reached either
+    /// [`GroupState::Stopped`], [`GroupState::Killed`] or
+    /// [`GroupState::Faulted`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// use bastion::children_ref::GroupState;
+    /// use futures::StreamExt;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    /// let mut changes = children_ref.state_changes();
+    /// children_ref.stop().expect("Couldn't stop the group.");
+    ///
+    /// run!(async {
+    ///     while let Some(state) = changes.next().await {
+    ///         println!("{:?}", state);
+    ///     }
+    /// });
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`GroupState::Stopped`]: enum.GroupState.html#variant.Stopped
+    /// [`GroupState::Killed`]: enum.GroupState.html#variant.Killed
+    /// [`GroupState::Faulted`]: enum.GroupState.html#variant.Faulted
+    pub fn state_changes(&self) -> impl Stream<Item = GroupState> + Unpin {
+        self.state.subscribe()
+    }
+
+    /// Returns a snapshot of the metrics registered by the
+    /// elements of the children group this `ChildrenRef` is
+    /// referencing (using [`BastionContext::metrics`]), along with
+    /// their run-time statistics (see [`ChildRef::stats`]).
+    ///
+    /// Retrieving the snapshot doesn't change the metrics. The
+    /// metrics of the elements that terminated are included (and
+    /// flagged as terminal) until they are reset using
+    /// [`reset_stats`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    /// let stats: GroupStats = children_ref.stats();
+    /// let jobs_ok: u64 = stats.counter("jobs_ok");
+    /// let backlog: usize = stats.mailbox_len();
+    /// // Or to expose them to Prometheus...
+    /// let exposed: String = stats.to_prometheus();
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`BastionContext::metrics`]: ../context/struct.BastionContext.html#method.metrics
+    /// [`ChildRef::stats`]: ../child_ref/struct.ChildRef.html#method.stats
+    /// [`reset_stats`]: #method.reset_stats
+    pub fn stats(&self) -> GroupStats {
+        let children = self.children.iter().map(ChildRef::stats).collect();
+        self.metrics.stats(&self.id, children)
+    }
+
+    /// Resets the metrics registered by the elements of the
+    /// children group this `ChildrenRef` is referencing: the
+    /// metrics of the elements that terminated are dropped while
+    /// the counters and histograms of the others are set back to
+    /// zero (their gauges are kept as is).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    /// // Exports the metrics since the previous export...
+    /// let exposed = children_ref.stats().to_prometheus();
+    /// children_ref.reset_stats();
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn reset_stats(&self) {
+        self.metrics.reset();
+    }
+
+    /// Returns the placement statistics of the affinity group the
+    /// elements of the children group this `ChildrenRef` is
+    /// referencing are spawned with, or `None` if it doesn't
+    /// have one (see [`Children::with_affinity_group`]).
+    ///
+    /// The statistics are shared by every group with the same
+    /// affinity group label.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_affinity_group("pipeline")
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// let stats: AffinityStats = children_ref.affinity_stats().unwrap();
+    /// let stolen: usize = stats.moved();
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::with_affinity_group`]: ../children/struct.Children.html#method.with_affinity_group
+    pub fn affinity_stats(&self) -> Option<AffinityStats> {
+        self.affinity.as_ref().map(AffinityGroup::stats)
+    }
+
+    pub(crate) fn metrics(&self) -> &GroupMetrics {
+        &self.metrics
+    }
+
+    #[cfg(feature = "introspection")]
+    pub(crate) fn launch(&self) -> &LaunchProgress {
+        &self.launch
+    }
+
     /// Returns a list of dispatcher names that can be used for
     /// comminucation with other actors in the same group(s).
     ///
@@ -97,6 +638,10 @@ impl ChildrenRef {
     /// Returns a list of [`ChildRef`] referencing the elements
     /// of the children group this `ChildrenRef` is referencing.
     ///
+    /// The `ChildRef`s keep on reaching the elements once they are
+    /// restarted by their supervisor, their restarted incarnation
+    /// receiving the messages sent to them from then on.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -127,8 +672,12 @@ impl ChildrenRef {
     /// elements of the group and then send the message to all
     /// of them.
     ///
-    /// This method returns `()` if it succeeded, or `Err(msg)`
-    /// otherwise.
+    /// This method returns `()` if it succeeded, or a
+    /// [`SendError`](../errors/struct.SendError.html) carrying the message otherwise.
+    /// If the group only accepts some message types (see
+    /// [`Children::with_accepted_types`]), a message of another type
+    /// is rejected with [`SendErrorKind::TypeNotAccepted`] instead of
+    /// being sent.
     ///
     /// # Arguments
     ///
@@ -171,7 +720,13 @@ impl ChildrenRef {
     /// ```
     ///
     /// [`elems`]: #method.elems
-    pub fn broadcast<M: Message>(&self, msg: M) -> Result<(), M> {
+    /// [`Children::with_accepted_types`]: ../children/struct.Children.html#method.with_accepted_types
+    /// [`SendErrorKind::TypeNotAccepted`]: ../errors/enum.SendErrorKind.html#variant.TypeNotAccepted
+    pub fn broadcast<M: Message>(&self, msg: M) -> Result<(), SendError<M>> {
+        if let Err(kind) = self.accepted_types.check::<M>() {
+            return Err(SendError::new(kind, msg));
+        }
+
         debug!(
             "ChildrenRef({}): Broadcasting message: {:?}",
             self.id(),
@@ -180,15 +735,27 @@ impl ChildrenRef {
         let msg = BastionMessage::broadcast(msg);
         let env = Envelope::from_dead_letters(msg);
         // FIXME: panics?
-        self.send(env).map_err(|err| err.into_msg().unwrap())
+        self.send(env)
+            .map_err(|env| SendError::stopped(env.into_msg().unwrap()))
     }
 
-    /// Sends a message to the children group this `ChildrenRef`
-    /// is referencing to tell it to stop all of its running
-    /// elements.
+    /// "Asks" a clone of a message to every element of the children
+    /// group this `ChildrenRef` is referencing (as returned by
+    /// [`elems`]) and returns the [`Answer`]s to them, in the same
+    /// order as the elements.
     ///
-    /// This method returns `()` if it succeeded, or `Err(())`
-    /// otherwise.
+    /// The answers of the elements that stopped since this
+    /// `ChildrenRef` was created resolve to
+    /// [`AnswerError::Unanswered`] instead of making this method
+    /// fail.
+    ///
+    /// This method returns the answers if it succeeded, or a
+    /// [`SendError`](../errors/struct.SendError.html) carrying the
+    /// message if the group doesn't have any element.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
     ///
     /// # Example
     ///
@@ -198,54 +765,1378 @@ impl ChildrenRef {
     /// # fn main() {
     ///     # Bastion::init();
     ///     #
-    ///     # let children_ref = Bastion::children(|children| children).unwrap();
-    /// children_ref.stop().expect("Couldn't send the message.");
+    /// let children_ref = Bastion::children(|children| {
+    ///     children
+    ///         .with_redundancy(4)
+    ///         .with_exec(|ctx: BastionContext| {
+    ///             async move {
+    ///                 loop {
+    ///                     msg! { ctx.recv().await?,
+    ///                         n: u64 =!> {
+    ///                             answer!(ctx, n * 2).ok();
+    ///                         };
+    ///                         _: _ => ();
+    ///                     }
+    ///                 }
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
     ///     #
     ///     # Bastion::start();
+    ///
+    /// let answers = children_ref.ask_everyone(21u64).expect("Couldn't send the message.");
+    /// let sum = run!(async {
+    ///     let mut sum = 0;
+    ///     for answer in answers {
+    ///         msg! { answer.await.expect("Couldn't receive the answer."),
+    ///             n: u64 => sum += n;
+    ///             _: _ => ();
+    ///         }
+    ///     }
+    ///
+    ///     sum
+    /// });
+    /// assert_eq!(sum, 4 * 42);
+    ///     #
     ///     # Bastion::stop();
     ///     # Bastion::block_until_stopped();
     /// # }
     /// ```
-    pub fn stop(&self) -> Result<(), ()> {
-        debug!("ChildrenRef({}): Stopping.", self.id());
-        let msg = BastionMessage::stop();
-        let env = Envelope::from_dead_letters(msg);
-        self.send(env).map_err(|_| ())
+    ///
+    /// [`elems`]: #method.elems
+    /// [`Answer`]: ../message/struct.Answer.html
+    /// [`AnswerError::Unanswered`]: ../errors/enum.AnswerError.html#variant.Unanswered
+    pub fn ask_everyone<M: Message + Clone>(&self, msg: M) -> Result<Vec<Answer>, SendError<M>> {
+        debug!(
+            "ChildrenRef({}): Asking message to every element: {:?}",
+            self.id(),
+            msg
+        );
+        if let Err(kind) = self.accepted_types.check::<M>() {
+            return Err(SendError::new(kind, msg));
+        }
+
+        if self.children.is_empty() {
+            return Err(SendError::new(SendErrorKind::NoRecipient, msg));
+        }
+
+        let answers = self
+            .children
+            .iter()
+            .map(|child| match child.ask_unchecked(msg.clone()) {
+                Ok(answer) => answer,
+                Err(err) => {
+                    trace!(
+                        "ChildrenRef({}): Child({}) stopped before being asked.",
+                        self.id(),
+                        child.id()
+                    );
+                    // The message is dropped without being answered.
+                    let (_, answer) = BastionMessage::ask(err.into_inner_msg());
+                    answer
+                }
+            })
+            .collect();
+
+        Ok(answers)
     }
 
-    /// Sends a message to the children group this `ChildrenRef`
-    /// is referencing to tell it to kill all of its running
-    /// elements.
+    /// Sends a message to every element of the children group
+    /// this `ChildrenRef` is referencing, like [`ask_everyone`]
+    /// does, and returns a [`Future`] reducing their answers into
+    /// a single result, starting with `init`.
     ///
-    /// This method returns `()` if it succeeded, or `Err(())`
-    /// otherwise.
+    /// `reduce` is called by the task polling the future as the
+    /// answers are received. The future resolves to the result
+    /// once every element answered, or to a [`ReduceError`]
+    /// carrying the partial result once the others either
+    /// dropped the message without answering it or didn't
+    /// answer before `timeout` expired (their late answers being
+    /// discarded).
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    /// * `init` - The initial value of the result.
+    /// * `reduce` - The closure reducing an answer into the
+    ///   result.
+    /// * `timeout` - How long to wait for all the answers,
+    ///   starting now.
     ///
     /// # Example
     ///
     /// ```rust
     /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
     /// #
     /// # fn main() {
     ///     # Bastion::init();
     ///     #
-    ///     # let children_ref = Bastion::children(|children| children).unwrap();
-    /// children_ref.kill().expect("Couldn't send the message.");
+    /// let children_ref = Bastion::children(|children| {
+    ///     children
+    ///         .with_redundancy(4)
+    ///         .with_exec(|ctx: BastionContext| {
+    ///             async move {
+    ///                 loop {
+    ///                     msg! { ctx.recv().await?,
+    ///                         n: u64 =!> {
+    ///                             answer!(ctx, n * 2).ok();
+    ///                         };
+    ///                         _: _ => ();
+    ///                     }
+    ///                 }
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
     ///     #
     ///     # Bastion::start();
+    ///
+    /// let sum = children_ref.ask_reduce(
+    ///     21u64,
+    ///     0,
+    ///     |sum, answer: Msg| sum + answer.downcast::<u64>().unwrap_or_default(),
+    ///     Duration::from_secs(1),
+    /// );
+    /// assert_eq!(run!(sum), Ok(4 * 42));
+    ///     #
     ///     # Bastion::stop();
     ///     # Bastion::block_until_stopped();
     /// # }
     /// ```
-    pub fn kill(&self) -> Result<(), ()> {
-        debug!("ChildrenRef({}): Killing.", self.id());
-        let msg = BastionMessage::kill();
-        let env = Envelope::from_dead_letters(msg);
-        self.send(env).map_err(|_| ())
-    }
+    ///
+    /// [`ask_everyone`]: #method.ask_everyone
+    /// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+    /// [`ReduceError`]: struct.ReduceError.html
+    pub fn ask_reduce<M, R, F>(
+        &self,
+        msg: M,
+        init: R,
+        reduce: F,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<R, ReduceError<R>>>
+    where
+        M: Message + Clone,
+        F: Fn(R, Msg) -> R + Send + 'static,
+    {
+        let asked = self.ask_everyone(msg).map_err(|err| err.kind());
+        let id = self.id().clone();
 
-    pub(crate) fn send(&self, env: Envelope) -> Result<(), Envelope> {
-        trace!("ChildrenRef({}): Sending message: {:?}", self.id(), env);
-        self.sender
+        async move {
+            let mut err = ReduceError {
+                partial: init,
+                answered: 0,
+                unanswered: 0,
+                timed_out: 0,
+                send: None,
+            };
+
+            let mut answers = match asked {
+                Ok(answers) => answers.into_iter().collect::<FuturesUnordered<_>>(),
+                Err(kind) => {
+                    err.send = Some(kind);
+                    return Err(err);
+                }
+            };
+
+            let mut delay = Delay::new(timeout);
+            while !answers.is_empty() {
+                match future::select(answers.next(), &mut delay).await {
+                    Either::Left((Some(Ok(answer)), _)) => {
+                        let (msg, _) = answer.extract();
+                        err.partial = reduce(err.partial, msg);
+                        err.answered += 1;
+                    }
+                    Either::Left((Some(Err(_)), _)) => err.unanswered += 1,
+                    Either::Left((None, _)) => break,
+                    Either::Right(_) => {
+                        debug!("ChildrenRef({}): Reducing timed out.", id);
+                        break;
+                    }
+                }
+            }
+
+            // Dropping the answers still pending discards them.
+            err.timed_out = answers.len();
+            if err.unanswered == 0 && err.timed_out == 0 {
+                Ok(err.partial)
+            } else {
+                Err(err)
+            }
+        }
+    }
+
+    /// Broadcasts a message to the children group this
+    /// `ChildrenRef` is referencing, like [`broadcast`] does, and
+    /// returns a [`Future`] resolving once every element (as
+    /// returned by [`elems`]) acknowledged it using
+    /// [`BastionContext::ack`], or once `timeout` expired.
+    ///
+    /// The message is sent with an [`AckToken`] (see
+    /// [`SignedMessage::ack_token`]). It is sent again to the
+    /// elements restarted before acknowledging it, as long as the
+    /// future didn't resolve, their restarted incarnation's
+    /// acknowledgement counting instead (note that a restarted
+    /// element might thus receive it twice, if its previous
+    /// incarnation faulted before retrieving it).
+    ///
+    /// The future resolves to an [`AckReport`] listing the
+    /// elements which acknowledged the message, the ones which
+    /// are still pending, and the ones which stopped without
+    /// acknowledging it.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to broadcast.
+    /// * `timeout` - How long to wait for the acknowledgements,
+    ///   starting now.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children
+    ///         .with_redundancy(4)
+    ///         .with_exec(|ctx: BastionContext| {
+    ///             async move {
+    ///                 loop {
+    ///                     let msg = ctx.recv().await?;
+    ///                     let token = msg.ack_token();
+    ///                     msg! { msg,
+    ///                         ref level: &'static str => {
+    ///                             // Apply the new log level...
+    ///                             if let Some(token) = token {
+    ///                                 ctx.ack(token);
+    ///                             }
+    ///                         };
+    ///                         _: _ => ();
+    ///                     }
+    ///                 }
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///
+    /// let report = run!(children_ref.broadcast_acked("debug", Duration::from_secs(1)));
+    /// assert!(report.is_complete());
+    /// assert_eq!(report.acked().len(), 4);
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`broadcast`]: #method.broadcast
+    /// [`elems`]: #method.elems
+    /// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+    /// [`BastionContext::ack`]: ../context/struct.BastionContext.html#method.ack
+    /// [`AckToken`]: ../ack/struct.AckToken.html
+    /// [`SignedMessage::ack_token`]: ../envelope/struct.SignedMessage.html#method.ack_token
+    /// [`AckReport`]: ../ack/struct.AckReport.html
+    pub fn broadcast_acked<M: Message + Clone>(
+        &self,
+        msg: M,
+        timeout: Duration,
+    ) -> impl Future<Output = AckReport> {
+        debug!(
+            "ChildrenRef({}): Broadcasting message to acknowledge: {:?}",
+            self.id(),
+            msg
+        );
+        let tracker = AckTracker::new();
+        let elems = self.children.clone();
+        let sent = self.accepted_types.check::<M>().and_then(|()| {
+            let msg = BastionMessage::broadcast_acked(msg, tracker.token().clone());
+            let env = Envelope::from_dead_letters(msg);
+            self.send(env).map_err(|_| SendErrorKind::Stopped)
+        });
+
+        async move {
+            match sent {
+                Ok(()) => tracker.wait(elems, timeout).await,
+                Err(kind) => AckReport::new(&elems, tracker.token(), Some(kind)),
+            }
+        }
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing, like [`broadcast`], but also tells whether
+    /// the message is kept until the group is started.
+    ///
+    /// This method returns the [`SendOutcome`] of the message if
+    /// it succeeded, or a [`SendError`](../errors/struct.SendError.html)
+    /// carrying the message otherwise.
+    ///
+    /// Note that a message sent while the group is being started
+    /// might be reported as [`SendOutcome::Buffered`] even if it
+    /// is delivered right away.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// use bastion::children_ref::SendOutcome;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    /// let outcome = children_ref.tell_outcome("A message containing data.")
+    ///     .expect("Couldn't send the message.");
+    ///
+    /// // The system isn't started yet...
+    /// assert_eq!(outcome, SendOutcome::Buffered);
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`broadcast`]: #method.broadcast
+    /// [`SendOutcome`]: enum.SendOutcome.html
+    /// [`SendOutcome::Buffered`]: enum.SendOutcome.html#variant.Buffered
+    pub fn tell_outcome<M: Message>(&self, msg: M) -> Result<SendOutcome, SendError<M>> {
+        // Checked before sending the message, so that it can't be
+        // reported as delivered while it was buffered.
+        let outcome = if self.started.load(Ordering::SeqCst) {
+            SendOutcome::Delivered
+        } else {
+            SendOutcome::Buffered
+        };
+
+        self.broadcast(msg)?;
+        Ok(outcome)
+    }
+
+    /// Sends a message to one of the warm elements of the children
+    /// group this `ChildrenRef` is referencing, chosen in a
+    /// round-robin fashion. The elements of a group without a
+    /// warmup are warm as soon as they are launched, while the
+    /// others are once their warmup passed its gate (see
+    /// [`Children::with_warmup`]).
+    ///
+    /// If none of the group's elements is warm, the message is
+    /// queued by the group until one of them is. This queue is
+    /// bounded like the elements' mailboxes, and the group's
+    /// [`OverflowPolicy`] applies to it once it is full (regardless
+    /// of the method it was sent with, [`OverflowPolicy::Block`]
+    /// fails like [`OverflowPolicy::Fail`]).
+    ///
+    /// This method returns `()` if it succeeded, or a
+    /// [`SendError`](../errors/struct.SendError.html) carrying the
+    /// message otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children.with_redundancy(4)).unwrap();
+    /// // Only one of the group's elements receives the message...
+    /// children_ref.tell_one("A message containing data.")
+    ///     .expect("Couldn't send the message.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::with_warmup`]: ../children/struct.Children.html#method.with_warmup
+    /// [`OverflowPolicy`]: ../mailbox/enum.OverflowPolicy.html
+    /// [`OverflowPolicy::Block`]: ../mailbox/enum.OverflowPolicy.html#variant.Block
+    /// [`OverflowPolicy::Fail`]: ../mailbox/enum.OverflowPolicy.html#variant.Fail
+    pub fn tell_one<M: Message>(&self, msg: M) -> Result<(), SendError<M>> {
+        if let Err(kind) = self.accepted_types.check::<M>() {
+            return Err(SendError::new(kind, msg));
+        }
+
+        let msg = match &self.batcher {
+            Some(batcher) => match batcher.push(msg) {
+                Ok(()) => return Ok(()),
+                // The message isn't of the batched type.
+                Err(msg) => msg,
+            },
+            None => msg,
+        };
+
+        debug!("ChildrenRef({}): Routing message: {:?}", self.id(), msg);
+        let key = self.routing.key(&msg);
+        let mut msg = Some(msg);
+        let routed = self.routing.route(key, || {
+            let msg = BastionMessage::tell(msg.take().unwrap());
+            Envelope::from_dead_letters(msg)
+        });
+
+        match routed {
+            Ok(Some(child)) => child.tell_anonymously(msg.take().unwrap()),
+            // The message was queued (or dropped).
+            Ok(None) => Ok(()),
+            Err(kind) => Err(SendError::new(kind, msg.take().unwrap())),
+        }
+    }
+
+    /// Sends a message to one of the warm elements of the children
+    /// group this `ChildrenRef` is referencing, like [`tell_one`]
+    /// does, allowing it to answer.
+    ///
+    /// This method returns the [`Answer`] of the message if it
+    /// succeeded, or a [`SendError`](../errors/struct.SendError.html)
+    /// carrying the message otherwise. If the message was dropped
+    /// because the group's queue was full, the answer resolves
+    /// without being answered.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_redundancy(4).with_exec(|ctx: BastionContext| async move {
+    ///         loop {
+    ///             msg! { ctx.recv().await?,
+    ///                 n: u64 =!> {
+    ///                     answer!(ctx, n * 2).unwrap();
+    ///                 };
+    ///                 _: _ => ();
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// let answer = children_ref.ask_one(21u64).expect("Couldn't send the message.");
+    ///     #
+    ///     # Bastion::start();
+    /// let answer = run!(answer).expect("Couldn't receive the answer.");
+    /// msg! { answer,
+    ///     n: u64 => assert_eq!(n, 42);
+    ///     _: _ => panic!("Unexpected answer.");
+    /// }
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`tell_one`]: #method.tell_one
+    /// [`Answer`]: ../message/struct.Answer.html
+    pub fn ask_one<M: Message>(&self, msg: M) -> Result<Answer, SendError<M>> {
+        if let Err(kind) = self.accepted_types.check::<M>() {
+            return Err(SendError::new(kind, msg));
+        }
+
+        debug!("ChildrenRef({}): Routing question: {:?}", self.id(), msg);
+        let key = self.routing.key(&msg);
+        let mut msg = Some(msg);
+        let mut answer = None;
+        let routed = self.routing.route(key, || {
+            let (msg, queued) = BastionMessage::ask(msg.take().unwrap());
+            answer = Some(queued);
+            Envelope::from_dead_letters(msg)
+        });
+
+        match routed {
+            Ok(Some(child)) => child.ask_anonymously(msg.take().unwrap()),
+            Ok(None) => match answer {
+                Some(answer) => Ok(answer),
+                // The message is dropped without being answered.
+                None => Ok(BastionMessage::ask(msg.take().unwrap()).1),
+            },
+            Err(kind) => Err(SendError::new(kind, msg.take().unwrap())),
+        }
+    }
+
+    /// Sends a message to exactly one of the warm elements of the
+    /// children group this `ChildrenRef` is referencing, each of
+    /// them in turn (the elements that stopped being skipped)
+    /// whatever the group's routing mode. Contrary to [`tell_one`],
+    /// the messages aren't batched.
+    ///
+    /// The rotation is shared by every `ChildrenRef` of the group,
+    /// and with [`tell_one`] while the group routes its messages
+    /// using [`Routing::RoundRobin`]. Like with [`tell_one`], the
+    /// messages sent while no element is warm are queued until one
+    /// is.
+    ///
+    /// This method returns `()` if it succeeded, or a
+    /// [`SendError`](../errors/struct.SendError.html) carrying the
+    /// message otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children.with_redundancy(4)).unwrap();
+    /// // Each message is received by the next element of the group...
+    /// for _ in 0..4 {
+    ///     children_ref.tell_next("A message containing data.")
+    ///         .expect("Couldn't send the message.");
+    /// }
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`tell_one`]: #method.tell_one
+    /// [`Routing::RoundRobin`]: ../routing/enum.Routing.html#variant.RoundRobin
+    pub fn tell_next<M: Message>(&self, msg: M) -> Result<(), SendError<M>> {
+        if let Err(kind) = self.accepted_types.check::<M>() {
+            return Err(SendError::new(kind, msg));
+        }
+
+        debug!(
+            "ChildrenRef({}): Sending message in turn: {:?}",
+            self.id(),
+            msg
+        );
+        let mut msg = Some(msg);
+        let routed = self.routing.route_next(|| {
+            let msg = BastionMessage::tell(msg.take().unwrap());
+            Envelope::from_dead_letters(msg)
+        });
+
+        match routed {
+            Ok(Some(child)) => child.tell_anonymously(msg.take().unwrap()),
+            // The message was queued (or dropped).
+            Ok(None) => Ok(()),
+            Err(kind) => Err(SendError::new(kind, msg.take().unwrap())),
+        }
+    }
+
+    /// Returns the number of elements of the children group this
+    /// `ChildrenRef` is referencing that are warm, and that routed
+    /// messages are sent to (see [`tell_one`]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    /// let warm: usize = children_ref.warm_elems();
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`tell_one`]: #method.tell_one
+    pub fn warm_elems(&self) -> usize {
+        self.routing.warm()
+    }
+
+    /// Returns whether the children group this `ChildrenRef` is
+    /// referencing is ready, once the fraction of its elements set
+    /// using [`Children::with_ready_fraction`] (all of them by
+    /// default) is warm.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_warmup(
+    ///         |_: BastionContext| async {
+    ///             // Fill the caches...
+    ///         },
+    ///         WarmupGate::Duration(Duration::from_secs(1)),
+    ///     )
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// // The group wasn't started yet...
+    /// assert!(!children_ref.is_ready());
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::with_ready_fraction`]: ../children/struct.Children.html#method.with_ready_fraction
+    pub fn is_ready(&self) -> bool {
+        self.routing.is_ready()
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to tell it to stop all of its running
+    /// elements.
+    ///
+    /// The elements are stopped like with [`stop_with_timeout`],
+    /// getting five seconds to finish handling their messages
+    /// before the ones still running are killed.
+    ///
+    /// This method returns `()` if it succeeded, or a
+    /// [`ShutdownError`](../errors/enum.ShutdownError.html) otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    /// children_ref.stop().expect("Couldn't send the message.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`stop_with_timeout`]: #method.stop_with_timeout
+    pub fn stop(&self) -> Result<(), ShutdownError> {
+        debug!("ChildrenRef({}): Stopping.", self.id());
+        let msg = BastionMessage::stop();
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ShutdownError::AlreadyStopped)
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to tell it to stop all of its running
+    /// elements, like [`stop`], and returns a [`Future`] resolving
+    /// once it terminated.
+    ///
+    /// The future resolves to the state the group ended up in
+    /// (which is [`GroupState::Faulted`] if it faulted before
+    /// handling the message) once the group stopped handling
+    /// messages and all of its elements' futures completed, or to
+    /// a [`ShutdownError`] if it already stopped or faulted.
+    ///
+    /// Note that a group which wasn't started yet only handles
+    /// the message once it was.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// use bastion::children_ref::GroupState;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    /// let stopping = children_ref.stop_and_wait();
+    ///
+    /// let state = run!(stopping).expect("Couldn't stop the group.");
+    /// assert_eq!(state, GroupState::Stopped);
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`stop`]: #method.stop
+    /// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+    /// [`GroupState::Faulted`]: enum.GroupState.html#variant.Faulted
+    /// [`ShutdownError`]: ../errors/enum.ShutdownError.html
+    pub fn stop_and_wait(&self) -> impl Future<Output = Result<GroupState, ShutdownError>> {
+        debug!("ChildrenRef({}): Stopping.", self.id());
+        self.terminate_and_wait(BastionMessage::stop())
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to tell it to stop all of its running
+    /// elements once they are done handling their messages, and
+    /// to kill the ones still running once `timeout` expired,
+    /// and returns a [`Future`] resolving once it terminated.
+    ///
+    /// An element is done once its future waits for a message
+    /// (using [`BastionContext::recv`] or
+    /// [`BastionContext::recv_timeout`]) while there isn't any
+    /// left in its mailbox, or when its future completes.
+    ///
+    /// The future resolves to the state the group ended up in,
+    /// which is [`GroupState::Stopped`] if all of its elements
+    /// stopped in time or [`GroupState::Killed`] if some of them
+    /// had to be killed, like [`stop_and_wait`] does otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long to wait for the elements to stop
+    ///     before killing them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// use bastion::children_ref::GroupState;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             loop {
+    ///                 msg! { ctx.recv().await?,
+    ///                     _: _ => ();
+    ///                 }
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// let stopping = children_ref.stop_with_timeout(Duration::from_secs(5));
+    ///
+    /// let state = run!(stopping).expect("Couldn't stop the group.");
+    /// assert_eq!(state, GroupState::Stopped);
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+    /// [`BastionContext::recv`]: ../context/struct.BastionContext.html#method.recv
+    /// [`BastionContext::recv_timeout`]: ../context/struct.BastionContext.html#method.recv_timeout
+    /// [`GroupState::Stopped`]: enum.GroupState.html#variant.Stopped
+    /// [`GroupState::Killed`]: enum.GroupState.html#variant.Killed
+    /// [`stop_and_wait`]: #method.stop_and_wait
+    pub fn stop_with_timeout(
+        &self,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<GroupState, ShutdownError>> {
+        debug!("ChildrenRef({}): Stopping within {:?}.", self.id(), timeout);
+        self.terminate_and_wait(BastionMessage::stop_within(timeout))
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to tell it to quiesce its elements, and
+    /// returns a [`Future`] resolving once it terminated.
+    ///
+    /// Each element receives a [`ContextEvent::Quiesce`] (using
+    /// [`BastionContext::next_event`]), after which it is expected
+    /// to gracefully close the connections it holds and to have
+    /// its future complete. The elements still running once
+    /// `deadline` expired are then stopped like
+    /// [`stop_with_timeout`] would (using the same timeout).
+    ///
+    /// The group is [`GroupState::Quiescing`] until then, and the
+    /// future resolves to the state it ended up in, which is
+    /// [`GroupState::Stopped`] unless some elements had to be
+    /// killed.
+    ///
+    /// # Arguments
+    ///
+    /// * `deadline` - How long to wait for the elements to stop
+    ///     on their own before stopping them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// use bastion::children_ref::GroupState;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // Serve the subscriptions until asked to stop...
+    ///             ctx.next_event().await;
+    ///             // ...and close them gracefully.
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// let quiescing = children_ref.quiesce(Duration::from_secs(5));
+    ///
+    /// let state = run!(quiescing).expect("Couldn't quiesce the group.");
+    /// assert_eq!(state, GroupState::Stopped);
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+    /// [`ContextEvent::Quiesce`]: ../context/enum.ContextEvent.html#variant.Quiesce
+    /// [`BastionContext::next_event`]: ../context/struct.BastionContext.html#method.next_event
+    /// [`stop_with_timeout`]: #method.stop_with_timeout
+    /// [`GroupState::Quiescing`]: enum.GroupState.html#variant.Quiescing
+    /// [`GroupState::Stopped`]: enum.GroupState.html#variant.Stopped
+    pub fn quiesce(
+        &self,
+        deadline: Duration,
+    ) -> impl Future<Output = Result<GroupState, ShutdownError>> {
+        debug!(
+            "ChildrenRef({}): Quiescing within {:?}.",
+            self.id(),
+            deadline
+        );
+        self.terminate_and_wait(BastionMessage::quiesce(deadline))
+    }
+
+    // Sends `msg` to the group and returns a future resolving to
+    // the state it ended up in.
+    fn terminate_and_wait(
+        &self,
+        msg: BastionMessage,
+    ) -> impl Future<Output = Result<GroupState, ShutdownError>> {
+        // Subscribing before sending the message makes sure that
+        // the transition to a terminal state isn't missed.
+        let mut changes = self.state.subscribe();
+        let stopped = if self.state.get().is_terminal() {
+            Err(ShutdownError::AlreadyStopped)
+        } else {
+            let env = Envelope::from_dead_letters(msg);
+            self.send(env).map_err(|_| ShutdownError::AlreadyStopped)
+        };
+
+        async move {
+            stopped?;
+
+            let mut state = GroupState::Dormant;
+            while let Some(next) = changes.next().await {
+                state = next;
+            }
+
+            Ok(state)
+        }
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to tell it to kill all of its running
+    /// elements.
+    ///
+    /// This method returns `()` if it succeeded, or a
+    /// [`ShutdownError`](../errors/enum.ShutdownError.html) otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    /// children_ref.kill().expect("Couldn't send the message.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn kill(&self) -> Result<(), ShutdownError> {
+        debug!("ChildrenRef({}): Killing.", self.id());
+        let msg = BastionMessage::kill();
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ShutdownError::AlreadyStopped)
+    }
+
+    #[cfg(feature = "testing")]
+    #[doc(hidden)]
+    /// Asks the supervisor of the children group this `ChildrenRef`
+    /// is referencing to cancel the group's task, as the executor
+    /// would, without the group stopping its elements.
+    ///
+    /// The group's elements then stop on their own (being killed
+    /// if they didn't within a second), and the supervisor cancels
+    /// the ones that are still running. This is only meant to test
+    /// how orphaned elements are handled.
+    pub fn cancel_task(&self) -> Result<(), ShutdownError> {
+        debug!("ChildrenRef({}): Cancelling the task.", self.id());
+        let msg = BastionMessage::cancel_task(self.id().clone());
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ShutdownError::AlreadyStopped)
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to tell it to kill and remove its element
+    /// identified by `id`, without restarting it nor considering
+    /// the group faulted.
+    ///
+    /// Nothing happens if the group doesn't have an element
+    /// identified by `id` (e.g. because it was already pruned).
+    ///
+    /// This method returns `()` if it succeeded, or a
+    /// [`ShutdownError`](../errors/enum.ShutdownError.html) otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the element to remove.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    /// let elem = &children_ref.elems()[0];
+    /// children_ref.prune_elem(elem.id()).expect("Couldn't send the message.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn prune_elem(&self, id: &BastionId) -> Result<(), ShutdownError> {
+        debug!("ChildrenRef({}): Pruning Child({}).", self.id(), id);
+        let msg = BastionMessage::prune(id.clone());
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ShutdownError::AlreadyStopped)
+    }
+
+    /// Moves the pending messages of the element identified by
+    /// `from` to the element identified by `to` (both being
+    /// elements of the children group this `ChildrenRef` is
+    /// referencing) and then stops the source element.
+    ///
+    /// The source element stops handling its messages while it
+    /// is migrated, the messages that it didn't retrieve yet are
+    /// sent to the target element in the order they were received
+    /// and the source element's mailbox gets closed. A message
+    /// sent concurrently to the source element is thus either
+    /// transferred to the target element or given back to its
+    /// sender (eg. as the `SendError` of [`ChildRef::tell_anonymously`]),
+    /// but never both. If the target element stops once the
+    /// source element's mailbox got closed, the messages still
+    /// arriving in it are sent to the dead letters instead, which
+    /// is counted by [`MigrationReport::dead_lettered`].
+    ///
+    /// This method returns a [`Future`] resolving to a
+    /// [`MigrationReport`] if it succeeded, or a [`MigrationError`]
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The identifier of the element to migrate.
+    /// * `to` - The identifier of the element that will receive the
+    ///   source element's pending messages.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use bastion::children_ref::MigrationReport;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| {
+    ///         # children.with_redundancy(2).with_exec(|ctx: BastionContext| {
+    ///             # async move {
+    ///                 # loop {
+    ///                     # ctx.recv().await?;
+    ///                 # }
+    ///             # }
+    ///         # })
+    ///     # }).unwrap();
+    ///     # Bastion::start();
+    /// let elems = children_ref.elems();
+    /// let migration = children_ref.migrate_elem(elems[0].id(), elems[1].id());
+    ///
+    /// let report: MigrationReport = run!(migration).expect("Couldn't migrate the element.");
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+    /// [`ChildRef::tell_anonymously`]: ../child_ref/struct.ChildRef.html#method.tell_anonymously
+    /// [`MigrationReport`]: struct.MigrationReport.html
+    /// [`MigrationReport::dead_lettered`]: struct.MigrationReport.html#method.dead_lettered
+    /// [`MigrationError`]: enum.MigrationError.html
+    pub fn migrate_elem(
+        &self,
+        from: &BastionId,
+        to: &BastionId,
+    ) -> impl Future<Output = Result<MigrationReport, MigrationError>> {
+        debug!(
+            "ChildrenRef({}): Migrating Child({}) to Child({}).",
+            self.id(),
+            from,
+            to
+        );
+        let migration = self.send_migration(from, to);
+
+        async move {
+            migration?
+                .await
+                .map_err(|_| MigrationError::SourceStopped)?
+        }
+    }
+
+    /// Deploys a new element in the children group this
+    /// `ChildrenRef` is referencing, running the future returned
+    /// by `init` instead of the one returned by the group's
+    /// closure (including when it is restarted).
+    ///
+    /// If the group wasn't started yet, the element will be
+    /// deployed once it is.
+    ///
+    /// This method returns a [`Future`] resolving to a [`ChildRef`]
+    /// referencing the new element, or to a
+    /// [`BuilderError`](../errors/enum.BuilderError.html) if the
+    /// group stopped before deploying it.
+    ///
+    /// Note that the new element is only part of the list returned
+    /// by [`elems`] for the `ChildrenRef`s created after it was
+    /// deployed (e.g. the ones returned by [`BastionContext::parent`]
+    /// for the elements launched after it).
+    ///
+    /// # Arguments
+    ///
+    /// * `init` - The closure taking a [`BastionContext`] and
+    ///   returning the future the element will run, like the one
+    ///   passed to [`Children::with_exec`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    /// let deployment = children_ref.deploy_elem(|ctx: BastionContext| {
+    ///     async move {
+    ///         // ...
+    ///         # Ok(())
+    ///     }
+    /// });
+    ///
+    /// let elem: ChildRef = run!(deployment).expect("Couldn't deploy the element.");
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+    /// [`ChildRef`]: ../child_ref/struct.ChildRef.html
+    /// [`elems`]: #method.elems
+    /// [`BastionContext`]: ../context/struct.BastionContext.html
+    /// [`BastionContext::parent`]: ../context/struct.BastionContext.html#method.parent
+    /// [`Children::with_exec`]: ../children/struct.Children.html#method.with_exec
+    pub fn deploy_elem<I, F>(&self, init: I) -> impl Future<Output = Result<ChildRef, BuilderError>>
+    where
+        I: Fn(BastionContext) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        debug!("ChildrenRef({}): Deploying a new element.", self.id());
+        let (sender, recver) = oneshot::channel();
+        let msg = BastionMessage::deploy_child(Init::new(init), sender);
+        let env = Envelope::from_dead_letters(msg);
+        let sent = self
+            .send(env)
+            .map(|_| recver)
+            .map_err(|_| BuilderError::ParentStopped);
+
+        async move { sent?.await.map_err(|_| BuilderError::ParentStopped) }
+    }
+
+    /// Changes how the children group this `ChildrenRef` is
+    /// referencing routes the messages sent using [`tell_one`] or
+    /// [`ask_one`] to its warm elements (see
+    /// [`Children::with_routing`]).
+    ///
+    /// The routing is changed by the group itself, for the
+    /// messages routed afterwards using any of its `ChildrenRef`s
+    /// (the messages queued while none of its elements was warm
+    /// are still sent to the first one warming up).
+    ///
+    /// This method returns a [`Future`] resolving once the routing
+    /// was changed, or to a [`RoutingError`] if the messages are
+    /// routed by key (using [`Routing::HashBy`]) while the group
+    /// wasn't built with a key function (see
+    /// [`Children::with_routing_key`]), if they are pinned (using
+    /// [`Routing::Pinned`]) to an element that isn't part of the
+    /// group, or if the group stopped.
+    ///
+    /// # Arguments
+    ///
+    /// * `routing` - How the routed messages should be routed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| {
+    ///     #     children.with_redundancy(4).with_exec(|ctx: BastionContext| async move {
+    ///     #         loop {
+    ///     #             ctx.recv().await?;
+    ///     #         }
+    ///     #     })
+    ///     # }).unwrap();
+    /// // Every routed message is sent to the same element...
+    /// let healthy = children_ref.elems()[0].id().clone();
+    /// run!(children_ref.set_routing(Routing::Pinned(healthy.clone())))
+    ///     .expect("Couldn't change the routing.");
+    /// assert_eq!(children_ref.routing(), Routing::Pinned(healthy));
+    ///
+    /// // ...or to each element in turn again.
+    /// run!(children_ref.set_routing(Routing::RoundRobin))
+    ///     .expect("Couldn't change the routing.");
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`tell_one`]: #method.tell_one
+    /// [`ask_one`]: #method.ask_one
+    /// [`Children::with_routing`]: ../children/struct.Children.html#method.with_routing
+    /// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+    /// [`RoutingError`]: ../routing/enum.RoutingError.html
+    /// [`Routing::HashBy`]: ../routing/enum.Routing.html#variant.HashBy
+    /// [`Children::with_routing_key`]: ../children/struct.Children.html#method.with_routing_key
+    /// [`Routing::Pinned`]: ../routing/enum.Routing.html#variant.Pinned
+    pub fn set_routing(&self, routing: Routing) -> impl Future<Output = Result<(), RoutingError>> {
+        debug!("ChildrenRef({}): Setting routing: {:?}", self.id(), routing);
+        let (sender, recver) = oneshot::channel();
+        let msg = BastionMessage::set_routing(routing, sender);
+        let env = Envelope::from_dead_letters(msg);
+        let sent = self
+            .send(env)
+            .map(|_| recver)
+            .map_err(|_| RoutingError::Stopped);
+
+        async move { sent?.await.map_err(|_| RoutingError::Stopped)? }
+    }
+
+    /// Sends to the children group this `ChildrenRef` is
+    /// referencing the strategy that its supervisor should start
+    /// using to restart its elements when one of them faults,
+    /// instead of the supervisor's own strategy (like
+    /// [`Children::with_supervision_strategy`] does).
+    ///
+    /// This method returns `()` if it succeeded, or a [`SendError`]
+    /// carrying the strategy otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `strategy` - The strategy to restart the group's elements
+    ///     with.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children.with_redundancy(4)).unwrap();
+    /// // Only the element that faulted is restarted from now on.
+    /// children_ref
+    ///     .strategy(SupervisionStrategy::OneForOne)
+    ///     .expect("Couldn't set the strategy.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::with_supervision_strategy`]: ../children/struct.Children.html#method.with_supervision_strategy
+    /// [`SendError`]: ../errors/struct.SendError.html
+    pub fn strategy(
+        &self,
+        strategy: SupervisionStrategy,
+    ) -> Result<(), SendError<SupervisionStrategy>> {
+        debug!(
+            "ChildrenRef({}): Setting strategy: {:?}",
+            self.id(),
+            strategy
+        );
+        let msg = BastionMessage::supervise_with(strategy.clone());
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| SendError::stopped(strategy))
+    }
+
+    /// Returns how the children group this `ChildrenRef` is
+    /// referencing currently routes the messages sent using
+    /// [`tell_one`] or [`ask_one`] (see [`set_routing`]).
+    ///
+    /// [`tell_one`]: #method.tell_one
+    /// [`ask_one`]: #method.ask_one
+    /// [`set_routing`]: #method.set_routing
+    pub fn routing(&self) -> Routing {
+        self.routing.mode()
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to tell it to launch or stop elements until
+    /// it has `redundancy` of them, without restarting it.
+    ///
+    /// The missing elements run the future returned by the
+    /// group's closure (see [`Children::with_exec`]) and the
+    /// surplus ones are stopped newest first, like
+    /// [`prune_elem`] does: the elements with the highest
+    /// indexes (see [`BastionContext::elem_index`]) are stopped
+    /// first, and the new elements take the lowest indexes
+    /// unused. The group is then launched with `redundancy`
+    /// elements when it is restarted.
+    ///
+    /// Scaling a group to `0` elements pauses it: it keeps
+    /// running, the messages sent using [`tell_one`] are queued
+    /// until it is scaled up again and the other ones aren't
+    /// received by any element.
+    ///
+    /// This method returns a [`Future`] resolving to a
+    /// `ChildrenRef` whose [`elems`] are the group's elements once
+    /// it was scaled (which is also the one returned by
+    /// [`Bastion::children_of`] afterwards), or to a
+    /// [`BuilderError`](../errors/enum.BuilderError.html) if the
+    /// group stopped before scaling.
+    ///
+    /// # Arguments
+    ///
+    /// * `redundancy` - The number of elements the group should
+    ///     have.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    /// let scaling = children_ref.scale(4);
+    ///
+    /// let children_ref = run!(scaling).expect("Couldn't scale the group.");
+    /// assert_eq!(children_ref.elems().len(), 4);
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::with_exec`]: ../children/struct.Children.html#method.with_exec
+    /// [`prune_elem`]: #method.prune_elem
+    /// [`BastionContext::elem_index`]: ../context/struct.BastionContext.html#method.elem_index
+    /// [`tell_one`]: #method.tell_one
+    /// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+    /// [`elems`]: #method.elems
+    /// [`Bastion::children_of`]: ../struct.Bastion.html#method.children_of
+    pub fn scale(
+        &self,
+        redundancy: usize,
+    ) -> impl Future<Output = Result<ChildrenRef, BuilderError>> {
+        debug!(
+            "ChildrenRef({}): Scaling to {} elements.",
+            self.id(),
+            redundancy
+        );
+        let (sender, recver) = oneshot::channel();
+        let msg = BastionMessage::scale(redundancy, sender);
+        let env = Envelope::from_dead_letters(msg);
+        let sent = self
+            .send(env)
+            .map(|_| recver)
+            .map_err(|_| BuilderError::ParentStopped);
+
+        async move { sent?.await.map_err(|_| BuilderError::ParentStopped) }
+    }
+
+    fn send_migration(
+        &self,
+        from: &BastionId,
+        to: &BastionId,
+    ) -> Result<Receiver<Result<MigrationReport, MigrationError>>, MigrationError> {
+        if from == to {
+            return Err(MigrationError::SameElement);
+        }
+
+        let source = self
+            .elem(from)
+            .ok_or_else(|| MigrationError::UnknownElement(from.clone()))?;
+        let target = self
+            .elem(to)
+            .ok_or_else(|| MigrationError::UnknownElement(to.clone()))?;
+
+        let (sender, recver) = oneshot::channel();
+        let msg = BastionMessage::migrate_child(target.clone(), sender);
+        let env = Envelope::from_dead_letters(msg);
+        source
+            .send(env)
+            .map_err(|_| MigrationError::SourceStopped)?;
+
+        Ok(recver)
+    }
+
+    fn elem(&self, id: &BastionId) -> Option<&ChildRef> {
+        self.children.iter().find(|child| child.id() == id)
+    }
+
+    // Returns the warm element a message (`msg`) rejected by the
+    // element with this id should be rerouted to, if any.
+    pub(crate) fn reroute(&self, from: &BastionId, msg: &dyn Any) -> Option<ChildRef> {
+        self.routing.reroute(from, msg)
+    }
+
+    pub(crate) fn send(&self, env: Envelope) -> Result<(), Envelope> {
+        trace!("ChildrenRef({}): Sending message: {:?}", self.id(), env);
+        self.sender
             .unbounded_send(env)
             .map_err(|err| err.into_inner())
     }
@@ -267,3 +2158,196 @@ impl PartialEq for ChildrenRef {
 }
 
 impl Eq for ChildrenRef {}
+
+impl GroupPolicies {
+    pub(crate) fn new(restart_strategy: RestartStrategy, pre_start_watermark: usize) -> Self {
+        GroupPolicies {
+            restart_strategy,
+            #[cfg(feature = "supervision")]
+            restart_window: None,
+            #[cfg(feature = "supervision")]
+            restart_backoff: None,
+            pre_start_watermark,
+            mailbox_capacity: None,
+            overflow_policy: OverflowPolicy::default(),
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT,
+            requeue_on_restart: true,
+            preset: None,
+        }
+    }
+
+    pub(crate) fn with_mailbox(
+        mut self,
+        mailbox_capacity: Option<usize>,
+        overflow_policy: OverflowPolicy,
+    ) -> Self {
+        self.mailbox_capacity = mailbox_capacity;
+        self.overflow_policy = overflow_policy;
+        self
+    }
+
+    pub(crate) fn with_drain(mut self, drain_timeout: Duration, requeue_on_restart: bool) -> Self {
+        self.drain_timeout = drain_timeout;
+        self.requeue_on_restart = requeue_on_restart;
+        self
+    }
+
+    #[cfg(feature = "supervision")]
+    pub(crate) fn with_restart_window(mut self, restart_window: Option<(usize, Duration)>) -> Self {
+        self.restart_window = restart_window;
+        self
+    }
+
+    #[cfg(feature = "supervision")]
+    pub(crate) fn with_restart_backoff(mut self, restart_backoff: Option<Backoff>) -> Self {
+        self.restart_backoff = restart_backoff;
+        self
+    }
+
+    pub(crate) fn with_preset(mut self, preset: Option<&'static str>) -> Self {
+        self.preset = preset;
+        self
+    }
+
+    /// Returns the restart strategy used by the group's supervisor
+    /// to restart its elements (see
+    /// [`Supervisor::with_restart_strategy`]).
+    ///
+    /// [`Supervisor::with_restart_strategy`]: ../supervisor/struct.Supervisor.html#method.with_restart_strategy
+    pub fn restart_strategy(&self) -> &RestartStrategy {
+        &self.restart_strategy
+    }
+
+    #[cfg(feature = "supervision")]
+    /// Returns the maximum number of restarts and the window during
+    /// which the group's supervisor allows them, if it limits them
+    /// (see [`Supervisor::with_restart_window`]).
+    ///
+    /// [`Supervisor::with_restart_window`]: ../supervisor/struct.Supervisor.html#method.with_restart_window
+    pub fn restart_window(&self) -> Option<(usize, Duration)> {
+        self.restart_window
+    }
+
+    #[cfg(feature = "supervision")]
+    /// Returns the back-off delaying the restarts of the group's
+    /// elements, if they are delayed (see
+    /// [`Children::with_restart_backoff`]).
+    ///
+    /// [`Children::with_restart_backoff`]: ../children/struct.Children.html#method.with_restart_backoff
+    pub fn restart_backoff(&self) -> Option<&Backoff> {
+        self.restart_backoff.as_ref()
+    }
+
+    /// Returns the group's pre-start watermark (see
+    /// [`Children::with_pre_start_watermark`]).
+    ///
+    /// [`Children::with_pre_start_watermark`]: ../children/struct.Children.html#method.with_pre_start_watermark
+    pub fn pre_start_watermark(&self) -> usize {
+        self.pre_start_watermark
+    }
+
+    /// Returns the capacity of the group's elements' mailboxes, if
+    /// they are bounded (see [`Children::with_mailbox_capacity`]).
+    ///
+    /// [`Children::with_mailbox_capacity`]: ../children/struct.Children.html#method.with_mailbox_capacity
+    pub fn mailbox_capacity(&self) -> Option<usize> {
+        self.mailbox_capacity
+    }
+
+    /// Returns what happens to the messages sent to the group's
+    /// elements once their mailboxes are full (see
+    /// [`Children::with_overflow_policy`]).
+    ///
+    /// [`Children::with_overflow_policy`]: ../children/struct.Children.html#method.with_overflow_policy
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        self.overflow_policy
+    }
+
+    /// Returns how long the group's elements are given to drain
+    /// their mailboxes when the group is stopped (see
+    /// [`Children::with_drain_timeout`]).
+    ///
+    /// [`Children::with_drain_timeout`]: ../children/struct.Children.html#method.with_drain_timeout
+    pub fn drain_timeout(&self) -> Duration {
+        self.drain_timeout
+    }
+
+    /// Returns whether the group's restarted elements handle the
+    /// messages their previous incarnation didn't (see
+    /// [`Children::with_requeue_on_restart`]).
+    ///
+    /// [`Children::with_requeue_on_restart`]: ../children/struct.Children.html#method.with_requeue_on_restart
+    pub fn requeues_on_restart(&self) -> bool {
+        self.requeue_on_restart
+    }
+
+    /// Returns the name of the preset applied to the group, or to
+    /// the nearest supervisor applying one if it didn't (see
+    /// [`SupervisionPreset`]).
+    ///
+    /// [`SupervisionPreset`]: ../preset/struct.SupervisionPreset.html
+    pub fn preset(&self) -> Option<&'static str> {
+        self.preset
+    }
+}
+
+impl Default for GroupPolicies {
+    fn default() -> Self {
+        GroupPolicies::new(RestartStrategy::default(), DEFAULT_PRE_START_WATERMARK)
+    }
+}
+
+impl GroupState {
+    pub(crate) fn is_terminal(self) -> bool {
+        matches!(
+            self,
+            GroupState::Stopped | GroupState::Killed | GroupState::Faulted
+        )
+    }
+}
+
+impl GroupStateCell {
+    pub(crate) fn new() -> Self {
+        let inner = GroupStateInner {
+            state: GroupState::Dormant,
+            subscribers: Vec::new(),
+        };
+
+        GroupStateCell(Arc::new(Mutex::new(inner)))
+    }
+
+    pub(crate) fn get(&self) -> GroupState {
+        self.0.lock().unwrap().state
+    }
+
+    pub(crate) fn set(&self, state: GroupState) {
+        let mut inner = self.0.lock().unwrap();
+        // A group can't leave a terminal state.
+        if inner.state == state || inner.state.is_terminal() {
+            return;
+        }
+
+        inner.state = state;
+        inner
+            .subscribers
+            .retain(|subscriber| subscriber.unbounded_send(state).is_ok());
+
+        if state.is_terminal() {
+            // Ends the subscribers' streams.
+            inner.subscribers.clear();
+        }
+    }
+
+    fn subscribe(&self) -> UnboundedReceiver<GroupState> {
+        let mut inner = self.0.lock().unwrap();
+        let (sender, recver) = mpsc::unbounded();
+        // The channel was just created so this can't fail.
+        sender.unbounded_send(inner.state).ok();
+
+        if !inner.state.is_terminal() {
+            inner.subscribers.push(sender);
+        }
+
+        recver
+    }
+}
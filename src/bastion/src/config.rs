@@ -1,9 +1,42 @@
+use crate::correlation::{DEFAULT_MAX_PENDING, DEFAULT_PENDING_TTL};
+use crate::dead_letters::{DeadLetter, DeadLetterMode, Handler, Serializers, Summarizer};
+use crate::instrument::{ExecInfo, ExecWrapper};
+use crate::message::Message;
+use futures::future::BoxFuture;
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::time::Duration;
+
 #[derive(Default, Debug, Clone)]
 /// The configuration that should be used to initialize the
 /// system using [`Bastion::init_with`].
 ///
 /// The default behaviors are the following:
 /// - All backtraces are shown (see [`Config::show_backtraces`]).
+/// - The system supervisor (supervising the children groups
+///     created with [`Bastion::children`]) never gives up
+///     restarting its elements (see
+///     [`Config::with_root_restart_window`]).
+/// - The system's panic hook runs before the hook that was
+///     installed before the system was initialized (see
+///     [`Config::panic_hook_order`]).
+/// - The dead letters carry their full payload (see
+///     [`Config::dead_letter_mode`]).
+/// - The answers to the asked messages are waited for without a
+///     timeout (see [`RuntimeConfig::with_default_ask_timeout`]).
+/// - The elements run in parallel, in an order which changes
+///     from one run to another (see
+///     [`Config::deterministic_scheduler`]).
+/// - The children groups that terminated aren't kept in a
+///     history (see [`Config::with_group_history`]).
+/// - At most [`DEFAULT_MAX_PENDING`] correlation ids wait for a
+///     reply at the same time, and the ones nobody waits for are
+///     expired after [`DEFAULT_PENDING_TTL`] (see
+///     [`Config::with_reply_broker_limits`]).
+///
+/// The settings of its [`RuntimeConfig`] (see
+/// [`Config::with_runtime`]) can be changed while the system runs
+/// using [`Bastion::reload_config`], unlike the others.
 ///
 /// # Example
 ///
@@ -24,8 +57,168 @@
 /// ```
 ///
 /// [`Bastion::init_with`]: struct.Bastion.html#method.init_with
+/// [`Config::show_backtraces`]: #method.show_backtraces
+/// [`Bastion::children`]: struct.Bastion.html#method.children
+/// [`Config::with_root_restart_window`]: #method.with_root_restart_window
+/// [`Config::panic_hook_order`]: #method.panic_hook_order
+/// [`Config::dead_letter_mode`]: #method.dead_letter_mode
+/// [`RuntimeConfig::with_default_ask_timeout`]: struct.RuntimeConfig.html#method.with_default_ask_timeout
+/// [`RuntimeConfig`]: struct.RuntimeConfig.html
+/// [`Config::with_runtime`]: #method.with_runtime
+/// [`Bastion::reload_config`]: struct.Bastion.html#method.reload_config
+/// [`Config::deterministic_scheduler`]: #method.deterministic_scheduler
+/// [`Config::with_group_history`]: #method.with_group_history
+/// [`DEFAULT_MAX_PENDING`]: correlation/constant.DEFAULT_MAX_PENDING.html
+/// [`DEFAULT_PENDING_TTL`]: correlation/constant.DEFAULT_PENDING_TTL.html
+/// [`Config::with_reply_broker_limits`]: #method.with_reply_broker_limits
 pub struct Config {
     backtraces: Backtraces,
+    panic_hook_order: PanicHookOrder,
+    // The restart window of the system supervisor.
+    #[cfg(feature = "supervision")]
+    root_restart_window: Option<(usize, Duration)>,
+    // What happens once the system supervisor restarted more
+    // elements than its window allows, instead of giving up.
+    #[cfg(feature = "supervision")]
+    root_exhaustion: Option<RootAction>,
+    dead_letter_serializers: Serializers,
+    dead_letter_handler: Option<Handler>,
+    // The seed of the executor's deterministic scheduler, if it
+    // should use one.
+    deterministic_seed: Option<u64>,
+    // What the elements' futures are wrapped with, if anything.
+    exec_wrapper: Option<ExecWrapper>,
+    // The maximum number of terminated groups kept in the system's
+    // history and for how long, if it keeps one.
+    group_history: Option<(usize, Duration)>,
+    // The maximum number of correlation ids waiting for a reply
+    // and how long they are kept if nobody waits for it, unless
+    // the defaults are used.
+    reply_broker_limits: Option<(usize, Duration)>,
+    // The settings that can be reloaded while the system runs.
+    runtime: RuntimeConfig,
+}
+
+#[derive(Default, Debug, Clone, Eq, PartialEq)]
+/// The settings of a [`Config`] that can be changed while the
+/// system runs, by passing a new `RuntimeConfig` to
+/// [`Bastion::reload_config`].
+///
+/// The default behaviors are the following:
+/// - The dead letters carry their full payload (see
+///     [`RuntimeConfig::dead_letter_mode`]).
+/// - The answers to the asked messages are waited for without a
+///     timeout (see [`RuntimeConfig::with_default_ask_timeout`]).
+///
+/// # Example
+///
+/// ```rust
+/// use bastion::prelude::*;
+/// use std::time::Duration;
+///
+/// fn main() {
+///     let runtime = RuntimeConfig::new().with_default_ask_timeout(Duration::from_secs(5));
+///     let config = Config::new().with_runtime(runtime);
+///
+///     Bastion::init_with(config);
+///     # Bastion::start();
+///
+///     // Later, without restarting the system...
+///     let runtime = RuntimeConfig::new().with_default_ask_timeout(Duration::from_secs(1));
+///     Bastion::reload_config(runtime).expect("Couldn't reload the configuration.");
+///     #
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// }
+/// ```
+///
+/// [`Config`]: struct.Config.html
+/// [`Bastion::reload_config`]: struct.Bastion.html#method.reload_config
+/// [`RuntimeConfig::dead_letter_mode`]: #method.dead_letter_mode
+/// [`RuntimeConfig::with_default_ask_timeout`]: #method.with_default_ask_timeout
+pub struct RuntimeConfig {
+    default_ask_timeout: Option<Duration>,
+    dead_letter_mode: DeadLetterMode,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// A setting changed by [`Bastion::reload_config`], as reported
+/// by [`Event::ConfigReloaded`].
+///
+/// [`Bastion::reload_config`]: struct.Bastion.html#method.reload_config
+/// [`Event::ConfigReloaded`]: event/enum.Event.html#variant.ConfigReloaded
+pub struct ConfigChange {
+    field: &'static str,
+    before: String,
+    after: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// The reasons why the configuration couldn't be reloaded (see
+/// [`Bastion::reload_config`]).
+///
+/// [`Bastion::reload_config`]: struct.Bastion.html#method.reload_config
+pub enum ReloadError {
+    /// The configuration changed settings that can only be set
+    /// when the system is initialized (see
+    /// [`Bastion::reload_with`]).
+    ///
+    /// [`Bastion::reload_with`]: struct.Bastion.html#method.reload_with
+    ConstructionTime {
+        /// The names of the settings that changed.
+        fields: Vec<&'static str>,
+    },
+    /// The system wasn't initialized using [`Bastion::init`].
+    ///
+    /// [`Bastion::init`]: struct.Bastion.html#method.init
+    NotInitialized,
+    /// The system is stopping or stopped, and wasn't initialized
+    /// again since then.
+    ShuttingDown,
+}
+
+#[cfg(feature = "supervision")]
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// What the system does once the system supervisor restarted
+/// more elements than its restart window allows (see
+/// [`Config::on_root_exhaustion`]).
+///
+/// [`Config::on_root_exhaustion`]: struct.Config.html#method.on_root_exhaustion
+pub enum RootAction {
+    /// Stops the whole system, like [`Bastion::stop`] would.
+    ///
+    /// [`Bastion::stop`]: struct.Bastion.html#method.stop
+    Stop,
+    /// Stops every children group except those registered with
+    /// one of the names in `keep` (see [`Children::with_name`]),
+    /// until [`Bastion::recover`] is called.
+    ///
+    /// [`Children::with_name`]: children/struct.Children.html#method.with_name
+    /// [`Bastion::recover`]: struct.Bastion.html#method.recover
+    Degrade {
+        /// The names of the children groups to keep running.
+        keep: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// When the panic hook installed by the system while it runs
+/// is called, relatively to the hook that was installed before
+/// the system was initialized (e.g. to report panics somewhere),
+/// which it replaces (see [`Config::panic_hook_order`]).
+///
+/// [`Config::panic_hook_order`]: struct.Config.html#method.panic_hook_order
+pub enum PanicHookOrder {
+    /// The system's hook is called first and the previous hook
+    /// afterwards.
+    First,
+    /// The previous hook is called first and the system's hook
+    /// afterwards.
+    Last,
+    /// Only the system's hook is called for the panics of
+    /// elements, the previous hook only being called for the
+    /// panics of threads that don't run elements.
+    Exclusive,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -42,8 +235,11 @@ impl Config {
     /// Creates a new configuration with the following default
     /// behaviors:
     /// - All backtraces are shown (see [`Config::show_backtraces`]).
+    /// - The system supervisor never gives up restarting its
+    ///     elements (see [`Config::with_root_restart_window`]).
     ///
     /// [`Config::show_backtraces`]: #method.show_backtraces
+    /// [`Config::with_root_restart_window`]: #method.with_root_restart_window
     pub fn new() -> Self {
         Config::default()
     }
@@ -77,7 +273,10 @@ impl Config {
         self
     }
 
-    /// Makes Bastion hide all backtraces.
+    /// Makes Bastion hide the backtraces of panicking elements,
+    /// by not calling the panic hook that was installed before the
+    /// system was initialized (usually the default one, printing
+    /// them) for their panics.
     ///
     /// Note that the default behavior is to show all backtraces
     /// (see [`Config::show_backtraces`]).
@@ -107,11 +306,744 @@ impl Config {
         self
     }
 
+    #[cfg(feature = "supervision")]
+    /// Sets the maximum number of restarts the system supervisor
+    /// (supervising the children groups created with
+    /// [`Bastion::children`]) allows during a window of time, like
+    /// [`Supervisor::with_restart_window`] does for other
+    /// supervisors.
+    ///
+    /// Once the window is exceeded, the system supervisor kills all
+    /// its children groups and restarts them, unless another action
+    /// was set using [`Config::on_root_exhaustion`].
+    ///
+    /// # Arguments
+    ///
+    /// * `max_restarts` - The maximum number of restarts during
+    ///     the window.
+    /// * `within` - How long the window lasts.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// fn main() {
+    ///     let config = Config::new().with_root_restart_window(10, Duration::from_secs(60));
+    ///
+    ///     Bastion::init_with(config);
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// }
+    /// ```
+    ///
+    /// [`Bastion::children`]: struct.Bastion.html#method.children
+    /// [`Supervisor::with_restart_window`]: supervisor/struct.Supervisor.html#method.with_restart_window
+    /// [`Config::on_root_exhaustion`]: #method.on_root_exhaustion
+    pub fn with_root_restart_window(mut self, max_restarts: usize, within: Duration) -> Self {
+        self.root_restart_window = Some((max_restarts, within));
+        self
+    }
+
+    #[cfg(feature = "supervision")]
+    /// Sets what the system does once the system supervisor
+    /// restarted more elements than the window set using
+    /// [`Config::with_root_restart_window`] allows.
+    ///
+    /// When degrading the system (using [`RootAction::Degrade`]),
+    /// the children groups that aren't kept are stopped (whichever
+    /// supervisor supervises them) and a [`Event::SystemDegraded`]
+    /// event is emitted. They are only created again (with the same
+    /// definition, but new elements) once [`Bastion::recover`] is
+    /// called, and should then be retrieved again using
+    /// [`Bastion::children_of`].
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - What the system should do.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// fn main() {
+    ///     let config = Config::new()
+    ///         .with_root_restart_window(10, Duration::from_secs(60))
+    ///         .on_root_exhaustion(RootAction::Degrade {
+    ///             keep: vec!["health".to_string(), "admin".to_string()],
+    ///         });
+    ///
+    ///     Bastion::init_with(config);
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// }
+    /// ```
+    ///
+    /// [`Config::with_root_restart_window`]: #method.with_root_restart_window
+    /// [`RootAction::Degrade`]: enum.RootAction.html#variant.Degrade
+    /// [`Event::SystemDegraded`]: event/enum.Event.html#variant.SystemDegraded
+    /// [`Bastion::recover`]: struct.Bastion.html#method.recover
+    /// [`Bastion::children_of`]: struct.Bastion.html#method.children_of
+    pub fn on_root_exhaustion(mut self, action: RootAction) -> Self {
+        self.root_exhaustion = Some(action);
+        self
+    }
+
+    /// Sets when the panic hook installed by the system while it
+    /// runs is called, relatively to the hook that was installed
+    /// before the system was initialized (or the default one).
+    ///
+    /// The system's hook only does something for the panics of
+    /// elements, and the previous hook is still called for the
+    /// panics of other threads (even when using
+    /// [`PanicHookOrder::Exclusive`]). The previous hook is
+    /// restored once the system stopped, unless another hook was
+    /// installed in the meantime, which is then chained the next
+    /// time the system is initialized.
+    ///
+    /// Note that the default order is [`PanicHookOrder::First`].
+    ///
+    /// # Arguments
+    ///
+    /// * `order` - When the system's hook is called.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    ///
+    /// fn main() {
+    ///     std::panic::set_hook(Box::new(|info| {
+    ///         // Report the panic somewhere...
+    ///         # drop(info);
+    ///     }));
+    ///
+    ///     let config = Config::new().panic_hook_order(PanicHookOrder::Last);
+    ///
+    ///     Bastion::init_with(config);
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// }
+    /// ```
+    ///
+    /// [`PanicHookOrder::Exclusive`]: enum.PanicHookOrder.html#variant.Exclusive
+    /// [`PanicHookOrder::First`]: enum.PanicHookOrder.html#variant.First
+    pub fn panic_hook_order(mut self, order: PanicHookOrder) -> Self {
+        self.panic_hook_order = order;
+        self
+    }
+
+    /// Sets what the dead letters (the messages sent to senders
+    /// that aren't identified, e.g. the replies to messages that
+    /// were told anonymously) carry.
+    ///
+    /// Using [`DeadLetterMode::SummaryOnly`], a message sent as a
+    /// dead letter is replaced by a summary of it (its type's name,
+    /// its size and, if its type has a serializer, a prefix of its
+    /// serialized form) and dropped as soon as it is sent, so that
+    /// the dead letters use a bounded amount of memory whatever
+    /// the size of their payloads.
+    ///
+    /// Note that the default mode is [`DeadLetterMode::Full`].
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - What the dead letters carry.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    ///
+    /// fn main() {
+    ///     let config = Config::new()
+    ///         .dead_letter_mode(DeadLetterMode::SummaryOnly { max_payload_bytes: 64 })
+    ///         .with_dead_letter_handler(|letter: DeadLetter| {
+    ///             if let DeadLetterPayload::Summary(summary) = letter.payload() {
+    ///                 println!("Dropped a {} ({} bytes).", summary.type_name(), summary.size());
+    ///             }
+    ///         });
+    ///
+    ///     Bastion::init_with(config);
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// }
+    /// ```
+    ///
+    /// [`DeadLetterMode::SummaryOnly`]: dead_letters/enum.DeadLetterMode.html#variant.SummaryOnly
+    /// [`DeadLetterMode::Full`]: dead_letters/enum.DeadLetterMode.html#variant.Full
+    pub fn dead_letter_mode(mut self, mode: DeadLetterMode) -> Self {
+        self.runtime.dead_letter_mode = mode;
+        self
+    }
+
+    /// Sets the serializer used to summarize the dead letters of
+    /// type `T` when using [`DeadLetterMode::SummaryOnly`],
+    /// replacing the one that was set before for this type.
+    ///
+    /// Only a prefix of the serialized message is kept in the
+    /// summary.
+    ///
+    /// # Arguments
+    ///
+    /// * `serializer` - The function serializing the messages of
+    ///     type `T`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    ///
+    /// fn main() {
+    ///     let config = Config::new()
+    ///         .dead_letter_mode(DeadLetterMode::SummaryOnly { max_payload_bytes: 64 })
+    ///         .with_dead_letter_serializer(|msg: &String| msg.as_bytes().to_vec());
+    ///
+    ///     Bastion::init_with(config);
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// }
+    /// ```
+    ///
+    /// [`DeadLetterMode::SummaryOnly`]: dead_letters/enum.DeadLetterMode.html#variant.SummaryOnly
+    pub fn with_dead_letter_serializer<T, F>(mut self, serializer: F) -> Self
+    where
+        T: Message,
+        F: Fn(&T) -> Vec<u8> + Send + Sync + 'static,
+    {
+        self.dead_letter_serializers.register(serializer);
+        self
+    }
+
+    /// Sets the handler called with every dead letter received by
+    /// the system's dead-letters group (the messages sent to
+    /// senders that aren't identified, or that couldn't be
+    /// delivered to their recipient), which carries the full
+    /// message or a summary of it depending on the mode set using
+    /// [`Config::dead_letter_mode`], along with why it was sent
+    /// there (see [`DeadLetterReason`]).
+    ///
+    /// The handler is called by the dead-letters group, which is
+    /// restarted if it panics.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The function called with the dead letters.
+    ///
+    /// [`Config::dead_letter_mode`]: #method.dead_letter_mode
+    /// [`DeadLetterReason`]: dead_letters/enum.DeadLetterReason.html
+    pub fn with_dead_letter_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(DeadLetter) + Send + Sync + 'static,
+    {
+        self.dead_letter_handler = Some(Handler::new(handler));
+        self
+    }
+
+    /// Sets the settings that can be changed while the system runs
+    /// (see [`Bastion::reload_config`]), replacing the ones that
+    /// were set before (including the mode set using
+    /// [`Config::dead_letter_mode`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `runtime` - The settings to initialize the system with.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// fn main() {
+    ///     let runtime = RuntimeConfig::new().with_default_ask_timeout(Duration::from_secs(5));
+    ///     let config = Config::new().with_runtime(runtime);
+    ///
+    ///     Bastion::init_with(config);
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// }
+    /// ```
+    ///
+    /// [`Bastion::reload_config`]: struct.Bastion.html#method.reload_config
+    /// [`Config::dead_letter_mode`]: #method.dead_letter_mode
+    pub fn with_runtime(mut self, runtime: RuntimeConfig) -> Self {
+        self.runtime = runtime;
+        self
+    }
+
+    /// Makes the executor run all the elements (and the system's
+    /// processes) on a single thread, picking the next one to run
+    /// among those that are ready using a pseudo-random generator
+    /// seeded with `seed`.
+    ///
+    /// Running the same workload with the same seed then runs its
+    /// elements in the same order, which allows to reproduce the
+    /// race conditions reported with the seed of the run that
+    /// triggered them, while running it with different seeds
+    /// explores different orders.
+    ///
+    /// The order is reproduced as long as the threads outside of
+    /// the system (e.g. the one calling [`Bastion::children`])
+    /// interact with it in the same order, while its elements are
+    /// waiting: the elements they wake up (e.g. by sending them
+    /// messages) only run once the elements woken up by the system
+    /// itself are all waiting, and once the call that woke them up
+    /// returned (so that e.g. all the elements of a new group are
+    /// launched before any of them runs), being picked among using
+    /// the same generator.
+    ///
+    /// Time is virtualized for the elements' timers (e.g.
+    /// [`BastionContext::sleep`], [`ChildRef::tell_after`] or the
+    /// restart back-offs): once all the elements are waiting, the
+    /// executor's clock is advanced to the earliest deadline, so
+    /// that the timers fire right away and in a reproducible order.
+    /// The other timeouts (e.g. [`Answer::timeout`]) still fire
+    /// after their real delay, so workloads depending on them
+    /// aren't always reproduced.
+    ///
+    /// This should only be used to debug or test a workload, and
+    /// only applies if the executor wasn't used yet by the process
+    /// (e.g. by a system that was started and stopped before).
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The seed of the executor's scheduler.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    ///
+    /// fn main() {
+    ///     let config = Config::new().deterministic_scheduler(42);
+    ///
+    ///     Bastion::init_with(config);
+    ///
+    ///     // The elements will run in the same order at
+    ///     // each run...
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// }
+    /// ```
+    ///
+    /// [`Bastion::children`]: struct.Bastion.html#method.children
+    /// [`BastionContext::sleep`]: context/struct.BastionContext.html#method.sleep
+    /// [`ChildRef::tell_after`]: child_ref/struct.ChildRef.html#method.tell_after
+    /// [`Answer::timeout`]: message/struct.Answer.html#method.timeout
+    pub fn deterministic_scheduler(mut self, seed: u64) -> Self {
+        self.deterministic_seed = Some(seed);
+        self
+    }
+
+    /// Sets a function wrapping the future executed by every
+    /// element of the system's children groups in another one,
+    /// e.g. to instrument its polls.
+    ///
+    /// The function is called with the element it wraps the
+    /// future of (see [`ExecInfo`]) and the future itself, each
+    /// time the element is launched or restarted (except for the
+    /// elements of the system's own groups, like the group
+    /// handling the dead letters), and returns the future that
+    /// the element executes instead. The element
+    /// faults if the returned future returns an error (reporting
+    /// the error returned by the wrapped future, if it did) or
+    /// panics.
+    ///
+    /// The returned future is polled by the element within its
+    /// own instrumentation: its polls are recorded by its group's
+    /// flight recorder (see [`Children::with_flight_recorder`]),
+    /// the messages sent while it is polled carry the provenance
+    /// of the message being handled (see
+    /// [`Children::with_provenance`]), and its panics are caught
+    /// and reported like those of the wrapped future. The warmup
+    /// of the element (see [`Children::with_warmup`]) isn't
+    /// wrapped.
+    ///
+    /// # Arguments
+    ///
+    /// * `wrapper` - The function wrapping the elements' futures.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    /// use futures::future::FutureExt;
+    ///
+    /// fn main() {
+    ///     let config = Config::new().exec_wrapper(|info: ExecInfo, exec| {
+    ///         async move {
+    ///             println!("Element {} started.", info.id());
+    ///             let done = exec.await;
+    ///             println!("Element {} finished: {:?}.", info.id(), done);
+    ///             done
+    ///         }
+    ///         .boxed()
+    ///     });
+    ///
+    ///     Bastion::init_with(config);
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// }
+    /// ```
+    ///
+    /// [`ExecInfo`]: instrument/struct.ExecInfo.html
+    /// [`Children::with_flight_recorder`]: children/struct.Children.html#method.with_flight_recorder
+    /// [`Children::with_provenance`]: children/struct.Children.html#method.with_provenance
+    /// [`Children::with_warmup`]: children/struct.Children.html#method.with_warmup
+    pub fn exec_wrapper<W>(mut self, wrapper: W) -> Self
+    where
+        W: Fn(ExecInfo, BoxFuture<'static, Result<(), ()>>) -> BoxFuture<'static, Result<(), ()>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.exec_wrapper = Some(ExecWrapper::new(wrapper));
+        self
+    }
+
+    /// Makes the system keep a history of the children groups that
+    /// stopped, were killed or faulted, returned by
+    /// [`Bastion::terminated_groups`] (and by the tree snapshots
+    /// returned by [`Bastion::tree`]) once they can't be retrieved
+    /// anymore.
+    ///
+    /// Each entry is a [`TerminatedGroup`] summarizing a group
+    /// (without referencing it, its elements or its messages). The
+    /// oldest entries are dropped once the history holds more than
+    /// `max_groups` of them, and the entries are dropped once they
+    /// were recorded more than `max_age` ago.
+    ///
+    /// Note that the default behavior is not to keep a history.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_groups` - The maximum number of groups kept in the
+    ///     history.
+    /// * `max_age` - How long the groups are kept in the history.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// fn main() {
+    ///     let config = Config::new().with_group_history(100, Duration::from_secs(24 * 60 * 60));
+    ///
+    ///     Bastion::init_with(config);
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// }
+    /// ```
+    ///
+    /// [`Bastion::terminated_groups`]: struct.Bastion.html#method.terminated_groups
+    /// [`Bastion::tree`]: struct.Bastion.html#method.tree
+    /// [`TerminatedGroup`]: history/struct.TerminatedGroup.html
+    pub fn with_group_history(mut self, max_groups: usize, max_age: Duration) -> Self {
+        self.group_history = Some((max_groups, max_age));
+        self
+    }
+
+    /// Sets the limits of the system's [`ReplyBroker`]: how many
+    /// correlation ids (see [`ChildRef::tell_correlated`]) can wait
+    /// for a reply at the same time, and how long a correlation id
+    /// is kept if nobody waits for its reply using
+    /// [`ReplyBroker::wait`].
+    ///
+    /// The expired correlation ids are removed whenever a new one
+    /// is registered, and sending a correlated message fails once
+    /// `max_pending` of them are still waiting for a reply.
+    ///
+    /// Note that the default limits are [`DEFAULT_MAX_PENDING`]
+    /// and [`DEFAULT_PENDING_TTL`].
+    ///
+    /// # Arguments
+    ///
+    /// * `max_pending` - The maximum number of correlation ids
+    ///     waiting for a reply at the same time.
+    /// * `ttl` - How long the correlation ids that nobody waits
+    ///     for a reply for are kept.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// fn main() {
+    ///     let config = Config::new().with_reply_broker_limits(64, Duration::from_secs(5));
+    ///
+    ///     Bastion::init_with(config);
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// }
+    /// ```
+    ///
+    /// [`ReplyBroker`]: correlation/struct.ReplyBroker.html
+    /// [`ChildRef::tell_correlated`]: child_ref/struct.ChildRef.html#method.tell_correlated
+    /// [`ReplyBroker::wait`]: correlation/struct.ReplyBroker.html#method.wait
+    /// [`DEFAULT_MAX_PENDING`]: correlation/constant.DEFAULT_MAX_PENDING.html
+    /// [`DEFAULT_PENDING_TTL`]: correlation/constant.DEFAULT_PENDING_TTL.html
+    pub fn with_reply_broker_limits(mut self, max_pending: usize, ttl: Duration) -> Self {
+        self.reply_broker_limits = Some((max_pending, ttl));
+        self
+    }
+
     pub(crate) fn backtraces(&self) -> &Backtraces {
         &self.backtraces
     }
+
+    pub(crate) fn hook_order(&self) -> PanicHookOrder {
+        self.panic_hook_order
+    }
+
+    pub(crate) fn runtime(&self) -> &RuntimeConfig {
+        &self.runtime
+    }
+
+    // Returns how the dead letters are summarized when using these
+    // runtime settings.
+    pub(crate) fn summarizer(&self, runtime: &RuntimeConfig) -> Option<Summarizer> {
+        Summarizer::new(runtime.dead_letter_mode, &self.dead_letter_serializers)
+    }
+
+    pub(crate) fn dead_letter_handler(&self) -> Option<&Handler> {
+        self.dead_letter_handler.as_ref()
+    }
+
+    #[cfg(feature = "supervision")]
+    pub(crate) fn root_restart_window(&self) -> Option<(usize, Duration)> {
+        self.root_restart_window
+    }
+
+    #[cfg(feature = "supervision")]
+    pub(crate) fn root_exhaustion(&self) -> Option<&RootAction> {
+        self.root_exhaustion.as_ref()
+    }
+
+    pub(crate) fn deterministic_seed(&self) -> Option<u64> {
+        self.deterministic_seed
+    }
+
+    pub(crate) fn wrapper(&self) -> Option<&ExecWrapper> {
+        self.exec_wrapper.as_ref()
+    }
+
+    pub(crate) fn group_history(&self) -> Option<(usize, Duration)> {
+        self.group_history
+    }
+
+    pub(crate) fn reply_broker_limits(&self) -> (usize, Duration) {
+        self.reply_broker_limits
+            .unwrap_or((DEFAULT_MAX_PENDING, DEFAULT_PENDING_TTL))
+    }
+
+    // Returns the names of the construction-time settings that
+    // differ between both configurations. The dead letters'
+    // serializers and handler are only compared by the types they
+    // serialize and whether a handler is set.
+    pub(crate) fn construction_diff(&self, other: &Config) -> Vec<&'static str> {
+        let mut fields = Vec::new();
+        if self.backtraces != other.backtraces {
+            fields.push("backtraces");
+        }
+        if self.panic_hook_order != other.panic_hook_order {
+            fields.push("panic_hook_order");
+        }
+        #[cfg(feature = "supervision")]
+        {
+            if self.root_restart_window != other.root_restart_window {
+                fields.push("root_restart_window");
+            }
+            if self.root_exhaustion != other.root_exhaustion {
+                fields.push("root_exhaustion");
+            }
+        }
+        if !self
+            .dead_letter_serializers
+            .same_types(&other.dead_letter_serializers)
+        {
+            fields.push("dead_letter_serializers");
+        }
+        if self.dead_letter_handler.is_some() != other.dead_letter_handler.is_some() {
+            fields.push("dead_letter_handler");
+        }
+        if self.deterministic_seed != other.deterministic_seed {
+            fields.push("deterministic_scheduler");
+        }
+        if self.exec_wrapper.is_some() != other.exec_wrapper.is_some() {
+            fields.push("exec_wrapper");
+        }
+        if self.group_history != other.group_history {
+            fields.push("group_history");
+        }
+        if self.reply_broker_limits != other.reply_broker_limits {
+            fields.push("reply_broker_limits");
+        }
+
+        fields
+    }
 }
 
+impl RuntimeConfig {
+    /// Creates new runtime settings with the following default
+    /// behaviors:
+    /// - The dead letters carry their full payload (see
+    ///     [`RuntimeConfig::dead_letter_mode`]).
+    /// - The answers to the asked messages are waited for without
+    ///     a timeout (see
+    ///     [`RuntimeConfig::with_default_ask_timeout`]).
+    ///
+    /// [`RuntimeConfig::dead_letter_mode`]: #method.dead_letter_mode
+    /// [`RuntimeConfig::with_default_ask_timeout`]: #method.with_default_ask_timeout
+    pub fn new() -> Self {
+        RuntimeConfig::default()
+    }
+
+    /// Sets how long the [`Answer`]s to the messages asked from
+    /// now on (e.g. using [`ChildRef::ask_anonymously`]) wait for
+    /// the message to be answered before resolving to
+    /// [`AnswerError::TimedOut`], unless another timeout is set
+    /// using [`Answer::timeout`] or [`Answer::wait`].
+    ///
+    /// The answers to the messages asked before the settings were
+    /// reloaded keep the timeout they were created with.
+    ///
+    /// Note that the default behavior is to wait without a
+    /// timeout.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long the answers wait by default.
+    ///
+    /// [`Answer`]: message/struct.Answer.html
+    /// [`ChildRef::ask_anonymously`]: child_ref/struct.ChildRef.html#method.ask_anonymously
+    /// [`AnswerError::TimedOut`]: errors/enum.AnswerError.html#variant.TimedOut
+    /// [`Answer::timeout`]: message/struct.Answer.html#method.timeout
+    /// [`Answer::wait`]: message/struct.Answer.html#method.wait
+    pub fn with_default_ask_timeout(mut self, timeout: Duration) -> Self {
+        self.default_ask_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets what the dead letters carry, like
+    /// [`Config::dead_letter_mode`] does.
+    ///
+    /// The messages that were already summarized or sent to the
+    /// dead letters with their full payload before the settings
+    /// were reloaded are left as they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - What the dead letters carry.
+    ///
+    /// [`Config::dead_letter_mode`]: struct.Config.html#method.dead_letter_mode
+    pub fn dead_letter_mode(mut self, mode: DeadLetterMode) -> Self {
+        self.dead_letter_mode = mode;
+        self
+    }
+
+    pub(crate) fn default_ask_timeout(&self) -> Option<Duration> {
+        self.default_ask_timeout
+    }
+
+    // Returns the settings that differ between both runtime
+    // configurations.
+    pub(crate) fn diff(&self, other: &RuntimeConfig) -> Vec<ConfigChange> {
+        let mut changes = Vec::new();
+        if self.default_ask_timeout != other.default_ask_timeout {
+            let change = ConfigChange::new(
+                "default_ask_timeout",
+                &self.default_ask_timeout,
+                &other.default_ask_timeout,
+            );
+            changes.push(change);
+        }
+        if self.dead_letter_mode != other.dead_letter_mode {
+            let change = ConfigChange::new(
+                "dead_letter_mode",
+                &self.dead_letter_mode,
+                &other.dead_letter_mode,
+            );
+            changes.push(change);
+        }
+
+        changes
+    }
+}
+
+impl ConfigChange {
+    fn new<T: Debug>(field: &'static str, before: &T, after: &T) -> Self {
+        ConfigChange {
+            field,
+            before: format!("{:?}", before),
+            after: format!("{:?}", after),
+        }
+    }
+
+    /// Returns the name of the setting that changed (e.g.
+    /// `"default_ask_timeout"`).
+    pub fn field(&self) -> &'static str {
+        self.field
+    }
+
+    /// Returns the value of the setting before it changed,
+    /// formatted using its `Debug` implementation.
+    pub fn before(&self) -> &str {
+        &self.before
+    }
+
+    /// Returns the value of the setting after it changed,
+    /// formatted using its `Debug` implementation.
+    pub fn after(&self) -> &str {
+        &self.after
+    }
+}
+
+impl Display for ReloadError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            ReloadError::ConstructionTime { fields } => write!(
+                fmt,
+                "couldn't reload the configuration: `{}` can only be set at initialization",
+                fields.join("`, `")
+            ),
+            ReloadError::NotInitialized => {
+                fmt.write_str("couldn't reload the configuration: the system isn't initialized")
+            }
+            ReloadError::ShuttingDown => {
+                fmt.write_str("couldn't reload the configuration: the system is shutting down")
+            }
+        }
+    }
+}
+
+impl Error for ReloadError {}
+
 impl Backtraces {
     fn show() -> Self {
         Backtraces::Show
@@ -126,6 +1058,12 @@ impl Backtraces {
     }
 }
 
+impl Default for PanicHookOrder {
+    fn default() -> Self {
+        PanicHookOrder::First
+    }
+}
+
 impl Default for Backtraces {
     fn default() -> Self {
         Backtraces::Show
@@ -2,21 +2,51 @@
 //! A context allows a child's future to access its received
 //! messages, parent and supervisor.
 
+use crate::ack::AckToken;
+use crate::actor::MessageTimeout;
 use crate::child_ref::ChildRef;
 use crate::children_ref::ChildrenRef;
+use crate::correlation::CorrelationId;
+use crate::dead_letters::{self, DeadLetterReason};
 use crate::dispatcher::{BroadcastTarget, DispatcherType, NotificationType};
 use crate::envelope::{Envelope, RefAddr, SignedMessage};
-use crate::message::{Answer, BastionMessage, Message, Msg};
+use crate::errors::{BuilderError, ReceiveError, SendError, SendErrorKind};
+use crate::flight_recorder;
+use crate::launch::{LaunchPermit, WeakLaunchPermit};
+use crate::mailbox::MailboxSlot;
+use crate::message::{Answer, BastionMessage, Message, Msg, Priority, TypedMsg};
+use crate::metrics::{ChildCounters, Metrics};
+use crate::provenance;
+use crate::reactor::{self, Sleep};
+use crate::reject::{RejectDisposition, MAX_REJECT_HOPS};
+use crate::schedule::{self, ScheduleHandle};
+use crate::state_cell::StateError;
 use crate::supervisor::SupervisorRef;
 use crate::system::SYSTEM;
-use futures::pending;
+use bastion_executor::deterministic;
+use bastion_executor::pool;
+use futures::future::{self, FutureExt};
+use futures::{pending, poll};
+use futures_timer::Delay;
+use lightproc::proc_stack::ProcStack;
 use qutex::{Guard, Qutex};
+use std::any::type_name;
 use std::collections::VecDeque;
 use std::fmt::{self, Display, Formatter};
+use std::future::Future;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
+// The number of messages an element's future can receive in a
+// row before yielding, for its element to handle the control
+// messages sent to it (e.g. when it is killed) without having
+// to wait for its future to empty its mailbox.
+const RECV_IN_A_ROW: usize = 128;
+
 /// Identifier for a root supervisor and dead-letters children.
 pub const NIL_ID: BastionId = BastionId(Uuid::nil());
 
@@ -107,15 +137,198 @@ pub struct BastionContext {
     children: ChildrenRef,
     supervisor: Option<SupervisorRef>,
     state: Qutex<Pin<Box<ContextState>>>,
+    token: CancellationToken,
+    next_stage: Option<ChildrenRef>,
+    // The index of the element in its group, kept when it is
+    // restarted.
+    index: usize,
+    // The permit the element holds until it is initialized, if it
+    // was launched along with its group.
+    launch: WeakLaunchPermit,
+    // How long the element's actor can take to handle a message,
+    // if its group limits it.
+    message_timeout: Option<MessageTimeout>,
+}
+
+#[derive(Debug, Clone)]
+/// A token getting cancelled once the children group's element
+/// it was retrieved from (using [`BastionContext::cancellation_token`])
+/// stops, is killed or terminates in any other way.
+///
+/// A `CancellationToken` is cheap to clone and is a [`Future`]
+/// resolving once it gets cancelled, which makes it usable by
+/// libraries or IO loops accepting a cancellation future. Once
+/// the element it is linked to terminated, the token (and all
+/// of its clones) stays cancelled.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// Bastion::children(|children| {
+///     children.with_exec(|ctx: BastionContext| {
+///         async move {
+///             let token: CancellationToken = ctx.cancellation_token();
+///             assert!(!token.is_cancelled());
+///
+///             // Hand `token` to code running outside of bastion,
+///             // which can either check it or `.await` it...
+///             blocking!(
+///                 while !token.is_cancelled() {
+///                     // ...
+///                     # break;
+///                 }
+///             );
+///
+///             Ok(())
+///         }
+///     })
+/// }).expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`BastionContext::cancellation_token`]: struct.BastionContext.html#method.cancellation_token
+/// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+pub struct CancellationToken {
+    inner: Arc<CancellationState>,
+}
+
+#[derive(Debug, Clone)]
+/// A token getting triggered once the children group's element
+/// it was retrieved from (using [`BastionContext::shutdown_token`])
+/// is asked to stop, before it gets killed if it didn't in time.
+///
+/// Unlike a [`CancellationToken`], which only gets cancelled once
+/// the element terminated, a `ShutdownToken` lets the element (or
+/// the code it handed the token to) finish what it is doing and
+/// release the resources it holds on its own: it gets triggered
+/// when the element's group is stopped or quiesced, and at the
+/// latest when the element is stopped, killed or terminates in
+/// any other way.
+///
+/// Like a [`CancellationToken`], it is cheap to clone and is a
+/// [`Future`] resolving once it gets triggered, after which it
+/// (and all of its clones) stays triggered.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// Bastion::children(|children| {
+///     children.with_exec(|ctx: BastionContext| {
+///         async move {
+///             let token: ShutdownToken = ctx.shutdown_token();
+///             let computed = blocking!({
+///                 let mut computed = 0u64;
+///                 // Compute until the element is asked to stop...
+///                 while !token.is_triggered() {
+///                     computed += 1;
+///                     # break;
+///                 }
+///
+///                 computed
+///             });
+///
+///             // ...and exit cleanly once it is.
+///             computed.await;
+///             Ok(())
+///         }
+///     })
+/// }).expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`BastionContext::shutdown_token`]: struct.BastionContext.html#method.shutdown_token
+/// [`CancellationToken`]: struct.CancellationToken.html
+/// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+pub struct ShutdownToken {
+    inner: Arc<CancellationState>,
+}
+
+#[derive(Debug, Default)]
+struct CancellationState {
+    cancelled: AtomicBool,
+    wakers: Mutex<Vec<Waker>>,
+    // Set once the element is asked to stop, which happens at the
+    // latest when the token gets cancelled.
+    shutdown: AtomicBool,
+    shutdown_wakers: Mutex<Vec<Waker>>,
+    // The tokens created using `child_token`, which need to
+    // be cancelled along with this one.
+    children: Mutex<Vec<Weak<CancellationState>>>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// An event concerning the element a [`BastionContext`] is
+/// linked to, as returned by [`BastionContext::next_event`].
+///
+/// [`BastionContext`]: struct.BastionContext.html
+/// [`BastionContext::next_event`]: struct.BastionContext.html#method.next_event
+#[non_exhaustive]
+pub enum ContextEvent {
+    /// The element's group is being quiesced (see
+    /// [`ChildrenRef::quiesce`]), so the element should gracefully
+    /// close the connections it holds and have its future
+    /// complete before `deadline`, after which it is stopped like
+    /// [`ChildrenRef::stop_with_timeout`] would.
+    ///
+    /// [`ChildrenRef::quiesce`]: ../children_ref/struct.ChildrenRef.html#method.quiesce
+    /// [`ChildrenRef::stop_with_timeout`]: ../children_ref/struct.ChildrenRef.html#method.stop_with_timeout
+    Quiesce {
+        /// The time the element is stopped at if it is still
+        /// running.
+        deadline: Instant,
+    },
 }
 
 #[derive(Debug)]
 pub(crate) struct ContextState {
-    messages: VecDeque<SignedMessage>,
+    // The messages along with the place they take in the
+    // element's mailbox, released once they are retrieved.
+    messages: VecDeque<(SignedMessage, Option<MailboxSlot>)>,
+    // Whether the element is waiting for a message, rather than
+    // handling one.
+    waiting: bool,
+    // The waker of the element's future while it is waiting.
+    waker: Option<Waker>,
+    // The element's run-time counters, kept up to date with its
+    // messages.
+    counters: Arc<ChildCounters>,
+    // The number of messages the element's future received in a
+    // row, without waiting for one nor yielding.
+    received: usize,
+    // The events not retrieved by the element's future yet.
+    events: VecDeque<ContextEvent>,
 }
 
 impl BastionId {
     pub(crate) fn new() -> Self {
+        // The ids are reproduced along with the order in which the
+        // elements run when the executor's scheduler is
+        // deterministic (as they might be iterated over in their
+        // hashes' order).
+        if deterministic::seed().is_some() {
+            static NEXT: AtomicU64 = AtomicU64::new(1);
+            let next = NEXT.fetch_add(1, Ordering::Relaxed);
+            return BastionId(Uuid::from_u128(next.into()));
+        }
+
         let uuid = Uuid::new_v4();
 
         BastionId(uuid)
@@ -129,6 +342,8 @@ impl BastionContext {
         children: ChildrenRef,
         supervisor: Option<SupervisorRef>,
         state: Qutex<Pin<Box<ContextState>>>,
+        token: CancellationToken,
+        next_stage: Option<ChildrenRef>,
     ) -> Self {
         debug!("BastionContext({}): Creating.", id);
         BastionContext {
@@ -137,12 +352,72 @@ impl BastionContext {
             children,
             supervisor,
             state,
+            token,
+            next_stage,
+            index: 0,
+            launch: WeakLaunchPermit::default(),
+            message_timeout: None,
+        }
+    }
+
+    pub(crate) fn with_index(mut self, index: usize) -> Self {
+        self.index = index;
+        self
+    }
+
+    pub(crate) fn with_launch_permit(mut self, launch: WeakLaunchPermit) -> Self {
+        self.launch = launch;
+        self
+    }
+
+    pub(crate) fn with_message_timeout(mut self, timeout: Option<MessageTimeout>) -> Self {
+        self.message_timeout = timeout;
+        self
+    }
+
+    pub(crate) fn message_timeout(&self) -> Option<MessageTimeout> {
+        self.message_timeout
+    }
+
+    // Marks the element as busy after it stopped waiting for a
+    // message without receiving one, so that it isn't considered
+    // idle (and stopped while it is finishing) anymore.
+    pub(crate) async fn stop_waiting(&self) {
+        if let Ok(mut guard) = self.state.clone().lock_async().await {
+            guard.as_mut().set_waiting(None);
         }
     }
 
+    // Returns the permit the element holds until it is initialized,
+    // unless it already is.
+    pub(crate) fn launch_permit(&self) -> Option<LaunchPermit> {
+        self.launch.upgrade()
+    }
+
+    // A context linked to the same element, for another future it
+    // runs (e.g. its warmup).
+    pub(crate) fn duplicate(&self) -> Self {
+        BastionContext::new(
+            self.id.clone(),
+            self.child.clone(),
+            self.children.clone(),
+            self.supervisor.clone(),
+            self.state.clone(),
+            self.token.clone(),
+            self.next_stage.clone(),
+        )
+        .with_index(self.index)
+        .with_launch_permit(self.launch.clone())
+        .with_message_timeout(self.message_timeout)
+    }
+
     /// Returns a [`ChildRef`] referencing the children group's
     /// element that is linked to this `BastionContext`.
     ///
+    /// It is available as soon as the element's future is first
+    /// polled, and can be cloned and handed to other elements (e.g.
+    /// for them to send it a reply later).
+    ///
     /// # Example
     ///
     /// ```rust
@@ -169,11 +444,57 @@ impl BastionContext {
     /// # }
     /// ```
     ///
-    /// [`ChildRef`]: children/struct.ChildRef.html
+    /// [`ChildRef`]: ../child_ref/struct.ChildRef.html
     pub fn current(&self) -> &ChildRef {
         &self.child
     }
 
+    /// Returns the index of the element linked to this
+    /// `BastionContext` in its children group, between `0` and
+    /// the group's redundancy (see [`Children::with_redundancy`]).
+    ///
+    /// An element keeps its index when it is restarted, and the
+    /// elements are launched again with the same indexes when the
+    /// whole group is restarted, so that it can be used to shard
+    /// work between them. The elements added to the group later
+    /// (e.g. when it scales up) use the lowest index unused by the
+    /// other elements.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_redundancy(4)
+    ///         .with_exec(|ctx: BastionContext| {
+    ///             async move {
+    ///                 if ctx.elem_index() == 0 {
+    ///                     // Lead the other elements...
+    ///                 } else {
+    ///                     // Follow the leader...
+    ///                 }
+    ///
+    ///                 Ok(())
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::with_redundancy`]: ../children/struct.Children.html#method.with_redundancy
+    pub fn elem_index(&self) -> usize {
+        self.index
+    }
+
     /// Returns a [`ChildrenRef`] referencing the children group
     /// of the element that is linked to this `BastionContext`.
     ///
@@ -203,11 +524,74 @@ impl BastionContext {
     /// # }
     /// ```
     ///
-    /// [`ChildrenRef`]: children/struct.ChildrenRef.html
+    /// [`ChildrenRef`]: ../children_ref/struct.ChildrenRef.html
     pub fn parent(&self) -> &ChildrenRef {
         &self.children
     }
 
+    /// Adds a new element running the future returned by `init` to
+    /// the children group of the element that is linked to this
+    /// `BastionContext` (e.g. one handling each connection accepted
+    /// by this element), like [`ChildrenRef::deploy_elem`] does.
+    ///
+    /// The new element is stopped, killed and restarted along with
+    /// the group's other elements, and restarted by running the
+    /// future returned by `init` again.
+    ///
+    /// This method returns a [`Future`] resolving to a [`ChildRef`]
+    /// referencing the new element, or to a
+    /// [`BuilderError`](../errors/enum.BuilderError.html) if the
+    /// group stopped before deploying it.
+    ///
+    /// # Arguments
+    ///
+    /// * `init` - The closure taking a `BastionContext` and
+    ///   returning the future the new element will run, like the
+    ///   one passed to [`Children::with_exec`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // For each connection accepted...
+    ///             let worker: ChildRef = ctx
+    ///                 .spawn_child(|ctx: BastionContext| async move {
+    ///                     // ...an element handles it.
+    ///                     # Ok(())
+    ///                 })
+    ///                 .await
+    ///                 .expect("Couldn't spawn the element.");
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef::deploy_elem`]: ../children_ref/struct.ChildrenRef.html#method.deploy_elem
+    /// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+    /// [`ChildRef`]: ../child_ref/struct.ChildRef.html
+    /// [`Children::with_exec`]: ../children/struct.Children.html#method.with_exec
+    pub fn spawn_child<I, F>(&self, init: I) -> impl Future<Output = Result<ChildRef, BuilderError>>
+    where
+        I: Fn(BastionContext) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        self.children.deploy_elem(init)
+    }
+
     /// Returns a [`SupervisorRef`] referencing the supervisor
     /// that supervises the element that is linked to this
     /// `BastionContext` if it isn't the system supervisor
@@ -259,20 +643,17 @@ impl BastionContext {
     /// # }
     /// ```
     ///
-    /// [`SupervisorRef`]: supervisor/struct.SupervisorRef.html
-    /// [`Bastion::children`]: struct.Bastion.html#method.children
+    /// [`SupervisorRef`]: ../supervisor/struct.SupervisorRef.html
+    /// [`Bastion::children`]: ../struct.Bastion.html#method.children
     pub fn supervisor(&self) -> Option<&SupervisorRef> {
         self.supervisor.as_ref()
     }
 
-    /// Tries to retrieve asynchronously a message received by
-    /// the element this `BastionContext` is linked to.
-    ///
-    /// If you need to wait (always asynchronously) until at
-    /// least one message can be retrieved, use [`recv`] instead.
-    ///
-    /// This method returns [`SignedMessage`] if a message was available, or
-    /// `None` otherwise.
+    /// Returns a [`CancellationToken`] that gets cancelled once
+    /// the element this `BastionContext` is linked to stops, is
+    /// killed or terminates in any other way (including after
+    /// having been restarted, in which case its new context will
+    /// have a new token).
     ///
     /// # Example
     ///
@@ -285,9 +666,12 @@ impl BastionContext {
     /// Bastion::children(|children| {
     ///     children.with_exec(|ctx: BastionContext| {
     ///         async move {
-    ///             let opt_msg: Option<SignedMessage> = ctx.try_recv().await;
-    ///             // If a message was received by the element, `opt_msg` will
-    ///             // be `Some(Msg)`, otherwise it will be `None`.
+    ///             let token: CancellationToken = ctx.cancellation_token();
+    ///             spawn! {
+    ///                 // Wait for the element to terminate...
+    ///                 token.await;
+    ///                 // ...and clean up what needs to be.
+    ///             };
     ///
     ///             Ok(())
     ///         }
@@ -300,32 +684,17 @@ impl BastionContext {
     /// # }
     /// ```
     ///
-    /// [`recv`]: #method.recv
-    /// [`SignedMessage`]: ../prelude/struct.SignedMessage.html
-    pub async fn try_recv(&self) -> Option<SignedMessage> {
-        debug!("BastionContext({}): Trying to receive message.", self.id);
-        // TODO: Err(Error)
-        let mut guard = self.state.clone().lock_async().await.ok()?;
-        let mut state = guard.as_mut();
-
-        if let Some(msg) = state.pop_message() {
-            trace!("BastionContext({}): Received message: {:?}", self.id, msg);
-            Some(msg)
-        } else {
-            trace!("BastionContext({}): Received no message.", self.id);
-            None
-        }
+    /// [`CancellationToken`]: struct.CancellationToken.html
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.token.clone()
     }
 
-    /// Retrieves asynchronously a message received by the element
-    /// this `BastionContext` is linked to and waits (always
-    /// asynchronously) for one if none has been received yet.
-    ///
-    /// If you don't need to wait until at least one message
-    /// can be retrieved, use [`try_recv`] instead.
-    ///
-    /// This method returns [`SignedMessage`] if it succeeded, or `Err(())`
-    /// otherwise.
+    /// Returns a [`ShutdownToken`] that gets triggered once the
+    /// element this `BastionContext` is linked to is asked to stop
+    /// (when its group is stopped or quiesced), before it gets
+    /// killed if it didn't stop in time, and at the latest when it
+    /// terminates (including after having been restarted, in which
+    /// case its new context will have a new token).
     ///
     /// # Example
     ///
@@ -338,8 +707,12 @@ impl BastionContext {
     /// Bastion::children(|children| {
     ///     children.with_exec(|ctx: BastionContext| {
     ///         async move {
-    ///             // This will block until a message has been received...
-    ///             let msg: SignedMessage = ctx.recv().await?;
+    ///             let token: ShutdownToken = ctx.shutdown_token();
+    ///             spawn! {
+    ///                 // Wait for the element to be asked to stop...
+    ///                 token.await;
+    ///                 // ...and release what needs to be.
+    ///             };
     ///
     ///             Ok(())
     ///         }
@@ -352,66 +725,79 @@ impl BastionContext {
     /// # }
     /// ```
     ///
-    /// [`try_recv`]: #method.try_recv
-    /// [`SignedMessage`]: ../prelude/struct.SignedMessage.html
-    pub async fn recv(&self) -> Result<SignedMessage, ()> {
-        debug!("BastionContext({}): Waiting to receive message.", self.id);
-        loop {
-            // TODO: Err(Error)
-            let mut guard = self.state.clone().lock_async().await.unwrap();
-            let mut state = guard.as_mut();
-
-            if let Some(msg) = state.pop_message() {
-                trace!("BastionContext({}): Received message: {:?}", self.id, msg);
-                return Ok(msg);
-            }
-
-            Guard::unlock(guard);
-            pending!();
-        }
+    /// [`ShutdownToken`]: struct.ShutdownToken.html
+    pub fn shutdown_token(&self) -> ShutdownToken {
+        self.token.shutdown_token()
     }
 
-    /// Returns [`RefAddr`] of the current `BastionContext`
+    /// Retrieves asynchronously the oldest [`ContextEvent`]
+    /// concerning the element this `BastionContext` is linked to,
+    /// waiting (always asynchronously) for one if there isn't any.
+    ///
+    /// This allows elements whose future never finishes on its
+    /// own (e.g. because it serves long-lived subscriptions) to
+    /// know when to stop, by waiting for an event along with their
+    /// messages.
     ///
     /// # Example
     ///
     /// ```rust
     /// # use bastion::prelude::*;
+    /// use futures::{pin_mut, select, FutureExt};
     /// #
     /// # fn main() {
     ///     # Bastion::init();
     ///     #
-    ///
     /// Bastion::children(|children| {
     ///     children.with_exec(|ctx: BastionContext| {
     ///         async move {
-    ///             ctx.tell(&ctx.signature(), "Hello to myself");
-    ///
-    ///             # Bastion::stop();
-    ///             Ok(())
+    ///             loop {
+    ///                 let event = ctx.next_event().fuse();
+    ///                 let msg = ctx.recv().fuse();
+    ///                 pin_mut!(event, msg);
+    ///                 select! {
+    ///                     event = event => match event {
+    ///                         ContextEvent::Quiesce { .. } => {
+    ///                             // Close the connections gracefully...
+    ///                             return Ok(());
+    ///                         }
+    ///                         // Other events could be added later.
+    ///                         _ => (),
+    ///                     },
+    ///                     msg = msg => {
+    ///                         // Handle the message...
+    ///                         msg?;
+    ///                     }
+    ///                 }
+    ///             }
     ///         }
     ///     })
     /// }).expect("Couldn't create the children group.");
     ///     #
     ///     # Bastion::start();
+    ///     # Bastion::stop();
     ///     # Bastion::block_until_stopped();
     /// # }
     /// ```
     ///
-    /// [`RefAddr`]: /prelude/struct.Answer.html
-    pub fn signature(&self) -> RefAddr {
-        RefAddr::new(
-            self.current().path().clone(),
-            self.current().sender().clone(),
-        )
+    /// [`ContextEvent`]: enum.ContextEvent.html
+    pub async fn next_event(&self) -> ContextEvent {
+        loop {
+            let mut guard = self.state.clone().lock_async().await.unwrap();
+            if let Some(event) = guard.as_mut().pop_event() {
+                trace!("BastionContext({}): Received event: {:?}", self.id, event);
+                return event;
+            }
+
+            Guard::unlock(guard);
+            pending!();
+        }
     }
 
-    /// Sends a message to the specified [`RefAddr`]
-    ///
-    /// # Arguments
-    ///
-    /// * `to` – the [`RefAddr`] to send the message to
-    /// * `msg` – The actual message to send
+    /// Returns the [`Metrics`] of the element this
+    /// `BastionContext` is linked to, used to register its
+    /// counters, gauges and histograms, which can then be
+    /// retrieved for the whole group using [`ChildrenRef::stats`].
     ///
     /// # Example
     ///
@@ -424,12 +810,10 @@ impl BastionContext {
     /// Bastion::children(|children| {
     ///     children.with_exec(|ctx: BastionContext| {
     ///         async move {
-    ///             // Wait for a message to be received...
-    ///             let smsg: SignedMessage = ctx.recv().await?;
-    ///             // Obtain address of this message sender...
-    ///             let sender_addr = smsg.signature();
-    ///             // And send something back
-    ///             ctx.tell(&sender_addr, "Ack").expect("Unable to acknowledge");
+    ///             let jobs_ok = ctx.metrics().counter("jobs_ok")?;
+    ///             // Do the job...
+    ///             jobs_ok.inc();
+    ///
     ///             Ok(())
     ///         }
     ///     })
@@ -441,46 +825,1119 @@ impl BastionContext {
     /// # }
     /// ```
     ///
-    /// [`RefAddr`]: ../prelude/struct.RefAddr.html
-    pub fn tell<M: Message>(&self, to: &RefAddr, msg: M) -> Result<(), M> {
-        debug!(
-            "{:?}: Telling message: {:?} to: {:?}",
-            self.current().path(),
-            msg,
-            to.path()
-        );
-        let msg = BastionMessage::tell(msg);
-        let env = Envelope::new_with_sign(msg, self.signature());
-        // FIXME: panics?
-        to.sender()
-            .unbounded_send(env)
-            .map_err(|err| err.into_inner().into_msg().unwrap())
+    /// [`Metrics`]: ../metrics/struct.Metrics.html
+    /// [`ChildrenRef::stats`]: ../children_ref/struct.ChildrenRef.html#method.stats
+    pub fn metrics(&self) -> Metrics {
+        Metrics::new(self.id.clone(), self.children.metrics().clone())
     }
 
-    /// Sends a message from behalf of current context to the addr,
-    /// allowing to addr owner answer.
-    ///
-    /// This method returns [`Answer`] if it succeeded, or `Err(msg)`
-    /// otherwise.
-    ///
-    /// # Argument
-    ///
-    /// * `msg` - The message to send.
+    /// Returns a [`ChildrenRef`] referencing the children group
+    /// of the next stage of the pipeline this element's group is
+    /// a stage of, or `None` if it isn't part of a [`Pipeline`]
+    /// or is its last stage.
     ///
     /// # Example
     ///
-    /// ```
+    /// ```rust
     /// # use bastion::prelude::*;
     /// #
     /// # fn main() {
     ///     # Bastion::init();
-    /// // The message that will be "asked"...
-    /// const ASK_MSG: &'static str = "A message containing data (ask).";
-    /// // The message the will be "answered"...
-    /// const ANSWER_MSG: &'static str = "A message containing data (answer).";
-    ///
-    ///     # let children_ref =
-    /// // Create a new child...
+    ///     #
+    /// Pipeline::new()
+    ///     .stage("forward", |children| {
+    ///         children.with_exec(|ctx: BastionContext| {
+    ///             async move {
+    ///                 let next_stage: Option<&ChildrenRef> = ctx.next_stage();
+    ///                 assert!(next_stage.is_some());
+    ///                 # Ok(())
+    ///             }
+    ///         })
+    ///     })
+    ///     .stage("sink", |children| {
+    ///         children.with_exec(|ctx: BastionContext| {
+    ///             async move {
+    ///                 assert!(ctx.next_stage().is_none());
+    ///                 # Ok(())
+    ///             }
+    ///         })
+    ///     })
+    ///     .build()
+    ///     .expect("Couldn't create the pipeline.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef`]: children/struct.ChildrenRef.html
+    /// [`Pipeline`]: pipeline/struct.Pipeline.html
+    #[cfg(feature = "patterns")]
+    pub fn next_stage(&self) -> Option<&ChildrenRef> {
+        self.next_stage.as_ref()
+    }
+
+    /// Tries to retrieve asynchronously a message received by
+    /// the element this `BastionContext` is linked to.
+    ///
+    /// If you need to wait (always asynchronously) until at
+    /// least one message can be retrieved, use [`recv`] instead.
+    ///
+    /// This method returns [`SignedMessage`] if a message was available, or
+    /// `None` otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             let opt_msg: Option<SignedMessage> = ctx.try_recv().await;
+    ///             // If a message was received by the element, `opt_msg` will
+    ///             // be `Some(Msg)`, otherwise it will be `None`.
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`recv`]: #method.recv
+    /// [`SignedMessage`]: ../prelude/struct.SignedMessage.html
+    pub async fn try_recv(&self) -> Option<SignedMessage> {
+        debug!("BastionContext({}): Trying to receive message.", self.id);
+        // TODO: Err(Error)
+        let mut guard = self.state.clone().lock_async().await.ok()?;
+        let mut state = guard.as_mut();
+
+        if let Some(msg) = state.pop_message() {
+            trace!("BastionContext({}): Received message: {:?}", self.id, msg);
+            Some(msg)
+        } else {
+            trace!("BastionContext({}): Received no message.", self.id);
+            None
+        }
+    }
+
+    /// Retrieves asynchronously a message received by the element
+    /// this `BastionContext` is linked to and waits (always
+    /// asynchronously) for one if none has been received yet.
+    ///
+    /// If you don't need to wait until at least one message
+    /// can be retrieved, use [`try_recv`] instead. If you only
+    /// need to wait for some time, use [`recv_timeout`].
+    ///
+    /// This method returns [`SignedMessage`] if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // This will block until a message has been received...
+    ///             let msg: SignedMessage = ctx.recv().await?;
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`try_recv`]: #method.try_recv
+    /// [`recv_timeout`]: #method.recv_timeout
+    /// [`SignedMessage`]: ../prelude/struct.SignedMessage.html
+    pub async fn recv(&self) -> Result<SignedMessage, ()> {
+        debug!("BastionContext({}): Waiting to receive message.", self.id);
+        loop {
+            // TODO: Err(Error)
+            let mut guard = self.state.clone().lock_async().await.unwrap();
+            let mut state = guard.as_mut();
+
+            if state.should_yield() {
+                Guard::unlock(guard);
+                yield_now().await;
+                continue;
+            }
+
+            if let Some(msg) = state.pop_message() {
+                trace!("BastionContext({}): Received message: {:?}", self.id, msg);
+                return Ok(msg);
+            }
+
+            state.set_waiting(Some(current_waker().await));
+            Guard::unlock(guard);
+            pending!();
+        }
+    }
+
+    /// Retrieves asynchronously the oldest message received by the
+    /// element this `BastionContext` is linked to for which
+    /// `predicate` returns `true`, waiting (always asynchronously)
+    /// for one if none has been received yet.
+    ///
+    /// The messages that don't match are left in the element's
+    /// mailbox in the order they were received, to be retrieved
+    /// later using [`recv`] or the like (they are sent to the dead
+    /// letters if the element is stopped using [`ChildRef::stop`],
+    /// and kept if it is restarted).
+    /// Note that a children group being stopped using
+    /// [`ChildrenRef::stop_with_timeout`] waits for its elements to
+    /// retrieve them (or for the timeout to expire).
+    ///
+    /// This method returns [`SignedMessage`] if it succeeded, or
+    /// `Err(())` otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `predicate` - Returns whether a message should be
+    ///     retrieved (see [`Msg::downcast_ref`]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // Waits for the reply while other messages keep
+    ///             // arriving...
+    ///             let reply = ctx
+    ///                 .recv_where(|msg| msg.downcast_ref::<&'static str>() == Some(&"reply"))
+    ///                 .await?;
+    ///             // ...which are retrieved afterwards.
+    ///             let msg: SignedMessage = ctx.recv().await?;
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`recv`]: #method.recv
+    /// [`ChildRef::stop`]: ../child_ref/struct.ChildRef.html#method.stop
+    /// [`ChildrenRef::stop_with_timeout`]: ../children_ref/struct.ChildrenRef.html#method.stop_with_timeout
+    /// [`SignedMessage`]: ../prelude/struct.SignedMessage.html
+    /// [`Msg::downcast_ref`]: ../message/struct.Msg.html#method.downcast_ref
+    pub async fn recv_where<F>(&self, predicate: F) -> Result<SignedMessage, ()>
+    where
+        F: Fn(&Msg) -> bool,
+    {
+        debug!(
+            "BastionContext({}): Waiting to receive a matching message.",
+            self.id
+        );
+        loop {
+            // TODO: Err(Error)
+            let mut guard = self.state.clone().lock_async().await.unwrap();
+            let mut state = guard.as_mut();
+
+            if state.should_yield() {
+                Guard::unlock(guard);
+                yield_now().await;
+                continue;
+            }
+
+            if let Some(msg) = state.pop_message_where(&predicate) {
+                trace!("BastionContext({}): Received message: {:?}", self.id, msg);
+                return Ok(msg);
+            }
+
+            state.set_waiting(Some(current_waker().await));
+            Guard::unlock(guard);
+            pending!();
+        }
+    }
+
+    /// Retrieves asynchronously a message received by the element
+    /// this `BastionContext` is linked to, like [`recv`] does, and
+    /// downcasts it to a [`TypedMsg`] of `T`.
+    ///
+    /// This method returns the message along with the signature
+    /// of its sender if it is of this type, or the original
+    /// [`SignedMessage`] otherwise, so that it can be handled in
+    /// another way.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             loop {
+    ///                 match ctx.recv_typed::<u64>().await {
+    ///                     Ok((TypedMsg::Ask { msg, sender }, _)) => {
+    ///                         sender.answer(&ctx, msg * 2).unwrap();
+    ///                     }
+    ///                     Ok((TypedMsg::Tell(msg), _)) => {
+    ///                         // Handle the message...
+    ///                         # drop(msg);
+    ///                     }
+    ///                     Ok((TypedMsg::Broadcast(msg), _)) => {
+    ///                         // Handle the message (an `Arc<u64>`)...
+    ///                         # drop(msg);
+    ///                     }
+    ///                     Err(msg) => {
+    ///                         // Handle messages of other types...
+    ///                         # drop(msg);
+    ///                     }
+    ///                 }
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`recv`]: #method.recv
+    /// [`TypedMsg`]: ../message/enum.TypedMsg.html
+    /// [`SignedMessage`]: ../prelude/struct.SignedMessage.html
+    pub async fn recv_typed<T: Message>(&self) -> Result<(TypedMsg<T>, RefAddr), SignedMessage> {
+        debug!(
+            "BastionContext({}): Waiting to receive message of type {}.",
+            self.id,
+            type_name::<T>()
+        );
+        // `recv` only returns once a message was received.
+        let signed = self.recv().await.unwrap();
+        let (msg, sign) = signed.extract();
+        match msg.downcast_typed() {
+            Ok(msg) => Ok((msg, sign)),
+            Err(msg) => Err(SignedMessage::new(msg, sign)),
+        }
+    }
+
+    /// Retrieves asynchronously a message of type `M` received by
+    /// the element this `BastionContext` is linked to, like
+    /// [`recv_typed`] does, for the elements that only accept
+    /// messages of this type (see [`Bastion::typed_children`]).
+    ///
+    /// The messages of other types that the element might still
+    /// receive (e.g. sent using [`ChildRef::tell_unchecked`]) are
+    /// rejected and sent to the dead letters (see
+    /// [`reject_message`]) in the meantime.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::typed_children::<u64, _>(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             loop {
+    ///                 match ctx.recv_msg::<u64>().await {
+    ///                     (TypedMsg::Ask { msg, sender }, _) => {
+    ///                         sender.answer(&ctx, msg * 2).unwrap();
+    ///                     }
+    ///                     (TypedMsg::Tell(msg), _) => {
+    ///                         // Handle the message...
+    ///                         # drop(msg);
+    ///                     }
+    ///                     (TypedMsg::Broadcast(msg), _) => {
+    ///                         // Handle the message (an `Arc<u64>`)...
+    ///                         # drop(msg);
+    ///                     }
+    ///                 }
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`recv_typed`]: #method.recv_typed
+    /// [`reject_message`]: #method.reject_message
+    /// [`Bastion::typed_children`]: ../struct.Bastion.html#method.typed_children
+    /// [`ChildRef::tell_unchecked`]: ../child_ref/struct.ChildRef.html#method.tell_unchecked
+    pub async fn recv_msg<M: Message>(&self) -> (TypedMsg<M>, RefAddr) {
+        loop {
+            match self.recv_typed().await {
+                Ok(msg) => return msg,
+                Err(msg) => self.reject_message(msg, RejectDisposition::DeadLetter),
+            }
+        }
+    }
+
+    /// Retrieves asynchronously a message received by the element
+    /// this `BastionContext` is linked to and waits (always
+    /// asynchronously) for one if none has been received yet,
+    /// for at most `timeout`.
+    ///
+    /// This method returns [`SignedMessage`] if it succeeded, or
+    /// [`ReceiveError::Timeout`] if no message was received in
+    /// time.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long to wait for a message.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             loop {
+    ///                 match ctx.recv_timeout(Duration::from_secs(1)).await {
+    ///                     Ok(msg) => {
+    ///                         // Handle the message...
+    ///                         # drop(msg);
+    ///                     }
+    ///                     Err(ReceiveError::Timeout(_)) => {
+    ///                         // Do some housekeeping while idle...
+    ///                     }
+    ///                 }
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`SignedMessage`]: ../prelude/struct.SignedMessage.html
+    /// [`ReceiveError::Timeout`]: ../errors/enum.ReceiveError.html#variant.Timeout
+    pub async fn recv_timeout(&self, timeout: Duration) -> Result<SignedMessage, ReceiveError> {
+        debug!(
+            "BastionContext({}): Waiting to receive message within {:?}.",
+            self.id, timeout
+        );
+        let mut delay = Delay::new(timeout);
+        loop {
+            // TODO: Err(Error)
+            let mut guard = self.state.clone().lock_async().await.unwrap();
+            let mut state = guard.as_mut();
+
+            if state.should_yield() {
+                Guard::unlock(guard);
+                yield_now().await;
+                continue;
+            }
+
+            if let Some(msg) = state.pop_message() {
+                trace!("BastionContext({}): Received message: {:?}", self.id, msg);
+                return Ok(msg);
+            }
+
+            // The element gets polled again when either a message
+            // is received or the timeout expires.
+            let timed_out = poll!(&mut delay).is_ready();
+            if timed_out {
+                state.set_waiting(None);
+            } else {
+                state.set_waiting(Some(current_waker().await));
+            }
+            Guard::unlock(guard);
+
+            if timed_out {
+                trace!("BastionContext({}): Received no message in time.", self.id);
+                return Err(ReceiveError::Timeout(timeout));
+            }
+
+            pending!();
+        }
+    }
+
+    /// Waits for the specified duration, the element being woken by
+    /// the system's reactor once it elapsed.
+    ///
+    /// The reactor's timers outlive its restarts, but the elements
+    /// sleeping are only woken while it is running (see
+    /// [`Health::reactor`]): once the system started and until
+    /// every other element stopped.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - How long to wait for.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             loop {
+    ///                 // Do some housekeeping every second...
+    ///                 ctx.sleep(Duration::from_secs(1)).await;
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Health::reactor`]: ../struct.Health.html#method.reactor
+    pub async fn sleep(&self, duration: Duration) {
+        debug!("BastionContext({}): Sleeping for {:?}.", self.id, duration);
+        Sleep::new(reactor::now() + duration).await
+    }
+
+    /// Sends the messages created by `factory` to the element linked
+    /// to this context every `interval` (the first one once
+    /// `interval` elapsed), like [`ChildRef::tell_after`] does,
+    /// instead of running a loop sleeping between them.
+    ///
+    /// The messages stop being sent once the returned
+    /// [`ScheduleHandle`] is cancelled, or once the element
+    /// terminates (a restarted element needs to schedule them
+    /// again). A message that can't be received because the
+    /// element's mailbox is full is sent to the dead letters.
+    ///
+    /// # Arguments
+    ///
+    /// * `factory` - The function creating the messages.
+    /// * `interval` - How long to wait between them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// #[derive(Debug)]
+    /// struct Tick;
+    ///
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             let ticks = ctx.schedule_periodic(|| Tick, Duration::from_secs(1));
+    ///             loop {
+    ///                 msg! { ctx.recv().await?,
+    ///                     _tick: Tick => {
+    ///                         // Do some housekeeping every second...
+    ///                     };
+    ///                     msg: &'static str => {
+    ///                         if msg == "pause" {
+    ///                             ticks.cancel();
+    ///                         }
+    ///                     };
+    ///                     _: _ => ();
+    ///                 }
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildRef::tell_after`]: ../child_ref/struct.ChildRef.html#method.tell_after
+    /// [`ScheduleHandle`]: ../schedule/struct.ScheduleHandle.html
+    pub fn schedule_periodic<M, F>(&self, factory: F, interval: Duration) -> ScheduleHandle
+    where
+        M: Message,
+        F: Fn() -> M + Send + 'static,
+    {
+        debug!(
+            "BastionContext({}): Scheduling a message every {:?}.",
+            self.id, interval
+        );
+        let token = self.token.child_token();
+        schedule::periodic(self.child.clone(), factory, interval, token)
+    }
+
+    /// Returns the state of type `T` owned by the system, created
+    /// using [`Bastion::state_cell`] and constructed if it is
+    /// accessed for the first time.
+    ///
+    /// This method returns the state if it succeeded, or a
+    /// [`StateError`] if no state cell of this type was created
+    /// for the current system or if the system stopped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// use std::sync::Mutex;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// struct Cache(Mutex<Vec<String>>);
+    ///
+    /// Bastion::state_cell(|| Cache(Mutex::new(Vec::new())));
+    ///
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| async move {
+    ///         let cache = ctx.global::<Cache>()?;
+    ///         cache.0.lock().unwrap().push("cached".to_string());
+    ///
+    ///         Ok(())
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Bastion::state_cell`]: ../struct.Bastion.html#method.state_cell
+    /// [`StateError`]: ../state_cell/enum.StateError.html
+    pub fn global<T: Send + Sync + 'static>(&self) -> Result<Arc<T>, StateError> {
+        SYSTEM.states().get()
+    }
+
+    /// Returns [`RefAddr`] of the current `BastionContext`
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             ctx.tell(&ctx.signature(), "Hello to myself");
+    ///
+    ///             # Bastion::stop();
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`RefAddr`]: /prelude/struct.Answer.html
+    pub fn signature(&self) -> RefAddr {
+        RefAddr::new(self.current().path().clone(), self.current().sender())
+            .with_origin(self.current().clone())
+    }
+
+    /// Sends a message to the specified [`RefAddr`]
+    ///
+    /// # Arguments
+    ///
+    /// * `to` – the [`RefAddr`] to send the message to
+    /// * `msg` – The actual message to send
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // Wait for a message to be received...
+    ///             let smsg: SignedMessage = ctx.recv().await?;
+    ///             // Obtain address of this message sender...
+    ///             let sender_addr = smsg.signature();
+    ///             // And send something back
+    ///             ctx.tell(&sender_addr, "Ack").expect("Unable to acknowledge");
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`RefAddr`]: ../prelude/struct.RefAddr.html
+    pub fn tell<M: Message>(&self, to: &RefAddr, msg: M) -> Result<(), SendError<M>> {
+        debug!(
+            "{:?}: Telling message: {:?} to: {:?}",
+            self.current().path(),
+            msg,
+            to.path()
+        );
+        if let Some(summary) = dead_letters::summarize(to, &msg) {
+            let env = Envelope::new_with_sign(BastionMessage::tell(summary), self.signature());
+            // The message is dropped once its summary was sent.
+            return to
+                .sender()
+                .unbounded_send(env)
+                .map_err(|_| SendError::stopped(msg));
+        }
+
+        let msg = BastionMessage::tell(msg);
+        let env = Envelope::new_with_sign(msg, self.signature());
+        // FIXME: panics?
+        to.sender()
+            .unbounded_send(env)
+            .map_err(|err| SendError::stopped(err.into_inner().into_msg().unwrap()))
+    }
+
+    /// Sends a message to the element this `BastionContext` is
+    /// linked to, putting it directly in its mailbox instead of
+    /// sending it through its group (as using [`current`] would).
+    ///
+    /// The messages sent this way are retrieved in the order they
+    /// were sent in, but in no particular order relative to the
+    /// messages sent by others. Like the other messages in the
+    /// element's mailbox, they are kept if the element is restarted
+    /// and still handled if its group finishes handling its
+    /// messages before stopping, but dropped if it is killed.
+    ///
+    /// If the element is waiting for a message (e.g. because this
+    /// method is called from another future than the one waiting
+    /// for it), it is woken up to retrieve it.
+    ///
+    /// This method returns `()` if it succeeded, or a
+    /// [`SendError`](../errors/struct.SendError.html) carrying the
+    /// message otherwise (if the element's group doesn't accept
+    /// messages of this type).
+    ///
+    /// # Argument
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// #[derive(Debug)]
+    /// enum Step {
+    ///     Connect,
+    ///     Handshake,
+    /// }
+    ///
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             ctx.tell_self(Step::Connect).expect("Couldn't send the message.");
+    ///             loop {
+    ///                 msg! { ctx.recv().await?,
+    ///                     step: Step => {
+    ///                         if let Step::Connect = step {
+    ///                             ctx.tell_self(Step::Handshake)
+    ///                                 .expect("Couldn't send the message.");
+    ///                         }
+    ///                     };
+    ///                     _: _ => ();
+    ///                 }
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`current`]: #method.current
+    pub fn tell_self<M: Message>(&self, msg: M) -> Result<(), SendError<M>> {
+        debug!("BastionContext({}): Telling itself: {:?}", self.id, msg);
+        if let Err(kind) = self.child.accepted_types().check::<M>() {
+            return Err(SendError::new(kind, msg));
+        }
+
+        let msg = SignedMessage::new(Msg::tell(msg), self.signature());
+        let mut locking = Box::pin(self.state.clone().lock_async());
+        if let Some(guard) = locking.as_mut().now_or_never() {
+            push_own_message(&self.id, guard, msg);
+            return Ok(());
+        }
+
+        // The state is held by another task sharing this context:
+        // the message is pushed once it is released (and
+        // before the ones sent after it, the lock being handed over
+        // in the order it was asked for).
+        let id = self.id.clone();
+        let pushed = async move { push_own_message(&id, locking.await, msg) };
+        pool::spawn(pushed, ProcStack::default());
+
+        Ok(())
+    }
+
+    /// Sends a message to the element this `BastionContext` is
+    /// linked to once `delay` elapsed, the system's reactor waking
+    /// the timer once it expires (which can be a few milliseconds
+    /// late).
+    ///
+    /// Unlike the messages sent using [`tell_self`], the message
+    /// is sent through the element's group, like using
+    /// [`ChildRef::tell_after`] on [`current`] would, so that the
+    /// element is woken up to retrieve it. It is sent to the dead
+    /// letters instead if the element terminates before.
+    ///
+    /// This method returns a [`ScheduleHandle`] if the element's
+    /// group accepts messages of this type, or a
+    /// [`SendError`](../errors/struct.SendError.html) carrying the
+    /// message otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    /// * `delay` - How long to wait before sending it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             ctx.tell_self_after("retry", Duration::from_millis(100))
+    ///                 .expect("Couldn't schedule the message.");
+    ///             msg! { ctx.recv().await?,
+    ///                 msg: &'static str => {
+    ///                     assert_eq!(msg, "retry");
+    ///                 };
+    ///                 _: _ => ();
+    ///             }
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`tell_self`]: #method.tell_self
+    /// [`current`]: #method.current
+    /// [`ChildRef::tell_after`]: ../child_ref/struct.ChildRef.html#method.tell_after
+    /// [`ScheduleHandle`]: ../schedule/struct.ScheduleHandle.html
+    pub fn tell_self_after<M: Message>(
+        &self,
+        msg: M,
+        delay: Duration,
+    ) -> Result<ScheduleHandle, SendError<M>> {
+        self.child.tell_after(msg, delay)
+    }
+
+    /// Acknowledges that the element this `BastionContext` is
+    /// linked to applied a message broadcasted using
+    /// [`ChildrenRef::broadcast_acked`], given the token it was
+    /// sent with (see [`SignedMessage::ack_token`]).
+    ///
+    /// Acknowledging a message after its sender stopped waiting
+    /// for the acknowledgements has no effect.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token the message was sent with.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             loop {
+    ///                 let msg = ctx.recv().await?;
+    ///                 let token = msg.ack_token();
+    ///                 msg! { msg,
+    ///                     ref config: &'static str => {
+    ///                         // Apply the configuration...
+    ///                         if let Some(token) = token {
+    ///                             ctx.ack(token);
+    ///                         }
+    ///                     };
+    ///                     _: _ => ();
+    ///                 }
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef::broadcast_acked`]: ../children_ref/struct.ChildrenRef.html#method.broadcast_acked
+    /// [`SignedMessage::ack_token`]: ../envelope/struct.SignedMessage.html#method.ack_token
+    pub fn ack(&self, token: AckToken) {
+        debug!("BastionContext({}): Acknowledging a message.", self.id);
+        token.ack(&self.id);
+    }
+
+    /// Rejects a message the element linked to this
+    /// `BastionContext` received, because it doesn't belong to it
+    /// (e.g. because its sender used a stale sharding of the
+    /// group's elements), handing it to the system to dispose of
+    /// it as `disposition` says (see [`RejectDisposition`]).
+    ///
+    /// The rejections are counted in the element's (and its
+    /// group's) statistics (see [`ChildStats::rejected`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to reject, which the element still
+    ///     owns.
+    /// * `disposition` - What to do with the message.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_redundancy(4)
+    ///         .with_exec(|ctx: BastionContext| {
+    ///             async move {
+    ///                 loop {
+    ///                     let msg = ctx.recv().await?;
+    ///                     match msg.msg().downcast_ref::<u64>() {
+    ///                         // The key isn't in this element's shard...
+    ///                         Some(key) if *key as usize % 4 != ctx.elem_index() => {
+    ///                             ctx.reject_message(msg, RejectDisposition::Reroute);
+    ///                         }
+    ///                         _ => {
+    ///                             // Handle the message...
+    ///                         }
+    ///                     }
+    ///                 }
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`RejectDisposition`]: ../reject/enum.RejectDisposition.html
+    /// [`ChildStats::rejected`]: ../metrics/struct.ChildStats.html#method.rejected
+    pub fn reject_message(&self, msg: SignedMessage, disposition: RejectDisposition) {
+        debug!(
+            "BastionContext({}): Rejecting message ({:?}): {:?}",
+            self.id, disposition, msg
+        );
+        self.child.counters().rejected();
+
+        let SignedMessage { mut msg, sign } = msg;
+        match disposition {
+            RejectDisposition::Reroute if msg.hops() < MAX_REJECT_HOPS => {
+                if let Some(child) = self.children.reroute(&self.id, msg.payload()) {
+                    msg.rerouted();
+                    let env = Envelope::new_with_sign(BastionMessage::Message(msg), sign);
+                    if let Err((kind, env)) = child.send_bounded(env) {
+                        let reason = match kind {
+                            SendErrorKind::MailboxFull => DeadLetterReason::MailboxFull,
+                            _ => DeadLetterReason::RecipientStopped,
+                        };
+                        dead_letters::bounce(env, Some(child.id()), reason);
+                    }
+
+                    return;
+                }
+            }
+            RejectDisposition::ReturnToSender if sign.origin().is_some() => {
+                let env = Envelope::new_with_sign(BastionMessage::Message(msg), self.signature());
+                if let Err(err) = sign.sender().unbounded_send(env) {
+                    let reason = DeadLetterReason::Rejected;
+                    dead_letters::bounce(err.into_inner(), Some(&self.id), reason);
+                }
+
+                return;
+            }
+            // The message is sent to the dead letters.
+            _ => (),
+        }
+
+        let env = Envelope::new_with_sign(BastionMessage::Message(msg), sign);
+        dead_letters::bounce(env, Some(&self.id), DeadLetterReason::Rejected);
+    }
+
+    /// Sends a reply to a message received along with a
+    /// [`CorrelationId`] (as a [`Correlated`] message) to the
+    /// global [`ReplyBroker`], where the message's sender can
+    /// wait for it.
+    ///
+    /// Only the first reply sent with a correlation id is kept.
+    ///
+    /// This method returns `()` if it succeeded, or a
+    /// [`SendError`](../errors/struct.SendError.html) carrying the message otherwise
+    /// (if a reply was already sent with this correlation id or
+    /// it expired).
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The correlation id the message was received with.
+    /// * `msg` - The reply to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             msg! { ctx.recv().await?,
+    ///                 req: Correlated<u64> => {
+    ///                     ctx.reply_correlated(req.id(), *req.msg() + 1)
+    ///                         .expect("Couldn't send the reply.");
+    ///                 };
+    ///                 _: _ => ();
+    ///             }
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`CorrelationId`]: ../correlation/struct.CorrelationId.html
+    /// [`Correlated`]: ../correlation/struct.Correlated.html
+    /// [`ReplyBroker`]: ../correlation/struct.ReplyBroker.html
+    pub fn reply_correlated<M: Message>(
+        &self,
+        id: &CorrelationId,
+        msg: M,
+    ) -> Result<(), SendError<M>> {
+        debug!(
+            "{:?}: Replying to CorrelationId({}): {:?}",
+            self.current().path(),
+            id,
+            msg
+        );
+        SYSTEM
+            .reply_broker()
+            .reply(id, msg, self.signature())
+            .map_err(|msg| SendError::new(SendErrorKind::UnknownCorrelation, msg))
+    }
+
+    /// Sends a message from behalf of current context to the addr,
+    /// allowing to addr owner answer.
+    ///
+    /// This method returns [`Answer`] if it succeeded, or a
+    /// [`SendError`](../errors/struct.SendError.html) carrying the message otherwise.
+    /// Like with [`ChildRef::ask_anonymously`], a message asked
+    /// while too many questions are waiting for the answer of the
+    /// element the addr is the one of is rejected (see
+    /// [`Children::with_max_pending_answers`]).
+    ///
+    /// # Argument
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    /// // The message that will be "asked"...
+    /// const ASK_MSG: &'static str = "A message containing data (ask).";
+    /// // The message the will be "answered"...
+    /// const ANSWER_MSG: &'static str = "A message containing data (answer).";
+    ///
+    ///     # let children_ref =
+    /// // Create a new child...
     /// Bastion::children(|children| {
     ///     children.with_exec(|ctx: BastionContext| {
     ///         async move {
@@ -533,7 +1990,9 @@ impl BastionContext {
     /// ```
     ///
     /// [`Answer`]: /message/struct.Answer.html
-    pub fn ask<M: Message>(&self, to: &RefAddr, msg: M) -> Result<Answer, M> {
+    /// [`ChildRef::ask_anonymously`]: ../child_ref/struct.ChildRef.html#method.ask_anonymously
+    /// [`Children::with_max_pending_answers`]: ../children/struct.Children.html#method.with_max_pending_answers
+    pub fn ask<M: Message>(&self, to: &RefAddr, msg: M) -> Result<Answer, SendError<M>> {
         debug!(
             "{:?}: Asking message: {:?} to: {:?}",
             self.current().path(),
@@ -541,11 +2000,24 @@ impl BastionContext {
             to
         );
         let (msg, answer) = BastionMessage::ask(msg);
+        // The question takes a place among the ones waiting for the
+        // recipient's answer, like when asked using its `ChildRef`.
+        let msg = match to.pending_answers() {
+            Some(pending_answers) => match pending_answers.reserve() {
+                Some(slot) => msg.with_answer_slot(slot),
+                None => {
+                    let kind = SendErrorKind::TooManyPending;
+                    // FIXME: panics?
+                    return Err(SendError::new(kind, msg.into_msg().unwrap()));
+                }
+            },
+            None => msg,
+        };
         let env = Envelope::new_with_sign(msg, self.signature());
         // FIXME: panics?
         to.sender()
             .unbounded_send(env)
-            .map_err(|err| err.into_inner().into_msg().unwrap())?;
+            .map_err(|err| SendError::stopped(err.into_inner().into_msg().unwrap()))?;
 
         Ok(answer)
     }
@@ -584,20 +2056,419 @@ impl BastionContext {
     }
 }
 
-impl ContextState {
+impl CancellationToken {
     pub(crate) fn new() -> Self {
+        let inner = Arc::new(CancellationState::default());
+
+        CancellationToken { inner }
+    }
+
+    /// Returns whether this token was cancelled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// # Bastion::children(|children| {
+    ///     # children.with_exec(|ctx: BastionContext| {
+    ///         # async move {
+    /// let token = ctx.cancellation_token();
+    /// if token.is_cancelled() {
+    ///     // The element is terminating...
+    /// }
+    ///             #
+    ///             # Ok(())
+    ///         # }
+    ///     # })
+    /// # }).unwrap();
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Creates a new token linked to this one, which means that
+    /// it will get cancelled when this token gets cancelled (it
+    /// will already be cancelled if this token already is).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// # Bastion::children(|children| {
+    ///     # children.with_exec(|ctx: BastionContext| {
+    ///         # async move {
+    /// let token = ctx.cancellation_token();
+    /// let child_token = token.child_token();
+    /// assert_eq!(token.is_cancelled(), child_token.is_cancelled());
+    ///             #
+    ///             # Ok(())
+    ///         # }
+    ///     # })
+    /// # }).unwrap();
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn child_token(&self) -> CancellationToken {
+        let child = CancellationToken::new();
+
+        let mut children = self.inner.children.lock().unwrap();
+        if self.is_cancelled() {
+            child.cancel();
+        } else {
+            if self.inner.shutdown.load(Ordering::SeqCst) {
+                child.shut_down();
+            }
+
+            children.retain(|child| child.strong_count() > 0);
+            children.push(Arc::downgrade(&child.inner));
+        }
+
+        child
+    }
+
+    pub(crate) fn shutdown_token(&self) -> ShutdownToken {
+        ShutdownToken {
+            inner: self.inner.clone(),
+        }
+    }
+
+    // Triggers the token's `ShutdownToken`s (and the ones of the
+    // tokens created from it), without cancelling it.
+    pub(crate) fn shut_down(&self) {
+        if self.inner.shutdown.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        wake_all(&self.inner.shutdown_wakers);
+
+        let children = self.inner.children.lock().unwrap().clone();
+        for inner in children.iter().filter_map(Weak::upgrade) {
+            CancellationToken { inner }.shut_down();
+        }
+    }
+
+    pub(crate) fn cancel(&self) {
+        self.shut_down();
+        if self.inner.cancelled.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        wake_all(&self.inner.wakers);
+
+        let children = std::mem::take(&mut *self.inner.children.lock().unwrap());
+        for inner in children.iter().filter_map(Weak::upgrade) {
+            CancellationToken { inner }.cancel();
+        }
+    }
+}
+
+impl ShutdownToken {
+    /// Returns whether this token was triggered, meaning that the
+    /// element it was retrieved from was asked to stop.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// # Bastion::children(|children| {
+    ///     # children.with_exec(|ctx: BastionContext| {
+    ///         # async move {
+    /// let token = ctx.shutdown_token();
+    /// if token.is_triggered() {
+    ///     // The element is asked to stop...
+    /// }
+    ///             #
+    ///             # Ok(())
+    ///         # }
+    ///     # })
+    /// # }).unwrap();
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn is_triggered(&self) -> bool {
+        self.inner.shutdown.load(Ordering::SeqCst)
+    }
+}
+
+// Wakes and forgets the tasks waiting on a token.
+fn wake_all(wakers: &Mutex<Vec<Waker>>) {
+    let wakers = std::mem::take(&mut *wakers.lock().unwrap());
+    for waker in wakers {
+        waker.wake();
+    }
+}
+
+// Resolves once `flag` is set, registering the task's waker in
+// `wakers` until then.
+fn poll_flag(flag: &AtomicBool, wakers: &Mutex<Vec<Waker>>, ctx: &mut Context) -> Poll<()> {
+    if flag.load(Ordering::SeqCst) {
+        return Poll::Ready(());
+    }
+
+    let mut wakers = wakers.lock().unwrap();
+    // The flag might have been set while we were waiting for the
+    // lock.
+    if flag.load(Ordering::SeqCst) {
+        return Poll::Ready(());
+    }
+
+    if !wakers.iter().any(|waker| waker.will_wake(ctx.waker())) {
+        wakers.push(ctx.waker().clone());
+    }
+
+    Poll::Pending
+}
+
+impl Future for CancellationToken {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<()> {
+        poll_flag(&self.inner.cancelled, &self.inner.wakers, ctx)
+    }
+}
+
+impl Future for ShutdownToken {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<()> {
+        poll_flag(&self.inner.shutdown, &self.inner.shutdown_wakers, ctx)
+    }
+}
+
+impl ContextState {
+    pub(crate) fn new(counters: Arc<ChildCounters>) -> Self {
         ContextState {
             messages: VecDeque::new(),
+            waiting: false,
+            waker: None,
+            counters,
+            received: 0,
+            events: VecDeque::new(),
+        }
+    }
+
+    // Returns the messages that were dropped because the mailbox
+    // overflowed.
+    pub(crate) fn push_message(
+        &mut self,
+        msg: Msg,
+        sign: RefAddr,
+        slot: Option<MailboxSlot>,
+    ) -> Vec<SignedMessage> {
+        let mailbox = slot.as_ref().map(|slot| slot.mailbox().clone());
+        let msg = SignedMessage::new(msg, sign);
+        let mut pushed = match msg.msg.priority() {
+            // The message lands behind the other high-priority ones.
+            Priority::High => {
+                let lane = self
+                    .messages
+                    .iter()
+                    .take_while(|(msg, _)| msg.msg.priority() == Priority::High)
+                    .count();
+                self.messages.insert(lane, (msg, slot));
+                lane
+            }
+            Priority::Normal => {
+                self.messages.push_back((msg, slot));
+                self.messages.len() - 1
+            }
+        };
+
+        // Drops the oldest messages taking a place in the mailbox
+        // until it doesn't overflow anymore, if its policy is to
+        // (but never the message that was just pushed, which can
+        // be the oldest one if it was pushed in the high-priority
+        // lane).
+        let mut dropped = Vec::new();
+        if let Some(mailbox) = mailbox {
+            while mailbox.overflows() {
+                let oldest = self
+                    .messages
+                    .iter()
+                    .enumerate()
+                    .position(|(index, (_, slot))| index != pushed && slot.is_some());
+                match oldest {
+                    Some(oldest) => {
+                        if oldest < pushed {
+                            pushed -= 1;
+                        }
+
+                        let (oldest, _) = self.messages.remove(oldest).unwrap();
+                        debug!("ContextState: Dropping the oldest message: {:?}", oldest);
+                        dropped.push(oldest);
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        self.counters.set_mailbox_len(self.messages.len());
+        dropped
+    }
+
+    pub(crate) fn push_event(&mut self, event: ContextEvent) {
+        self.events.push_back(event);
+    }
+
+    pub(crate) fn pop_event(&mut self) -> Option<ContextEvent> {
+        self.events.pop_front()
+    }
+
+    // Marks the element as waiting for a message if its future's
+    // waker is given, for the messages it sends itself to wake it
+    // up.
+    pub(crate) fn set_waiting(&mut self, waker: Option<Waker>) {
+        let waiting = waker.is_some();
+        if waiting {
+            flight_recorder::waiting();
+            self.received = 0;
         }
+
+        self.waiting = waiting;
+        self.waker = waker;
+        self.counters.set_waiting(waiting);
+    }
+
+    pub(crate) fn is_idle(&self) -> bool {
+        self.waiting && self.messages.is_empty()
     }
 
-    pub(crate) fn push_message(&mut self, msg: Msg, sign: RefAddr) {
-        self.messages.push_back(SignedMessage::new(msg, sign))
+    // Returns whether the element's future received enough messages
+    // in a row to yield, which it then has to before receiving the
+    // next one.
+    pub(crate) fn should_yield(&mut self) -> bool {
+        if self.received < RECV_IN_A_ROW {
+            return false;
+        }
+
+        self.received = 0;
+        true
     }
 
     pub(crate) fn pop_message(&mut self) -> Option<SignedMessage> {
-        self.messages.pop_front()
+        self.remove_message(0)
+    }
+
+    // Retrieves the oldest message matching the predicate, leaving
+    // the others in the mailbox in the order they were received.
+    pub(crate) fn pop_message_where<F>(&mut self, predicate: F) -> Option<SignedMessage>
+    where
+        F: Fn(&Msg) -> bool,
+    {
+        let index = self
+            .messages
+            .iter()
+            .position(|(smsg, _)| predicate(&smsg.msg))?;
+        self.remove_message(index)
+    }
+
+    fn remove_message(&mut self, index: usize) -> Option<SignedMessage> {
+        let (msg, _) = self.messages.remove(index)?;
+        self.waiting = false;
+        self.waker = None;
+        self.counters.set_waiting(false);
+        self.received += 1;
+        self.counters.set_mailbox_len(self.messages.len());
+        self.counters.processed();
+        provenance::handling(&msg.msg);
+        flight_recorder::handling(msg.msg.type_name());
+
+        Some(msg)
+    }
+
+    // The messages leave the element's mailbox.
+    pub(crate) fn take_messages(&mut self) -> VecDeque<SignedMessage> {
+        self.counters.set_mailbox_len(0);
+        std::mem::take(&mut self.messages)
+            .into_iter()
+            .map(|(msg, _)| msg)
+            .collect()
+    }
+
+    pub(crate) fn restore_messages(&mut self, msgs: VecDeque<SignedMessage>) {
+        let mut msgs = msgs
+            .into_iter()
+            .map(|msg| (msg, None))
+            .collect::<VecDeque<_>>();
+        msgs.append(&mut self.messages);
+        self.messages = msgs;
+        self.counters.set_mailbox_len(self.messages.len());
+    }
+}
+
+// Pushes a message the element sent itself into its mailbox,
+// waking it up if it is waiting for one.
+fn push_own_message<E>(
+    id: &BastionId,
+    guard: Result<Guard<Pin<Box<ContextState>>>, E>,
+    msg: SignedMessage,
+) {
+    let mut guard = match guard {
+        Ok(guard) => guard,
+        Err(_) => {
+            let env = Envelope::new_with_sign(BastionMessage::Message(msg.msg), msg.sign);
+            dead_letters::bounce(env, Some(id), DeadLetterReason::RecipientStopped);
+            return;
+        }
+    };
+
+    let mut state = guard.as_mut();
+    let dropped = state.push_message(msg.msg, msg.sign, None);
+    let waker = state.waker.take();
+    Guard::unlock(guard);
+    if let Some(waker) = waker {
+        waker.wake();
     }
+
+    for SignedMessage { msg, sign } in dropped {
+        let env = Envelope::new_with_sign(BastionMessage::Message(msg), sign);
+        dead_letters::bounce(env, Some(id), DeadLetterReason::MailboxFull);
+    }
+}
+
+// Returns the waker of the current task, which the messages it
+// sends itself wake up while it is waiting for one.
+async fn current_waker() -> Waker {
+    future::poll_fn(|ctx| Poll::Ready(ctx.waker().clone())).await
+}
+
+// Yields once to the executor, waking the current task right
+// away for it to be polled again.
+async fn yield_now() {
+    let mut yielded = false;
+    future::poll_fn(|ctx| {
+        if yielded {
+            return Poll::Ready(());
+        }
+
+        yielded = true;
+        ctx.waker().wake_by_ref();
+        Poll::Pending
+    })
+    .await
 }
 
 impl Display for BastionId {
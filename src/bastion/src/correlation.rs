@@ -0,0 +1,480 @@
+//!
+//! Correlation ids allow to match replies with the messages
+//! they answer to without using [`ChildRef::ask_anonymously`],
+//! by sending them using [`ChildRef::tell_correlated`] and
+//! waiting for their reply using the global [`ReplyBroker`].
+//!
+//! [`ChildRef::ask_anonymously`]: ../child_ref/struct.ChildRef.html#method.ask_anonymously
+//! [`ChildRef::tell_correlated`]: ../child_ref/struct.ChildRef.html#method.tell_correlated
+//! [`ReplyBroker`]: struct.ReplyBroker.html
+use crate::envelope::{RefAddr, SignedMessage};
+use crate::message::{Message, Msg};
+use futures::future::{self, Either};
+use futures_timer::Delay;
+use fxhash::FxHashMap;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// The maximum number of correlation ids that can be waiting
+/// for a reply at the same time, unless another one is set using
+/// [`Config::with_reply_broker_limits`].
+///
+/// [`Config::with_reply_broker_limits`]: ../struct.Config.html#method.with_reply_broker_limits
+pub const DEFAULT_MAX_PENDING: usize = 4096;
+
+/// How long a correlation id is kept in the broker if no one
+/// waits for its reply using [`ReplyBroker::wait`], unless another
+/// duration is set using [`Config::with_reply_broker_limits`].
+///
+/// [`ReplyBroker::wait`]: struct.ReplyBroker.html#method.wait
+/// [`Config::with_reply_broker_limits`]: ../struct.Config.html#method.with_reply_broker_limits
+pub const DEFAULT_PENDING_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Copy)]
+/// An identifier used to match a message sent using
+/// [`ChildRef::tell_correlated`] with its reply, using a v4
+/// UUID.
+///
+/// [`ChildRef::tell_correlated`]: ../child_ref/struct.ChildRef.html#method.tell_correlated
+pub struct CorrelationId(Uuid);
+
+#[derive(Debug)]
+/// A message sent using [`ChildRef::tell_correlated`], along
+/// with the [`CorrelationId`] that its reply should be sent
+/// with, using [`BastionContext::reply_correlated`].
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// Bastion::children(|children| {
+///     children.with_exec(|ctx: BastionContext| {
+///         async move {
+///             msg! { ctx.recv().await?,
+///                 req: Correlated<u64> => {
+///                     let reply = *req.msg() * 2;
+///                     ctx.reply_correlated(req.id(), reply).ok();
+///                 };
+///                 _: _ => ();
+///             }
+///
+///             Ok(())
+///         }
+///     })
+/// }).expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`ChildRef::tell_correlated`]: ../child_ref/struct.ChildRef.html#method.tell_correlated
+/// [`CorrelationId`]: struct.CorrelationId.html
+/// [`BastionContext::reply_correlated`]: ../context/struct.BastionContext.html#method.reply_correlated
+pub struct Correlated<M: Message> {
+    id: CorrelationId,
+    msg: M,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// The reasons why [`ReplyBroker::wait`] can fail.
+///
+/// [`ReplyBroker::wait`]: struct.ReplyBroker.html#method.wait
+pub enum ReplyError {
+    /// No reply was received before the timeout expired. The
+    /// correlation id expired and a reply sent later will be
+    /// counted as a dropped reply.
+    Timeout,
+    /// The correlation id isn't (or isn't anymore) known by
+    /// the broker, eg. because its reply was already retrieved
+    /// or because it expired.
+    Unknown(CorrelationId),
+}
+
+#[derive(Debug)]
+/// A broker holding the replies to the messages sent using
+/// [`ChildRef::tell_correlated`] until their sender retrieves
+/// them using [`wait`].
+///
+/// There is one broker per process, shared by all groups,
+/// which can be retrieved using [`Bastion::reply_broker`].
+///
+/// The number of correlation ids waiting for a reply is
+/// bounded, and the correlation ids that nobody waits for
+/// a reply for are expired after some time (see
+/// [`Config::with_reply_broker_limits`]).
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// # use std::time::Duration;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+///     # let children_ref = Bastion::children(|children| {
+///         # children.with_exec(|ctx: BastionContext| {
+///             # async move {
+///                 # msg! { ctx.recv().await?,
+///                     # req: Correlated<&'static str> => {
+///                         # ctx.reply_correlated(req.id(), "Pong").ok();
+///                     # };
+///                     # _: _ => ();
+///                 # }
+///                 # Ok(())
+///             # }
+///         # })
+///     # }).unwrap();
+///     #
+///     # Bastion::start();
+///     # let child_ref = &children_ref.elems()[0];
+/// let id = child_ref.tell_correlated("Ping").expect("Couldn't send the message.");
+///
+/// let broker = Bastion::reply_broker();
+/// let reply: SignedMessage = run!(broker.wait(&id, Duration::from_secs(1)))
+///     .expect("Didn't receive a reply.");
+///     #
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`ChildRef::tell_correlated`]: ../child_ref/struct.ChildRef.html#method.tell_correlated
+/// [`wait`]: #method.wait
+/// [`Bastion::reply_broker`]: ../struct.Bastion.html#method.reply_broker
+/// [`Config::with_reply_broker_limits`]: ../struct.Config.html#method.with_reply_broker_limits
+pub struct ReplyBroker {
+    pending: Mutex<FxHashMap<CorrelationId, Pending>>,
+    // The correlation ids in the order they were registered, which
+    // can still contain ids that were already removed from
+    // `pending`. Only locked while `pending` is.
+    registered: Mutex<VecDeque<(Instant, CorrelationId)>>,
+    max_pending: usize,
+    ttl: Duration,
+    dropped_replies: AtomicUsize,
+}
+
+#[derive(Debug)]
+struct Pending {
+    state: PendingState,
+}
+
+#[derive(Debug)]
+enum PendingState {
+    Waiting(Option<Waker>),
+    Replied(Box<SignedMessage>),
+}
+
+struct WaitReply<'a> {
+    broker: &'a ReplyBroker,
+    id: CorrelationId,
+}
+
+impl CorrelationId {
+    pub(crate) fn new() -> Self {
+        let uuid = Uuid::new_v4();
+
+        CorrelationId(uuid)
+    }
+}
+
+impl<M: Message> Correlated<M> {
+    pub(crate) fn new(id: CorrelationId, msg: M) -> Self {
+        Correlated { id, msg }
+    }
+
+    /// Returns the correlation id that the reply to this
+    /// message should be sent with.
+    pub fn id(&self) -> &CorrelationId {
+        &self.id
+    }
+
+    /// Returns a reference to the message itself.
+    pub fn msg(&self) -> &M {
+        &self.msg
+    }
+
+    /// Returns the message itself, consuming the correlation
+    /// id along with it.
+    pub fn into_msg(self) -> M {
+        self.msg
+    }
+}
+
+impl ReplyBroker {
+    pub(crate) fn new(max_pending: usize, ttl: Duration) -> Self {
+        let pending = Mutex::new(FxHashMap::default());
+        let registered = Mutex::new(VecDeque::new());
+        let dropped_replies = AtomicUsize::new(0);
+
+        ReplyBroker {
+            pending,
+            registered,
+            max_pending,
+            ttl,
+            dropped_replies,
+        }
+    }
+
+    /// Registers a new correlation id, returning `None` if too
+    /// many correlation ids are already waiting for a reply.
+    pub(crate) fn register(&self) -> Option<CorrelationId> {
+        // FIXME: panics?
+        let mut pending = self.pending.lock().unwrap();
+        // FIXME: panics?
+        let mut registered = self.registered.lock().unwrap();
+        self.expire(&mut pending, &mut registered);
+        if pending.len() >= self.max_pending {
+            warn!("ReplyBroker: Too many correlation ids waiting for a reply.");
+            return None;
+        }
+
+        let id = CorrelationId::new();
+        let registered_at = Instant::now();
+        let state = PendingState::Waiting(None);
+        pending.insert(id, Pending { state });
+        registered.push_back((registered_at, id));
+
+        Some(id)
+    }
+
+    // Removes the correlation ids that were registered more than
+    // `ttl` ago and that nobody waits for a reply for (the ones
+    // waited for are removed once their own timeout expires).
+    fn expire(
+        &self,
+        pending: &mut FxHashMap<CorrelationId, Pending>,
+        registered: &mut VecDeque<(Instant, CorrelationId)>,
+    ) {
+        while let Some((registered_at, id)) = registered.front() {
+            if registered_at.elapsed() < self.ttl {
+                break;
+            }
+
+            match pending.get(id) {
+                Some(Pending {
+                    state: PendingState::Waiting(Some(_)),
+                    ..
+                }) => (),
+                Some(_) => {
+                    debug!("ReplyBroker: CorrelationId({}) expired.", id);
+                    pending.remove(id);
+                }
+                None => (),
+            }
+
+            registered.pop_front();
+        }
+
+        // The ids whose reply was already retrieved are only
+        // dropped from the queue once it grew too much compared
+        // to the ids that are still pending.
+        if registered.len() > 2 * pending.len().max(self.max_pending) {
+            registered.retain(|(_, id)| pending.contains_key(id));
+        }
+    }
+
+    pub(crate) fn unregister(&self, id: &CorrelationId) {
+        // FIXME: panics?
+        self.pending.lock().unwrap().remove(id);
+    }
+
+    /// Stores the reply for the given correlation id, returning
+    /// the message back if the correlation id isn't waiting for
+    /// a reply (anymore).
+    pub(crate) fn reply<M: Message>(
+        &self,
+        id: &CorrelationId,
+        msg: M,
+        sign: RefAddr,
+    ) -> Result<(), M> {
+        // FIXME: panics?
+        let mut pending = self.pending.lock().unwrap();
+        let waker = match pending.get_mut(id) {
+            Some(Pending {
+                state: state @ PendingState::Waiting(_),
+                ..
+            }) => {
+                let msg = Msg::tell(msg).with_correlation_id(*id);
+                let msg = SignedMessage::new(msg, sign);
+                match std::mem::replace(state, PendingState::Replied(Box::new(msg))) {
+                    PendingState::Waiting(waker) => waker,
+                    PendingState::Replied(_) => unreachable!(),
+                }
+            }
+            _ => {
+                debug!("ReplyBroker: Dropping reply for CorrelationId({}).", id);
+                self.dropped_replies.fetch_add(1, Ordering::SeqCst);
+                return Err(msg);
+            }
+        };
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+
+        Ok(())
+    }
+
+    /// Waits for the reply to the message sent with the given
+    /// correlation id, or until `timeout` expires.
+    ///
+    /// The reply can only be retrieved once, and the correlation
+    /// id is forgotten once this method returns.
+    ///
+    /// This method returns a [`Future`] resolving to the reply
+    /// if it was received, or a [`ReplyError`] otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The correlation id returned when sending the
+    ///   message using [`ChildRef::tell_correlated`].
+    /// * `timeout` - How long to wait for the reply.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use bastion::correlation::ReplyError;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| {
+    ///     #     children.with_exec(|ctx: BastionContext| async move {
+    ///     #         loop {
+    ///     #             ctx.recv().await?;
+    ///     #         }
+    ///     #     })
+    ///     # }).unwrap();
+    ///     # Bastion::start();
+    ///     # let child_ref = &children_ref.elems()[0];
+    /// // Nobody is going to reply to this message...
+    /// let id = child_ref.tell_correlated("Ping").unwrap();
+    ///
+    /// let reply = run!(Bastion::reply_broker().wait(&id, Duration::from_millis(10)));
+    /// assert_eq!(reply.unwrap_err(), ReplyError::Timeout);
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+    /// [`ReplyError`]: enum.ReplyError.html
+    /// [`ChildRef::tell_correlated`]: ../child_ref/struct.ChildRef.html#method.tell_correlated
+    pub async fn wait(
+        &self,
+        id: &CorrelationId,
+        timeout: Duration,
+    ) -> Result<SignedMessage, ReplyError> {
+        let reply = WaitReply {
+            broker: self,
+            id: *id,
+        };
+
+        match future::select(reply, Delay::new(timeout)).await {
+            Either::Left((reply, _)) => reply,
+            Either::Right(_) => {
+                // FIXME: panics?
+                let expired = self.pending.lock().unwrap().remove(id);
+                match expired {
+                    // The reply was received right before the timeout expired.
+                    Some(Pending {
+                        state: PendingState::Replied(msg),
+                        ..
+                    }) => Ok(*msg),
+                    _ => {
+                        debug!("ReplyBroker: CorrelationId({}) expired.", id);
+                        Err(ReplyError::Timeout)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the number of replies that were dropped because
+    /// their correlation id wasn't waiting for a reply anymore
+    /// (because it already received one, its reply was already
+    /// retrieved or it expired) or was unknown.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let dropped_replies: usize = Bastion::reply_broker().dropped_replies();
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn dropped_replies(&self) -> usize {
+        self.dropped_replies.load(Ordering::SeqCst)
+    }
+}
+
+impl Future for WaitReply<'_> {
+    type Output = Result<SignedMessage, ReplyError>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        // FIXME: panics?
+        let mut pending = self.broker.pending.lock().unwrap();
+        match pending.get_mut(&self.id) {
+            Some(Pending {
+                state: PendingState::Waiting(waker),
+                ..
+            }) => {
+                *waker = Some(ctx.waker().clone());
+                Poll::Pending
+            }
+            Some(Pending {
+                state: PendingState::Replied(_),
+                ..
+            }) => match pending.remove(&self.id) {
+                Some(Pending {
+                    state: PendingState::Replied(msg),
+                    ..
+                }) => Poll::Ready(Ok(*msg)),
+                _ => unreachable!(),
+            },
+            None => Poll::Ready(Err(ReplyError::Unknown(self.id))),
+        }
+    }
+}
+
+impl Display for CorrelationId {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        self.0.fmt(fmt)
+    }
+}
+
+impl Display for ReplyError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            ReplyError::Timeout => fmt.write_str("no reply was received before the timeout"),
+            ReplyError::Unknown(id) => write!(fmt, "unknown correlation id: {}", id),
+        }
+    }
+}
+
+impl Error for ReplyError {}
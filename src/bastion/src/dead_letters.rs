@@ -0,0 +1,458 @@
+//!
+//! Dead letters, the messages sent to senders that aren't identified
+//! (e.g. the replies to messages that were told anonymously) or that
+//! couldn't be delivered to their recipient (e.g. because it stopped
+//! in the meantime), which are received by the system's dead-letters
+//! group and passed to the handler set using
+//! [`Config::with_dead_letter_handler`].
+//!
+//! Note that the messages that couldn't be sent are only sent as
+//! dead letters if they would have been lost otherwise: the methods
+//! failing to send a message (e.g. [`ChildRef::tell_anonymously`])
+//! return it in their error instead.
+//!
+//! Depending on the [`DeadLetterMode`] set using
+//! [`Config::dead_letter_mode`], the dead letters carry their full
+//! payload or only a summary of it, the payload being dropped as
+//! soon as it is sent so that the dead letters waiting to be
+//! handled don't keep it.
+//!
+//! [`Config::with_dead_letter_handler`]: ../struct.Config.html#method.with_dead_letter_handler
+//! [`DeadLetterMode`]: enum.DeadLetterMode.html
+//! [`Config::dead_letter_mode`]: ../struct.Config.html#method.dead_letter_mode
+//! [`ChildRef::tell_anonymously`]: ../child_ref/struct.ChildRef.html#method.tell_anonymously
+use crate::context::BastionId;
+use crate::correlation::CorrelationId;
+use crate::envelope::{Envelope, RefAddr, SignedMessage};
+use crate::message::{BastionMessage, Message, Msg};
+use crate::provenance::ProvenanceEntry;
+use crate::system::SYSTEM;
+use std::any::{type_name, Any, TypeId};
+use std::fmt::{self, Debug, Formatter};
+use std::mem;
+use std::sync::Arc;
+use std::time::Duration;
+
+type Serializer = Arc<dyn Fn(&dyn Any) -> Vec<u8> + Send + Sync>;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// What the dead letters carry (see
+/// [`Config::dead_letter_mode`]).
+///
+/// [`Config::dead_letter_mode`]: ../struct.Config.html#method.dead_letter_mode
+pub enum DeadLetterMode {
+    /// The dead letters carry their full payload.
+    Full,
+    /// The dead letters only carry a [`DeadLetterSummary`] of their
+    /// payload, which is dropped as soon as it is sent.
+    ///
+    /// [`DeadLetterSummary`]: struct.DeadLetterSummary.html
+    SummaryOnly {
+        /// The maximum number of bytes of the serialized prefix
+        /// kept for the payloads of a type with a serializer (see
+        /// [`Config::with_dead_letter_serializer`]).
+        ///
+        /// [`Config::with_dead_letter_serializer`]: ../struct.Config.html#method.with_dead_letter_serializer
+        max_payload_bytes: usize,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// Why a message was sent as a [`DeadLetter`].
+///
+/// [`DeadLetter`]: struct.DeadLetter.html
+pub enum DeadLetterReason {
+    /// The message was sent to a sender that wasn't identified
+    /// (e.g. as a reply to a message that was told anonymously).
+    Unidentified,
+    /// The message's recipient stopped before it could be
+    /// delivered.
+    RecipientStopped,
+    /// The message was dropped because the bounded mailbox of its
+    /// recipient was full (see [`OverflowPolicy::DropNewest`] and
+    /// [`OverflowPolicy::DropOldest`]).
+    ///
+    /// [`OverflowPolicy::DropNewest`]: ../mailbox/enum.OverflowPolicy.html#variant.DropNewest
+    /// [`OverflowPolicy::DropOldest`]: ../mailbox/enum.OverflowPolicy.html#variant.DropOldest
+    MailboxFull,
+    /// The message was rejected by its recipient (see
+    /// [`BastionContext::reject_message`]), and couldn't be
+    /// rerouted or sent back to its sender.
+    ///
+    /// [`BastionContext::reject_message`]: ../context/struct.BastionContext.html#method.reject_message
+    Rejected,
+    /// The message was skipped by its recipient's actor, which took
+    /// too long to handle it (see [`Children::with_message_timeout`]).
+    ///
+    /// The message itself was dropped along with its handling, the
+    /// dead letter only carrying a summary of it.
+    ///
+    /// [`Children::with_message_timeout`]: ../children/struct.Children.html#method.with_message_timeout
+    TimedOut {
+        /// The name of the message's type.
+        type_name: &'static str,
+        /// The id the message was sent with, if it was sent using
+        /// [`ChildRef::tell_correlated`].
+        ///
+        /// [`ChildRef::tell_correlated`]: ../child_ref/struct.ChildRef.html#method.tell_correlated
+        correlation_id: Option<CorrelationId>,
+        /// How long the message was handled for (the last time, if
+        /// it was retried) before its handling was cancelled.
+        elapsed: Duration,
+    },
+}
+
+#[derive(Debug)]
+/// A message received by the system's dead-letters group, passed
+/// to the handler set using [`Config::with_dead_letter_handler`].
+///
+/// [`Config::with_dead_letter_handler`]: ../struct.Config.html#method.with_dead_letter_handler
+pub struct DeadLetter {
+    sender: RefAddr,
+    recipient: Option<BastionId>,
+    reason: DeadLetterReason,
+    provenance: Vec<ProvenanceEntry>,
+    payload: DeadLetterPayload,
+}
+
+#[derive(Debug)]
+/// The payload of a [`DeadLetter`], depending on the
+/// [`DeadLetterMode`] of the system when it was sent.
+///
+/// [`DeadLetter`]: struct.DeadLetter.html
+/// [`DeadLetterMode`]: enum.DeadLetterMode.html
+pub enum DeadLetterPayload {
+    /// The message that was sent.
+    Full(Msg),
+    /// A summary of the message that was sent, which was dropped.
+    Summary(DeadLetterSummary),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// A summary of a message sent as a dead letter while the system
+/// used [`DeadLetterMode::SummaryOnly`], or that was dropped before
+/// being sent as one (see [`DeadLetterReason::TimedOut`]).
+///
+/// [`DeadLetterMode::SummaryOnly`]: enum.DeadLetterMode.html#variant.SummaryOnly
+/// [`DeadLetterReason::TimedOut`]: enum.DeadLetterReason.html#variant.TimedOut
+pub struct DeadLetterSummary {
+    type_name: &'static str,
+    size: usize,
+    prefix: Option<Vec<u8>>,
+    truncated: bool,
+}
+
+#[derive(Debug)]
+// What a dead letter is replaced with when it wasn't sent to the
+// dead letters directly or is summarized.
+pub(crate) struct Bounced {
+    recipient: Option<BastionId>,
+    reason: DeadLetterReason,
+    // The provenance of the original message, if it isn't the one
+    // this is sent with.
+    provenance: Option<Vec<ProvenanceEntry>>,
+    payload: DeadLetterPayload,
+}
+
+#[derive(Debug)]
+// What is kept of a message before it is handled, to send it to
+// the dead letters if it is dropped while being handled.
+pub(crate) struct DroppedMsg {
+    sign: RefAddr,
+    provenance: Vec<ProvenanceEntry>,
+    summary: DeadLetterSummary,
+}
+
+#[derive(Default, Clone)]
+// The serializers used to summarize dead letters, by type.
+pub(crate) struct Serializers(Vec<(TypeId, Serializer)>);
+
+#[derive(Clone)]
+pub(crate) struct Handler(Arc<dyn Fn(DeadLetter) + Send + Sync>);
+
+#[derive(Debug, Clone)]
+// How a system using `DeadLetterMode::SummaryOnly` summarizes
+// its dead letters.
+pub(crate) struct Summarizer {
+    max_payload_bytes: usize,
+    serializers: Serializers,
+}
+
+impl DeadLetter {
+    pub(crate) fn new(smsg: SignedMessage) -> Self {
+        let (msg, sender) = smsg.extract();
+        let sent_with = msg.provenance().to_vec();
+        match msg.downcast::<Bounced>() {
+            Ok(bounced) => DeadLetter {
+                sender,
+                recipient: bounced.recipient,
+                reason: bounced.reason,
+                provenance: bounced.provenance.unwrap_or(sent_with),
+                payload: bounced.payload,
+            },
+            Err(msg) => DeadLetter {
+                sender,
+                recipient: None,
+                reason: DeadLetterReason::Unidentified,
+                provenance: sent_with,
+                payload: DeadLetterPayload::Full(msg),
+            },
+        }
+    }
+
+    /// Returns the signature of the dead letter's sender.
+    pub fn sender(&self) -> &RefAddr {
+        &self.sender
+    }
+
+    /// Returns the id of the element the dead letter was meant
+    /// for, if it wasn't sent to a sender that isn't identified.
+    pub fn recipient(&self) -> Option<&BastionId> {
+        self.recipient.as_ref()
+    }
+
+    /// Returns why the message was sent as a dead letter.
+    pub fn reason(&self) -> DeadLetterReason {
+        self.reason
+    }
+
+    /// Returns the provenance chain of the dead letter (see
+    /// [`Msg::provenance`]).
+    ///
+    /// [`Msg::provenance`]: ../message/struct.Msg.html#method.provenance
+    pub fn provenance(&self) -> &[ProvenanceEntry] {
+        &self.provenance
+    }
+
+    /// Returns whether the dead letter only carries a summary of
+    /// its payload.
+    pub fn is_summary(&self) -> bool {
+        match self.payload {
+            DeadLetterPayload::Summary(_) => true,
+            DeadLetterPayload::Full(_) => false,
+        }
+    }
+
+    /// Returns the payload of the dead letter.
+    pub fn payload(&self) -> &DeadLetterPayload {
+        &self.payload
+    }
+
+    /// Returns the payload of the dead letter, consuming it.
+    pub fn into_payload(self) -> DeadLetterPayload {
+        self.payload
+    }
+}
+
+impl DroppedMsg {
+    pub(crate) fn new(smsg: &SignedMessage) -> Self {
+        let msg = &smsg.msg;
+        let summary = DeadLetterSummary {
+            type_name: msg.type_name(),
+            size: mem::size_of_val(msg.payload()),
+            prefix: None,
+            truncated: false,
+        };
+
+        DroppedMsg {
+            sign: smsg.sign.clone(),
+            provenance: msg.provenance().to_vec(),
+            summary,
+        }
+    }
+}
+
+impl DeadLetterSummary {
+    /// Returns the name of the type of the payload.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns the size of the payload, in bytes: the size of its
+    /// serialized form if its type has a serializer, or the size
+    /// of its value otherwise (not including the memory it owns).
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the prefix of the serialized payload if its type
+    /// has a serializer, at most `max_payload_bytes` long.
+    pub fn prefix(&self) -> Option<&[u8]> {
+        self.prefix.as_deref()
+    }
+
+    /// Returns whether the prefix is shorter than the serialized
+    /// payload.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+impl Serializers {
+    pub(crate) fn register<T, F>(&mut self, serializer: F)
+    where
+        T: Message,
+        F: Fn(&T) -> Vec<u8> + Send + Sync + 'static,
+    {
+        let id = TypeId::of::<T>();
+        self.0.retain(|(registered, _)| *registered != id);
+        let serializer = move |msg: &dyn Any| serializer(msg.downcast_ref().unwrap());
+        self.0.push((id, Arc::new(serializer)));
+    }
+
+    // Returns whether both have a serializer for the same types
+    // (whatever the serializers are).
+    pub(crate) fn same_types(&self, other: &Serializers) -> bool {
+        self.0.len() == other.0.len()
+            && self
+                .0
+                .iter()
+                .all(|(id, _)| other.0.iter().any(|(other, _)| other == id))
+    }
+
+    fn serialize(&self, msg: &dyn Any) -> Option<Vec<u8>> {
+        let id = msg.type_id();
+        self.0
+            .iter()
+            .find(|(registered, _)| *registered == id)
+            .map(|(_, serializer)| serializer(msg))
+    }
+}
+
+impl Handler {
+    pub(crate) fn new<F>(handler: F) -> Self
+    where
+        F: Fn(DeadLetter) + Send + Sync + 'static,
+    {
+        Handler(Arc::new(handler))
+    }
+
+    pub(crate) fn handle(&self, letter: DeadLetter) {
+        (self.0)(letter)
+    }
+}
+
+impl Summarizer {
+    pub(crate) fn new(mode: DeadLetterMode, serializers: &Serializers) -> Option<Self> {
+        match mode {
+            DeadLetterMode::Full => None,
+            DeadLetterMode::SummaryOnly { max_payload_bytes } => Some(Summarizer {
+                max_payload_bytes,
+                serializers: serializers.clone(),
+            }),
+        }
+    }
+
+    fn summarize(&self, type_name: &'static str, msg: &dyn Any) -> DeadLetterSummary {
+        let serialized = self.serializers.serialize(msg);
+        let size = match &serialized {
+            Some(serialized) => serialized.len(),
+            None => mem::size_of_val(msg),
+        };
+
+        let prefix = serialized.map(|serialized| {
+            let len = serialized.len().min(self.max_payload_bytes);
+            serialized[..len].to_vec()
+        });
+        let truncated = match &prefix {
+            Some(prefix) => prefix.len() < size,
+            None => false,
+        };
+
+        DeadLetterSummary {
+            type_name,
+            size,
+            prefix,
+            truncated,
+        }
+    }
+}
+
+// Returns the summary replacing `msg` if it is sent to the dead
+// letters and the system summarizes them.
+pub(crate) fn summarize<M: Message>(to: &RefAddr, msg: &M) -> Option<Bounced> {
+    let summarizer = SYSTEM.summarizer()?;
+    if !to.sender().same_receiver(SYSTEM.dead_letters().sender()) {
+        return None;
+    }
+
+    let summary = summarizer.summarize(type_name::<M>(), msg);
+    Some(Bounced {
+        recipient: None,
+        reason: DeadLetterReason::Unidentified,
+        provenance: None,
+        payload: DeadLetterPayload::Summary(summary),
+    })
+}
+
+// Sends the message `env` carries (if it isn't an internal one)
+// to the dead letters, because it couldn't be delivered to
+// `recipient`.
+pub(crate) fn bounce(env: Envelope, recipient: Option<&BastionId>, reason: DeadLetterReason) {
+    let (msg, sign) = match env {
+        Envelope {
+            msg: BastionMessage::Message(msg),
+            sign,
+            ..
+        } => (msg, sign),
+        _ => return,
+    };
+
+    debug!("Bouncing message ({:?}): {:?}", reason, msg);
+    let provenance = Some(msg.provenance().to_vec());
+    let payload = match SYSTEM.summarizer() {
+        Some(summarizer) => {
+            DeadLetterPayload::Summary(summarizer.summarize(msg.type_name(), msg.payload()))
+        }
+        None => DeadLetterPayload::Full(msg),
+    };
+
+    let bounced = Bounced {
+        recipient: recipient.cloned(),
+        reason,
+        provenance,
+        payload,
+    };
+    let env = Envelope::new_with_sign(BastionMessage::tell(bounced), sign);
+    // FIXME: handle errors
+    SYSTEM.dead_letters().send(env).ok();
+}
+
+// Sends a summary of a message that `recipient` dropped while
+// handling it (e.g. because it timed out) to the dead letters.
+pub(crate) fn bounce_dropped(
+    dropped: DroppedMsg,
+    recipient: Option<&BastionId>,
+    reason: DeadLetterReason,
+) {
+    debug!("Bouncing dropped message ({:?}): {:?}", reason, dropped);
+    let bounced = Bounced {
+        recipient: recipient.cloned(),
+        reason,
+        provenance: Some(dropped.provenance),
+        payload: DeadLetterPayload::Summary(dropped.summary),
+    };
+    let env = Envelope::new_with_sign(BastionMessage::tell(bounced), dropped.sign);
+    // FIXME: handle errors
+    SYSTEM.dead_letters().send(env).ok();
+}
+
+impl Default for DeadLetterMode {
+    fn default() -> Self {
+        DeadLetterMode::Full
+    }
+}
+
+impl Debug for Serializers {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Serializers")
+            .field("len", &self.0.len())
+            .finish()
+    }
+}
+
+impl Debug for Handler {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Handler").finish()
+    }
+}
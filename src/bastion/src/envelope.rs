@@ -2,18 +2,31 @@
 //! A envelope wraps messages and signs them to help user identify message senders
 //! and instruct Bastion how to send messages back to them
 
+use crate::ack::AckToken;
+use crate::answer_stream::AnswerStreamSender;
 use crate::broadcast::Sender;
+use crate::child_ref::ChildRef;
+use crate::mailbox::{MailboxSlot, PendingAnswers};
 use crate::message::{BastionMessage, Message, Msg};
 use crate::path::BastionPath;
 use crate::system::SYSTEM;
+use std::mem;
 use std::sync::Arc;
 
 #[derive(Debug)]
 pub(crate) struct Envelope {
     pub(crate) msg: BastionMessage,
     pub(crate) sign: RefAddr,
+    // The place the message takes in its recipient's mailbox, if
+    // it is bounded.
+    pub(crate) slot: Option<MailboxSlot>,
 }
 
+// Envelopes are moved along with each message (and given back along
+// with the ones that couldn't be sent), so the large messages (e.g.
+// the deployments) are boxed.
+const _: () = assert!(mem::size_of::<Envelope>() <= 320);
+
 #[derive(Debug)]
 /// A struct containing a message and its sender signature
 ///
@@ -83,6 +96,77 @@ impl SignedMessage {
     pub fn signature(&self) -> &RefAddr {
         &self.sign
     }
+
+    /// Returns a [`ChildRef`] referencing the element that sent
+    /// the message, or `None` if it wasn't sent by an element using
+    /// its [`BastionContext`] (e.g. if it was sent from outside of
+    /// any element, or using [`ChildRef::tell_anonymously`]). See
+    /// [`RefAddr::origin`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             let msg: SignedMessage = ctx.recv().await?;
+    ///             if let Some(sender) = msg.sender() {
+    ///                 println!("received message from {}", sender.id());
+    ///             }
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildRef`]: ../child_ref/struct.ChildRef.html
+    /// [`BastionContext`]: ../context/struct.BastionContext.html
+    /// [`ChildRef::tell_anonymously`]: ../child_ref/struct.ChildRef.html#method.tell_anonymously
+    /// [`RefAddr::origin`]: struct.RefAddr.html#method.origin
+    pub fn sender(&self) -> Option<&ChildRef> {
+        self.sign.origin()
+    }
+
+    /// Returns the token to pass to [`BastionContext::ack`] once
+    /// the message is applied, if it was broadcasted using
+    /// [`ChildrenRef::broadcast_acked`] (see [`Msg::ack_token`]).
+    ///
+    /// [`BastionContext::ack`]: ../context/struct.BastionContext.html#method.ack
+    /// [`ChildrenRef::broadcast_acked`]: ../children_ref/struct.ChildrenRef.html#method.broadcast_acked
+    /// [`Msg::ack_token`]: ../message/struct.Msg.html#method.ack_token
+    pub fn ack_token(&self) -> Option<AckToken> {
+        self.msg.ack_token()
+    }
+
+    /// Takes the sender allowing to reply several times to the
+    /// message, if it was sent using [`ChildRef::ask_stream`] (see
+    /// [`Msg::take_stream_sender`]).
+    ///
+    /// [`ChildRef::ask_stream`]: ../child_ref/struct.ChildRef.html#method.ask_stream
+    /// [`Msg::take_stream_sender`]: ../message/struct.Msg.html#method.take_stream_sender
+    pub fn take_stream_sender(&mut self) -> Option<AnswerStreamSender> {
+        self.msg.take_stream_sender()
+    }
+
+    /// Returns a reference to the message, without consuming it,
+    /// e.g. to downcast it using [`Msg::downcast_ref`] and decide
+    /// whether to reject it using [`BastionContext::reject_message`].
+    ///
+    /// [`Msg::downcast_ref`]: ../message/struct.Msg.html#method.downcast_ref
+    /// [`BastionContext::reject_message`]: ../context/struct.BastionContext.html#method.reject_message
+    pub fn msg(&self) -> &Msg {
+        &self.msg
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -116,11 +200,40 @@ impl SignedMessage {
 pub struct RefAddr {
     path: Arc<BastionPath>,
     sender: Sender,
+    // The element signing using this address, if it was signed
+    // using `BastionContext::signature`.
+    origin: Option<ChildRef>,
+    // The questions waiting for the answer of the element this
+    // address is the one of, if its group bounds them.
+    pending_answers: Option<Arc<PendingAnswers>>,
 }
 
 impl RefAddr {
     pub(crate) fn new(path: Arc<BastionPath>, sender: Sender) -> Self {
-        RefAddr { path, sender }
+        RefAddr {
+            path,
+            sender,
+            origin: None,
+            pending_answers: None,
+        }
+    }
+
+    pub(crate) fn with_origin(mut self, origin: ChildRef) -> Self {
+        self.pending_answers = origin.pending_answers().cloned();
+        self.origin = Some(origin);
+        self
+    }
+
+    pub(crate) fn with_pending_answers(
+        mut self,
+        pending_answers: Option<Arc<PendingAnswers>>,
+    ) -> Self {
+        self.pending_answers = pending_answers;
+        self
+    }
+
+    pub(crate) fn pending_answers(&self) -> Option<&Arc<PendingAnswers>> {
+        self.pending_answers.as_ref()
     }
 
     pub(crate) fn dead_letters() -> Self {
@@ -130,7 +243,8 @@ impl RefAddr {
         )
     }
 
-    /// Checks whether the sender is identified.
+    /// Checks whether the sender is identified, i.e. whether the
+    /// message was signed by an element (see [`RefAddr::origin`]).
     /// Usually anonymous sender means messages sent by
     /// [broadcast][crate::Bastion::broadcast()] and it's other methods implied to
     /// be called outside of the Bastion context.
@@ -143,34 +257,37 @@ impl RefAddr {
     /// # fn main() {
     ///     # Bastion::init();
     ///     #
-    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             msg! { ctx.recv().await?,
+    ///                 ref msg: &'static str => {
+    ///                     // The message was sent from outside of any
+    ///                     // element.
+    ///                     assert!(!signature!().is_sender_identified());
+    ///                 };
+    ///                 // We are only sending a `&'static str` in this
+    ///                 // example, so we know that this won't happen...
+    ///                 _: _ => ();
+    ///             }
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///
     /// let msg = "A message containing data.";
     /// children_ref.broadcast(msg).expect("Couldn't send the message.");
-    ///
-    ///     # Bastion::children(|children| {
-    ///         # children.with_exec(|ctx: BastionContext| {
-    ///             # async move {
-    /// msg! { ctx.recv().await?,
-    ///     ref msg: &'static str => {
-    ///         assert!(signature!().is_sender_identified());
-    ///     };
-    ///     // We are only sending a `&'static str` in this
-    ///     // example, so we know that this won't happen...
-    ///     _: _ => ();
-    /// }
-    ///                 #
-    ///                 # Ok(())
-    ///             # }
-    ///         # })
-    ///     # }).unwrap();
     ///     #
     ///     # Bastion::start();
     ///     # Bastion::stop();
     ///     # Bastion::block_until_stopped();
     /// # }
     /// ```
+    ///
+    /// [`RefAddr::origin`]: struct.RefAddr.html#method.origin
     pub fn is_sender_identified(&self) -> bool {
-        self.path.is_dead_letters()
+        self.origin.is_some()
     }
 
     /// Returns `BastionPath` of a sender
@@ -214,6 +331,50 @@ impl RefAddr {
         &self.path
     }
 
+    /// Returns a [`ChildRef`] referencing the element that signed
+    /// using this `RefAddr`, if it was an element signing using its
+    /// [`BastionContext`] (which [`BastionContext::tell`],
+    /// [`BastionContext::ask`] and the [`answer!`] macro do), or
+    /// `None` if it was signed from outside of any element.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             let (_, sign) = ctx.recv().await?.extract();
+    ///             match sign.origin() {
+    ///                 // The message was sent by an element...
+    ///                 Some(sender) => sender.tell_anonymously("reply").unwrap(),
+    ///                 // ...or from outside of any element.
+    ///                 None => (),
+    ///             }
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildRef`]: ../child_ref/struct.ChildRef.html
+    /// [`BastionContext`]: ../context/struct.BastionContext.html
+    /// [`BastionContext::tell`]: ../context/struct.BastionContext.html#method.tell
+    /// [`BastionContext::ask`]: ../context/struct.BastionContext.html#method.ask
+    /// [`answer!`]: ../macro.answer.html
+    pub fn origin(&self) -> Option<&ChildRef> {
+        self.origin.as_ref()
+    }
+
     pub(crate) fn sender(&self) -> &Sender {
         &self.sender
     }
@@ -224,17 +385,23 @@ impl Envelope {
         Envelope {
             msg,
             sign: RefAddr::new(path, sender),
+            slot: None,
         }
     }
 
     pub(crate) fn new_with_sign(msg: BastionMessage, sign: RefAddr) -> Self {
-        Envelope { msg, sign }
+        Envelope {
+            msg,
+            sign,
+            slot: None,
+        }
     }
 
     pub(crate) fn from_dead_letters(msg: BastionMessage) -> Self {
         Envelope {
             msg,
             sign: RefAddr::dead_letters(),
+            slot: None,
         }
     }
 
@@ -242,6 +409,7 @@ impl Envelope {
         self.msg.try_clone().map(|msg| Envelope {
             msg,
             sign: self.sign.clone(),
+            slot: None,
         })
     }
 
@@ -0,0 +1,831 @@
+//!
+//! The errors returned by the fallible parts of the public API.
+//!
+//! Every error implements [`std::error::Error`] (and is `Send`,
+//! `Sync` and `'static` as long as the message it carries is), and
+//! can be converted into a [`BastionError`].
+//!
+//! # Migrating from `Err(())` and `Err(msg)`
+//!
+//! - The methods sending a message (e.g. [`ChildRef::tell_anonymously`]
+//!   or [`ChildrenRef::broadcast`]) now return a [`SendError`]
+//!   instead of the message itself, which can still be retrieved
+//!   using [`SendError::into_inner_msg`].
+//! - The methods creating supervisors or children groups (e.g.
+//!   [`Bastion::children`]) now return a [`BuilderError`].
+//! - The methods stopping or killing an element (e.g.
+//!   [`ChildrenRef::stop`]) now return a [`ShutdownError`].
+//! - [`Answer`] now resolves to an [`AnswerError`] if the message
+//!   was dropped without being answered.
+//! - [`BastionContext::recv_timeout`] returns a [`ReceiveError`]
+//!   if no message was received in time.
+//!
+//! The futures returned by the closures passed to
+//! [`Children::with_exec`] still return a `Result<(), ()>`, so all
+//! those errors convert into `()`, allowing to keep using the `?`
+//! operator on them. Those passed to [`Children::with_exec_err`]
+//! can return any error instead, which is reported as an
+//! [`ExecError`] when the element faults.
+//!
+//! [`std::error::Error`]: https://doc.rust-lang.org/std/error/trait.Error.html
+//! [`BastionError`]: enum.BastionError.html
+//! [`ChildRef::tell_anonymously`]: ../child_ref/struct.ChildRef.html#method.tell_anonymously
+//! [`ChildrenRef::broadcast`]: ../children_ref/struct.ChildrenRef.html#method.broadcast
+//! [`SendError`]: struct.SendError.html
+//! [`SendError::into_inner_msg`]: struct.SendError.html#method.into_inner_msg
+//! [`Bastion::children`]: ../struct.Bastion.html#method.children
+//! [`BuilderError`]: enum.BuilderError.html
+//! [`ChildrenRef::stop`]: ../children_ref/struct.ChildrenRef.html#method.stop
+//! [`ShutdownError`]: enum.ShutdownError.html
+//! [`Answer`]: ../message/struct.Answer.html
+//! [`AnswerError`]: enum.AnswerError.html
+//! [`BastionContext::recv_timeout`]: ../context/struct.BastionContext.html#method.recv_timeout
+//! [`ReceiveError`]: enum.ReceiveError.html
+//! [`Children::with_exec`]: ../children/struct.Children.html#method.with_exec
+//! [`Children::with_exec_err`]: ../children/struct.Children.html#method.with_exec_err
+//! [`ExecError`]: struct.ExecError.html
+use crate::blocking_bridge::BridgeError;
+use crate::children_ref::{MigrationError, ReduceError};
+use crate::config::ReloadError;
+use crate::correlation::ReplyError;
+use crate::metrics::MetricError;
+use crate::routing::RoutingError;
+use crate::state_cell::StateError;
+use crate::sync::BarrierError;
+use std::any::Any;
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// Any of the errors returned by the public API, for the
+/// applications that don't need to tell them apart.
+///
+/// Note that the message carried by a [`SendError`] is dropped
+/// when it is converted into a `BastionError`.
+///
+/// [`SendError`]: struct.SendError.html
+pub enum BastionError {
+    /// A message couldn't be sent.
+    Send(SendError<()>),
+    /// A supervisor, children group or element couldn't be
+    /// created.
+    Builder(BuilderError),
+    /// An element couldn't be stopped or killed.
+    Shutdown(ShutdownError),
+    /// A message wasn't answered.
+    Answer(AnswerError),
+    /// A message wasn't received.
+    Receive(ReceiveError),
+    /// A correlated message wasn't replied to.
+    Reply(ReplyError),
+    /// An element couldn't be migrated.
+    Migration(MigrationError),
+    /// A children group's routing couldn't be changed.
+    Routing(RoutingError),
+    /// Some elements didn't answer a message asked to their
+    /// group to be reduced.
+    Reduce(ReduceError<()>),
+    /// A barrier couldn't wait for all its participants.
+    Barrier(BarrierError),
+    /// A message asked from synchronous code wasn't answered.
+    Bridge(BridgeError),
+    /// The state of a state cell couldn't be accessed.
+    State(StateError),
+    /// A metric couldn't be registered.
+    Metric(MetricError),
+    /// The configuration couldn't be reloaded.
+    Reload(ReloadError),
+    /// The system wasn't initialized using [`Bastion::init`].
+    ///
+    /// [`Bastion::init`]: ../struct.Bastion.html#method.init
+    NotInitialized,
+    /// The system is stopping or stopped, and wasn't initialized
+    /// again since then.
+    ShuttingDown,
+}
+
+#[derive(Clone, Eq, PartialEq)]
+/// The error returned when a message couldn't be sent, which
+/// carries the message back.
+pub struct SendError<M> {
+    kind: SendErrorKind,
+    msg: M,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// The reasons why a message couldn't be sent.
+pub enum SendErrorKind {
+    /// The recipient stopped (or the sender of a message waiting
+    /// to be answered stopped waiting for it).
+    Stopped,
+    /// There isn't any recipient to send the message to (e.g.
+    /// the pipeline doesn't have any stage).
+    NoRecipient,
+    /// Too many messages are waiting for a reply.
+    Full,
+    /// The correlation id the reply was sent for is unknown (it
+    /// was already replied to or stopped being waited for).
+    UnknownCorrelation,
+    /// The recipient's children group doesn't accept messages of
+    /// this type (see [`Children::with_accepted_types`], the types
+    /// it accepts being returned by [`GroupConfig::accepted_types`]).
+    ///
+    /// [`Children::with_accepted_types`]: ../children/struct.Children.html#method.with_accepted_types
+    /// [`GroupConfig::accepted_types`]: ../children/struct.GroupConfig.html#method.accepted_types
+    TypeNotAccepted {
+        /// The name of the message's type.
+        got: &'static str,
+    },
+    /// The recipient's mailbox is full (see
+    /// [`Children::with_mailbox_capacity`]).
+    ///
+    /// [`Children::with_mailbox_capacity`]: ../children/struct.Children.html#method.with_mailbox_capacity
+    MailboxFull,
+    /// The recipient has as many questions waiting to be answered
+    /// as its children group allows (see
+    /// [`Children::with_max_pending_answers`]).
+    ///
+    /// [`Children::with_max_pending_answers`]: ../children/struct.Children.html#method.with_max_pending_answers
+    TooManyPending,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// The reasons why a supervisor, children group or element
+/// couldn't be created.
+pub enum BuilderError {
+    /// The supervisor or children group that should have
+    /// supervised it stopped.
+    ParentStopped,
+    /// Another children group that is still running was
+    /// created with the same name.
+    NameTaken,
+    /// The system wasn't initialized using [`Bastion::init`].
+    ///
+    /// [`Bastion::init`]: ../struct.Bastion.html#method.init
+    NotInitialized,
+    /// The system is stopping or stopped, and wasn't initialized
+    /// again since then.
+    ShuttingDown,
+    /// The thread a bridge created using
+    /// [`bridge::from_blocking_iter`] iterates over its source from
+    /// couldn't be spawned.
+    ///
+    /// [`bridge::from_blocking_iter`]: ../bridge/fn.from_blocking_iter.html
+    ThreadSpawn,
+    /// Some of the settings of the children group conflict with
+    /// each other, which is only checked once the closure
+    /// configuring it returned.
+    Conflict(BuilderConflict),
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// The settings of a children group that conflict with each other,
+/// making [`Bastion::children`] (or [`SupervisorRef::children`])
+/// fail with [`BuilderError::Conflict`].
+///
+/// The methods whose settings conflict are returned by
+/// [`methods`].
+///
+/// [`Bastion::children`]: ../struct.Bastion.html#method.children
+/// [`SupervisorRef::children`]: ../supervisor/struct.SupervisorRef.html#method.children
+/// [`BuilderError::Conflict`]: enum.BuilderError.html#variant.Conflict
+/// [`methods`]: #method.methods
+pub enum BuilderConflict {
+    /// An overflow policy was set using
+    /// [`Children::with_overflow_policy`] while the elements'
+    /// mailboxes are unbounded.
+    ///
+    /// [`Children::with_overflow_policy`]: ../children/struct.Children.html#method.with_overflow_policy
+    OverflowWithoutCapacity,
+    /// The type of the messages batched using
+    /// [`Children::with_batching`] isn't one of the types set
+    /// using [`Children::with_accepted_types`].
+    ///
+    /// [`Children::with_batching`]: ../children/struct.Children.html#method.with_batching
+    /// [`Children::with_accepted_types`]: ../children/struct.Children.html#method.with_accepted_types
+    BatchedTypeNotAccepted {
+        /// The name of the batched type.
+        batched: &'static str,
+    },
+    /// The group routes its messages by key (see
+    /// [`Children::with_routing`]) without a key function being
+    /// set using [`Children::with_routing_key`].
+    ///
+    /// [`Children::with_routing`]: ../children/struct.Children.html#method.with_routing
+    /// [`Children::with_routing_key`]: ../children/struct.Children.html#method.with_routing_key
+    MissingRoutingKey,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// The reasons why an element couldn't be stopped or killed.
+pub enum ShutdownError {
+    /// The element already stopped.
+    AlreadyStopped,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// The reasons why an [`Answer`] couldn't resolve to the answer
+/// to its message.
+///
+/// [`Answer`]: ../message/struct.Answer.html
+pub enum AnswerError {
+    /// The message was dropped without being answered (e.g.
+    /// because its recipient stopped).
+    Unanswered,
+    /// The message wasn't answered before the timeout expired.
+    TimedOut,
+    /// The answer wasn't of the type it was expected to be of
+    /// (see [`Answer::await_typed`]).
+    ///
+    /// [`Answer::await_typed`]: ../message/struct.Answer.html#method.await_typed
+    UnexpectedType {
+        /// The name of the type the answer was expected to be of.
+        expected: &'static str,
+        /// The name of the answer's type.
+        got: &'static str,
+    },
+    /// [`Answer::wait`] was called from one of the executor's
+    /// worker threads, which must not be blocked (e.g. from an
+    /// element's future). The answer can be awaited instead.
+    ///
+    /// [`Answer::wait`]: ../message/struct.Answer.html#method.wait
+    WouldBlockExecutor,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// The reasons why an element couldn't receive a message.
+pub enum ReceiveError {
+    /// No message was received before the timeout (which this
+    /// variant carries) expired.
+    Timeout(Duration),
+}
+
+#[derive(Clone)]
+/// The error returned by the future of a children group's
+/// element, or its panic, which made it fault.
+///
+/// Only its [`Debug`] representation can be retrieved, e.g. by
+/// the callback set using [`Callbacks::with_after_error`]. The
+/// futures set using [`Children::with_exec`] return `()` as their
+/// error.
+///
+/// [`Debug`]: https://doc.rust-lang.org/std/fmt/trait.Debug.html
+/// [`Callbacks::with_after_error`]: ../struct.Callbacks.html#method.with_after_error
+/// [`Children::with_exec`]: ../children/struct.Children.html#method.with_exec
+pub struct ExecError {
+    // Behind a lock so that errors which aren't `Sync` can be
+    // shared with the supervisor.
+    error: Arc<Mutex<dyn Debug + Send>>,
+    // The element's panic, if it panicked.
+    panic: Option<PanicReport>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// The panic of an element, reported along with its fault (see
+/// [`ExecError::panic`] and [`Callbacks::with_after_panic`]).
+///
+/// [`ExecError::panic`]: struct.ExecError.html#method.panic
+/// [`Callbacks::with_after_panic`]: ../struct.Callbacks.html#method.with_after_panic
+pub struct PanicReport {
+    message: Option<String>,
+    location: Option<String>,
+    backtrace: Option<String>,
+}
+
+/// The error returned by the methods of an [`Actor`], making the
+/// element running it fault (see [`Children::with_actor`]).
+///
+/// It can be created from any error using [`ActorError::new`], and
+/// every error of the public API (as well as `()`) converts into
+/// it, allowing to use the `?` operator on them. Only its [`Debug`]
+/// representation can be retrieved, like for an [`ExecError`].
+///
+/// [`Actor`]: ../actor/trait.Actor.html
+/// [`Children::with_actor`]: ../children/struct.Children.html#method.with_actor
+/// [`ActorError::new`]: #method.new
+/// [`Debug`]: https://doc.rust-lang.org/std/fmt/trait.Debug.html
+/// [`ExecError`]: struct.ExecError.html
+pub struct ActorError(
+    // Behind a lock so that errors which aren't `Sync` can be
+    // returned.
+    Mutex<Box<dyn Debug + Send>>,
+);
+
+impl ExecError {
+    pub(crate) fn new<E: Debug + Send + 'static>(error: E) -> Self {
+        let error = Arc::new(Mutex::new(error));
+
+        ExecError { error, panic: None }
+    }
+
+    pub(crate) fn panicked(panic: PanicReport) -> Self {
+        let error = Arc::new(Mutex::new(panic.to_string()));
+
+        ExecError {
+            error,
+            panic: Some(panic),
+        }
+    }
+
+    /// Returns the report of the element's panic, if it panicked
+    /// instead of its future returning an error.
+    pub fn panic(&self) -> Option<&PanicReport> {
+        self.panic.as_ref()
+    }
+}
+
+impl ActorError {
+    /// Creates a new error from `error`.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The error the element faults with.
+    pub fn new<E: Debug + Send + 'static>(error: E) -> Self {
+        ActorError(Mutex::new(Box::new(error)))
+    }
+}
+
+impl PanicReport {
+    pub(crate) fn new(
+        payload: &(dyn Any + Send),
+        location: Option<String>,
+        backtrace: Option<String>,
+    ) -> Self {
+        let message = match payload.downcast_ref::<&'static str>() {
+            Some(message) => Some(message.to_string()),
+            None => payload.downcast_ref::<String>().cloned(),
+        };
+
+        PanicReport {
+            message,
+            location,
+            backtrace,
+        }
+    }
+
+    /// Returns the message the element panicked with, if its
+    /// payload was a `&str` or a `String` (e.g. using `panic!`).
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// Returns where the element panicked (as `file:line:column`),
+    /// if the system's panic hook was called (see
+    /// [`Config::panic_hook_order`]).
+    ///
+    /// [`Config::panic_hook_order`]: ../struct.Config.html#method.panic_hook_order
+    pub fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+
+    /// Returns the backtrace of the panic, if the system's panic
+    /// hook was called and backtraces are enabled (e.g. using the
+    /// `RUST_BACKTRACE` environment variable).
+    pub fn backtrace(&self) -> Option<&str> {
+        self.backtrace.as_deref()
+    }
+}
+
+impl<M> SendError<M> {
+    pub(crate) fn new(kind: SendErrorKind, msg: M) -> Self {
+        SendError { kind, msg }
+    }
+
+    pub(crate) fn stopped(msg: M) -> Self {
+        SendError::new(SendErrorKind::Stopped, msg)
+    }
+
+    /// Returns the reason why the message couldn't be sent.
+    pub fn kind(&self) -> SendErrorKind {
+        self.kind
+    }
+
+    /// Returns a reference to the message that couldn't be sent.
+    pub fn msg(&self) -> &M {
+        &self.msg
+    }
+
+    /// Returns the message that couldn't be sent.
+    pub fn into_inner_msg(self) -> M {
+        self.msg
+    }
+}
+
+impl<M> Debug for SendError<M> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        // The message isn't required to implement `Debug` to allow
+        // converting this error into a `BastionError`.
+        fmt.debug_struct("SendError")
+            .field("kind", &self.kind)
+            .finish()
+    }
+}
+
+impl<M> Display for SendError<M> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        write!(fmt, "couldn't send the message: {}", self.kind)
+    }
+}
+
+impl<M> Error for SendError<M> {}
+
+impl Display for SendErrorKind {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        let reason = match self {
+            SendErrorKind::Stopped => "the recipient stopped",
+            SendErrorKind::NoRecipient => "there is no recipient",
+            SendErrorKind::Full => "too many messages are waiting for a reply",
+            SendErrorKind::UnknownCorrelation => "the correlation id is unknown",
+            SendErrorKind::MailboxFull => "the recipient's mailbox is full",
+            SendErrorKind::TooManyPending => {
+                "too many questions are waiting for the recipient's answer"
+            }
+            SendErrorKind::TypeNotAccepted { got } => {
+                return write!(fmt, "the recipient doesn't accept `{}`", got);
+            }
+        };
+
+        fmt.write_str(reason)
+    }
+}
+
+impl Display for BuilderError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            BuilderError::ParentStopped => {
+                fmt.write_str("couldn't create the element: its parent stopped")
+            }
+            BuilderError::NameTaken => {
+                fmt.write_str("couldn't create the element: its name is already taken")
+            }
+            BuilderError::NotInitialized => {
+                fmt.write_str("couldn't create the element: the system isn't initialized")
+            }
+            BuilderError::ShuttingDown => {
+                fmt.write_str("couldn't create the element: the system is shutting down")
+            }
+            BuilderError::ThreadSpawn => {
+                fmt.write_str("couldn't create the element: its thread couldn't be spawned")
+            }
+            BuilderError::Conflict(conflict) => {
+                write!(fmt, "couldn't create the element: {}", conflict)
+            }
+        }
+    }
+}
+
+impl Error for BuilderError {}
+
+impl BuilderConflict {
+    /// Returns the names of the methods of [`Children`] whose
+    /// settings conflict.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let err = Bastion::children(|children| {
+    ///     children.with_overflow_policy(OverflowPolicy::Block)
+    /// })
+    /// .unwrap_err();
+    ///
+    /// if let BuilderError::Conflict(conflict) = err {
+    ///     assert_eq!(
+    ///         conflict.methods(),
+    ///         &["with_overflow_policy", "with_mailbox_capacity"]
+    ///     );
+    /// }
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children`]: ../children/struct.Children.html
+    pub fn methods(&self) -> &'static [&'static str] {
+        match self {
+            BuilderConflict::OverflowWithoutCapacity => {
+                &["with_overflow_policy", "with_mailbox_capacity"]
+            }
+            BuilderConflict::BatchedTypeNotAccepted { .. } => {
+                &["with_batching", "with_accepted_types"]
+            }
+            BuilderConflict::MissingRoutingKey => &["with_routing", "with_routing_key"],
+        }
+    }
+}
+
+impl Display for BuilderConflict {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            BuilderConflict::OverflowWithoutCapacity => {
+                fmt.write_str("an overflow policy is set while the mailboxes are unbounded")
+            }
+            BuilderConflict::BatchedTypeNotAccepted { batched } => write!(
+                fmt,
+                "the batched type `{}` isn't one of the accepted types",
+                batched
+            ),
+            BuilderConflict::MissingRoutingKey => {
+                fmt.write_str("the messages are routed by key without a key function")
+            }
+        }
+    }
+}
+
+impl Display for ShutdownError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            ShutdownError::AlreadyStopped => {
+                fmt.write_str("couldn't stop the element: it already stopped")
+            }
+        }
+    }
+}
+
+impl Error for ShutdownError {}
+
+impl Display for AnswerError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            AnswerError::Unanswered => {
+                fmt.write_str("the message was dropped without being answered")
+            }
+            AnswerError::TimedOut => {
+                fmt.write_str("the message wasn't answered before the timeout")
+            }
+            AnswerError::UnexpectedType { expected, got } => write!(
+                fmt,
+                "the answer was of type `{}` instead of `{}`",
+                got, expected
+            ),
+            AnswerError::WouldBlockExecutor => fmt.write_str(
+                "couldn't wait for the answer: it would block one of the executor's worker threads",
+            ),
+        }
+    }
+}
+
+impl Error for AnswerError {}
+
+impl Display for ReceiveError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            ReceiveError::Timeout(timeout) => {
+                write!(fmt, "no message was received in {:?}", timeout)
+            }
+        }
+    }
+}
+
+impl Error for ReceiveError {}
+
+impl Debug for ExecError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        let error = self.error.lock().unwrap_or_else(|err| err.into_inner());
+        Debug::fmt(&*error, fmt)
+    }
+}
+
+impl Display for ExecError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match &self.panic {
+            Some(panic) => write!(fmt, "the element {}", panic),
+            None => write!(fmt, "the element's future returned an error: {:?}", self),
+        }
+    }
+}
+
+impl Display for PanicReport {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.write_str("panicked")?;
+        if let Some(location) = &self.location {
+            write!(fmt, " at {}", location)?;
+        }
+
+        match &self.message {
+            Some(message) => write!(fmt, ": {}", message),
+            None => fmt.write_str(": Box<dyn Any>"),
+        }
+    }
+}
+
+impl Error for ExecError {}
+
+impl Debug for ActorError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        let error = self.0.lock().unwrap_or_else(|err| err.into_inner());
+        Debug::fmt(&**error, fmt)
+    }
+}
+
+impl Display for ActorError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        write!(fmt, "the actor returned an error: {:?}", self)
+    }
+}
+
+impl Error for ActorError {}
+
+impl Display for BastionError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            BastionError::Send(err) => Display::fmt(err, fmt),
+            BastionError::Builder(err) => Display::fmt(err, fmt),
+            BastionError::Shutdown(err) => Display::fmt(err, fmt),
+            BastionError::Answer(err) => Display::fmt(err, fmt),
+            BastionError::Receive(err) => Display::fmt(err, fmt),
+            BastionError::Reply(err) => Display::fmt(err, fmt),
+            BastionError::Migration(err) => Display::fmt(err, fmt),
+            BastionError::Routing(err) => Display::fmt(err, fmt),
+            BastionError::Reduce(err) => Display::fmt(err, fmt),
+            BastionError::Barrier(err) => Display::fmt(err, fmt),
+            BastionError::Bridge(err) => Display::fmt(err, fmt),
+            BastionError::State(err) => Display::fmt(err, fmt),
+            BastionError::Metric(err) => Display::fmt(err, fmt),
+            BastionError::Reload(err) => Display::fmt(err, fmt),
+            BastionError::NotInitialized => fmt.write_str("the system isn't initialized"),
+            BastionError::ShuttingDown => fmt.write_str("the system is shutting down"),
+        }
+    }
+}
+
+impl Error for BastionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BastionError::Send(err) => Some(err),
+            BastionError::Builder(err) => Some(err),
+            BastionError::Shutdown(err) => Some(err),
+            BastionError::Answer(err) => Some(err),
+            BastionError::Receive(err) => Some(err),
+            BastionError::Reply(err) => Some(err),
+            BastionError::Migration(err) => Some(err),
+            BastionError::Routing(err) => Some(err),
+            BastionError::Reduce(err) => Some(err),
+            BastionError::Barrier(err) => Some(err),
+            BastionError::Bridge(err) => Some(err),
+            BastionError::State(err) => Some(err),
+            BastionError::Metric(err) => Some(err),
+            BastionError::Reload(err) => Some(err),
+            BastionError::NotInitialized | BastionError::ShuttingDown => None,
+        }
+    }
+}
+
+impl<M> From<SendError<M>> for BastionError {
+    fn from(err: SendError<M>) -> Self {
+        BastionError::Send(SendError::new(err.kind, ()))
+    }
+}
+
+impl From<BuilderError> for BastionError {
+    fn from(err: BuilderError) -> Self {
+        match err {
+            BuilderError::NotInitialized => BastionError::NotInitialized,
+            BuilderError::ShuttingDown => BastionError::ShuttingDown,
+            err => BastionError::Builder(err),
+        }
+    }
+}
+
+impl From<ShutdownError> for BastionError {
+    fn from(err: ShutdownError) -> Self {
+        BastionError::Shutdown(err)
+    }
+}
+
+impl From<AnswerError> for BastionError {
+    fn from(err: AnswerError) -> Self {
+        BastionError::Answer(err)
+    }
+}
+
+impl From<ReceiveError> for BastionError {
+    fn from(err: ReceiveError) -> Self {
+        BastionError::Receive(err)
+    }
+}
+
+impl From<ReplyError> for BastionError {
+    fn from(err: ReplyError) -> Self {
+        BastionError::Reply(err)
+    }
+}
+
+impl From<MigrationError> for BastionError {
+    fn from(err: MigrationError) -> Self {
+        BastionError::Migration(err)
+    }
+}
+
+impl From<RoutingError> for BastionError {
+    fn from(err: RoutingError) -> Self {
+        BastionError::Routing(err)
+    }
+}
+
+impl<R> From<ReduceError<R>> for BastionError {
+    fn from(err: ReduceError<R>) -> Self {
+        BastionError::Reduce(err.map(|_| ()))
+    }
+}
+
+impl From<BarrierError> for BastionError {
+    fn from(err: BarrierError) -> Self {
+        BastionError::Barrier(err)
+    }
+}
+
+impl From<BridgeError> for BastionError {
+    fn from(err: BridgeError) -> Self {
+        BastionError::Bridge(err)
+    }
+}
+
+impl From<StateError> for BastionError {
+    fn from(err: StateError) -> Self {
+        BastionError::State(err)
+    }
+}
+
+impl From<MetricError> for BastionError {
+    fn from(err: MetricError) -> Self {
+        BastionError::Metric(err)
+    }
+}
+
+impl From<ReloadError> for BastionError {
+    fn from(err: ReloadError) -> Self {
+        match err {
+            ReloadError::NotInitialized => BastionError::NotInitialized,
+            ReloadError::ShuttingDown => BastionError::ShuttingDown,
+            err => BastionError::Reload(err),
+        }
+    }
+}
+
+// NOTE: those allow using the `?` operator in the futures returned
+//      by the closures passed to `Children::with_exec`.
+
+impl<M> From<SendError<M>> for () {
+    fn from(_: SendError<M>) -> Self {}
+}
+
+impl From<BuilderError> for () {
+    fn from(_: BuilderError) -> Self {}
+}
+
+impl From<ShutdownError> for () {
+    fn from(_: ShutdownError) -> Self {}
+}
+
+impl From<AnswerError> for () {
+    fn from(_: AnswerError) -> Self {}
+}
+
+impl From<ReceiveError> for () {
+    fn from(_: ReceiveError) -> Self {}
+}
+
+impl<R> From<ReduceError<R>> for () {
+    fn from(_: ReduceError<R>) -> Self {}
+}
+
+impl From<BarrierError> for () {
+    fn from(_: BarrierError) -> Self {}
+}
+
+impl From<StateError> for () {
+    fn from(_: StateError) -> Self {}
+}
+
+impl From<MetricError> for () {
+    fn from(_: MetricError) -> Self {}
+}
+
+impl From<ReloadError> for () {
+    fn from(_: ReloadError) -> Self {}
+}
+
+impl From<BastionError> for () {
+    fn from(_: BastionError) -> Self {}
+}
+
+impl<E: Into<BastionError>> From<E> for ActorError {
+    fn from(error: E) -> Self {
+        ActorError::new(error.into())
+    }
+}
+
+impl From<()> for ActorError {
+    fn from(error: ()) -> Self {
+        ActorError::new(error)
+    }
+}
@@ -0,0 +1,329 @@
+//!
+//! Diagnostic events emitted by the system, that can be
+//! subscribed to using [`Bastion::events`].
+//!
+//! [`Bastion::events`]: ../struct.Bastion.html#method.events
+use crate::config::ConfigChange;
+use crate::context::BastionId;
+use crate::errors::{ExecError, PanicReport};
+use crate::flight_recorder::FlightRecord;
+use crate::provenance::Provenance;
+use crate::system::SYSTEM;
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures::prelude::*;
+use futures_timer::Delay;
+use fxhash::FxHashMap;
+use std::sync::Mutex;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+/// The default window during which identical faults are
+/// coalesced.
+pub(crate) const DEFAULT_FAULT_WINDOW: Duration = Duration::from_secs(1);
+/// The default number of identical faults reported individually
+/// during a window.
+pub(crate) const DEFAULT_FAULT_THRESHOLD: usize = 1;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// A diagnostic event emitted by the system.
+pub enum Event {
+    /// The number of messages a children group received before
+    /// being started (which are kept until it is) exceeded the
+    /// watermark set with [`Children::with_pre_start_watermark`].
+    ///
+    /// This event is only emitted once per children group.
+    ///
+    /// [`Children::with_pre_start_watermark`]: ../children/struct.Children.html#method.with_pre_start_watermark
+    PreStartBufferExceeded {
+        /// The identifier of the children group.
+        group: BastionId,
+        /// The number of messages buffered by the group.
+        buffered: usize,
+        /// The group's watermark.
+        watermark: usize,
+    },
+    /// Elements of a children group faulted `count` times in a
+    /// row, for the same reason.
+    ///
+    /// A group's faults are reported as they happen until as many
+    /// identical faults as the threshold set with
+    /// [`Supervisor::with_fault_reports`] were reported during the
+    /// same window. The following ones are then coalesced into a
+    /// single event, emitted once the window closes (or once the
+    /// group faults for another reason). Every fault is still
+    /// counted by the supervisor's restart strategy.
+    ///
+    /// [`Supervisor::with_fault_reports`]: ../supervisor/struct.Supervisor.html#method.with_fault_reports
+    ElemsFaulted {
+        /// The identifier of the children group.
+        group: BastionId,
+        /// Why the elements faulted.
+        reason: FaultReason,
+        /// The number of faults this event reports.
+        count: usize,
+        /// The provenance of the message the element was handling
+        /// when it faulted, if the event reports a single fault of
+        /// an element whose group records it (see
+        /// [`Children::with_provenance`]), or an empty chain.
+        ///
+        /// [`Children::with_provenance`]: ../children/struct.Children.html#method.with_provenance
+        provenance: Provenance,
+        /// The messages the element handled last before it
+        /// faulted, oldest first, if the event reports a single
+        /// fault of an element whose group enables its flight
+        /// recorder (see [`Children::with_flight_recorder`]), or
+        /// an empty list.
+        ///
+        /// [`Children::with_flight_recorder`]: ../children/struct.Children.html#method.with_flight_recorder
+        records: Vec<FlightRecord>,
+        /// The report of the element's panic (its message, where it
+        /// panicked and its backtrace), if the event reports a
+        /// single fault of an element that panicked.
+        panic: Option<PanicReport>,
+    },
+    /// The system supervisor restarted more elements than its
+    /// restart window allows and the system was degraded, stopping
+    /// every children group but the `kept` ones until
+    /// [`Bastion::recover`] is called.
+    ///
+    /// This event is only emitted once per degradation.
+    ///
+    /// [`Bastion::recover`]: ../struct.Bastion.html#method.recover
+    SystemDegraded {
+        /// The names of the children groups kept running.
+        kept: Vec<String>,
+    },
+    /// The runtime settings of the system's configuration were
+    /// reloaded using [`Bastion::reload_config`].
+    ///
+    /// [`Bastion::reload_config`]: ../struct.Bastion.html#method.reload_config
+    ConfigReloaded {
+        /// The settings that changed (which is empty if the new
+        /// settings were the same).
+        changes: Vec<ConfigChange>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+/// The reasons why an element can fault.
+pub enum FaultReason {
+    /// The element panicked.
+    Panicked,
+    /// The element's future returned an error.
+    Errored,
+    /// The element's future finished without the element
+    /// reporting whether it stopped or faulted.
+    Unreported,
+}
+
+#[derive(Debug)]
+// Reports the faults of the children groups supervised by a
+// supervisor, coalescing the identical consecutive ones.
+pub(crate) struct FaultReports {
+    window: Duration,
+    threshold: usize,
+    groups: FxHashMap<BastionId, GroupFaults>,
+    // The time the next window closes at, and its timer.
+    timer: Option<(Instant, Delay)>,
+}
+
+#[derive(Debug)]
+struct GroupFaults {
+    reason: FaultReason,
+    closes: Instant,
+    // The number of faults reported during the window.
+    reported: usize,
+    // The number of faults coalesced during the window.
+    coalesced: usize,
+}
+
+#[derive(Debug, Default)]
+// The subscribers to the system's events.
+pub(crate) struct Events {
+    subscribers: Mutex<Vec<UnboundedSender<Event>>>,
+}
+
+impl Events {
+    pub(crate) fn new() -> Self {
+        Events::default()
+    }
+
+    pub(crate) fn subscribe(&self) -> UnboundedReceiver<Event> {
+        let (sender, recver) = mpsc::unbounded();
+        self.subscribers.lock().unwrap().push(sender);
+
+        recver
+    }
+
+    pub(crate) fn emit(&self, event: Event) {
+        trace!("Events: Emitting event: {:?}", event);
+        // Subscribers whose stream was dropped are forgotten.
+        self.subscribers
+            .lock()
+            .unwrap()
+            .retain(|subscriber| subscriber.unbounded_send(event.clone()).is_ok());
+    }
+}
+
+impl FaultReports {
+    pub(crate) fn new() -> Self {
+        FaultReports {
+            window: DEFAULT_FAULT_WINDOW,
+            threshold: DEFAULT_FAULT_THRESHOLD,
+            groups: FxHashMap::default(),
+            timer: None,
+        }
+    }
+
+    pub(crate) fn set_window(&mut self, window: Duration, threshold: usize) {
+        self.window = window;
+        self.threshold = threshold;
+    }
+
+    pub(crate) fn report(
+        &mut self,
+        group: &BastionId,
+        reason: FaultReason,
+        error: Option<&ExecError>,
+        provenance: Provenance,
+        records: Vec<FlightRecord>,
+    ) {
+        let now = Instant::now();
+        if let Some(faults) = self.groups.get_mut(group) {
+            if faults.reason == reason && now < faults.closes {
+                if faults.reported < self.threshold {
+                    faults.reported += 1;
+                    Self::emit(group, reason, 1, error, provenance, records);
+                } else {
+                    faults.coalesced += 1;
+                }
+
+                return;
+            }
+
+            let faults = self.groups.remove(group).unwrap();
+            faults.flush(group);
+        }
+
+        let faults = GroupFaults {
+            reason,
+            closes: now + self.window,
+            reported: 0,
+            coalesced: 0,
+        };
+        self.groups.insert(group.clone(), faults);
+
+        // The first fault of the window is always reported.
+        self.groups.get_mut(group).unwrap().reported += 1;
+        Self::emit(group, reason, 1, error, provenance, records);
+    }
+
+    // Emits the faults coalesced during the windows that closed,
+    // and resolves once there isn't any window left open.
+    pub(crate) fn poll_flush(&mut self, ctx: &mut Context) -> Poll<()> {
+        loop {
+            let now = Instant::now();
+            let closed = self
+                .groups
+                .iter()
+                .filter(|(_, faults)| faults.closes <= now)
+                .map(|(group, _)| group.clone())
+                .collect::<Vec<_>>();
+            for group in closed {
+                let faults = self.groups.remove(&group).unwrap();
+                faults.flush(&group);
+            }
+
+            let closes = match self.groups.values().map(|faults| faults.closes).min() {
+                Some(closes) => closes,
+                None => {
+                    self.timer = None;
+                    return Poll::Ready(());
+                }
+            };
+
+            match &mut self.timer {
+                Some((timer_closes, _)) if *timer_closes == closes => (),
+                timer => *timer = Some((closes, Delay::new(closes - now))),
+            }
+
+            let (_, timer) = self.timer.as_mut().unwrap();
+            match timer.poll_unpin(ctx) {
+                Poll::Ready(()) => self.timer = None,
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    // Emits `count` faults, along with the error returned by the
+    // element, the provenance of the message it was handling and
+    // the messages it handled last if only one of them is reported.
+    fn emit(
+        group: &BastionId,
+        reason: FaultReason,
+        count: usize,
+        error: Option<&ExecError>,
+        provenance: Provenance,
+        records: Vec<FlightRecord>,
+    ) {
+        match error {
+            Some(error) => warn!(
+                "Children({}): Elements faulted {} time(s): {:?}: {:?}",
+                group, count, reason, error
+            ),
+            None => warn!(
+                "Children({}): Elements faulted {} time(s): {:?}.",
+                group, count, reason
+            ),
+        }
+        if !provenance.is_empty() {
+            warn!(
+                "Children({}): The element faulted while handling a message caused by: {:?}",
+                group,
+                provenance.entries()
+            );
+        }
+        if let Some(backtrace) = error
+            .and_then(ExecError::panic)
+            .and_then(PanicReport::backtrace)
+        {
+            warn!(
+                "Children({}): The element panicked with this backtrace:\n{}",
+                group, backtrace
+            );
+        }
+        for record in &records {
+            warn!(
+                "Children({}): The element handled {} for {:?} before faulting: {:?}",
+                group,
+                record.type_name(),
+                record.duration(),
+                record.outcome()
+            );
+        }
+        let event = Event::ElemsFaulted {
+            group: group.clone(),
+            reason,
+            count,
+            provenance,
+            records,
+            panic: error.and_then(ExecError::panic).cloned(),
+        };
+        SYSTEM.events().emit(event);
+    }
+}
+
+impl GroupFaults {
+    fn flush(self, group: &BastionId) {
+        if self.coalesced > 0 {
+            FaultReports::emit(
+                group,
+                self.reason,
+                self.coalesced,
+                None,
+                Provenance::default(),
+                Vec::new(),
+            );
+        }
+    }
+}
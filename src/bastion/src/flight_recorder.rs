@@ -0,0 +1,230 @@
+//!
+//! Flight recorders, keeping the last messages handled by the
+//! elements of a children group to report them along with their
+//! faults (see [`Children::with_flight_recorder`]).
+//!
+//! [`Children::with_flight_recorder`]: ../children/struct.Children.html#method.with_flight_recorder
+use fxhash::FxHashMap;
+use lazy_static::lazy_static;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// The duration of a record whose message is still being handled.
+const IN_PROGRESS: u64 = u64::MAX;
+
+lazy_static! {
+    // The names of the types of the messages recorded, which the
+    // slots refer to by index.
+    static ref NAMES: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+}
+
+thread_local! {
+    // The recorder of the element whose future is being polled by
+    // this thread, if its group enables it.
+    static CURRENT: RefCell<Option<FlightRecorder>> = const { RefCell::new(None) };
+    // The indexes of the names this thread interned, by address
+    // and length, so that recording doesn't lock `NAMES`.
+    static INTERNED: RefCell<FxHashMap<(usize, usize), usize>> = RefCell::new(FxHashMap::default());
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// A message handled by an element, as recorded by its flight
+/// recorder.
+pub struct FlightRecord {
+    type_name: &'static str,
+    started: Instant,
+    duration: Duration,
+    outcome: RecordOutcome,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// How an element's handling of a message recorded by its flight
+/// recorder ended.
+pub enum RecordOutcome {
+    /// The element was done handling the message: it waited for
+    /// another one or retrieved it.
+    Handled,
+    /// The element faulted while handling the message.
+    Faulted,
+}
+
+#[derive(Debug, Clone)]
+// The last messages handled by an element, only written by the
+// thread polling its future and read once it faulted.
+pub(crate) struct FlightRecorder(Arc<Ring>);
+
+#[derive(Debug)]
+struct Ring {
+    // The instant the slots' timestamps are relative to.
+    epoch: Instant,
+    slots: Box<[Slot]>,
+    // The number of records written since the element started.
+    written: AtomicUsize,
+}
+
+#[derive(Debug, Default)]
+struct Slot {
+    name: AtomicUsize,
+    // In nanoseconds since the ring's epoch.
+    started: AtomicU64,
+    // In nanoseconds, or `IN_PROGRESS`.
+    duration: AtomicU64,
+}
+
+// Unsets the recorder of the current thread once dropped
+// (including if the element's future panicked).
+pub(crate) struct Entered(());
+
+impl FlightRecord {
+    /// Returns the name of the type of the message.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns when the element started handling the message.
+    pub fn started(&self) -> Instant {
+        self.started
+    }
+
+    /// Returns how long the element handled the message for (or
+    /// until it faulted).
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Returns how the element's handling of the message ended.
+    pub fn outcome(&self) -> RecordOutcome {
+        self.outcome
+    }
+}
+
+impl FlightRecorder {
+    pub(crate) fn new(capacity: usize) -> Self {
+        let slots = (0..capacity.max(1)).map(|_| Slot::default()).collect();
+        let ring = Ring {
+            epoch: Instant::now(),
+            slots,
+            written: AtomicUsize::new(0),
+        };
+
+        FlightRecorder(Arc::new(ring))
+    }
+
+    // Sets the recorder of the current thread, until the returned
+    // guard is dropped.
+    pub(crate) fn enter(&self) -> Entered {
+        CURRENT.with(|current| *current.borrow_mut() = Some(self.clone()));
+        Entered(())
+    }
+
+    // Returns the records kept, oldest first, the message that is
+    // still being handled (if any) being recorded as faulted.
+    pub(crate) fn records(&self) -> Vec<FlightRecord> {
+        let ring = &self.0;
+        let now = ring.now();
+        let written = ring.written.load(Ordering::Relaxed);
+        let names = NAMES.lock().unwrap();
+
+        let kept = written.saturating_sub(ring.slots.len())..written;
+        kept.map(|index| {
+            let slot = ring.slot(index);
+            let started = slot.started.load(Ordering::Relaxed);
+            let (duration, outcome) = match slot.duration.load(Ordering::Relaxed) {
+                IN_PROGRESS => (now.saturating_sub(started), RecordOutcome::Faulted),
+                duration => (duration, RecordOutcome::Handled),
+            };
+
+            FlightRecord {
+                type_name: names[slot.name.load(Ordering::Relaxed)],
+                started: ring.epoch + Duration::from_nanos(started),
+                duration: Duration::from_nanos(duration),
+                outcome,
+            }
+        })
+        .collect()
+    }
+
+    fn begin(&self, type_name: &'static str) {
+        let ring = &self.0;
+        let now = ring.now();
+        ring.finish(now);
+
+        let written = ring.written.load(Ordering::Relaxed);
+        let slot = ring.slot(written);
+        slot.name.store(intern(type_name), Ordering::Relaxed);
+        slot.started.store(now, Ordering::Relaxed);
+        slot.duration.store(IN_PROGRESS, Ordering::Relaxed);
+        ring.written.store(written + 1, Ordering::Relaxed);
+    }
+}
+
+impl Ring {
+    fn now(&self) -> u64 {
+        self.epoch.elapsed().as_nanos() as u64
+    }
+
+    fn slot(&self, index: usize) -> &Slot {
+        &self.slots[index % self.slots.len()]
+    }
+
+    // Records that the element is done handling the last message
+    // it retrieved, if it wasn't yet.
+    fn finish(&self, now: u64) {
+        let written = self.written.load(Ordering::Relaxed);
+        if written == 0 {
+            return;
+        }
+
+        let slot = self.slot(written - 1);
+        if slot.duration.load(Ordering::Relaxed) == IN_PROGRESS {
+            let started = slot.started.load(Ordering::Relaxed);
+            slot.duration
+                .store(now.saturating_sub(started), Ordering::Relaxed);
+        }
+    }
+}
+
+impl Drop for Entered {
+    fn drop(&mut self) {
+        CURRENT.with(|current| current.borrow_mut().take());
+    }
+}
+
+// The index of `name` in `NAMES`.
+fn intern(name: &'static str) -> usize {
+    let key = (name.as_ptr() as usize, name.len());
+    INTERNED.with(|interned| {
+        *interned.borrow_mut().entry(key).or_insert_with(|| {
+            let mut names = NAMES.lock().unwrap();
+            match names.iter().position(|interned| *interned == name) {
+                Some(index) => index,
+                None => {
+                    names.push(name);
+                    names.len() - 1
+                }
+            }
+        })
+    })
+}
+
+// Records that the element whose future is being polled by the
+// current thread retrieved a message of this type.
+pub(crate) fn handling(type_name: &'static str) {
+    CURRENT.with(|current| {
+        if let Some(recorder) = &*current.borrow() {
+            recorder.begin(type_name);
+        }
+    })
+}
+
+// Records that the element whose future is being polled by the
+// current thread waits for a message.
+pub(crate) fn waiting() {
+    CURRENT.with(|current| {
+        if let Some(recorder) = &*current.borrow() {
+            recorder.0.finish(recorder.0.now());
+        }
+    })
+}
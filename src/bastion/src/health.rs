@@ -0,0 +1,47 @@
+//!
+//! The health of the system, as returned by [`Bastion::health`].
+//!
+//! [`Bastion::health`]: ../struct.Bastion.html#method.health
+use crate::children_ref::GroupState;
+use crate::reactor::ReactorHealth;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// The health of the system, as returned by [`Bastion::health`].
+///
+/// [`Bastion::health`]: struct.Bastion.html#method.health
+pub struct Health {
+    pub(crate) degraded: bool,
+    pub(crate) dead_letters: GroupState,
+    pub(crate) reactor: ReactorHealth,
+}
+
+impl Health {
+    /// Returns whether the system is degraded (see
+    /// [`Bastion::is_degraded`]).
+    ///
+    /// [`Bastion::is_degraded`]: struct.Bastion.html#method.is_degraded
+    pub fn is_degraded(&self) -> bool {
+        self.degraded
+    }
+
+    /// Returns the state of the group receiving the dead letters
+    /// (e.g. [`GroupState::Restarting`] while its element is waiting
+    /// to be restarted after it faulted).
+    ///
+    /// Like the reactor, the group is supervised by a supervisor of
+    /// the system that always restarts it, and is stopped after the
+    /// other groups.
+    ///
+    /// [`GroupState::Restarting`]: children_ref/enum.GroupState.html#variant.Restarting
+    pub fn dead_letters(&self) -> GroupState {
+        self.dead_letters
+    }
+
+    /// Returns the health of the reactor, which wakes the elements
+    /// sleeping (see [`BastionContext::sleep`]).
+    ///
+    /// [`BastionContext::sleep`]: context/struct.BastionContext.html#method.sleep
+    pub fn reactor(&self) -> &ReactorHealth {
+        &self.reactor
+    }
+}
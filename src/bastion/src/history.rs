@@ -0,0 +1,244 @@
+//!
+//! The history of the children groups that terminated, kept by the
+//! system if it was configured to (see [`Config::with_group_history`])
+//! and returned by [`Bastion::terminated_groups`].
+//!
+//! The history only holds summaries of the groups, which don't
+//! reference them, their elements or their messages: it can't keep
+//! the mailboxes of the groups that terminated alive.
+//!
+//! [`Config::with_group_history`]: ../struct.Config.html#method.with_group_history
+//! [`Bastion::terminated_groups`]: ../struct.Bastion.html#method.terminated_groups
+use crate::children_ref::GroupState;
+use crate::context::BastionId;
+use crate::event::FaultReason;
+use crate::metrics::ChildStats;
+use std::collections::VecDeque;
+use std::mem;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// A summary of a children group that stopped, was killed or
+/// faulted, as returned by [`Bastion::terminated_groups`].
+///
+/// [`Bastion::terminated_groups`]: ../struct.Bastion.html#method.terminated_groups
+pub struct TerminatedGroup {
+    id: BastionId,
+    name: Option<String>,
+    elems: Vec<BastionId>,
+    state: GroupState,
+    faults: Vec<(FaultReason, usize)>,
+    restarts: usize,
+    processed: u64,
+    rejected: u64,
+    lifetime: Duration,
+    terminated_at: SystemTime,
+}
+
+#[derive(Debug)]
+// What the entry of a children group will summarize, updated as
+// its elements fault or terminate.
+pub(crate) struct GroupRecord {
+    launched: Instant,
+    // The elements the group had when it terminated.
+    elems: Vec<BastionId>,
+    // The number of faults of its elements, by reason (in the order
+    // they first happened).
+    faults: Vec<(FaultReason, usize)>,
+    restarts: usize,
+    processed: u64,
+    rejected: u64,
+}
+
+#[derive(Debug)]
+// The groups that terminated, oldest first, along with when they
+// did, keeping at most `max_groups` of them for `max_age`.
+pub(crate) struct History {
+    limits: Option<(usize, Duration)>,
+    entries: Mutex<VecDeque<(Instant, TerminatedGroup)>>,
+}
+
+impl TerminatedGroup {
+    /// Returns the group's identifier.
+    pub fn id(&self) -> &BastionId {
+        &self.id
+    }
+
+    /// Returns the group's name, if it had one (see
+    /// [`Children::with_name`]).
+    ///
+    /// [`Children::with_name`]: ../children/struct.Children.html#method.with_name
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the identifiers of the elements the group had when
+    /// it terminated.
+    pub fn elems(&self) -> &[BastionId] {
+        &self.elems
+    }
+
+    /// Returns the state the group ended up in, which is either
+    /// [`GroupState::Stopped`], [`GroupState::Killed`] or
+    /// [`GroupState::Faulted`].
+    ///
+    /// [`GroupState::Stopped`]: ../children_ref/enum.GroupState.html#variant.Stopped
+    /// [`GroupState::Killed`]: ../children_ref/enum.GroupState.html#variant.Killed
+    /// [`GroupState::Faulted`]: ../children_ref/enum.GroupState.html#variant.Faulted
+    pub fn state(&self) -> GroupState {
+        self.state
+    }
+
+    /// Returns the number of times the group's elements faulted
+    /// during its lifetime, by reason (in the order they first
+    /// faulted for each reason).
+    pub fn faults(&self) -> &[(FaultReason, usize)] {
+        &self.faults
+    }
+
+    /// Returns the number of times the group's elements were
+    /// restarted during its lifetime (the sum of their
+    /// generations).
+    pub fn restarts(&self) -> usize {
+        self.restarts
+    }
+
+    /// Returns the number of messages the group's elements handled
+    /// during its lifetime.
+    pub fn processed(&self) -> u64 {
+        self.processed
+    }
+
+    /// Returns the number of messages the group's elements
+    /// rejected during its lifetime.
+    pub fn rejected(&self) -> u64 {
+        self.rejected
+    }
+
+    /// Returns how long the group ran for, from when it was
+    /// launched until it terminated.
+    pub fn lifetime(&self) -> Duration {
+        self.lifetime
+    }
+
+    /// Returns when the group terminated.
+    pub fn terminated_at(&self) -> SystemTime {
+        self.terminated_at
+    }
+}
+
+impl GroupRecord {
+    pub(crate) fn new() -> Self {
+        GroupRecord {
+            launched: Instant::now(),
+            elems: Vec::new(),
+            faults: Vec::new(),
+            restarts: 0,
+            processed: 0,
+            rejected: 0,
+        }
+    }
+
+    pub(crate) fn faulted(&mut self, reason: FaultReason) {
+        match self
+            .faults
+            .iter_mut()
+            .find(|(faulted, _)| *faulted == reason)
+        {
+            Some((_, count)) => *count += 1,
+            None => self.faults.push((reason, 1)),
+        }
+    }
+
+    // Adds the statistics of an element that was pruned or dropped
+    // to the group's.
+    pub(crate) fn retired(&mut self, stats: &ChildStats) {
+        self.restarts += stats.restarts();
+        self.processed += stats.processed();
+        self.rejected += stats.rejected();
+    }
+
+    // Adds the statistics of an element that the group had when it
+    // terminated to the group's.
+    pub(crate) fn terminated(&mut self, stats: &ChildStats) {
+        self.retired(stats);
+        self.elems.push(stats.id().clone());
+    }
+
+    pub(crate) fn finish(
+        &mut self,
+        id: BastionId,
+        name: Option<String>,
+        state: GroupState,
+    ) -> TerminatedGroup {
+        let mut elems = mem::take(&mut self.elems);
+        elems.sort_by_key(|elem| elem.to_string());
+
+        TerminatedGroup {
+            id,
+            name,
+            elems,
+            state,
+            faults: self.faults.clone(),
+            restarts: self.restarts,
+            processed: self.processed,
+            rejected: self.rejected,
+            lifetime: self.launched.elapsed(),
+            terminated_at: SystemTime::now(),
+        }
+    }
+}
+
+impl History {
+    pub(crate) fn new(limits: Option<(usize, Duration)>) -> Self {
+        let entries = Mutex::new(VecDeque::new());
+
+        History { limits, entries }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        matches!(self.limits, Some((max_groups, _)) if max_groups > 0)
+    }
+
+    pub(crate) fn record(&self, group: TerminatedGroup) {
+        let (max_groups, max_age) = match self.limits {
+            Some(limits) => limits,
+            None => return,
+        };
+
+        trace!("History: Recording Children({}).", group.id);
+        // FIXME: panics?
+        let mut entries = self.entries.lock().unwrap();
+        entries.push_back((Instant::now(), group));
+        while entries.len() > max_groups {
+            entries.pop_front();
+        }
+
+        evict(&mut entries, max_age);
+    }
+
+    pub(crate) fn entries(&self) -> Vec<TerminatedGroup> {
+        let max_age = match self.limits {
+            Some((_, max_age)) => max_age,
+            None => return Vec::new(),
+        };
+
+        // FIXME: panics?
+        let mut entries = self.entries.lock().unwrap();
+        evict(&mut entries, max_age);
+
+        entries.iter().map(|(_, group)| group.clone()).collect()
+    }
+}
+
+// Drops the entries recorded more than `max_age` ago.
+fn evict(entries: &mut VecDeque<(Instant, TerminatedGroup)>, max_age: Duration) {
+    while let Some((recorded, _)) = entries.front() {
+        if recorded.elapsed() <= max_age {
+            break;
+        }
+
+        entries.pop_front();
+    }
+}
@@ -0,0 +1,150 @@
+//!
+//! The activity of the children groups, which the system watches
+//! to stop once it is idle (see [`Bastion::stop_when_idle`]).
+//!
+//! [`Bastion::stop_when_idle`]: ../struct.Bastion.html#method.stop_when_idle
+use crate::bastion::Bastion;
+use crate::system::SYSTEM;
+use futures_timer::Delay;
+use lazy_static::lazy_static;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+// The shortest interval at which the system's activity is checked.
+const MIN_TICK: Duration = Duration::from_millis(1);
+
+lazy_static! {
+    // NOTE: the activity isn't held by the system because the
+    //      groups watched include the ones launched while it is
+    //      initialized (e.g. the dead letters).
+    static ref ACTIVITY: Activity = Activity::default();
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// When the system is considered idle by [`Bastion::stop_when_idle`].
+///
+/// [`Bastion::stop_when_idle`]: struct.Bastion.html#method.stop_when_idle
+pub struct IdleCriteria {
+    /// How long none of the elements must have been handling or
+    /// received a message for.
+    pub quiescent_for: Duration,
+    /// The names of the groups whose elements are never idle (e.g.
+    /// because they are waiting for a timer) and whose activity is
+    /// ignored.
+    pub ignore_groups: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+// The activity of every children group of the system.
+struct Activity {
+    groups: Mutex<Vec<Weak<Group>>>,
+}
+
+#[derive(Debug, Clone, Default)]
+// The activity of a children group, shared by its elements.
+pub(crate) struct GroupActivity(Arc<Group>);
+
+#[derive(Debug, Default)]
+struct Group {
+    name: Option<String>,
+    // The number of elements that aren't waiting for a message
+    // while there isn't any left to retrieve.
+    busy: AtomicUsize,
+    // Incremented each time an element receives a message or
+    // becomes idle or busy.
+    changes: AtomicUsize,
+}
+
+impl Activity {
+    fn register(&self, name: Option<String>) -> GroupActivity {
+        let group = Arc::new(Group {
+            name,
+            ..Group::default()
+        });
+
+        // FIXME: panics?
+        let mut groups = self.groups.lock().unwrap();
+        groups.retain(|group| group.strong_count() > 0);
+        groups.push(Arc::downgrade(&group));
+
+        GroupActivity(group)
+    }
+
+    // Returns the number of changes of the groups that aren't
+    // ignored, unless one of their elements is busy.
+    fn quiescent(&self, ignored: &[String]) -> Option<usize> {
+        // FIXME: panics?
+        let groups = self.groups.lock().unwrap();
+        let mut changes = 0usize;
+        for group in groups.iter().filter_map(Weak::upgrade) {
+            if let Some(name) = &group.name {
+                if ignored.contains(name) {
+                    continue;
+                }
+            }
+
+            if group.busy.load(Ordering::SeqCst) > 0 {
+                return None;
+            }
+
+            changes = changes.wrapping_add(group.changes.load(Ordering::SeqCst));
+        }
+
+        Some(changes)
+    }
+}
+
+impl GroupActivity {
+    // Returns the activity of a children group with this name,
+    // whose elements are then watched by the system.
+    pub(crate) fn register(name: Option<String>) -> Self {
+        ACTIVITY.register(name)
+    }
+
+    pub(crate) fn busy(&self) {
+        self.0.busy.fetch_add(1, Ordering::SeqCst);
+        self.touch();
+    }
+
+    pub(crate) fn idle(&self) {
+        self.0.busy.fetch_sub(1, Ordering::SeqCst);
+        self.touch();
+    }
+
+    // Records that an element received a message.
+    pub(crate) fn touch(&self) {
+        self.0.changes.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+// Stops the system once none of the elements of the groups that
+// aren't ignored changed for the criteria's window, or returns
+// if it is stopped before that.
+pub(crate) async fn stop_when_idle(criteria: IdleCriteria) {
+    let tick = (criteria.quiescent_for / 10).max(MIN_TICK);
+    // The changes last observed while every element was idle, and
+    // since when.
+    let mut quiescent: Option<(usize, Instant)> = None;
+    loop {
+        Delay::new(tick).await;
+        if SYSTEM.check_running().is_err() {
+            return;
+        }
+
+        let changes = ACTIVITY.quiescent(&criteria.ignore_groups);
+        quiescent = match (changes, quiescent) {
+            (Some(changes), Some((since_changes, since))) if changes == since_changes => {
+                if since.elapsed() >= criteria.quiescent_for {
+                    debug!("Bastion: Stopping the idle system.");
+                    Bastion::stop();
+                    return;
+                }
+
+                Some((since_changes, since))
+            }
+            (Some(changes), _) => Some((changes, Instant::now())),
+            (None, _) => None,
+        };
+    }
+}
@@ -0,0 +1,76 @@
+//!
+//! The instrumentation of the futures executed by the children
+//! groups' elements, wrapped using the wrapper set with
+//! [`Config::exec_wrapper`].
+//!
+//! [`Config::exec_wrapper`]: ../struct.Config.html#method.exec_wrapper
+use crate::context::BastionId;
+use futures::future::BoxFuture;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// The element whose future is wrapped by the wrapper set with
+/// [`Config::exec_wrapper`].
+///
+/// [`Config::exec_wrapper`]: ../struct.Config.html#method.exec_wrapper
+pub struct ExecInfo {
+    group: Option<String>,
+    id: BastionId,
+    generation: usize,
+}
+
+type Wrapped = BoxFuture<'static, Result<(), ()>>;
+
+#[derive(Clone)]
+pub(crate) struct ExecWrapper(Arc<dyn Fn(ExecInfo, Wrapped) -> Wrapped + Send + Sync>);
+
+impl ExecInfo {
+    pub(crate) fn new(group: Option<String>, id: BastionId, generation: usize) -> Self {
+        ExecInfo {
+            group,
+            id,
+            generation,
+        }
+    }
+
+    /// Returns the name of the element's group, if it has one
+    /// (see [`Children::with_name`]).
+    ///
+    /// [`Children::with_name`]: ../children/struct.Children.html#method.with_name
+    pub fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    /// Returns the identifier of the element, which it keeps when
+    /// it is restarted.
+    pub fn id(&self) -> &BastionId {
+        &self.id
+    }
+
+    /// Returns how many times the element was restarted before
+    /// executing this future (`0` for the future it executes
+    /// once launched).
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+}
+
+impl ExecWrapper {
+    pub(crate) fn new<W>(wrapper: W) -> Self
+    where
+        W: Fn(ExecInfo, Wrapped) -> Wrapped + Send + Sync + 'static,
+    {
+        ExecWrapper(Arc::new(wrapper))
+    }
+
+    pub(crate) fn wrap(&self, info: ExecInfo, exec: Wrapped) -> Wrapped {
+        (self.0)(info, exec)
+    }
+}
+
+impl Debug for ExecWrapper {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("ExecWrapper").finish()
+    }
+}
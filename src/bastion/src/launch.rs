@@ -0,0 +1,81 @@
+//!
+//! The launch of the elements of the children groups, which can
+//! be limited to a number of elements initializing concurrently
+//! (see [`Children::with_launch_concurrency`]).
+//!
+//! [`Children::with_launch_concurrency`]: ../children/struct.Children.html#method.with_launch_concurrency
+use crate::context::BastionId;
+use futures::channel::mpsc::UnboundedSender;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+
+#[derive(Debug, Clone)]
+// Held by a launched element until it is initialized (once it is
+// warm and the state returned by its group's async init closure,
+// if it has one, resolved), letting its group know once dropped.
+pub(crate) struct LaunchPermit(Arc<Permit>);
+
+#[derive(Debug, Clone, Default)]
+// Held by an element's contexts, which can't keep its permit
+// from being dropped.
+pub(crate) struct WeakLaunchPermit(Weak<Permit>);
+
+#[derive(Debug)]
+struct Permit {
+    id: BastionId,
+    sender: UnboundedSender<BastionId>,
+}
+
+#[derive(Debug, Clone, Default)]
+// The number of elements a group launched and of those which are
+// initialized, shared by the group and its `ChildrenRef`s.
+pub(crate) struct LaunchProgress(Arc<Progress>);
+
+#[derive(Debug, Default)]
+struct Progress {
+    launched: AtomicUsize,
+    initialized: AtomicUsize,
+}
+
+impl LaunchPermit {
+    // Returns a permit sending `id` to `sender` once dropped.
+    pub(crate) fn new(id: BastionId, sender: UnboundedSender<BastionId>) -> Self {
+        LaunchPermit(Arc::new(Permit { id, sender }))
+    }
+
+    pub(crate) fn downgrade(&self) -> WeakLaunchPermit {
+        WeakLaunchPermit(Arc::downgrade(&self.0))
+    }
+}
+
+impl WeakLaunchPermit {
+    // Returns the permit if it wasn't dropped yet, for the element
+    // to hold it until something else is initialized.
+    pub(crate) fn upgrade(&self) -> Option<LaunchPermit> {
+        self.0.upgrade().map(LaunchPermit)
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        // The group might have stopped in the meantime.
+        self.sender.unbounded_send(self.id.clone()).ok();
+    }
+}
+
+impl LaunchProgress {
+    pub(crate) fn set(&self, launched: usize, initialized: usize) {
+        self.0.launched.store(launched, Ordering::SeqCst);
+        self.0.initialized.store(initialized, Ordering::SeqCst);
+    }
+
+    #[cfg(feature = "introspection")]
+    pub(crate) fn launched(&self) -> usize {
+        self.0.launched.load(Ordering::SeqCst)
+    }
+
+    #[cfg(feature = "introspection")]
+    pub(crate) fn initialized(&self) -> usize {
+        self.0.initialized.load(Ordering::SeqCst)
+    }
+}
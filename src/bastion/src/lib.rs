@@ -21,6 +21,29 @@
 //! * Supervision system makes it easy to manage lifecycles.
 //!     * Kill your application in certain condition or restart you subprocesses whenever a certain condition is met.
 //!
+//! ## Cargo features
+//! The children groups, their elements, their messages and their
+//! supervision form the core of the crate, which is always
+//! compiled. The following features can be disabled to only
+//! compile what is used:
+//! * `patterns` (enabled by default): the [`patterns`] and
+//!     [`pipeline`] modules.
+//! * `introspection` (enabled by default): the snapshots of the
+//!     supervision tree returned by `Bastion::tree` and
+//!     `Bastion::tree_watch`, along with the [`tree`] module's
+//!     types.
+//! * `autoscaling` (enabled by default): the [`resizer`] module,
+//!     scaling the children groups with `Children::with_resizer`.
+//! * `supervision` (enabled by default): the restart back-offs of
+//!     the `backoff` module, and the restart windows of the
+//!     supervisors (`Supervisor::with_restart_window` and
+//!     `Config::with_root_restart_window`).
+//! * `signals`: shutting the system down gracefully once it
+//!     receives a signal (see `Bastion::handle_signals`).
+//!
+//! The items requiring a disabled feature are reported by the
+//! compiler as being gated behind it.
+//!
 //! ## Guarantees
 //! * At most once delivery for all the messages.
 //! * Completely asynchronous system design.
@@ -43,6 +66,10 @@
 //!
 //! [lightproc]: https://docs.rs/lightproc/
 //! [fort]: https://docs.rs/fort/
+//! [`patterns`]: patterns/index.html
+//! [`pipeline`]: pipeline/index.html
+//! [`resizer`]: resizer/index.html
+//! [`tree`]: tree/index.html
 //!
 
 #![doc(
@@ -63,47 +90,269 @@ extern crate bastion_qutex as qutex;
 
 pub use self::bastion::Bastion;
 pub use self::callbacks::Callbacks;
-pub use self::config::Config;
+#[cfg(feature = "supervision")]
+pub use self::config::RootAction;
+pub use self::config::{Config, ConfigChange, PanicHookOrder, ReloadError, RuntimeConfig};
+pub use self::health::Health;
+pub use self::idle::IdleCriteria;
+pub use self::reactor::ReactorHealth;
+#[cfg(feature = "signals")]
+pub use self::signals::ShutdownConfig;
 
 mod bastion;
 mod broadcast;
 mod callbacks;
 mod child;
 mod config;
+mod health;
+mod idle;
+mod launch;
+mod lifeline;
 mod macros;
+mod panic_hook;
+mod reactor;
+mod registry;
+#[cfg(feature = "signals")]
+mod signals;
 mod system;
 
+pub mod ack;
+pub mod actor;
+pub mod answer_stream;
+#[cfg(feature = "supervision")]
+pub mod backoff;
+pub mod batch;
+pub mod blocking_bridge;
+pub mod bridge;
 pub mod child_ref;
 pub mod children;
 pub mod children_ref;
 pub mod context;
+pub mod correlation;
+pub mod dead_letters;
 pub mod dispatcher;
 pub mod envelope;
+pub mod errors;
+pub mod event;
+pub mod flight_recorder;
+pub mod history;
+pub mod instrument;
+pub mod mailbox;
 pub mod message;
+pub mod metrics;
 pub mod path;
+#[cfg(feature = "patterns")]
+pub mod patterns;
+#[cfg(feature = "patterns")]
+pub mod pipeline;
+pub mod preset;
+pub mod provenance;
+pub mod reject;
+#[cfg(feature = "autoscaling")]
+pub mod resizer;
+pub mod routing;
+pub mod schedule;
+pub mod state_cell;
 pub mod supervisor;
+pub mod sync;
+pub mod task;
+pub mod tree;
+pub mod typed;
+pub mod warmup;
+pub mod watch;
 
 ///
 /// Prelude of Bastion
 pub mod prelude {
+    pub use crate::ack::{AckReport, AckToken};
+    pub use crate::actor::{Actor, MessageTimeoutAction, Reply};
+    pub use crate::answer_stream::{AnswerStream, AnswerStreamSender};
+    #[cfg(feature = "supervision")]
+    pub use crate::backoff::Backoff;
     pub use crate::bastion::Bastion;
+    pub use crate::batch::Batch;
     pub use crate::callbacks::Callbacks;
     pub use crate::child_ref::ChildRef;
-    pub use crate::children::Children;
-    pub use crate::children_ref::ChildrenRef;
-    pub use crate::config::Config;
-    pub use crate::context::{BastionContext, BastionId, NIL_ID};
+    pub use crate::children::{Children, GroupConfig, StatefulChildren};
+    pub use crate::children_ref::{AffinityStats, ChildrenRef};
+    #[cfg(feature = "supervision")]
+    pub use crate::config::RootAction;
+    pub use crate::config::{Config, ConfigChange, PanicHookOrder, ReloadError, RuntimeConfig};
+    pub use crate::context::{
+        BastionContext, BastionId, CancellationToken, ContextEvent, ShutdownToken, NIL_ID,
+    };
+    pub use crate::correlation::{Correlated, CorrelationId, ReplyBroker};
+    pub use crate::dead_letters::{
+        DeadLetter, DeadLetterMode, DeadLetterPayload, DeadLetterReason, DeadLetterSummary,
+    };
     pub use crate::dispatcher::{
         BroadcastTarget, DefaultDispatcherHandler, Dispatcher, DispatcherHandler, DispatcherMap,
         DispatcherType, NotificationType,
     };
     pub use crate::envelope::{RefAddr, SignedMessage};
-    pub use crate::message::{Answer, AnswerSender, Message, Msg};
+    pub use crate::errors::{
+        ActorError, AnswerError, BastionError, BuilderConflict, BuilderError, ExecError,
+        PanicReport, ReceiveError, SendError, SendErrorKind, ShutdownError,
+    };
+    pub use crate::event::{Event, FaultReason};
+    pub use crate::flight_recorder::{FlightRecord, RecordOutcome};
+    pub use crate::health::Health;
+    pub use crate::history::TerminatedGroup;
+    pub use crate::idle::IdleCriteria;
+    pub use crate::instrument::ExecInfo;
+    pub use crate::mailbox::OverflowPolicy;
+    pub use crate::message::{
+        Answer, AnswerSender, AnswerTimeout, InlineMessage, Message, MessageTypes, Msg, Priority,
+        TypedMsg,
+    };
+    pub use crate::metrics::{ChildStats, GroupStats, MetricError, Metrics};
     pub use crate::msg;
     pub use crate::path::{BastionPath, BastionPathElement};
+    #[cfg(feature = "patterns")]
+    pub use crate::pipeline::{Pipeline, PipelineRef};
+    pub use crate::preset::SupervisionPreset;
+    pub use crate::provenance::{Provenance, ProvenanceEntry};
+    pub use crate::reactor::ReactorHealth;
+    pub use crate::reject::RejectDisposition;
+    #[cfg(feature = "autoscaling")]
+    pub use crate::resizer::{Pressure, Resizer};
+    pub use crate::routing::{Routing, RoutingError};
+    pub use crate::schedule::ScheduleHandle;
+    #[cfg(feature = "signals")]
+    pub use crate::signals::ShutdownConfig;
+    pub use crate::state_cell::{StateCell, StateError};
+    pub use crate::supervisor::{
+        ActorRestartStrategy, RestartPolicy, RestartStrategy, SupervisionStrategy, Supervisor,
+        SupervisorRef,
+    };
+    pub use crate::typed::{TypedChildRef, TypedChildrenRef};
+    pub use crate::warmup::WarmupGate;
+    pub use crate::watch::{Termination, Watcher};
+    pub use crate::{answer, blocking, children, inline_message, run, spawn, supervisor};
+}
+
+///
+/// Prelude of Bastion for the code that doesn't use its macros,
+/// exporting the same items as [`prelude`] except for the macros,
+/// along with the functions of the [`task`] module replacing
+/// [`run!`], [`spawn!`] and [`blocking!`].
+///
+/// The messages are then received using
+/// [`BastionContext::recv_msg`] or [`BastionContext::recv_typed`]
+/// (or handled by an [`Actor`]) instead of being matched using
+/// [`msg!`], answered using [`AnswerSender::answer`] instead of
+/// [`answer!`], and their answers are awaited using
+/// [`Answer::await_typed`]. The supervisors and children groups
+/// are created using [`Bastion::supervisor`] and
+/// [`Bastion::children`].
+///
+/// # Example
+///
+/// ```rust
+/// use bastion::prelude_v2::*;
+///
+/// # fn main() {
+///     # Bastion::init();
+///     # Bastion::start();
+///     #
+/// let children_ref = Bastion::children(|children| {
+///     children.with_exec(|ctx: BastionContext| async move {
+///         loop {
+///             if let (TypedMsg::Ask { msg, sender }, _) = ctx.recv_msg::<u64>().await {
+///                 sender.answer(&ctx, msg * 2).expect("Couldn't answer.");
+///             }
+///         }
+///     })
+/// }).expect("Couldn't create the children group.");
+///
+/// let answer = children_ref.elems()[0]
+///     .ask_anonymously(21u64)
+///     .expect("Couldn't send the message.");
+/// let doubled: u64 = run(answer.await_typed()).expect("Couldn't receive the answer.");
+/// assert_eq!(doubled, 42);
+///     #
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`prelude`]: prelude/index.html
+/// [`task`]: task/index.html
+/// [`run!`]: macro.run.html
+/// [`spawn!`]: macro.spawn.html
+/// [`blocking!`]: macro.blocking.html
+/// [`BastionContext::recv_msg`]: context/struct.BastionContext.html#method.recv_msg
+/// [`BastionContext::recv_typed`]: context/struct.BastionContext.html#method.recv_typed
+/// [`Actor`]: actor/trait.Actor.html
+/// [`msg!`]: macro.msg.html
+/// [`AnswerSender::answer`]: message/struct.AnswerSender.html#method.answer
+/// [`answer!`]: macro.answer.html
+/// [`Answer::await_typed`]: message/struct.Answer.html#method.await_typed
+/// [`Bastion::supervisor`]: struct.Bastion.html#method.supervisor
+/// [`Bastion::children`]: struct.Bastion.html#method.children
+pub mod prelude_v2 {
+    pub use crate::ack::{AckReport, AckToken};
+    pub use crate::actor::{Actor, MessageTimeoutAction, Reply};
+    pub use crate::answer_stream::{AnswerStream, AnswerStreamSender};
+    #[cfg(feature = "supervision")]
+    pub use crate::backoff::Backoff;
+    pub use crate::bastion::Bastion;
+    pub use crate::batch::Batch;
+    pub use crate::callbacks::Callbacks;
+    pub use crate::child_ref::ChildRef;
+    pub use crate::children::{Children, GroupConfig, StatefulChildren};
+    pub use crate::children_ref::{AffinityStats, ChildrenRef};
+    #[cfg(feature = "supervision")]
+    pub use crate::config::RootAction;
+    pub use crate::config::{Config, ConfigChange, PanicHookOrder, ReloadError, RuntimeConfig};
+    pub use crate::context::{
+        BastionContext, BastionId, CancellationToken, ContextEvent, ShutdownToken, NIL_ID,
+    };
+    pub use crate::correlation::{Correlated, CorrelationId, ReplyBroker};
+    pub use crate::dead_letters::{
+        DeadLetter, DeadLetterMode, DeadLetterPayload, DeadLetterReason, DeadLetterSummary,
+    };
+    pub use crate::dispatcher::{
+        BroadcastTarget, DefaultDispatcherHandler, Dispatcher, DispatcherHandler, DispatcherMap,
+        DispatcherType, NotificationType,
+    };
+    pub use crate::envelope::{RefAddr, SignedMessage};
+    pub use crate::errors::{
+        ActorError, AnswerError, BastionError, BuilderConflict, BuilderError, ExecError,
+        PanicReport, ReceiveError, SendError, SendErrorKind, ShutdownError,
+    };
+    pub use crate::event::{Event, FaultReason};
+    pub use crate::flight_recorder::{FlightRecord, RecordOutcome};
+    pub use crate::health::Health;
+    pub use crate::history::TerminatedGroup;
+    pub use crate::idle::IdleCriteria;
+    pub use crate::instrument::ExecInfo;
+    pub use crate::mailbox::OverflowPolicy;
+    pub use crate::message::{
+        Answer, AnswerSender, AnswerTimeout, InlineMessage, Message, MessageTypes, Msg, Priority,
+        TypedMsg,
+    };
+    pub use crate::metrics::{ChildStats, GroupStats, MetricError, Metrics};
+    pub use crate::path::{BastionPath, BastionPathElement};
+    #[cfg(feature = "patterns")]
+    pub use crate::pipeline::{Pipeline, PipelineRef};
+    pub use crate::preset::SupervisionPreset;
+    pub use crate::provenance::{Provenance, ProvenanceEntry};
+    pub use crate::reactor::ReactorHealth;
+    pub use crate::reject::RejectDisposition;
+    #[cfg(feature = "autoscaling")]
+    pub use crate::resizer::{Pressure, Resizer};
+    pub use crate::routing::{Routing, RoutingError};
+    pub use crate::schedule::ScheduleHandle;
+    #[cfg(feature = "signals")]
+    pub use crate::signals::ShutdownConfig;
+    pub use crate::state_cell::{StateCell, StateError};
     pub use crate::supervisor::{
         ActorRestartStrategy, RestartPolicy, RestartStrategy, SupervisionStrategy, Supervisor,
         SupervisorRef,
     };
-    pub use crate::{answer, blocking, children, run, spawn, supervisor};
+    pub use crate::task::{run, spawn, spawn_blocking, RecoverableHandle};
+    pub use crate::typed::{TypedChildRef, TypedChildrenRef};
+    pub use crate::warmup::WarmupGate;
+    pub use crate::watch::{Termination, Watcher};
 }
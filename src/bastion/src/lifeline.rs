@@ -0,0 +1,103 @@
+//!
+//! The lifelines of the children groups, letting their elements and
+//! their supervisor know once a group's task was dropped without it
+//! stopping its elements (e.g. because its proc was cancelled).
+use crate::context::BastionId;
+use futures::channel::oneshot;
+use futures::future::{AbortHandle, Shared};
+use futures::prelude::*;
+use fxhash::FxHashMap;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+// How long the elements of a group whose task was dropped have to
+// finish handling their messages before they are killed.
+pub(crate) const ORPHAN_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+// Held by a children group (and moved along with it when it is
+// respawned), its lifeline being cut once it is dropped.
+//
+// NOTE: nothing is ever sent using the sender, which is only
+//      dropped.
+pub(crate) struct Anchor {
+    _sender: oneshot::Sender<()>,
+}
+
+#[derive(Debug, Clone)]
+// Shared by a children group with its elements and its supervisor,
+// keeping track of the elements' procs for the supervisor to
+// cancel the ones that didn't stop on their own once the group's
+// task was dropped.
+pub(crate) struct Lifeline {
+    cut: Shared<oneshot::Receiver<()>>,
+    elems: Arc<Elems>,
+}
+
+#[derive(Debug, Default)]
+struct Elems {
+    next: AtomicUsize,
+    handles: Mutex<FxHashMap<usize, (BastionId, AbortHandle)>>,
+}
+
+impl Lifeline {
+    pub(crate) fn new() -> (Anchor, Self) {
+        let (anchor, cut) = oneshot::channel();
+        let lifeline = Lifeline {
+            cut: cut.shared(),
+            elems: Arc::default(),
+        };
+
+        (Anchor { _sender: anchor }, lifeline)
+    }
+
+    // Resolves once the group's anchor was dropped.
+    pub(crate) fn poll_cut(&mut self, ctx: &mut Context) -> Poll<()> {
+        Pin::new(&mut self.cut).poll(ctx).map(|_| ())
+    }
+
+    // Keeps track of the proc of the element with this id until
+    // the returned key is untracked.
+    pub(crate) fn track(&self, id: BastionId, handle: AbortHandle) -> usize {
+        let key = self.elems.next.fetch_add(1, Ordering::Relaxed);
+        // FIXME: panics?
+        let mut handles = self.elems.handles.lock().unwrap();
+        handles.insert(key, (id, handle));
+
+        key
+    }
+
+    pub(crate) fn untrack(&self, key: usize) {
+        // FIXME: panics?
+        let mut handles = self.elems.handles.lock().unwrap();
+        handles.remove(&key);
+    }
+
+    pub(crate) fn tracks_elems(&self) -> bool {
+        // FIXME: panics?
+        !self.elems.handles.lock().unwrap().is_empty()
+    }
+
+    // Cancels the procs of the elements still tracked, returning
+    // their ids.
+    pub(crate) fn cancel_elems(&self) -> Vec<BastionId> {
+        // NOTE: the elements untrack themselves once dropped, so
+        //      the handles are only used once the lock is released.
+        let handles = {
+            // FIXME: panics?
+            let mut handles = self.elems.handles.lock().unwrap();
+            handles.drain().map(|(_, elem)| elem).collect::<Vec<_>>()
+        };
+
+        handles
+            .into_iter()
+            .map(|(id, handle)| {
+                handle.abort();
+                id
+            })
+            .collect()
+    }
+}
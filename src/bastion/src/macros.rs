@@ -9,6 +9,7 @@
 /// ```
 /// # use bastion::prelude::*;
 /// # fn main() {
+/// # Bastion::init();
 /// let children = children! {
 ///     // the default redundancy is 1
 ///     redundancy: 100,
@@ -134,6 +135,7 @@ macro_rules! children {
 /// ```
 /// # use bastion::prelude::*;
 /// # fn main() {
+/// # Bastion::init();
 /// let sp = supervisor! {
 ///     callbacks: Callbacks::default(),
 ///     strategy: SupervisionStrategy::OneForAll,
@@ -0,0 +1,229 @@
+//!
+//! Bounded mailboxes, limiting the number of messages sent to an
+//! element of a children group that it didn't retrieve yet (see
+//! [`Children::with_mailbox_capacity`]), and the number of
+//! questions asked to it that it didn't answer yet (see
+//! [`Children::with_max_pending_answers`]).
+//!
+//! [`Children::with_mailbox_capacity`]: ../children/struct.Children.html#method.with_mailbox_capacity
+//! [`Children::with_max_pending_answers`]: ../children/struct.Children.html#method.with_max_pending_answers
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// What happens to a message sent to an element whose mailbox
+/// is full (see [`Children::with_overflow_policy`]).
+///
+/// [`Children::with_overflow_policy`]: ../children/struct.Children.html#method.with_overflow_policy
+pub enum OverflowPolicy {
+    /// [`ChildRef::tell_async`] waits until the element retrieved
+    /// a message, while the other methods sending a message fail
+    /// like with [`Fail`].
+    ///
+    /// [`ChildRef::tell_async`]: ../child_ref/struct.ChildRef.html#method.tell_async
+    /// [`Fail`]: #variant.Fail
+    Block,
+    /// The message is dropped (and sent to the dead letters), but
+    /// sending it succeeds (and questions are never answered).
+    DropNewest,
+    /// The message is sent, and the oldest message of the mailbox
+    /// is dropped (and sent to the dead letters) once the element
+    /// receives it.
+    DropOldest,
+    /// Sending the message fails with
+    /// [`SendErrorKind::MailboxFull`].
+    ///
+    /// [`SendErrorKind::MailboxFull`]: ../errors/enum.SendErrorKind.html#variant.MailboxFull
+    Fail,
+}
+
+#[derive(Debug)]
+// The number of messages sent to an element using its `ChildRef`s
+// and not retrieved yet, shared by its `ChildRef`s and the slots
+// of those messages.
+pub(crate) struct Mailbox {
+    capacity: usize,
+    policy: OverflowPolicy,
+    len: AtomicUsize,
+    // The senders waiting for a message to be retrieved.
+    waiting: Mutex<Vec<Waker>>,
+}
+
+#[derive(Debug)]
+// A message's place in a mailbox, released once it is dropped.
+pub(crate) struct MailboxSlot(Arc<Mailbox>);
+
+#[derive(Debug)]
+// The number of questions asked to an element using its
+// `ChildRef`s whose answer's sender wasn't dropped yet (because
+// the element didn't answer nor drop them), shared by its
+// `ChildRef`s and the senders of those answers.
+pub(crate) struct PendingAnswers {
+    capacity: usize,
+    len: AtomicUsize,
+    // The senders waiting for a question to be answered.
+    waiting: Mutex<Vec<Waker>>,
+}
+
+#[derive(Debug)]
+// A question's place among the pending answers of an element,
+// released once the sender of its answer is dropped.
+pub(crate) struct AnswerSlot(Arc<PendingAnswers>);
+
+impl Default for OverflowPolicy {
+    fn default() -> Self {
+        OverflowPolicy::Fail
+    }
+}
+
+impl Mailbox {
+    pub(crate) fn new(capacity: usize, policy: OverflowPolicy) -> Arc<Self> {
+        let mailbox = Mailbox {
+            capacity,
+            policy,
+            len: AtomicUsize::new(0),
+            waiting: Mutex::new(Vec::new()),
+        };
+
+        Arc::new(mailbox)
+    }
+
+    pub(crate) fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    // Takes a place in the mailbox, unless it is full and its
+    // policy isn't to drop its oldest messages.
+    pub(crate) fn reserve(self: &Arc<Self>) -> Option<MailboxSlot> {
+        if self.policy == OverflowPolicy::DropOldest {
+            self.len.fetch_add(1, Ordering::SeqCst);
+            return Some(MailboxSlot(self.clone()));
+        }
+
+        let mut len = self.len.load(Ordering::SeqCst);
+        loop {
+            if len >= self.capacity {
+                return None;
+            }
+
+            match self
+                .len
+                .compare_exchange_weak(len, len + 1, Ordering::SeqCst, Ordering::SeqCst)
+            {
+                Ok(_) => return Some(MailboxSlot(self.clone())),
+                Err(current) => len = current,
+            }
+        }
+    }
+
+    // Whether the mailbox holds more messages than its capacity
+    // because its policy is to drop its oldest messages.
+    pub(crate) fn overflows(&self) -> bool {
+        self.policy == OverflowPolicy::DropOldest && self.len.load(Ordering::SeqCst) > self.capacity
+    }
+
+    // Takes a place in the mailbox, or registers the task to be
+    // woken once a place was released if it is full.
+    pub(crate) fn poll_reserve(self: &Arc<Self>, ctx: &mut Context) -> Poll<MailboxSlot> {
+        if let Some(slot) = self.reserve() {
+            return Poll::Ready(slot);
+        }
+
+        let mut waiting = self.waiting.lock().unwrap();
+        if !waiting.iter().any(|waker| waker.will_wake(ctx.waker())) {
+            waiting.push(ctx.waker().clone());
+        }
+
+        // A place might have been released before the task was
+        // registered.
+        match self.reserve() {
+            Some(slot) => Poll::Ready(slot),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl PendingAnswers {
+    pub(crate) fn new(capacity: usize) -> Arc<Self> {
+        let pending = PendingAnswers {
+            capacity,
+            len: AtomicUsize::new(0),
+            waiting: Mutex::new(Vec::new()),
+        };
+
+        Arc::new(pending)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len.load(Ordering::SeqCst)
+    }
+
+    // Takes a place among the pending answers, unless there are
+    // already as many of them as allowed.
+    pub(crate) fn reserve(self: &Arc<Self>) -> Option<AnswerSlot> {
+        let mut len = self.len.load(Ordering::SeqCst);
+        loop {
+            if len >= self.capacity {
+                return None;
+            }
+
+            match self
+                .len
+                .compare_exchange_weak(len, len + 1, Ordering::SeqCst, Ordering::SeqCst)
+            {
+                Ok(_) => return Some(AnswerSlot(self.clone())),
+                Err(current) => len = current,
+            }
+        }
+    }
+
+    // Takes a place among the pending answers, or registers the
+    // task to be woken once a place was released if there isn't
+    // any left.
+    pub(crate) fn poll_reserve(self: &Arc<Self>, ctx: &mut Context) -> Poll<AnswerSlot> {
+        if let Some(slot) = self.reserve() {
+            return Poll::Ready(slot);
+        }
+
+        let mut waiting = self.waiting.lock().unwrap();
+        if !waiting.iter().any(|waker| waker.will_wake(ctx.waker())) {
+            waiting.push(ctx.waker().clone());
+        }
+
+        // A place might have been released before the task was
+        // registered.
+        match self.reserve() {
+            Some(slot) => Poll::Ready(slot),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl MailboxSlot {
+    pub(crate) fn mailbox(&self) -> &Arc<Mailbox> {
+        &self.0
+    }
+}
+
+impl Drop for MailboxSlot {
+    fn drop(&mut self) {
+        self.0.len.fetch_sub(1, Ordering::SeqCst);
+
+        let waiting = std::mem::take(&mut *self.0.waiting.lock().unwrap());
+        for waker in waiting {
+            waker.wake();
+        }
+    }
+}
+
+impl Drop for AnswerSlot {
+    fn drop(&mut self) {
+        self.0.len.fetch_sub(1, Ordering::SeqCst);
+
+        let waiting = std::mem::take(&mut *self.0.waiting.lock().unwrap());
+        for waker in waiting {
+            waker.wake();
+        }
+    }
+}
@@ -6,19 +6,40 @@
 //! * All message communication relies on at-most-once delivery guarantee.
 //! * Messages are not guaranteed to be ordered, all message's order is causal.
 //!
+use crate::ack::AckToken;
+use crate::answer_stream::{self, AnswerStream, AnswerStreamSender};
+use crate::blocking_bridge;
 use crate::callbacks::CallbackType;
+use crate::child::Init;
+use crate::child_ref::ChildRef;
 use crate::children::Children;
-use crate::context::{BastionId, ContextState};
+use crate::children_ref::{ChildrenRef, MigrationError, MigrationReport};
+use crate::context::{BastionContext, BastionId, ContextState};
+use crate::correlation::{Correlated, CorrelationId};
 use crate::envelope::{RefAddr, SignedMessage};
+use crate::errors::{AnswerError, ExecError, SendError, SendErrorKind};
+use crate::event::FaultReason;
+use crate::flight_recorder::FlightRecord;
+use crate::mailbox::AnswerSlot;
+use crate::provenance::{self, Provenance, ProvenanceEntry};
+use crate::routing::{Routing, RoutingError};
 use crate::supervisor::{SupervisionStrategy, Supervisor};
+use crate::system::SYSTEM;
+use bastion_executor::worker;
 use futures::channel::oneshot::{self, Receiver};
+use futures_timer::Delay;
+use fxhash::FxHashSet;
+use lazy_static::lazy_static;
 use qutex::Qutex;
-use std::any::{type_name, Any};
-use std::fmt::Debug;
+use std::any::{type_name, Any, TypeId};
+use std::fmt::{self, Debug, Formatter};
 use std::future::Future;
+use std::mem::{self, MaybeUninit};
 use std::pin::Pin;
-use std::sync::Arc;
+use std::ptr;
+use std::sync::{Arc, Mutex, RwLock};
 use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 
 /// A trait that any message sent needs to implement (it is
 /// already automatically implemented but forces message to
@@ -32,16 +53,349 @@ use std::task::{Context, Poll};
 pub trait Message: Any + Send + Sync + Debug {}
 impl<T> Message for T where T: Any + Send + Sync + Debug {}
 
-#[derive(Debug)]
+/// A list of [`Message`] types, implemented for the tuples of
+/// up to eight of them (e.g. `(A,)` or `(A, B, C)`), which is
+/// passed to [`Children::with_accepted_types`].
+///
+/// [`Message`]: trait.Message.html
+/// [`Children::with_accepted_types`]: ../children/struct.Children.html#method.with_accepted_types
+pub trait MessageTypes {
+    #[doc(hidden)]
+    fn types() -> Vec<(TypeId, &'static str)>;
+}
+
+macro_rules! impl_message_types {
+    ($($ty:ident),+) => {
+        impl<$($ty: Message),+> MessageTypes for ($($ty,)+) {
+            fn types() -> Vec<(TypeId, &'static str)> {
+                vec![$((TypeId::of::<$ty>(), type_name::<$ty>())),+]
+            }
+        }
+    };
+}
+
+impl_message_types!(A);
+impl_message_types!(A, B);
+impl_message_types!(A, B, C);
+impl_message_types!(A, B, C, D);
+impl_message_types!(A, B, C, D, E);
+impl_message_types!(A, B, C, D, E, F);
+impl_message_types!(A, B, C, D, E, F, G);
+impl_message_types!(A, B, C, D, E, F, G, H);
+
+#[doc(hidden)]
+pub mod private {
+    pub trait Sealed {}
+}
+
+/// A [`Message`] small enough to be stored inline when it is told,
+/// instead of being boxed, which saves an allocation per message.
+///
+/// The messages are stored inline transparently: they are still
+/// matched using [`msg!`] and downcasted like any other message.
+/// Only the told messages whose type implements this trait are
+/// stored inline (in a buffer of three words, aligned like a
+/// `usize`), the others being boxed.
+///
+/// This trait is sealed: it is implemented for the primitive
+/// types (except `u128` and `i128`), `()`, `&'static str` and
+/// [`Duration`], and can be derived for the small `Copy` types of
+/// the messages using the [`inline_message!`] macro, which fails
+/// to compile for the ones that don't fit. The messages of the
+/// derived types are only stored inline once their type was
+/// registered using [`register`].
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// #[derive(Debug, Clone, Copy)]
+/// struct Ping {
+///     seq: u64,
+/// }
+///
+/// inline_message!(Ping);
+///
+/// # fn main() {
+/// Ping::register();
+/// # }
+/// ```
+///
+/// [`Message`]: trait.Message.html
+/// [`Duration`]: https://doc.rust-lang.org/std/time/struct.Duration.html
+/// [`msg!`]: ../macro.msg.html
+/// [`inline_message!`]: ../macro.inline_message.html
+/// [`register`]: #method.register
+pub trait InlineMessage: Message + Copy + private::Sealed {
+    /// Makes the messages of this type be stored inline from now
+    /// on, when they are told.
+    ///
+    /// Registering a type more than once (or one of the types this
+    /// trait is implemented for by bastion) doesn't do anything.
+    fn register() {
+        if is_builtin_inline::<Self>() {
+            return;
+        }
+
+        // FIXME: panics?
+        REGISTERED.write().unwrap().insert(TypeId::of::<Self>());
+    }
+}
+
+/// Implements [`InlineMessage`] for the given `Copy` message
+/// types, failing to compile if one of them is too large to be
+/// stored inline.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// #[derive(Debug, Clone, Copy)]
+/// struct Id(u64);
+///
+/// #[derive(Debug, Clone, Copy)]
+/// enum Command {
+///     Start,
+///     Stop,
+/// }
+///
+/// inline_message!(Id, Command);
+/// ```
+///
+/// ```compile_fail
+/// # use bastion::prelude::*;
+/// #
+/// #[derive(Debug, Clone, Copy)]
+/// struct TooLarge([u64; 4]);
+///
+/// inline_message!(TooLarge);
+/// ```
+///
+/// [`InlineMessage`]: message/trait.InlineMessage.html
+#[macro_export]
+macro_rules! inline_message {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl $crate::message::private::Sealed for $ty {}
+            impl $crate::message::InlineMessage for $ty {}
+            const _: () = assert!(
+                $crate::message::fits_inline::<$ty>(),
+                "The message type is too large to be stored inline."
+            );
+        )+
+    };
+}
+
+// Implements `InlineMessage` for the given types, which are always
+// stored inline.
+macro_rules! builtin_inline_messages {
+    ($($ty:ty),+ $(,)?) => {
+        inline_message!($($ty),+);
+
+        // Whether `M` is one of the types `InlineMessage` is
+        // implemented for by bastion.
+        fn is_builtin_inline<M: Message>() -> bool {
+            let id = TypeId::of::<M>();
+            $(id == TypeId::of::<$ty>())||+
+        }
+    };
+}
+
+// The size of the buffer the inline messages are stored in, in
+// words, which holds e.g. a `Duration` or a `&'static str`.
+const INLINE_WORDS: usize = 3;
+
 #[doc(hidden)]
-pub struct AnswerSender(oneshot::Sender<SignedMessage>);
+// Whether the messages of type `M` can be stored inline: they
+// must fit in the buffer and not need to be dropped (so that
+// storing them doesn't require running their destructor).
+pub const fn fits_inline<M>() -> bool {
+    mem::size_of::<M>() <= mem::size_of::<InlineBuf>()
+        && mem::align_of::<M>() <= mem::align_of::<InlineBuf>()
+        && !mem::needs_drop::<M>()
+}
+
+// Whether the told messages of type `M` are stored inline: only the
+// ones implementing `InlineMessage` are, whose `Copy` bound the
+// buffer relies on rather than on `fits_inline` alone (which e.g.
+// allows `&'static mut T`).
+fn is_inline<M: Message>() -> bool {
+    if !fits_inline::<M>() {
+        return false;
+    }
+
+    if is_builtin_inline::<M>() {
+        return true;
+    }
+
+    // FIXME: panics?
+    REGISTERED.read().unwrap().contains(&TypeId::of::<M>())
+}
+
+lazy_static! {
+    // The types deriving `InlineMessage` that were registered using
+    // `InlineMessage::register`.
+    static ref REGISTERED: RwLock<FxHashSet<TypeId>> = RwLock::new(FxHashSet::default());
+}
+
+#[derive(Clone, Copy)]
+// The buffer an inline message is stored in.
+struct InlineBuf([MaybeUninit<usize>; INLINE_WORDS]);
+
+// A told message stored inline, along with the function returning
+// it (for the type it was stored as).
+//
+// The payload takes four words: its buffer and the function's
+// pointer.
+pub(crate) struct InlinePayload {
+    buf: InlineBuf,
+    as_any: fn(&InlineBuf) -> &(dyn Any + Send + Sync + 'static),
+}
+
+const _: () = assert!(mem::size_of::<InlineBuf>() == 3 * mem::size_of::<usize>());
+const _: () = assert!(mem::size_of::<InlinePayload>() <= 4 * mem::size_of::<usize>());
+
+builtin_inline_messages!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    isize,
+    f32,
+    f64,
+    &'static str,
+    Duration,
+);
+
+#[allow(unsafe_code)]
+impl InlinePayload {
+    // Stores `msg` inline if its type is (see `is_inline`), or
+    // returns it otherwise.
+    fn new<M: Message>(msg: M) -> Result<Self, M> {
+        if !is_inline::<M>() {
+            return Err(msg);
+        }
+
+        let mut buf = InlineBuf([MaybeUninit::uninit(); INLINE_WORDS]);
+        // SAFETY: the buffer is large and aligned enough for `M`,
+        // and `msg` is moved into it.
+        unsafe { ptr::write(buf.0.as_mut_ptr() as *mut M, msg) };
+
+        Ok(InlinePayload {
+            buf,
+            as_any: as_any::<M>,
+        })
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+        (self.as_any)(&self.buf)
+    }
+
+    fn take<M: Message>(self) -> Result<M, Self> {
+        if !self.as_any().is::<M>() {
+            return Err(self);
+        }
+
+        // SAFETY: the buffer holds a `M`, which is moved out of the
+        // payload (consumed, as `M` doesn't need to be dropped).
+        Ok(unsafe { ptr::read(self.buf.0.as_ptr() as *const M) })
+    }
+}
+
+#[allow(unsafe_code)]
+// Returns the `M` stored in the buffer of an inline payload.
+fn as_any<M: Message>(buf: &InlineBuf) -> &(dyn Any + Send + Sync + 'static) {
+    // SAFETY: only used by the payloads holding a `M` (see
+    // `InlinePayload::new`).
+    unsafe { &*(buf.0.as_ptr() as *const M) }
+}
+
+impl Debug for InlinePayload {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_tuple("InlinePayload")
+            .field(&self.as_any())
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+// The message types a children group accepts, or `None` if it
+// accepts any of them.
+pub(crate) struct AcceptedTypes(Option<Arc<[(TypeId, &'static str)]>>);
+
+#[derive(Debug)]
+/// The sender of the answer to a message that was "asked",
+/// retrieved along with it by [`BastionContext::recv_typed`]
+/// or [`Msg::downcast_typed`].
+///
+/// [`BastionContext::recv_typed`]: ../context/struct.BastionContext.html#method.recv_typed
+/// [`Msg::downcast_typed`]: struct.Msg.html#method.downcast_typed
+pub struct AnswerSender(oneshot::Sender<SignedMessage>, Priority, Option<AnswerSlot>);
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+/// The lane of their recipient's mailbox messages land in, the
+/// ones sent with a `High` priority being retrieved before the
+/// `Normal` ones received before them (see
+/// [`ChildRef::ask_with_priority`] and
+/// [`Children::with_ask_priority`]).
+///
+/// [`ChildRef::ask_with_priority`]: ../child_ref/struct.ChildRef.html#method.ask_with_priority
+/// [`Children::with_ask_priority`]: ../children/struct.Children.html#method.with_ask_priority
+pub enum Priority {
+    /// The priority of the messages that are sent without one.
+    Normal,
+    /// The priority of latency-critical messages, which don't wait
+    /// behind the `Normal` ones.
+    High,
+}
+
+#[derive(Debug)]
+/// A message of type `T` received by an element, retrieved
+/// using [`BastionContext::recv_typed`] or
+/// [`Msg::downcast_typed`].
+///
+/// [`BastionContext::recv_typed`]: ../context/struct.BastionContext.html#method.recv_typed
+/// [`Msg::downcast_typed`]: struct.Msg.html#method.downcast_typed
+pub enum TypedMsg<T> {
+    /// A message that was broadcasted, shared with its other
+    /// recipients.
+    Broadcast(Arc<T>),
+    /// A message that was "told" (or "asked", if the sender of
+    /// its answer was already taken).
+    Tell(T),
+    /// A message that was "asked", along with the sender of its
+    /// answer.
+    Ask {
+        /// The message.
+        msg: T,
+        /// The sender its answer can be sent with.
+        sender: AnswerSender,
+    },
+}
 
 #[derive(Debug)]
 /// A [`Future`] returned when successfully "asking" a
 /// message using [`ChildRef::ask`] and which resolves to
-/// a `Result<Msg, ()>` where the [`Msg`] is the message
-/// answered by the child (see the [`msg!`] macro for more
-/// information).
+/// a `Result<Msg, AnswerError>` where the [`Msg`] is the
+/// message answered by the child (see the [`msg!`] macro for
+/// more information), or the [`AnswerError`] if the message
+/// was dropped without being answered.
+///
+/// The message is dropped once the child stops holding it
+/// without answering it (e.g. because it panicked), or if it
+/// didn't retrieve it before being stopped or killed. A child
+/// that gets restarted instead keeps the messages it didn't
+/// retrieve yet.
 ///
 /// # Example
 ///
@@ -112,7 +466,24 @@ pub struct AnswerSender(oneshot::Sender<SignedMessage>);
 /// [`ChildRef::ask`]: ../children/struct.ChildRef.html#method.ask
 /// [`Msg`]: message/struct.Msg.html
 /// [`msg!`]: macro.msg.html
-pub struct Answer(Receiver<SignedMessage>);
+/// [`AnswerError`]: ../errors/enum.AnswerError.html
+pub struct Answer(Receiver<SignedMessage>, Option<Delay>);
+
+#[derive(Debug)]
+/// A [`Future`] returned by [`Answer::timeout`] (or
+/// [`ChildRef::ask_timeout`]), resolving like the [`Answer`] it
+/// was created from, or to [`AnswerError::TimedOut`] if the
+/// message wasn't answered before the timeout expired.
+///
+/// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+/// [`Answer::timeout`]: struct.Answer.html#method.timeout
+/// [`ChildRef::ask_timeout`]: ../child_ref/struct.ChildRef.html#method.ask_timeout
+/// [`Answer`]: struct.Answer.html
+/// [`AnswerError::TimedOut`]: ../errors/enum.AnswerError.html#variant.TimedOut
+pub struct AnswerTimeout {
+    answer: Answer,
+    delay: Delay,
+}
 
 #[derive(Debug)]
 /// A message returned by [`BastionContext::recv`] or
@@ -173,27 +544,98 @@ pub struct Answer(Receiver<SignedMessage>);
 /// [`BastionContext::recv`]: context/struct.BastionContext.html#method.recv
 /// [`BastionContext::try_recv`]: context/struct.BastionContext.html#method.try_recv
 /// [`msg!`]: macro.msg.html
-pub struct Msg(MsgInner);
+pub struct Msg {
+    inner: MsgInner,
+    // What the message is recorded as in the provenance chains of
+    // the messages sent while handling it.
+    entry: ProvenanceEntry,
+    // Only set if the message was sent by an element recording the
+    // provenance, so that the others don't pay for it.
+    provenance: Option<Box<Provenance>>,
+    // The priority the message was sent with, if any.
+    priority: Option<Priority>,
+    // The token to acknowledge the message with, if it was
+    // broadcasted using `ChildrenRef::broadcast_acked`.
+    ack: Option<AckToken>,
+    // The sender of the replies to the message, if it was sent
+    // using `ChildRef::ask_stream`.
+    stream: Option<AnswerStreamSender>,
+    // The number of times the message was rerouted after being
+    // rejected (see `BastionContext::reject_message`).
+    hops: usize,
+}
 
 #[derive(Debug)]
 enum MsgInner {
     Broadcast(Arc<dyn Any + Send + Sync + 'static>),
     Tell(Box<dyn Any + Send + Sync + 'static>),
+    // A told message stored without being boxed.
+    Inline(InlinePayload),
     Ask {
         msg: Box<dyn Any + Send + Sync + 'static>,
         sender: Option<AnswerSender>,
     },
+    // A message lent to an actor whose handling can be cancelled
+    // (see `Msg::lend`).
+    Lent(Box<Lent>),
+}
+
+// The slot a lent message is moved back into.
+type LentSlot = Arc<Mutex<Option<MsgInner>>>;
+
+#[derive(Debug)]
+// A message which is moved back into `slot` if it is dropped
+// without being taken, e.g. along with an actor's handling that
+// took too long.
+struct Lent {
+    inner: Option<MsgInner>,
+    slot: LentSlot,
+}
+
+#[derive(Debug)]
+// What is needed to get a lent message back once the actor it
+// was lent to is done with it (see `Msg::lend`).
+pub(crate) struct Reclaim {
+    slot: LentSlot,
+    entry: ProvenanceEntry,
+    provenance: Option<Box<Provenance>>,
+    priority: Option<Priority>,
+    ack: Option<AckToken>,
+    hops: usize,
 }
 
 #[derive(Debug)]
 pub(crate) enum BastionMessage {
     Start,
     Stop,
+    // Stops the elements of a group once they are done handling
+    // their messages, killing the ones still running once the
+    // timeout expired.
+    StopWithin(Duration),
+    // Asks the elements of a group to stop on their own before
+    // the deadline, stopping the ones still running once it
+    // expired like `StopWithin` does.
+    Quiesce(Duration),
     Kill,
-    Deploy(Deployment),
+    Deploy(Box<Deployment>),
     Prune {
         id: BastionId,
     },
+    // Cancels the task of the children group with this id, as the
+    // executor would, without it stopping its elements.
+    CancelTask(BastionId),
+    // Launches or prunes elements of a group until it has this
+    // number of them, answering with its updated `ChildrenRef`.
+    Scale {
+        redundancy: usize,
+        sender: oneshot::Sender<ChildrenRef>,
+    },
+    // Changes how a group routes its routed messages, answering
+    // once it did or why it couldn't.
+    SetRouting {
+        routing: Routing,
+        sender: oneshot::Sender<Result<(), RoutingError>>,
+    },
     SuperviseWith(SupervisionStrategy),
     ApplyCallback(CallbackType),
     InstantiatedChild {
@@ -205,12 +647,29 @@ pub(crate) enum BastionMessage {
     RestartRequired {
         id: BastionId,
         parent_id: BastionId,
+        reason: FaultReason,
+        // The error returned by the element's future, or the
+        // report of its panic.
+        error: Option<ExecError>,
+        // The provenance of the message the element was handling,
+        // if its group records it.
+        provenance: Provenance,
+        // The messages the element handled last, if its group
+        // enables its flight recorder.
+        records: Vec<FlightRecord>,
     },
     FinishedChild {
         id: BastionId,
         parent_id: BastionId,
     },
     RestartSubtree,
+    // Stops the supervised children groups, except the ones
+    // registered with one of those names, until `Recover` is
+    // received.
+    Degrade {
+        keep: Vec<String>,
+    },
+    Recover,
     RestoreChild {
         id: BastionId,
         state: Qutex<Pin<Box<ContextState>>>,
@@ -223,58 +682,368 @@ pub(crate) enum BastionMessage {
     },
     Stopped {
         id: BastionId,
+        // Whether the group had to kill the elements which didn't
+        // stop in time.
+        killed: bool,
     },
     Faulted {
         id: BastionId,
     },
+    MigrateChild {
+        to: ChildRef,
+        sender: oneshot::Sender<Result<MigrationReport, MigrationError>>,
+    },
 }
 
 #[derive(Debug)]
+// Always boxed (see `BastionMessage::Deploy`), so that its variants
+// don't need to be.
+#[allow(clippy::large_enum_variant)]
 pub(crate) enum Deployment {
     Supervisor(Supervisor),
     Children(Children),
+    // A new element of the children group receiving it.
+    Child {
+        init: Init,
+        sender: oneshot::Sender<ChildRef>,
+    },
 }
 
 impl AnswerSender {
     // FIXME: we can't let manipulating Signature in a public API
     // but now it's being called only by a macro so we are trusting it
     #[doc(hidden)]
-    pub fn send<M: Message>(self, msg: M, sign: RefAddr) -> Result<(), M> {
+    pub fn send<M: Message>(self, msg: M, sign: RefAddr) -> Result<(), SendError<M>> {
         debug!("{:?}: Sending answer: {:?}", self, msg);
-        let msg = Msg::tell(msg);
+        self.send_msg(Msg::tell(msg), sign)
+            .map_err(|msg| SendError::stopped(msg.try_unwrap().unwrap()))
+    }
+
+    // Sends the answer, returning it if the question's sender
+    // stopped waiting for it.
+    pub(crate) fn send_msg(self, msg: Msg, sign: RefAddr) -> Result<(), Msg> {
+        // The answer inherits the priority of the question.
+        let msg = msg.with_priority(self.1);
         trace!("{:?}: Sending message: {:?}", self, msg);
-        self.0
+        // The question stops being pending before its sender can
+        // receive the answer.
+        let AnswerSender(sender, _, slot) = self;
+        drop(slot);
+        sender
             .send(SignedMessage::new(msg, sign))
-            .map_err(|smsg| smsg.msg.try_unwrap().unwrap())
+            .map_err(|smsg| smsg.msg)
+    }
+
+    /// Sends the answer to the message this sender was retrieved
+    /// with, signed by the element `ctx` is linked to.
+    ///
+    /// This method returns `()` if it succeeded, or a
+    /// [`SendError`](../errors/struct.SendError.html) carrying the
+    /// answer if the message's sender stopped waiting for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of the element answering.
+    /// * `msg` - The answer.
+    pub fn answer<M: Message>(self, ctx: &BastionContext, msg: M) -> Result<(), SendError<M>> {
+        self.send(msg, ctx.signature())
+    }
+
+    // Resolves once the `Answer` waiting for the answer was
+    // dropped.
+    pub(crate) fn poll_canceled(&mut self, ctx: &mut Context) -> Poll<()> {
+        self.0.poll_canceled(ctx)
+    }
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Priority::Normal
+    }
+}
+
+impl AcceptedTypes {
+    pub(crate) fn new<T: MessageTypes>() -> Self {
+        AcceptedTypes(Some(T::types().into()))
+    }
+
+    // Returns the names of the accepted types, unless all the
+    // types are.
+    pub(crate) fn names(&self) -> Option<Vec<&'static str>> {
+        let types = self.0.as_ref()?;
+        Some(types.iter().map(|(_, name)| *name).collect())
+    }
+
+    pub(crate) fn accepts(&self, id: TypeId) -> bool {
+        match &self.0 {
+            Some(types) => types.iter().any(|(accepted, _)| *accepted == id),
+            None => true,
+        }
+    }
+
+    // Returns whether messages of type `M` are the only ones that
+    // are accepted.
+    pub(crate) fn only<M: Message>(&self) -> bool {
+        match &self.0 {
+            Some(types) => types.iter().all(|(id, _)| *id == TypeId::of::<M>()),
+            None => false,
+        }
+    }
+
+    pub(crate) fn check<M: Message>(&self) -> Result<(), SendErrorKind> {
+        let types = match &self.0 {
+            Some(types) => types,
+            None => return Ok(()),
+        };
+
+        let id = TypeId::of::<M>();
+        if types.iter().any(|(accepted, _)| *accepted == id) {
+            return Ok(());
+        }
+
+        Err(SendErrorKind::TypeNotAccepted {
+            got: type_name::<M>(),
+        })
     }
 }
 
 impl Msg {
+    fn new<M: Message>(inner: MsgInner) -> Self {
+        Msg {
+            inner,
+            entry: ProvenanceEntry::new(type_name::<M>()),
+            provenance: provenance::current().map(Box::new),
+            priority: None,
+            ack: None,
+            stream: None,
+            hops: 0,
+        }
+    }
+
     pub(crate) fn broadcast<M: Message>(msg: M) -> Self {
         let inner = MsgInner::Broadcast(Arc::new(msg));
-        Msg(inner)
+        Msg::new::<M>(inner)
     }
 
     pub(crate) fn tell<M: Message>(msg: M) -> Self {
-        let inner = MsgInner::Tell(Box::new(msg));
-        Msg(inner)
+        let inner = match InlinePayload::new(msg) {
+            Ok(msg) => MsgInner::Inline(msg),
+            Err(msg) => MsgInner::Tell(Box::new(msg)),
+        };
+
+        Msg::new::<M>(inner)
+    }
+
+    // A message sent using `ChildRef::tell_correlated`, recorded
+    // as `M` in the provenance chains.
+    pub(crate) fn correlated<M: Message>(id: CorrelationId, msg: M) -> Self {
+        let inner = MsgInner::Tell(Box::new(Correlated::new(id, msg)));
+        Msg::new::<M>(inner).with_correlation_id(id)
+    }
+
+    /// Returns the name of the message's type (as returned by
+    /// [`std::any::type_name`] when it was sent), e.g. to log the
+    /// messages that weren't matched by the [`msg!`] macro.
+    ///
+    /// The messages sent using [`ChildRef::tell_correlated`] are
+    /// named after the type of the message that was sent.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             loop {
+    ///                 msg! { ctx.recv().await?,
+    ///                     n: u64 => {
+    ///                         // Handle the message...
+    ///                         # drop(n);
+    ///                     };
+    ///                     unknown: _ => {
+    ///                         println!("Ignoring a `{}`.", unknown.type_name());
+    ///                     };
+    ///                 }
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`std::any::type_name`]: https://doc.rust-lang.org/std/any/fn.type_name.html
+    /// [`msg!`]: ../macro.msg.html
+    /// [`ChildRef::tell_correlated`]: ../child_ref/struct.ChildRef.html#method.tell_correlated
+    pub fn type_name(&self) -> &'static str {
+        self.entry.type_name()
+    }
+
+    pub(crate) fn payload(&self) -> &(dyn Any + Send + Sync + 'static) {
+        match self.inner.get() {
+            MsgInner::Broadcast(msg) => &**msg,
+            MsgInner::Tell(msg) => &**msg,
+            MsgInner::Inline(msg) => msg.as_any(),
+            MsgInner::Ask { msg, .. } => &**msg,
+            MsgInner::Lent(_) => unreachable!(),
+        }
+    }
+
+    pub(crate) fn with_ack_token(mut self, token: AckToken) -> Self {
+        self.ack = Some(token);
+        self
+    }
+
+    pub(crate) fn hops(&self) -> usize {
+        self.hops
+    }
+
+    pub(crate) fn rerouted(&mut self) {
+        self.hops += 1;
+    }
+
+    pub(crate) fn with_correlation_id(mut self, id: CorrelationId) -> Self {
+        self.entry.set_correlation_id(id);
+        self
+    }
+
+    // Sets the message's priority, which its answer inherits if it
+    // is asked.
+    pub(crate) fn with_priority(mut self, priority: Priority) -> Self {
+        if let MsgInner::Ask {
+            sender: Some(sender),
+            ..
+        } = self.inner.get_mut()
+        {
+            sender.1 = priority;
+        }
+
+        self.priority = Some(priority);
+        self
+    }
+
+    // Makes the message take this place among the pending answers
+    // of its recipient until the sender of its answer is dropped,
+    // if it is asked.
+    pub(crate) fn with_answer_slot(mut self, slot: AnswerSlot) -> Self {
+        if let MsgInner::Ask {
+            sender: Some(sender),
+            ..
+        } = self.inner.get_mut()
+        {
+            sender.2 = Some(slot);
+        }
+
+        self
+    }
+
+    // Sets the priority of the message if it was asked without one.
+    pub(crate) fn or_ask_priority(self, priority: Priority) -> Self {
+        if self.priority.is_none() && self.is_ask() {
+            self.with_priority(priority)
+        } else {
+            self
+        }
+    }
+
+    /// Returns the priority the message was sent with (which the
+    /// answer of an "asked" message inherits), or
+    /// [`Priority::Normal`] if it was sent without one.
+    ///
+    /// [`Priority::Normal`]: enum.Priority.html#variant.Normal
+    pub fn priority(&self) -> Priority {
+        self.priority.unwrap_or_default()
+    }
+
+    /// Returns the token to pass to [`BastionContext::ack`] once
+    /// the message is applied, if it was broadcasted using
+    /// [`ChildrenRef::broadcast_acked`].
+    ///
+    /// [`BastionContext::ack`]: ../context/struct.BastionContext.html#method.ack
+    /// [`ChildrenRef::broadcast_acked`]: ../children_ref/struct.ChildrenRef.html#method.broadcast_acked
+    pub fn ack_token(&self) -> Option<AckToken> {
+        self.ack.clone()
+    }
+
+    /// Returns the entries of the message's provenance chain,
+    /// starting with the message its sender was handling when it
+    /// sent it, if the sender's children group records the
+    /// provenance (see [`Children::with_provenance`]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             let (msg, _) = ctx.recv().await?.extract();
+    ///             for entry in msg.provenance() {
+    ///                 println!("Caused by a `{}`.", entry.type_name());
+    ///             }
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::with_provenance`]: ../children/struct.Children.html#method.with_provenance
+    pub fn provenance(&self) -> &[ProvenanceEntry] {
+        match &self.provenance {
+            Some(provenance) => provenance.entries(),
+            None => &[],
+        }
+    }
+
+    /// Returns whether entries were dropped from the message's
+    /// provenance chain because it exceeded its maximum depth.
+    pub fn is_provenance_truncated(&self) -> bool {
+        self.provenance
+            .as_ref()
+            .is_some_and(|provenance| provenance.is_truncated())
+    }
+
+    pub(crate) fn provenance_entry(&self) -> &ProvenanceEntry {
+        &self.entry
+    }
+
+    pub(crate) fn provenance_chain(&self) -> Option<&Provenance> {
+        self.provenance.as_deref()
     }
 
     pub(crate) fn ask<M: Message>(msg: M) -> (Self, Answer) {
         let msg = Box::new(msg);
         let (sender, recver) = oneshot::channel();
-        let sender = AnswerSender(sender);
-        let answer = Answer(recver);
+        let sender = AnswerSender(sender, Priority::default(), None);
+        let answer = Answer::new(recver);
 
         let sender = Some(sender);
         let inner = MsgInner::Ask { msg, sender };
 
-        (Msg(inner), answer)
+        (Msg::new::<M>(inner), answer)
     }
 
     #[doc(hidden)]
     pub fn is_broadcast(&self) -> bool {
-        if let MsgInner::Broadcast(_) = self.0 {
+        if let MsgInner::Broadcast(_) = self.inner.get() {
             true
         } else {
             false
@@ -283,84 +1052,235 @@ impl Msg {
 
     #[doc(hidden)]
     pub fn is_tell(&self) -> bool {
-        if let MsgInner::Tell(_) = self.0 {
-            true
-        } else {
-            false
-        }
+        matches!(self.inner.get(), MsgInner::Tell(_) | MsgInner::Inline(_))
     }
 
     #[doc(hidden)]
     pub fn is_ask(&self) -> bool {
-        if let MsgInner::Ask { .. } = self.0 {
+        if let MsgInner::Ask { .. } = self.inner.get() {
             true
         } else {
             false
         }
     }
 
+    /// Takes the sender allowing to reply several times to the
+    /// message, if it was sent using [`ChildRef::ask_stream`] (and
+    /// the sender wasn't taken yet).
+    ///
+    /// Note that the sender is dropped along with the message
+    /// (ending the stream of replies with an error) if it isn't
+    /// taken beforehand, e.g. when matching the message using
+    /// [`msg!`].
+    ///
+    /// [`ChildRef::ask_stream`]: ../child_ref/struct.ChildRef.html#method.ask_stream
+    /// [`msg!`]: ../macro.msg.html
+    pub fn take_stream_sender(&mut self) -> Option<AnswerStreamSender> {
+        self.stream.take()
+    }
+
     #[doc(hidden)]
     pub fn take_sender(&mut self) -> Option<AnswerSender> {
         debug!("{:?}: Taking sender.", self);
-        if let MsgInner::Ask { sender, .. } = &mut self.0 {
+        if let MsgInner::Ask { sender, .. } = self.inner.get_mut() {
             sender.take()
         } else {
             None
         }
     }
 
-    #[doc(hidden)]
+    /// Returns whether the message is of type `M`, whether it was
+    /// "told", "asked" or broadcasted, without consuming it.
     pub fn is<M: Message>(&self) -> bool {
-        match &self.0 {
+        match self.inner.get() {
             MsgInner::Tell(msg) => msg.is::<M>(),
+            MsgInner::Inline(msg) => msg.as_any().is::<M>(),
             MsgInner::Ask { msg, .. } => msg.is::<M>(),
             MsgInner::Broadcast(msg) => msg.is::<M>(),
+            MsgInner::Lent(_) => unreachable!(),
         }
     }
 
-    #[doc(hidden)]
+    /// Downcasts the message to `M` if it was "told" or "asked"
+    /// and is of this type, or returns it otherwise (including if
+    /// it was broadcasted, see [`downcast_typed`]).
+    ///
+    /// Note that the sender of an "asked" message's answer is
+    /// dropped with it, unless it was taken beforehand.
+    ///
+    /// [`downcast_typed`]: #method.downcast_typed
     pub fn downcast<M: Message>(self) -> Result<M, Self> {
         trace!("{:?}: Downcasting to {}.", self, type_name::<M>());
-        match self.0 {
+        let Msg {
+            inner,
+            entry,
+            provenance,
+            priority,
+            ack,
+            stream,
+            hops,
+        } = self;
+        let (inner, slot) = inner.unlend();
+        let inner = match inner {
             MsgInner::Tell(msg) => {
                 if msg.is::<M>() {
                     let msg: Box<dyn Any + 'static> = msg;
-                    Ok(*msg.downcast().unwrap())
-                } else {
-                    let inner = MsgInner::Tell(msg);
-                    Err(Msg(inner))
+                    return Ok(*msg.downcast().unwrap());
                 }
+
+                MsgInner::Tell(msg)
             }
+            MsgInner::Inline(msg) => match msg.take() {
+                Ok(msg) => return Ok(msg),
+                Err(msg) => MsgInner::Inline(msg),
+            },
             MsgInner::Ask { msg, sender } => {
                 if msg.is::<M>() {
                     let msg: Box<dyn Any + 'static> = msg;
-                    Ok(*msg.downcast().unwrap())
-                } else {
-                    let inner = MsgInner::Ask { msg, sender };
-                    Err(Msg(inner))
+                    return Ok(*msg.downcast().unwrap());
                 }
+
+                MsgInner::Ask { msg, sender }
             }
-            _ => Err(self),
+            inner => inner,
+        };
+
+        Err(Msg {
+            inner: inner.relend(slot),
+            entry,
+            provenance,
+            priority,
+            ack,
+            stream,
+            hops,
+        })
+    }
+
+    /// Downcasts the message to a [`TypedMsg`] of `M` if it is of
+    /// this type, keeping whether it was broadcasted, "told" or
+    /// "asked" (along with the sender of its answer), or returns
+    /// it otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             let (msg, _) = ctx.recv().await?.extract();
+    ///             match msg.downcast_typed::<u64>() {
+    ///                 Ok(TypedMsg::Ask { msg, sender }) => {
+    ///                     sender.answer(&ctx, msg * 2).unwrap();
+    ///                 }
+    ///                 Ok(_) => (),
+    ///                 Err(msg) => {
+    ///                     // Handle the message otherwise...
+    ///                     # drop(msg);
+    ///                 }
+    ///             }
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`TypedMsg`]: enum.TypedMsg.html
+    pub fn downcast_typed<M: Message>(self) -> Result<TypedMsg<M>, Self> {
+        trace!("{:?}: Downcasting to typed {}.", self, type_name::<M>());
+        if !self.is::<M>() {
+            return Err(self);
         }
+
+        let typed = match self.inner.unlend().0 {
+            MsgInner::Broadcast(msg) => TypedMsg::Broadcast(msg.downcast().unwrap()),
+            MsgInner::Tell(msg) => {
+                let msg: Box<dyn Any + 'static> = msg;
+                TypedMsg::Tell(*msg.downcast().unwrap())
+            }
+            MsgInner::Inline(msg) => TypedMsg::Tell(msg.take().unwrap()),
+            MsgInner::Ask { msg, sender } => {
+                let msg: Box<dyn Any + 'static> = msg;
+                let msg = *msg.downcast().unwrap();
+                match sender {
+                    Some(sender) => TypedMsg::Ask { msg, sender },
+                    None => TypedMsg::Tell(msg),
+                }
+            }
+            MsgInner::Lent(_) => unreachable!(),
+        };
+
+        Ok(typed)
     }
 
-    #[doc(hidden)]
-    pub fn downcast_ref<M: Message>(&self) -> Option<Arc<M>> {
+    /// Returns a reference to the message if it is of type `M`,
+    /// whether it was "told", "asked" or broadcasted, without
+    /// consuming it (e.g. to select the messages to retrieve using
+    /// [`BastionContext::recv_where`]), or `None` otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             let (msg, _) = ctx.recv().await?.extract();
+    ///             if let Some(n) = msg.downcast_ref::<u64>() {
+    ///                 println!("Received {}.", n);
+    ///             }
+    ///
+    ///             // The message can still be downcasted...
+    ///             # drop(msg);
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`BastionContext::recv_where`]: ../context/struct.BastionContext.html#method.recv_where
+    pub fn downcast_ref<M: Message>(&self) -> Option<&M> {
         trace!("{:?}: Downcasting to ref of {}.", self, type_name::<M>());
-        if let MsgInner::Broadcast(msg) = &self.0 {
-            if msg.is::<M>() {
-                return Some(msg.clone().downcast::<M>().unwrap());
-            }
+        match self.inner.get() {
+            MsgInner::Tell(msg) => msg.downcast_ref(),
+            MsgInner::Inline(msg) => msg.as_any().downcast_ref(),
+            MsgInner::Ask { msg, .. } => msg.downcast_ref(),
+            MsgInner::Broadcast(msg) => msg.downcast_ref(),
+            MsgInner::Lent(_) => unreachable!(),
         }
-
-        None
     }
 
     pub(crate) fn try_clone(&self) -> Option<Self> {
         trace!("{:?}: Trying to clone.", self);
-        if let MsgInner::Broadcast(msg) = &self.0 {
-            let inner = MsgInner::Broadcast(msg.clone());
-            Some(Msg(inner))
+        if let MsgInner::Broadcast(msg) = self.inner.get() {
+            Some(Msg {
+                inner: MsgInner::Broadcast(msg.clone()),
+                entry: self.entry.clone(),
+                provenance: self.provenance.clone(),
+                priority: self.priority,
+                ack: self.ack.clone(),
+                stream: None,
+                hops: self.hops,
+            })
         } else {
             None
         }
@@ -368,24 +1288,122 @@ impl Msg {
 
     pub(crate) fn try_unwrap<M: Message>(self) -> Result<M, Self> {
         debug!("{:?}: Trying to unwrap.", self);
-        if let MsgInner::Broadcast(msg) = self.0 {
-            match msg.downcast() {
-                Ok(msg) => match Arc::try_unwrap(msg) {
-                    Ok(msg) => Ok(msg),
-                    Err(msg) => {
-                        let inner = MsgInner::Broadcast(msg);
-                        Err(Msg(inner))
-                    }
-                },
-                Err(msg) => {
-                    let inner = MsgInner::Broadcast(msg);
-                    Err(Msg(inner))
-                }
+        let (inner, slot) = self.inner.unlend();
+        let msg = match inner {
+            MsgInner::Broadcast(msg) => msg,
+            inner => {
+                let inner = inner.relend(slot);
+                let msg = Msg { inner, ..self };
+                return msg.downcast();
             }
-        } else {
-            self.downcast()
+        };
+
+        let msg = match msg.downcast() {
+            Ok(msg) => match Arc::try_unwrap(msg) {
+                Ok(msg) => return Ok(msg),
+                Err(msg) => msg,
+            },
+            Err(msg) => msg,
+        };
+
+        Err(Msg {
+            inner: MsgInner::Broadcast(msg).relend(slot),
+            ..self
+        })
+    }
+
+    // Lends the message to an actor whose handling of it can be
+    // cancelled, returning what is needed to get it back if the
+    // actor drops it without taking it (the sender of its answer
+    // should be taken beforehand, and the sender of its replies is
+    // lent along with it).
+    pub(crate) fn lend(self) -> (Self, Reclaim) {
+        let slot = Arc::new(Mutex::new(None));
+        let reclaim = Reclaim {
+            slot: slot.clone(),
+            entry: self.entry.clone(),
+            provenance: self.provenance.clone(),
+            priority: self.priority,
+            ack: self.ack.clone(),
+            hops: self.hops,
+        };
+
+        let (inner, _) = self.inner.unlend();
+        let lent = Lent {
+            inner: Some(inner),
+            slot,
+        };
+
+        let msg = Msg {
+            inner: MsgInner::Lent(Box::new(lent)),
+            ..self
+        };
+
+        (msg, reclaim)
+    }
+}
+
+impl MsgInner {
+    // Returns the message that was lent, if it was.
+    fn get(&self) -> &MsgInner {
+        match self {
+            MsgInner::Lent(lent) => lent.inner.as_ref().unwrap(),
+            inner => inner,
         }
     }
+
+    fn get_mut(&mut self) -> &mut MsgInner {
+        match self {
+            MsgInner::Lent(lent) => lent.inner.as_mut().unwrap(),
+            inner => inner,
+        }
+    }
+
+    // Takes the message that was lent out of its loan (which then
+    // doesn't move it back anymore), along with the slot it should
+    // be moved back into if it isn't taken after all.
+    fn unlend(self) -> (MsgInner, Option<LentSlot>) {
+        match self {
+            MsgInner::Lent(mut lent) => (lent.inner.take().unwrap(), Some(lent.slot.clone())),
+            inner => (inner, None),
+        }
+    }
+
+    fn relend(self, slot: Option<LentSlot>) -> MsgInner {
+        match slot {
+            Some(slot) => MsgInner::Lent(Box::new(Lent {
+                inner: Some(self),
+                slot,
+            })),
+            None => self,
+        }
+    }
+}
+
+impl Drop for Lent {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.take() {
+            *self.slot.lock().unwrap() = Some(inner);
+        }
+    }
+}
+
+impl Reclaim {
+    // Returns the lent message if it was dropped without being
+    // taken, without the sender of its replies (which was dropped
+    // along with it).
+    pub(crate) fn reclaim(self) -> Option<Msg> {
+        let inner = self.slot.lock().unwrap().take()?;
+        Some(Msg {
+            inner,
+            entry: self.entry,
+            provenance: self.provenance,
+            priority: self.priority,
+            ack: self.ack,
+            stream: None,
+            hops: self.hops,
+        })
+    }
 }
 
 impl BastionMessage {
@@ -397,6 +1415,14 @@ impl BastionMessage {
         BastionMessage::Stop
     }
 
+    pub(crate) fn stop_within(timeout: Duration) -> Self {
+        BastionMessage::StopWithin(timeout)
+    }
+
+    pub(crate) fn quiesce(deadline: Duration) -> Self {
+        BastionMessage::Quiesce(deadline)
+    }
+
     pub(crate) fn kill() -> Self {
         BastionMessage::Kill
     }
@@ -404,19 +1430,40 @@ impl BastionMessage {
     pub(crate) fn deploy_supervisor(supervisor: Supervisor) -> Self {
         let deployment = Deployment::Supervisor(supervisor);
 
-        BastionMessage::Deploy(deployment)
+        BastionMessage::Deploy(Box::new(deployment))
     }
 
     pub(crate) fn deploy_children(children: Children) -> Self {
         let deployment = Deployment::Children(children);
 
-        BastionMessage::Deploy(deployment)
+        BastionMessage::Deploy(Box::new(deployment))
+    }
+
+    pub(crate) fn deploy_child(init: Init, sender: oneshot::Sender<ChildRef>) -> Self {
+        let deployment = Deployment::Child { init, sender };
+
+        BastionMessage::Deploy(Box::new(deployment))
     }
 
     pub(crate) fn prune(id: BastionId) -> Self {
         BastionMessage::Prune { id }
     }
 
+    pub(crate) fn cancel_task(id: BastionId) -> Self {
+        BastionMessage::CancelTask(id)
+    }
+
+    pub(crate) fn scale(redundancy: usize, sender: oneshot::Sender<ChildrenRef>) -> Self {
+        BastionMessage::Scale { redundancy, sender }
+    }
+
+    pub(crate) fn set_routing(
+        routing: Routing,
+        sender: oneshot::Sender<Result<(), RoutingError>>,
+    ) -> Self {
+        BastionMessage::SetRouting { routing, sender }
+    }
+
     pub(crate) fn supervise_with(strategy: SupervisionStrategy) -> Self {
         BastionMessage::SuperviseWith(strategy)
     }
@@ -442,6 +1489,11 @@ impl BastionMessage {
         BastionMessage::Message(msg)
     }
 
+    pub(crate) fn broadcast_acked<M: Message>(msg: M, token: AckToken) -> Self {
+        let msg = Msg::broadcast(msg).with_ack_token(token);
+        BastionMessage::Message(msg)
+    }
+
     pub(crate) fn tell<M: Message>(msg: M) -> Self {
         let msg = Msg::tell(msg);
         BastionMessage::Message(msg)
@@ -452,8 +1504,46 @@ impl BastionMessage {
         (BastionMessage::Message(msg), answer)
     }
 
-    pub(crate) fn restart_required(id: BastionId, parent_id: BastionId) -> Self {
-        BastionMessage::RestartRequired { id, parent_id }
+    pub(crate) fn ask_stream<M: Message>(msg: M) -> (Self, AnswerStream) {
+        let (sender, stream) = answer_stream::channel();
+        let mut msg = Msg::tell(msg);
+        msg.stream = Some(sender);
+        (BastionMessage::Message(msg), stream)
+    }
+
+    pub(crate) fn ask_with_priority<M: Message>(msg: M, priority: Priority) -> (Self, Answer) {
+        let (msg, answer) = Msg::ask(msg);
+        (BastionMessage::Message(msg.with_priority(priority)), answer)
+    }
+
+    pub(crate) fn with_answer_slot(self, slot: AnswerSlot) -> Self {
+        match self {
+            BastionMessage::Message(msg) => BastionMessage::Message(msg.with_answer_slot(slot)),
+            msg => msg,
+        }
+    }
+
+    pub(crate) fn tell_correlated<M: Message>(id: CorrelationId, msg: M) -> Self {
+        let msg = Msg::correlated(id, msg);
+        BastionMessage::Message(msg)
+    }
+
+    pub(crate) fn restart_required(
+        id: BastionId,
+        parent_id: BastionId,
+        reason: FaultReason,
+        error: Option<ExecError>,
+        provenance: Provenance,
+        records: Vec<FlightRecord>,
+    ) -> Self {
+        BastionMessage::RestartRequired {
+            id,
+            parent_id,
+            reason,
+            error,
+            provenance,
+            records,
+        }
     }
 
     pub(crate) fn finished_child(id: BastionId, parent_id: BastionId) -> Self {
@@ -464,6 +1554,14 @@ impl BastionMessage {
         BastionMessage::RestartSubtree
     }
 
+    pub(crate) fn degrade(keep: Vec<String>) -> Self {
+        BastionMessage::Degrade { keep }
+    }
+
+    pub(crate) fn recover() -> Self {
+        BastionMessage::Recover
+    }
+
     pub(crate) fn restore_child(id: BastionId, state: Qutex<Pin<Box<ContextState>>>) -> Self {
         BastionMessage::RestoreChild { id, state }
     }
@@ -477,22 +1575,40 @@ impl BastionMessage {
     }
 
     pub(crate) fn stopped(id: BastionId) -> Self {
-        BastionMessage::Stopped { id }
+        BastionMessage::Stopped { id, killed: false }
+    }
+
+    pub(crate) fn killed(id: BastionId) -> Self {
+        BastionMessage::Stopped { id, killed: true }
     }
 
     pub(crate) fn faulted(id: BastionId) -> Self {
         BastionMessage::Faulted { id }
     }
 
+    pub(crate) fn migrate_child(
+        to: ChildRef,
+        sender: oneshot::Sender<Result<MigrationReport, MigrationError>>,
+    ) -> Self {
+        BastionMessage::MigrateChild { to, sender }
+    }
+
     pub(crate) fn try_clone(&self) -> Option<Self> {
         trace!("{:?}: Trying to clone.", self);
         let clone = match self {
             BastionMessage::Start => BastionMessage::start(),
             BastionMessage::Stop => BastionMessage::stop(),
+            BastionMessage::StopWithin(timeout) => BastionMessage::stop_within(*timeout),
+            BastionMessage::Quiesce(deadline) => BastionMessage::quiesce(*deadline),
             BastionMessage::Kill => BastionMessage::kill(),
-            // FIXME
-            BastionMessage::Deploy(_) => unimplemented!(),
+            // The deployed supervisor or children group can only be
+            // deployed once.
+            BastionMessage::Deploy(_) => return None,
             BastionMessage::Prune { id } => BastionMessage::prune(id.clone()),
+            BastionMessage::CancelTask(id) => BastionMessage::cancel_task(id.clone()),
+            // The group's answer can only be sent once.
+            BastionMessage::Scale { .. } => return None,
+            BastionMessage::SetRouting { .. } => return None,
             BastionMessage::SuperviseWith(strategy) => {
                 BastionMessage::supervise_with(strategy.clone())
             }
@@ -509,20 +1625,39 @@ impl BastionMessage {
                 state.clone(),
             ),
             BastionMessage::Message(msg) => BastionMessage::Message(msg.try_clone()?),
-            BastionMessage::RestartRequired { id, parent_id } => {
-                BastionMessage::restart_required(id.clone(), parent_id.clone())
-            }
+            BastionMessage::RestartRequired {
+                id,
+                parent_id,
+                reason,
+                error,
+                provenance,
+                records,
+            } => BastionMessage::restart_required(
+                id.clone(),
+                parent_id.clone(),
+                *reason,
+                error.clone(),
+                provenance.clone(),
+                records.clone(),
+            ),
             BastionMessage::FinishedChild { id, parent_id } => {
                 BastionMessage::finished_child(id.clone(), parent_id.clone())
             }
             BastionMessage::RestartSubtree => BastionMessage::restart_subtree(),
+            BastionMessage::Degrade { keep } => BastionMessage::degrade(keep.clone()),
+            BastionMessage::Recover => BastionMessage::recover(),
             BastionMessage::RestoreChild { id, state } => {
                 BastionMessage::restore_child(id.clone(), state.clone())
             }
             BastionMessage::DropChild { id } => BastionMessage::drop_child(id.clone()),
             BastionMessage::SetState { state } => BastionMessage::set_state(state.clone()),
-            BastionMessage::Stopped { id } => BastionMessage::stopped(id.clone()),
+            BastionMessage::Stopped { id, killed } => BastionMessage::Stopped {
+                id: id.clone(),
+                killed: *killed,
+            },
             BastionMessage::Faulted { id } => BastionMessage::faulted(id.clone()),
+            // The migration's answer can only be sent once.
+            BastionMessage::MigrateChild { .. } => return None,
         };
 
         Some(clone)
@@ -537,12 +1672,248 @@ impl BastionMessage {
     }
 }
 
+impl Answer {
+    // The answer times out after the default timeout of the
+    // system's runtime settings when it was created, if any.
+    fn new(recver: Receiver<SignedMessage>) -> Self {
+        let timeout = if SYSTEM.is_initialized() {
+            SYSTEM.default_ask_timeout()
+        } else {
+            None
+        };
+
+        Answer(recver, timeout.map(Delay::new))
+    }
+
+    /// Makes this answer resolve to [`AnswerError::TimedOut`] if
+    /// the message wasn't answered before the timeout expires.
+    ///
+    /// Once the timeout expired, the message's recipient can't
+    /// answer it anymore, the sender of its answer failing with
+    /// a [`SendError`] carrying the answer (whether it was slow,
+    /// stopped or ignored the message).
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long to wait for the answer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // The messages are kept without being answered...
+    ///             let mut held = Vec::new();
+    ///             loop {
+    ///                 held.push(ctx.recv().await?);
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     # Bastion::start();
+    ///
+    /// let child_ref = &children_ref.elems()[0];
+    /// let answer = child_ref.ask_anonymously("hello").expect("Couldn't send the message.");
+    /// // ...so the answer times out.
+    /// let answered = run!(answer.timeout(Duration::from_millis(100)));
+    /// assert_eq!(answered.unwrap_err(), AnswerError::TimedOut);
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// This timeout replaces the default one set using
+    /// [`RuntimeConfig::with_default_ask_timeout`], if any.
+    ///
+    /// [`AnswerError::TimedOut`]: ../errors/enum.AnswerError.html#variant.TimedOut
+    /// [`SendError`]: ../errors/struct.SendError.html
+    /// [`RuntimeConfig::with_default_ask_timeout`]: ../struct.RuntimeConfig.html#method.with_default_ask_timeout
+    pub fn timeout(mut self, timeout: Duration) -> AnswerTimeout {
+        self.1 = None;
+        AnswerTimeout {
+            answer: self,
+            delay: Delay::new(timeout),
+        }
+    }
+
+    /// Waits for the answer and downcasts it to `T`, instead of
+    /// matching it using [`msg!`].
+    ///
+    /// This method resolves to the answer if it was of type `T`,
+    /// or to an [`AnswerError`] otherwise (the answer being
+    /// dropped if it was of another type).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             loop {
+    ///                 msg! { ctx.recv().await?,
+    ///                     n: u64 =!> {
+    ///                         answer!(ctx, n * 2).ok();
+    ///                     };
+    ///                     _: _ => ();
+    ///                 }
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     # Bastion::start();
+    ///
+    /// let child_ref = &children_ref.elems()[0];
+    /// let answer = child_ref.ask_anonymously(21u64).expect("Couldn't send the message.");
+    /// assert_eq!(run!(answer.await_typed::<u64>()), Ok(42));
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`msg!`]: ../macro.msg.html
+    /// [`AnswerError`]: ../errors/enum.AnswerError.html
+    pub async fn await_typed<T: Message>(self) -> Result<T, AnswerError> {
+        let (msg, _) = self.await?.extract();
+        let got = msg.type_name();
+        msg.downcast().map_err(|_| AnswerError::UnexpectedType {
+            expected: type_name::<T>(),
+            got,
+        })
+    }
+
+    /// Blocks the current thread until the message is answered or
+    /// until `timeout` expires, for synchronous code that isn't run
+    /// by the executor (e.g. the main thread).
+    ///
+    /// This method returns the answer if it was received in time,
+    /// or an [`AnswerError`] otherwise (including right away with
+    /// [`AnswerError::WouldBlockExecutor`] if it is called from
+    /// one of the executor's worker threads, e.g. from an
+    /// element's future, which could prevent the message from
+    /// being answered).
+    ///
+    /// Like with [`Answer::timeout`], this timeout replaces the
+    /// default one, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long to wait for the answer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             loop {
+    ///                 msg! { ctx.recv().await?,
+    ///                     msg: &'static str =!> {
+    ///                         answer!(ctx, msg).ok();
+    ///                     };
+    ///                     _: _ => ();
+    ///                 }
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     # Bastion::start();
+    ///
+    /// let child_ref = &children_ref.elems()[0];
+    /// let answer = child_ref.ask_anonymously("hello").expect("Couldn't send the message.");
+    /// let (msg, _) = answer
+    ///     .wait(Duration::from_secs(1))
+    ///     .expect("Couldn't receive the answer in time.")
+    ///     .extract();
+    /// assert_eq!(msg.downcast::<&'static str>().unwrap(), "hello");
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`AnswerError`]: ../errors/enum.AnswerError.html
+    /// [`AnswerError::WouldBlockExecutor`]: ../errors/enum.AnswerError.html#variant.WouldBlockExecutor
+    /// [`Answer::timeout`]: #method.timeout
+    pub fn wait(mut self, timeout: Duration) -> Result<SignedMessage, AnswerError> {
+        if worker::is_worker() {
+            warn!("{:?}: Refusing to block an executor's worker thread.", self);
+            return Err(AnswerError::WouldBlockExecutor);
+        }
+
+        // The default timeout is replaced by this one.
+        self.1 = None;
+        let deadline = Instant::now() + timeout;
+        match blocking_bridge::block_on(&mut self, deadline) {
+            Some(answered) => answered,
+            None => {
+                debug!("{:?}: Timed out.", self);
+                self.0.close();
+                Err(AnswerError::TimedOut)
+            }
+        }
+    }
+}
+
+impl Future for AnswerTimeout {
+    type Output = Result<SignedMessage, AnswerError>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        if let Poll::Ready(answered) = Pin::new(&mut this.answer).poll(ctx) {
+            return Poll::Ready(answered);
+        }
+
+        match Pin::new(&mut this.delay).poll(ctx) {
+            Poll::Ready(()) => {
+                debug!("{:?}: Timed out.", this.answer);
+                // The answer's sender fails once the receiver is
+                // closed, instead of sending it to nobody.
+                this.answer.0.close();
+                Poll::Ready(Err(AnswerError::TimedOut))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
 impl Future for Answer {
-    type Output = Result<SignedMessage, ()>;
+    type Output = Result<SignedMessage, AnswerError>;
 
     fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
         debug!("{:?}: Polling.", self);
-        Pin::new(&mut self.get_mut().0).poll(ctx).map_err(|_| ())
+        let this = self.get_mut();
+        if let Poll::Ready(answered) = Pin::new(&mut this.0).poll(ctx) {
+            return Poll::Ready(answered.map_err(|_| AnswerError::Unanswered));
+        }
+
+        match &mut this.1 {
+            Some(delay) => match Pin::new(delay).poll(ctx) {
+                Poll::Ready(()) => {
+                    debug!("{:?}: Timed out.", this);
+                    this.0.close();
+                    Poll::Ready(Err(AnswerError::TimedOut))
+                }
+                Poll::Pending => Poll::Pending,
+            },
+            None => Poll::Pending,
+        }
     }
 }
 
@@ -568,9 +1939,16 @@ impl Future for Answer {
 /// it to the `answer!` macro that will be generated for this
 /// use.
 ///
+/// In every case, the message's signature can be retrieved using
+/// the `signature!` macro, and a [`ChildRef`] referencing the
+/// element that sent it (or `None` if it wasn't sent by an element,
+/// see [`RefAddr::origin`]) using the `sender!` macro.
+///
 /// A default case is required, which is defined in the same
 /// way as any other case but with its type set as `_` (note
-/// that it doesn't has the optional `ref` or `=!>`).
+/// that it doesn't has the optional `ref` or `=!>`). Its variable
+/// is the [`Msg`] that wasn't matched, whose type can be inspected
+/// using [`Msg::type_name`], [`Msg::is`] or [`Msg::downcast_ref`].
 ///
 /// # Example
 ///
@@ -604,6 +1982,10 @@ impl Future for Answer {
 ///                     msg: &'static str => {
 ///                         assert_eq!(msg, TELL_MSG);
 ///                         // Handle the message...
+///
+///                         // ...and eventually reply to the element that told it...
+///                         let sender: Option<ChildRef> = sender!();
+///                         # assert!(sender.is_some());
 ///                     };
 ///                     // We match `&'static str`'s "asked" to this child...
 ///                     msg: &'static str =!> {
@@ -616,7 +1998,7 @@ impl Future for Answer {
 ///                     // We are only broadcasting, "telling" and "asking" a
 ///                     // `&'static str` in this example, so we know that this won't
 ///                     // happen...
-///                     _: _ => ();
+///                     unknown: _ => panic!("Unexpected `{}`.", unknown.type_name());
 ///                 }
 ///             }
 ///         }
@@ -633,6 +2015,11 @@ impl Future for Answer {
 /// [`Msg`]: children/struct.Msg.html
 /// [`BastionContext::recv`]: context/struct.BastionContext.html#method.recv
 /// [`BastionContext::try_recv`]: context/struct.BastionContext.html#method.try_recv
+/// [`ChildRef`]: child_ref/struct.ChildRef.html
+/// [`RefAddr::origin`]: envelope/struct.RefAddr.html#method.origin
+/// [`Msg::type_name`]: message/struct.Msg.html#method.type_name
+/// [`Msg::is`]: message/struct.Msg.html#method.is
+/// [`Msg::downcast_ref`]: message/struct.Msg.html#method.downcast_ref
 macro_rules! msg {
     ($msg:expr, $($tokens:tt)+) => {
         msg!(@internal $msg, (), (), (), $($tokens)+)
@@ -718,6 +2105,12 @@ macro_rules! msg {
             };
         }
 
+        macro_rules! sender {
+            () => {
+                sign.origin().cloned()
+            };
+        }
+
         let sender = $var.take_sender();
         if $var.is_broadcast() {
             if false {
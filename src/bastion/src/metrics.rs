@@ -0,0 +1,855 @@
+//!
+//! Application-level metrics registered by the elements of
+//! children groups (using [`BastionContext::metrics`]) and
+//! aggregated per group (using [`ChildrenRef::stats`]), along
+//! with the run-time statistics of the elements (using
+//! [`ChildRef::stats`]).
+//!
+//! [`BastionContext::metrics`]: ../context/struct.BastionContext.html#method.metrics
+//! [`ChildrenRef::stats`]: ../children_ref/struct.ChildrenRef.html#method.stats
+//! [`ChildRef::stats`]: ../child_ref/struct.ChildRef.html#method.stats
+use crate::context::BastionId;
+use fxhash::FxHashMap;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter, Write};
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+// The upper bounds of the buckets of the histograms.
+const HISTOGRAM_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+#[derive(Debug, Clone)]
+/// The metrics of a children group's element, used to register
+/// its counters, gauges and histograms.
+///
+/// Registering a metric returns a handle that can be kept and
+/// updated without locking. The metrics registered by an element
+/// outlive its restarts.
+///
+/// Registering a metric fails with a [`MetricError`] if its name
+/// isn't a valid Prometheus metric name (matching
+/// `[a-zA-Z_:][a-zA-Z0-9_:]*`) or if a metric of another kind was
+/// already registered with the same name in the children group.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// Bastion::children(|children| {
+///     children.with_exec(|ctx: BastionContext| {
+///         async move {
+///             let jobs_ok = ctx.metrics().counter("jobs_ok")?;
+///             let queued = ctx.metrics().gauge("queued")?;
+///             let duration = ctx.metrics().histogram("job_duration_seconds")?;
+///
+///             queued.inc();
+///             // Do the job...
+///             jobs_ok.inc();
+///             duration.observe(0.042);
+///             queued.dec();
+///
+///             Ok(())
+///         }
+///     })
+/// }).expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`MetricError`]: enum.MetricError.html
+pub struct Metrics {
+    id: BastionId,
+    group: GroupMetrics,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// The reasons why a metric couldn't be registered (see
+/// [`Metrics`]).
+///
+/// [`Metrics`]: struct.Metrics.html
+pub enum MetricError {
+    /// The name isn't a valid Prometheus metric name.
+    InvalidName(String),
+    /// A metric of another kind (`"counter"`, `"gauge"` or
+    /// `"histogram"`) was already registered with this name in
+    /// the children group.
+    Registered {
+        /// The name of the metric.
+        name: String,
+        /// The kind of metric registered with this name.
+        kind: &'static str,
+    },
+}
+
+#[derive(Debug, Clone)]
+/// A monotonically increasing counter.
+pub struct Counter(Arc<AtomicU64>);
+
+#[derive(Debug, Clone)]
+/// A value that can go up and down.
+pub struct Gauge(Arc<AtomicI64>);
+
+#[derive(Debug, Clone)]
+/// A distribution of observed values, counted in buckets whose
+/// upper bounds are the default ones of Prometheus' clients
+/// (from `0.005` to `10.0`).
+pub struct Histogram(Arc<HistogramCell>);
+
+#[derive(Debug)]
+struct HistogramCell {
+    buckets: Vec<AtomicU64>,
+    // The bits of a `f64`.
+    sum: AtomicU64,
+    count: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum MetricKind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+#[derive(Debug, Clone)]
+enum Metric {
+    Counter(Counter),
+    Gauge(Gauge),
+    Histogram(Histogram),
+}
+
+#[derive(Debug, Clone, Default)]
+// The metrics of all the elements of a children group, shared
+// with its `ChildrenRef`s.
+pub(crate) struct GroupMetrics(Arc<Mutex<GroupMetricsInner>>);
+
+#[derive(Debug, Default)]
+struct GroupMetricsInner {
+    // The interned names of the metrics registered in the group,
+    // which were validated when first registered.
+    names: FxHashMap<Arc<str>, MetricKind>,
+    elems: FxHashMap<BastionId, ElemMetrics>,
+}
+
+#[derive(Debug, Default)]
+struct ElemMetrics {
+    metrics: FxHashMap<Arc<str>, Metric>,
+    // Whether the element terminated, in which case its metrics
+    // are only kept until they are retrieved once.
+    terminal: bool,
+}
+
+#[derive(Debug, Default)]
+// The run-time counters of an element, updated by the element as
+// it receives and handles messages and shared with its `ChildRef`s
+// (they outlive its restarts).
+pub(crate) struct ChildCounters {
+    mailbox_len: AtomicUsize,
+    processed: AtomicU64,
+    restarts: AtomicUsize,
+    rejected: AtomicU64,
+    // Whether the element's future is waiting for a message.
+    waiting: AtomicBool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A snapshot of the metrics registered by the elements of a
+/// children group and of their run-time statistics, as returned
+/// by [`ChildrenRef::stats`].
+///
+/// [`ChildrenRef::stats`]: ../children_ref/struct.ChildrenRef.html#method.stats
+pub struct GroupStats {
+    group: BastionId,
+    elems: Vec<ElemStats>,
+    children: Vec<ChildStats>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// A snapshot of the run-time statistics of an element of a
+/// children group, as returned by [`ChildRef::stats`].
+///
+/// [`ChildRef::stats`]: ../child_ref/struct.ChildRef.html#method.stats
+pub struct ChildStats {
+    id: BastionId,
+    mailbox_len: usize,
+    processed: u64,
+    pending_answers: usize,
+    restarts: usize,
+    rejected: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A snapshot of the metrics registered by an element of a
+/// children group.
+pub struct ElemStats {
+    id: BastionId,
+    terminal: bool,
+    metrics: Vec<(String, MetricValue)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The value of a metric.
+pub enum MetricValue {
+    /// The value of a [`Counter`](struct.Counter.html).
+    Counter(u64),
+    /// The value of a [`Gauge`](struct.Gauge.html).
+    Gauge(i64),
+    /// The value of a [`Histogram`](struct.Histogram.html).
+    Histogram(HistogramValue),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The value of a [`Histogram`](struct.Histogram.html).
+pub struct HistogramValue {
+    buckets: Vec<(f64, u64)>,
+    sum: f64,
+    count: u64,
+}
+
+impl Metrics {
+    pub(crate) fn new(id: BastionId, group: GroupMetrics) -> Self {
+        Metrics { id, group }
+    }
+
+    /// Returns the counter named `name` of this element,
+    /// registering it if needed.
+    ///
+    /// This method returns the counter if it succeeded, or a
+    /// [`MetricError`] otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the counter.
+    ///
+    /// [`MetricError`]: enum.MetricError.html
+    pub fn counter(&self, name: &str) -> Result<Counter, MetricError> {
+        match self.group.register(&self.id, name, MetricKind::Counter)? {
+            Metric::Counter(counter) => Ok(counter),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns the gauge named `name` of this element,
+    /// registering it if needed.
+    ///
+    /// This method returns the gauge if it succeeded, or a
+    /// [`MetricError`] otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the gauge.
+    ///
+    /// [`MetricError`]: enum.MetricError.html
+    pub fn gauge(&self, name: &str) -> Result<Gauge, MetricError> {
+        match self.group.register(&self.id, name, MetricKind::Gauge)? {
+            Metric::Gauge(gauge) => Ok(gauge),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns the histogram named `name` of this element,
+    /// registering it if needed.
+    ///
+    /// This method returns the histogram if it succeeded, or a
+    /// [`MetricError`] otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the histogram.
+    ///
+    /// [`MetricError`]: enum.MetricError.html
+    pub fn histogram(&self, name: &str) -> Result<Histogram, MetricError> {
+        match self.group.register(&self.id, name, MetricKind::Histogram)? {
+            Metric::Histogram(histogram) => Ok(histogram),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Counter {
+    fn new() -> Self {
+        Counter(Arc::new(AtomicU64::new(0)))
+    }
+
+    /// Increments the counter by one.
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    /// Increments the counter by `n`.
+    pub fn add(&self, n: u64) {
+        self.0.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Returns the counter's value.
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl Gauge {
+    fn new() -> Self {
+        Gauge(Arc::new(AtomicI64::new(0)))
+    }
+
+    /// Sets the gauge's value.
+    pub fn set(&self, value: i64) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+
+    /// Increments the gauge by one.
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    /// Decrements the gauge by one.
+    pub fn dec(&self) {
+        self.add(-1);
+    }
+
+    /// Adds `n` (which can be negative) to the gauge.
+    pub fn add(&self, n: i64) {
+        self.0.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Returns the gauge's value.
+    pub fn get(&self) -> i64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl Histogram {
+    fn new() -> Self {
+        let cell = HistogramCell {
+            buckets: HISTOGRAM_BUCKETS
+                .iter()
+                .map(|_| AtomicU64::new(0))
+                .collect(),
+            sum: AtomicU64::new(0f64.to_bits()),
+            count: AtomicU64::new(0),
+        };
+
+        Histogram(Arc::new(cell))
+    }
+
+    /// Records an observed value.
+    pub fn observe(&self, value: f64) {
+        let cell = &self.0;
+        for (bound, bucket) in HISTOGRAM_BUCKETS.iter().zip(cell.buckets.iter()) {
+            if value <= *bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let mut sum = cell.sum.load(Ordering::Relaxed);
+        loop {
+            let new = (f64::from_bits(sum) + value).to_bits();
+            match cell
+                .sum
+                .compare_exchange_weak(sum, new, Ordering::Relaxed, Ordering::Relaxed)
+            {
+                Ok(_) => break,
+                Err(current) => sum = current,
+            }
+        }
+
+        cell.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the histogram's value.
+    pub fn get(&self) -> HistogramValue {
+        let cell = &self.0;
+        let buckets = HISTOGRAM_BUCKETS
+            .iter()
+            .zip(cell.buckets.iter())
+            .map(|(bound, bucket)| (*bound, bucket.load(Ordering::Relaxed)))
+            .collect();
+
+        HistogramValue {
+            buckets,
+            sum: f64::from_bits(cell.sum.load(Ordering::Relaxed)),
+            count: cell.count.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl MetricKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            MetricKind::Counter => "counter",
+            MetricKind::Gauge => "gauge",
+            MetricKind::Histogram => "histogram",
+        }
+    }
+}
+
+impl Metric {
+    fn new(kind: MetricKind) -> Self {
+        match kind {
+            MetricKind::Counter => Metric::Counter(Counter::new()),
+            MetricKind::Gauge => Metric::Gauge(Gauge::new()),
+            MetricKind::Histogram => Metric::Histogram(Histogram::new()),
+        }
+    }
+
+    fn reset(&self) {
+        match self {
+            Metric::Counter(counter) => counter.0.store(0, Ordering::Relaxed),
+            Metric::Gauge(_) => (),
+            Metric::Histogram(histogram) => {
+                let cell = &histogram.0;
+                for bucket in cell.buckets.iter() {
+                    bucket.store(0, Ordering::Relaxed);
+                }
+
+                cell.sum.store(0f64.to_bits(), Ordering::Relaxed);
+                cell.count.store(0, Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn value(&self) -> MetricValue {
+        match self {
+            Metric::Counter(counter) => MetricValue::Counter(counter.get()),
+            Metric::Gauge(gauge) => MetricValue::Gauge(gauge.get()),
+            Metric::Histogram(histogram) => MetricValue::Histogram(histogram.get()),
+        }
+    }
+}
+
+impl ChildCounters {
+    pub(crate) fn set_mailbox_len(&self, len: usize) {
+        self.mailbox_len.store(len, Ordering::Relaxed);
+    }
+
+    pub(crate) fn mailbox_len(&self) -> usize {
+        self.mailbox_len.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn processed(&self) {
+        self.processed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn set_waiting(&self, waiting: bool) {
+        self.waiting.store(waiting, Ordering::Relaxed);
+    }
+
+    // Returns whether the element is waiting for a message while
+    // its mailbox is empty.
+    #[cfg(feature = "autoscaling")]
+    pub(crate) fn is_idle(&self) -> bool {
+        self.waiting.load(Ordering::Relaxed) && self.mailbox_len() == 0
+    }
+
+    pub(crate) fn restarted(&self) {
+        self.restarts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn restarts(&self) -> usize {
+        self.restarts.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn rejected(&self) {
+        self.rejected.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn stats(&self, id: &BastionId, pending_answers: usize) -> ChildStats {
+        ChildStats {
+            id: id.clone(),
+            mailbox_len: self.mailbox_len(),
+            processed: self.processed.load(Ordering::Relaxed),
+            pending_answers,
+            restarts: self.restarts(),
+            rejected: self.rejected.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl GroupMetrics {
+    pub(crate) fn new() -> Self {
+        GroupMetrics::default()
+    }
+
+    fn register(
+        &self,
+        id: &BastionId,
+        name: &str,
+        kind: MetricKind,
+    ) -> Result<Metric, MetricError> {
+        // FIXME: panics?
+        let mut inner = self.0.lock().unwrap();
+
+        let name = match inner.names.get_key_value(name) {
+            Some((name, registered)) => {
+                if *registered != kind {
+                    return Err(MetricError::Registered {
+                        name: name.to_string(),
+                        kind: registered.as_str(),
+                    });
+                }
+
+                name.clone()
+            }
+            None => {
+                if !is_valid_name(name) {
+                    return Err(MetricError::InvalidName(name.to_string()));
+                }
+
+                let name: Arc<str> = Arc::from(name);
+                inner.names.insert(name.clone(), kind);
+                name
+            }
+        };
+
+        let elem = inner.elems.entry(id.clone()).or_default();
+        let metric = elem
+            .metrics
+            .entry(name)
+            .or_insert_with(|| Metric::new(kind))
+            .clone();
+
+        Ok(metric)
+    }
+
+    // Marks the metrics of the element identified by `id` as
+    // terminal, so that they are dropped once reset.
+    pub(crate) fn terminate(&self, id: &BastionId) {
+        if let Some(elem) = self.0.lock().unwrap().elems.get_mut(id) {
+            elem.terminal = true;
+        }
+    }
+
+    pub(crate) fn stats(&self, group: &BastionId, children: Vec<ChildStats>) -> GroupStats {
+        // FIXME: panics?
+        let inner = self.0.lock().unwrap();
+
+        let mut elems = inner
+            .elems
+            .iter()
+            .map(|(id, elem)| {
+                let mut metrics = elem
+                    .metrics
+                    .iter()
+                    .map(|(name, metric)| (name.to_string(), metric.value()))
+                    .collect::<Vec<_>>();
+                metrics.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+                ElemStats {
+                    id: id.clone(),
+                    terminal: elem.terminal,
+                    metrics,
+                }
+            })
+            .collect::<Vec<_>>();
+        elems.sort_by_key(|elem| elem.id.to_string());
+
+        GroupStats {
+            group: group.clone(),
+            elems,
+            children,
+        }
+    }
+
+    // Drops the metrics of the elements that terminated and resets
+    // the counters and histograms of the others.
+    pub(crate) fn reset(&self) {
+        // FIXME: panics?
+        let mut inner = self.0.lock().unwrap();
+        inner.elems.retain(|_, elem| !elem.terminal);
+        for elem in inner.elems.values() {
+            for metric in elem.metrics.values() {
+                metric.reset();
+            }
+        }
+    }
+}
+
+impl GroupStats {
+    /// Returns the identifier of the children group.
+    pub fn group(&self) -> &BastionId {
+        &self.group
+    }
+
+    /// Returns the metrics of each of the group's elements
+    /// that registered some.
+    pub fn elems(&self) -> &[ElemStats] {
+        &self.elems
+    }
+
+    /// Returns the run-time statistics of each of the group's
+    /// elements.
+    pub fn children(&self) -> &[ChildStats] {
+        &self.children
+    }
+
+    /// Returns the number of messages sent to all the group's
+    /// elements that they didn't retrieve yet.
+    pub fn mailbox_len(&self) -> usize {
+        self.children.iter().map(ChildStats::mailbox_len).sum()
+    }
+
+    /// Returns the number of messages all the group's elements
+    /// retrieved.
+    pub fn processed(&self) -> u64 {
+        self.children.iter().map(ChildStats::processed).sum()
+    }
+
+    /// Returns the number of questions asked to all the group's
+    /// elements that are waiting for their answer.
+    pub fn pending_answers(&self) -> usize {
+        self.children.iter().map(ChildStats::pending_answers).sum()
+    }
+
+    /// Returns how many times the group's elements were
+    /// restarted.
+    pub fn restarts(&self) -> usize {
+        self.children.iter().map(ChildStats::restarts).sum()
+    }
+
+    /// Returns the number of messages all the group's elements
+    /// rejected (see [`BastionContext::reject_message`]).
+    ///
+    /// [`BastionContext::reject_message`]: ../context/struct.BastionContext.html#method.reject_message
+    pub fn rejected(&self) -> u64 {
+        self.children.iter().map(ChildStats::rejected).sum()
+    }
+
+    /// Returns the sum of the values of the counters named
+    /// `name` of all the group's elements.
+    pub fn counter(&self, name: &str) -> u64 {
+        self.values(name)
+            .filter_map(|value| match value {
+                MetricValue::Counter(value) => Some(*value),
+                _ => None,
+            })
+            .sum()
+    }
+
+    /// Returns the sum of the values of the gauges named `name`
+    /// of all the group's elements.
+    pub fn gauge(&self, name: &str) -> i64 {
+        self.values(name)
+            .filter_map(|value| match value {
+                MetricValue::Gauge(value) => Some(*value),
+                _ => None,
+            })
+            .sum()
+    }
+
+    /// Returns the merged values of the histograms named `name`
+    /// of all the group's elements, if any of them registered
+    /// one.
+    pub fn histogram(&self, name: &str) -> Option<HistogramValue> {
+        self.values(name)
+            .filter_map(|value| match value {
+                MetricValue::Histogram(value) => Some(value),
+                _ => None,
+            })
+            .fold(None, |merged: Option<HistogramValue>, value| match merged {
+                Some(merged) => Some(merged.merge(value)),
+                None => Some(value.clone()),
+            })
+    }
+
+    /// Renders the metrics of the group's elements using
+    /// Prometheus' text exposition format, labelled with the
+    /// identifiers of the group (`group`) and of the elements
+    /// (`element`).
+    pub fn to_prometheus(&self) -> String {
+        let mut names = self
+            .elems
+            .iter()
+            .flat_map(|elem| elem.metrics.iter())
+            .map(|(name, value)| (name.as_str(), value))
+            .collect::<Vec<_>>();
+        names.sort_by_key(|(name, _)| *name);
+        names.dedup_by(|(a, _), (b, _)| a == b);
+
+        let mut out = String::new();
+        for (name, kind) in names {
+            let kind = match kind {
+                MetricValue::Counter(_) => "counter",
+                MetricValue::Gauge(_) => "gauge",
+                MetricValue::Histogram(_) => "histogram",
+            };
+            writeln!(out, "# TYPE {} {}", name, kind).unwrap();
+
+            for elem in self.elems.iter() {
+                let labels = format!("group=\"{}\",element=\"{}\"", self.group, elem.id);
+                let value = match elem.metric(name) {
+                    Some(value) => value,
+                    None => continue,
+                };
+
+                match value {
+                    MetricValue::Counter(value) => {
+                        writeln!(out, "{}{{{}}} {}", name, labels, value).unwrap()
+                    }
+                    MetricValue::Gauge(value) => {
+                        writeln!(out, "{}{{{}}} {}", name, labels, value).unwrap()
+                    }
+                    MetricValue::Histogram(value) => {
+                        for (bound, count) in value.buckets.iter() {
+                            writeln!(
+                                out,
+                                "{}_bucket{{{},le=\"{}\"}} {}",
+                                name, labels, bound, count
+                            )
+                            .unwrap();
+                        }
+                        writeln!(
+                            out,
+                            "{}_bucket{{{},le=\"+Inf\"}} {}",
+                            name, labels, value.count
+                        )
+                        .unwrap();
+                        writeln!(out, "{}_sum{{{}}} {}", name, labels, value.sum).unwrap();
+                        writeln!(out, "{}_count{{{}}} {}", name, labels, value.count).unwrap();
+                    }
+                }
+            }
+        }
+
+        out
+    }
+
+    fn values<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a MetricValue> + 'a {
+        self.elems.iter().filter_map(move |elem| elem.metric(name))
+    }
+}
+
+impl ElemStats {
+    /// Returns the identifier of the element.
+    pub fn id(&self) -> &BastionId {
+        &self.id
+    }
+
+    /// Returns whether the element terminated since the group's
+    /// metrics were last reset (in which case its metrics will be
+    /// dropped by the next reset).
+    pub fn is_terminal(&self) -> bool {
+        self.terminal
+    }
+
+    /// Returns the names and values of the element's metrics,
+    /// sorted by name.
+    pub fn metrics(&self) -> &[(String, MetricValue)] {
+        &self.metrics
+    }
+
+    /// Returns the value of the element's metric named `name`,
+    /// if it registered one.
+    pub fn metric(&self, name: &str) -> Option<&MetricValue> {
+        self.metrics
+            .iter()
+            .find(|(metric, _)| metric == name)
+            .map(|(_, value)| value)
+    }
+}
+
+impl ChildStats {
+    /// Returns the identifier of the element.
+    pub fn id(&self) -> &BastionId {
+        &self.id
+    }
+
+    /// Returns the number of messages sent to the element that
+    /// it didn't retrieve yet (using [`BastionContext::recv`] or
+    /// the like).
+    ///
+    /// [`BastionContext::recv`]: ../context/struct.BastionContext.html#method.recv
+    pub fn mailbox_len(&self) -> usize {
+        self.mailbox_len
+    }
+
+    /// Returns the number of messages the element retrieved.
+    pub fn processed(&self) -> u64 {
+        self.processed
+    }
+
+    /// Returns the number of questions asked to the element that
+    /// are waiting for its answer (see
+    /// [`Children::with_max_pending_answers`]).
+    ///
+    /// [`Children::with_max_pending_answers`]: ../children/struct.Children.html#method.with_max_pending_answers
+    pub fn pending_answers(&self) -> usize {
+        self.pending_answers
+    }
+
+    /// Returns how many times the element was restarted after it
+    /// faulted.
+    pub fn restarts(&self) -> usize {
+        self.restarts
+    }
+
+    /// Returns the number of messages the element rejected (see
+    /// [`BastionContext::reject_message`]), counting each of the
+    /// times it rejected a message that was rerouted to it again.
+    ///
+    /// [`BastionContext::reject_message`]: ../context/struct.BastionContext.html#method.reject_message
+    pub fn rejected(&self) -> u64 {
+        self.rejected
+    }
+}
+
+impl HistogramValue {
+    /// Returns the upper bounds of the histogram's buckets along
+    /// with the number of observed values lower or equal to them.
+    pub fn buckets(&self) -> &[(f64, u64)] {
+        &self.buckets
+    }
+
+    /// Returns the sum of the observed values.
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    /// Returns the number of observed values.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    fn merge(mut self, other: &HistogramValue) -> Self {
+        for ((_, count), (_, other)) in self.buckets.iter_mut().zip(other.buckets.iter()) {
+            *count += other;
+        }
+        self.sum += other.sum;
+        self.count += other.count;
+
+        self
+    }
+}
+impl Display for MetricError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            MetricError::InvalidName(name) => {
+                write!(fmt, "\"{}\" isn't a valid metric name", name)
+            }
+            MetricError::Registered { name, kind } => write!(
+                fmt,
+                "the metric \"{}\" was already registered as a {}",
+                name, kind
+            ),
+        }
+    }
+}
+
+impl Error for MetricError {}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() || first == '_' || first == ':' => (),
+        _ => return false,
+    }
+
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+}
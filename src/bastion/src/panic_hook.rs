@@ -0,0 +1,131 @@
+//!
+//! The panic hook installed while the system runs, chained with
+//! the hook that was installed before it was initialized (see
+//! [`Config::panic_hook_order`]).
+//!
+//! [`Config::panic_hook_order`]: ../struct.Config.html#method.panic_hook_order
+use crate::config::PanicHookOrder;
+use bastion_executor::worker;
+use lazy_static::lazy_static;
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::cell::RefCell;
+use std::panic::{self, PanicHookInfo};
+use std::sync::{Arc, Mutex};
+
+type Hook = Box<dyn Fn(&PanicHookInfo) + Send + Sync + 'static>;
+
+lazy_static! {
+    static ref INSTALLED: Mutex<Option<Installed>> = Mutex::new(None);
+}
+
+thread_local! {
+    // The location and backtrace of the last panic of an element
+    // run by this thread, until the element reports it.
+    static CAPTURED: RefCell<(Option<String>, Option<String>)> = const { RefCell::new((None, None)) };
+}
+
+// The hook installed by `install` and the one it replaced.
+struct Installed {
+    // The address of the installed hook, to tell whether it was
+    // replaced since then.
+    addr: usize,
+    previous: Arc<Hook>,
+}
+
+// Installs the system's hook, unless it already is, chaining it
+// with the current one in the given order. The current hook isn't
+// called for the panics of elements if `hide` is set.
+pub(crate) fn install(order: PanicHookOrder, hide: bool) {
+    // FIXME: panics?
+    let mut installed = INSTALLED.lock().unwrap();
+    if installed.is_some() {
+        return;
+    }
+
+    debug!("Bastion: Installing the panic hook ({:?}).", order);
+    let previous = Arc::new(panic::take_hook());
+    let chained = previous.clone();
+    let hook: Hook = Box::new(move |info| {
+        // The elements are run by the executor's workers.
+        let elem = worker::is_worker();
+        if order == PanicHookOrder::Exclusive {
+            // The panics of other threads aren't the system's.
+            if elem {
+                capture(info);
+            } else {
+                chained(info);
+            }
+
+            return;
+        }
+
+        if order == PanicHookOrder::First && elem {
+            capture(info);
+        }
+        if !(hide && elem) {
+            chained(info);
+        }
+        if order == PanicHookOrder::Last && elem {
+            capture(info);
+        }
+    });
+
+    let addr = addr(&hook);
+    panic::set_hook(hook);
+    *installed = Some(Installed { addr, previous });
+}
+
+// Restores the hook the system's one replaced, unless it was
+// replaced since then (in which case it is chained again the next
+// time the system is initialized).
+pub(crate) fn restore() {
+    // FIXME: panics?
+    let Installed {
+        addr: installed,
+        previous,
+    } = match INSTALLED.lock().unwrap().take() {
+        Some(installed) => installed,
+        None => return,
+    };
+
+    let current = panic::take_hook();
+    if addr(&current) != installed {
+        debug!("Bastion: Keeping the panic hook installed since.");
+        panic::set_hook(current);
+        return;
+    }
+
+    debug!("Bastion: Restoring the previous panic hook.");
+    // Dropping the system's hook drops its reference to the
+    // previous one.
+    drop(current);
+    match Arc::try_unwrap(previous) {
+        Ok(previous) => panic::set_hook(previous),
+        Err(previous) => panic::set_hook(Box::new(move |info| previous(info))),
+    }
+}
+
+// What the system's hook does with the panics of elements, which
+// are then caught to restart them.
+fn capture(info: &PanicHookInfo) {
+    warn!("Bastion: An element panicked: {}", info);
+    let location = info.location().map(ToString::to_string);
+    let backtrace = Backtrace::capture();
+    let backtrace = match backtrace.status() {
+        BacktraceStatus::Captured => Some(backtrace.to_string()),
+        _ => None,
+    };
+
+    CAPTURED.with(|captured| *captured.borrow_mut() = (location, backtrace));
+}
+
+// Returns the location and backtrace of the last panic of an
+// element run by the current thread, if the system's hook was
+// called for it.
+pub(crate) fn take_captured() -> (Option<String>, Option<String>) {
+    CAPTURED.with(|captured| captured.replace((None, None)))
+}
+
+fn addr(hook: &Hook) -> usize {
+    &**hook as *const _ as *const () as usize
+}
@@ -0,0 +1,343 @@
+//!
+//! Patterns built on top of the elements' messaging, to avoid
+//! doing the same bookkeeping in every application.
+//!
+//! [`gather`] and [`gather_unordered`] ask different questions
+//! to different elements and match each answer with the key
+//! its question was asked with.
+//!
+//! [`gather`]: fn.gather.html
+//! [`gather_unordered`]: fn.gather_unordered.html
+use crate::child_ref::ChildRef;
+use crate::envelope::SignedMessage;
+use crate::errors::AnswerError;
+use crate::message::Message;
+use futures::future::BoxFuture;
+use futures::prelude::*;
+use futures::stream::FuturesUnordered;
+use futures_timer::Delay;
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// Asks each message to its element and returns a [`Future`]
+/// resolving to one result per key, in the order of `requests`,
+/// once every question was answered or failed.
+///
+/// An element that stops (or was already stopped) without
+/// answering its question makes its key fail with
+/// [`AnswerError::Unanswered`]. If an overall timeout is set
+/// using [`Gather::with_timeout`], the keys that weren't answered
+/// before it expired fail with [`AnswerError::TimedOut`].
+///
+/// # Arguments
+///
+/// * `requests` - The key, element and message of each question.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// # use bastion::patterns;
+/// # use std::time::Duration;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// let children_ref = Bastion::children(|children| {
+///     children
+///         .with_redundancy(2)
+///         .with_exec(|ctx: BastionContext| {
+///             async move {
+///                 loop {
+///                     msg! { ctx.recv().await?,
+///                         n: u64 =!> {
+///                             answer!(ctx, n * 2).ok();
+///                         };
+///                         _: _ => ();
+///                     }
+///                 }
+///             }
+///         })
+/// }).expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///
+/// let elems = children_ref.elems();
+/// let requests = vec![("a", elems[0].clone(), 1u64), ("b", elems[1].clone(), 2u64)];
+/// let results = run!(patterns::gather(requests).with_timeout(Duration::from_secs(1)));
+///
+/// for (key, result) in results {
+///     msg! { result.expect("Couldn't receive the answer."),
+///         n: u64 => println!("{}: {}", key, n);
+///         _: _ => ();
+///     }
+/// }
+///     #
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+/// [`AnswerError::Unanswered`]: ../errors/enum.AnswerError.html#variant.Unanswered
+/// [`AnswerError::TimedOut`]: ../errors/enum.AnswerError.html#variant.TimedOut
+/// [`Gather::with_timeout`]: struct.Gather.html#method.with_timeout
+pub fn gather<K, M: Message>(requests: Vec<(K, ChildRef, M)>) -> Gather<K> {
+    let asks = Asks::new(requests);
+    let results = asks.keys.iter().map(|_| None).collect();
+
+    Gather { asks, results }
+}
+
+/// Asks each message to its element and returns a [`Stream`]
+/// yielding one result per key, as soon as its question is
+/// answered or failed.
+///
+/// See [`gather`] for the way the questions can fail.
+///
+/// # Arguments
+///
+/// * `requests` - The key, element and message of each question.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// # use bastion::patterns;
+/// # use futures::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+///     # let children_ref = Bastion::children(|children| {
+///     #     children.with_exec(|ctx: BastionContext| {
+///     #         async move {
+///     #             loop {
+///     #                 msg! { ctx.recv().await?,
+///     #                     n: u64 =!> { answer!(ctx, n * 2).ok(); };
+///     #                     _: _ => ();
+///     #                 }
+///     #             }
+///     #         }
+///     #     })
+///     # }).unwrap();
+///     # Bastion::start();
+///     # let child_ref = children_ref.elems()[0].clone();
+/// let requests = (0..10u64).map(|n| (n, child_ref.clone(), n)).collect();
+/// let mut results = patterns::gather_unordered(requests);
+///
+/// run!(async {
+///     while let Some((key, result)) = results.next().await {
+///         // Retry the failed questions...
+///         # let _ = (key, result);
+///     }
+/// });
+///     #
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`Stream`]: https://docs.rs/futures/0.3/futures/stream/trait.Stream.html
+/// [`gather`]: fn.gather.html
+pub fn gather_unordered<K, M: Message>(requests: Vec<(K, ChildRef, M)>) -> GatherUnordered<K> {
+    let asks = Asks::new(requests);
+
+    GatherUnordered { asks }
+}
+
+/// The [`Future`] returned by [`gather`].
+///
+/// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+/// [`gather`]: fn.gather.html
+pub struct Gather<K> {
+    asks: Asks<K>,
+    results: Vec<Option<(K, Result<SignedMessage, AnswerError>)>>,
+}
+
+/// The [`Stream`] returned by [`gather_unordered`].
+///
+/// [`Stream`]: https://docs.rs/futures/0.3/futures/stream/trait.Stream.html
+/// [`gather_unordered`]: fn.gather_unordered.html
+pub struct GatherUnordered<K> {
+    asks: Asks<K>,
+}
+
+// The result of a question.
+type Answered = Result<SignedMessage, AnswerError>;
+
+struct Asks<K> {
+    // The key of each question, taken once its result is
+    // returned.
+    keys: Vec<Option<K>>,
+    // The index of each question along with its answer.
+    answers: FuturesUnordered<BoxFuture<'static, (usize, Answered)>>,
+    timeout: Option<Delay>,
+    // The index of the next key to check for when the timeout
+    // expired, the keys before it being already returned.
+    expired: Option<usize>,
+}
+
+impl<K> Gather<K> {
+    /// Sets how long to wait for all the answers, after which
+    /// the keys that weren't answered fail with
+    /// [`AnswerError::TimedOut`].
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long to wait for all the answers,
+    ///   starting now.
+    ///
+    /// [`AnswerError::TimedOut`]: ../errors/enum.AnswerError.html#variant.TimedOut
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.asks.timeout = Some(Delay::new(timeout));
+        self
+    }
+}
+
+impl<K> GatherUnordered<K> {
+    /// Sets how long to wait for all the answers, after which
+    /// the keys that weren't answered fail with
+    /// [`AnswerError::TimedOut`].
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long to wait for all the answers,
+    ///   starting now.
+    ///
+    /// [`AnswerError::TimedOut`]: ../errors/enum.AnswerError.html#variant.TimedOut
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.asks.timeout = Some(Delay::new(timeout));
+        self
+    }
+}
+
+impl<K> Asks<K> {
+    fn new<M: Message>(requests: Vec<(K, ChildRef, M)>) -> Self {
+        let mut keys = Vec::with_capacity(requests.len());
+        let answers = FuturesUnordered::new();
+
+        for (index, (key, child_ref, msg)) in requests.into_iter().enumerate() {
+            keys.push(Some(key));
+
+            let answer = child_ref.ask_anonymously(msg);
+            answers.push(
+                async move {
+                    match answer {
+                        Ok(answer) => (index, answer.await),
+                        Err(_) => (index, Err(AnswerError::Unanswered)),
+                    }
+                }
+                .boxed(),
+            );
+        }
+
+        Asks {
+            keys,
+            answers,
+            timeout: None,
+            expired: None,
+        }
+    }
+
+    fn poll_next(&mut self, ctx: &mut Context) -> Poll<Option<(usize, K, Answered)>> {
+        if self.expired.is_none() {
+            match self.answers.poll_next_unpin(ctx) {
+                Poll::Ready(Some((index, result))) => {
+                    let key = self.keys[index].take().unwrap();
+                    return Poll::Ready(Some((index, key, result)));
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => (),
+            }
+
+            match self.timeout.as_mut().map(|timeout| timeout.poll_unpin(ctx)) {
+                Some(Poll::Ready(())) => {
+                    debug!("Gather: Timed out.");
+                    // Dropping the answers still pending makes their
+                    // messages get dropped without being answered.
+                    self.answers = FuturesUnordered::new();
+                    self.expired = Some(0);
+                }
+                _ => return Poll::Pending,
+            }
+        }
+
+        let start = self.expired.unwrap_or_default();
+        for index in start..self.keys.len() {
+            if let Some(key) = self.keys[index].take() {
+                self.expired = Some(index + 1);
+                return Poll::Ready(Some((index, key, Err(AnswerError::TimedOut))));
+            }
+        }
+
+        self.expired = Some(self.keys.len());
+        Poll::Ready(None)
+    }
+}
+
+// The keys are never pinned.
+impl<K> Unpin for Gather<K> {}
+impl<K> Unpin for GatherUnordered<K> {}
+
+impl<K> Future for Gather<K> {
+    type Output = Vec<(K, Result<SignedMessage, AnswerError>)>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let gather = self.get_mut();
+
+        loop {
+            match gather.asks.poll_next(ctx) {
+                Poll::Ready(Some((index, key, result))) => {
+                    gather.results[index] = Some((key, result));
+                }
+                Poll::Ready(None) => {
+                    let results = gather.results.drain(..);
+                    // Every key gets exactly one result.
+                    return Poll::Ready(results.map(Option::unwrap).collect());
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<K> Stream for GatherUnordered<K> {
+    type Item = (K, Result<SignedMessage, AnswerError>);
+
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Option<Self::Item>> {
+        self.get_mut()
+            .asks
+            .poll_next(ctx)
+            .map(|next| next.map(|(_, key, result)| (key, result)))
+    }
+}
+
+impl<K> Debug for Gather<K> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Gather")
+            .field("asks", &self.asks)
+            .finish()
+    }
+}
+
+impl<K> Debug for GatherUnordered<K> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("GatherUnordered")
+            .field("asks", &self.asks)
+            .finish()
+    }
+}
+
+impl<K> Debug for Asks<K> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Asks")
+            .field("keys", &self.keys.len())
+            .field("pending", &self.answers.len())
+            .field("expired", &self.expired.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,341 @@
+//!
+//! A pipeline is a chain of children groups where each group
+//! (or stage) sends its output to the next one.
+use crate::bastion::Bastion;
+use crate::children::Children;
+use crate::children_ref::ChildrenRef;
+use crate::errors::{BuilderError, SendError, SendErrorKind};
+use crate::message::Message;
+use std::fmt::{self, Debug, Formatter};
+
+/// A builder creating the children groups of a pipeline and
+/// wiring them together.
+///
+/// Each stage is a children group whose elements can retrieve
+/// a [`ChildrenRef`] referencing the next stage's group using
+/// [`BastionContext::next_stage`]. The groups are created from
+/// the last stage to the first one, so that a stage is always
+/// started before the stages sending to it.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// let pipeline = Pipeline::new()
+///     .stage("double", |children| {
+///         children.with_exec(|ctx: BastionContext| {
+///             async move {
+///                 loop {
+///                     msg! { ctx.recv().await?,
+///                         // Messages sent to a stage are broadcasted
+///                         // to its elements...
+///                         ref n: u64 => {
+///                             // `next_stage` is only `None` for the last stage.
+///                             ctx.next_stage().unwrap().broadcast(*n * 2).ok();
+///                         };
+///                         _: _ => ();
+///                     }
+///                 }
+///             }
+///         })
+///     })
+///     .stage("print", |children| {
+///         children.with_exec(|ctx: BastionContext| {
+///             async move {
+///                 loop {
+///                     msg! { ctx.recv().await?,
+///                         ref n: u64 => {
+///                             println!("{}", n);
+///                         };
+///                         _: _ => ();
+///                     }
+///                 }
+///             }
+///         })
+///     })
+///     .build()
+///     .expect("Couldn't create the pipeline.");
+///
+/// pipeline.push(21u64).expect("Couldn't push the message.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`ChildrenRef`]: ../children_ref/struct.ChildrenRef.html
+/// [`BastionContext::next_stage`]: ../context/struct.BastionContext.html#method.next_stage
+#[derive(Default)]
+pub struct Pipeline {
+    stages: Vec<Stage>,
+}
+
+struct Stage {
+    name: String,
+    init: Box<dyn FnOnce(Children) -> Children>,
+}
+
+#[derive(Debug, Clone)]
+/// A "reference" to the children groups of a pipeline created
+/// using [`Pipeline::build`], allowing to feed its first stage.
+///
+/// [`Pipeline::build`]: struct.Pipeline.html#method.build
+pub struct PipelineRef {
+    stages: Vec<(String, ChildrenRef)>,
+}
+
+impl Pipeline {
+    /// Creates a new pipeline without any stage.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// let pipeline = Pipeline::new();
+    /// ```
+    pub fn new() -> Self {
+        Pipeline::default()
+    }
+
+    /// Appends a stage to this pipeline.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the stage, which can later be
+    ///   used with [`PipelineRef::stage`].
+    /// * `init` - The closure taking a new [`Children`] and
+    ///   returning it once configured, like the one passed to
+    ///   [`Bastion::children`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// let pipeline = Pipeline::new().stage("parse", |children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // ...
+    ///             # Ok(())
+    ///         }
+    ///     })
+    /// });
+    /// ```
+    ///
+    /// [`PipelineRef::stage`]: struct.PipelineRef.html#method.stage
+    /// [`Children`]: ../children/struct.Children.html
+    /// [`Bastion::children`]: ../struct.Bastion.html#method.children
+    pub fn stage<N, C>(mut self, name: N, init: C) -> Self
+    where
+        N: Into<String>,
+        C: FnOnce(Children) -> Children + 'static,
+    {
+        let name = name.into();
+        debug!("Pipeline: Adding stage \"{}\".", name);
+        let init = Box::new(init);
+        self.stages.push(Stage { name, init });
+
+        self
+    }
+
+    /// Creates the children groups of this pipeline's stages,
+    /// from the last one to the first one, and sends them to
+    /// the system's default supervisor.
+    ///
+    /// This method returns a [`PipelineRef`] referencing the
+    /// newly created groups if the creation was successful,
+    /// otherwise stops the groups that were already created and
+    /// returns a [`BuilderError`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let pipeline_ref: PipelineRef = Pipeline::new()
+    ///     .stage("sink", |children| children)
+    ///     .build()
+    ///     .expect("Couldn't create the pipeline.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`PipelineRef`]: struct.PipelineRef.html
+    /// [`BuilderError`]: ../errors/enum.BuilderError.html
+    pub fn build(self) -> Result<PipelineRef, BuilderError> {
+        debug!("Pipeline: Building {} stages.", self.stages.len());
+        let mut stages: Vec<(String, ChildrenRef)> = Vec::with_capacity(self.stages.len());
+        let mut next_stage: Option<ChildrenRef> = None;
+
+        for Stage { name, init } in self.stages.into_iter().rev() {
+            let next = next_stage.take();
+            let children = Bastion::children(move |children| {
+                let children = init(children);
+                match next {
+                    Some(next) => children.with_next_stage(next),
+                    None => children,
+                }
+            });
+
+            let children = match children {
+                Ok(children) => children,
+                Err(err) => {
+                    // The stages that were already created would
+                    // otherwise be left running without a way to
+                    // reach them.
+                    warn!(
+                        "Pipeline: Couldn't create stage \"{}\", stopping the {} created ones.",
+                        name,
+                        stages.len()
+                    );
+                    for (_, children) in stages {
+                        children.stop().ok();
+                    }
+
+                    return Err(err);
+                }
+            };
+
+            debug!("Pipeline: Created stage \"{}\": {}.", name, children.id());
+            next_stage = Some(children.clone());
+            stages.push((name, children));
+        }
+
+        stages.reverse();
+        Ok(PipelineRef { stages })
+    }
+}
+
+impl PipelineRef {
+    /// Sends a message to the first stage of the pipeline
+    /// this `PipelineRef` is referencing.
+    ///
+    /// Like [`ChildrenRef::tell_one`], the message is sent to a
+    /// single element of the stage's children group (chosen in a
+    /// round-robin fashion by default), so that each item is only
+    /// processed once by the stage whatever its redundancy.
+    ///
+    /// This method returns `()` if it succeeded, or a [`SendError`]
+    /// carrying the message otherwise (whose kind is
+    /// [`SendErrorKind::NoRecipient`] if the pipeline doesn't have
+    /// any stage).
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// # let pipeline = Pipeline::new().stage("sink", |children| children).build().unwrap();
+    /// pipeline.push("A message.").expect("Couldn't push the message.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef::tell_one`]: ../children_ref/struct.ChildrenRef.html#method.tell_one
+    /// [`SendError`]: ../errors/struct.SendError.html
+    /// [`SendErrorKind::NoRecipient`]: ../errors/enum.SendErrorKind.html#variant.NoRecipient
+    pub fn push<M: Message>(&self, msg: M) -> Result<(), SendError<M>> {
+        match self.stages.first() {
+            Some((_, head)) => head.tell_one(msg),
+            None => Err(SendError::new(SendErrorKind::NoRecipient, msg)),
+        }
+    }
+
+    /// Returns a [`ChildrenRef`] referencing the children group
+    /// of the stage named `name`, if it exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the stage, as given to
+    ///   [`Pipeline::stage`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// # let pipeline = Pipeline::new().stage("sink", |children| children).build().unwrap();
+    /// let sink: &ChildrenRef = pipeline.stage("sink").unwrap();
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef`]: ../children_ref/struct.ChildrenRef.html
+    /// [`Pipeline::stage`]: struct.Pipeline.html#method.stage
+    pub fn stage(&self, name: &str) -> Option<&ChildrenRef> {
+        self.stages
+            .iter()
+            .find(|(stage, _)| stage == name)
+            .map(|(_, children)| children)
+    }
+
+    /// Returns the [`ChildrenRef`]s referencing the children
+    /// groups of the pipeline's stages, from the first stage
+    /// to the last one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// # let pipeline = Pipeline::new().stage("sink", |children| children).build().unwrap();
+    /// for stage in pipeline.stages() {
+    ///     // ...
+    /// }
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef`]: ../children_ref/struct.ChildrenRef.html
+    pub fn stages(&self) -> Vec<&ChildrenRef> {
+        self.stages.iter().map(|(_, children)| children).collect()
+    }
+}
+
+impl Debug for Pipeline {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Pipeline")
+            .field("stages", &self.stages)
+            .finish()
+    }
+}
+
+impl Debug for Stage {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Stage").field("name", &self.name).finish()
+    }
+}
@@ -0,0 +1,216 @@
+//!
+//! Named bundles of policies that can be applied to the supervisors
+//! and the children groups (see [`Supervisor::apply_preset`] and
+//! [`Children::apply_preset`]), for common kinds of workloads.
+//!
+//! [`Supervisor::apply_preset`]: ../supervisor/struct.Supervisor.html#method.apply_preset
+//! [`Children::apply_preset`]: ../children/struct.Children.html#method.apply_preset
+#[cfg(feature = "supervision")]
+use crate::backoff::Backoff;
+use crate::mailbox::OverflowPolicy;
+use crate::supervisor::{ActorRestartStrategy, RestartPolicy, RestartStrategy};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+/// A named bundle of policies, which can be applied to a supervisor
+/// using [`Supervisor::apply_preset`] (for the policies applying to
+/// the children groups it supervises) or to a children group using
+/// [`Children::apply_preset`] (for the policies applying to the
+/// group itself), and then selectively overridden by calling the
+/// builders' other methods.
+///
+/// The presets are plain data: their policies can be inspected, and
+/// the name of the preset applied to a group is part of its
+/// [`GroupPolicies`] (and of the exported topology).
+///
+/// The restart strategy and window are applied to the
+/// supervisors, the drain timeout and whether the messages are
+/// requeued to the children groups, and the other policies to both
+/// (as the default ones of the groups a supervisor supervises):
+///
+/// | Preset                    | Restart policy     | Restart window | Restart back-off          | Pre-start watermark | Mailbox capacity | Overflow policy | Drain timeout | Requeue on restart |
+/// |---------------------------|--------------------|----------------|---------------------------|---------------------|------------------|-----------------|---------------|--------------------|
+/// | [`web_service`]           | always             | 10 within 10s  | none                      | 1000                | 1024             | fail            | 1s            | no                 |
+/// | [`batch_pipeline`]        | always             | none           | exponential (100ms - 10s) | 10000               | 10000            | block           | 60s           | yes                |
+/// | [`best_effort_telemetry`] | never (temporary)  | none           | none                      | 100                 | 256              | drop oldest     | 1s            | no                 |
+///
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// Bastion::supervisor(|sp| {
+///     sp.apply_preset(SupervisionPreset::web_service())
+///         .children(|children| {
+///             children
+///                 .apply_preset(SupervisionPreset::web_service())
+///                 // Overrides the preset's capacity.
+///                 .with_mailbox_capacity(4096)
+///         })
+/// }).expect("Couldn't create the supervisor.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`Supervisor::apply_preset`]: ../supervisor/struct.Supervisor.html#method.apply_preset
+/// [`Children::apply_preset`]: ../children/struct.Children.html#method.apply_preset
+/// [`GroupPolicies`]: ../children_ref/struct.GroupPolicies.html
+/// [`web_service`]: #method.web_service
+/// [`batch_pipeline`]: #method.batch_pipeline
+/// [`best_effort_telemetry`]: #method.best_effort_telemetry
+pub struct SupervisionPreset {
+    name: &'static str,
+    restart_strategy: RestartStrategy,
+    #[cfg(feature = "supervision")]
+    restart_window: Option<(usize, Duration)>,
+    #[cfg(feature = "supervision")]
+    restart_backoff: Option<Backoff>,
+    pre_start_watermark: usize,
+    mailbox_capacity: Option<usize>,
+    overflow_policy: OverflowPolicy,
+    drain_timeout: Duration,
+    requeue_on_restart: bool,
+}
+
+impl SupervisionPreset {
+    /// Returns the preset of the groups serving requests, which
+    /// are restarted right away (giving up after 10 restarts in
+    /// 10 seconds) without the requests their elements didn't
+    /// handle, whose bounded mailboxes shed the load by making
+    /// the sends fail once they are full and which are given a
+    /// second to drain them when stopped.
+    pub fn web_service() -> Self {
+        SupervisionPreset {
+            name: "web_service",
+            restart_strategy: RestartStrategy::new(
+                RestartPolicy::Always,
+                ActorRestartStrategy::Immediate,
+            ),
+            #[cfg(feature = "supervision")]
+            restart_window: Some((10, Duration::from_secs(10))),
+            #[cfg(feature = "supervision")]
+            restart_backoff: None,
+            pre_start_watermark: 1_000,
+            mailbox_capacity: Some(1_024),
+            overflow_policy: OverflowPolicy::Fail,
+            drain_timeout: Duration::from_secs(1),
+            requeue_on_restart: false,
+        }
+    }
+
+    /// Returns the preset of the groups processing batches, which
+    /// are restarted with an exponential back-off along with the
+    /// messages their elements didn't handle, whose bounded
+    /// mailboxes make the senders wait once they are full (so that
+    /// no message is shed) and which are given a minute to drain
+    /// them when stopped.
+    pub fn batch_pipeline() -> Self {
+        SupervisionPreset {
+            name: "batch_pipeline",
+            restart_strategy: RestartStrategy::new(
+                RestartPolicy::Always,
+                ActorRestartStrategy::Immediate,
+            ),
+            #[cfg(feature = "supervision")]
+            restart_window: None,
+            #[cfg(feature = "supervision")]
+            restart_backoff: Some(Backoff::exponential(
+                Duration::from_millis(100),
+                Duration::from_secs(10),
+                0.1,
+            )),
+            pre_start_watermark: 10_000,
+            mailbox_capacity: Some(10_000),
+            overflow_policy: OverflowPolicy::Block,
+            drain_timeout: Duration::from_secs(60),
+            requeue_on_restart: true,
+        }
+    }
+
+    /// Returns the preset of the groups whose messages can be
+    /// lost, whose elements are temporary (they are never
+    /// restarted), whose bounded mailboxes drop their oldest
+    /// messages once they are full and which are given a second
+    /// to drain them when stopped.
+    pub fn best_effort_telemetry() -> Self {
+        SupervisionPreset {
+            name: "best_effort_telemetry",
+            restart_strategy: RestartStrategy::new(
+                RestartPolicy::Never,
+                ActorRestartStrategy::Immediate,
+            ),
+            #[cfg(feature = "supervision")]
+            restart_window: None,
+            #[cfg(feature = "supervision")]
+            restart_backoff: None,
+            pre_start_watermark: 100,
+            mailbox_capacity: Some(256),
+            overflow_policy: OverflowPolicy::DropOldest,
+            drain_timeout: Duration::from_secs(1),
+            requeue_on_restart: false,
+        }
+    }
+
+    /// Returns the preset's name (e.g. `"web_service"`).
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the restart strategy applied to the supervisors.
+    pub fn restart_strategy(&self) -> &RestartStrategy {
+        &self.restart_strategy
+    }
+
+    #[cfg(feature = "supervision")]
+    /// Returns the restart window applied to the supervisors, if
+    /// they limit the restarts.
+    pub fn restart_window(&self) -> Option<(usize, Duration)> {
+        self.restart_window
+    }
+
+    #[cfg(feature = "supervision")]
+    /// Returns the back-off delaying the restarts, applied to the
+    /// supervisors (as their default one) and to the children
+    /// groups, if the restarts are delayed.
+    pub fn restart_backoff(&self) -> Option<&Backoff> {
+        self.restart_backoff.as_ref()
+    }
+
+    /// Returns the pre-start watermark applied to the supervisors
+    /// (as their default one) and to the children groups.
+    pub fn pre_start_watermark(&self) -> usize {
+        self.pre_start_watermark
+    }
+
+    /// Returns the mailbox capacity applied to the supervisors (as
+    /// their default one) and to the children groups, if their
+    /// mailboxes are bounded.
+    pub fn mailbox_capacity(&self) -> Option<usize> {
+        self.mailbox_capacity
+    }
+
+    /// Returns the overflow policy applied to the supervisors (as
+    /// their default one) and to the children groups.
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        self.overflow_policy
+    }
+
+    /// Returns how long the elements of the children groups are
+    /// given to drain their mailboxes when they are stopped.
+    pub fn drain_timeout(&self) -> Duration {
+        self.drain_timeout
+    }
+
+    /// Returns whether the children groups' restarted elements
+    /// handle the messages their previous incarnation didn't.
+    pub fn requeues_on_restart(&self) -> bool {
+        self.requeue_on_restart
+    }
+}
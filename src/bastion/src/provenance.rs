@@ -0,0 +1,176 @@
+//!
+//! Provenance chains, recording the messages that caused the
+//! messages sent by the elements of a children group to be sent
+//! (see [`Children::with_provenance`]).
+//!
+//! [`Children::with_provenance`]: ../children/struct.Children.html#method.with_provenance
+use crate::correlation::CorrelationId;
+use crate::message::Msg;
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex};
+
+/// The default maximum number of entries of a provenance chain.
+pub(crate) const DEFAULT_MAX_DEPTH: usize = 8;
+
+thread_local! {
+    // The tracker of the element whose future is being polled by
+    // this thread, if its group records the provenance.
+    static CURRENT: RefCell<Option<Tracker>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// A message recorded in the provenance chain of a message that
+/// was sent while handling it.
+pub struct ProvenanceEntry {
+    type_name: &'static str,
+    correlation_id: Option<CorrelationId>,
+}
+
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+/// The chain of messages that caused a message to be sent,
+/// starting with the message its sender was handling when it
+/// sent it, followed by the message that caused this one to be
+/// sent, and so on.
+///
+/// Chains are only recorded for the messages sent by the elements
+/// of the children groups enabling it (using
+/// [`Children::with_provenance`]) and are bounded, the oldest
+/// entries being dropped once a chain exceeds its maximum depth.
+///
+/// [`Children::with_provenance`]: ../children/struct.Children.html#method.with_provenance
+pub struct Provenance {
+    entries: Vec<ProvenanceEntry>,
+    truncated: bool,
+}
+
+#[derive(Debug, Clone)]
+// The provenance recorded by an element, shared with the thread
+// polling its future.
+pub(crate) struct Tracker(Arc<Mutex<Tracking>>);
+
+#[derive(Debug)]
+struct Tracking {
+    max_depth: usize,
+    // The provenance of the messages sent while handling the last
+    // message the element received.
+    sent: Provenance,
+}
+
+// Unsets the tracker of the current thread once dropped
+// (including if the element's future panicked).
+pub(crate) struct Entered(());
+
+impl ProvenanceEntry {
+    pub(crate) fn new(type_name: &'static str) -> Self {
+        ProvenanceEntry {
+            type_name,
+            correlation_id: None,
+        }
+    }
+
+    pub(crate) fn set_correlation_id(&mut self, id: CorrelationId) {
+        self.correlation_id = Some(id);
+    }
+
+    /// Returns the name of the message's type.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns the correlation id the message was sent or replied
+    /// with, if it was sent using [`ChildRef::tell_correlated`] or
+    /// replied using [`BastionContext::reply_correlated`].
+    ///
+    /// [`ChildRef::tell_correlated`]: ../child_ref/struct.ChildRef.html#method.tell_correlated
+    /// [`BastionContext::reply_correlated`]: ../context/struct.BastionContext.html#method.reply_correlated
+    pub fn correlation_id(&self) -> Option<&CorrelationId> {
+        self.correlation_id.as_ref()
+    }
+}
+
+impl Provenance {
+    // The provenance of the messages sent while handling `msg`.
+    fn caused_by(msg: &Msg, max_depth: usize) -> Self {
+        let (parent, parent_truncated) = match msg.provenance_chain() {
+            Some(parent) => (&parent.entries[..], parent.truncated),
+            None => (&[][..], false),
+        };
+        let mut entries = Vec::with_capacity(max_depth.min(parent.len() + 1));
+        entries.push(msg.provenance_entry().clone());
+        entries.extend(parent.iter().take(max_depth - 1).cloned());
+
+        let truncated = parent_truncated || parent.len() >= max_depth;
+        Provenance { entries, truncated }
+    }
+
+    /// Returns the entries of the chain, starting with the message
+    /// that directly caused the message to be sent.
+    pub fn entries(&self) -> &[ProvenanceEntry] {
+        &self.entries
+    }
+
+    /// Returns whether entries were dropped from the chain because
+    /// it exceeded its maximum depth.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Returns whether the chain doesn't have any entry.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Tracker {
+    pub(crate) fn new(max_depth: usize) -> Self {
+        let tracking = Tracking {
+            max_depth,
+            sent: Provenance::default(),
+        };
+
+        Tracker(Arc::new(Mutex::new(tracking)))
+    }
+
+    // Sets the tracker of the current thread, until the returned
+    // guard is dropped.
+    pub(crate) fn enter(&self) -> Entered {
+        CURRENT.with(|current| *current.borrow_mut() = Some(self.clone()));
+        Entered(())
+    }
+
+    // The provenance of the messages sent while handling the last
+    // message the element received.
+    pub(crate) fn provenance(&self) -> Provenance {
+        self.0.lock().unwrap().sent.clone()
+    }
+}
+
+impl Drop for Entered {
+    fn drop(&mut self) {
+        CURRENT.with(|current| current.borrow_mut().take());
+    }
+}
+
+// The provenance that a message sent by the current thread gets,
+// if it is polling the future of an element recording it and this
+// element already received a message.
+pub(crate) fn current() -> Option<Provenance> {
+    CURRENT.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .map(Tracker::provenance)
+            .filter(|provenance| !provenance.is_empty())
+    })
+}
+
+// Records that the element whose future is being polled by the
+// current thread received `msg`.
+pub(crate) fn handling(msg: &Msg) {
+    CURRENT.with(|current| {
+        if let Some(tracker) = &*current.borrow() {
+            let mut tracking = tracker.0.lock().unwrap();
+            tracking.sent = Provenance::caused_by(msg, tracking.max_depth);
+        }
+    })
+}
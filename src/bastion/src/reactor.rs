@@ -0,0 +1,310 @@
+//!
+//! The reactor, an element supervised by the system which owns the
+//! timers of the elements (see [`BastionContext::sleep`]) and wakes
+//! them once they expire.
+//!
+//! If the executor schedules the processes deterministically, the
+//! timers' deadlines follow its virtual clock (see
+//! [`Config::deterministic_scheduler`]).
+//!
+//! [`Config::deterministic_scheduler`]: ../struct.Config.html#method.deterministic_scheduler
+//! [`BastionContext::sleep`]: ../context/struct.BastionContext.html#method.sleep
+use crate::context::BastionContext;
+use bastion_executor::deterministic;
+use futures::future::{self, Either};
+use futures::prelude::*;
+use futures_timer::Delay;
+use lazy_static::lazy_static;
+use std::collections::BTreeMap;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll, Waker};
+use std::time::Instant;
+
+lazy_static! {
+    // NOTE: the wheel isn't held by the system because the timers
+    //      registered by the elements outlive the restarts of the
+    //      reactor, and the system's initialization.
+    static ref WHEEL: Mutex<Wheel> = Mutex::new(Wheel::default());
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// The health of the system's reactor, as returned by
+/// [`Health::reactor`].
+///
+/// [`Health::reactor`]: struct.Health.html#method.reactor
+pub struct ReactorHealth {
+    running: bool,
+    restarts: usize,
+    timers: usize,
+}
+
+#[derive(Debug, Default)]
+// The timers registered by the elements, by deadline.
+struct Wheel {
+    // Incremented each time the reactor starts, the timers
+    // registered before being registered again by their owners.
+    generation: u64,
+    next_id: u64,
+    timers: BTreeMap<(Instant, u64), Waker>,
+    // Woken when a timer expiring before the others is registered,
+    // or when the reactor is asked to fault.
+    reactor: Option<Waker>,
+    running: bool,
+    restarts: usize,
+    // Whether the reactor is asked to fault, or faulted and wasn't
+    // restarted yet.
+    fault: bool,
+    faulted: bool,
+}
+
+#[derive(Debug)]
+// A future completing once its deadline is reached, which the
+// reactor wakes once it is.
+pub(crate) struct Sleep {
+    deadline: Instant,
+    // The generation of the wheel the timer is registered in and
+    // its id, if it is.
+    timer: Option<(u64, u64)>,
+}
+
+// Marks the reactor as stopped once dropped (including if it
+// faulted or panicked).
+struct Running(());
+
+// Returns the current time, against which the timers' deadlines
+// are compared (which is virtual if the executor schedules the
+// processes deterministically).
+pub(crate) fn now() -> Instant {
+    deterministic::now()
+}
+
+impl ReactorHealth {
+    /// Returns whether the reactor is running (it isn't until the
+    /// system is started, while it restarts, or once the system is
+    /// stopped).
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Returns how many times the reactor was restarted after it
+    /// faulted.
+    pub fn restarts(&self) -> usize {
+        self.restarts
+    }
+
+    /// Returns the number of timers that are registered and didn't
+    /// expire yet.
+    pub fn timers(&self) -> usize {
+        self.timers
+    }
+}
+
+impl Wheel {
+    fn register(&mut self, deadline: Instant, waker: Waker) -> (u64, u64) {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let earliest = self.timers.keys().next().map(|(deadline, _)| *deadline);
+        self.timers.insert((deadline, id), waker);
+        if earliest.is_none_or(|earliest| deadline < earliest) {
+            self.wake_reactor();
+        }
+
+        (self.generation, id)
+    }
+
+    // Wakes the owners of the timers that expired.
+    fn expire(&mut self, now: Instant) {
+        while let Some(&(deadline, id)) = self.timers.keys().next() {
+            if deadline > now {
+                return;
+            }
+
+            if let Some(waker) = self.timers.remove(&(deadline, id)) {
+                waker.wake();
+            }
+        }
+    }
+
+    fn next_deadline(&self) -> Option<Instant> {
+        self.timers.keys().next().map(|(deadline, _)| *deadline)
+    }
+
+    fn wake_reactor(&mut self) {
+        if let Some(reactor) = self.reactor.take() {
+            reactor.wake();
+        }
+    }
+}
+
+impl Sleep {
+    pub(crate) fn new(deadline: Instant) -> Self {
+        Sleep {
+            deadline,
+            timer: None,
+        }
+    }
+
+    fn cancel(&mut self) {
+        if let Some((generation, id)) = self.timer.take() {
+            // FIXME: panics?
+            let mut wheel = WHEEL.lock().unwrap();
+            if wheel.generation == generation {
+                wheel.timers.remove(&(self.deadline, id));
+            }
+        }
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        if now() >= self.deadline {
+            self.cancel();
+            return Poll::Ready(());
+        }
+
+        let deadline = self.deadline;
+        // FIXME: panics?
+        let mut wheel = WHEEL.lock().unwrap();
+        let registered = match self.timer {
+            Some((generation, id)) if generation == wheel.generation => {
+                wheel.timers.get_mut(&(deadline, id))
+            }
+            _ => None,
+        };
+
+        match registered {
+            Some(waker) => *waker = cx.waker().clone(),
+            // The timer expired or was registered before the
+            // reactor restarted.
+            None => self.timer = Some(wheel.register(deadline, cx.waker().clone())),
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+impl Running {
+    fn start() -> Self {
+        // FIXME: panics?
+        let mut wheel = WHEEL.lock().unwrap();
+        if wheel.faulted {
+            warn!("Reactor: Restarting.");
+            wheel.faulted = false;
+            wheel.restarts += 1;
+        }
+
+        wheel.generation += 1;
+        wheel.running = true;
+        // The owners of the timers register them again once woken.
+        let timers = std::mem::take(&mut wheel.timers);
+        for (_, waker) in timers {
+            waker.wake();
+        }
+
+        Running(())
+    }
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        // FIXME: panics?
+        let mut wheel = WHEEL.lock().unwrap();
+        wheel.running = false;
+        wheel.reactor = None;
+    }
+}
+
+// Wakes the owners of the timers as they expire, until the reactor
+// is asked to fault.
+fn poll_timers(cx: &mut Context, delay: &mut Option<(Instant, Delay)>) -> Poll<Result<(), ()>> {
+    loop {
+        // FIXME: panics?
+        let mut wheel = WHEEL.lock().unwrap();
+        if wheel.fault {
+            error!("Reactor: Faulting as asked.");
+            wheel.fault = false;
+            wheel.faulted = true;
+            return Poll::Ready(Err(()));
+        }
+
+        let now = now();
+        wheel.expire(now);
+        wheel.reactor = Some(cx.waker().clone());
+        let next = match wheel.next_deadline() {
+            Some(next) => next,
+            None => {
+                *delay = None;
+                return Poll::Pending;
+            }
+        };
+        drop(wheel);
+
+        // The deterministic scheduler wakes the reactor once its
+        // clock reaches the deadline.
+        if deterministic::wake_at(next, cx.waker().clone()) {
+            *delay = None;
+            return Poll::Pending;
+        }
+
+        match delay {
+            Some((deadline, _)) if *deadline == next => (),
+            _ => *delay = Some((next, Delay::new(next - now))),
+        }
+
+        if let Some((_, delay)) = delay {
+            match Pin::new(delay).poll(cx) {
+                Poll::Ready(()) => continue,
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+// The future of the reactor's element, which ignores the messages
+// it receives.
+pub(crate) async fn run(ctx: BastionContext) -> Result<(), ()> {
+    let _running = Running::start();
+    info!("Reactor: Started.");
+
+    let mut delay = None;
+    loop {
+        let timers = future::poll_fn(|cx| poll_timers(cx, &mut delay));
+        let recv = ctx.recv();
+        futures::pin_mut!(recv);
+
+        match future::select(timers, recv).await {
+            Either::Left((res, _)) => res?,
+            Either::Right((res, _)) => {
+                res?;
+            }
+        }
+    }
+}
+
+pub(crate) fn health() -> ReactorHealth {
+    // FIXME: panics?
+    let wheel = WHEEL.lock().unwrap();
+    ReactorHealth {
+        running: wheel.running,
+        restarts: wheel.restarts,
+        timers: wheel.timers.len(),
+    }
+}
+
+// Asks the reactor to fault, for it to be restarted.
+pub(crate) fn fault() {
+    // FIXME: panics?
+    let mut wheel = WHEEL.lock().unwrap();
+    wheel.fault = true;
+    wheel.wake_reactor();
+}
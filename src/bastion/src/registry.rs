@@ -0,0 +1,67 @@
+//!
+//! The registry allowing to retrieve the named children groups.
+use crate::children_ref::ChildrenRef;
+use crate::context::BastionId;
+use crate::errors::BuilderError;
+use fxhash::FxHashMap;
+use std::sync::Mutex;
+
+#[derive(Debug, Default)]
+// The children groups created with a name, by name. A name is
+// reserved for a group before the group's elements are launched
+// and the group can only be retrieved once it was launched.
+pub(crate) struct Registry {
+    groups: Mutex<FxHashMap<String, (BastionId, Option<ChildrenRef>)>>,
+}
+
+impl Registry {
+    pub(crate) fn new() -> Self {
+        Registry::default()
+    }
+
+    pub(crate) fn reserve(&self, name: &str, id: &BastionId) -> Result<(), BuilderError> {
+        // FIXME: panics?
+        let mut groups = self.groups.lock().unwrap();
+        if groups.contains_key(name) {
+            return Err(BuilderError::NameTaken);
+        }
+
+        trace!("Registry: Reserving name {:?} for Children({}).", name, id);
+        groups.insert(name.to_string(), (id.clone(), None));
+        Ok(())
+    }
+
+    pub(crate) fn register(&self, name: &str, children_ref: ChildrenRef) {
+        // FIXME: panics?
+        let mut groups = self.groups.lock().unwrap();
+        match groups.get_mut(name) {
+            Some((id, registered)) if id == children_ref.id() => {
+                debug!("Registry: Registering Children({}) as {:?}.", id, name);
+                *registered = Some(children_ref);
+            }
+            _ => trace!(
+                "Registry: Not registering Children({}) as {:?}.",
+                children_ref.id(),
+                name
+            ),
+        }
+    }
+
+    pub(crate) fn unregister(&self, name: &str, id: &BastionId) {
+        // FIXME: panics?
+        let mut groups = self.groups.lock().unwrap();
+        // The name might have been reserved by another group.
+        if groups.get(name).map(|(reserved, _)| reserved) == Some(id) {
+            debug!("Registry: Unregistering Children({}) as {:?}.", id, name);
+            groups.remove(name);
+        }
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<ChildrenRef> {
+        // FIXME: panics?
+        let groups = self.groups.lock().unwrap();
+        groups
+            .get(name)
+            .and_then(|(_, children_ref)| children_ref.clone())
+    }
+}
@@ -0,0 +1,55 @@
+//!
+//! Rejections allow an element to hand a message it received back
+//! to the system after realizing it doesn't belong to it (e.g.
+//! because it was routed using a stale sharding of the group's
+//! elements), using [`BastionContext::reject_message`].
+//!
+//! [`BastionContext::reject_message`]: ../context/struct.BastionContext.html#method.reject_message
+
+/// The number of times a message can be rerouted (see
+/// [`RejectDisposition::Reroute`]) before it is sent to the dead
+/// letters instead of being rerouted again.
+///
+/// [`RejectDisposition::Reroute`]: enum.RejectDisposition.html#variant.Reroute
+pub const MAX_REJECT_HOPS: usize = 16;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// What happens to a message rejected using
+/// [`BastionContext::reject_message`].
+///
+/// [`BastionContext::reject_message`]: ../context/struct.BastionContext.html#method.reject_message
+pub enum RejectDisposition {
+    /// The message is routed again to one of the warm elements of
+    /// the rejecting element's group following the group's
+    /// routing (see [`ChildrenRef::tell_one`]), excluding the
+    /// rejecting element itself.
+    ///
+    /// A message is rerouted to the element its group is pinned
+    /// to, or to the one its key picks, and otherwise to the
+    /// least loaded element other than the rejecting one if the
+    /// group routes to those. Otherwise, successive reroutes go
+    /// through the group's elements in turn, so that a message
+    /// rejected by every element but one reaches it within one
+    /// hop less than the group's number of warm elements.
+    ///
+    /// A message that was already rerouted [`MAX_REJECT_HOPS`]
+    /// times, or that can't be rerouted (because the rejecting
+    /// element is the group's only warm element, or the one the
+    /// group is pinned to, or because that one isn't warm), is
+    /// sent to the dead letters instead.
+    ///
+    /// [`ChildrenRef::tell_one`]: ../children_ref/struct.ChildrenRef.html#method.tell_one
+    /// [`MAX_REJECT_HOPS`]: constant.MAX_REJECT_HOPS.html
+    Reroute,
+    /// The message is sent to the dead letters, with
+    /// [`DeadLetterReason::Rejected`].
+    ///
+    /// [`DeadLetterReason::Rejected`]: ../dead_letters/enum.DeadLetterReason.html#variant.Rejected
+    DeadLetter,
+    /// The message is sent back to its sender (signed by the
+    /// rejecting element), or to the dead letters if its sender
+    /// wasn't an element (see [`RefAddr::origin`]) or stopped.
+    ///
+    /// [`RefAddr::origin`]: ../envelope/struct.RefAddr.html#method.origin
+    ReturnToSender,
+}
@@ -0,0 +1,246 @@
+//!
+//! Resizers scaling the children groups up and down depending on
+//! how many messages their elements have to handle (see
+//! [`Children::with_resizer`]).
+//!
+//! [`Children::with_resizer`]: ../children/struct.Children.html#method.with_resizer
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The default minimum number of elements of a resized group.
+const DEFAULT_LOWER: usize = 1;
+/// The default maximum number of elements of a resized group.
+const DEFAULT_UPPER: usize = 10;
+/// The default interval at which a group's pressure is sampled.
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+/// The default average number of messages waiting in the elements'
+/// mailboxes above which a group is scaled up.
+const DEFAULT_PRESSURE: usize = 10;
+/// The shortest interval at which a group's pressure is sampled.
+const MIN_INTERVAL: Duration = Duration::from_millis(1);
+
+#[derive(Clone)]
+/// Scales a children group (see [`Children::with_resizer`]) within
+/// bounds, by sampling the [`Pressure`] on its elements at a regular
+/// interval and launching or stopping an element each time it is
+/// asked to.
+///
+/// By default, a group is resized between `1` and `10` elements,
+/// every second. It is scaled up when its elements have more than
+/// `10` messages waiting in their mailboxes on average and scaled
+/// down when at least two of them are idle while none of them has
+/// a message waiting.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// # use bastion::resizer::Resizer;
+/// # use std::time::Duration;
+/// #
+/// let resizer = Resizer::new()
+///     .lower(1)
+///     .upper(16)
+///     .sample_every(Duration::from_millis(500))
+///     .scale_up_when(|pressure| pressure.avg_mailbox > 100)
+///     .scale_down_when(|pressure| pressure.idle > pressure.elems / 2);
+/// ```
+///
+/// [`Children::with_resizer`]: ../children/struct.Children.html#method.with_resizer
+/// [`Pressure`]: struct.Pressure.html
+pub struct Resizer {
+    lower: usize,
+    upper: usize,
+    interval: Duration,
+    scale_up: Arc<dyn Fn(&Pressure) -> bool + Send + Sync>,
+    scale_down: Arc<dyn Fn(&Pressure) -> bool + Send + Sync>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// The pressure on the elements of a resized children group, as
+/// sampled by its [`Resizer`].
+///
+/// The elements being stopped by the resizer aren't sampled.
+///
+/// [`Resizer`]: struct.Resizer.html
+pub struct Pressure {
+    /// The number of elements of the group, including those that
+    /// weren't launched yet.
+    pub elems: usize,
+    /// The number of elements waiting for a message while their
+    /// mailbox is empty.
+    pub idle: usize,
+    /// The average number of messages waiting in the elements'
+    /// mailboxes.
+    pub avg_mailbox: usize,
+    /// The largest number of messages waiting in an element's
+    /// mailbox.
+    pub max_mailbox: usize,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+// What a group's resizer asks it to do once it sampled its
+// pressure.
+pub(crate) enum Resize {
+    // Launches elements until the group has this many of them.
+    Up(usize),
+    // Stops an idle element.
+    Down,
+}
+
+impl Resizer {
+    /// Creates a resizer using the default bounds, interval and
+    /// policies (see [`Resizer`]).
+    ///
+    /// [`Resizer`]: struct.Resizer.html
+    pub fn new() -> Self {
+        Resizer::default()
+    }
+
+    /// Sets the minimum number of elements of the group (at least
+    /// `1`), which is launched if it has less.
+    ///
+    /// # Arguments
+    ///
+    /// * `lower` - The minimum number of elements.
+    pub fn lower(mut self, lower: usize) -> Self {
+        self.lower = lower.max(1);
+        self
+    }
+
+    /// Sets the maximum number of elements of the group (at least
+    /// its minimum number of elements).
+    ///
+    /// # Arguments
+    ///
+    /// * `upper` - The maximum number of elements.
+    pub fn upper(mut self, upper: usize) -> Self {
+        self.upper = upper;
+        self
+    }
+
+    /// Sets the interval at which the pressure on the group's
+    /// elements is sampled, and at which it is resized (at least
+    /// a millisecond).
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - How long to wait between two samples.
+    pub fn sample_every(mut self, interval: Duration) -> Self {
+        self.interval = interval.max(MIN_INTERVAL);
+        self
+    }
+
+    /// Sets the policy deciding whether an element should be
+    /// launched, given the pressure on the group's elements.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - Returns whether the group should be scaled up.
+    pub fn scale_up_when<F>(mut self, policy: F) -> Self
+    where
+        F: Fn(&Pressure) -> bool + Send + Sync + 'static,
+    {
+        self.scale_up = Arc::new(policy);
+        self
+    }
+
+    /// Sets the policy deciding whether an idle element should be
+    /// stopped, given the pressure on the group's elements.
+    ///
+    /// The group is only scaled down when it isn't scaled up, and
+    /// when one of its elements is idle: it stops once it is done
+    /// handling the messages it was sent in the meantime.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - Returns whether the group should be scaled
+    ///     down.
+    pub fn scale_down_when<F>(mut self, policy: F) -> Self
+    where
+        F: Fn(&Pressure) -> bool + Send + Sync + 'static,
+    {
+        self.scale_down = Arc::new(policy);
+        self
+    }
+
+    /// Returns the minimum and maximum numbers of elements of the
+    /// group.
+    pub fn bounds(&self) -> (usize, usize) {
+        (self.lower, self.upper.max(self.lower))
+    }
+
+    /// Returns the interval at which the pressure on the group's
+    /// elements is sampled.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    // Returns how the group should be resized given the pressure
+    // on its elements, if it should.
+    pub(crate) fn resize(&self, pressure: &Pressure) -> Option<Resize> {
+        let (lower, upper) = self.bounds();
+        if pressure.elems < lower {
+            Some(Resize::Up(lower))
+        } else if pressure.elems > upper {
+            // The surplus is stopped one element at a time, like
+            // when the group is scaled down.
+            (pressure.idle > 0).then_some(Resize::Down)
+        } else if pressure.elems < upper && (self.scale_up)(pressure) {
+            Some(Resize::Up(pressure.elems + 1))
+        } else if pressure.elems > lower && pressure.idle > 0 && (self.scale_down)(pressure) {
+            Some(Resize::Down)
+        } else {
+            None
+        }
+    }
+}
+
+impl Pressure {
+    // Samples the pressure given the length of the mailboxes of
+    // the group's elements and whether they are idle, along with
+    // the number of elements that weren't launched yet.
+    pub(crate) fn sample(elems: impl Iterator<Item = (usize, bool)>, queued: usize) -> Self {
+        let mut pressure = Pressure {
+            elems: queued,
+            idle: 0,
+            avg_mailbox: 0,
+            max_mailbox: 0,
+        };
+
+        let mut total = 0;
+        for (mailbox_len, idle) in elems {
+            pressure.elems += 1;
+            pressure.idle += idle as usize;
+            pressure.max_mailbox = pressure.max_mailbox.max(mailbox_len);
+            total += mailbox_len;
+        }
+
+        pressure.avg_mailbox = total.checked_div(pressure.elems).unwrap_or(0);
+
+        pressure
+    }
+}
+
+impl Default for Resizer {
+    fn default() -> Self {
+        Resizer {
+            lower: DEFAULT_LOWER,
+            upper: DEFAULT_UPPER,
+            interval: DEFAULT_INTERVAL,
+            scale_up: Arc::new(|pressure| pressure.avg_mailbox > DEFAULT_PRESSURE),
+            scale_down: Arc::new(|pressure| pressure.idle > 1 && pressure.max_mailbox == 0),
+        }
+    }
+}
+
+impl Debug for Resizer {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Resizer")
+            .field("lower", &self.lower)
+            .field("upper", &self.upper)
+            .field("interval", &self.interval)
+            .finish()
+    }
+}
@@ -0,0 +1,123 @@
+//!
+//! How the routed messages of a children group (sent using
+//! [`ChildrenRef::tell_one`] or [`ChildrenRef::ask_one`]) are
+//! routed to its warm elements, which can be changed while the
+//! group is running using [`ChildrenRef::set_routing`].
+//!
+//! [`ChildrenRef::tell_one`]: ../children_ref/struct.ChildrenRef.html#method.tell_one
+//! [`ChildrenRef::ask_one`]: ../children_ref/struct.ChildrenRef.html#method.ask_one
+//! [`ChildrenRef::set_routing`]: ../children_ref/struct.ChildrenRef.html#method.set_routing
+use crate::context::BastionId;
+use crate::message::Message;
+use std::any::Any;
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// How a children group routes the messages sent using
+/// [`ChildrenRef::tell_one`] or [`ChildrenRef::ask_one`] to its warm
+/// elements (see [`Children::with_routing`]).
+///
+/// [`ChildrenRef::tell_one`]: ../children_ref/struct.ChildrenRef.html#method.tell_one
+/// [`ChildrenRef::ask_one`]: ../children_ref/struct.ChildrenRef.html#method.ask_one
+/// [`Children::with_routing`]: ../children/struct.Children.html#method.with_routing
+pub enum Routing {
+    /// The messages are sent to each warm element in turn.
+    RoundRobin,
+    /// The messages are sent to the warm element whose mailbox
+    /// holds the fewest messages, each warm element in turn
+    /// among the ones holding as many.
+    LeastLoaded,
+    /// The messages are sent to the element with this id, or in
+    /// turn to the other ones while it isn't warm.
+    Pinned(BastionId),
+    /// The messages are sent to the warm element picked by the
+    /// key the group's key function returns for them (see
+    /// [`Children::with_routing_key`]), the messages of other
+    /// types being sent to each warm element in turn.
+    ///
+    /// Note that the same key only picks the same element while
+    /// the group's warm elements don't change.
+    ///
+    /// [`Children::with_routing_key`]: ../children/struct.Children.html#method.with_routing_key
+    HashBy,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// The reasons why [`ChildrenRef::set_routing`] can fail.
+///
+/// [`ChildrenRef::set_routing`]: ../children_ref/struct.ChildrenRef.html#method.set_routing
+pub enum RoutingError {
+    /// The group is routing by key while it wasn't built with a
+    /// key function (see [`Children::with_routing_key`]).
+    ///
+    /// [`Children::with_routing_key`]: ../children/struct.Children.html#method.with_routing_key
+    MissingKey,
+    /// The element to pin the routed messages to isn't (or isn't
+    /// anymore) part of the children group.
+    UnknownElement(BastionId),
+    /// The children group stopped.
+    Stopped,
+}
+
+type KeyFn = Arc<dyn Fn(&dyn Any) -> Option<u64> + Send + Sync>;
+
+#[derive(Clone)]
+// The key function of a group routing by key, returning `None`
+// for the messages of other types.
+pub(crate) struct RoutingKey(KeyFn);
+
+impl Routing {
+    // The snake-cased name of the mode, as exported by the tree
+    // snapshots.
+    #[cfg(feature = "introspection")]
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Routing::RoundRobin => "round_robin",
+            Routing::LeastLoaded => "least_loaded",
+            Routing::Pinned(_) => "pinned",
+            Routing::HashBy => "hash_by",
+        }
+    }
+}
+
+impl Default for Routing {
+    fn default() -> Self {
+        Routing::RoundRobin
+    }
+}
+
+impl RoutingKey {
+    pub(crate) fn new<M, F>(key: F) -> Self
+    where
+        M: Message,
+        F: Fn(&M) -> u64 + Send + Sync + 'static,
+    {
+        RoutingKey(Arc::new(move |msg: &dyn Any| {
+            msg.downcast_ref::<M>().map(&key)
+        }))
+    }
+
+    pub(crate) fn key(&self, msg: &dyn Any) -> Option<u64> {
+        (self.0)(msg)
+    }
+}
+
+impl Display for RoutingError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            RoutingError::MissingKey => fmt.write_str("the group doesn't have a key function"),
+            RoutingError::UnknownElement(id) => write!(fmt, "unknown element: {}", id),
+            RoutingError::Stopped => fmt.write_str("the children group stopped"),
+        }
+    }
+}
+
+impl Error for RoutingError {}
+
+impl Debug for RoutingKey {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("RoutingKey").finish()
+    }
+}
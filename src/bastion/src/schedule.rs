@@ -0,0 +1,160 @@
+//!
+//! Messages sent to an element once a delay elapsed (see
+//! [`ChildRef::tell_after`]) or periodically (see
+//! [`BastionContext::schedule_periodic`]), woken by the system's
+//! reactor.
+//!
+//! [`ChildRef::tell_after`]: ../child_ref/struct.ChildRef.html#method.tell_after
+//! [`BastionContext::schedule_periodic`]: ../context/struct.BastionContext.html#method.schedule_periodic
+use crate::child_ref::ChildRef;
+use crate::context::CancellationToken;
+use crate::dead_letters::{self, DeadLetterReason};
+use crate::envelope::Envelope;
+use crate::errors::SendErrorKind;
+use crate::message::{BastionMessage, Message};
+use crate::reactor::{self, Sleep};
+use crate::watch::Termination;
+use bastion_executor::pool::{self, ProcClass};
+use futures::future::{self, Either};
+use lightproc::proc_stack::ProcStack;
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+/// A handle to a message scheduled using [`ChildRef::tell_after`]
+/// or [`BastionContext::schedule_periodic`], allowing to cancel it.
+///
+/// Dropping the handle doesn't cancel the message.
+///
+/// [`ChildRef::tell_after`]: ../child_ref/struct.ChildRef.html#method.tell_after
+/// [`BastionContext::schedule_periodic`]: ../context/struct.BastionContext.html#method.schedule_periodic
+pub struct ScheduleHandle {
+    token: CancellationToken,
+}
+
+impl ScheduleHandle {
+    /// Cancels the scheduled message, which won't be sent anymore
+    /// (the periodic messages already sent are left in the
+    /// recipient's mailbox).
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    /// Returns whether the scheduled message was cancelled, using
+    /// [`cancel`] or because its recipient terminated.
+    ///
+    /// [`cancel`]: #method.cancel
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+}
+
+// Sends `msg` to `child` once `delay` elapsed, unless the handle is
+// cancelled or the child terminates before, in which case the
+// message is sent to the dead letters.
+pub(crate) fn tell_after<M: Message>(child: ChildRef, msg: M, delay: Duration) -> ScheduleHandle {
+    let token = CancellationToken::new();
+    let handle = ScheduleHandle {
+        token: token.clone(),
+    };
+
+    let deadline = reactor::now() + delay;
+    let scheduled = async move {
+        let terminated = future::select(token.clone(), Box::pin(stopped(&child)));
+        match future::select(Sleep::new(deadline), terminated).await {
+            Either::Left(_) => {
+                deliver(&child, msg);
+            }
+            Either::Right((Either::Left(_), _)) => {
+                debug!("ChildRef({}): Cancelled a delayed message.", child.id());
+            }
+            Either::Right((Either::Right(_), _)) => {
+                token.cancel();
+                bounce(&child, msg, DeadLetterReason::RecipientStopped);
+            }
+        }
+    };
+
+    pool::spawn_with_class(scheduled, ProcStack::default(), ProcClass::Timer);
+    handle
+}
+
+// Sends the messages created by `factory` to `child` every
+// `interval`, until `token` is cancelled or the child terminates.
+pub(crate) fn periodic<M, F>(
+    child: ChildRef,
+    factory: F,
+    interval: Duration,
+    token: CancellationToken,
+) -> ScheduleHandle
+where
+    M: Message,
+    F: Fn() -> M + Send + 'static,
+{
+    let handle = ScheduleHandle {
+        token: token.clone(),
+    };
+
+    let scheduled = async move {
+        // The deadlines don't drift if a message is sent late.
+        let mut deadline = reactor::now() + interval;
+        let mut watcher = child.watch();
+        loop {
+            let terminated = future::select(token.clone(), &mut watcher);
+            match future::select(Sleep::new(deadline), terminated).await {
+                Either::Left(_) => {
+                    if !deliver(&child, factory()) {
+                        token.cancel();
+                        return;
+                    }
+                }
+                Either::Right((Either::Left(_), _)) => {
+                    debug!("ChildRef({}): Cancelled a periodic message.", child.id());
+                    return;
+                }
+                Either::Right((Either::Right(_), _)) => {
+                    token.cancel();
+                    return;
+                }
+            }
+
+            deadline += interval;
+        }
+    };
+
+    pool::spawn_with_class(scheduled, ProcStack::default(), ProcClass::Timer);
+    handle
+}
+
+// Resolves once the child stopped or was killed. A child that
+// faulted might be restarted, its next incarnation being reached
+// using the same sender, so whether the message can be delivered
+// is only known once it should be.
+async fn stopped(child: &ChildRef) {
+    if let Termination::Faulted(_) = child.watch().await {
+        future::pending::<()>().await;
+    }
+}
+
+// Returns whether the child can still receive messages.
+fn deliver<M: Message>(child: &ChildRef, msg: M) -> bool {
+    match child.tell_unchecked(msg) {
+        Ok(()) => true,
+        Err(err) if err.kind() == SendErrorKind::MailboxFull => {
+            bounce(child, err.into_inner_msg(), DeadLetterReason::MailboxFull);
+            true
+        }
+        Err(err) => {
+            bounce(
+                child,
+                err.into_inner_msg(),
+                DeadLetterReason::RecipientStopped,
+            );
+            false
+        }
+    }
+}
+
+fn bounce<M: Message>(child: &ChildRef, msg: M, reason: DeadLetterReason) {
+    let env = Envelope::from_dead_letters(BastionMessage::tell(msg));
+    dead_letters::bounce(env, Some(child.id()), reason);
+}
@@ -0,0 +1,296 @@
+//!
+//! The handlers of the termination signals installed by
+//! [`Bastion::handle_signals`], which stop the system gracefully.
+//!
+//! [`Bastion::handle_signals`]: ../struct.Bastion.html#method.handle_signals
+use crate::bastion::Bastion;
+use crate::system::SYSTEM;
+use crate::tree;
+use bastion_executor::run::run;
+use futures::future;
+use lightproc::proc_stack::ProcStack;
+use std::time::{Duration, Instant};
+
+// Shorter than the grace period usually given by container
+// runtimes before they kill a stopping process.
+const DEFAULT_DEADLINE: Duration = Duration::from_secs(25);
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// How the system is stopped once a termination signal is received
+/// (see [`Bastion::handle_signals`]).
+///
+/// [`Bastion::handle_signals`]: struct.Bastion.html#method.handle_signals
+pub struct ShutdownConfig {
+    deadline: Duration,
+}
+
+impl ShutdownConfig {
+    /// Creates a new configuration, whose deadline is 25 seconds.
+    pub fn new() -> Self {
+        ShutdownConfig::default()
+    }
+
+    /// Sets how long the children groups are given to handle the
+    /// messages left in their elements' mailboxes (and the system
+    /// to stop) before they are killed.
+    ///
+    /// # Arguments
+    ///
+    /// * `deadline` - How long to wait for the system to stop
+    ///     once a signal was received.
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    /// Returns how long the system is given to stop once a signal
+    /// was received.
+    pub fn deadline(&self) -> Duration {
+        self.deadline
+    }
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        ShutdownConfig {
+            deadline: DEFAULT_DEADLINE,
+        }
+    }
+}
+
+pub(crate) fn install(config: ShutdownConfig) {
+    #[cfg(unix)]
+    unix::install(config);
+    #[cfg(not(unix))]
+    {
+        let _ = config;
+        warn!("Bastion: Not handling the signals: they are only supported on Unix.");
+    }
+}
+
+// Makes the handlers stop the new system gracefully once a signal
+// is received, even if they already stopped (or killed) a previous
+// one.
+pub(crate) fn reset() {
+    #[cfg(unix)]
+    unix::reset();
+}
+
+// Stops the children groups within the deadline, their elements
+// handling the messages left in their mailboxes, and then the
+// system (killing it if it didn't stop in time).
+fn shutdown(config: ShutdownConfig) {
+    if SYSTEM.check_running().is_err() {
+        return;
+    }
+
+    info!("Bastion: Stopping within {:?}.", config.deadline);
+    let deadline = Instant::now() + config.deadline;
+    let stopping = tree::groups()
+        .into_iter()
+        .map(|children| children.stop_with_timeout(config.deadline));
+    run(future::join_all(stopping), ProcStack::default());
+
+    Bastion::stop();
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    if !SYSTEM.wait_until_stopped_timeout(remaining) {
+        warn!("Bastion: Killing the system: it didn't stop in time.");
+        Bastion::kill();
+    }
+}
+
+// NOTE: the handlers only write to a pipe, whose other end is read
+//      by a thread stopping the system (the handlers can only call
+//      async-signal-safe functions).
+#[cfg(unix)]
+#[allow(unsafe_code)]
+mod unix {
+    use super::ShutdownConfig;
+    use crate::bastion::Bastion;
+    use lazy_static::lazy_static;
+    use libc::c_int;
+    use std::io;
+    use std::mem;
+    use std::ptr;
+    use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+    use std::sync::Mutex;
+    use std::thread;
+
+    const SIGNALS: [c_int; 2] = [libc::SIGTERM, libc::SIGINT];
+
+    // The end of the pipe the handlers write to, once installed.
+    static PIPE: AtomicI32 = AtomicI32::new(-1);
+    // Whether a signal was received since the system was
+    // initialized, the next one then killing it.
+    static STOPPING: AtomicBool = AtomicBool::new(false);
+
+    lazy_static! {
+        // The configuration used once a signal is received, which
+        // can be replaced by installing the handlers again.
+        static ref CONFIG: Mutex<ShutdownConfig> = Mutex::new(ShutdownConfig::default());
+    }
+
+    pub(super) fn install(config: ShutdownConfig) {
+        // FIXME: panics?
+        *CONFIG.lock().unwrap() = config;
+        if PIPE.load(Ordering::SeqCst) >= 0 {
+            debug!("Bastion: The signals are already handled.");
+            return;
+        }
+
+        let mut fds = [0 as c_int; 2];
+        if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+            warn!("Bastion: Not handling the signals: couldn't create a pipe.");
+            return;
+        }
+
+        let [read, write] = fds;
+        let spawned = thread::Builder::new()
+            .name("bastion-signals".to_string())
+            .spawn(move || wait(read));
+        if spawned.is_err() {
+            warn!("Bastion: Not handling the signals: couldn't spawn a thread.");
+            unsafe {
+                libc::close(read);
+                libc::close(write);
+            }
+
+            return;
+        }
+
+        // The pipe is only used once its other end is read.
+        PIPE.store(write, Ordering::SeqCst);
+
+        // The system calls interrupted by the signals are restarted
+        // instead of failing with `EINTR`.
+        let mut action: libc::sigaction = unsafe { mem::zeroed() };
+        action.sa_sigaction = on_signal as extern "C" fn(c_int) as libc::sighandler_t;
+        action.sa_flags = libc::SA_RESTART;
+        unsafe {
+            libc::sigemptyset(&mut action.sa_mask);
+        }
+
+        for signal in SIGNALS.iter() {
+            if unsafe { libc::sigaction(*signal, &action, ptr::null_mut()) } != 0 {
+                let err = io::Error::last_os_error();
+                warn!("Bastion: Not handling signal {}: {}.", signal, err);
+            }
+        }
+
+        debug!("Bastion: Handling the signals.");
+    }
+
+    pub(super) fn reset() {
+        STOPPING.store(false, Ordering::SeqCst);
+    }
+
+    extern "C" fn on_signal(_: c_int) {
+        // The interrupted code could be about to read `errno`, which
+        // `write` might overwrite.
+        let errno = unsafe { errno() };
+        let saved = if errno.is_null() {
+            None
+        } else {
+            Some(unsafe { *errno })
+        };
+
+        let byte = 1u8;
+        unsafe {
+            libc::write(
+                PIPE.load(Ordering::SeqCst),
+                &byte as *const u8 as *const libc::c_void,
+                1,
+            );
+        }
+
+        if let Some(saved) = saved {
+            unsafe {
+                *errno = saved;
+            }
+        }
+    }
+
+    // Returns the location of the calling thread's `errno`, or null
+    // on the platforms where it isn't known.
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "emscripten",
+        target_os = "fuchsia",
+        target_os = "redox",
+        target_os = "dragonfly"
+    ))]
+    unsafe fn errno() -> *mut c_int {
+        libc::__errno_location()
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+    unsafe fn errno() -> *mut c_int {
+        libc::__error()
+    }
+
+    #[cfg(any(target_os = "android", target_os = "netbsd", target_os = "openbsd"))]
+    unsafe fn errno() -> *mut c_int {
+        libc::__errno()
+    }
+
+    #[cfg(any(target_os = "solaris", target_os = "illumos"))]
+    unsafe fn errno() -> *mut c_int {
+        libc::___errno()
+    }
+
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "emscripten",
+        target_os = "fuchsia",
+        target_os = "redox",
+        target_os = "dragonfly",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "android",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_os = "solaris",
+        target_os = "illumos"
+    )))]
+    unsafe fn errno() -> *mut c_int {
+        ptr::null_mut()
+    }
+
+    // Stops the system gracefully once a signal is received, and
+    // kills it if another one is received meanwhile.
+    fn wait(read: c_int) {
+        loop {
+            let mut byte = 0u8;
+            let len = unsafe { libc::read(read, &mut byte as *mut u8 as *mut libc::c_void, 1) };
+            if len < 0 {
+                let err = io::Error::last_os_error();
+                // Interrupted by a signal, which is retried.
+                if err.raw_os_error() == Some(libc::EINTR) {
+                    continue;
+                }
+
+                warn!("Bastion: Not handling the signals anymore: {}.", err);
+                return;
+            } else if len == 0 {
+                return;
+            }
+
+            if STOPPING.swap(true, Ordering::SeqCst) {
+                warn!("Bastion: Killing the system: received another signal.");
+                Bastion::kill();
+                continue;
+            }
+
+            // FIXME: panics?
+            let config = CONFIG.lock().unwrap().clone();
+            let spawned = thread::Builder::new()
+                .name("bastion-shutdown".to_string())
+                .spawn(move || super::shutdown(config));
+            if spawned.is_err() {
+                warn!("Bastion: Stopping the system: couldn't spawn a thread.");
+                Bastion::stop();
+            }
+        }
+    }
+}
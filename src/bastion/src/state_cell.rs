@@ -0,0 +1,205 @@
+//!
+//! Shared state owned by the system, outliving the children groups
+//! but not the system itself (see [`Bastion::state_cell`]).
+//!
+//! Contrary to statics, the state of a cell is constructed lazily
+//! for the system it was created for and dropped while this
+//! system stops, once all its children groups stopped, so that a
+//! system initialized again after it stopped starts with a fresh
+//! state.
+//!
+//! [`Bastion::state_cell`]: ../struct.Bastion.html#method.state_cell
+use std::any::{type_name, Any, TypeId};
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+type Value = Arc<dyn Any + Send + Sync>;
+type Cells = Vec<(TypeId, Arc<Mutex<Slot>>)>;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// The reasons why the state of a [`StateCell`] couldn't be
+/// accessed.
+///
+/// [`StateCell`]: struct.StateCell.html
+pub enum StateError {
+    /// No state cell of this type was created for the current
+    /// system (see [`Bastion::state_cell`]).
+    ///
+    /// [`Bastion::state_cell`]: ../struct.Bastion.html#method.state_cell
+    Unknown,
+    /// The system the state cell was created for stopped, and
+    /// its state was dropped.
+    Dropped,
+}
+
+/// A handle to a state of type `T` owned by the system, created
+/// using [`Bastion::state_cell`].
+///
+/// The state can be retrieved using [`get`], or from the elements
+/// of the system's children groups using
+/// [`BastionContext::global`].
+///
+/// [`Bastion::state_cell`]: ../struct.Bastion.html#method.state_cell
+/// [`get`]: #method.get
+/// [`BastionContext::global`]: ../context/struct.BastionContext.html#method.global
+pub struct StateCell<T> {
+    states: Arc<States>,
+    _state: PhantomData<fn() -> T>,
+}
+
+#[derive(Debug)]
+// The state cells of a system, in the order they were created,
+// until they are dropped.
+pub(crate) struct States {
+    cells: Mutex<Option<Cells>>,
+}
+
+// The state of a cell, or how to construct it if it wasn't
+// accessed yet.
+struct Slot {
+    init: Option<Box<dyn FnOnce() -> Value + Send>>,
+    value: Option<Value>,
+}
+
+impl<T: Send + Sync + 'static> StateCell<T> {
+    pub(crate) fn new(states: Arc<States>) -> Self {
+        StateCell {
+            states,
+            _state: PhantomData,
+        }
+    }
+
+    /// Returns the state of this cell, constructing it if it is
+    /// accessed for the first time.
+    ///
+    /// This method returns the state if it succeeded, or
+    /// [`StateError::Dropped`] if the system it was created for
+    /// stopped.
+    ///
+    /// Note that the state is only dropped once all the `Arc`s
+    /// returned are, so they shouldn't be kept after the elements
+    /// holding them stopped.
+    ///
+    /// [`StateError::Dropped`]: enum.StateError.html#variant.Dropped
+    pub fn get(&self) -> Result<Arc<T>, StateError> {
+        self.states.get()
+    }
+}
+
+impl States {
+    pub(crate) fn new() -> Self {
+        States {
+            cells: Mutex::new(Some(Vec::new())),
+        }
+    }
+
+    // Creates the cell of `T` if there isn't any yet (`init` being
+    // dropped otherwise).
+    pub(crate) fn register<T, I>(&self, init: I)
+    where
+        T: Send + Sync + 'static,
+        I: FnOnce() -> T + Send + 'static,
+    {
+        // FIXME: panics?
+        let mut cells = self.cells.lock().unwrap();
+        let cells = match &mut *cells {
+            Some(cells) => cells,
+            None => return,
+        };
+
+        let id = TypeId::of::<T>();
+        if cells.iter().any(|(cell, _)| *cell == id) {
+            trace!("States: {} is already registered.", type_name::<T>());
+            return;
+        }
+
+        debug!("States: Registering {}.", type_name::<T>());
+        let slot = Slot {
+            init: Some(Box::new(move || Arc::new(init()) as Value)),
+            value: None,
+        };
+        cells.push((id, Arc::new(Mutex::new(slot))));
+    }
+
+    pub(crate) fn get<T: Send + Sync + 'static>(&self) -> Result<Arc<T>, StateError> {
+        let slot = {
+            // FIXME: panics?
+            let cells = self.cells.lock().unwrap();
+            let cells = cells.as_ref().ok_or(StateError::Dropped)?;
+            let id = TypeId::of::<T>();
+            match cells.iter().find(|(cell, _)| *cell == id) {
+                Some((_, slot)) => slot.clone(),
+                None => return Err(StateError::Unknown),
+            }
+        };
+
+        // The state is constructed while holding its slot, so that
+        // it is only constructed once.
+        let mut slot = slot.lock().unwrap();
+        if slot.value.is_none() {
+            match slot.init.take() {
+                Some(init) => {
+                    debug!("States: Constructing {}.", type_name::<T>());
+                    slot.value = Some(init());
+                }
+                // The cells were dropped after the slot was retrieved.
+                None => return Err(StateError::Dropped),
+            }
+        }
+
+        let value = slot.value.clone().unwrap();
+        Ok(value.downcast().unwrap())
+    }
+
+    // Drops the states, in the reverse order their cells were
+    // created.
+    pub(crate) fn drop_all(&self) {
+        // FIXME: panics?
+        let cells = self.cells.lock().unwrap().take();
+        for (_, slot) in cells.into_iter().flatten().rev() {
+            let mut slot = slot.lock().unwrap();
+            slot.init.take();
+            slot.value.take();
+        }
+    }
+}
+
+impl<T> Clone for StateCell<T> {
+    fn clone(&self) -> Self {
+        StateCell {
+            states: self.states.clone(),
+            _state: PhantomData,
+        }
+    }
+}
+
+impl<T> Debug for StateCell<T> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("StateCell")
+            .field("state", &type_name::<T>())
+            .finish()
+    }
+}
+
+impl Debug for Slot {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Slot")
+            .field("constructed", &self.value.is_some())
+            .finish()
+    }
+}
+
+impl Display for StateError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            StateError::Unknown => fmt.write_str("no state cell of this type was created"),
+            StateError::Dropped => {
+                fmt.write_str("the state was dropped because the system stopped")
+            }
+        }
+    }
+}
+
+impl Error for StateError {}
@@ -1,29 +1,58 @@
 //!
 //! Supervisors enable users to supervise a subtree of children
 //! or other supervisor trees under themselves.
+#[cfg(feature = "supervision")]
+use crate::backoff::Backoff;
+#[cfg(feature = "supervision")]
+use crate::bastion::Bastion;
 use crate::broadcast::{Broadcast, Parent, Sender};
 use crate::callbacks::Callbacks;
-use crate::children::Children;
-use crate::children_ref::ChildrenRef;
-use crate::context::{BastionId, ContextState};
+use crate::children::{Children, DEFAULT_PRE_START_WATERMARK};
+use crate::children_ref::{ChildrenRef, GroupPolicies};
+use crate::config::Config;
+#[cfg(feature = "supervision")]
+use crate::config::RootAction;
+use crate::context::{BastionId, ContextState, NIL_ID};
 use crate::envelope::Envelope;
+use crate::errors::{BuilderError, ExecError, SendError, ShutdownError};
+#[cfg(feature = "supervision")]
+use crate::event::Event;
+use crate::event::{FaultReason, FaultReports};
+use crate::flight_recorder::FlightRecord;
+use crate::lifeline::{Lifeline, ORPHAN_TIMEOUT};
+use crate::mailbox::OverflowPolicy;
 use crate::message::{BastionMessage, Deployment, Message};
 use crate::path::{BastionPath, BastionPathElement};
-use bastion_executor::pool;
+use crate::preset::SupervisionPreset;
+use crate::provenance::Provenance;
+use crate::system::SYSTEM;
+use crate::tree;
+use crate::typed::TypedChildrenRef;
+use bastion_executor::deterministic;
+use bastion_executor::pool::{self, ProcClass};
 use futures::prelude::*;
 use futures::stream::FuturesOrdered;
 use futures::{pending, poll};
 use futures_timer::Delay;
-use fxhash::FxHashMap;
+use fxhash::{FxHashMap, FxHashSet};
 use lightproc::prelude::*;
 use log::Level;
 use qutex::Qutex;
 use std::cmp::{Eq, PartialEq};
+#[cfg(feature = "supervision")]
+use std::collections::VecDeque;
 use std::ops::Range;
 use std::pin::Pin;
-use std::sync::Arc;
-use std::task::Poll;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
 use std::time::Duration;
+#[cfg(feature = "supervision")]
+use std::time::Instant;
+
+// How long the supervisor of the system's utility groups waits
+// before restarting their elements, multiplied by the number of
+// times they were restarted.
+const UTILITIES_BACKOFF: Duration = Duration::from_secs(1);
 
 #[derive(Debug)]
 /// A supervisor that can supervise both [`Children`] and other
@@ -75,9 +104,25 @@ pub struct Supervisor {
     tracked_groups: FxHashMap<BastionId, Vec<TrackedChildState>>,
     // Hold the insertion order of the childs.
     tracked_groups_order: FxHashMap<BastionId, usize>,
+    // The strategies set by the children groups to restart their
+    // own elements with.
+    group_strategies: FxHashMap<BastionId, GroupStrategy>,
+    // The children groups delaying the restarts of their elements
+    // with a back-off, which replaces the delay of the restart
+    // strategy.
+    #[cfg(feature = "supervision")]
+    backed_off: FxHashSet<BastionId>,
     // The currently launched supervised children and supervisors.
     // The last value is the amount of times a given actor has restarted.
     launched: FxHashMap<BastionId, (usize, RecoverableHandle<Supervised>)>,
+    // The supervised children groups (as opposed to supervisors).
+    groups: FxHashSet<BastionId>,
+    // The lifelines of the supervised children groups, cut once
+    // a group's task was dropped.
+    lifelines: FxHashMap<BastionId, Lifeline>,
+    // The children groups stopped when the system was degraded,
+    // deployed again once it recovers.
+    degraded: Vec<Children>,
     // Supervised children and supervisors that are stopped.
     // This is used when resetting or recovering when the
     // supervision strategy is not "one-for-one".
@@ -86,14 +131,48 @@ pub struct Supervisor {
     // This is used when resetting only.
     killed: FxHashMap<BastionId, Supervised>,
     strategy: SupervisionStrategy,
-    restart_strategy: RestartStrategy,
+    // The restart strategy and window are inherited by the
+    // supervisors deployed under this one.
+    restart_strategy: Inherited<RestartStrategy>,
+    // The maximum number of restarts allowed during a window,
+    // after which the supervisor gives up.
+    #[cfg(feature = "supervision")]
+    restart_window: Inherited<Option<(usize, Duration)>>,
+    // The back-off delaying the restarts of the elements of the
+    // children groups deployed under this supervisor.
+    #[cfg(feature = "supervision")]
+    restart_backoff: Inherited<Option<Backoff>>,
+    // The watermark of the children groups deployed under this
+    // supervisor (or the supervisors deployed under it).
+    default_pre_start_watermark: Inherited<usize>,
+    // The mailbox capacity and overflow policy of the children
+    // groups created under this supervisor (or the supervisors
+    // created under it), shared with its `SupervisorRef`s.
+    default_mailbox: DefaultMailbox,
+    // The name of the preset applied to the supervisor, recorded
+    // in the policies of the groups it supervises.
+    preset: Inherited<Option<&'static str>>,
+    // The times of the restarts that happened during the last
+    // window.
+    #[cfg(feature = "supervision")]
+    restart_times: VecDeque<Instant>,
+    // Reports the faults of the supervised children groups.
+    fault_reports: FaultReports,
     // The callbacks called at the supervisor's different
     // lifecycle events.
     callbacks: Callbacks,
+    // What the system supervisor does instead of giving up once
+    // its restart window is exceeded.
+    #[cfg(feature = "supervision")]
+    on_exhaustion: Option<RootAction>,
     // Whether this supervisor was started by the system (in
     // which case, users shouldn't be able to get a reference
     // to it).
     is_system_supervisor: bool,
+    // Whether this supervisor is the one of the system's utility
+    // groups (in which case it is part of the `system` subtree of
+    // the tree snapshots).
+    is_utilities_supervisor: bool,
     // Messages that were received before the supervisor was
     // started. Those will be "replayed" once a start message
     // is received.
@@ -103,6 +182,18 @@ pub struct Supervisor {
     subtree_restarts: usize,
     // Store the maximum acceptable restarts for the supervisor.
     subtree_restarts_limit: usize,
+    // The error the first children group (added using `children`)
+    // or supervisor that couldn't be deployed failed with, which
+    // creating this supervisor fails with.
+    builder_error: Option<BuilderError>,
+}
+
+#[derive(Debug, Clone)]
+// A setting inherited from the parent supervisor, unless it was
+// explicitly set.
+pub(crate) struct Inherited<T> {
+    value: T,
+    explicit: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -120,9 +211,21 @@ enum RestartedElement {
 
 #[derive(Debug)]
 enum ActorSearchMethod {
-    OneActor { id: BastionId, parent_id: BastionId },
-    FromActor { id: BastionId, parent_id: BastionId },
+    OneActor {
+        id: BastionId,
+        parent_id: BastionId,
+    },
+    FromActor {
+        id: BastionId,
+        parent_id: BastionId,
+    },
     All,
+    // Only searches the elements of the faulted element's group.
+    Group {
+        id: BastionId,
+        parent_id: BastionId,
+        strategy: SupervisionStrategy,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -134,6 +237,7 @@ pub struct SupervisorRef {
     id: BastionId,
     sender: Sender,
     path: Arc<BastionPath>,
+    default_mailbox: DefaultMailbox,
 }
 
 #[derive(Debug, Clone)]
@@ -159,16 +263,32 @@ pub enum SupervisionStrategy {
     /// group and all the ones that were added to the
     /// supervisor after it are restarted (even those which
     /// were stopped) in the same order they were added to
-    /// the supervisor.
+    /// the supervisor. Restarted groups keep their position,
+    /// so the same groups are restarted every time one of
+    /// them dies.
     RestForOne,
 }
 
+// The strategy a children group restarts its elements with, if it
+// set one, shared between the group and its supervisor.
+pub(crate) type GroupStrategy = Arc<Mutex<Option<SupervisionStrategy>>>;
+
+// The default mailbox of the children groups of a supervisor, read
+// when they are created (as their mailboxes are created along with
+// their elements, before they are deployed).
+pub(crate) type DefaultMailbox = Arc<Mutex<Inherited<Option<(usize, OverflowPolicy)>>>>;
+
 #[derive(Debug)]
 enum Supervised {
-    Supervisor(Supervisor),
-    Children(Children),
+    Supervisor(Box<Supervisor>),
+    Children(Box<Children>),
 }
 
+#[derive(Debug)]
+// The handle of a supervised object's own proc, cancelled along
+// with the proc that launched it.
+struct Cancelling<R>(RecoverableHandle<R>);
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 /// The restart policy which is used during restoring failed
 /// actors by the supervisor.
@@ -231,40 +351,106 @@ impl Supervisor {
         let order = Vec::new();
         let tracked_groups = FxHashMap::default();
         let tracked_groups_order = FxHashMap::default();
+        let group_strategies = FxHashMap::default();
+        #[cfg(feature = "supervision")]
+        let backed_off = FxHashSet::default();
         let launched = FxHashMap::default();
+        let groups = FxHashSet::default();
+        let lifelines = FxHashMap::default();
+        let degraded = Vec::new();
         let stopped = FxHashMap::default();
         let killed = FxHashMap::default();
         let strategy = SupervisionStrategy::default();
-        let restart_strategy = RestartStrategy::default();
+        let restart_strategy = Inherited::new(RestartStrategy::default());
+        #[cfg(feature = "supervision")]
+        let restart_window = Inherited::new(None);
+        #[cfg(feature = "supervision")]
+        let restart_backoff = Inherited::new(None);
+        let default_pre_start_watermark = Inherited::new(DEFAULT_PRE_START_WATERMARK);
+        let mut default_mailbox = Inherited::new(None);
+        if let Some(parent) = bcast.parent().clone().into_supervisor() {
+            default_mailbox.inherit(&parent.default_mailbox());
+        }
+        let default_mailbox = Arc::new(Mutex::new(default_mailbox));
+        let preset = Inherited::new(None);
+        #[cfg(feature = "supervision")]
+        let restart_times = VecDeque::new();
+        let fault_reports = FaultReports::new();
         let callbacks = Callbacks::new();
+        #[cfg(feature = "supervision")]
+        let on_exhaustion = None;
         let is_system_supervisor = false;
+        let is_utilities_supervisor = false;
         let pre_start_msgs = Vec::new();
         let started = false;
         let subtree_restarts = 0;
         let subtree_restarts_limit = 3;
+        let builder_error = None;
 
         Supervisor {
             bcast,
             order,
             tracked_groups,
             tracked_groups_order,
+            group_strategies,
+            #[cfg(feature = "supervision")]
+            backed_off,
             launched,
+            groups,
+            lifelines,
+            degraded,
             stopped,
             killed,
             strategy,
             restart_strategy,
+            #[cfg(feature = "supervision")]
+            restart_window,
+            #[cfg(feature = "supervision")]
+            restart_backoff,
+            default_pre_start_watermark,
+            default_mailbox,
+            preset,
+            #[cfg(feature = "supervision")]
+            restart_times,
+            fault_reports,
             callbacks,
+            #[cfg(feature = "supervision")]
+            on_exhaustion,
             is_system_supervisor,
+            is_utilities_supervisor,
             pre_start_msgs,
             started,
             subtree_restarts,
             subtree_restarts_limit,
+            builder_error,
         }
     }
 
-    pub(crate) fn system(bcast: Broadcast) -> Self {
+    #[cfg_attr(not(feature = "supervision"), allow(unused_variables))]
+    pub(crate) fn system(bcast: Broadcast, config: &Config) -> Self {
         let mut supervisor = Supervisor::new(bcast);
         supervisor.is_system_supervisor = true;
+        #[cfg(feature = "supervision")]
+        {
+            supervisor.restart_window.set(config.root_restart_window());
+            supervisor.on_exhaustion = config.root_exhaustion().cloned();
+        }
+
+        supervisor
+    }
+
+    // The supervisor of the system's utility groups (the dead
+    // letters and the reactor), which always restarts their faulted
+    // elements, but less and less eagerly so that a faulting utility
+    // doesn't restart in a loop.
+    pub(crate) fn utilities(bcast: Broadcast) -> Self {
+        let strategy = ActorRestartStrategy::LinearBackOff {
+            timeout: UTILITIES_BACKOFF,
+        };
+
+        let mut supervisor = Supervisor::new(bcast)
+            .with_restart_strategy(RestartStrategy::new(RestartPolicy::Always, strategy));
+        supervisor.is_utilities_supervisor = true;
 
         supervisor
     }
@@ -378,8 +564,9 @@ impl Supervisor {
         let id = self.bcast.id().clone();
         let sender = self.bcast.sender().clone();
         let path = self.bcast.path().clone();
+        let default_mailbox = self.default_mailbox.clone();
 
-        SupervisorRef::new(id, sender, path)
+        SupervisorRef::new(id, sender, path, default_mailbox)
     }
 
     /// Creates a new supervisor, passes it through the specified
@@ -418,7 +605,7 @@ impl Supervisor {
     ///
     /// [`SupervisorRef`]: ../struct.SupervisorRef.html
     /// [`supervisor_ref`]: #method.supervisor_ref
-    pub fn supervisor<S>(self, init: S) -> Self
+    pub fn supervisor<S>(mut self, init: S) -> Self
     where
         S: FnOnce(Supervisor) -> Supervisor,
     {
@@ -432,8 +619,11 @@ impl Supervisor {
             bcast.id()
         );
         let supervisor = Supervisor::new(bcast);
-        let supervisor = init(supervisor);
+        let mut supervisor = init(supervisor);
         debug!("Supervisor({}): Initialized.", supervisor.id());
+        if let Some(err) = supervisor.builder_error.take() {
+            self.failed(err);
+        }
 
         debug!(
             "Supervisor({}): Deploying Supervisor({}).",
@@ -498,8 +688,11 @@ impl Supervisor {
             bcast.id()
         );
         let supervisor = Supervisor::new(bcast);
-        let supervisor = init(supervisor);
+        let mut supervisor = init(supervisor);
         debug!("Supervisor({}): Initialized.", supervisor.id());
+        if let Some(err) = supervisor.builder_error.take() {
+            self.failed(err);
+        }
         let supervisor_ref = supervisor.as_ref();
 
         debug!(
@@ -521,6 +714,14 @@ impl Supervisor {
     /// and need to get a [`ChildrenRef`] referencing the newly
     /// created supervisor, use the [`children`] method instead.
     ///
+    /// The group isn't deployed if some of its settings conflict
+    /// with each other (see [`BuilderConflict`]) or if its name is
+    /// already taken (see [`Children::with_name`]), in which case
+    /// creating the supervisor (using [`Bastion::supervisor`] or
+    /// [`SupervisorRef::supervisor`]) fails with the group's
+    /// [`BuilderError`], the groups that were deployed being
+    /// stopped along with it.
+    ///
     /// # Arguments
     ///
     /// * `init` - The closure taking the new `Children` as an
@@ -559,10 +760,20 @@ impl Supervisor {
     /// [`Children`]: children/struct.Children.html
     /// [`ChildrenRef`]: children/struct.ChildrenRef.html
     /// [`children_ref`]: #method.children_ref
-    pub fn children<C>(self, init: C) -> Self
+    /// [`BuilderConflict`]: ../errors/enum.BuilderConflict.html
+    /// [`Children::with_name`]: children/struct.Children.html#method.with_name
+    /// [`Bastion::supervisor`]: ../struct.Bastion.html#method.supervisor
+    /// [`SupervisorRef::supervisor`]: struct.SupervisorRef.html#method.supervisor
+    /// [`BuilderError`]: ../errors/enum.BuilderError.html
+    pub fn children<C>(mut self, init: C) -> Self
     where
         C: FnOnce(Children) -> Children,
     {
+        // The supervisor won't be created anyway.
+        if self.builder_error.is_some() {
+            return self;
+        }
+
         debug!("Supervisor({}): Creating children group.", self.id());
         let parent = Parent::supervisor(self.as_ref());
         let bcast = Broadcast::new(parent, BastionPathElement::Children(BastionId::new()));
@@ -575,9 +786,20 @@ impl Supervisor {
         let children = Children::new(bcast);
         let mut children = init(children);
         debug!("Children({}): Initialized.", children.id());
+        if let Err(err) = children.finalize().and_then(|()| children.reserve_name()) {
+            warn!(
+                "Supervisor({}): Not deploying Children({}): {}",
+                self.id(),
+                children.id(),
+                err
+            );
+            self.failed(err);
+            return self;
+        }
         // FIXME: children group elems launched without the group itself being launched
         children.register_dispatchers();
         children.launch_elems();
+        children.register();
 
         debug!(
             "Supervisor({}): Deploying Children({}).",
@@ -598,6 +820,12 @@ impl Supervisor {
     /// don't need to get a [`ChildrenRef`] referencing the newly
     /// created supervisor, use the [`children`] method instead.
     ///
+    /// This method returns a [`ChildrenRef`] referencing the newly
+    /// created children group if it succeeded, or a
+    /// [`BuilderError`] otherwise (if some of its settings conflict
+    /// with each other, see [`BuilderConflict`], or if its name is
+    /// already taken, see [`Children::with_name`]).
+    ///
     /// # Arguments
     ///
     /// * `init` - The closure taking the new `Children` as an
@@ -624,7 +852,7 @@ impl Supervisor {
     ///             // restart the children group.
     ///         }
     ///     })
-    /// });
+    /// }).expect("Couldn't create the children group.");
     ///         # sp
     ///     # }).unwrap();
     ///     #
@@ -637,7 +865,10 @@ impl Supervisor {
     /// [`Children`]: children/struct.Children.html
     /// [`ChildrenRef`]: children/struct.ChildrenRef.html
     /// [`children`]: #method.children
-    pub fn children_ref<C>(&self, init: C) -> ChildrenRef
+    /// [`BuilderConflict`]: ../errors/enum.BuilderConflict.html
+    /// [`Children::with_name`]: children/struct.Children.html#method.with_name
+    /// [`BuilderError`]: ../errors/enum.BuilderError.html
+    pub fn children_ref<C>(&self, init: C) -> Result<ChildrenRef, BuilderError>
     where
         C: FnOnce(Children) -> Children,
     {
@@ -653,8 +884,11 @@ impl Supervisor {
         let children = Children::new(bcast);
         let mut children = init(children);
         debug!("Children({}): Initialized.", children.id());
+        children.finalize()?;
+        children.reserve_name()?;
         // FIXME: children group elems launched without the group itself being launched
         children.launch_elems();
+        children.register();
 
         let children_ref = children.as_ref();
         debug!(
@@ -666,7 +900,20 @@ impl Supervisor {
         let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
         self.bcast.send_self(env);
 
-        children_ref
+        Ok(children_ref)
+    }
+
+    // Records the error a children group or supervisor that
+    // couldn't be deployed failed with, unless another one already
+    // failed.
+    fn failed(&mut self, err: BuilderError) {
+        self.builder_error.get_or_insert(err);
+    }
+
+    // Returns the error creating the supervisor fails with, if one
+    // of its children groups or supervisors couldn't be deployed.
+    pub(crate) fn builder_error(&self) -> Option<BuilderError> {
+        self.builder_error
     }
 
     /// Sets the strategy the supervisor should use when one
@@ -731,7 +978,9 @@ impl Supervisor {
     /// restore in the correct state.
     ///
     /// The default strategy is the [`ActorRestartStrategy::Immediate`] and
-    /// unlimited amount of retries.
+    /// unlimited amount of retries, unless this supervisor is
+    /// supervised by a supervisor that set one (in which case it
+    /// inherits it).
     ///
     /// # Example
     ///
@@ -765,7 +1014,329 @@ impl Supervisor {
             self.id(),
             restart_strategy
         );
-        self.restart_strategy = restart_strategy;
+        self.restart_strategy.set(restart_strategy);
+        self
+    }
+
+    #[cfg(feature = "supervision")]
+    /// Sets how many times this supervisor can restart its
+    /// supervised elements during a window of time before giving
+    /// up, to avoid restarting elements that always fault in a
+    /// loop.
+    ///
+    /// When an element faults while `max_restarts` restarts already
+    /// happened during the last `within`, all the supervised
+    /// children groups and supervisors are killed and this
+    /// supervisor faults, letting its own parent handle it. The
+    /// restarts older than `within` stop being counted.
+    ///
+    /// By default, the number of restarts isn't limited (but
+    /// see [`with_restart_strategy`]), unless this supervisor is
+    /// supervised by a supervisor that set a window (in which case
+    /// it inherits it).
+    ///
+    /// # Arguments
+    ///
+    /// * `max_restarts` - How many restarts are allowed during
+    ///     the window.
+    /// * `within` - How long the window lasts.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::supervisor(|sp| {
+    ///     // Gives up after 5 restarts in less than 10 seconds.
+    ///     sp.with_restart_window(5, Duration::from_secs(10))
+    /// }).expect("Couldn't create the supervisor.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`with_restart_strategy`]: #method.with_restart_strategy
+    pub fn with_restart_window(mut self, max_restarts: usize, within: Duration) -> Self {
+        trace!(
+            "Supervisor({}): Setting restart window: max_restarts={} within={:?}",
+            self.id(),
+            max_restarts,
+            within
+        );
+        self.restart_window.set(Some((max_restarts, within)));
+        self
+    }
+
+    #[cfg(feature = "supervision")]
+    /// Sets the back-off delaying the restarts of the elements of
+    /// the children groups supervised by this supervisor (or by the
+    /// supervisors it supervises) which don't set their own using
+    /// [`Children::with_restart_backoff`].
+    ///
+    /// By default, the restarts aren't delayed (but see
+    /// [`with_restart_strategy`]), unless this supervisor is
+    /// supervised by a supervisor that set a back-off (in which case
+    /// it inherits it). The back-off replaces the delays of the
+    /// restart strategy for the groups using it.
+    ///
+    /// # Arguments
+    ///
+    /// * `backoff` - The back-off of the children groups supervised
+    ///     by this supervisor.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::supervisor(|sp| {
+    ///     sp.with_restart_backoff(Backoff::exponential(
+    ///         Duration::from_millis(100),
+    ///         Duration::from_secs(30),
+    ///         0.1,
+    ///     ))
+    /// }).expect("Couldn't create the supervisor.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::with_restart_backoff`]: children/struct.Children.html#method.with_restart_backoff
+    /// [`with_restart_strategy`]: #method.with_restart_strategy
+    pub fn with_restart_backoff(mut self, backoff: Backoff) -> Self {
+        trace!(
+            "Supervisor({}): Setting restart back-off: {:?}",
+            self.id(),
+            backoff
+        );
+        self.restart_backoff.set(Some(backoff));
+        self
+    }
+
+    /// Sets the watermark of the children groups supervised by this
+    /// supervisor (or by the supervisors it supervises) which don't
+    /// set their own using [`Children::with_pre_start_watermark`].
+    ///
+    /// The default watermark is inherited from the nearest
+    /// supervisor setting one, or is `1000` messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `watermark` - The watermark of the children groups
+    ///     supervised by this supervisor.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::supervisor(|sp| {
+    ///     sp.with_default_pre_start_watermark(100)
+    ///         // This group's watermark is 100 messages...
+    ///         .children(|children| children)
+    ///         // ...and this one's is 10 messages.
+    ///         .children(|children| children.with_pre_start_watermark(10))
+    /// }).expect("Couldn't create the supervisor.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::with_pre_start_watermark`]: children/struct.Children.html#method.with_pre_start_watermark
+    pub fn with_default_pre_start_watermark(mut self, watermark: usize) -> Self {
+        trace!(
+            "Supervisor({}): Setting default pre-start watermark: {}",
+            self.id(),
+            watermark
+        );
+        self.default_pre_start_watermark.set(watermark);
+        self
+    }
+
+    /// Sets the mailbox capacity and overflow policy of the
+    /// children groups created under this supervisor (or under the
+    /// supervisors created under it) which don't set their own
+    /// using [`Children::with_mailbox_capacity`].
+    ///
+    /// As the groups' mailboxes are created along with their
+    /// elements, it only applies to the groups created once it
+    /// was set. By default, it is inherited from the nearest
+    /// supervisor setting one, or the mailboxes are unbounded.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The capacity of the elements' mailboxes, or
+    ///     `1` if it is `0`.
+    /// * `policy` - What happens to the messages sent to an
+    ///     element whose mailbox is full.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::supervisor(|sp| {
+    ///     sp.with_default_mailbox(1024, OverflowPolicy::DropOldest)
+    ///         // This group's elements can hold 1024 messages...
+    ///         .children(|children| children)
+    ///         // ...and this one's 16.
+    ///         .children(|children| children.with_mailbox_capacity(16))
+    /// }).expect("Couldn't create the supervisor.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::with_mailbox_capacity`]: children/struct.Children.html#method.with_mailbox_capacity
+    pub fn with_default_mailbox(self, capacity: usize, policy: OverflowPolicy) -> Self {
+        trace!(
+            "Supervisor({}): Setting default mailbox: capacity={} policy={:?}",
+            self.id(),
+            capacity,
+            policy
+        );
+        // FIXME: panics?
+        self.default_mailbox
+            .lock()
+            .unwrap()
+            .set(Some((capacity.max(1), policy)));
+        self
+    }
+
+    /// Applies the policies of a [`SupervisionPreset`] applying to
+    /// the supervisors: its restart strategy, its restart window
+    /// and (as the default ones of the supervised children groups)
+    /// its restart back-off, its pre-start watermark and its
+    /// mailbox, which the supervisors deployed under this one
+    /// inherit.
+    ///
+    /// The preset's name is recorded in the policies of the
+    /// supervised children groups, unless they apply a preset
+    /// themselves (see [`Children::apply_preset`], which applies
+    /// the policies applying to the groups). Calling this
+    /// supervisor's other methods afterwards overrides the
+    /// preset's policies.
+    ///
+    /// # Arguments
+    ///
+    /// * `preset` - The preset to apply.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::supervisor(|sp| {
+    ///     sp.apply_preset(SupervisionPreset::web_service())
+    ///         // Gives up after 3 restarts instead.
+    ///         .with_restart_window(3, Duration::from_secs(10))
+    /// }).expect("Couldn't create the supervisor.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`SupervisionPreset`]: preset/struct.SupervisionPreset.html
+    /// [`Children::apply_preset`]: children/struct.Children.html#method.apply_preset
+    pub fn apply_preset(mut self, preset: SupervisionPreset) -> Self {
+        trace!("Supervisor({}): Applying preset: {:?}", self.id(), preset);
+        self.restart_strategy.set(preset.restart_strategy().clone());
+        #[cfg(feature = "supervision")]
+        {
+            self.restart_window.set(preset.restart_window());
+            self.restart_backoff.set(preset.restart_backoff().cloned());
+        }
+        self.default_pre_start_watermark
+            .set(preset.pre_start_watermark());
+        self.preset.set(Some(preset.name()));
+
+        match preset.mailbox_capacity() {
+            Some(capacity) => self.with_default_mailbox(capacity, preset.overflow_policy()),
+            None => {
+                // FIXME: panics?
+                self.default_mailbox.lock().unwrap().set(None);
+                self
+            }
+        }
+    }
+
+    /// Sets how the faults of the children groups supervised by
+    /// this supervisor are reported, as [`Event::ElemsFaulted`]
+    /// events and warnings.
+    ///
+    /// Only the first `threshold` faults of a group happening
+    /// for the same reason during a `window` are reported as
+    /// they happen. The following ones are counted and reported
+    /// at once when the window closes, so that elements faulting
+    /// in a loop don't flood the logs. This doesn't change how
+    /// the faults are counted by the restart strategy.
+    ///
+    /// By default, the window lasts one second and only its
+    /// first fault is reported as it happens.
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - How long identical faults are coalesced for,
+    ///     starting at the first one.
+    /// * `threshold` - How many identical faults are reported as
+    ///     they happen during a window (the first one always is).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::supervisor(|sp| {
+    ///     sp.with_fault_reports(Duration::from_secs(10), 3)
+    /// }).expect("Couldn't create the supervisor.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Event::ElemsFaulted`]: ../event/enum.Event.html#variant.ElemsFaulted
+    pub fn with_fault_reports(mut self, window: Duration, threshold: usize) -> Self {
+        trace!(
+            "Supervisor({}): Setting fault reports: window={:?} threshold={}",
+            self.id(),
+            window,
+            threshold
+        );
+        self.fault_reports.set_window(window, threshold);
         self
     }
 
@@ -844,7 +1415,7 @@ impl Supervisor {
                     };
                     let restarts_count = tracked_state.restarts_count();
 
-                    let restart_required = match self.restart_strategy.restart_policy() {
+                    let restart_required = match self.restart_strategy.get().restart_policy() {
                         RestartPolicy::Always => true,
                         RestartPolicy::Never => false,
                         RestartPolicy::Tries(max_retries) => restarts_count < max_retries,
@@ -861,10 +1432,14 @@ impl Supervisor {
                             BastionMessage::drop_child(id)
                         }
                     };
-                    let restart_strategy = self.restart_strategy.clone();
-
-                    restart_futures.push(async move {
-                        if restart_required {
+                    let restart_strategy = self.restart_strategy.get().clone();
+                    #[cfg(feature = "supervision")]
+                    let backed_off = self.backed_off.contains(&parent_id);
+                    #[cfg(not(feature = "supervision"))]
+                    let backed_off = false;
+
+                    restart_futures.push_back(async move {
+                        if restart_required && !backed_off {
                             restart_strategy.apply_strategy(restarts_count).await;
                         }
 
@@ -880,6 +1455,27 @@ impl Supervisor {
         }
     }
 
+    // Stops tracking the states of a group's elements, which drops
+    // the messages they didn't retrieve (making the questions among
+    // them fail with `AnswerError::Unanswered`) once the elements
+    // themselves stopped.
+    fn untrack_group(&mut self, id: &BastionId) {
+        if let Some(childs) = self.tracked_groups.remove(id) {
+            debug!(
+                "Supervisor({}): Dropping the states of Children({}).",
+                self.id(),
+                id
+            );
+            for state in childs {
+                self.tracked_groups_order.remove(&state.id);
+            }
+        }
+
+        self.group_strategies.remove(id);
+        #[cfg(feature = "supervision")]
+        self.backed_off.remove(id);
+    }
+
     fn remove_child(&mut self, id: &BastionId, parent_id: &BastionId) {
         let index = match self.tracked_groups_order.get(id) {
             Some(index) => *index,
@@ -915,7 +1511,7 @@ impl Supervisor {
             // TODO: Err if None?
             if let Some((_, launched)) = self.launched.remove(&id) {
                 // TODO: add a "stopped" list and poll from it instead of awaiting
-                supervised.push(launched);
+                supervised.push_back(launched);
             }
         }
 
@@ -927,13 +1523,16 @@ impl Supervisor {
                         self.id(),
                         supervised.id()
                     );
-                    supervised.callbacks().after_stop();
+                    if let Some(callbacks) = supervised.callbacks() {
+                        callbacks.after_stop();
+                    }
 
                     let id = supervised.id().clone();
                     self.stopped.insert(id, supervised);
                 }
-                // FIXME
-                None => unimplemented!(),
+                // The group's task was cancelled, its elements being
+                // cancelled once its lifeline is found cut.
+                None => debug!("Supervisor({}): Unknown Supervised cancelled.", self.id()),
             }
         }
     }
@@ -956,7 +1555,7 @@ impl Supervisor {
             // TODO: Err if None?
             if let Some((_, launched)) = self.launched.remove(&id) {
                 // TODO: add a "stopped" list and poll from it instead of awaiting
-                supervised.push(launched);
+                supervised.push_back(launched);
             }
         }
 
@@ -971,23 +1570,222 @@ impl Supervisor {
                     let id = supervised.id().clone();
                     self.killed.insert(id, supervised);
                 }
-                // FIXME
-                None => unimplemented!(),
+                // The group's task was cancelled, its elements being
+                // cancelled once its lifeline is found cut.
+                None => debug!("Supervisor({}): Unknown Supervised cancelled.", self.id()),
             }
         }
     }
 
     fn stopped(&mut self) {
         debug!("Supervisor({}): Stopped.", self.id());
+        let groups = self.tracked_groups.keys().cloned().collect::<Vec<_>>();
+        for id in groups {
+            self.untrack_group(&id);
+        }
+
+        tree::unregister(self.id());
         self.bcast.stopped();
     }
 
     fn faulted(&mut self) {
         debug!("Supervisor({}): Faulted.", self.id());
+        tree::unregister(self.id());
         self.bcast.faulted();
     }
 
+    #[cfg(feature = "supervision")]
+    // Counts a new restart and returns whether there were too many
+    // of them during the restart window.
+    fn restarts_exceeded(&mut self) -> bool {
+        let (max_restarts, within) = match *self.restart_window.get() {
+            Some(restart_window) => restart_window,
+            None => return false,
+        };
+
+        let now = Instant::now();
+        while let Some(time) = self.restart_times.front() {
+            if now.duration_since(*time) < within {
+                break;
+            }
+
+            self.restart_times.pop_front();
+        }
+
+        if self.restart_times.len() >= max_restarts {
+            warn!(
+                "Supervisor({}): Restarted {} times in less than {:?}, giving up.",
+                self.id(),
+                self.restart_times.len(),
+                within
+            );
+            return true;
+        }
+
+        self.restart_times.push_back(now);
+        false
+    }
+
+    #[cfg(feature = "supervision")]
+    // Applies the action set using `Config::on_root_exhaustion`
+    // once the system supervisor's restart window was exceeded, and
+    // returns whether the supervisor should give up instead.
+    fn exhausted(&mut self) -> bool {
+        let keep = match &self.on_exhaustion {
+            Some(RootAction::Stop) => {
+                warn!("Supervisor({}): Stopping the system.", self.id());
+                Bastion::stop();
+                return false;
+            }
+            Some(RootAction::Degrade { keep }) => keep.clone(),
+            None => return true,
+        };
+
+        // The kept groups can fault again without degrading the
+        // system once more right away.
+        self.restart_times.clear();
+
+        if SYSTEM.notify_degraded() {
+            warn!("Supervisor({}): Degrading the system.", self.id());
+            let event = Event::SystemDegraded { kept: keep.clone() };
+            SYSTEM.events().emit(event);
+        }
+
+        // The system sends it to every supervisor, including this one.
+        let msg = BastionMessage::degrade(keep);
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        // FIXME: Err(Error)
+        SYSTEM.sender().unbounded_send(env).ok();
+
+        false
+    }
+
+    // Stops the supervised children groups which weren't registered
+    // with one of the names in `keep` (or the dead letters), keeping
+    // them until the system recovers.
+    async fn degrade(&mut self, keep: Vec<String>) {
+        let kept = keep
+            .iter()
+            .filter_map(|name| SYSTEM.registry().get(name))
+            .map(|children| children.id().clone())
+            .collect::<FxHashSet<_>>();
+        let degraded = self
+            .order
+            .iter()
+            .filter(|id| self.groups.contains(*id))
+            .filter(|id| !kept.contains(*id) && **id != NIL_ID)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        debug!(
+            "Supervisor({}): Stopping {} children groups.",
+            self.id(),
+            degraded.len()
+        );
+        let mut supervised = FuturesOrdered::new();
+        for id in degraded {
+            self.bcast.stop_child(&id);
+            if let Some((_, launched)) = self.launched.remove(&id) {
+                supervised.push_back(launched);
+            }
+
+            self.order.retain(|order_id| order_id != &id);
+            self.groups.remove(&id);
+            self.untrack_group(&id);
+        }
+
+        for (index, id) in self.order.iter().enumerate() {
+            if let Some((order, _)) = self.launched.get_mut(id) {
+                *order = index;
+            }
+        }
+
+        while let Some(supervised) = supervised.next().await {
+            match supervised {
+                Some(Supervised::Children(children)) => {
+                    trace!(
+                        "Supervisor({}): Children({}) stopped.",
+                        self.id(),
+                        children.id()
+                    );
+                    self.degraded.push(*children);
+                }
+                Some(Supervised::Supervisor(_)) => unreachable!(),
+                // The group's task was cancelled, its elements being
+                // cancelled once its lifeline is found cut.
+                None => debug!("Supervisor({}): Unknown Supervised cancelled.", self.id()),
+            }
+        }
+
+        let msg = BastionMessage::degrade(keep);
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        self.bcast.send_children(env);
+    }
+
+    // Deploys the children groups stopped when the system was
+    // degraded again, with the same identifiers and definitions.
+    async fn recover_degraded(&mut self) {
+        debug!(
+            "Supervisor({}): Recovering {} children groups.",
+            self.id(),
+            self.degraded.len()
+        );
+        let mut still_degraded = Vec::new();
+        for children in std::mem::take(&mut self.degraded) {
+            let parent = Parent::supervisor(self.as_ref());
+            let bcast = Broadcast::new(parent, BastionPathElement::Children(children.id().clone()));
+
+            let mut children = children.respawn(bcast);
+            // Another group took the name meanwhile: the group stays
+            // degraded until it is released.
+            if let Err(err) = children.reserve_name() {
+                warn!(
+                    "Supervisor({}): Not recovering Children({}): {}",
+                    self.id(),
+                    children.id(),
+                    err
+                );
+                still_degraded.push(children);
+                continue;
+            }
+            children.register_dispatchers();
+            children.launch_elems();
+            children.register();
+
+            self.deploy_supervised_object(Deployment::Children(children))
+                .await;
+        }
+        self.degraded = still_degraded;
+
+        let msg = BastionMessage::recover();
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        self.bcast.send_children(env);
+    }
+
     async fn recover(&mut self, id: BastionId, parent_id: BastionId) -> Result<(), ()> {
+        // FIXME: panics?
+        let strategy = self
+            .group_strategies
+            .get(&parent_id)
+            .and_then(|strategy| strategy.lock().unwrap().clone());
+        if let Some(strategy) = strategy {
+            debug!(
+                "Supervisor({}): Recovering using Children({})'s strategy: {:?}",
+                self.id(),
+                parent_id,
+                strategy
+            );
+            let search_method = ActorSearchMethod::Group {
+                id,
+                parent_id,
+                strategy,
+            };
+            let objects = self.search_restarted_objects(search_method);
+            self.restart(objects).await;
+
+            return Ok(());
+        }
+
         debug!(
             "Supervisor({}): Recovering using strategy: {:?}",
             self.id(),
@@ -1061,7 +1859,8 @@ impl Supervisor {
                             }
                         }
                         None => {
-                            let restarted_element = RestartedElement::Supervisor(id.clone());
+                            let restarted_element =
+                                RestartedElement::Supervisor(element_id.clone());
                             objects.push(restarted_element);
                         }
                     }
@@ -1086,6 +1885,34 @@ impl Supervisor {
                     }
                 }
             }
+            ActorSearchMethod::Group {
+                id,
+                parent_id,
+                strategy,
+            } => {
+                let childs = match self.tracked_groups.get(&parent_id) {
+                    Some(childs) => childs,
+                    None => return objects,
+                };
+                let start_index = match strategy {
+                    SupervisionStrategy::OneForOne => {
+                        objects.push(RestartedElement::Child { id, parent_id });
+                        return objects;
+                    }
+                    SupervisionStrategy::OneForAll => 0,
+                    SupervisionStrategy::RestForOne => {
+                        *self.tracked_groups_order.get(&id).unwrap_or(&0)
+                    }
+                };
+
+                for tracked_state in &childs[start_index..] {
+                    let element = RestartedElement::Child {
+                        id: tracked_state.id(),
+                        parent_id: parent_id.clone(),
+                    };
+                    objects.push(element);
+                }
+            }
         }
 
         objects
@@ -1109,26 +1936,76 @@ impl Supervisor {
         self.stopped();
     }
 
+    // Returns the policies of the children groups deployed under
+    // this supervisor, for those they don't set themselves.
+    fn group_policies(&self) -> GroupPolicies {
+        let policies = GroupPolicies::new(
+            self.restart_strategy.get().clone(),
+            *self.default_pre_start_watermark.get(),
+        );
+        #[cfg(feature = "supervision")]
+        let policies = policies
+            .with_restart_window(*self.restart_window.get())
+            .with_restart_backoff(self.restart_backoff.get().clone());
+
+        policies.with_preset(*self.preset.get())
+    }
+
+    fn inherit_policies(&mut self, parent: &Supervisor) {
+        self.restart_strategy.inherit(parent.restart_strategy.get());
+        #[cfg(feature = "supervision")]
+        {
+            self.restart_window.inherit(parent.restart_window.get());
+            self.restart_backoff.inherit(parent.restart_backoff.get());
+        }
+        self.default_pre_start_watermark
+            .inherit(parent.default_pre_start_watermark.get());
+        // FIXME: panics?
+        let default_mailbox = *parent.default_mailbox.lock().unwrap().get();
+        self.default_mailbox
+            .lock()
+            .unwrap()
+            .inherit(&default_mailbox);
+        self.preset.inherit(parent.preset.get());
+    }
+
     async fn deploy_supervised_object(&mut self, deployment: Deployment) {
         let supervised = match deployment {
-            Deployment::Supervisor(supervisor) => {
+            Deployment::Supervisor(mut supervisor) => {
                 debug!(
                     "Supervisor({}): Deploying Supervisor({}).",
                     self.id(),
                     supervisor.id()
                 );
+                supervisor.inherit_policies(self);
                 supervisor.callbacks().before_start();
                 Supervised::supervisor(supervisor)
             }
-            Deployment::Children(children) => {
+            Deployment::Children(mut children) => {
                 debug!(
                     "Supervisor({}): Deploying Children({}).",
                     self.id(),
                     children.id()
                 );
-                children.callbacks().before_start();
+                children.resolve_policies(self.group_policies());
+                self.groups.insert(children.id().clone());
+                self.lifelines
+                    .insert(children.id().clone(), children.lifeline().clone());
+                let strategy = children.supervision_strategy().clone();
+                self.group_strategies
+                    .insert(children.id().clone(), strategy);
+                #[cfg(feature = "supervision")]
+                {
+                    if children.has_restart_backoff() {
+                        self.backed_off.insert(children.id().clone());
+                    } else {
+                        self.backed_off.remove(children.id());
+                    }
+                }
+
                 Supervised::children(children)
             }
+            Deployment::Child { .. } => unreachable!(),
         };
 
         self.bcast.register(supervised.bcast());
@@ -1155,12 +2032,18 @@ impl Supervisor {
         if let Some((_, launched)) = self.launched.remove(&id) {
             debug!("Supervisor({}): Supervised({}) stopped.", self.id(), id);
             // TODO: add a "waiting" list an poll from it instead of awaiting
-            // FIXME: panics?
-            let supervised = launched.await.unwrap();
-            supervised.callbacks().after_stop();
+            let supervised = launched.await;
+            if let Some(callbacks) = supervised.as_ref().and_then(Supervised::callbacks) {
+                callbacks.after_stop();
+            }
 
             self.bcast.unregister(&id);
-            self.stopped.insert(id.clone(), supervised);
+            self.groups.remove(&id);
+            self.untrack_group(&id);
+            // The group's task might have been cancelled.
+            if let Some(supervised) = supervised {
+                self.stopped.insert(id.clone(), supervised);
+            }
         }
     }
 
@@ -1168,12 +2051,24 @@ impl Supervisor {
         &mut self,
         id: BastionId,
         parent_id: BastionId,
+        reason: FaultReason,
+        error: Option<ExecError>,
+        provenance: Provenance,
+        records: Vec<FlightRecord>,
     ) -> Result<(), ()> {
-        if self.launched.contains_key(&id) {
+        if self.tracked_groups.contains_key(&parent_id) {
+            self.fault_reports
+                .report(&parent_id, reason, error.as_ref(), provenance, records);
+        } else if self.launched.contains_key(&id) {
             warn!("Supervisor({}): Supervised({}) faulted.", self.id(), id);
         }
 
-        if self.recover(id, parent_id).await.is_err() {
+        #[cfg(feature = "supervision")]
+        let exhausted = self.restarts_exceeded() && self.exhausted();
+        #[cfg(not(feature = "supervision"))]
+        let exhausted = false;
+
+        if exhausted || self.recover(id, parent_id).await.is_err() {
             // TODO: stop or kill?
             self.kill(0..self.order.len()).await;
             self.faulted();
@@ -1197,6 +2092,16 @@ impl Supervisor {
                 self.deinit_with_stop().await;
                 return Err(());
             }
+            // Only the children groups are stopped within a timeout
+            // or quiesced.
+            Envelope {
+                msg: BastionMessage::StopWithin(_),
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Quiesce(_),
+                ..
+            } => unreachable!(),
             Envelope {
                 msg: BastionMessage::Kill,
                 ..
@@ -1207,12 +2112,35 @@ impl Supervisor {
             Envelope {
                 msg: BastionMessage::Deploy(deployment),
                 ..
-            } => self.deploy_supervised_object(deployment).await,
+            } => self.deploy_supervised_object(*deployment).await,
             // FIXME
             Envelope {
                 msg: BastionMessage::Prune { .. },
                 ..
             } => unimplemented!(),
+            Envelope {
+                msg: BastionMessage::CancelTask(id),
+                ..
+            } => {
+                if let Some((_, launched)) = self.launched.get(&id) {
+                    warn!(
+                        "Supervisor({}): Cancelling the task of Children({}).",
+                        self.id(),
+                        id
+                    );
+                    launched.cancel();
+                }
+            }
+            // Only the children groups are scaled.
+            Envelope {
+                msg: BastionMessage::Scale { .. },
+                ..
+            } => unreachable!(),
+            // Only the children groups route messages.
+            Envelope {
+                msg: BastionMessage::SetRouting { .. },
+                ..
+            } => unreachable!(),
             Envelope {
                 msg: BastionMessage::SuperviseWith(strategy),
                 ..
@@ -1222,6 +2150,7 @@ impl Supervisor {
                     self.id(),
                     strategy
                 );
+                tree::set_strategy(self.id(), &strategy);
                 self.strategy = strategy;
             }
             Envelope {
@@ -1261,10 +2190,22 @@ impl Supervisor {
                 self.bcast.send_children(env);
             }
             Envelope {
-                msg: BastionMessage::RestartRequired { id, parent_id },
+                msg:
+                    BastionMessage::RestartRequired {
+                        id,
+                        parent_id,
+                        reason,
+                        error,
+                        provenance,
+                        records,
+                    },
                 ..
             } => {
-                if self.recover_supervised_object(id, parent_id).await.is_err() {
+                if self
+                    .recover_supervised_object(id, parent_id, reason, error, provenance, records)
+                    .await
+                    .is_err()
+                {
                     return Err(());
                 }
             }
@@ -1276,6 +2217,14 @@ impl Supervisor {
                 msg: BastionMessage::RestartSubtree,
                 ..
             } => self.restart_subtree().await,
+            Envelope {
+                msg: BastionMessage::Degrade { keep },
+                ..
+            } => self.degrade(keep).await,
+            Envelope {
+                msg: BastionMessage::Recover,
+                ..
+            } => self.recover_degraded().await,
             Envelope {
                 msg: BastionMessage::RestoreChild { .. },
                 ..
@@ -1289,13 +2238,27 @@ impl Supervisor {
                 ..
             } => unreachable!(),
             Envelope {
-                msg: BastionMessage::Stopped { id },
+                msg: BastionMessage::Stopped { id, killed },
                 ..
-            } => self.cleanup_supervised_object(id).await,
+            } => {
+                if killed {
+                    warn!(
+                        "Supervisor({}): Children({}) was killed because it didn't stop in time.",
+                        self.id(),
+                        id
+                    );
+                }
+
+                self.cleanup_supervised_object(id).await
+            }
             Envelope {
                 msg: BastionMessage::Faulted { id },
                 ..
             } => self.cleanup_supervised_object(id).await,
+            Envelope {
+                msg: BastionMessage::MigrateChild { .. },
+                ..
+            } => unreachable!(),
         }
 
         Ok(())
@@ -1333,7 +2296,20 @@ impl Supervisor {
 
     async fn run(mut self) -> Self {
         debug!("Supervisor({}): Launched.", self.id());
+        tree::register_supervisor(
+            self.id(),
+            self.bcast.parent(),
+            &self.strategy,
+            self.is_utilities_supervisor,
+        );
+
         loop {
+            // Reports the faults coalesced during the windows that
+            // closed, even if no other fault happened since.
+            let flush_faults = future::poll_fn(|ctx| self.fault_reports.poll_flush(ctx));
+            let _ = poll!(flush_faults);
+            self.reconcile_orphans().await;
+
             match poll!(&mut self.bcast.next()) {
                 // TODO: Err if started == true?
                 Poll::Ready(Some(Envelope {
@@ -1371,16 +2347,91 @@ impl Supervisor {
         }
     }
 
+    // Forgets about the children groups whose task was dropped
+    // without them stopping their elements (e.g. because their
+    // proc was cancelled), whose elements stop on their own, and
+    // cancels the procs of the elements that are still running
+    // once they had the time to.
+    async fn reconcile_orphans(&mut self) {
+        let lifelines = &mut self.lifelines;
+        let cut = future::poll_fn(|ctx| {
+            let cut = lifelines
+                .iter_mut()
+                .filter_map(|(id, lifeline)| match lifeline.poll_cut(ctx) {
+                    Poll::Ready(()) => Some(id.clone()),
+                    Poll::Pending => None,
+                })
+                .collect::<Vec<_>>();
+
+            Poll::Ready(cut)
+        })
+        .await;
+
+        for id in cut {
+            // FIXME: panics?
+            let lifeline = self.lifelines.remove(&id).unwrap();
+            // The groups that stopped are only dropped once they
+            // aren't launched anymore (their elements having
+            // stopped too).
+            if self.launched.remove(&id).is_some() {
+                warn!(
+                    "Supervisor({}): Children({}) was dropped without stopping its elements.",
+                    self.id(),
+                    id
+                );
+                self.bcast.unregister(&id);
+                self.groups.remove(&id);
+                self.group_strategies.remove(&id);
+                self.untrack_group(&id);
+                tree::unregister(&id);
+            }
+
+            if !lifeline.tracks_elems() {
+                continue;
+            }
+
+            let parent = self.id().clone();
+            let sweep = async move {
+                Delay::new(ORPHAN_TIMEOUT).await;
+                for elem in lifeline.cancel_elems() {
+                    warn!(
+                        "Supervisor({}): Cancelled orphaned Child({}) of Children({}).",
+                        parent, elem, id
+                    );
+                }
+            };
+            pool::spawn_with_class(sweep, ProcStack::default(), ProcClass::Timer);
+        }
+    }
+
     pub(crate) fn launch(self) -> RecoverableHandle<Self> {
         debug!("Supervisor({}): Launching.", self.id());
         let stack = self.stack();
-        pool::spawn(self.run(), stack)
+        pool::spawn_with_class(self.run(), stack, ProcClass::Management)
     }
 }
 
 impl SupervisorRef {
-    pub(crate) fn new(id: BastionId, sender: Sender, path: Arc<BastionPath>) -> Self {
-        SupervisorRef { id, sender, path }
+    pub(crate) fn new(
+        id: BastionId,
+        sender: Sender,
+        path: Arc<BastionPath>,
+        default_mailbox: DefaultMailbox,
+    ) -> Self {
+        SupervisorRef {
+            id,
+            sender,
+            path,
+            default_mailbox,
+        }
+    }
+
+    // Returns the mailbox capacity and overflow policy of the
+    // children groups created under the supervisor, if it (or the
+    // nearest supervisor above it) set them.
+    pub(crate) fn default_mailbox(&self) -> Option<(usize, OverflowPolicy)> {
+        // FIXME: panics?
+        *self.default_mailbox.lock().unwrap().get()
     }
 
     /// Returns the identifier of the supervisor this `SupervisorRef`
@@ -1418,7 +2469,7 @@ impl SupervisorRef {
     /// `SupervisorRef` is referencing to supervise it.
     ///
     /// This method returns a [`SupervisorRef`] referencing the newly
-    /// created supervisor if it succeeded, or `Err(())`
+    /// created supervisor if it succeeded, or a [`BuilderError`]
     /// otherwise.
     ///
     /// # Arguments
@@ -1448,11 +2499,15 @@ impl SupervisorRef {
     /// ```
     ///
     /// [`Supervisor`]: supervisor/struct.Supervisor.html
-    pub fn supervisor<S>(&self, init: S) -> Result<Self, ()>
+    /// [`BuilderError`]: errors/enum.BuilderError.html
+    pub fn supervisor<S>(&self, init: S) -> Result<Self, BuilderError>
     where
         S: FnOnce(Supervisor) -> Supervisor,
     {
         debug!("SupervisorRef({}): Creating supervisor.", self.id());
+        // The groups are all launched before any of their elements
+        // runs.
+        let _hold = deterministic::hold();
         let parent = Parent::supervisor(self.clone());
         let bcast = Broadcast::new(parent, BastionPathElement::Supervisor(BastionId::new()));
 
@@ -1464,6 +2519,7 @@ impl SupervisorRef {
         let supervisor = Supervisor::new(bcast);
         let supervisor = init(supervisor);
         let supervisor_ref = supervisor.as_ref();
+        let builder_error = supervisor.builder_error();
         debug!("Supervisor({}): Initialized.", supervisor.id());
 
         debug!(
@@ -1473,7 +2529,20 @@ impl SupervisorRef {
         );
         let msg = BastionMessage::deploy_supervisor(supervisor);
         let env = Envelope::new(msg, self.path.clone(), self.sender.clone());
-        self.send(env).map_err(|_| ())?;
+        self.send(env).map_err(|_| BuilderError::ParentStopped)?;
+
+        if let Some(err) = builder_error {
+            // The groups that were deployed are stopped along with
+            // the supervisor.
+            warn!(
+                "SupervisorRef({}): Stopping Supervisor({}): {}",
+                self.id(),
+                supervisor_ref.id(),
+                err
+            );
+            supervisor_ref.stop().ok();
+            return Err(err);
+        }
 
         Ok(supervisor_ref)
     }
@@ -1482,8 +2551,14 @@ impl SupervisorRef {
     /// `init` closure and then sends it to the supervisor this
     /// `SupervisorRef` is referencing to supervise it.
     ///
+    /// This can be done before or after the system is started: if
+    /// the supervisor is already running, the children group is
+    /// started as soon as the supervisor receives it, and its
+    /// failures are handled using the supervisor's strategy like
+    /// the ones of the groups it was started with.
+    ///
     /// This methods returns a [`ChildrenRef`] referencing the newly
-    /// created children group it it succeeded, or `Err(())`
+    /// created children group it it succeeded, or a [`BuilderError`]
     /// otherwise.
     ///
     /// # Arguments
@@ -1522,18 +2597,81 @@ impl SupervisorRef {
     ///
     /// [`Children`]: children/struct.Children.html
     /// [`ChildrenRef`]: children/struct.ChildrenRef.html
-    pub fn children<C>(&self, init: C) -> Result<ChildrenRef, ()>
+    /// [`BuilderError`]: errors/enum.BuilderError.html
+    pub fn children<C>(&self, init: C) -> Result<ChildrenRef, BuilderError>
     where
         C: FnOnce(Children) -> Children,
     {
         self.children_with_id(BastionId::new(), init)
     }
 
-    pub(crate) fn children_with_id<C>(&self, id: BastionId, init: C) -> Result<ChildrenRef, ()>
+    /// Creates a new [`Children`] whose elements only accept
+    /// messages of type `M`, passes it through the specified `init`
+    /// closure and then sends it to the supervisor this
+    /// `SupervisorRef` is referencing, like [`children`] does (see
+    /// [`Bastion::typed_children`]).
+    ///
+    /// This methods returns a [`TypedChildrenRef`] referencing the
+    /// newly created children group if it succeeded, or a
+    /// [`BuilderError`] otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `init` - The closure taking the new [`Children`] as an
+    ///     argument and returning it once configured.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let sp_ref = Bastion::supervisor(|sp| sp).unwrap();
+    /// let typed_ref: TypedChildrenRef<u64> = sp_ref.typed_children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             loop {
+    ///                 let (msg, _) = ctx.recv_msg::<u64>().await;
+    ///                 // Handle the message...
+    ///                 # drop(msg);
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children`]: children/struct.Children.html
+    /// [`children`]: #method.children
+    /// [`Bastion::typed_children`]: struct.Bastion.html#method.typed_children
+    /// [`TypedChildrenRef`]: typed/struct.TypedChildrenRef.html
+    /// [`BuilderError`]: errors/enum.BuilderError.html
+    pub fn typed_children<M, C>(&self, init: C) -> Result<TypedChildrenRef<M>, BuilderError>
+    where
+        M: Message,
+        C: FnOnce(Children) -> Children,
+    {
+        self.children(|children| init(children).with_accepted_types::<(M,)>())
+            .map(TypedChildrenRef::new)
+    }
+
+    pub(crate) fn children_with_id<C>(
+        &self,
+        id: BastionId,
+        init: C,
+    ) -> Result<ChildrenRef, BuilderError>
     where
         C: FnOnce(Children) -> Children,
     {
         debug!("SupervisorRef({}): Creating children group.", self.id());
+        // The elements are all launched before any of them runs.
+        let _hold = deterministic::hold();
         let parent = Parent::supervisor(self.clone());
         let bcast = Broadcast::new(parent, BastionPathElement::Children(id));
 
@@ -1545,10 +2683,14 @@ impl SupervisorRef {
         let children = Children::new(bcast);
         let mut children = init(children);
         debug!("Children({}): Initialized.", children.id());
+        children.finalize()?;
+        children.reserve_name()?;
         // FIXME: children group elems launched without the group itself being launched
         children.launch_elems();
+        children.register();
 
         let children_ref = children.as_ref();
+        let name = children.name().map(str::to_string);
         debug!(
             "SupervisorRef({}): Deplying Children({}).",
             self.id(),
@@ -1556,7 +2698,13 @@ impl SupervisorRef {
         );
         let msg = BastionMessage::deploy_children(children);
         let env = Envelope::new(msg, self.path.clone(), self.sender.clone());
-        self.send(env).map_err(|_| ())?;
+        self.send(env).map_err(|_| {
+            if let Some(name) = &name {
+                SYSTEM.registry().unregister(name, children_ref.id());
+            }
+
+            BuilderError::ParentStopped
+        })?;
 
         Ok(children_ref)
     }
@@ -1571,8 +2719,8 @@ impl SupervisorRef {
     /// The default strategy `Supervisor` is
     /// [`SupervisionStrategy::OneForOne`].
     ///
-    /// This method returns `()` if it succeeded, or `Err(())`
-    /// otherwise.
+    /// This method returns `()` if it succeeded, or a [`SendError`]
+    /// carrying the strategy otherwise.
     ///
     /// # Arguments
     ///
@@ -1612,23 +2760,27 @@ impl SupervisorRef {
     /// [`SupervisionStrategy::OneForOne`]: supervisor/enum.SupervisionStrategy.html#variant.OneForOne
     /// [`SupervisionStrategy::OneForAll`]: supervisor/enum.SupervisionStrategy.html#variant.OneForAll
     /// [`SupervisionStrategy::RestForOne`]: supervisor/enum.SupervisionStrategy.html#variant.RestForOne
-    pub fn strategy(&self, strategy: SupervisionStrategy) -> Result<(), ()> {
+    /// [`SendError`]: errors/struct.SendError.html
+    pub fn strategy(
+        &self,
+        strategy: SupervisionStrategy,
+    ) -> Result<(), SendError<SupervisionStrategy>> {
         debug!(
             "SupervisorRef({}): Setting strategy: {:?}",
             self.id(),
             strategy
         );
-        let msg = BastionMessage::supervise_with(strategy);
+        let msg = BastionMessage::supervise_with(strategy.clone());
         let env = Envelope::from_dead_letters(msg);
-        self.send(env).map_err(|_| ())
+        self.send(env).map_err(|_| SendError::stopped(strategy))
     }
 
     /// Sends a message to the supervisor this `SupervisorRef`
     /// is referencing which will then send it to all of its
     /// supervised children groups and supervisors.
     ///
-    /// This method returns `()` if it succeeded, or `Err(msg)`
-    /// otherwise.
+    /// This method returns `()` if it succeeded, or a [`SendError`]
+    /// carrying the message otherwise.
     ///
     /// # Arguments
     ///
@@ -1671,7 +2823,9 @@ impl SupervisorRef {
     ///     # Bastion::block_until_stopped();
     /// # }
     /// ```
-    pub fn broadcast<M: Message>(&self, msg: M) -> Result<(), M> {
+    ///
+    /// [`SendError`]: errors/struct.SendError.html
+    pub fn broadcast<M: Message>(&self, msg: M) -> Result<(), SendError<M>> {
         debug!(
             "SupervisorRef({}): Broadcasting message: {:?}",
             self.id(),
@@ -1680,15 +2834,16 @@ impl SupervisorRef {
         let msg = BastionMessage::broadcast(msg);
         let env = Envelope::from_dead_letters(msg);
         // FIXME: panics?
-        self.send(env).map_err(|env| env.into_msg().unwrap())
+        self.send(env)
+            .map_err(|env| SendError::stopped(env.into_msg().unwrap()))
     }
 
     /// Sends a message to the supervisor this `SupervisorRef`
     /// is referencing to tell it to stop every running children
     /// groups and supervisors that it is supervising.
     ///
-    /// This method returns `()` if it succeeded, or `Err(())`
-    /// otherwise.
+    /// This method returns `()` if it succeeded, or a
+    /// [`ShutdownError`] otherwise.
     ///
     /// # Example
     ///
@@ -1706,19 +2861,21 @@ impl SupervisorRef {
     ///     # Bastion::block_until_stopped();
     /// # }
     /// ```
-    pub fn stop(&self) -> Result<(), ()> {
+    ///
+    /// [`ShutdownError`]: errors/enum.ShutdownError.html
+    pub fn stop(&self) -> Result<(), ShutdownError> {
         debug!("SupervisorRef({}): Stopping.", self.id());
         let msg = BastionMessage::stop();
         let env = Envelope::from_dead_letters(msg);
-        self.send(env).map_err(|_| ())
+        self.send(env).map_err(|_| ShutdownError::AlreadyStopped)
     }
 
     /// Sends a message to the supervisor this `SupervisorRef`
     /// is referencing to tell it to kill every running children
     /// groups and supervisors that it is supervising.
     ///
-    /// This method returns `()` if it succeeded, or `Err(())`
-    /// otherwise.
+    /// This method returns `()` if it succeeded, or a
+    /// [`ShutdownError`] otherwise.
     ///
     /// # Example
     ///
@@ -1736,11 +2893,13 @@ impl SupervisorRef {
     ///     # Bastion::block_until_stopped();
     /// # }
     /// ```
-    pub fn kill(&self) -> Result<(), ()> {
+    ///
+    /// [`ShutdownError`]: errors/enum.ShutdownError.html
+    pub fn kill(&self) -> Result<(), ShutdownError> {
         debug!("SupervisorRef({}): Killing.", self.id());
         let msg = BastionMessage::kill();
         let env = Envelope::from_dead_letters(msg);
-        self.send(env).map_err(|_| ())
+        self.send(env).map_err(|_| ShutdownError::AlreadyStopped)
     }
 
     pub(crate) fn send(&self, env: Envelope) -> Result<(), Envelope> {
@@ -1783,11 +2942,11 @@ impl TrackedChildState {
 
 impl Supervised {
     fn supervisor(supervisor: Supervisor) -> Self {
-        Supervised::Supervisor(supervisor)
+        Supervised::Supervisor(Box::new(supervisor))
     }
 
     fn children(children: Children) -> Self {
-        Supervised::Children(children)
+        Supervised::Children(Box::new(children))
     }
 
     fn stack(&self) -> ProcStack {
@@ -1810,10 +2969,12 @@ impl Supervised {
         }
     }
 
-    fn callbacks(&self) -> &Callbacks {
+    // A children group's callbacks are called by each of its
+    // elements instead.
+    fn callbacks(&self) -> Option<&Callbacks> {
         match self {
-            Supervised::Supervisor(supervisor) => supervisor.callbacks(),
-            Supervised::Children(children) => children.callbacks(),
+            Supervised::Supervisor(supervisor) => Some(supervisor.callbacks()),
+            Supervised::Children(_) => None,
         }
     }
 
@@ -1822,29 +2983,48 @@ impl Supervised {
         let stack = self.stack();
         match self {
             Supervised::Supervisor(supervisor) => {
-                pool::spawn(
+                let launched = Cancelling(supervisor.launch());
+                pool::spawn_with_class(
                     async {
                         // FIXME: panics?
-                        let supervisor = supervisor.launch().await.unwrap();
-                        Supervised::Supervisor(supervisor)
+                        let supervisor = launched.await.unwrap();
+                        Supervised::supervisor(supervisor)
                     },
                     stack,
+                    ProcClass::Management,
                 )
             }
             Supervised::Children(children) => {
-                pool::spawn(
+                let launched = Cancelling(children.launch());
+                pool::spawn_with_class(
                     async {
                         // FIXME: panics?
-                        let children = children.launch().await.unwrap();
-                        Supervised::Children(children)
+                        let children = launched.await.unwrap();
+                        Supervised::children(children)
                     },
                     stack,
+                    ProcClass::Management,
                 )
             }
         }
     }
 }
 
+impl<R> Future for Cancelling<R> {
+    type Output = Option<R>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(ctx)
+    }
+}
+
+impl<R> Drop for Cancelling<R> {
+    fn drop(&mut self) {
+        // NOTE: this has no effect if the proc completed.
+        self.0.cancel();
+    }
+}
+
 impl RestartStrategy {
     /// Creates a new instance of RestartStrategy.
     ///
@@ -1939,6 +3119,30 @@ impl RestartStrategy {
     }
 }
 
+impl<T: Clone> Inherited<T> {
+    pub(crate) fn new(value: T) -> Self {
+        Inherited {
+            value,
+            explicit: false,
+        }
+    }
+
+    pub(crate) fn get(&self) -> &T {
+        &self.value
+    }
+
+    pub(crate) fn set(&mut self, value: T) {
+        self.value = value;
+        self.explicit = true;
+    }
+
+    pub(crate) fn inherit(&mut self, value: &T) {
+        if !self.explicit {
+            self.value = value.clone();
+        }
+    }
+}
+
 impl Default for SupervisionStrategy {
     fn default() -> Self {
         SupervisionStrategy::OneForOne
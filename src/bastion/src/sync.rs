@@ -0,0 +1,264 @@
+//!
+//! Synchronization primitives allowing elements of different
+//! children groups to coordinate with each other.
+//!
+//! A [`Barrier`] makes a fixed number of participants wait for
+//! each other before continuing.
+//!
+//! [`Barrier`]: struct.Barrier.html
+use crate::bastion::Bastion;
+use crate::child_ref::ChildRef;
+use crate::children_ref::ChildrenRef;
+use crate::context::BastionContext;
+use crate::errors::BuilderError;
+use crate::message::AnswerSender;
+use futures::future::{self, Either};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+use std::task::Poll;
+
+#[derive(Debug, Clone)]
+/// A cyclic barrier making `parties` participants (e.g. the
+/// elements of different children groups) wait for each other
+/// before continuing.
+///
+/// The barrier is kept by an element of a children group
+/// supervised by the system supervisor, which is stopped once
+/// every clone of the `Barrier` is dropped (or when the system
+/// is stopped).
+///
+/// Once all the participants called [`wait`], all of them are
+/// released and the barrier can be used again. If one of the
+/// participants stops waiting before that (e.g. because its
+/// element was killed and its future dropped), the others are
+/// released with [`BarrierError::ParticipantLost`] instead of
+/// waiting forever. A participant that gets killed before
+/// calling [`wait`] can't be noticed by the barrier.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// # use bastion::sync::Barrier;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// // The elements of both groups will wait for each other.
+/// let barrier = Barrier::new(2).expect("Couldn't create the barrier.");
+///
+/// for _ in 0..2 {
+///     let barrier = barrier.clone();
+///     Bastion::children(move |children| {
+///         let barrier = barrier.clone();
+///         children.with_exec(move |ctx: BastionContext| {
+///             let barrier = barrier.clone();
+///             async move {
+///                 // Phase 1...
+///                 barrier.wait().await?;
+///                 // Phase 2, once both elements finished phase 1...
+///
+///                 Ok(())
+///             }
+///         })
+///     }).expect("Couldn't create the children group.");
+/// }
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`wait`]: #method.wait
+/// [`BarrierError::ParticipantLost`]: enum.BarrierError.html#variant.ParticipantLost
+pub struct Barrier {
+    inner: Arc<BarrierInner>,
+}
+
+#[derive(Debug)]
+struct BarrierInner {
+    parties: usize,
+    // The group of the element keeping the barrier.
+    children: ChildrenRef,
+    child: ChildRef,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// The result of [`Barrier::wait`], once all the participants
+/// arrived.
+///
+/// [`Barrier::wait`]: struct.Barrier.html#method.wait
+pub struct BarrierWaitResult {
+    leader: bool,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// The reasons why [`Barrier::wait`] couldn't wait for all the
+/// participants.
+///
+/// [`Barrier::wait`]: struct.Barrier.html#method.wait
+pub enum BarrierError {
+    /// A participant stopped waiting before all of them arrived.
+    /// The barrier can still be used again.
+    ParticipantLost,
+    /// The element keeping the barrier stopped (e.g. because the
+    /// system is stopping).
+    Stopped,
+}
+
+#[derive(Debug)]
+// The message asked to the barrier's element by a participant.
+struct Arrive;
+
+#[derive(Debug)]
+// The answer to `Arrive`.
+enum Arrived {
+    Leader,
+    Follower,
+    Lost,
+}
+
+impl Barrier {
+    /// Creates a new barrier waiting for `parties` participants.
+    ///
+    /// This method returns the barrier if its children group was
+    /// created, or a [`BuilderError`] otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `parties` - The number of participants to wait for
+    ///     (waiting for no participant is the same as waiting
+    ///     for one).
+    ///
+    /// [`BuilderError`]: ../errors/enum.BuilderError.html
+    pub fn new(parties: usize) -> Result<Self, BuilderError> {
+        let parties = parties.max(1);
+        let children = Bastion::children(|children| {
+            children.with_exec(move |ctx: BastionContext| run(ctx, parties))
+        })?;
+        let child = children.elems()[0].clone();
+
+        let inner = Arc::new(BarrierInner {
+            parties,
+            children,
+            child,
+        });
+
+        Ok(Barrier { inner })
+    }
+
+    /// Returns the number of participants this barrier waits for.
+    pub fn parties(&self) -> usize {
+        self.inner.parties
+    }
+
+    /// Waits (always asynchronously) until all the participants
+    /// called this method.
+    ///
+    /// This method returns [`BarrierWaitResult`] once all the
+    /// participants arrived, or a [`BarrierError`] otherwise.
+    /// Dropping the returned future before it resolves makes the
+    /// other participants waiting fail with
+    /// [`BarrierError::ParticipantLost`].
+    ///
+    /// [`BarrierWaitResult`]: struct.BarrierWaitResult.html
+    /// [`BarrierError`]: enum.BarrierError.html
+    /// [`BarrierError::ParticipantLost`]: enum.BarrierError.html#variant.ParticipantLost
+    pub async fn wait(&self) -> Result<BarrierWaitResult, BarrierError> {
+        let answer = self
+            .inner
+            .child
+            .ask_anonymously(Arrive)
+            .map_err(|_| BarrierError::Stopped)?;
+        let (msg, _) = answer.await.map_err(|_| BarrierError::Stopped)?.extract();
+
+        match msg.downcast() {
+            Ok(Arrived::Leader) => Ok(BarrierWaitResult { leader: true }),
+            Ok(Arrived::Follower) => Ok(BarrierWaitResult { leader: false }),
+            Ok(Arrived::Lost) => Err(BarrierError::ParticipantLost),
+            Err(_) => Err(BarrierError::Stopped),
+        }
+    }
+}
+
+impl BarrierWaitResult {
+    /// Returns whether this participant is the "leader" of its
+    /// generation, which is true for exactly one of them (the
+    /// last one to arrive).
+    pub fn is_leader(&self) -> bool {
+        self.leader
+    }
+}
+
+// The future of the element keeping the barrier.
+async fn run(ctx: BastionContext, parties: usize) -> Result<(), ()> {
+    let mut waiting: Vec<AnswerSender> = Vec::with_capacity(parties);
+
+    loop {
+        let lost = future::poll_fn(|ctx| {
+            for sender in waiting.iter_mut() {
+                if sender.poll_canceled(ctx).is_ready() {
+                    return Poll::Ready(());
+                }
+            }
+
+            Poll::Pending
+        });
+        let msg = match future::select(Box::pin(ctx.recv()), lost).await {
+            Either::Left((msg, _)) => Some(msg?),
+            Either::Right(_) => None,
+        };
+
+        let msg = match msg {
+            Some(msg) => msg,
+            None => {
+                debug!("Barrier: A participant stopped waiting.");
+                for sender in waiting.drain(..) {
+                    sender.send(Arrived::Lost, ctx.signature()).ok();
+                }
+
+                continue;
+            }
+        };
+
+        let (mut msg, _) = msg.extract();
+        if !msg.is::<Arrive>() {
+            continue;
+        }
+
+        if let Some(sender) = msg.take_sender() {
+            waiting.push(sender);
+        }
+
+        if waiting.len() == parties {
+            trace!("Barrier: Releasing {} participants.", parties);
+            let leader = waiting.pop().unwrap();
+            for sender in waiting.drain(..) {
+                sender.send(Arrived::Follower, ctx.signature()).ok();
+            }
+
+            leader.send(Arrived::Leader, ctx.signature()).ok();
+        }
+    }
+}
+
+impl Drop for BarrierInner {
+    fn drop(&mut self) {
+        self.children.stop().ok();
+    }
+}
+
+impl Display for BarrierError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            BarrierError::ParticipantLost => {
+                fmt.write_str("a participant stopped waiting at the barrier")
+            }
+            BarrierError::Stopped => fmt.write_str("the barrier stopped"),
+        }
+    }
+}
+
+impl Error for BarrierError {}
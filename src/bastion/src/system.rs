@@ -1,12 +1,26 @@
 use crate::broadcast::{Broadcast, Parent, Sender};
 use crate::children_ref::ChildrenRef;
+use crate::config::{Config, ConfigChange, RuntimeConfig};
 use crate::context::{BastionContext, BastionId, NIL_ID};
+use crate::correlation::ReplyBroker;
+use crate::dead_letters::{DeadLetter, Handler, Summarizer};
 use crate::dispatcher::GlobalDispatcher;
 use crate::envelope::Envelope;
+use crate::errors::BuilderError;
+use crate::event::Events;
+use crate::history::History;
 use crate::message::{BastionMessage, Deployment};
+use crate::panic_hook;
 use crate::path::{BastionPath, BastionPathElement};
+use crate::reactor;
+use crate::registry::Registry;
+use crate::state_cell::States;
 use crate::supervisor::{Supervisor, SupervisorRef};
-use bastion_executor::pool;
+use crate::tree::Nodes;
+use bastion_executor::affinity::AffinityGroup;
+use bastion_executor::deterministic;
+use bastion_executor::pool::{self, ProcClass};
+use futures::channel::oneshot;
 use futures::prelude::*;
 use futures::stream::FuturesUnordered;
 use futures::{pending, poll};
@@ -14,11 +28,24 @@ use fxhash::{FxHashMap, FxHashSet};
 use lazy_static::lazy_static;
 use lightproc::prelude::*;
 use qutex::Qutex;
-use std::sync::{Arc, Condvar, Mutex};
+use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
 use std::task::Poll;
+use std::time::Duration;
 
 lazy_static! {
-    pub(crate) static ref SYSTEM: GlobalSystem = System::init();
+    pub(crate) static ref SYSTEM: CurrentSystem = CurrentSystem::new();
+}
+
+// The system currently used, replaced by a new one when
+// `Bastion::init` is called again after it stopped.
+pub(crate) struct CurrentSystem {
+    // NOTE: the systems are leaked because references to them
+    //      might be held forever (e.g. `Bastion::reply_broker`),
+    //      which only happens once per restart of the system.
+    current: RwLock<Option<&'static GlobalSystem>>,
+    tree: Nodes,
 }
 
 pub(crate) struct GlobalSystem {
@@ -29,7 +56,29 @@ pub(crate) struct GlobalSystem {
     handle: Qutex<Option<RecoverableHandle<()>>>,
     running: Mutex<bool>,
     stopping_cvar: Condvar,
+    // Notified once the system stopped (see `Bastion::stopped`).
+    stopped: Mutex<Vec<oneshot::Sender<()>>>,
     dispatcher: GlobalDispatcher,
+    reply_broker: ReplyBroker,
+    events: Events,
+    registry: Registry,
+    // The affinity groups of the children groups, by label (see
+    // `Children::with_affinity_group`), which are placed again once
+    // the system is initialized again.
+    affinity_groups: Mutex<FxHashMap<String, AffinityGroup>>,
+    states: Arc<States>,
+    // The configuration the system was initialized with, whose
+    // runtime settings are replaced by `runtime` once reloaded.
+    config: Config,
+    runtime: RwLock<Runtime>,
+    // Whether `Bastion::stop` or `Bastion::kill` was called.
+    stopping: AtomicBool,
+    // Whether the system was degraded and `Bastion::recover`
+    // wasn't called since.
+    degraded: AtomicBool,
+    // The children groups that terminated, if the system keeps
+    // a history of them.
+    history: History,
 }
 
 #[derive(Debug)]
@@ -41,6 +90,19 @@ struct System {
     waiting: FuturesUnordered<RecoverableHandle<Supervisor>>,
     pre_start_msgs: Vec<Envelope>,
     started: bool,
+    // The identifier of the supervisor of the system's utility
+    // groups (the dead letters and the reactor), which is stopped
+    // after the other ones.
+    utilities: BastionId,
+}
+
+#[derive(Debug)]
+// The runtime settings and how the dead letters are summarized
+// according to them (unless they carry their full payload),
+// replaced together once reloaded.
+struct Runtime {
+    config: RuntimeConfig,
+    summarizer: Option<Arc<Summarizer>>,
 }
 
 impl GlobalSystem {
@@ -49,13 +111,26 @@ impl GlobalSystem {
         supervisor: SupervisorRef,
         dead_letters: ChildrenRef,
         handle: RecoverableHandle<()>,
+        config: &Config,
     ) -> Self {
         let handle = Some(handle);
         let handle = Qutex::new(handle);
         let path = Arc::new(BastionPath::root());
         let running = Mutex::new(true);
         let stopping_cvar = Condvar::new();
+        let stopped = Mutex::new(Vec::new());
         let dispatcher = GlobalDispatcher::new();
+        let (max_pending, ttl) = config.reply_broker_limits();
+        let reply_broker = ReplyBroker::new(max_pending, ttl);
+        let events = Events::new();
+        let registry = Registry::new();
+        let affinity_groups = Mutex::new(FxHashMap::default());
+        let states = Arc::new(States::new());
+        let runtime = RwLock::new(Runtime::new(config, config.runtime().clone()));
+        let config = config.clone();
+        let stopping = AtomicBool::new(false);
+        let degraded = AtomicBool::new(false);
+        let history = History::new(config.group_history());
 
         GlobalSystem {
             sender,
@@ -65,7 +140,18 @@ impl GlobalSystem {
             handle,
             running,
             stopping_cvar,
+            stopped,
             dispatcher,
+            reply_broker,
+            events,
+            registry,
+            affinity_groups,
+            states,
+            config,
+            runtime,
+            stopping,
+            degraded,
+            history,
         }
     }
 
@@ -81,6 +167,33 @@ impl GlobalSystem {
         &self.dead_letters
     }
 
+    pub(crate) fn summarizer(&self) -> Option<Arc<Summarizer>> {
+        // FIXME: panics?
+        self.runtime.read().unwrap().summarizer.clone()
+    }
+
+    pub(crate) fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub(crate) fn default_ask_timeout(&self) -> Option<Duration> {
+        // FIXME: panics?
+        self.runtime.read().unwrap().config.default_ask_timeout()
+    }
+
+    // Replaces the runtime settings, returning those that changed.
+    pub(crate) fn reload(&self, runtime: RuntimeConfig) -> Vec<ConfigChange> {
+        // FIXME: panics?
+        // Both settings are swapped at once, so that the summarizer
+        // is never the one of other runtime settings.
+        let runtime = Runtime::new(&self.config, runtime);
+        let mut current = self.runtime.write().unwrap();
+        let changes = current.config.diff(&runtime.config);
+        *current = runtime;
+
+        changes
+    }
+
     pub(crate) fn handle(&self) -> Qutex<Option<RecoverableHandle<()>>> {
         self.handle.clone()
     }
@@ -93,12 +206,92 @@ impl GlobalSystem {
         &self.dispatcher
     }
 
+    pub(crate) fn reply_broker(&self) -> &ReplyBroker {
+        &self.reply_broker
+    }
+
+    pub(crate) fn events(&self) -> &Events {
+        &self.events
+    }
+
+    pub(crate) fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    // Returns the affinity group with this label, creating it if
+    // there isn't any yet.
+    pub(crate) fn affinity_group(&self, label: &str) -> AffinityGroup {
+        // FIXME: panics?
+        let mut groups = self.affinity_groups.lock().unwrap();
+        groups
+            .entry(label.to_string())
+            .or_insert_with(|| AffinityGroup::new(label))
+            .clone()
+    }
+
+    pub(crate) fn history(&self) -> &History {
+        &self.history
+    }
+
+    pub(crate) fn states(&self) -> &Arc<States> {
+        &self.states
+    }
+
+    pub(crate) fn notify_stopping(&self) {
+        self.stopping.store(true, Ordering::SeqCst);
+    }
+
+    fn is_stopping(&self) -> bool {
+        self.stopping.load(Ordering::SeqCst)
+    }
+
+    #[cfg(feature = "supervision")]
+    // Returns whether the system wasn't already degraded.
+    pub(crate) fn notify_degraded(&self) -> bool {
+        !self.degraded.swap(true, Ordering::SeqCst)
+    }
+
+    // Returns whether the system was degraded.
+    pub(crate) fn notify_recovered(&self) -> bool {
+        self.degraded.swap(false, Ordering::SeqCst)
+    }
+
+    pub(crate) fn is_degraded(&self) -> bool {
+        self.degraded.load(Ordering::SeqCst)
+    }
+
+    fn is_stopped(&self) -> bool {
+        // FIXME: panics
+        !*self.running.lock().unwrap()
+    }
+
     pub(crate) fn notify_stopped(&self) {
+        panic_hook::restore();
         // FIXME: panics
-        *self.running.lock().unwrap() = false;
+        let mut running = self.running.lock().unwrap();
+        *running = false;
+        for stopped in self.stopped.lock().unwrap().drain(..) {
+            stopped.send(()).ok();
+        }
+
+        drop(running);
         self.stopping_cvar.notify_all();
     }
 
+    // Returns a receiver notified once the system stopped, unless
+    // it already did.
+    pub(crate) fn on_stopped(&self) -> Option<oneshot::Receiver<()>> {
+        // FIXME: panics
+        let running = self.running.lock().unwrap();
+        if !*running {
+            return None;
+        }
+
+        let (sender, recver) = oneshot::channel();
+        self.stopped.lock().unwrap().push(sender);
+        Some(recver)
+    }
+
     pub(crate) fn wait_until_stopped(&self) {
         // FIXME: panics
         let mut running = self.running.lock().unwrap();
@@ -106,11 +299,109 @@ impl GlobalSystem {
             running = self.stopping_cvar.wait(running).unwrap();
         }
     }
+
+    #[cfg(feature = "signals")]
+    // Returns whether the system stopped before the timeout.
+    pub(crate) fn wait_until_stopped_timeout(&self, timeout: Duration) -> bool {
+        // FIXME: panics
+        let running = self.running.lock().unwrap();
+        let (running, _) = self
+            .stopping_cvar
+            .wait_timeout_while(running, timeout, |running| *running)
+            .unwrap();
+
+        !*running
+    }
+}
+
+impl CurrentSystem {
+    fn new() -> Self {
+        let current = RwLock::new(None);
+        let tree = Nodes::default();
+
+        CurrentSystem { current, tree }
+    }
+
+    // Initializes a new system if there isn't any or if the current
+    // one is stopping (once it stopped) or stopped.
+    pub(crate) fn init(&self, config: &Config) {
+        loop {
+            // FIXME: panics
+            let mut current = self.current.write().unwrap();
+            match *current {
+                Some(system) if !system.is_stopping() && !system.is_stopped() => return,
+                Some(system) if !system.is_stopped() => {
+                    debug!("System: Waiting for the stopping system to stop.");
+                    drop(current);
+                    system.wait_until_stopped();
+                }
+                Some(_) => {
+                    info!("System: Resetting the stopped system.");
+                    *current = Some(Box::leak(Box::new(System::init(config))));
+                    return;
+                }
+                None => {
+                    *current = Some(Box::leak(Box::new(System::init(config))));
+                    return;
+                }
+            }
+        }
+    }
+
+    pub(crate) fn tree(&self) -> &Nodes {
+        &self.tree
+    }
+
+    pub(crate) fn is_initialized(&self) -> bool {
+        // FIXME: panics
+        self.current.read().unwrap().is_some()
+    }
+
+    // Returns an error if the system wasn't initialized or if it is
+    // stopping or stopped and wasn't initialized again since.
+    pub(crate) fn check_running(&self) -> Result<(), BuilderError> {
+        // FIXME: panics
+        match *self.current.read().unwrap() {
+            None => Err(BuilderError::NotInitialized),
+            Some(system) if system.is_stopping() || system.is_stopped() => {
+                Err(BuilderError::ShuttingDown)
+            }
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+impl Deref for CurrentSystem {
+    type Target = GlobalSystem;
+
+    fn deref(&self) -> &GlobalSystem {
+        // FIXME: panics
+        if let Some(system) = *self.current.read().unwrap() {
+            return system;
+        }
+
+        // The system is used before being initialized.
+        self.init(&Config::default());
+        self.current.read().unwrap().unwrap()
+    }
+}
+
+impl Runtime {
+    fn new(config: &Config, runtime: RuntimeConfig) -> Self {
+        let summarizer = config.summarizer(&runtime).map(Arc::new);
+        Runtime {
+            config: runtime,
+            summarizer,
+        }
+    }
 }
 
 impl System {
-    fn init() -> GlobalSystem {
+    fn init(config: &Config) -> GlobalSystem {
         info!("System: Initializing.");
+        // The supervisors and groups are all launched before any of
+        // them runs.
+        let _hold = deterministic::hold();
         let parent = Parent::none();
         let bcast = Broadcast::new_root(parent);
         let launched = FxHashMap::default();
@@ -118,6 +409,7 @@ impl System {
         let waiting = FuturesUnordered::new();
         let pre_start_msgs = Vec::new();
         let started = false;
+        let utilities = BastionId::new();
 
         let sender = bcast.sender().clone();
 
@@ -128,13 +420,14 @@ impl System {
             waiting,
             pre_start_msgs,
             started,
+            utilities,
         };
 
         debug!("System: Creating the system supervisor.");
         let parent = Parent::system();
         let bcast = Broadcast::new(parent, BastionPathElement::Supervisor(NIL_ID));
 
-        let supervisor = Supervisor::system(bcast);
+        let supervisor = Supervisor::system(bcast, config);
         let supervisor_ref = supervisor.as_ref();
 
         let msg = BastionMessage::deploy_supervisor(supervisor);
@@ -145,14 +438,32 @@ impl System {
         );
         system.bcast.send_self(env);
 
+        debug!("System: Creating the utilities supervisor.");
+        let parent = Parent::system();
+        let element = BastionPathElement::Supervisor(system.utilities.clone());
+        let utilities = Supervisor::utilities(Broadcast::new(parent, element));
+        let utilities_ref = utilities.as_ref();
+
+        let msg = BastionMessage::deploy_supervisor(utilities);
+        let env = Envelope::new(
+            msg,
+            system.bcast.path().clone(),
+            system.bcast.sender().clone(),
+        );
+        system.bcast.send_self(env);
+
         debug!("System: Launching.");
         let stack = system.stack();
-        let handle = pool::spawn(system.run(), stack);
+        let handle = pool::spawn_with_class(system.run(), stack, ProcClass::Management);
 
+        let handler = config.dead_letter_handler().cloned();
         let dead_letters_ref =
-            Self::spawn_dead_letters(&supervisor_ref).expect("Can't spawn dead letters");
+            Self::spawn_dead_letters(&utilities_ref, handler).expect("Can't spawn dead letters");
+        utilities_ref
+            .children(|children| children.unwrapped().with_exec(reactor::run))
+            .expect("Can't spawn the reactor");
 
-        GlobalSystem::new(sender, supervisor_ref, dead_letters_ref, handle)
+        GlobalSystem::new(sender, supervisor_ref, dead_letters_ref, handle, config)
     }
 
     fn stack(&self) -> ProcStack {
@@ -160,12 +471,25 @@ impl System {
         ProcStack::default()
     }
 
-    fn spawn_dead_letters(root_sv: &SupervisorRef) -> Result<ChildrenRef, ()> {
-        root_sv.children_with_id(NIL_ID, |children| {
-            children.with_exec(|ctx: BastionContext| async move {
-                loop {
-                    let smsg = ctx.recv().await?;
-                    debug!("Received dead letter: {:?}", smsg);
+    fn spawn_dead_letters(
+        utilities: &SupervisorRef,
+        handler: Option<Handler>,
+    ) -> Result<ChildrenRef, BuilderError> {
+        utilities.children_with_id(NIL_ID, |children| {
+            children.unwrapped().with_exec(move |ctx: BastionContext| {
+                let handler = handler.clone();
+                async move {
+                    loop {
+                        let smsg = ctx.recv().await?;
+                        debug!("Received dead letter: {:?}", smsg);
+                        if !smsg.msg.provenance().is_empty() {
+                            debug!("The dead letter was caused by: {:?}", smsg.msg.provenance());
+                        }
+
+                        if let Some(handler) = &handler {
+                            handler.handle(DeadLetter::new(smsg));
+                        }
+                    }
                 }
             })
         })
@@ -177,7 +501,7 @@ impl System {
         supervisor.callbacks().before_restart();
 
         let parent = Parent::system();
-        let bcast = if supervisor.id() == &NIL_ID {
+        let bcast = if supervisor.id() == &NIL_ID || supervisor.id() == &self.utilities {
             None
         } else {
             Some(Broadcast::new(
@@ -198,12 +522,29 @@ impl System {
     }
 
     async fn stop(&mut self) -> Vec<Supervisor> {
-        self.bcast.stop_children();
+        let utilities = self.launched.remove(&self.utilities);
+        self.bcast.stop_children_except(&self.utilities);
 
         for (_, launched) in self.launched.drain() {
             self.waiting.push(launched);
         }
 
+        let mut supervisors = self.wait_stopped().await;
+        // The utilities are stopped once every other supervisor is,
+        // for their elements to be woken and their late dead letters
+        // to be received until they are stopped.
+        if let Some(utilities) = utilities {
+            self.bcast.stop_child(&self.utilities);
+            self.waiting.push(utilities);
+            supervisors.extend(self.wait_stopped().await);
+        }
+
+        supervisors
+    }
+
+    // Returns the supervisors that stopped once every one that was
+    // waited for did.
+    async fn wait_stopped(&mut self) -> Vec<Supervisor> {
         let mut supervisors = Vec::new();
         loop {
             match poll!(&mut self.waiting.next()) {
@@ -268,6 +609,7 @@ impl System {
             }
             // FIXME
             Deployment::Children(_) => unimplemented!(),
+            Deployment::Child { .. } => unreachable!(),
         }
     }
 
@@ -304,25 +646,54 @@ impl System {
                     supervisor.callbacks().after_stop();
                 }
 
+                // Once every children group stopped.
+                SYSTEM.states().drop_all();
+
                 return Err(());
             }
+            // Only the children groups are stopped within a timeout
+            // or quiesced.
+            Envelope {
+                msg: BastionMessage::StopWithin(_),
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Quiesce(_),
+                ..
+            } => unreachable!(),
             Envelope {
                 msg: BastionMessage::Kill,
                 ..
             } => {
                 info!("System: Killing.");
                 self.kill().await;
+                SYSTEM.states().drop_all();
 
                 return Err(());
             }
             Envelope {
                 msg: BastionMessage::Deploy(deployment),
                 ..
-            } => self.deploy(deployment).await,
+            } => self.deploy(*deployment).await,
             Envelope {
                 msg: BastionMessage::Prune { id },
                 ..
             } => self.prune_supervised_object(id).await,
+            // The children groups are only deployed by supervisors.
+            Envelope {
+                msg: BastionMessage::CancelTask(_),
+                ..
+            } => unreachable!(),
+            // Only the children groups are scaled.
+            Envelope {
+                msg: BastionMessage::Scale { .. },
+                ..
+            } => unreachable!(),
+            // Only the children groups route messages.
+            Envelope {
+                msg: BastionMessage::SetRouting { .. },
+                ..
+            } => unreachable!(),
             // FIXME
             Envelope {
                 msg: BastionMessage::SuperviseWith(_),
@@ -351,6 +722,22 @@ impl System {
                 msg: BastionMessage::RestartSubtree,
                 ..
             } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Degrade { .. },
+                ..
+            } => {
+                warn!("System: Degrading.");
+                // The utilities keep running for the groups that are
+                // kept.
+                self.bcast.send_children_except(&self.utilities, env);
+            }
+            Envelope {
+                msg: BastionMessage::Recover,
+                ..
+            } => {
+                info!("System: Recovering.");
+                self.bcast.send_children(env);
+            }
             Envelope {
                 msg: BastionMessage::RestoreChild { .. },
                 ..
@@ -375,6 +762,10 @@ impl System {
                 msg: BastionMessage::Faulted { id, .. },
                 ..
             } => self.restart_supervised_object(id),
+            Envelope {
+                msg: BastionMessage::MigrateChild { .. },
+                ..
+            } => unreachable!(),
         }
 
         Ok(())
@@ -0,0 +1,99 @@
+//!
+//! Functions running futures on the executor, equivalent to the
+//! [`run!`], [`spawn!`] and [`blocking!`] macros for the code that
+//! doesn't use them (see [`prelude_v2`]).
+//!
+//! [`run!`]: ../macro.run.html
+//! [`spawn!`]: ../macro.spawn.html
+//! [`blocking!`]: ../macro.blocking.html
+//! [`prelude_v2`]: ../prelude_v2/index.html
+use bastion_executor::{blocking, pool};
+use lightproc::proc_stack::ProcStack;
+use std::future::Future;
+
+pub use lightproc::recoverable_handle::RecoverableHandle;
+
+/// Blocks the current thread until `future` resolves, returning
+/// its output (like [`run!`] does).
+///
+/// # Arguments
+///
+/// * `future` - The future to run.
+///
+/// # Example
+///
+/// ```rust
+/// use bastion::prelude_v2::*;
+///
+/// let doubled = run(async { 21 * 2 });
+/// assert_eq!(doubled, 42);
+/// ```
+///
+/// [`run!`]: ../macro.run.html
+pub fn run<F, T>(future: F) -> T
+where
+    F: Future<Output = T>,
+{
+    bastion_executor::run::run(future, ProcStack::default())
+}
+
+/// Spawns `future` onto the executor (like [`spawn!`] does),
+/// returning a [`RecoverableHandle`] resolving to its output, or
+/// to `None` if it panicked.
+///
+/// # Arguments
+///
+/// * `future` - The future to spawn.
+///
+/// # Example
+///
+/// ```rust
+/// use bastion::prelude_v2::*;
+///
+/// let handle = spawn(async { 21 * 2 });
+/// assert_eq!(run(handle), Some(42));
+/// ```
+///
+/// [`spawn!`]: ../macro.spawn.html
+/// [`RecoverableHandle`]: struct.RecoverableHandle.html
+pub fn spawn<F, T>(future: F) -> RecoverableHandle<T>
+where
+    F: Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    pool::spawn(future, ProcStack::default())
+}
+
+/// Spawns `future` onto the executor's threads dedicated to
+/// blocking tasks (like [`blocking!`] does), returning a
+/// [`RecoverableHandle`] resolving to its output, or to `None` if
+/// it panicked.
+///
+/// # Arguments
+///
+/// * `future` - The future to spawn, which can block the thread it
+///     runs on.
+///
+/// # Example
+///
+/// ```rust
+/// use bastion::prelude_v2::*;
+/// use std::thread;
+/// use std::time::Duration;
+///
+/// let handle = spawn_blocking(async {
+///     thread::sleep(Duration::from_millis(10));
+///     42
+/// });
+/// assert_eq!(run(handle), Some(42));
+/// ```
+///
+/// [`blocking!`]: ../macro.blocking.html
+/// [`RecoverableHandle`]: struct.RecoverableHandle.html
+pub fn spawn_blocking<F, T>(future: F) -> RecoverableHandle<T>
+where
+    F: Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    blocking::spawn_blocking(future, ProcStack::default())
+}
@@ -0,0 +1,922 @@
+//!
+//! Snapshots of the supervision tree, as returned by
+//! [`Bastion::tree`], which can be exported as Graphviz graphs or
+//! as JSON documents.
+//!
+//! The snapshots are only available with the `introspection`
+//! feature (which is enabled by default).
+//!
+//! [`Bastion::tree`]: ../struct.Bastion.html#method.tree
+use crate::broadcast::Parent;
+use crate::children_ref::ChildrenRef;
+#[cfg(feature = "introspection")]
+use crate::children_ref::{GroupPolicies, GroupState};
+use crate::context::BastionId;
+#[cfg(feature = "introspection")]
+use crate::event::FaultReason;
+#[cfg(feature = "introspection")]
+use crate::history::TerminatedGroup;
+#[cfg(feature = "introspection")]
+use crate::mailbox::OverflowPolicy;
+#[cfg(feature = "introspection")]
+use crate::routing::Routing;
+use crate::supervisor::SupervisionStrategy;
+#[cfg(feature = "introspection")]
+use crate::supervisor::{ActorRestartStrategy, RestartPolicy};
+use crate::system::SYSTEM;
+use fxhash::FxHashMap;
+#[cfg(feature = "introspection")]
+use std::fmt::Write;
+use std::sync::Mutex;
+#[cfg(feature = "introspection")]
+use std::time::UNIX_EPOCH;
+
+#[cfg(feature = "introspection")]
+/// The version of the schema of the documents returned by
+/// [`TreeSnapshot::to_json`], increased whenever a field is
+/// renamed or removed or its meaning changes.
+///
+/// [`TreeSnapshot::to_json`]: struct.TreeSnapshot.html#method.to_json
+pub const SCHEMA_VERSION: u32 = 3;
+
+// The supervisors and children groups currently launched, held
+// by the current system (rather than by the system being used,
+// whose supervisors are launched while it is being initialized).
+pub(crate) type Nodes = Mutex<FxHashMap<BastionId, Node>>;
+
+#[derive(Debug)]
+// Only the snapshots read everything a node records.
+#[cfg_attr(not(feature = "introspection"), allow(dead_code))]
+pub(crate) struct Node {
+    parent: Option<BastionId>,
+    kind: NodeKind,
+}
+
+#[derive(Debug)]
+#[cfg_attr(not(feature = "introspection"), allow(dead_code))]
+enum NodeKind {
+    Supervisor {
+        strategy: SupervisionStrategy,
+        utilities: bool,
+    },
+    Group {
+        name: Option<String>,
+        children: ChildrenRef,
+    },
+}
+
+#[cfg(feature = "introspection")]
+#[derive(Debug, Clone)]
+/// A snapshot of the supervision tree, as returned by
+/// [`Bastion::tree`].
+///
+/// The supervisors, groups and elements are sorted (the groups by
+/// name, then all of them by identifier), so that exporting the
+/// same tree twice gives the same output.
+///
+/// [`Bastion::tree`]: ../struct.Bastion.html#method.tree
+pub struct TreeSnapshot {
+    supervisors: Vec<SupervisorNode>,
+    system: Option<SupervisorNode>,
+    // The system's history of terminated groups, if it keeps one.
+    history: Option<Vec<TerminatedGroup>>,
+}
+
+#[cfg(feature = "introspection")]
+#[derive(Debug, Clone)]
+/// A supervisor of a [`TreeSnapshot`], along with the supervisors
+/// and children groups it supervises.
+///
+/// [`TreeSnapshot`]: struct.TreeSnapshot.html
+pub struct SupervisorNode {
+    id: BastionId,
+    strategy: SupervisionStrategy,
+    supervisors: Vec<SupervisorNode>,
+    groups: Vec<GroupNode>,
+}
+
+#[cfg(feature = "introspection")]
+#[derive(Debug, Clone)]
+/// A children group of a [`TreeSnapshot`], along with its
+/// elements.
+///
+/// [`TreeSnapshot`]: struct.TreeSnapshot.html
+pub struct GroupNode {
+    id: BastionId,
+    name: Option<String>,
+    state: GroupState,
+    policies: GroupPolicies,
+    routing: Routing,
+    launched: usize,
+    initialized: usize,
+    elems: Vec<ElemNode>,
+}
+
+#[cfg(feature = "introspection")]
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// An element of a children group of a [`TreeSnapshot`].
+///
+/// [`TreeSnapshot`]: struct.TreeSnapshot.html
+pub struct ElemNode {
+    id: BastionId,
+    generation: usize,
+}
+
+pub(crate) fn register_supervisor(
+    id: &BastionId,
+    parent: &Parent,
+    strategy: &SupervisionStrategy,
+    utilities: bool,
+) {
+    let kind = NodeKind::Supervisor {
+        strategy: strategy.clone(),
+        utilities,
+    };
+
+    register(id, parent, kind);
+}
+
+pub(crate) fn register_group(
+    id: &BastionId,
+    parent: &Parent,
+    name: Option<&String>,
+    children: ChildrenRef,
+) {
+    let kind = NodeKind::Group {
+        name: name.cloned(),
+        children,
+    };
+
+    register(id, parent, kind);
+}
+
+fn register(id: &BastionId, parent: &Parent, kind: NodeKind) {
+    let parent = match parent {
+        Parent::Supervisor(supervisor) => Some(supervisor.id().clone()),
+        _ => None,
+    };
+
+    trace!("Tree: Registering {}.", id);
+    // FIXME: panics?
+    SYSTEM
+        .tree()
+        .lock()
+        .unwrap()
+        .insert(id.clone(), Node { parent, kind });
+}
+
+pub(crate) fn set_strategy(id: &BastionId, new: &SupervisionStrategy) {
+    // FIXME: panics?
+    if let Some(node) = SYSTEM.tree().lock().unwrap().get_mut(id) {
+        if let NodeKind::Supervisor { strategy, .. } = &mut node.kind {
+            *strategy = new.clone();
+        }
+    }
+}
+
+pub(crate) fn unregister(id: &BastionId) {
+    trace!("Tree: Unregistering {}.", id);
+    // FIXME: panics?
+    SYSTEM.tree().lock().unwrap().remove(id);
+}
+
+// Returns the children group with this identifier, as it was
+// registered when it was last launched (i.e. after it was restarted
+// if it was).
+pub(crate) fn group(id: &BastionId) -> Option<ChildrenRef> {
+    // FIXME: panics?
+    match &SYSTEM.tree().lock().unwrap().get(id)?.kind {
+        NodeKind::Group { children, .. } => Some(children.clone()),
+        NodeKind::Supervisor { .. } => None,
+    }
+}
+
+#[cfg(feature = "signals")]
+// Returns the children groups which aren't part of the system.
+pub(crate) fn groups() -> Vec<ChildrenRef> {
+    // FIXME: panics?
+    let tree = SYSTEM.tree().lock().unwrap();
+
+    tree.values()
+        .filter_map(|node| match &node.kind {
+            NodeKind::Group { children, .. } if !is_system(&tree, node) => Some(children.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(feature = "signals")]
+// Returns whether the node is supervised by the utilities
+// supervisor.
+fn is_system<'a>(tree: &'a FxHashMap<BastionId, Node>, mut node: &'a Node) -> bool {
+    while let Some(parent) = node.parent.as_ref().and_then(|id| tree.get(id)) {
+        if let NodeKind::Supervisor {
+            utilities: true, ..
+        } = parent.kind
+        {
+            return true;
+        }
+
+        node = parent;
+    }
+
+    false
+}
+
+#[cfg(feature = "introspection")]
+pub(crate) fn snapshot() -> TreeSnapshot {
+    // FIXME: panics?
+    let tree = SYSTEM.tree().lock().unwrap();
+
+    let mut supervisors = Vec::new();
+    let mut system = None;
+    for (id, node) in tree.iter() {
+        if let (None, NodeKind::Supervisor { utilities, .. }) = (&node.parent, &node.kind) {
+            let supervisor = SupervisorNode::new(&tree, id);
+            if *utilities {
+                system = Some(supervisor);
+            } else {
+                supervisors.push(supervisor);
+            }
+        }
+    }
+    supervisors.sort_by_key(|supervisor| supervisor.id.to_string());
+
+    let history = SYSTEM.history();
+    let history = if history.is_enabled() {
+        Some(history.entries())
+    } else {
+        None
+    };
+
+    TreeSnapshot {
+        supervisors,
+        system,
+        history,
+    }
+}
+
+#[cfg(feature = "introspection")]
+impl TreeSnapshot {
+    /// Returns the supervisors at the top of the tree: the system's
+    /// root supervisor (whose identifier is [`NIL_ID`], which
+    /// supervises the groups created using [`Bastion::children`])
+    /// followed by the supervisors created using
+    /// [`Bastion::supervisor`].
+    ///
+    /// [`NIL_ID`]: ../context/constant.NIL_ID.html
+    /// [`Bastion::children`]: ../struct.Bastion.html#method.children
+    /// [`Bastion::supervisor`]: ../struct.Bastion.html#method.supervisor
+    pub fn supervisors(&self) -> &[SupervisorNode] {
+        &self.supervisors
+    }
+
+    /// Returns the supervisor of the system's utility groups (the
+    /// dead letters and the reactor, see [`Bastion::health`]), if
+    /// it is running.
+    ///
+    /// [`Bastion::health`]: ../struct.Bastion.html#method.health
+    pub fn system(&self) -> Option<&SupervisorNode> {
+        self.system.as_ref()
+    }
+
+    /// Returns the children groups that terminated, oldest first,
+    /// as returned by [`Bastion::terminated_groups`] when the
+    /// snapshot was taken, if the system keeps a history of them
+    /// (see [`Config::with_group_history`]).
+    ///
+    /// [`Bastion::terminated_groups`]: ../struct.Bastion.html#method.terminated_groups
+    /// [`Config::with_group_history`]: ../struct.Config.html#method.with_group_history
+    pub fn history(&self) -> Option<&[TerminatedGroup]> {
+        self.history.as_deref()
+    }
+
+    /// Returns the tree as a Graphviz graph, whose supervisors are
+    /// drawn as boxes and groups as ellipses (colored depending on
+    /// their state), the system's utilities being grouped in a
+    /// `system` cluster.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    /// let dot = Bastion::tree().to_dot();
+    /// assert!(dot.starts_with("digraph bastion {"));
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph bastion {\n");
+        for supervisor in &self.supervisors {
+            supervisor.write_dot(&mut dot, "    ");
+        }
+
+        if let Some(system) = &self.system {
+            dot.push_str("    subgraph cluster_system {\n");
+            dot.push_str("        label=\"system\";\n");
+            system.write_dot(&mut dot, "        ");
+            dot.push_str("    }\n");
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Returns the tree as a JSON document, following the schema
+    /// whose version is [`SCHEMA_VERSION`]:
+    ///
+    /// ```json
+    /// {
+    ///   "version": 3,
+    ///   "supervisors": [<supervisor>],
+    ///   "system": <supervisor> | null,
+    ///   "history": [<terminated group>]
+    /// }
+    /// ```
+    ///
+    /// where a supervisor is
+    /// `{"id", "strategy", "supervisors": [<supervisor>], "groups": [<group>]}`
+    /// (its strategy being `"one_for_one"`, `"one_for_all"` or
+    /// `"rest_for_one"`), a group is
+    /// `{"id", "name", "state", "restart_attempt", "policies", "routing", "pinned_to", "elem_count", "launched", "initialized", "elems": [<elem>]}`
+    /// (its state and routing being the snake-cased names of its
+    /// [`GroupState`] and [`Routing`], and `pinned_to` the element
+    /// the messages are pinned to, if they are),
+    /// its policies are
+    /// `{"restart_policy", "max_tries", "backoff", "backoff_ms", "backoff_multiplier", "restart_window", "restart_backoff", "pre_start_watermark", "mailbox_capacity", "overflow_policy", "drain_timeout_ms", "requeue_on_restart", "preset"}`
+    /// and an element is `{"id", "generation"}` (its generation
+    /// being the number of times it was restarted).
+    ///
+    /// The `history` is only part of the document if the system
+    /// keeps a history of the terminated groups (see
+    /// [`Config::with_group_history`]), a terminated group being
+    /// `{"id", "name", "state", "elems": [<id>], "faults": [{"reason", "count"}], "restarts", "processed", "rejected", "lifetime_ms", "terminated_at_ms"}`
+    /// (its faults' reasons being the snake-cased names of their
+    /// [`FaultReason`], and `terminated_at_ms` the number of
+    /// milliseconds elapsed since the Unix epoch when it
+    /// terminated).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// use bastion::tree::SCHEMA_VERSION;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    /// let json = Bastion::tree().to_json();
+    /// assert!(json.starts_with(&format!("{{\"version\":{},", SCHEMA_VERSION)));
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`SCHEMA_VERSION`]: constant.SCHEMA_VERSION.html
+    /// [`GroupState`]: ../children_ref/enum.GroupState.html
+    /// [`Routing`]: ../routing/enum.Routing.html
+    /// [`Config::with_group_history`]: ../struct.Config.html#method.with_group_history
+    /// [`FaultReason`]: ../event/enum.FaultReason.html
+    pub fn to_json(&self) -> String {
+        let mut json = format!("{{\"version\":{},\"supervisors\":[", SCHEMA_VERSION);
+        for (index, supervisor) in self.supervisors.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+
+            supervisor.write_json(&mut json);
+        }
+
+        json.push_str("],\"system\":");
+        match &self.system {
+            Some(system) => system.write_json(&mut json),
+            None => json.push_str("null"),
+        }
+
+        if let Some(history) = &self.history {
+            json.push_str(",\"history\":[");
+            for (index, group) in history.iter().enumerate() {
+                if index > 0 {
+                    json.push(',');
+                }
+
+                write_terminated(&mut json, group);
+            }
+
+            json.push(']');
+        }
+
+        json.push('}');
+        json
+    }
+}
+
+#[cfg(feature = "introspection")]
+impl SupervisorNode {
+    fn new(tree: &FxHashMap<BastionId, Node>, id: &BastionId) -> Self {
+        let strategy = match &tree[id].kind {
+            NodeKind::Supervisor { strategy, .. } => strategy.clone(),
+            NodeKind::Group { .. } => unreachable!(),
+        };
+
+        let mut supervisors = Vec::new();
+        let mut groups = Vec::new();
+        for (child, node) in tree.iter() {
+            if node.parent.as_ref() != Some(id) {
+                continue;
+            }
+
+            match &node.kind {
+                NodeKind::Supervisor { .. } => supervisors.push(SupervisorNode::new(tree, child)),
+                NodeKind::Group { name, children } => groups.push(GroupNode::new(name, children)),
+            }
+        }
+        supervisors.sort_by_key(|supervisor| supervisor.id.to_string());
+        groups.sort_by_key(|group| (group.name.clone(), group.id.to_string()));
+
+        SupervisorNode {
+            id: id.clone(),
+            strategy,
+            supervisors,
+            groups,
+        }
+    }
+
+    /// Returns the supervisor's identifier.
+    pub fn id(&self) -> &BastionId {
+        &self.id
+    }
+
+    /// Returns the strategy the supervisor uses to restart what it
+    /// supervises.
+    pub fn strategy(&self) -> &SupervisionStrategy {
+        &self.strategy
+    }
+
+    /// Returns the supervisors supervised by this one.
+    pub fn supervisors(&self) -> &[SupervisorNode] {
+        &self.supervisors
+    }
+
+    /// Returns the children groups supervised by the supervisor.
+    pub fn groups(&self) -> &[GroupNode] {
+        &self.groups
+    }
+
+    fn write_dot(&self, dot: &mut String, indent: &str) {
+        let _ = writeln!(
+            dot,
+            "{}\"{}\" [label=\"supervisor\\n{}\", shape=box];",
+            indent,
+            self.id,
+            strategy_name(&self.strategy)
+        );
+        for supervisor in &self.supervisors {
+            supervisor.write_dot(dot, indent);
+            let _ = writeln!(dot, "{}\"{}\" -> \"{}\";", indent, self.id, supervisor.id);
+        }
+
+        for group in &self.groups {
+            group.write_dot(dot, indent);
+            let _ = writeln!(dot, "{}\"{}\" -> \"{}\";", indent, self.id, group.id);
+        }
+    }
+
+    fn write_json(&self, json: &mut String) {
+        let _ = write!(
+            json,
+            "{{\"id\":\"{}\",\"strategy\":\"{}\",\"supervisors\":[",
+            self.id,
+            strategy_name(&self.strategy)
+        );
+        for (index, supervisor) in self.supervisors.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+
+            supervisor.write_json(json);
+        }
+
+        json.push_str("],\"groups\":[");
+        for (index, group) in self.groups.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+
+            group.write_json(json);
+        }
+
+        json.push_str("]}");
+    }
+}
+
+#[cfg(feature = "introspection")]
+impl GroupNode {
+    fn new(name: &Option<String>, children: &ChildrenRef) -> Self {
+        let mut elems = children
+            .elems()
+            .iter()
+            .map(|elem| ElemNode {
+                id: elem.id().clone(),
+                generation: elem.stats().restarts(),
+            })
+            .collect::<Vec<_>>();
+        elems.sort_by_key(|elem| elem.id.to_string());
+
+        GroupNode {
+            id: children.id().clone(),
+            name: name.clone(),
+            state: children.state(),
+            policies: children.policies(),
+            routing: children.routing(),
+            launched: children.launch().launched(),
+            initialized: children.launch().initialized(),
+            elems,
+        }
+    }
+
+    /// Returns the group's identifier.
+    pub fn id(&self) -> &BastionId {
+        &self.id
+    }
+
+    /// Returns the group's name, if it has one (see
+    /// [`Children::with_name`]).
+    ///
+    /// [`Children::with_name`]: ../children/struct.Children.html#method.with_name
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the state the group was in.
+    pub fn state(&self) -> GroupState {
+        self.state
+    }
+
+    /// Returns the policies the group's supervisor applies to it.
+    pub fn policies(&self) -> &GroupPolicies {
+        &self.policies
+    }
+
+    /// Returns how the group was routing the messages sent to one
+    /// of its elements (see [`ChildrenRef::set_routing`]).
+    ///
+    /// [`ChildrenRef::set_routing`]: ../children_ref/struct.ChildrenRef.html#method.set_routing
+    pub fn routing(&self) -> &Routing {
+        &self.routing
+    }
+
+    /// Returns the group's elements (those it had when it was last
+    /// launched or restarted).
+    pub fn elems(&self) -> &[ElemNode] {
+        &self.elems
+    }
+
+    /// Returns the number of the group's elements that were
+    /// launched, which is lower than its number of elements while
+    /// they are launched in waves (see
+    /// [`Children::with_launch_concurrency`]).
+    ///
+    /// [`Children::with_launch_concurrency`]: ../children/struct.Children.html#method.with_launch_concurrency
+    pub fn launched(&self) -> usize {
+        self.launched
+    }
+
+    /// Returns the number of the group's launched elements that
+    /// are initialized (see [`Children::with_launch_concurrency`]).
+    ///
+    /// [`Children::with_launch_concurrency`]: ../children/struct.Children.html#method.with_launch_concurrency
+    pub fn initialized(&self) -> usize {
+        self.initialized
+    }
+
+    fn write_dot(&self, dot: &mut String, indent: &str) {
+        let label = match &self.name {
+            Some(name) => escape(name),
+            None => "children".to_string(),
+        };
+
+        // The progress of the launch is shown until it is over.
+        let progress = if self.initialized < self.elems.len() {
+            format!("\\n{}/{} initialized", self.initialized, self.elems.len())
+        } else {
+            String::new()
+        };
+
+        let _ = writeln!(
+            dot,
+            "{}\"{}\" [label=\"{}\\n{}{}\", shape=ellipse, style=filled, fillcolor={}];",
+            indent,
+            self.id,
+            label,
+            state_name(self.state),
+            progress,
+            state_color(self.state)
+        );
+        for elem in &self.elems {
+            let _ = writeln!(
+                dot,
+                "{}\"{}\" [label=\"{}\", shape=circle];",
+                indent, elem.id, elem.generation
+            );
+            let _ = writeln!(dot, "{}\"{}\" -> \"{}\";", indent, self.id, elem.id);
+        }
+    }
+
+    fn write_json(&self, json: &mut String) {
+        let _ = write!(json, "{{\"id\":\"{}\",\"name\":", self.id);
+        match &self.name {
+            Some(name) => {
+                let _ = write!(json, "\"{}\"", escape(name));
+            }
+            None => json.push_str("null"),
+        }
+
+        let _ = write!(json, ",\"state\":\"{}\"", state_name(self.state));
+        match self.state {
+            GroupState::Restarting { attempt } => {
+                let _ = write!(json, ",\"restart_attempt\":{}", attempt);
+            }
+            _ => json.push_str(",\"restart_attempt\":null"),
+        }
+
+        json.push_str(",\"policies\":");
+        write_policies(json, &self.policies);
+
+        let _ = write!(json, ",\"routing\":\"{}\"", self.routing.name());
+        match &self.routing {
+            Routing::Pinned(id) => {
+                let _ = write!(json, ",\"pinned_to\":\"{}\"", id);
+            }
+            _ => json.push_str(",\"pinned_to\":null"),
+        }
+
+        let _ = write!(
+            json,
+            ",\"elem_count\":{},\"launched\":{},\"initialized\":{},\"elems\":[",
+            self.elems.len(),
+            self.launched,
+            self.initialized
+        );
+        for (index, elem) in self.elems.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+
+            let _ = write!(
+                json,
+                "{{\"id\":\"{}\",\"generation\":{}}}",
+                elem.id, elem.generation
+            );
+        }
+
+        json.push_str("]}");
+    }
+}
+
+#[cfg(feature = "introspection")]
+impl ElemNode {
+    /// Returns the element's identifier.
+    pub fn id(&self) -> &BastionId {
+        &self.id
+    }
+
+    /// Returns the number of times the element was restarted.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+}
+
+#[cfg(feature = "introspection")]
+fn write_policies(json: &mut String, policies: &GroupPolicies) {
+    let restart_strategy = policies.restart_strategy();
+    match restart_strategy.restart_policy() {
+        RestartPolicy::Always => json.push_str("{\"restart_policy\":\"always\",\"max_tries\":null"),
+        RestartPolicy::Never => json.push_str("{\"restart_policy\":\"never\",\"max_tries\":null"),
+        RestartPolicy::Tries(tries) => {
+            let _ = write!(
+                json,
+                "{{\"restart_policy\":\"tries\",\"max_tries\":{}",
+                tries
+            );
+        }
+    }
+
+    match restart_strategy.strategy() {
+        ActorRestartStrategy::Immediate => json
+            .push_str(",\"backoff\":\"immediate\",\"backoff_ms\":null,\"backoff_multiplier\":null"),
+        ActorRestartStrategy::LinearBackOff { timeout } => {
+            let _ = write!(
+                json,
+                ",\"backoff\":\"linear\",\"backoff_ms\":{},\"backoff_multiplier\":null",
+                timeout.as_millis()
+            );
+        }
+        ActorRestartStrategy::ExponentialBackOff {
+            timeout,
+            multiplier,
+        } => {
+            let _ = write!(
+                json,
+                ",\"backoff\":\"exponential\",\"backoff_ms\":{},\"backoff_multiplier\":{}",
+                timeout.as_millis(),
+                multiplier
+            );
+        }
+    }
+
+    #[cfg(feature = "supervision")]
+    match policies.restart_window() {
+        Some((max_restarts, within)) => {
+            let _ = write!(
+                json,
+                ",\"restart_window\":{{\"max_restarts\":{},\"within_ms\":{}}}",
+                max_restarts,
+                within.as_millis()
+            );
+        }
+        None => json.push_str(",\"restart_window\":null"),
+    }
+
+    #[cfg(feature = "supervision")]
+    match policies.restart_backoff() {
+        Some(backoff) => {
+            let _ = write!(
+                json,
+                ",\"restart_backoff\":{{\"min_ms\":{},\"max_ms\":{}",
+                backoff.min().as_millis(),
+                backoff.max().as_millis()
+            );
+            let _ = write!(
+                json,
+                ",\"jitter\":{},\"reset_after_ms\":{}}}",
+                backoff.jitter(),
+                backoff.reset_after().as_millis()
+            );
+        }
+        None => json.push_str(",\"restart_backoff\":null"),
+    }
+    #[cfg(not(feature = "supervision"))]
+    json.push_str(",\"restart_window\":null,\"restart_backoff\":null");
+
+    let _ = write!(
+        json,
+        ",\"pre_start_watermark\":{}",
+        policies.pre_start_watermark()
+    );
+
+    match policies.mailbox_capacity() {
+        Some(capacity) => {
+            let _ = write!(json, ",\"mailbox_capacity\":{}", capacity);
+        }
+        None => json.push_str(",\"mailbox_capacity\":null"),
+    }
+
+    let overflow_policy = match policies.overflow_policy() {
+        OverflowPolicy::Block => "block",
+        OverflowPolicy::DropNewest => "drop_newest",
+        OverflowPolicy::DropOldest => "drop_oldest",
+        OverflowPolicy::Fail => "fail",
+    };
+    let _ = write!(json, ",\"overflow_policy\":\"{}\"", overflow_policy);
+    let _ = write!(
+        json,
+        ",\"drain_timeout_ms\":{},\"requeue_on_restart\":{}",
+        policies.drain_timeout().as_millis(),
+        policies.requeues_on_restart()
+    );
+
+    // The presets' names don't need to be escaped.
+    match policies.preset() {
+        Some(preset) => {
+            let _ = write!(json, ",\"preset\":\"{}\"}}", preset);
+        }
+        None => json.push_str(",\"preset\":null}"),
+    }
+}
+
+#[cfg(feature = "introspection")]
+fn write_terminated(json: &mut String, group: &TerminatedGroup) {
+    let _ = write!(json, "{{\"id\":\"{}\",\"name\":", group.id());
+    match group.name() {
+        Some(name) => {
+            let _ = write!(json, "\"{}\"", escape(name));
+        }
+        None => json.push_str("null"),
+    }
+
+    let _ = write!(
+        json,
+        ",\"state\":\"{}\",\"elems\":[",
+        state_name(group.state())
+    );
+    for (index, elem) in group.elems().iter().enumerate() {
+        if index > 0 {
+            json.push(',');
+        }
+
+        let _ = write!(json, "\"{}\"", elem);
+    }
+
+    json.push_str("],\"faults\":[");
+    for (index, (reason, count)) in group.faults().iter().enumerate() {
+        if index > 0 {
+            json.push(',');
+        }
+
+        let _ = write!(
+            json,
+            "{{\"reason\":\"{}\",\"count\":{}}}",
+            reason_name(*reason),
+            count
+        );
+    }
+
+    // A time before the Unix epoch is exported as the epoch.
+    let terminated_at = group
+        .terminated_at()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let _ = write!(
+        json,
+        "],\"restarts\":{},\"processed\":{},\"rejected\":{},\"lifetime_ms\":{},\"terminated_at_ms\":{}}}",
+        group.restarts(),
+        group.processed(),
+        group.rejected(),
+        group.lifetime().as_millis(),
+        terminated_at.as_millis()
+    );
+}
+
+#[cfg(feature = "introspection")]
+fn strategy_name(strategy: &SupervisionStrategy) -> &'static str {
+    match strategy {
+        SupervisionStrategy::OneForOne => "one_for_one",
+        SupervisionStrategy::OneForAll => "one_for_all",
+        SupervisionStrategy::RestForOne => "rest_for_one",
+    }
+}
+
+#[cfg(feature = "introspection")]
+fn state_name(state: GroupState) -> &'static str {
+    match state {
+        GroupState::Dormant => "dormant",
+        GroupState::Running => "running",
+        GroupState::Restarting { .. } => "restarting",
+        GroupState::Quiescing => "quiescing",
+        GroupState::Stopping => "stopping",
+        GroupState::Stopped => "stopped",
+        GroupState::Killed => "killed",
+        GroupState::Faulted => "faulted",
+    }
+}
+
+#[cfg(feature = "introspection")]
+fn reason_name(reason: FaultReason) -> &'static str {
+    match reason {
+        FaultReason::Panicked => "panicked",
+        FaultReason::Errored => "errored",
+        FaultReason::Unreported => "unreported",
+    }
+}
+
+#[cfg(feature = "introspection")]
+fn state_color(state: GroupState) -> &'static str {
+    match state {
+        GroupState::Dormant => "lightgrey",
+        GroupState::Running => "palegreen",
+        GroupState::Restarting { .. } => "orange",
+        GroupState::Quiescing | GroupState::Stopping => "khaki",
+        GroupState::Stopped => "grey",
+        GroupState::Killed | GroupState::Faulted => "tomato",
+    }
+}
+
+#[cfg(feature = "introspection")]
+// Escapes the quotes, backslashes and control characters of a
+// name for it to be used in a JSON string or a Graphviz label.
+fn escape(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
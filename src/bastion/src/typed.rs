@@ -0,0 +1,477 @@
+//!
+//! References to the children groups whose elements only accept
+//! messages of one type (see [`Bastion::typed_children`]), whose
+//! methods only take messages of this type.
+//!
+//! [`Bastion::typed_children`]: ../struct.Bastion.html#method.typed_children
+use crate::child_ref::ChildRef;
+use crate::children_ref::ChildrenRef;
+use crate::context::BastionId;
+use crate::errors::SendError;
+use crate::message::{Answer, AnswerTimeout, Message};
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::time::Duration;
+
+/// A [`ChildrenRef`] referencing a children group whose elements
+/// only accept messages of type `M`, returned by
+/// [`Bastion::typed_children`] and [`SupervisorRef::typed_children`].
+///
+/// It can be converted back into the `ChildrenRef` it wraps, e.g.
+/// to use the methods it doesn't wrap.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// let typed_ref: TypedChildrenRef<u64> = Bastion::typed_children(|children| {
+///     children.with_exec(|ctx: BastionContext| {
+///         async move {
+///             loop {
+///                 let (msg, _) = ctx.recv_msg::<u64>().await;
+///                 // Handle the message...
+///                 # drop(msg);
+///             }
+///         }
+///     })
+/// }).expect("Couldn't create the children group.");
+///
+/// typed_ref.tell_one(42).expect("Couldn't send the message.");
+///
+/// let children_ref: ChildrenRef = typed_ref.into();
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`ChildrenRef`]: ../children_ref/struct.ChildrenRef.html
+/// [`Bastion::typed_children`]: ../struct.Bastion.html#method.typed_children
+/// [`SupervisorRef::typed_children`]: ../supervisor/struct.SupervisorRef.html#method.typed_children
+pub struct TypedChildrenRef<M> {
+    inner: ChildrenRef,
+    elems: Vec<TypedChildRef<M>>,
+}
+
+/// A [`ChildRef`] referencing an element that only accepts
+/// messages of type `M`, retrieved using [`ChildRef::typed`] or
+/// [`TypedChildrenRef::elems`].
+///
+/// It can be converted back into the `ChildRef` it wraps, e.g. to
+/// use the methods it doesn't wrap.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+///     # let children_ref = Bastion::typed_children::<u64, _>(|children| children).unwrap();
+///     # let child_ref = children_ref.elems()[0].as_untyped().clone();
+/// // Some(...) if the element only accepts `u64`s.
+/// if let Some(typed_ref) = child_ref.typed::<u64>() {
+///     typed_ref.tell(42).expect("Couldn't send the message.");
+///
+///     let child_ref: ChildRef = typed_ref.into();
+///     # drop(child_ref);
+/// }
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`ChildRef`]: ../child_ref/struct.ChildRef.html
+/// [`ChildRef::typed`]: ../child_ref/struct.ChildRef.html#method.typed
+/// [`TypedChildrenRef::elems`]: struct.TypedChildrenRef.html#method.elems
+pub struct TypedChildRef<M> {
+    inner: ChildRef,
+    // `fn(M)` keeps the reference `Send` and `Sync` whatever `M` is,
+    // as it never holds a message.
+    _msg: PhantomData<fn(M)>,
+}
+
+impl<M: Message> TypedChildrenRef<M> {
+    pub(crate) fn new(inner: ChildrenRef) -> Self {
+        let elems = inner
+            .elems()
+            .iter()
+            .cloned()
+            .map(TypedChildRef::new)
+            .collect();
+
+        TypedChildrenRef { inner, elems }
+    }
+
+    /// Returns the identifier of the children group this
+    /// `TypedChildrenRef` is referencing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let typed_ref: TypedChildrenRef<u64> = Bastion::typed_children(|children| children).unwrap();
+    /// let group_id: &BastionId = typed_ref.id();
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn id(&self) -> &BastionId {
+        self.inner.id()
+    }
+
+    /// Returns the [`TypedChildRef`]s of the elements of the
+    /// children group this `TypedChildrenRef` is referencing (see
+    /// [`ChildrenRef::elems`]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let typed_ref: TypedChildrenRef<u64> = Bastion::typed_children(|children| children).unwrap();
+    /// for elem_ref in typed_ref.elems() {
+    ///     elem_ref.tell(42).expect("Couldn't send the message.");
+    /// }
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`TypedChildRef`]: struct.TypedChildRef.html
+    /// [`ChildrenRef::elems`]: ../children_ref/struct.ChildrenRef.html#method.elems
+    pub fn elems(&self) -> &[TypedChildRef<M>] {
+        &self.elems
+    }
+
+    /// Sends a message to all the elements of the children group
+    /// this `TypedChildrenRef` is referencing (see
+    /// [`ChildrenRef::broadcast`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let typed_ref: TypedChildrenRef<u64> = Bastion::typed_children(|children| children).unwrap();
+    /// typed_ref.broadcast(42).expect("Couldn't send the message.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef::broadcast`]: ../children_ref/struct.ChildrenRef.html#method.broadcast
+    pub fn broadcast(&self, msg: M) -> Result<(), SendError<M>> {
+        self.inner.broadcast(msg)
+    }
+
+    /// Sends a message to one of the elements of the children group
+    /// this `TypedChildrenRef` is referencing (see
+    /// [`ChildrenRef::tell_one`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let typed_ref: TypedChildrenRef<u64> = Bastion::typed_children(|children| children).unwrap();
+    /// typed_ref.tell_one(42).expect("Couldn't send the message.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef::tell_one`]: ../children_ref/struct.ChildrenRef.html#method.tell_one
+    pub fn tell_one(&self, msg: M) -> Result<(), SendError<M>> {
+        self.inner.tell_one(msg)
+    }
+
+    /// Sends a message to one of the elements of the children group
+    /// this `TypedChildrenRef` is referencing, allowing it to answer
+    /// (see [`ChildrenRef::ask_one`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let typed_ref: TypedChildrenRef<u64> = Bastion::typed_children(|children| children).unwrap();
+    /// let answer: Answer = typed_ref.ask_one(42).expect("Couldn't send the message.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef::ask_one`]: ../children_ref/struct.ChildrenRef.html#method.ask_one
+    pub fn ask_one(&self, msg: M) -> Result<Answer, SendError<M>> {
+        self.inner.ask_one(msg)
+    }
+
+    /// Returns the [`ChildrenRef`] this `TypedChildrenRef` wraps.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let typed_ref: TypedChildrenRef<u64> = Bastion::typed_children(|children| children).unwrap();
+    /// let stats = typed_ref.as_untyped().stats();
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef`]: ../children_ref/struct.ChildrenRef.html
+    pub fn as_untyped(&self) -> &ChildrenRef {
+        &self.inner
+    }
+}
+
+impl<M: Message> TypedChildRef<M> {
+    pub(crate) fn new(inner: ChildRef) -> Self {
+        TypedChildRef {
+            inner,
+            _msg: PhantomData,
+        }
+    }
+
+    /// Returns the identifier of the element this `TypedChildRef`
+    /// is referencing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let typed_ref: TypedChildrenRef<u64> = Bastion::typed_children(|children| children).unwrap();
+    ///     # let elem_ref = &typed_ref.elems()[0];
+    /// let elem_id: &BastionId = elem_ref.id();
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn id(&self) -> &BastionId {
+        self.inner.id()
+    }
+
+    /// Sends a message to the element this `TypedChildRef` is
+    /// referencing (see [`ChildRef::tell_anonymously`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let typed_ref: TypedChildrenRef<u64> = Bastion::typed_children(|children| children).unwrap();
+    ///     # let elem_ref = &typed_ref.elems()[0];
+    /// elem_ref.tell(42).expect("Couldn't send the message.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildRef::tell_anonymously`]: ../child_ref/struct.ChildRef.html#method.tell_anonymously
+    pub fn tell(&self, msg: M) -> Result<(), SendError<M>> {
+        self.inner.tell_anonymously(msg)
+    }
+
+    /// Sends a message to the element this `TypedChildRef` is
+    /// referencing, allowing it to answer (see
+    /// [`ChildRef::ask_anonymously`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let typed_ref: TypedChildrenRef<u64> = Bastion::typed_children(|children| children).unwrap();
+    ///     # let elem_ref = &typed_ref.elems()[0];
+    /// let answer: Answer = elem_ref.ask(42).expect("Couldn't send the message.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildRef::ask_anonymously`]: ../child_ref/struct.ChildRef.html#method.ask_anonymously
+    pub fn ask(&self, msg: M) -> Result<Answer, SendError<M>> {
+        self.inner.ask_anonymously(msg)
+    }
+
+    /// Sends a message to the element this `TypedChildRef` is
+    /// referencing, allowing it to answer within `timeout` (see
+    /// [`ChildRef::ask_timeout`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    /// * `timeout` - How long to wait for the answer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let typed_ref: TypedChildrenRef<u64> = Bastion::typed_children(|children| children).unwrap();
+    ///     # let elem_ref = &typed_ref.elems()[0];
+    /// let answer = elem_ref
+    ///     .ask_timeout(42, Duration::from_secs(1))
+    ///     .expect("Couldn't send the message.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildRef::ask_timeout`]: ../child_ref/struct.ChildRef.html#method.ask_timeout
+    pub fn ask_timeout(&self, msg: M, timeout: Duration) -> Result<AnswerTimeout, SendError<M>> {
+        self.inner.ask_timeout(msg, timeout)
+    }
+
+    /// Returns the [`ChildRef`] this `TypedChildRef` wraps.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let typed_ref: TypedChildrenRef<u64> = Bastion::typed_children(|children| children).unwrap();
+    ///     # let elem_ref = &typed_ref.elems()[0];
+    /// let stats = elem_ref.as_untyped().stats();
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildRef`]: ../child_ref/struct.ChildRef.html
+    pub fn as_untyped(&self) -> &ChildRef {
+        &self.inner
+    }
+}
+
+impl<M> Clone for TypedChildrenRef<M> {
+    fn clone(&self) -> Self {
+        TypedChildrenRef {
+            inner: self.inner.clone(),
+            elems: self.elems.clone(),
+        }
+    }
+}
+
+impl<M> Clone for TypedChildRef<M> {
+    fn clone(&self) -> Self {
+        TypedChildRef {
+            inner: self.inner.clone(),
+            _msg: PhantomData,
+        }
+    }
+}
+
+impl<M> Debug for TypedChildrenRef<M> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_tuple("TypedChildrenRef")
+            .field(&self.inner)
+            .finish()
+    }
+}
+
+impl<M> Debug for TypedChildRef<M> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_tuple("TypedChildRef").field(&self.inner).finish()
+    }
+}
+
+impl<M> From<TypedChildrenRef<M>> for ChildrenRef {
+    fn from(typed: TypedChildrenRef<M>) -> Self {
+        typed.inner
+    }
+}
+
+impl<M> From<TypedChildRef<M>> for ChildRef {
+    fn from(typed: TypedChildRef<M>) -> Self {
+        typed.inner
+    }
+}
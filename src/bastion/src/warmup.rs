@@ -0,0 +1,349 @@
+//!
+//! Warmups run by the elements of a children group before they
+//! start executing their future and are routed messages (see
+//! [`Children::with_warmup`]).
+//!
+//! [`Children::with_warmup`]: ../children/struct.Children.html#method.with_warmup
+use crate::child_ref::ChildRef;
+use crate::context::{BastionContext, BastionId};
+use crate::envelope::Envelope;
+use crate::errors::SendErrorKind;
+use crate::mailbox::OverflowPolicy;
+use crate::routing::{Routing, RoutingKey};
+use futures::future::{self, FutureExt};
+use futures_timer::Delay;
+use std::any::Any;
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// When an element of a children group is considered warm, and
+/// its warmup is stopped.
+pub enum WarmupGate {
+    /// Once the warmup ran for this long, or once it finished if
+    /// it finishes earlier.
+    Duration(Duration),
+    /// Once the warmup finished.
+    UntilReady,
+}
+
+#[derive(Clone)]
+// The warmup of a group's elements and its gate.
+pub(crate) struct Warmup {
+    init: Arc<dyn Fn(BastionContext) -> WarmupExec + Send + Sync>,
+    gate: WarmupGate,
+}
+
+// A warmup's future, resolving once its element is warm.
+pub(crate) struct WarmupExec(Pin<Box<dyn Future<Output = ()> + Send>>);
+
+#[derive(Debug, Clone)]
+// The elements of a group that routed messages can be sent to,
+// and how they are routed, shared by the group, its elements and
+// its `ChildrenRef`s.
+pub(crate) struct Router(Arc<Mutex<RouterInner>>);
+
+#[derive(Debug)]
+struct RouterInner {
+    // The warm elements, in the order they warmed up.
+    warm: Vec<ChildRef>,
+    // The index of the element the next routed message is sent to,
+    // when they are sent to each element in turn.
+    next: usize,
+    // How the routed messages are routed, only changed by the
+    // group, and its key function if it has one.
+    mode: Routing,
+    key: Option<RoutingKey>,
+    // The routed messages sent while no element was warm, bounded
+    // like its elements' mailboxes.
+    queued: VecDeque<Envelope>,
+    capacity: Option<usize>,
+    policy: OverflowPolicy,
+    // The number of elements the group launches (once it did), and
+    // the fraction of them that must be warm for the group to be
+    // ready.
+    expected: Option<usize>,
+    ready_fraction: f64,
+}
+
+impl Warmup {
+    pub(crate) fn new<I, F>(init: I, gate: WarmupGate) -> Self
+    where
+        I: Fn(BastionContext) -> F + Send + Sync + 'static,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let init = Arc::new(move |ctx: BastionContext| WarmupExec(Box::pin(init(ctx))));
+        Warmup { init, gate }
+    }
+
+    // Returns the warmup's future for the element `ctx` is linked
+    // to, resolving once it passed the gate.
+    pub(crate) fn exec(&self, ctx: BastionContext) -> WarmupExec {
+        let exec = (self.init)(ctx);
+        match self.gate {
+            WarmupGate::Duration(duration) => {
+                let exec = future::select(exec, Delay::new(duration)).map(|_| ());
+                WarmupExec(Box::pin(exec))
+            }
+            WarmupGate::UntilReady => exec,
+        }
+    }
+}
+
+impl Router {
+    pub(crate) fn new() -> Self {
+        let inner = RouterInner {
+            warm: Vec::new(),
+            next: 0,
+            mode: Routing::default(),
+            key: None,
+            queued: VecDeque::new(),
+            capacity: None,
+            policy: OverflowPolicy::default(),
+            expected: None,
+            ready_fraction: 1.0,
+        };
+
+        Router(Arc::new(Mutex::new(inner)))
+    }
+
+    pub(crate) fn mode(&self) -> Routing {
+        self.0.lock().unwrap().mode.clone()
+    }
+
+    pub(crate) fn set_mode(&self, mode: Routing) {
+        self.0.lock().unwrap().mode = mode;
+    }
+
+    pub(crate) fn has_key(&self) -> bool {
+        self.0.lock().unwrap().key.is_some()
+    }
+
+    pub(crate) fn set_key(&self, key: RoutingKey) {
+        self.0.lock().unwrap().key = Some(key);
+    }
+
+    // Returns the key `msg` is routed by, if the group is routing by
+    // key and `msg` is of the key function's type.
+    pub(crate) fn key(&self, msg: &dyn Any) -> Option<u64> {
+        let key = {
+            let inner = self.0.lock().unwrap();
+            match inner.mode {
+                Routing::HashBy => inner.key.clone(),
+                _ => None,
+            }
+        };
+
+        // The key function is called without holding the lock.
+        key?.key(msg)
+    }
+
+    // Sets how many elements the group launches and how its
+    // routed messages are queued while none of them is warm.
+    pub(crate) fn configure(
+        &self,
+        expected: usize,
+        ready_fraction: f64,
+        capacity: Option<usize>,
+        policy: OverflowPolicy,
+    ) {
+        let mut inner = self.0.lock().unwrap();
+        inner.expected = Some(expected);
+        inner.ready_fraction = ready_fraction;
+        inner.capacity = capacity;
+        inner.policy = policy;
+    }
+
+    // Returns the warm element the next routed message (whose key
+    // was returned by `key`) should be sent to, or queues the
+    // envelope built by `env` if there isn't any.
+    pub(crate) fn route(
+        &self,
+        key: Option<u64>,
+        env: impl FnOnce() -> Envelope,
+    ) -> Result<Option<ChildRef>, SendErrorKind> {
+        self.route_with(|inner| inner.pick(key), env)
+    }
+
+    // Returns the next warm element in turn, whatever the group's
+    // routing mode, or queues the envelope built by `env` if there
+    // isn't any (see `ChildrenRef::tell_next`).
+    pub(crate) fn route_next(
+        &self,
+        env: impl FnOnce() -> Envelope,
+    ) -> Result<Option<ChildRef>, SendErrorKind> {
+        self.route_with(RouterInner::next_in_turn, env)
+    }
+
+    fn route_with(
+        &self,
+        pick: impl FnOnce(&mut RouterInner) -> usize,
+        env: impl FnOnce() -> Envelope,
+    ) -> Result<Option<ChildRef>, SendErrorKind> {
+        let mut inner = self.0.lock().unwrap();
+        if !inner.warm.is_empty() {
+            let index = pick(&mut inner);
+            return Ok(Some(inner.warm[index].clone()));
+        }
+
+        let full = match inner.capacity {
+            Some(capacity) => inner.queued.len() >= capacity,
+            None => false,
+        };
+        if full {
+            match inner.policy {
+                OverflowPolicy::Block | OverflowPolicy::Fail => {
+                    return Err(SendErrorKind::MailboxFull)
+                }
+                // The message is dropped.
+                OverflowPolicy::DropNewest => return Ok(None),
+                OverflowPolicy::DropOldest => {
+                    inner.queued.pop_front();
+                }
+            }
+        }
+
+        inner.queued.push_back(env());
+        Ok(None)
+    }
+
+    // Returns the warm element a message (`msg`) rejected by the
+    // element with this id should be rerouted to, according to the
+    // group's routing mode, or `None` if there isn't any.
+    pub(crate) fn reroute(&self, from: &BastionId, msg: &dyn Any) -> Option<ChildRef> {
+        let key = self.key(msg);
+        let inner = self.0.lock().unwrap();
+        let warm = &inner.warm;
+        match &inner.mode {
+            // The message is only sent to the element it is pinned
+            // to, unless it is the one which rejected it.
+            Routing::Pinned(id) if id == from => return None,
+            Routing::Pinned(id) => return warm.iter().find(|elem| elem.id() == id).cloned(),
+            // The message is sent to the element its key is routed
+            // to, unless it is the one which rejected it.
+            Routing::HashBy if key.is_some() && warm.len() > 1 => {
+                let target = &warm[(key.unwrap() % warm.len() as u64) as usize];
+                if target.id() != from {
+                    return Some(target.clone());
+                }
+            }
+            Routing::LeastLoaded => {
+                return warm
+                    .iter()
+                    .filter(|elem| elem.id() != from)
+                    .min_by_key(|elem| elem.mailbox_len())
+                    .cloned();
+            }
+            _ => (),
+        }
+
+        match warm.iter().position(|child_ref| child_ref.id() == from) {
+            // The element following the rejecting one is picked, so
+            // that successive reroutes go through all of them.
+            Some(index) if warm.len() > 1 => Some(warm[(index + 1) % warm.len()].clone()),
+            Some(_) => None,
+            // The rejecting element isn't warm anymore (e.g. because
+            // it is stopping).
+            None if !warm.is_empty() => Some(warm[inner.next % warm.len()].clone()),
+            None => None,
+        }
+    }
+
+    // Marks the element `child_ref` is referencing as warm, and
+    // returns the routed messages queued while none was.
+    pub(crate) fn warmed(&self, child_ref: &ChildRef) -> VecDeque<Envelope> {
+        let mut inner = self.0.lock().unwrap();
+        inner.warm.push(child_ref.clone());
+        std::mem::take(&mut inner.queued)
+    }
+
+    // Stops routing messages to the element with this id.
+    pub(crate) fn cooled(&self, id: &BastionId) {
+        let mut inner = self.0.lock().unwrap();
+        inner.warm.retain(|child_ref| child_ref.id() != id);
+    }
+
+    pub(crate) fn warm(&self) -> usize {
+        self.0.lock().unwrap().warm.len()
+    }
+
+    pub(crate) fn is_ready(&self) -> bool {
+        let inner = self.0.lock().unwrap();
+        match inner.expected {
+            Some(expected) => {
+                let required = (expected as f64 * inner.ready_fraction).ceil() as usize;
+                inner.warm.len() >= required
+            }
+            None => false,
+        }
+    }
+}
+
+impl RouterInner {
+    // Returns the index of the warm element the message with this
+    // key should be sent to, while there is at least one.
+    fn pick(&mut self, key: Option<u64>) -> usize {
+        let len = self.warm.len();
+        match &self.mode {
+            Routing::Pinned(id) => {
+                if let Some(index) = self.warm.iter().position(|elem| elem.id() == id) {
+                    return index;
+                }
+            }
+            Routing::HashBy => {
+                if let Some(key) = key {
+                    return (key % len as u64) as usize;
+                }
+            }
+            Routing::LeastLoaded => {
+                // The elements are looked at starting with the next
+                // one in turn, for the ties to be spread.
+                let start = self.next % len;
+                let index = (0..len)
+                    .map(|offset| (start + offset) % len)
+                    .min_by_key(|index| self.warm[*index].mailbox_len())
+                    .unwrap_or(start);
+                self.next = index + 1;
+                return index;
+            }
+            Routing::RoundRobin => (),
+        }
+
+        self.next_in_turn()
+    }
+
+    // Returns the index of the next warm element in turn, while
+    // there is at least one.
+    fn next_in_turn(&mut self) -> usize {
+        let index = self.next % self.warm.len();
+        self.next = index + 1;
+        index
+    }
+}
+
+impl Future for WarmupExec {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        Pin::new(&mut self.get_mut().0).poll(ctx)
+    }
+}
+
+impl Debug for Warmup {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Warmup")
+            .field("gate", &self.gate)
+            .finish()
+    }
+}
+
+impl Debug for WarmupExec {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("WarmupExec").finish()
+    }
+}
@@ -0,0 +1,106 @@
+//!
+//! The termination of the children groups' elements, which can be
+//! watched using [`ChildRef::watch`].
+//!
+//! [`ChildRef::watch`]: ../child_ref/struct.ChildRef.html#method.watch
+use crate::event::FaultReason;
+use futures::channel::oneshot::{self, Receiver, Sender};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// How a children group's element terminated, as resolved by a
+/// [`Watcher`].
+///
+/// [`Watcher`]: struct.Watcher.html
+pub enum Termination {
+    /// The element stopped: its future finished successfully, or
+    /// it was asked to stop (e.g. using [`ChildRef::stop`]) or
+    /// migrated to another element.
+    ///
+    /// [`ChildRef::stop`]: ../child_ref/struct.ChildRef.html#method.stop
+    Stopped,
+    /// The element faulted, for this reason. Its supervisor might
+    /// restart it afterwards, the restarted element being watched
+    /// using the new [`ChildRef`]s referencing it.
+    ///
+    /// [`ChildRef`]: ../child_ref/struct.ChildRef.html
+    Faulted(FaultReason),
+    /// The element was killed (e.g. using [`ChildRef::kill`]), or
+    /// it was dropped along with its group (e.g. once its group
+    /// was killed or the element pruned).
+    ///
+    /// [`ChildRef::kill`]: ../child_ref/struct.ChildRef.html#method.kill
+    Killed,
+}
+
+#[derive(Debug)]
+/// A [`Future`] returned by [`ChildRef::watch`], resolving to how
+/// the watched element terminated once it did (or right away if
+/// it already did).
+///
+/// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+/// [`ChildRef::watch`]: ../child_ref/struct.ChildRef.html#method.watch
+pub struct Watcher(Receiver<Termination>);
+
+#[derive(Debug, Default)]
+// The watchers of an element, shared by the element and the
+// `ChildRef`s referencing it (but not those referencing it once
+// restarted).
+pub(crate) struct Watchers(Mutex<WatchersInner>);
+
+#[derive(Debug, Default)]
+struct WatchersInner {
+    // How the element terminated, once it did.
+    terminated: Option<Termination>,
+    waiting: Vec<Sender<Termination>>,
+}
+
+impl Watchers {
+    pub(crate) fn watch(&self) -> Watcher {
+        let (sender, recver) = oneshot::channel();
+        // FIXME: panics?
+        let mut inner = self.0.lock().unwrap();
+        match inner.terminated {
+            Some(termination) => {
+                sender.send(termination).ok();
+            }
+            None => {
+                // The watchers that were dropped are forgotten.
+                inner.waiting.retain(|waiting| !waiting.is_canceled());
+                inner.waiting.push(sender);
+            }
+        }
+
+        Watcher(recver)
+    }
+
+    // Notifies the watchers of how the element terminated, unless
+    // they already were.
+    pub(crate) fn terminated(&self, termination: Termination) {
+        // FIXME: panics?
+        let mut inner = self.0.lock().unwrap();
+        if inner.terminated.is_some() {
+            return;
+        }
+
+        inner.terminated = Some(termination);
+        for waiting in inner.waiting.drain(..) {
+            waiting.send(termination).ok();
+        }
+    }
+}
+
+impl Future for Watcher {
+    type Output = Termination;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Termination> {
+        // The element notifies its watchers before being dropped,
+        // so they are only dropped with it if it never started.
+        Pin::new(&mut self.get_mut().0)
+            .poll(ctx)
+            .map(|terminated| terminated.unwrap_or(Termination::Killed))
+    }
+}
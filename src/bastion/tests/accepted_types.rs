@@ -0,0 +1,109 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[derive(Debug)]
+struct Job;
+
+// Only accepts `u64`s and `Job`s, answering the former and
+// counting every message it receives.
+fn accepting(received: Arc<AtomicUsize>) -> ChildrenRef {
+    Bastion::children(move |children| {
+        let received = received.clone();
+        children
+            .with_accepted_types::<(u64, Job)>()
+            .with_exec(move |ctx: BastionContext| {
+                let received = received.clone();
+                async move {
+                    loop {
+                        msg! { ctx.recv().await?,
+                            n: u64 =!> {
+                                received.fetch_add(1, Ordering::SeqCst);
+                                answer!(ctx, n * 2).unwrap();
+                            };
+                            _: _ => {
+                                received.fetch_add(1, Ordering::SeqCst);
+                            };
+                        }
+                    }
+                }
+            })
+    })
+    .unwrap()
+}
+
+#[test]
+fn accepted() {
+    init_start();
+
+    let received = Arc::new(AtomicUsize::new(0));
+    let children = accepting(received.clone());
+    let elem = &children.elems()[0];
+
+    elem.tell_anonymously(Job).unwrap();
+    children.broadcast(Job).unwrap();
+    let answer = run!(elem.ask_anonymously(21u64).unwrap()).unwrap();
+    msg! { answer,
+        n: u64 => assert_eq!(n, 42);
+        _: _ => panic!("Unexpected answer.");
+    }
+
+    wait_for(|| received.load(Ordering::SeqCst) == 3);
+}
+
+#[test]
+fn rejected() {
+    init_start();
+
+    let received = Arc::new(AtomicUsize::new(0));
+    let children = accepting(received.clone());
+    let elem = &children.elems()[0];
+
+    let expected = SendErrorKind::TypeNotAccepted { got: "&str" };
+    assert_eq!(
+        children.config().accepted_types(),
+        Some(&["u64", "accepted_types::Job"][..])
+    );
+    let err = elem.tell_anonymously("job").unwrap_err();
+    assert_eq!(err.kind(), expected);
+    assert_eq!(err.into_inner_msg(), "job");
+    assert_eq!(elem.ask_anonymously("job").unwrap_err().kind(), expected);
+    assert_eq!(elem.tell_correlated("job").unwrap_err().kind(), expected);
+    assert_eq!(children.broadcast("job").unwrap_err().kind(), expected);
+    assert_eq!(children.ask_everyone("job").unwrap_err().kind(), expected);
+
+    // Nothing was enqueued.
+    elem.tell_anonymously(Job).unwrap();
+    wait_for(|| received.load(Ordering::SeqCst) == 1);
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(received.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn unchecked() {
+    init_start();
+
+    let received = Arc::new(AtomicUsize::new(0));
+    let children = accepting(received.clone());
+
+    children.elems()[0].tell_unchecked("job").unwrap();
+    wait_for(|| received.load(Ordering::SeqCst) == 1);
+}
+
+#[test]
+fn type_names() {
+    init_start();
+
+    let children = accepting(Arc::new(AtomicUsize::new(0)));
+    let err = children.broadcast(1.5f64).unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "couldn't send the message: the recipient doesn't accept `f64`"
+    );
+}
@@ -0,0 +1,141 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum Hook {
+    Started(u64),
+    Stopped(u64),
+}
+
+// Sums the amounts it is told, answers the sum when asked for it,
+// and fails when told 0.
+struct Summer {
+    sum: u64,
+    hooks: Arc<Mutex<Vec<Hook>>>,
+}
+
+impl Actor for Summer {
+    async fn started(&mut self, _: &BastionContext) -> Result<(), ActorError> {
+        self.hooks.lock().unwrap().push(Hook::Started(self.sum));
+        Ok(())
+    }
+
+    async fn handle(
+        &mut self,
+        _: &BastionContext,
+        msg: SignedMessage,
+    ) -> Result<Reply, ActorError> {
+        msg! { msg,
+            amount: u64 => {
+                if amount == 0 {
+                    return Err(ActorError::new("told 0"));
+                }
+
+                self.sum += amount;
+            };
+            _question: &'static str => return Ok(Reply::new(self.sum));
+            _: _ => ();
+        }
+
+        Ok(Reply::none())
+    }
+
+    // The element is still running, so the hook can wait.
+    async fn stopped(&mut self, ctx: &BastionContext) {
+        ctx.sleep(Duration::from_millis(10)).await;
+        self.hooks.lock().unwrap().push(Hook::Stopped(self.sum));
+    }
+}
+
+fn summer() -> (ChildrenRef, Arc<Mutex<Vec<Hook>>>) {
+    let hooks = Arc::new(Mutex::new(Vec::new()));
+    let recorded = hooks.clone();
+    let children = Bastion::children(move |children| {
+        let hooks = recorded.clone();
+        children.with_actor(move || Summer {
+            sum: 0,
+            hooks: hooks.clone(),
+        })
+    })
+    .unwrap();
+
+    (children, hooks)
+}
+
+fn sum(child: &ChildRef) -> u64 {
+    let answer = child.ask_anonymously("sum").unwrap();
+    let (msg, _) = run!(answer).unwrap().extract();
+    msg.downcast::<u64>().unwrap()
+}
+
+#[test]
+fn state_and_answers() {
+    init_start();
+
+    let (children, hooks) = summer();
+    let child = &children.elems()[0];
+    child.tell_anonymously(2u64).unwrap();
+    child.tell_anonymously(3u64).unwrap();
+
+    assert_eq!(sum(child), 5);
+    assert_eq!(*hooks.lock().unwrap(), vec![Hook::Started(0)]);
+}
+
+#[test]
+fn recreated_on_restart() {
+    init_start();
+
+    let (children, hooks) = summer();
+    let child = &children.elems()[0];
+    child.tell_anonymously(4u64).unwrap();
+    child.tell_anonymously(0u64).unwrap();
+
+    wait_for(|| hooks.lock().unwrap().len() == 3);
+    assert_eq!(
+        *hooks.lock().unwrap(),
+        vec![Hook::Started(0), Hook::Stopped(4), Hook::Started(0)]
+    );
+
+    // The restarted element runs a new actor.
+    child.tell_anonymously(1u64).unwrap();
+    assert_eq!(sum(child), 1);
+}
+
+#[test]
+fn stopped_hook() {
+    init_start();
+
+    // The messages left in the mailbox are handled before the
+    // hook is called.
+    let (children, hooks) = summer();
+    children.elems()[0].tell_anonymously(6u64).unwrap();
+    children.elems()[0].tell_anonymously(1u64).unwrap();
+    run!(children.stop_and_wait()).unwrap();
+    assert_eq!(
+        *hooks.lock().unwrap(),
+        vec![Hook::Started(0), Hook::Stopped(7)]
+    );
+
+    // It isn't called once the element is killed.
+    let (children, hooks) = summer();
+    assert_eq!(sum(&children.elems()[0]), 0);
+    children.kill().unwrap();
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(*hooks.lock().unwrap(), vec![Hook::Started(0)]);
+}
+
+#[test]
+fn unanswered() {
+    init_start();
+
+    // The question is asked while the element handles it, but
+    // isn't answered.
+    let (children, _) = summer();
+    let answer = children.elems()[0].ask_anonymously(3u64).unwrap();
+    assert!(run!(answer).is_err());
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+
+fn echo(children: Children) -> Children {
+    children.with_exec(|ctx: BastionContext| async move {
+        loop {
+            msg! { ctx.recv().await?,
+                msg: u64 =!> {
+                    answer!(ctx, msg).unwrap();
+                };
+                _: _ => ();
+            }
+        }
+    })
+}
+
+fn ask(children: &ChildrenRef) {
+    let answer = children.elems()[0].ask_anonymously(42u64).unwrap();
+    run!(answer).unwrap();
+}
+
+#[test]
+fn shared_stats() {
+    init_start();
+
+    let first = Bastion::children(|children| echo(children).with_affinity_group("shared")).unwrap();
+    let second =
+        Bastion::children(|children| echo(children).with_affinity_group("shared")).unwrap();
+
+    ask(&first);
+    ask(&second);
+
+    // Both groups' elements were placed on the same worker.
+    let stats = first.affinity_stats().unwrap();
+    assert_eq!(second.affinity_stats(), Some(stats));
+    assert!(stats.worker().is_some());
+    assert!(stats.scheduled() >= 2);
+}
+
+#[test]
+fn separate_stats() {
+    init_start();
+
+    let first = Bastion::children(|children| echo(children).with_affinity_group("first")).unwrap();
+    let second =
+        Bastion::children(|children| echo(children).with_affinity_group("second")).unwrap();
+
+    ask(&first);
+    ask(&second);
+
+    // Each group is placed on its own, so neither is left unplaced.
+    for children in &[first, second] {
+        wait_for(|| children.affinity_stats().unwrap().scheduled() > 0);
+        assert!(children.affinity_stats().unwrap().worker().is_some());
+    }
+}
+
+#[test]
+fn no_affinity() {
+    init_start();
+
+    let children = Bastion::children(echo).unwrap();
+    ask(&children);
+    assert_eq!(children.affinity_stats(), None);
+}
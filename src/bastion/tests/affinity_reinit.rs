@@ -0,0 +1,53 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+
+const ASKS: usize = 50;
+
+fn spawn_group() -> ChildrenRef {
+    Bastion::children(|children| {
+        children
+            .with_affinity_group("pipeline")
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        msg: u64 =!> {
+                            answer!(ctx, msg).unwrap();
+                        };
+                        _: _ => ();
+                    }
+                }
+            })
+    })
+    .unwrap()
+}
+
+#[test]
+fn placed_again() {
+    Bastion::init();
+    Bastion::start();
+
+    let children = spawn_group();
+    for n in 0..ASKS as u64 {
+        let answer = children.elems()[0].ask_anonymously(n).unwrap();
+        run!(answer).unwrap();
+    }
+    let scheduled = children.affinity_stats().unwrap().scheduled();
+    assert!(scheduled >= ASKS);
+
+    Bastion::kill();
+    Bastion::block_until_stopped();
+
+    // The groups of the new system don't share their statistics
+    // with those of the previous one.
+    Bastion::init();
+    Bastion::start();
+
+    let children = spawn_group();
+    wait_for(|| children.affinity_stats().unwrap().scheduled() > 0);
+    assert!(children.affinity_stats().unwrap().scheduled() < scheduled);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use bastion::prelude::*;
+use common::init_start;
+use futures::StreamExt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+// A group whose element replies `0..n` when it is asked `n`,
+// counting the replies it sent, and closes the stream unless `n`
+// is odd. It also answers the `&'static str`s it is asked.
+fn streaming(sent: Arc<AtomicUsize>) -> ChildrenRef {
+    Bastion::children(move |children| {
+        let sent = sent.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let sent = sent.clone();
+            async move {
+                loop {
+                    let mut msg = ctx.recv().await?;
+                    let sender = msg.take_stream_sender();
+                    msg! { msg,
+                        n: u64 => {
+                            let mut sender = sender.unwrap();
+                            for reply in 0..n {
+                                sender.reply(reply).await.unwrap();
+                                sent.fetch_add(1, Ordering::SeqCst);
+                            }
+
+                            if n % 2 == 0 {
+                                sender.close();
+                            }
+                        };
+                        question: &'static str =!> {
+                            assert!(sender.is_none());
+                            answer!(ctx, question).unwrap();
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .unwrap()
+}
+
+fn replies(stream: AnswerStream) -> Vec<Result<u64, AnswerError>> {
+    let replies = stream.map(|reply| reply.map(|msg| msg.downcast::<u64>().unwrap()));
+    run!(replies.collect())
+}
+
+#[test]
+fn closed() {
+    init_start();
+
+    let children = streaming(Arc::default());
+    let stream = children.elems()[0].ask_stream(4u64).unwrap();
+
+    assert_eq!(replies(stream), vec![Ok(0), Ok(1), Ok(2), Ok(3)]);
+}
+
+#[test]
+fn dropped_without_closing() {
+    init_start();
+
+    let children = streaming(Arc::default());
+    let stream = children.elems()[0].ask_stream(3u64).unwrap();
+
+    assert_eq!(
+        replies(stream),
+        vec![Ok(0), Ok(1), Ok(2), Err(AnswerError::Unanswered)]
+    );
+}
+
+#[test]
+fn bounded_buffer() {
+    init_start();
+
+    let sent = Arc::new(AtomicUsize::new(0));
+    let children = streaming(sent.clone());
+    let stream = children.elems()[0].ask_stream(100u64).unwrap();
+
+    // The element waits for the replies to be received...
+    thread::sleep(Duration::from_millis(100));
+    let buffered = sent.load(Ordering::SeqCst);
+    assert!(buffered > 0 && buffered < 20, "{}", buffered);
+
+    // ...and sends the rest once they are.
+    assert_eq!(replies(stream).len(), 100);
+    assert_eq!(sent.load(Ordering::SeqCst), 100);
+}
+
+#[test]
+fn single_answers_unaffected() {
+    init_start();
+
+    let children = streaming(Arc::default());
+    let answer = children.elems()[0].ask_anonymously("question").unwrap();
+
+    let (msg, _) = run!(answer).unwrap().extract();
+    assert_eq!(msg.downcast::<&'static str>().unwrap(), "question");
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use bastion::prelude::*;
+use common::init_start;
+use futures::future::{self, Either};
+use futures_timer::Delay;
+use std::thread;
+use std::time::Duration;
+
+// Never retrieves its messages.
+fn idle(children: Children) -> Children {
+    children.with_exec(|_: BastionContext| future::pending::<Result<(), ()>>())
+}
+
+// Asks a message to the group's element, terminates it using
+// `terminate` once the message was queued and checks that the
+// question fails instead of never resolving.
+fn unanswered(children: &ChildrenRef, terminate: impl FnOnce()) {
+    let answer = children.elems()[0].ask_anonymously("question").unwrap();
+    thread::sleep(Duration::from_millis(100));
+    terminate();
+
+    // The element never waits for a message, so a stopped group
+    // only kills it once its grace period expired.
+    let result = run!(async move {
+        match future::select(answer, Delay::new(Duration::from_secs(10))).await {
+            Either::Left((result, _)) => Some(result),
+            Either::Right(_) => None,
+        }
+    });
+
+    match result {
+        Some(result) => assert_eq!(result.unwrap_err(), AnswerError::Unanswered),
+        None => panic!("The question was never answered."),
+    }
+}
+
+#[test]
+fn group_killed() {
+    init_start();
+
+    let children = Bastion::children(idle).unwrap();
+    unanswered(&children, || children.kill().unwrap());
+}
+
+#[test]
+fn group_stopped() {
+    init_start();
+
+    let children = Bastion::children(idle).unwrap();
+    unanswered(&children, || children.stop().unwrap());
+}
+
+#[test]
+fn supervisor_stopped() {
+    init_start();
+
+    let supervisor = Bastion::supervisor(|sp| sp).unwrap();
+    let children = supervisor.children(idle).unwrap();
+    unanswered(&children, || supervisor.stop().unwrap());
+}
+
+#[test]
+fn elem_panicked() {
+    init_start();
+
+    let children = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            // Panics while holding the question.
+            let _question = ctx.recv().await?;
+            panic!("Element panicked.");
+        })
+    })
+    .unwrap();
+    unanswered(&children, || ());
+}
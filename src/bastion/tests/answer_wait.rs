@@ -0,0 +1,105 @@
+mod common;
+
+use bastion::prelude::*;
+use common::init_start;
+use futures::channel::oneshot;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// An element doubling the `u64`s it's asked.
+fn doubling() -> ChildRef {
+    let children = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    n: u64 =!> {
+                        answer!(ctx, n * 2).ok();
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    children.elems()[0].clone()
+}
+
+// An element keeping the messages it's asked without answering
+// them.
+fn holding() -> ChildRef {
+    let children = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            let mut held = Vec::new();
+            loop {
+                held.push(ctx.recv().await?);
+            }
+        })
+    })
+    .unwrap();
+
+    children.elems()[0].clone()
+}
+
+#[test]
+fn await_typed() {
+    init_start();
+
+    let child = doubling();
+    let answer = child.ask_anonymously(21u64).unwrap();
+    assert_eq!(run!(answer.await_typed::<u64>()), Ok(42));
+
+    let answer = child.ask_anonymously(21u64).unwrap();
+    assert_eq!(
+        run!(answer.await_typed::<u32>()),
+        Err(AnswerError::UnexpectedType {
+            expected: "u32",
+            got: "u64",
+        })
+    );
+}
+
+#[test]
+fn wait() {
+    init_start();
+
+    let child = doubling();
+    let answer = child.ask_anonymously(21u64).unwrap();
+    let (msg, _) = answer.wait(Duration::from_secs(5)).unwrap().extract();
+    assert_eq!(msg.downcast::<u64>().unwrap(), 42);
+
+    let answer = holding().ask_anonymously("held").unwrap();
+    let started = Instant::now();
+    let waited = answer.wait(Duration::from_millis(100));
+    assert_eq!(waited.unwrap_err(), AnswerError::TimedOut);
+    assert!(started.elapsed() >= Duration::from_millis(100));
+}
+
+#[test]
+fn wait_from_elem() {
+    init_start();
+
+    let child = doubling();
+    let (sender, waited) = oneshot::channel();
+    let sender = Mutex::new(Some(sender));
+    Bastion::children(move |children| {
+        let child = child.clone();
+        children.with_exec(move |_| {
+            let answer = child.ask_anonymously(21u64).unwrap();
+            let sender = sender.lock().unwrap().take();
+            async move {
+                // Blocking the worker would prevent the answer from
+                // being received.
+                let waited = answer.wait(Duration::from_secs(5)).map(|_| ());
+                if let Some(sender) = sender {
+                    sender.send(waited).ok();
+                }
+
+                Ok::<(), ()>(())
+            }
+        })
+    })
+    .unwrap();
+
+    assert_eq!(run!(waited), Ok(Err(AnswerError::WouldBlockExecutor)));
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+
+#[test]
+fn answers_in_elems_order() {
+    Bastion::init();
+    Bastion::start();
+
+    // Every element answers with its identifier.
+    let children = Bastion::children(|children| {
+        children
+            .with_redundancy(3)
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        _question: &'static str =!> {
+                            answer!(ctx, ctx.current().id().clone()).unwrap();
+                        };
+                        _: _ => ();
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    let pruned = children.elems()[1].id().clone();
+    children.prune_elem(&pruned).unwrap();
+    wait_for(|| children.elems()[1].tell_anonymously(()).is_err());
+
+    let answers = children.ask_everyone("Who are you?").unwrap();
+    assert_eq!(answers.len(), 3);
+
+    for (elem, answer) in children.elems().iter().zip(answers) {
+        let answer = run!(answer);
+        if elem.id() == &pruned {
+            assert_eq!(answer.unwrap_err(), AnswerError::Unanswered);
+            continue;
+        }
+
+        msg! { answer.unwrap(),
+            id: BastionId => assert_eq!(&id, elem.id());
+            _: _ => panic!("Unexpected answer.");
+        }
+    }
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use bastion::prelude::*;
+use common::init_start;
+use futures::channel::oneshot;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+const BACKLOG: usize = 10_000;
+
+// Makes the element wait until it is released.
+#[derive(Debug)]
+struct Hold;
+
+// An element of a group asked with this priority by default,
+// answering each question with the number of messages it handled
+// (including it), and the sender releasing it once it holds.
+fn holding(ask_priority: Priority) -> (ChildRef, oneshot::Sender<()>) {
+    let (release, released) = oneshot::channel::<()>();
+    let released = Arc::new(Mutex::new(Some(released)));
+    let children = Bastion::children(move |children| {
+        let released = released.clone();
+        children
+            .with_ask_priority(ask_priority)
+            .with_exec(move |ctx: BastionContext| {
+                let released = released.clone();
+                async move {
+                    let handled = AtomicUsize::new(0);
+                    loop {
+                        let signed = ctx.recv().await?;
+                        let handled = handled.fetch_add(1, Ordering::SeqCst) + 1;
+                        msg! { signed,
+                            _msg: Hold => {
+                                let released = released.lock().unwrap().take();
+                                if let Some(released) = released {
+                                    released.await.ok();
+                                }
+                            };
+                            _msg: &'static str =!> {
+                                answer!(ctx, handled).ok();
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    let child = children.elems()[0].clone();
+    child.tell_anonymously(Hold).unwrap();
+    for n in 0..BACKLOG {
+        child.tell_anonymously(n).unwrap();
+    }
+
+    (child, release)
+}
+
+fn handled(answer: Answer) -> (usize, Priority) {
+    let (msg, _) = run!(answer).unwrap().extract();
+    let priority = msg.priority();
+    (msg.downcast().unwrap(), priority)
+}
+
+#[test]
+fn high_priority_skips_backlog() {
+    init_start();
+
+    let (child, release) = holding(Priority::Normal);
+    let normal = child.ask_anonymously("normal").unwrap();
+    let high = child.ask_with_priority("high", Priority::High).unwrap();
+    release.send(()).unwrap();
+
+    // The question lands before the backlog (and maybe before the
+    // message making the element hold)...
+    let (high, priority) = handled(high);
+    assert!(high <= 2, "Answered after {} messages.", high);
+    assert_eq!(priority, Priority::High);
+
+    // ...unlike the ones asked with the normal priority.
+    let (normal, priority) = handled(normal);
+    assert_eq!(normal, BACKLOG + 3);
+    assert_eq!(priority, Priority::Normal);
+}
+
+#[test]
+fn group_default() {
+    init_start();
+
+    let (child, release) = holding(Priority::High);
+    let high = child.ask_anonymously("high").unwrap();
+    release.send(()).unwrap();
+
+    let (high, priority) = handled(high);
+    assert!(high <= 2, "Answered after {} messages.", high);
+    assert_eq!(priority, Priority::High);
+}
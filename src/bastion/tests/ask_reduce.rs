@@ -0,0 +1,106 @@
+mod common;
+
+use bastion::prelude::*;
+use common::init_start;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// What the first element launched does with the questions.
+#[derive(Clone, Copy)]
+enum First {
+    Answers,
+    Holds,
+    Drops,
+}
+
+// A group of four elements doubling the `u64`s they're asked,
+// except maybe the first one.
+fn doubling(first: First) -> ChildrenRef {
+    let launched = Arc::new(AtomicUsize::new(0));
+    Bastion::children(move |children| {
+        let launched = launched.clone();
+        children
+            .with_redundancy(4)
+            .with_exec(move |ctx: BastionContext| {
+                let is_first = launched.fetch_add(1, Ordering::SeqCst) == 0;
+                async move {
+                    let mut held = Vec::new();
+                    loop {
+                        let signed = ctx.recv().await?;
+                        match first {
+                            First::Holds if is_first => held.push(signed),
+                            First::Drops if is_first => drop(signed),
+                            _ => msg! { signed,
+                                n: u64 =!> {
+                                    answer!(ctx, n * 2).ok();
+                                };
+                                _: _ => ();
+                            },
+                        }
+                    }
+                }
+            })
+    })
+    .unwrap()
+}
+
+fn sum(sum: u64, answer: Msg) -> u64 {
+    sum + answer.downcast::<u64>().unwrap()
+}
+
+#[test]
+fn every_elem_answers() {
+    init_start();
+
+    let children = doubling(First::Answers);
+    let reduced = children.ask_reduce(21u64, 0, sum, Duration::from_secs(5));
+    assert_eq!(run!(reduced), Ok(4 * 42));
+}
+
+#[test]
+fn partial_on_timeout() {
+    init_start();
+
+    let children = doubling(First::Holds);
+    let started = Instant::now();
+    let reduced = children.ask_reduce(21u64, 0, sum, Duration::from_millis(200));
+    let err = run!(reduced).unwrap_err();
+    assert!(started.elapsed() >= Duration::from_millis(200));
+
+    assert_eq!(*err.partial(), 3 * 42);
+    assert_eq!(err.answered(), 3);
+    assert_eq!(err.unanswered(), 0);
+    assert_eq!(err.timed_out(), 1);
+    assert_eq!(err.send_error(), None);
+    assert_eq!(err.into_partial(), 3 * 42);
+}
+
+#[test]
+fn partial_on_unanswered() {
+    init_start();
+
+    let children = doubling(First::Drops);
+    let reduced = children.ask_reduce(21u64, 0, sum, Duration::from_secs(5));
+    let err = run!(reduced).unwrap_err();
+
+    assert_eq!(*err.partial(), 3 * 42);
+    assert_eq!(err.answered(), 3);
+    assert_eq!(err.unanswered(), 1);
+    assert_eq!(err.timed_out(), 0);
+}
+
+#[test]
+fn not_accepted() {
+    init_start();
+
+    let children = Bastion::children(|children| children.with_accepted_types::<(bool,)>()).unwrap();
+    let reduced = children.ask_reduce(21u64, 0, sum, Duration::from_secs(5));
+    let err = run!(reduced).unwrap_err();
+
+    assert_eq!(*err.partial(), 0);
+    assert!(matches!(
+        err.send_error(),
+        Some(SendErrorKind::TypeNotAccepted { .. })
+    ));
+}
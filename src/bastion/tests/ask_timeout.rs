@@ -0,0 +1,107 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// A child answering the `u64`s it's asked after waiting for this
+// many milliseconds, recording whether its answers were sent.
+fn delaying(sent: &Arc<Mutex<Vec<bool>>>) -> ChildRef {
+    let sent = sent.clone();
+    let children = Bastion::children(|children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let sent = sent.clone();
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        millis: u64 =!> {
+                            thread::sleep(Duration::from_millis(millis));
+                            let answered = answer!(ctx, millis);
+                            sent.lock().unwrap().push(answered.is_ok());
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    children.elems()[0].clone()
+}
+
+#[test]
+fn answered_in_time() {
+    init_start();
+
+    let sent = Arc::new(Mutex::new(Vec::new()));
+    let child = delaying(&sent);
+    let answer = child.ask_timeout(0u64, Duration::from_secs(5)).unwrap();
+    msg! { run!(answer).unwrap(),
+        millis: u64 => assert_eq!(millis, 0);
+        _: _ => panic!("Unexpected answer.");
+    }
+    wait_for(|| !sent.lock().unwrap().is_empty());
+    assert_eq!(*sent.lock().unwrap(), vec![true]);
+}
+
+#[test]
+fn slow() {
+    init_start();
+
+    let sent = Arc::new(Mutex::new(Vec::new()));
+    let child = delaying(&sent);
+    let answer = child
+        .ask_timeout(300u64, Duration::from_millis(50))
+        .unwrap();
+    assert_eq!(run!(answer).unwrap_err(), AnswerError::TimedOut);
+
+    // The late answer fails to be sent...
+    wait_for(|| !sent.lock().unwrap().is_empty());
+    assert_eq!(*sent.lock().unwrap(), vec![false]);
+
+    // ...without disturbing the child.
+    let answer = child.ask_timeout(0u64, Duration::from_secs(5)).unwrap();
+    assert!(run!(answer).is_ok());
+}
+
+#[test]
+fn ignored() {
+    init_start();
+
+    let children = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            // The questions are kept without being answered.
+            let mut held = Vec::new();
+            loop {
+                held.push(ctx.recv().await?);
+            }
+        })
+    })
+    .unwrap();
+    let child = &children.elems()[0];
+    let answer = child.ask_timeout(0u64, Duration::from_millis(50)).unwrap();
+    assert_eq!(run!(answer).unwrap_err(), AnswerError::TimedOut);
+}
+
+#[test]
+fn dead() {
+    init_start();
+
+    let children = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            // The child stops once it received the question...
+            ctx.recv().await?;
+            Ok(())
+        })
+    })
+    .unwrap();
+    let child = &children.elems()[0];
+    let answer = child.ask_timeout(0u64, Duration::from_secs(5)).unwrap();
+    // ...which resolves the answer without waiting for the timeout.
+    let started = Instant::now();
+    assert_eq!(run!(answer).unwrap_err(), AnswerError::Unanswered);
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
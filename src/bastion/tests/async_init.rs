@@ -0,0 +1,66 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+// A connection opened by the elements' async init, which fails
+// to open it the first `failures` times.
+#[derive(Debug)]
+struct Connection(usize);
+
+// A group whose elements record the connections they execute
+// their future with.
+fn connecting(failures: usize) -> (Arc<AtomicUsize>, Arc<Mutex<Vec<usize>>>) {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let connected = Arc::new(Mutex::new(Vec::new()));
+    let (attempted, recorded) = (attempts.clone(), connected.clone());
+    Bastion::children(move |children| {
+        children
+            .with_async_init(move |_: BastionContext| {
+                let attempt = attempted.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if attempt < failures {
+                        Err("Connection refused.")
+                    } else {
+                        Ok(Connection(attempt))
+                    }
+                }
+            })
+            .with_exec(move |conn: Connection, ctx: BastionContext| {
+                let recorded = recorded.clone();
+                async move {
+                    recorded.lock().unwrap().push(conn.0);
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    (attempts, connected)
+}
+
+#[test]
+fn state() {
+    init_start();
+
+    let (attempts, connected) = connecting(0);
+    wait_for(|| !connected.lock().unwrap().is_empty());
+    assert_eq!(*connected.lock().unwrap(), vec![0]);
+    assert_eq!(attempts.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn failing_init() {
+    init_start();
+
+    // The element faults and is restarted, running its init
+    // again, until it succeeds.
+    let (attempts, connected) = connecting(2);
+    wait_for(|| !connected.lock().unwrap().is_empty());
+    assert_eq!(*connected.lock().unwrap(), vec![2]);
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use bastion::prelude::*;
+use bastion::sync::{Barrier, BarrierError};
+use common::{init_start, wait_for};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// Creates a children group whose element calls `f` once.
+fn spawn<F, Fut>(f: F) -> ChildrenRef
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: std::future::Future<Output = ()> + Send + 'static,
+{
+    let f = Arc::new(f);
+    Bastion::children(move |children| {
+        let f = f.clone();
+        children.with_exec(move |_: BastionContext| {
+            let fut = f();
+            async move {
+                fut.await;
+                Ok(())
+            }
+        })
+    })
+    .unwrap()
+}
+
+#[test]
+fn rendezvous() {
+    init_start();
+
+    const PARTIES: usize = 3;
+    let barrier = Barrier::new(PARTIES).unwrap();
+    let arrived = Arc::new(AtomicUsize::new(0));
+    let released = Arc::new(Mutex::new(Vec::new()));
+
+    for _ in 0..PARTIES {
+        let (barrier, arrived, released) = (barrier.clone(), arrived.clone(), released.clone());
+        spawn(move || {
+            let (barrier, arrived, released) = (barrier.clone(), arrived.clone(), released.clone());
+            async move {
+                arrived.fetch_add(1, Ordering::SeqCst);
+                let result = barrier.wait().await.unwrap();
+                // Every participant arrived before any is released.
+                let seen = arrived.load(Ordering::SeqCst);
+                released.lock().unwrap().push((seen, result.is_leader()));
+            }
+        });
+    }
+
+    wait_for(|| released.lock().unwrap().len() == PARTIES);
+    let released = released.lock().unwrap();
+    assert!(released.iter().all(|(seen, _)| *seen == PARTIES));
+    assert_eq!(released.iter().filter(|(_, leader)| *leader).count(), 1);
+}
+
+#[test]
+fn participant_lost() {
+    init_start();
+
+    let barrier = Barrier::new(3).unwrap();
+    let result = Arc::new(Mutex::new(None));
+
+    let (barrier_inner, result_inner) = (barrier.clone(), result.clone());
+    spawn(move || {
+        let (barrier, result) = (barrier_inner.clone(), result_inner.clone());
+        async move {
+            let waited = barrier.wait().await;
+            *result.lock().unwrap() = Some(waited);
+        }
+    });
+
+    let barrier_inner = barrier.clone();
+    let lost = spawn(move || {
+        let barrier = barrier_inner.clone();
+        async move {
+            barrier.wait().await.ok();
+        }
+    });
+
+    // Both participants are waiting for the third one...
+    thread::sleep(Duration::from_millis(100));
+    assert!(result.lock().unwrap().is_none());
+
+    // ...until one of them gets killed.
+    lost.kill().unwrap();
+    wait_for(|| result.lock().unwrap().is_some());
+    let waited = result.lock().unwrap().take().unwrap();
+    assert_eq!(waited, Err(BarrierError::ParticipantLost));
+}
+
+#[test]
+fn reuse() {
+    init_start();
+
+    const PARTIES: usize = 2;
+    const ROUNDS: usize = 2;
+    let barrier = Barrier::new(PARTIES).unwrap();
+    let leaders = Arc::new(Mutex::new(vec![0; ROUNDS]));
+    let finished = Arc::new(AtomicUsize::new(0));
+
+    for _ in 0..PARTIES {
+        let (barrier, leaders, finished) = (barrier.clone(), leaders.clone(), finished.clone());
+        spawn(move || {
+            let (barrier, leaders, finished) = (barrier.clone(), leaders.clone(), finished.clone());
+            async move {
+                for round in 0..ROUNDS {
+                    if barrier.wait().await.unwrap().is_leader() {
+                        leaders.lock().unwrap()[round] += 1;
+                    }
+                }
+
+                finished.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+    }
+
+    wait_for(|| finished.load(Ordering::SeqCst) == PARTIES);
+    assert_eq!(*leaders.lock().unwrap(), vec![1; ROUNDS]);
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use bastion::children_ref::GroupState;
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const LONG: Duration = Duration::from_secs(3600);
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum Received {
+    Batch(Vec<u64>),
+    Other(&'static str),
+}
+
+// A group batching `u64`s, recording the batches and the other
+// messages its element receives.
+fn batching(max_items: usize, max_delay: Duration) -> (ChildrenRef, Arc<Mutex<Vec<Received>>>) {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let recorded = received.clone();
+    let children = Bastion::children(move |children| {
+        let recorded = recorded.clone();
+        children
+            .with_batching::<u64>(max_items, max_delay)
+            .with_exec(move |ctx: BastionContext| {
+                let recorded = recorded.clone();
+                async move {
+                    loop {
+                        msg! { ctx.recv().await?,
+                            batch: Batch<u64> => {
+                                let batch = Received::Batch(batch.into_inner());
+                                recorded.lock().unwrap().push(batch);
+                            };
+                            msg: &'static str => {
+                                recorded.lock().unwrap().push(Received::Other(msg));
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    (children, received)
+}
+
+#[test]
+fn max_items() {
+    init_start();
+
+    let (children, received) = batching(10, LONG);
+    for n in 0..25u64 {
+        children.tell_one(n).unwrap();
+    }
+
+    wait_for(|| received.lock().unwrap().len() == 2);
+    // The last 5 messages wait for the batch to be full.
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec![
+            Received::Batch((0..10).collect()),
+            Received::Batch((10..20).collect()),
+        ]
+    );
+}
+
+#[test]
+fn max_delay() {
+    init_start();
+
+    let (children, received) = batching(100, Duration::from_millis(100));
+    let started = Instant::now();
+    for n in 0..3u64 {
+        children.tell_one(n).unwrap();
+    }
+    // The messages of other types aren't batched.
+    children.tell_one("other").unwrap();
+
+    wait_for(|| received.lock().unwrap().len() == 2);
+    assert!(started.elapsed() >= Duration::from_millis(100));
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec![Received::Other("other"), Received::Batch(vec![0, 1, 2])]
+    );
+}
+
+#[test]
+fn stop() {
+    init_start();
+
+    let (children, received) = batching(100, LONG);
+    for n in 0..7u64 {
+        children.tell_one(n).unwrap();
+    }
+
+    let stopped = run!(children.stop_with_timeout(Duration::from_secs(5)));
+    assert_eq!(stopped, Ok(GroupState::Stopped));
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec![Received::Batch((0..7).collect())]
+    );
+
+    // The elements also get to handle it when simply stopped.
+    let (children, received) = batching(100, LONG);
+    for n in 0..3u64 {
+        children.tell_one(n).unwrap();
+    }
+
+    let stopped = run!(children.stop_and_wait());
+    assert_eq!(stopped, Ok(GroupState::Stopped));
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec![Received::Batch((0..3).collect())]
+    );
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use bastion::blocking_bridge::{self, BridgeError};
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use futures::future;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// Answers the `u64`s it receives with their double and ignores
+// everything else.
+fn doubling() -> ChildrenRef {
+    Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    n: u64 =!> {
+                        answer!(ctx, n * 2).unwrap();
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+    })
+    .unwrap()
+}
+
+#[test]
+fn from_thread() {
+    init_start();
+
+    let children = doubling();
+    let elem = children.elems()[0].clone();
+
+    let answer =
+        thread::spawn(move || blocking_bridge::ask_blocking(&elem, 21u64, Duration::from_secs(5)))
+            .join()
+            .unwrap()
+            .unwrap();
+    assert_eq!(answer.downcast::<u64>().unwrap(), 42);
+}
+
+#[test]
+fn unanswered() {
+    init_start();
+
+    let children = doubling();
+    let result =
+        blocking_bridge::ask_blocking(&children.elems()[0], "ignored", Duration::from_secs(5));
+    assert_eq!(result.unwrap_err(), BridgeError::Unanswered);
+}
+
+#[test]
+fn timed_out() {
+    init_start();
+
+    // Never retrieves its messages.
+    let children = Bastion::children(|children| {
+        children.with_exec(|_: BastionContext| future::pending::<Result<(), ()>>())
+    })
+    .unwrap();
+    let result =
+        blocking_bridge::ask_blocking(&children.elems()[0], 21u64, Duration::from_millis(100));
+    assert_eq!(result.unwrap_err(), BridgeError::TimedOut);
+}
+
+#[test]
+fn from_child() {
+    init_start();
+
+    let target = doubling();
+    let elem = target.elems()[0].clone();
+    let result = Arc::new(Mutex::new(None));
+    let recorded = result.clone();
+
+    Bastion::children(move |children| {
+        let elem = elem.clone();
+        let recorded = recorded.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let elem = elem.clone();
+            let recorded = recorded.clone();
+            async move {
+                let result = blocking_bridge::ask_blocking(&elem, 21u64, Duration::from_secs(5));
+                *recorded.lock().unwrap() = Some(result.map(|_| ()));
+
+                loop {
+                    ctx.recv().await?;
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    wait_for(|| result.lock().unwrap().is_some());
+    assert_eq!(
+        result.lock().unwrap().take().unwrap(),
+        Err(BridgeError::WouldBlockExecutor)
+    );
+}
@@ -0,0 +1,169 @@
+mod common;
+
+use bastion::bridge::{self, Exhaustion, SourceExhausted};
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use futures::channel::mpsc;
+use futures::prelude::*;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Default)]
+struct Received {
+    items: Mutex<Vec<u64>>,
+    exhausted: Mutex<Vec<BastionId>>,
+}
+
+impl Received {
+    fn items(&self) -> Vec<u64> {
+        self.items.lock().unwrap().clone()
+    }
+}
+
+// A group recording the `u64`s and the exhaustion notifications it
+// receives, whose element takes `delay` to handle each `u64` and
+// faults the first time it receives `fault_on`.
+fn target(
+    received: &Arc<Received>,
+    delay: Duration,
+    fault_on: Option<u64>,
+    children: Children,
+) -> Children {
+    let received = received.clone();
+    let faulted = Arc::new(Mutex::new(false));
+    children.with_exec(move |ctx: BastionContext| {
+        let received = received.clone();
+        let faulted = faulted.clone();
+        async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    n: u64 => {
+                        received.items.lock().unwrap().push(n);
+                        ctx.sleep(delay).await;
+                        if Some(n) == fault_on && !*faulted.lock().unwrap() {
+                            *faulted.lock().unwrap() = true;
+                            return Err(());
+                        }
+                    };
+                    ref exhausted: SourceExhausted => {
+                        received.exhausted.lock().unwrap().push(exhausted.bridge().clone());
+                    };
+                    _: _ => ();
+                }
+            }
+        }
+    })
+}
+
+#[test]
+fn from_stream() {
+    init_start();
+
+    let received = Arc::new(Received::default());
+    let children =
+        Bastion::children(|children| target(&received, Duration::default(), None, children))
+            .unwrap();
+
+    let (sender, recver) = mpsc::unbounded();
+    let recver = Arc::new(Mutex::new(Some(recver)));
+    let bridge = bridge::from_stream(
+        move || stream::iter(recver.lock().unwrap().take()).flatten(),
+        children,
+        |n: u64| n * 2,
+    )
+    .with_exhaustion(Exhaustion::NotifyTarget)
+    .build()
+    .unwrap();
+
+    for n in 0..100 {
+        sender.unbounded_send(n).unwrap();
+    }
+    wait_for(|| received.items().len() == 100);
+    assert_eq!(
+        received.items(),
+        (0..100).map(|n| n * 2).collect::<Vec<_>>()
+    );
+
+    // The target is notified once the stream is exhausted.
+    assert!(received.exhausted.lock().unwrap().is_empty());
+    drop(sender);
+    wait_for(|| !received.exhausted.lock().unwrap().is_empty());
+    assert_eq!(
+        *received.exhausted.lock().unwrap(),
+        vec![bridge.id().clone()]
+    );
+}
+
+#[test]
+fn from_blocking_iter_with_back_pressure() {
+    init_start();
+
+    // The target's mailbox is smaller than the number of items,
+    // which are told faster than they are handled.
+    let received = Arc::new(Received::default());
+    let children = Bastion::children(|children| {
+        target(&received, Duration::from_millis(1), None, children)
+            .with_mailbox_capacity(4)
+            .with_overflow_policy(OverflowPolicy::Fail)
+    })
+    .unwrap();
+
+    bridge::from_blocking_iter(|| 0..50u64, children, |n| n)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    // None of the items was dropped.
+    wait_for(|| received.items().len() == 50);
+    assert_eq!(received.items(), (0..50).collect::<Vec<_>>());
+}
+
+#[test]
+fn target_restart() {
+    init_start();
+
+    // The target's element faults while handling the 10th item, and
+    // is restarted by its group.
+    let received = Arc::new(Received::default());
+    let children = Bastion::children(|children| {
+        target(&received, Duration::from_millis(1), Some(10), children)
+    })
+    .unwrap();
+
+    bridge::from_blocking_iter(|| 0..50u64, children.clone(), |n| n)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    // The items told while the element was being restarted are
+    // told to the restarted element.
+    wait_for(|| received.items().contains(&49));
+    let items = received.items();
+    assert_eq!(items[..11], (0..=10).collect::<Vec<_>>()[..]);
+    for n in 11..50 {
+        assert!(items.contains(&n), "{} is missing: {:?}", n, items);
+    }
+    wait_for(|| children.elems()[0].stats().restarts() == 1);
+}
+
+#[cfg(feature = "supervision")]
+#[test]
+fn restart_source() {
+    init_start();
+
+    let received = Arc::new(Received::default());
+    let children =
+        Bastion::children(|children| target(&received, Duration::default(), None, children))
+            .unwrap();
+
+    let backoff = Backoff::exponential(Duration::from_millis(10), Duration::from_millis(50), 0.0);
+    bridge::from_blocking_iter(|| 0..3u64, children, |n| n)
+        .unwrap()
+        .with_exhaustion(Exhaustion::Restart(backoff))
+        .build()
+        .unwrap();
+
+    // The iterator is created again each time it is exhausted.
+    wait_for(|| received.items().len() >= 9);
+    assert_eq!(received.items()[..9], [0, 1, 2, 0, 1, 2, 0, 1, 2]);
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "supervision")]
+mod common;
+
+use bastion::bridge;
+use bastion::prelude::*;
+use common::wait_for;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn relaunched_target() {
+    let config = Config::new()
+        .with_root_restart_window(3, Duration::from_secs(60))
+        .on_root_exhaustion(RootAction::Degrade {
+            keep: vec!["bridge".to_string()],
+        });
+    Bastion::init_with(config);
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let target = Bastion::children(|children| {
+        let received = received.clone();
+        children
+            .with_name("sink")
+            .with_exec(move |ctx: BastionContext| {
+                let received = received.clone();
+                async move {
+                    loop {
+                        msg! { ctx.recv().await?,
+                            n: u64 => received.lock().unwrap().push(n);
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    let (sender, recver) = mpsc::channel::<u64>();
+    let recver = Mutex::new(Some(recver));
+    let bridge = bridge::from_blocking_iter(
+        move || recver.lock().unwrap().take().into_iter().flatten(),
+        target.clone(),
+        |n| n,
+    )
+    .unwrap()
+    .with_target_grace(Duration::from_secs(5));
+    Bastion::children(|children| bridge.configure(children).with_name("bridge")).unwrap();
+
+    Bastion::start();
+    sender.send(1).unwrap();
+    wait_for(|| *received.lock().unwrap() == [1]);
+
+    // Faults as soon as it starts, until told otherwise, which
+    // degrades the system.
+    let faulting = Arc::new(AtomicBool::new(true));
+    let hot = faulting.clone();
+    Bastion::children(move |children| {
+        let hot = hot.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let hot = hot.clone();
+            async move {
+                if hot.load(Ordering::SeqCst) {
+                    return Err(());
+                }
+
+                loop {
+                    ctx.recv().await?;
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    wait_for(Bastion::is_degraded);
+    wait_for(|| Bastion::children_of("sink").is_none());
+
+    // The bridge waits for its target to be relaunched...
+    sender.send(2).unwrap();
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(*received.lock().unwrap(), vec![1]);
+
+    faulting.store(false, Ordering::SeqCst);
+    Bastion::recover();
+    wait_for(|| Bastion::children_of("sink").is_some());
+
+    // ...and tells the messages to the relaunched group.
+    sender.send(3).unwrap();
+    wait_for(|| received.lock().unwrap().len() == 3);
+    assert_eq!(*received.lock().unwrap(), vec![1, 2, 3]);
+    assert_eq!(Bastion::children_of("sink").unwrap().id(), target.id());
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
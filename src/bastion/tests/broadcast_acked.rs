@@ -0,0 +1,130 @@
+mod common;
+
+use bastion::prelude::*;
+use common::init_start;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Default)]
+struct Behavior {
+    // The next element receiving the message takes a second to
+    // apply it.
+    slow: AtomicBool,
+    // The next element receiving the message faults before
+    // acknowledging it.
+    fault: AtomicBool,
+    // The number of times the message was received.
+    received: AtomicUsize,
+}
+
+// A group of `redundancy` elements acknowledging the "config"s
+// they receive, depending on `behavior`.
+fn acking(redundancy: usize, behavior: Arc<Behavior>) -> ChildrenRef {
+    Bastion::children(move |children| {
+        let behavior = behavior.clone();
+        children
+            .with_redundancy(redundancy)
+            .with_exec(move |ctx: BastionContext| {
+                let behavior = behavior.clone();
+                async move {
+                    loop {
+                        let msg = ctx.recv().await?;
+                        let token = msg.ack_token();
+                        msg! { msg,
+                            ref _config: &'static str => {
+                                behavior.received.fetch_add(1, Ordering::SeqCst);
+                                if behavior.fault.swap(false, Ordering::SeqCst) {
+                                    return Err(());
+                                } else if behavior.slow.swap(false, Ordering::SeqCst) {
+                                    ctx.sleep(Duration::from_secs(1)).await;
+                                }
+
+                                ctx.ack(token.unwrap());
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .unwrap()
+}
+
+fn sorted(ids: &[BastionId]) -> Vec<BastionId> {
+    let mut ids = ids.to_vec();
+    ids.sort_by_key(|id| id.to_string());
+    ids
+}
+
+#[test]
+fn all_acked() {
+    init_start();
+
+    let children = acking(3, Arc::default());
+    let report = run!(children.broadcast_acked("config", Duration::from_secs(5)));
+
+    assert!(report.is_complete(), "{:?}", report);
+    let ids = children.elems().iter().map(|elem| elem.id().clone());
+    assert_eq!(sorted(report.acked()), sorted(&ids.collect::<Vec<_>>()));
+}
+
+#[test]
+fn slow_elem_times_out() {
+    init_start();
+
+    let behavior = Arc::new(Behavior::default());
+    behavior.slow.store(true, Ordering::SeqCst);
+    let children = acking(2, behavior);
+    let report = run!(children.broadcast_acked("config", Duration::from_millis(200)));
+
+    assert!(!report.is_complete());
+    assert_eq!(report.acked().len(), 1);
+    assert_eq!(report.pending().len(), 1);
+    assert!(report.dead().is_empty());
+}
+
+#[test]
+fn restarted_mid_broadcast() {
+    init_start();
+
+    let behavior = Arc::new(Behavior::default());
+    behavior.fault.store(true, Ordering::SeqCst);
+    let children = acking(2, behavior.clone());
+    let report = run!(children.broadcast_acked("config", Duration::from_secs(5)));
+
+    // The faulted element received the message again once
+    // restarted, and acknowledged it.
+    assert!(report.is_complete(), "{:?}", report);
+    assert_eq!(report.acked().len(), 2);
+    assert_eq!(behavior.received.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn dead_elem() {
+    init_start();
+
+    let children = acking(2, Arc::default());
+    let killed = children.elems()[0].clone();
+    let watcher = killed.watch();
+    killed.kill().unwrap();
+    run!(watcher);
+
+    let report = run!(children.broadcast_acked("config", Duration::from_millis(200)));
+    assert_eq!(report.dead(), &[killed.id().clone()][..]);
+    assert_eq!(report.acked(), &[children.elems()[1].id().clone()][..]);
+}
+
+#[test]
+fn not_sent() {
+    init_start();
+
+    let children = Bastion::children(|children| children.with_accepted_types::<(u64,)>()).unwrap();
+    let report = run!(children.broadcast_acked("config", Duration::from_secs(5)));
+
+    assert!(matches!(
+        report.send_error(),
+        Some(SendErrorKind::TypeNotAccepted { .. })
+    ));
+    assert!(report.acked().is_empty());
+}
@@ -0,0 +1,214 @@
+mod common;
+
+use bastion::children_ref::GroupState;
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+// Creates a group counting its started elements, configured by
+// `configure`, and returns the conflict it was rejected with.
+fn rejected(configure: impl FnOnce(Children) -> Children) -> BuilderConflict {
+    let started = Arc::new(AtomicUsize::new(0));
+    let counted = started.clone();
+    let err = Bastion::children(move |children| {
+        configure(children.with_exec(move |ctx: BastionContext| {
+            counted.fetch_add(1, Ordering::SeqCst);
+            async move {
+                loop {
+                    ctx.recv().await?;
+                }
+            }
+        }))
+    })
+    .unwrap_err();
+
+    // None of the elements was launched.
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(started.load(Ordering::SeqCst), 0);
+
+    match err {
+        BuilderError::Conflict(conflict) => conflict,
+        err => panic!("Unexpected error: {}", err),
+    }
+}
+
+#[test]
+fn overflow_without_capacity() {
+    init_start();
+
+    let conflict = rejected(|children| children.with_overflow_policy(OverflowPolicy::DropOldest));
+    assert_eq!(conflict, BuilderConflict::OverflowWithoutCapacity);
+    assert_eq!(
+        conflict.methods(),
+        &["with_overflow_policy", "with_mailbox_capacity"]
+    );
+
+    // The policy set by a preset overrides the one set before it,
+    // along with its capacity.
+    let children = Bastion::children(|children| {
+        children
+            .with_overflow_policy(OverflowPolicy::DropOldest)
+            .apply_preset(SupervisionPreset::batch_pipeline())
+    })
+    .unwrap();
+    assert_eq!(children.config().mailbox_capacity(), Some(10_000));
+    assert_eq!(children.config().overflow_policy(), OverflowPolicy::Block);
+    assert_eq!(children.config().preset(), Some("batch_pipeline"));
+}
+
+#[test]
+fn batched_type_not_accepted() {
+    init_start();
+
+    let conflict = rejected(|children| {
+        children
+            .with_accepted_types::<(u8, String)>()
+            .with_batching::<u64>(16, Duration::from_millis(10))
+    });
+    assert_eq!(
+        conflict,
+        BuilderConflict::BatchedTypeNotAccepted { batched: "u64" }
+    );
+    assert_eq!(
+        conflict.methods(),
+        &["with_batching", "with_accepted_types"]
+    );
+}
+
+#[test]
+fn missing_routing_key() {
+    init_start();
+
+    let conflict = rejected(|children| children.with_routing(Routing::HashBy));
+    assert_eq!(conflict, BuilderConflict::MissingRoutingKey);
+    assert_eq!(conflict.methods(), &["with_routing", "with_routing_key"]);
+
+    Bastion::children(|children| {
+        children
+            .with_routing(Routing::HashBy)
+            .with_routing_key(|n: &u64| *n)
+    })
+    .unwrap();
+}
+
+#[test]
+fn supervised() {
+    init_start();
+
+    // A supervisor returns the conflicts of the groups it creates...
+    let mut rejected = None;
+    let sp = Bastion::supervisor(|sp| {
+        rejected = Some(sp.children_ref(|children| children.with_routing(Routing::HashBy)));
+        sp
+    })
+    .unwrap();
+    let err = BuilderError::Conflict(BuilderConflict::MissingRoutingKey);
+    assert_eq!(rejected.unwrap().unwrap_err(), err);
+
+    // ...and isn't created if one of the groups added to it (or to
+    // the supervisors it supervises) conflicts, the groups that
+    // were deployed being stopped.
+    let mut deployed = None;
+    let conflict = sp
+        .supervisor(|sp| {
+            let sp = sp.supervisor(|nested| {
+                nested.children(|children| children.with_routing(Routing::HashBy))
+            });
+            deployed = Some(sp.children_ref(|children| children).unwrap());
+            sp.children(|children| children.with_routing(Routing::HashBy))
+        })
+        .unwrap_err();
+    assert_eq!(
+        conflict,
+        BuilderError::Conflict(BuilderConflict::MissingRoutingKey)
+    );
+    let deployed = deployed.unwrap();
+    wait_for(|| deployed.state() == GroupState::Stopped);
+}
+
+#[test]
+fn late_mutation() {
+    init_start();
+
+    let children = Bastion::children(|children| children.with_redundancy(2)).unwrap();
+    let config = children.config();
+
+    // The settings that can be changed once the group was created
+    // are validated the same way...
+    let err = run!(children.set_routing(Routing::HashBy)).unwrap_err();
+    assert_eq!(err, RoutingError::MissingKey);
+    let unknown = Bastion::children(|children| children).unwrap().elems()[0]
+        .id()
+        .clone();
+    let err = run!(children.set_routing(Routing::Pinned(unknown.clone()))).unwrap_err();
+    assert_eq!(err, RoutingError::UnknownElement(unknown));
+
+    // ...and the resolved config doesn't change.
+    assert_eq!(children.config(), config);
+    assert_eq!(children.config().redundancy(), 2);
+}
+
+#[test]
+fn order_independent() {
+    init_start();
+
+    // The state closures replace the closure set with `with_exec`
+    // even when it is set after them.
+    let stateful = Arc::new(AtomicUsize::new(0));
+    let counted = stateful.clone();
+    let children = Bastion::children(move |children| {
+        children
+            .with_async_init(|_| async { Ok::<_, ()>(1usize) })
+            .with_exec(move |state: usize, ctx: BastionContext| {
+                counted.fetch_add(state, Ordering::SeqCst);
+                async move {
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+            .with_exec(|_: BastionContext| async { Err(()) })
+            .with_redundancy(2)
+    })
+    .unwrap();
+
+    wait_for(|| stateful.load(Ordering::SeqCst) == 2);
+    assert!(children.config().is_stateful());
+}
+
+#[test]
+fn resolved_config() {
+    init_start();
+
+    let children = Bastion::children(|children| {
+        children
+            .with_name("resolved")
+            .with_mailbox_capacity(0)
+            .with_overflow_policy(OverflowPolicy::Block)
+            .with_redundancy(2)
+            .with_ready_fraction(2.0)
+            .with_accepted_types::<(u64, &'static str)>()
+            .with_batching::<u64>(16, Duration::from_millis(10))
+            .with_launch_concurrency(2)
+    })
+    .unwrap();
+
+    let config = children.config();
+    assert_eq!(config.name(), Some("resolved"));
+    assert_eq!(config.redundancy(), 2);
+    assert_eq!(config.mailbox_capacity(), Some(1));
+    assert_eq!(config.overflow_policy(), OverflowPolicy::Block);
+    assert_eq!(config.ready_fraction(), 1.0);
+    assert_eq!(config.accepted_types(), Some(&["u64", "&str"][..]));
+    assert_eq!(config.batched_type(), Some("u64"));
+    assert_eq!(config.launch_concurrency(), Some(2));
+    assert!(!config.is_stateful());
+    assert!(!config.has_warmup());
+    assert_eq!(config.preset(), None);
+
+    // The elements' refs share the group's config.
+    assert_eq!(Bastion::children_of("resolved").unwrap().config(), config);
+}
@@ -0,0 +1,97 @@
+use bastion::prelude::*;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+type Events = Arc<Mutex<Vec<&'static str>>>;
+
+fn wait_for(events: &Events, expected: &[&str]) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while *events.lock().unwrap() != expected {
+        assert!(
+            Instant::now() < deadline,
+            "Timed out with events: {:?}",
+            events.lock().unwrap()
+        );
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+fn callbacks(events: &Events) -> Callbacks {
+    let (start, restart, restarted, stop) = (
+        events.clone(),
+        events.clone(),
+        events.clone(),
+        events.clone(),
+    );
+
+    Callbacks::new()
+        .with_before_start(move || start.lock().unwrap().push("before_start"))
+        .with_before_restart(move || restart.lock().unwrap().push("before_restart"))
+        .with_after_restart(move || restarted.lock().unwrap().push("after_restart"))
+        .with_after_stop(move || stop.lock().unwrap().push("after_stop"))
+}
+
+fn children(events: &Events) -> ChildrenRef {
+    let callbacks = callbacks(events);
+
+    Bastion::children(|children| {
+        children
+            .with_callbacks(callbacks)
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        ref msg: &'static str => {
+                            match *msg {
+                                "panic" => panic!("Element panicked."),
+                                "done" => return Ok(()),
+                                _ => (),
+                            }
+                        };
+                        _: _ => ();
+                    }
+                }
+            })
+    })
+    .unwrap()
+}
+
+#[test]
+fn callbacks_order() {
+    Bastion::init();
+    Bastion::start();
+
+    let events = Events::default();
+    let group = children(&events);
+    wait_for(&events, &["before_start"]);
+    group.broadcast("panic").unwrap();
+    wait_for(
+        &events,
+        &["before_start", "before_restart", "after_restart"],
+    );
+    group.broadcast("done").unwrap();
+    wait_for(
+        &events,
+        &[
+            "before_start",
+            "before_restart",
+            "after_restart",
+            "after_stop",
+        ],
+    );
+
+    let events = Events::default();
+    let group = children(&events);
+    wait_for(&events, &["before_start"]);
+    group.stop().unwrap();
+    wait_for(&events, &["before_start", "after_stop"]);
+
+    let events = Events::default();
+    let group = children(&events);
+    wait_for(&events, &["before_start"]);
+    group.kill().unwrap();
+    wait_for(&events, &["before_start", "after_stop"]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
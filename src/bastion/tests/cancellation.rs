@@ -0,0 +1,101 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for_value};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+#[derive(Clone, Copy)]
+enum Behavior {
+    Wait,
+    Return,
+    Panic,
+}
+
+fn spawn_group(behavior: Behavior) -> (ChildrenRef, CancellationToken) {
+    let slot = Arc::new(Mutex::new(None));
+    let slot_inner = slot.clone();
+
+    let children = Bastion::children(move |children| {
+        let slot = slot_inner.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let slot = slot.clone();
+            async move {
+                // Only keep the token of the first element started.
+                slot.lock()
+                    .unwrap()
+                    .get_or_insert_with(|| ctx.cancellation_token());
+
+                match behavior {
+                    Behavior::Wait => loop {
+                        ctx.recv().await?;
+                    },
+                    Behavior::Return => Ok(()),
+                    Behavior::Panic => panic!("Element panicked."),
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    let token = wait_for_value(|| slot.lock().unwrap().clone());
+    (children, token)
+}
+
+fn wait_for_cancellation(token: &CancellationToken) {
+    wait_for_value(|| if token.is_cancelled() { Some(()) } else { None });
+    // The token's future must resolve too.
+    run!(token.clone());
+}
+
+#[test]
+fn cancelled_on_stop() {
+    init_start();
+
+    let (children, token) = spawn_group(Behavior::Wait);
+    let child_token = token.child_token();
+    assert!(!token.is_cancelled());
+    assert!(!child_token.is_cancelled());
+
+    let waiting = token.clone();
+    let awaited = thread::spawn(move || run!(waiting));
+
+    children.stop().unwrap();
+    wait_for_cancellation(&token);
+    wait_for_cancellation(&child_token);
+    awaited.join().unwrap();
+}
+
+#[test]
+fn cancelled_on_kill() {
+    init_start();
+
+    let (children, token) = spawn_group(Behavior::Wait);
+    assert!(!token.is_cancelled());
+
+    children.kill().unwrap();
+    wait_for_cancellation(&token);
+}
+
+#[test]
+fn cancelled_on_termination() {
+    init_start();
+
+    let (_, token) = spawn_group(Behavior::Return);
+    wait_for_cancellation(&token);
+
+    // Tokens created from a terminated element's token are inert.
+    assert!(token.child_token().is_cancelled());
+    assert!(token.clone().is_cancelled());
+}
+
+#[test]
+fn cancelled_on_panic() {
+    init_start();
+
+    let (children, token) = spawn_group(Behavior::Panic);
+    wait_for_cancellation(&token);
+    assert!(token.child_token().is_cancelled());
+
+    children.stop().unwrap();
+}
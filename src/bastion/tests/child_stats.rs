@@ -0,0 +1,63 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+static HANDLING: AtomicBool = AtomicBool::new(false);
+
+#[test]
+fn child_stats() {
+    Bastion::init();
+    Bastion::start();
+
+    // Doesn't retrieve its messages until `HANDLING` is set, and
+    // faults when it receives "fail".
+    let children = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                while !HANDLING.load(Ordering::SeqCst) {
+                    ctx.sleep(Duration::from_millis(1)).await;
+                }
+
+                msg! { ctx.recv().await?,
+                    msg: &'static str => {
+                        if msg == "fail" {
+                            return Err(());
+                        }
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+    })
+    .unwrap();
+    let elem = &children.elems()[0];
+
+    for _ in 0..5 {
+        elem.tell_anonymously("job").unwrap();
+    }
+    wait_for(|| elem.mailbox_len() == 5);
+    let stats = elem.stats();
+    assert_eq!(stats.id(), elem.id());
+    assert_eq!(stats.processed(), 0);
+
+    HANDLING.store(true, Ordering::SeqCst);
+    wait_for(|| elem.stats().processed() == 5);
+    assert_eq!(elem.mailbox_len(), 0);
+
+    // The counters outlive the element's restarts.
+    elem.tell_anonymously("fail").unwrap();
+    wait_for(|| elem.stats().restarts() == 1);
+    assert_eq!(elem.stats().processed(), 6);
+
+    let stats = children.stats();
+    assert_eq!(stats.children(), &[elem.stats()]);
+    assert_eq!(stats.mailbox_len(), 0);
+    assert_eq!(stats.processed(), 6);
+    assert_eq!(stats.restarts(), 1);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,62 @@
+//! Helpers shared by the integration tests, which include them with
+//! `mod common;`.
+#![allow(dead_code)]
+
+use bastion::prelude::*;
+use std::sync::Once;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// How long the `wait_for*` helpers wait before failing the test.
+pub const TIMEOUT: Duration = Duration::from_secs(5);
+
+static START: Once = Once::new();
+
+// Initializes and starts the system once for all the tests of a
+// file.
+pub fn init_start() {
+    START.call_once(|| {
+        Bastion::init();
+        Bastion::start();
+    });
+}
+
+// Returns the configuration running the elements using the
+// deterministic scheduler if `BASTION_SEED` is set, e.g. to replay
+// a failure seen with it.
+pub fn seeded_config() -> Config {
+    match std::env::var("BASTION_SEED") {
+        Ok(seed) => Config::new().deterministic_scheduler(seed.parse().expect("Invalid seed.")),
+        Err(_) => Config::new(),
+    }
+}
+
+// Waits for `f` to hold, failing the test if it doesn't within
+// `TIMEOUT`.
+pub fn wait_for(f: impl Fn() -> bool) {
+    wait_for_within(TIMEOUT, f)
+}
+
+// Waits for `f` to hold, failing the test if it doesn't within
+// `timeout`.
+pub fn wait_for_within(timeout: Duration, f: impl Fn() -> bool) {
+    let deadline = Instant::now() + timeout;
+    while !f() {
+        assert!(Instant::now() < deadline, "Timed out.");
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+// Waits for `f` to return a value and returns it, failing the test
+// if it doesn't within `TIMEOUT`.
+pub fn wait_for_value<T>(f: impl Fn() -> Option<T>) -> T {
+    let deadline = Instant::now() + TIMEOUT;
+    loop {
+        if let Some(value) = f() {
+            return value;
+        }
+
+        assert!(Instant::now() < deadline, "Timed out.");
+        thread::sleep(Duration::from_millis(1));
+    }
+}
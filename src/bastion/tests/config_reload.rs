@@ -0,0 +1,98 @@
+use bastion::prelude::*;
+use futures::StreamExt;
+use std::sync::Once;
+use std::time::{Duration, Instant};
+
+static START: Once = Once::new();
+
+const INITIAL: Duration = Duration::from_millis(200);
+const RELOADED: Duration = Duration::from_millis(800);
+
+fn init_start() {
+    START.call_once(|| {
+        let runtime = RuntimeConfig::new().with_default_ask_timeout(INITIAL);
+        Bastion::init_with(Config::new().with_runtime(runtime));
+        Bastion::start();
+    });
+}
+
+// A group whose element keeps the messages it receives without
+// answering them.
+fn silent() -> ChildRef {
+    let children = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            let mut held = Vec::new();
+            loop {
+                held.push(ctx.recv().await?);
+            }
+        })
+    })
+    .unwrap();
+
+    children.elems()[0].clone()
+}
+
+#[test]
+fn default_ask_timeout() {
+    init_start();
+
+    let child = silent();
+    let mut events = Bastion::events();
+
+    let asked = Instant::now();
+    let in_flight = child.ask_anonymously("in flight").unwrap();
+
+    let runtime = RuntimeConfig::new().with_default_ask_timeout(RELOADED);
+    let changes = Bastion::reload_config(runtime).unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].field(), "default_ask_timeout");
+    assert_eq!(changes[0].before(), format!("{:?}", Some(INITIAL)));
+    assert_eq!(changes[0].after(), format!("{:?}", Some(RELOADED)));
+
+    let reloaded = Instant::now();
+    let new = child.ask_anonymously("new").unwrap();
+
+    // The answer asked before the reload keeps its deadline...
+    assert_eq!(run!(in_flight).unwrap_err(), AnswerError::TimedOut);
+    let waited = asked.elapsed();
+    assert!(waited >= INITIAL);
+    assert!(waited < RELOADED, "Waited for {:?}.", waited);
+
+    // ...while the new one uses the reloaded timeout.
+    assert_eq!(run!(new).unwrap_err(), AnswerError::TimedOut);
+    assert!(reloaded.elapsed() >= RELOADED);
+
+    // An explicit timeout replaces the default one.
+    let explicit = child.ask_anonymously("explicit").unwrap();
+    let answered = run!(explicit.timeout(Duration::from_millis(10)));
+    assert_eq!(answered.unwrap_err(), AnswerError::TimedOut);
+
+    loop {
+        match run!(events.next()) {
+            Some(Event::ConfigReloaded { changes: reported }) => {
+                assert_eq!(reported, changes);
+                break;
+            }
+            Some(_) => (),
+            None => panic!("The events stream ended."),
+        }
+    }
+}
+
+#[cfg(feature = "supervision")]
+#[test]
+fn construction_time_fields() {
+    init_start();
+
+    let config = Config::new()
+        .hide_backtraces()
+        .with_root_restart_window(3, Duration::from_secs(1))
+        .with_runtime(RuntimeConfig::new());
+    let reloaded = Bastion::reload_with(config);
+    assert_eq!(
+        reloaded,
+        Err(ReloadError::ConstructionTime {
+            fields: vec!["backtraces", "root_restart_window"],
+        })
+    );
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[test]
+fn kill_before_pending_messages() {
+    init_start();
+
+    let handled = Arc::new(AtomicUsize::new(0));
+    let counted = handled.clone();
+    let children = Bastion::children(move |children| {
+        let counted = counted.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let counted = counted.clone();
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        _n: u64 => {
+                            thread::sleep(Duration::from_micros(100));
+                            counted.fetch_add(1, Ordering::SeqCst);
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    let child = children.elems()[0].clone();
+    for n in 0..10_000u64 {
+        child.tell_anonymously(n).unwrap();
+    }
+    wait_for(|| handled.load(Ordering::SeqCst) > 0);
+
+    // The element is killed without handling each of the
+    // messages told to it before.
+    let killed = Instant::now();
+    child.kill().unwrap();
+    run!(child.watch());
+    assert!(killed.elapsed() < Duration::from_secs(1));
+    assert!(handled.load(Ordering::SeqCst) < 10_000);
+}
+
+#[test]
+fn user_messages_in_order() {
+    init_start();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let recorded = received.clone();
+    let children = Bastion::children(move |children| {
+        let recorded = recorded.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let recorded = recorded.clone();
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        n: u64 => recorded.lock().unwrap().push(n);
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    let child = children.elems()[0].clone();
+    for n in 0..1_000u64 {
+        child.tell_anonymously(n).unwrap();
+    }
+
+    wait_for(|| received.lock().unwrap().len() == 1_000);
+    assert_eq!(*received.lock().unwrap(), (0..1_000).collect::<Vec<_>>());
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use bastion::correlation::ReplyError;
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::time::Duration;
+
+// Replies `replies` times to every correlated `u64` it receives
+// (with the received number plus the reply's index), after having
+// received a `Duration` to wait before replying.
+fn spawn_responder(replies: u64) -> ChildRef {
+    let children = Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| async move {
+            let mut delay = Duration::from_millis(0);
+            loop {
+                msg! { ctx.recv().await?,
+                    msg: Duration => {
+                        delay = msg;
+                    };
+                    req: Correlated<u64> => {
+                        futures_timer::Delay::new(delay).await;
+                        for i in 0..replies {
+                            let reply = *req.msg() + i;
+                            // Only the first reply is accepted.
+                            assert_eq!(ctx.reply_correlated(req.id(), reply).is_ok(), i == 0);
+                        }
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    children.elems()[0].clone()
+}
+
+fn reply_value(reply: Result<SignedMessage, ReplyError>) -> u64 {
+    msg! { reply.expect("Didn't receive a reply."),
+        n: u64 => n;
+        _: _ => panic!("Unexpected reply.");
+    }
+}
+
+#[test]
+fn reply_across_groups() {
+    init_start();
+
+    let responder = spawn_responder(1);
+    let requester = Bastion::children(move |children| {
+        let responder = responder.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let responder = responder.clone();
+            async move {
+                msg! { ctx.recv().await?,
+                    msg: u64 =!> {
+                        let id = responder.tell_correlated(msg).unwrap();
+                        let reply = Bastion::reply_broker()
+                            .wait(&id, Duration::from_secs(5))
+                            .await;
+                        answer!(ctx, reply_value(reply)).unwrap();
+                    };
+                    _: _ => ();
+                }
+
+                Ok(())
+            }
+        })
+    })
+    .unwrap();
+
+    let answer = requester.elems()[0].ask_anonymously(41u64).unwrap();
+    msg! { run!(answer).unwrap(),
+        n: u64 => assert_eq!(n, 41);
+        _: _ => panic!("Unexpected answer.");
+    }
+}
+
+#[test]
+fn timeouts_and_duplicate_replies() {
+    init_start();
+
+    let broker = Bastion::reply_broker();
+
+    // The first reply wins and the second one is counted...
+    let responder = spawn_responder(2);
+    let dropped = broker.dropped_replies();
+    let id = responder.tell_correlated(10u64).unwrap();
+    assert_eq!(
+        reply_value(run!(broker.wait(&id, Duration::from_secs(5)))),
+        10
+    );
+    wait_for(|| broker.dropped_replies() > dropped);
+
+    // ...and the reply can only be retrieved once.
+    assert_eq!(
+        run!(broker.wait(&id, Duration::from_secs(5))).unwrap_err(),
+        ReplyError::Unknown(id)
+    );
+
+    // A correlation id expires when the timeout does...
+    let responder = spawn_responder(1);
+    responder
+        .tell_anonymously(Duration::from_millis(100))
+        .unwrap();
+    let dropped = broker.dropped_replies();
+    let id = responder.tell_correlated(20u64).unwrap();
+    assert_eq!(
+        run!(broker.wait(&id, Duration::from_millis(10))).unwrap_err(),
+        ReplyError::Timeout
+    );
+
+    // ...which drops its reply once it is sent.
+    wait_for(|| broker.dropped_replies() > dropped);
+    assert_eq!(
+        run!(broker.wait(&id, Duration::from_secs(5))).unwrap_err(),
+        ReplyError::Unknown(id)
+    );
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+use std::sync::Mutex;
+
+static LETTERS: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+#[test]
+fn full_payloads() {
+    let config = Config::new().with_dead_letter_handler(|letter: DeadLetter| {
+        assert!(!letter.is_summary());
+        if let DeadLetterPayload::Full(msg) = letter.into_payload() {
+            LETTERS.lock().unwrap().push(msg.downcast().unwrap());
+        }
+    });
+    Bastion::init_with(config);
+    Bastion::start();
+
+    let children = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    _msg: &'static str => {
+                        // Replying to a message told anonymously.
+                        ctx.tell(&signature!(), "reply").unwrap();
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+    })
+    .unwrap();
+    children.elems()[0].tell_anonymously("hello").unwrap();
+
+    wait_for(|| !LETTERS.lock().unwrap().is_empty());
+    assert_eq!(*LETTERS.lock().unwrap(), vec!["reply"]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
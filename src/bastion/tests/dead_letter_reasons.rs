@@ -0,0 +1,93 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+use std::sync::{Mutex, Once};
+use std::thread;
+use std::time::Duration;
+
+static START: Once = Once::new();
+
+// The dead letters received, with their recipient and reason.
+static LETTERS: Mutex<Vec<(Option<BastionId>, DeadLetterReason, &'static str)>> =
+    Mutex::new(Vec::new());
+
+fn init_start() {
+    START.call_once(|| {
+        let config = Config::new().with_dead_letter_handler(|letter: DeadLetter| {
+            let recipient = letter.recipient().cloned();
+            let reason = letter.reason();
+            if let DeadLetterPayload::Full(msg) = letter.into_payload() {
+                if let Ok(msg) = msg.downcast() {
+                    LETTERS.lock().unwrap().push((recipient, reason, msg));
+                }
+            }
+        });
+        Bastion::init_with(config);
+        Bastion::start();
+    });
+}
+
+fn letters_for(id: &BastionId) -> Vec<(DeadLetterReason, &'static str)> {
+    LETTERS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(recipient, _, _)| recipient.as_ref() == Some(id))
+        .map(|(_, reason, msg)| (*reason, *msg))
+        .collect()
+}
+
+#[test]
+fn unhandled_once_stopped() {
+    init_start();
+
+    let children = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            // Only the first message is handled...
+            ctx.recv().await?;
+            thread::sleep(Duration::from_millis(100));
+            Ok(())
+        })
+    })
+    .unwrap();
+    let child = &children.elems()[0];
+    child.tell_anonymously("first").unwrap();
+    child.tell_anonymously("second").unwrap();
+
+    // ...so the second one is sent to the dead letters.
+    wait_for(|| !letters_for(child.id()).is_empty());
+    assert_eq!(
+        letters_for(child.id()),
+        vec![(DeadLetterReason::RecipientStopped, "second")]
+    );
+}
+
+#[test]
+fn dropped_by_full_mailbox() {
+    init_start();
+
+    let children = Bastion::children(|children| {
+        children
+            .with_mailbox_capacity(1)
+            .with_overflow_policy(OverflowPolicy::DropNewest)
+            .with_exec(|ctx: BastionContext| async move {
+                // The mailbox is full until the element receives
+                // a message.
+                thread::sleep(Duration::from_millis(100));
+                loop {
+                    ctx.recv().await?;
+                }
+            })
+    })
+    .unwrap();
+    let child = &children.elems()[0];
+    child.tell_anonymously("kept").unwrap();
+    child.tell_anonymously("dropped").unwrap();
+
+    wait_for(|| !letters_for(child.id()).is_empty());
+    assert_eq!(
+        letters_for(child.id()),
+        vec![(DeadLetterReason::MailboxFull, "dropped")]
+    );
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for_within;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+// Tracks how many bytes are allocated, and the most that were
+// allocated at once since it was reset.
+struct Tracking;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Tracking {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+        PEAK.fetch_max(allocated, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static TRACKING: Tracking = Tracking;
+
+const LETTERS: usize = 10_000;
+const BLOB_SIZE: usize = 64 * 1024;
+
+#[derive(Debug)]
+struct Blob(Vec<u8>);
+
+// Asks the element receiving it to reply with this many blobs.
+#[derive(Debug)]
+struct Flood(usize);
+
+static SUMMARIES: AtomicUsize = AtomicUsize::new(0);
+static FULL: AtomicUsize = AtomicUsize::new(0);
+static LAST: Mutex<Option<DeadLetterSummary>> = Mutex::new(None);
+static RECEIVED: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+fn handle(letter: DeadLetter) {
+    match letter.into_payload() {
+        DeadLetterPayload::Summary(summary) => {
+            *LAST.lock().unwrap() = Some(summary);
+            SUMMARIES.fetch_add(1, Ordering::SeqCst);
+        }
+        DeadLetterPayload::Full(_) => {
+            FULL.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+#[test]
+fn bounded_summaries() {
+    let config = Config::new()
+        .dead_letter_mode(DeadLetterMode::SummaryOnly {
+            max_payload_bytes: 16,
+        })
+        .with_dead_letter_serializer(|blob: &Blob| blob.0.clone())
+        .with_dead_letter_handler(handle);
+    Bastion::init_with(config);
+    Bastion::start();
+
+    let recipient = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    blob: Blob => RECEIVED.lock().unwrap().push(blob.0.len());
+                    _: _ => ();
+                }
+            }
+        })
+    })
+    .unwrap();
+    let recipient = recipient.elems()[0].addr();
+
+    let flooding = Bastion::children(|children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let recipient = recipient.clone();
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        flood: Flood => {
+                            // Sent to the dead letters, since the
+                            // flood was told anonymously...
+                            for _ in 0..flood.0 {
+                                ctx.tell(&signature!(), Blob(vec![7; BLOB_SIZE])).unwrap();
+                            }
+                            // ...while this one is sent in full.
+                            ctx.tell(&recipient, Blob(vec![7; BLOB_SIZE])).unwrap();
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    let before = ALLOCATED.load(Ordering::SeqCst);
+    PEAK.store(before, Ordering::SeqCst);
+    flooding.elems()[0]
+        .tell_anonymously(Flood(LETTERS))
+        .unwrap();
+    wait_for_within(Duration::from_secs(30), || {
+        SUMMARIES.load(Ordering::SeqCst) == LETTERS
+    });
+    wait_for_within(Duration::from_secs(30), || {
+        !RECEIVED.lock().unwrap().is_empty()
+    });
+
+    // Far less than the blobs would use if they were all kept.
+    let peak = PEAK.load(Ordering::SeqCst).saturating_sub(before);
+    assert!(peak < LETTERS * BLOB_SIZE / 20, "Used {} bytes.", peak);
+
+    assert_eq!(FULL.load(Ordering::SeqCst), 0);
+    let summary = LAST.lock().unwrap().take().unwrap();
+    assert!(summary.type_name().ends_with("Blob"));
+    assert_eq!(summary.size(), BLOB_SIZE);
+    assert_eq!(summary.prefix(), Some(&[7; 16][..]));
+    assert!(summary.is_truncated());
+    assert_eq!(*RECEIVED.lock().unwrap(), vec![BLOB_SIZE]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,56 @@
+use bastion::children_ref::GroupState;
+use bastion::prelude::*;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+static LETTERS: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+#[test]
+fn restarted() {
+    let config = Config::new().with_dead_letter_handler(|letter: DeadLetter| {
+        if let DeadLetterPayload::Full(msg) = letter.into_payload() {
+            let msg: &'static str = msg.downcast().unwrap();
+            LETTERS.lock().unwrap().push(msg);
+            if msg == "boom" {
+                panic!("Couldn't handle the dead letter.");
+            }
+        }
+    });
+    Bastion::init_with(config);
+    Bastion::start();
+
+    // Replies to the messages told anonymously, the replies becoming
+    // dead letters.
+    let children = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    msg: &'static str => {
+                        ctx.tell(&signature!(), msg).unwrap();
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+    })
+    .unwrap();
+    let bounce = |msg| children.elems()[0].tell_anonymously(msg).unwrap();
+
+    bounce("boom");
+    // The dead letters' element is restarted after it panicked (and
+    // the dead letters sent meanwhile may be lost).
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !LETTERS.lock().unwrap().contains(&"after") {
+        assert!(Instant::now() < deadline, "Timed out.");
+        bounce("after");
+        thread::sleep(Duration::from_millis(100));
+    }
+
+    assert_eq!(LETTERS.lock().unwrap()[0], "boom");
+    assert_eq!(Bastion::health().dead_letters(), GroupState::Running);
+    assert!(!Bastion::is_degraded());
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
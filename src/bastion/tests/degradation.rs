@@ -0,0 +1,102 @@
+#![cfg(feature = "supervision")]
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+use futures::StreamExt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+fn idle(children: Children) -> Children {
+    children.with_exec(|ctx: BastionContext| async move {
+        loop {
+            ctx.recv().await?;
+        }
+    })
+}
+
+#[test]
+fn degrade_and_recover() {
+    let config = Config::new()
+        .with_root_restart_window(3, Duration::from_secs(60))
+        .on_root_exhaustion(RootAction::Degrade {
+            keep: vec!["health".to_string(), "admin".to_string()],
+        });
+    Bastion::init_with(config);
+    let mut events = Bastion::events();
+
+    Bastion::children(|children| idle(children).with_name("health")).unwrap();
+    Bastion::children(|children| idle(children).with_name("workers")).unwrap();
+    Bastion::supervisor(|sp| {
+        sp.children(|children| idle(children).with_name("admin"))
+            .children(|children| idle(children).with_name("metrics"))
+    })
+    .unwrap();
+
+    // Faults as soon as it starts, until told otherwise.
+    let faulting = Arc::new(AtomicBool::new(true));
+    let hot = faulting.clone();
+    Bastion::children(move |children| {
+        let hot = hot.clone();
+        children
+            .with_name("hot")
+            .with_exec(move |ctx: BastionContext| {
+                let hot = hot.clone();
+                async move {
+                    if hot.load(Ordering::SeqCst) {
+                        return Err(());
+                    }
+
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    Bastion::start();
+
+    wait_for(Bastion::is_degraded);
+    let kept = run!(async {
+        loop {
+            if let Some(Event::SystemDegraded { kept }) = events.next().await {
+                return kept;
+            }
+        }
+    });
+    assert_eq!(kept, vec!["health", "admin"]);
+
+    // Only the kept groups remain, whichever supervisor supervises them.
+    wait_for(|| {
+        ["workers", "metrics", "hot"]
+            .iter()
+            .all(|name| Bastion::children_of(name).is_none())
+    });
+    assert!(Bastion::children_of("health").is_some());
+    assert!(Bastion::children_of("admin").is_some());
+    thread::sleep(Duration::from_millis(100));
+    assert!(Bastion::is_degraded());
+
+    faulting.store(false, Ordering::SeqCst);
+    Bastion::recover();
+    assert!(!Bastion::is_degraded());
+
+    // The stopped groups are deployed again and can be used.
+    wait_for(|| {
+        ["workers", "metrics", "hot"]
+            .iter()
+            .all(|name| Bastion::children_of(name).is_some())
+    });
+    for name in &["health", "workers", "admin", "metrics", "hot"] {
+        let children = Bastion::children_of(name).unwrap();
+        assert_eq!(children.elems().len(), 1);
+        children.broadcast("ping").unwrap();
+    }
+    assert!(!Bastion::is_degraded());
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,52 @@
+use bastion::prelude::*;
+
+// Answers the number of elements its group had when it was
+// launched, to every `&str` it is asked.
+async fn counting_elems(ctx: BastionContext) -> Result<(), ()> {
+    loop {
+        msg! { ctx.recv().await?,
+            _msg: &'static str =!> {
+                answer!(ctx, ctx.parent().elems().len()).unwrap();
+            };
+            _: _ => ();
+        }
+    }
+}
+
+fn ask_elems(elem: &ChildRef) -> usize {
+    let answer = elem.ask_anonymously("elems").unwrap();
+    msg! { run!(answer).unwrap(),
+        n: usize => n;
+        _: _ => panic!("Unexpected answer.");
+    }
+}
+
+#[test]
+fn deploy_elems() {
+    Bastion::init();
+
+    let children = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                ctx.recv().await?;
+            }
+        })
+    })
+    .unwrap();
+
+    // Deployed once the group is started...
+    let before_start = children.deploy_elem(counting_elems);
+
+    Bastion::start();
+
+    let first = run!(before_start).unwrap();
+    assert_eq!(ask_elems(&first), 1);
+
+    // ...or right away, and is part of the group from then on.
+    let second = run!(children.deploy_elem(counting_elems)).unwrap();
+    assert_eq!(ask_elems(&second), 2);
+    assert_ne!(first.id(), second.id());
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
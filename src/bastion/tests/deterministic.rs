@@ -0,0 +1,260 @@
+mod common;
+
+use bastion::prelude::*;
+use bastion_executor::deterministic;
+use common::wait_for;
+use std::env;
+use std::fs;
+use std::future::Future;
+use std::pin::Pin;
+use std::process::{self, Command};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+// The seed used by `replay`, `sleep`, `backoff` and `ack`, which are
+// only run by `same_order`, `sleeps_virtually`, `backs_off_virtually`
+// and `acks_virtually` in another process (the executor being
+// global), and the file they write their output to.
+const SEED: &str = "BASTION_REPLAY_SEED";
+const OUTPUT: &str = "BASTION_REPLAY_OUTPUT";
+const SEEDS: [u64; 4] = [0, 1, 42, 1337];
+
+const ELEMS: usize = 4;
+const STEPS: usize = 5;
+
+// Lets the other elements run before resuming.
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+
+        self.0 = true;
+        ctx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+#[test]
+fn replay() {
+    let seed = match env::var(SEED) {
+        Ok(seed) => seed.parse().unwrap(),
+        Err(_) => return,
+    };
+
+    // The system only starts running once it was started and the
+    // children group was created (the scheduler being enabled before
+    // `init_with` does so, for it to be held).
+    deterministic::enable(seed);
+    let hold = deterministic::hold();
+    Bastion::init_with(Config::new().deterministic_scheduler(seed));
+    Bastion::start();
+
+    // The elements record their steps, yielding between them. They
+    // are created by another element, so that they are woken up by
+    // the system rather than by this thread.
+    let trace = Arc::new(Mutex::new(Vec::new()));
+    let recorded = trace.clone();
+    Bastion::spawn(move |_| {
+        let recorded = recorded.clone();
+        async move {
+            let started = Arc::new(AtomicUsize::new(0));
+            Bastion::children(move |children| {
+                children
+                    .with_redundancy(ELEMS)
+                    .with_exec(move |_: BastionContext| {
+                        let recorded = recorded.clone();
+                        let elem = started.fetch_add(1, Ordering::SeqCst);
+                        async move {
+                            for step in 0..STEPS {
+                                recorded.lock().unwrap().push((elem, step));
+                                YieldNow(false).await;
+                            }
+
+                            Ok(())
+                        }
+                    })
+            })
+            .unwrap();
+
+            Ok(())
+        }
+    })
+    .unwrap();
+    drop(hold);
+
+    wait_for(|| trace.lock().unwrap().len() == ELEMS * STEPS);
+    let trace = format!("{:?}", trace.lock().unwrap());
+    fs::write(env::var(OUTPUT).unwrap(), trace).unwrap();
+}
+
+#[test]
+fn sleep() {
+    let seed = match env::var(SEED) {
+        Ok(seed) => seed.parse().unwrap(),
+        Err(_) => return,
+    };
+
+    Bastion::init_with(Config::new().deterministic_scheduler(seed));
+    Bastion::start();
+
+    // The element's timer fires as soon as nothing else is left to
+    // run, rather than after an hour.
+    let slept = Arc::new(AtomicBool::new(false));
+    let recorded = slept.clone();
+    Bastion::spawn(move |ctx| {
+        let recorded = recorded.clone();
+        async move {
+            ctx.sleep(Duration::from_secs(3600)).await;
+            recorded.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+    })
+    .unwrap();
+
+    wait_for(|| slept.load(Ordering::SeqCst));
+    fs::write(env::var(OUTPUT).unwrap(), "slept").unwrap();
+}
+
+#[cfg(feature = "supervision")]
+#[test]
+fn backoff() {
+    let seed = match env::var(SEED) {
+        Ok(seed) => seed.parse().unwrap(),
+        Err(_) => return,
+    };
+
+    Bastion::init_with(Config::new().deterministic_scheduler(seed));
+    Bastion::start();
+
+    // The element's restart is delayed by an hour of virtual time.
+    let starts = Arc::new(AtomicUsize::new(0));
+    let recorded = starts.clone();
+    Bastion::children(move |children| {
+        let recorded = recorded.clone();
+        children
+            .with_restart_backoff(Backoff::exponential(
+                Duration::from_secs(3600),
+                Duration::from_secs(3600),
+                0.5,
+            ))
+            .with_exec(move |ctx: BastionContext| {
+                let recorded = recorded.clone();
+                async move {
+                    if recorded.fetch_add(1, Ordering::SeqCst) == 0 {
+                        return Err(());
+                    }
+
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    wait_for(|| starts.load(Ordering::SeqCst) == 2);
+    fs::write(env::var(OUTPUT).unwrap(), "restarted").unwrap();
+}
+
+#[test]
+fn ack() {
+    let seed = match env::var(SEED) {
+        Ok(seed) => seed.parse().unwrap(),
+        Err(_) => return,
+    };
+
+    Bastion::init_with(Config::new().deterministic_scheduler(seed));
+    Bastion::start();
+
+    // The element never acknowledges the message, which times out
+    // once nothing else is left to run rather than after an hour.
+    let children = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                ctx.recv().await?;
+            }
+        })
+    })
+    .unwrap();
+
+    let pending = Arc::new(AtomicUsize::new(0));
+    let recorded = pending.clone();
+    Bastion::spawn(move |_| {
+        let recorded = recorded.clone();
+        let children = children.clone();
+        async move {
+            let report = children
+                .broadcast_acked("ack", Duration::from_secs(3600))
+                .await;
+            recorded.store(report.pending().len() + 1, Ordering::SeqCst);
+            Ok(())
+        }
+    })
+    .unwrap();
+
+    wait_for(|| pending.load(Ordering::SeqCst) != 0);
+    let pending = pending.load(Ordering::SeqCst) - 1;
+    fs::write(env::var(OUTPUT).unwrap(), format!("pending: {}", pending)).unwrap();
+}
+
+// Runs `test` in another process with the given seed, returning
+// what it wrote to its output.
+fn run_seeded(test: &str, seed: u64) -> String {
+    let path = env::temp_dir().join(format!("bastion-{}-{}-{}", test, process::id(), seed));
+    let output = Command::new(env::current_exe().unwrap())
+        .args([test, "--exact", "--test-threads=1"])
+        .env(SEED, seed.to_string())
+        .env(OUTPUT, &path)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+
+    let written = fs::read_to_string(&path).expect("The output wasn't written.");
+    fs::remove_file(&path).unwrap();
+    written
+}
+
+fn run_replay(seed: u64) -> String {
+    run_seeded("replay", seed)
+}
+
+#[test]
+fn same_order() {
+    let traces: Vec<_> = SEEDS
+        .iter()
+        .map(|seed| {
+            let trace = run_replay(*seed);
+            for _ in 0..2 {
+                assert_eq!(run_replay(*seed), trace, "Seed {} wasn't replayed.", seed);
+            }
+
+            trace
+        })
+        .collect();
+
+    // The elements ran in an order depending on the seed.
+    assert!(traces.iter().any(|trace| *trace != traces[0]));
+}
+
+#[test]
+fn sleeps_virtually() {
+    assert_eq!(run_seeded("sleep", 42), "slept");
+}
+
+#[cfg(feature = "supervision")]
+#[test]
+fn backs_off_virtually() {
+    assert_eq!(run_seeded("backoff", 42), "restarted");
+}
+
+#[test]
+fn acks_virtually() {
+    assert_eq!(run_seeded("ack", 42), "pending: 1");
+}
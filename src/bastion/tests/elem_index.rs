@@ -0,0 +1,65 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const REDUNDANCY: usize = 4;
+
+// Broadcasted to make the element with this index panic.
+#[derive(Debug)]
+struct Panic(usize);
+
+fn sorted(started: &Mutex<Vec<usize>>) -> Vec<usize> {
+    let mut started = started.lock().unwrap().clone();
+    started.sort_unstable();
+    started
+}
+
+#[test]
+fn elem_index() {
+    Bastion::init();
+    Bastion::start();
+
+    // The indexes of the elements, each time one is started.
+    let started = Arc::new(Mutex::new(Vec::new()));
+    let recorded = started.clone();
+    Bastion::children(move |children| {
+        children
+            .with_redundancy(REDUNDANCY)
+            .with_exec(move |ctx: BastionContext| {
+                let recorded = recorded.clone();
+                async move {
+                    let index = ctx.elem_index();
+                    recorded.lock().unwrap().push(index);
+
+                    loop {
+                        msg! { ctx.recv().await?,
+                            ref msg: Panic => {
+                                if msg.0 == index {
+                                    panic!("Panicking as asked.");
+                                }
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    // Every element has its own index...
+    wait_for(|| started.lock().unwrap().len() == REDUNDANCY);
+    assert_eq!(sorted(&started), vec![0, 1, 2, 3]);
+
+    // ...which it keeps when it is restarted.
+    Bastion::broadcast(Panic(2)).unwrap();
+    wait_for(|| started.lock().unwrap().len() == REDUNDANCY + 1);
+    thread::sleep(Duration::from_millis(200));
+    assert_eq!(sorted(&started), vec![0, 1, 2, 2, 3]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,317 @@
+use bastion::blocking_bridge::BridgeError;
+use bastion::children_ref::MigrationError;
+use bastion::correlation::ReplyError;
+use bastion::errors::ReceiveError;
+use bastion::prelude::*;
+use bastion::routing::RoutingError;
+use bastion::state_cell::StateError;
+use bastion::sync::BarrierError;
+use std::error::Error;
+use std::time::Duration;
+
+#[test]
+fn display_and_source() {
+    let err = BastionError::from(BuilderError::ParentStopped);
+    assert_eq!(
+        err.to_string(),
+        "couldn't create the element: its parent stopped"
+    );
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        BuilderError::ParentStopped.to_string()
+    );
+
+    let err = BastionError::from(ShutdownError::AlreadyStopped);
+    assert_eq!(
+        err.to_string(),
+        "couldn't stop the element: it already stopped"
+    );
+
+    let err = BastionError::from(AnswerError::Unanswered);
+    assert_eq!(
+        err.to_string(),
+        "the message was dropped without being answered"
+    );
+}
+
+#[test]
+#[cfg(feature = "patterns")]
+fn send_error_carries_the_message() {
+    Bastion::init();
+
+    let pipeline = Pipeline::new().build().unwrap();
+    let err = pipeline.push("message").unwrap_err();
+    assert_eq!(err.kind(), SendErrorKind::NoRecipient);
+    assert_eq!(
+        err.to_string(),
+        "couldn't send the message: there is no recipient"
+    );
+    assert_eq!(err.msg(), &"message");
+
+    assert_eq!(err.clone().into_inner_msg(), "message");
+
+    // The message is dropped, but not the reason.
+    let err = BastionError::from(err);
+    match &err {
+        BastionError::Send(err) => assert_eq!(err.kind(), SendErrorKind::NoRecipient),
+        _ => panic!("Unexpected error."),
+    }
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        "couldn't send the message: there is no recipient"
+    );
+}
+
+// Names the variant of an error (and of the error or kind it
+// carries), which stops compiling once a variant is added until it
+// is covered by `every_variant` too.
+fn variant(err: &BastionError) -> &'static str {
+    match err {
+        BastionError::Send(err) => match err.kind() {
+            SendErrorKind::Stopped => "Send(Stopped)",
+            SendErrorKind::NoRecipient => "Send(NoRecipient)",
+            SendErrorKind::Full => "Send(Full)",
+            SendErrorKind::UnknownCorrelation => "Send(UnknownCorrelation)",
+            SendErrorKind::TypeNotAccepted { .. } => "Send(TypeNotAccepted)",
+            SendErrorKind::MailboxFull => "Send(MailboxFull)",
+            SendErrorKind::TooManyPending => "Send(TooManyPending)",
+        },
+        BastionError::Builder(err) => match err {
+            BuilderError::ParentStopped => "Builder(ParentStopped)",
+            BuilderError::NameTaken => "Builder(NameTaken)",
+            BuilderError::NotInitialized => "Builder(NotInitialized)",
+            BuilderError::ShuttingDown => "Builder(ShuttingDown)",
+            BuilderError::ThreadSpawn => "Builder(ThreadSpawn)",
+            BuilderError::Conflict(conflict) => match conflict {
+                BuilderConflict::OverflowWithoutCapacity => {
+                    "Builder(Conflict(OverflowWithoutCapacity))"
+                }
+                BuilderConflict::BatchedTypeNotAccepted { .. } => {
+                    "Builder(Conflict(BatchedTypeNotAccepted))"
+                }
+                BuilderConflict::MissingRoutingKey => "Builder(Conflict(MissingRoutingKey))",
+            },
+        },
+        BastionError::Shutdown(err) => match err {
+            ShutdownError::AlreadyStopped => "Shutdown(AlreadyStopped)",
+        },
+        BastionError::Answer(err) => match err {
+            AnswerError::Unanswered => "Answer(Unanswered)",
+            AnswerError::TimedOut => "Answer(TimedOut)",
+            AnswerError::UnexpectedType { .. } => "Answer(UnexpectedType)",
+            AnswerError::WouldBlockExecutor => "Answer(WouldBlockExecutor)",
+        },
+        BastionError::Receive(err) => match err {
+            ReceiveError::Timeout(_) => "Receive(Timeout)",
+        },
+        BastionError::Reply(err) => match err {
+            ReplyError::Timeout => "Reply(Timeout)",
+            ReplyError::Unknown(_) => "Reply(Unknown)",
+        },
+        BastionError::Migration(err) => match err {
+            MigrationError::UnknownElement(_) => "Migration(UnknownElement)",
+            MigrationError::SameElement => "Migration(SameElement)",
+            MigrationError::SourceStopped => "Migration(SourceStopped)",
+            MigrationError::TargetStopped => "Migration(TargetStopped)",
+        },
+        BastionError::Routing(err) => match err {
+            RoutingError::MissingKey => "Routing(MissingKey)",
+            RoutingError::UnknownElement(_) => "Routing(UnknownElement)",
+            RoutingError::Stopped => "Routing(Stopped)",
+        },
+        BastionError::Reduce(_) => "Reduce",
+        BastionError::Barrier(err) => match err {
+            BarrierError::ParticipantLost => "Barrier(ParticipantLost)",
+            BarrierError::Stopped => "Barrier(Stopped)",
+        },
+        BastionError::Bridge(err) => match err {
+            BridgeError::WouldBlockExecutor => "Bridge(WouldBlockExecutor)",
+            BridgeError::Send(_) => "Bridge(Send)",
+            BridgeError::Unanswered => "Bridge(Unanswered)",
+            BridgeError::TimedOut => "Bridge(TimedOut)",
+        },
+        BastionError::State(err) => match err {
+            StateError::Unknown => "State(Unknown)",
+            StateError::Dropped => "State(Dropped)",
+        },
+        BastionError::Metric(err) => match err {
+            MetricError::InvalidName(_) => "Metric(InvalidName)",
+            MetricError::Registered { .. } => "Metric(Registered)",
+        },
+        BastionError::Reload(err) => match err {
+            ReloadError::ConstructionTime { .. } => "Reload(ConstructionTime)",
+            ReloadError::NotInitialized => "Reload(NotInitialized)",
+            ReloadError::ShuttingDown => "Reload(ShuttingDown)",
+        },
+        BastionError::NotInitialized => "NotInitialized",
+        BastionError::ShuttingDown => "ShuttingDown",
+    }
+}
+
+#[test]
+fn every_variant() {
+    Bastion::init();
+    Bastion::start();
+
+    // The errors that are only returned by a running system...
+    let children = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                ctx.recv().await?;
+            }
+        })
+    })
+    .unwrap();
+    let elem = children.elems()[0].clone();
+    let group = children.id().clone();
+
+    let id = elem.tell_correlated(0u64).unwrap();
+    let broker = Bastion::reply_broker();
+    let timeout = Duration::from_millis(10);
+    let mut errors: Vec<BastionError> = vec![
+        run!(broker.wait(&id, timeout)).unwrap_err().into(),
+        run!(broker.wait(&id, timeout)).unwrap_err().into(),
+        run!(children.migrate_elem(elem.id(), elem.id()))
+            .unwrap_err()
+            .into(),
+        run!(children.migrate_elem(&group, elem.id()))
+            .unwrap_err()
+            .into(),
+        run!(children.ask_reduce(0u64, (), |(), _| (), timeout))
+            .unwrap_err()
+            .into(),
+    ];
+
+    run!(children.stop_and_wait()).unwrap();
+    errors.push(elem.tell_anonymously(0u64).unwrap_err().into());
+    errors.push(elem.stop().unwrap_err().into());
+
+    // ...and the other ones.
+    let kinds = vec![
+        SendErrorKind::Stopped,
+        SendErrorKind::NoRecipient,
+        SendErrorKind::Full,
+        SendErrorKind::UnknownCorrelation,
+        SendErrorKind::MailboxFull,
+        SendErrorKind::TooManyPending,
+    ];
+    let mut names = kinds.iter().map(ToString::to_string).collect::<Vec<_>>();
+    names.sort();
+    names.dedup();
+    assert_eq!(names.len(), kinds.len());
+    errors.extend(kinds.into_iter().map(|kind| BridgeError::Send(kind).into()));
+
+    let conflicts = vec![
+        BuilderConflict::OverflowWithoutCapacity,
+        BuilderConflict::BatchedTypeNotAccepted { batched: "u64" },
+        BuilderConflict::MissingRoutingKey,
+    ];
+    errors.extend(
+        conflicts
+            .into_iter()
+            .map(|conflict| BuilderError::Conflict(conflict).into()),
+    );
+    errors.extend(vec![
+        BuilderError::ParentStopped.into(),
+        BuilderError::NameTaken.into(),
+        BuilderError::NotInitialized.into(),
+        BuilderError::ShuttingDown.into(),
+        BuilderError::ThreadSpawn.into(),
+        AnswerError::Unanswered.into(),
+        AnswerError::TimedOut.into(),
+        AnswerError::UnexpectedType {
+            expected: "u64",
+            got: "u32",
+        }
+        .into(),
+        AnswerError::WouldBlockExecutor.into(),
+        ReceiveError::Timeout(timeout).into(),
+        MigrationError::SourceStopped.into(),
+        MigrationError::TargetStopped.into(),
+        RoutingError::MissingKey.into(),
+        RoutingError::UnknownElement(group).into(),
+        RoutingError::Stopped.into(),
+        BarrierError::ParticipantLost.into(),
+        BarrierError::Stopped.into(),
+        BridgeError::WouldBlockExecutor.into(),
+        BridgeError::Unanswered.into(),
+        BridgeError::TimedOut.into(),
+        StateError::Unknown.into(),
+        StateError::Dropped.into(),
+        MetricError::InvalidName("jobs ok".to_string()).into(),
+        MetricError::Registered {
+            name: "jobs_ok".to_string(),
+            kind: "counter",
+        }
+        .into(),
+        ReloadError::ConstructionTime {
+            fields: vec!["backtraces"],
+        }
+        .into(),
+        BastionError::Builder(BuilderError::NotInitialized),
+        BastionError::Builder(BuilderError::ShuttingDown),
+        BastionError::Reload(ReloadError::NotInitialized),
+        BastionError::Reload(ReloadError::ShuttingDown),
+    ]);
+
+    // Every variant is described, and is the source of the
+    // `BastionError` it was converted into.
+    for err in &errors {
+        assert!(!err.to_string().is_empty(), "{}", variant(err));
+        match err.source() {
+            Some(source) => assert_eq!(source.to_string(), err.to_string()),
+            None => assert!(matches!(
+                err,
+                BastionError::NotInitialized | BastionError::ShuttingDown
+            )),
+        }
+    }
+
+    let mut covered = errors.iter().map(variant).collect::<Vec<_>>();
+    covered.sort_unstable();
+    covered.dedup();
+    let mut expected = vec![
+        "Send(Stopped)",
+        "Builder(ParentStopped)",
+        "Builder(NameTaken)",
+        "Builder(NotInitialized)",
+        "Builder(ShuttingDown)",
+        "Builder(ThreadSpawn)",
+        "Builder(Conflict(OverflowWithoutCapacity))",
+        "Builder(Conflict(BatchedTypeNotAccepted))",
+        "Builder(Conflict(MissingRoutingKey))",
+        "Shutdown(AlreadyStopped)",
+        "Answer(Unanswered)",
+        "Answer(TimedOut)",
+        "Answer(UnexpectedType)",
+        "Answer(WouldBlockExecutor)",
+        "Receive(Timeout)",
+        "Reply(Timeout)",
+        "Reply(Unknown)",
+        "Migration(UnknownElement)",
+        "Migration(SameElement)",
+        "Migration(SourceStopped)",
+        "Migration(TargetStopped)",
+        "Routing(MissingKey)",
+        "Routing(UnknownElement)",
+        "Routing(Stopped)",
+        "Reduce",
+        "Barrier(ParticipantLost)",
+        "Barrier(Stopped)",
+        "Bridge(WouldBlockExecutor)",
+        "Bridge(Send)",
+        "Bridge(Unanswered)",
+        "Bridge(TimedOut)",
+        "State(Unknown)",
+        "State(Dropped)",
+        "Metric(InvalidName)",
+        "Metric(Registered)",
+        "Reload(ConstructionTime)",
+        "Reload(NotInitialized)",
+        "Reload(ShuttingDown)",
+        "NotInitialized",
+        "ShuttingDown",
+    ];
+    expected.sort_unstable();
+    assert_eq!(covered, expected);
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug)]
+enum JobError {
+    Invalid,
+    Stopped,
+}
+
+// Records the errors passed to the `after_error` callback.
+fn recording(errors: Arc<Mutex<Vec<String>>>) -> Callbacks {
+    Callbacks::new().with_after_error(move |err: &ExecError| {
+        errors.lock().unwrap().push(format!("{:?}", err));
+    })
+}
+
+#[test]
+fn custom_error() {
+    init_start();
+
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let starts = Arc::new(AtomicUsize::new(0));
+    let callbacks = recording(errors.clone());
+    let counter = starts.clone();
+
+    Bastion::children(move |children| {
+        let counter = counter.clone();
+        children
+            .with_callbacks(callbacks)
+            .with_exec_err(move |ctx: BastionContext| {
+                let counter = counter.clone();
+                async move {
+                    if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                        return Err(JobError::Invalid);
+                    }
+
+                    loop {
+                        ctx.recv().await.map_err(|_| JobError::Stopped)?;
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    // The element is still restarted.
+    wait_for(|| starts.load(Ordering::SeqCst) == 2);
+    assert_eq!(*errors.lock().unwrap(), vec!["Invalid"]);
+}
+
+#[test]
+fn unit_error() {
+    init_start();
+
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let callbacks = recording(errors.clone());
+    let starts = Arc::new(AtomicUsize::new(0));
+    let counter = starts.clone();
+
+    Bastion::children(move |children| {
+        let counter = counter.clone();
+        children
+            .with_callbacks(callbacks)
+            .with_exec(move |ctx: BastionContext| {
+                let counter = counter.clone();
+                async move {
+                    if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                        return Err(());
+                    }
+
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    wait_for(|| starts.load(Ordering::SeqCst) == 2);
+    assert_eq!(*errors.lock().unwrap(), vec!["()"]);
+}
+
+#[test]
+fn panicked() {
+    init_start();
+
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let callbacks = recording(errors.clone());
+    let starts = Arc::new(AtomicUsize::new(0));
+    let counter = starts.clone();
+
+    Bastion::children(move |children| {
+        let counter = counter.clone();
+        children
+            .with_callbacks(callbacks)
+            .with_exec_err(move |ctx: BastionContext| {
+                let counter = counter.clone();
+                async move {
+                    if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                        panic!("Element panicked.");
+                    }
+
+                    while ctx.recv().await.is_ok() {}
+                    Err("Stopped.")
+                }
+            })
+    })
+    .unwrap();
+
+    // The callback isn't called when the element panics.
+    wait_for(|| starts.load(Ordering::SeqCst) == 2);
+    assert!(errors.lock().unwrap().is_empty());
+}
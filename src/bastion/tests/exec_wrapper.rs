@@ -0,0 +1,130 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+use futures::future::{BoxFuture, FutureExt};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Once};
+use std::task::{Context, Poll};
+
+static START: Once = Once::new();
+
+// The elements whose futures were wrapped, and how many times
+// the wrapped futures were polled.
+static POLLS: Mutex<Vec<(ExecInfo, Arc<AtomicUsize>)>> = Mutex::new(Vec::new());
+
+struct Counted {
+    exec: BoxFuture<'static, Result<(), ()>>,
+    polls: Arc<AtomicUsize>,
+}
+
+impl Future for Counted {
+    type Output = Result<(), ()>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        self.polls.fetch_add(1, Ordering::SeqCst);
+        self.exec.as_mut().poll(ctx)
+    }
+}
+
+fn init_start() {
+    START.call_once(|| {
+        let config = Config::new().exec_wrapper(|info, exec| {
+            let polls = Arc::new(AtomicUsize::new(0));
+            POLLS.lock().unwrap().push((info, polls.clone()));
+            Counted { exec, polls }.boxed()
+        });
+        Bastion::init_with(config);
+        Bastion::start();
+    });
+}
+
+// The polls counted for the futures of the element with this id.
+fn polls(id: &BastionId) -> Vec<(usize, usize)> {
+    POLLS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(info, _)| info.id() == id)
+        .map(|(info, polls)| (info.generation(), polls.load(Ordering::SeqCst)))
+        .collect()
+}
+
+// A group whose elements count the messages they handle, and
+// return an error on "error".
+fn counting(name: &str, redundancy: usize) -> (ChildrenRef, Arc<AtomicUsize>) {
+    let handled = Arc::new(AtomicUsize::new(0));
+    let counted = handled.clone();
+    let children = Bastion::children(move |children| {
+        let counted = counted.clone();
+        children
+            .with_name(name)
+            .with_redundancy(redundancy)
+            .with_exec(move |ctx: BastionContext| {
+                let counted = counted.clone();
+                async move {
+                    loop {
+                        msg! { ctx.recv().await?,
+                            msg: &'static str => {
+                                counted.fetch_add(1, Ordering::SeqCst);
+                                if msg == "error" {
+                                    return Err(());
+                                }
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    (children, handled)
+}
+
+#[test]
+fn counts_polls() {
+    init_start();
+
+    // The messages are sent one after the other, for the elements
+    // to wait for each of them.
+    let (children, handled) = counting("counted", 2);
+    for (sent, elem) in children.elems().iter().cycle().take(6).enumerate() {
+        elem.tell_anonymously("hello").unwrap();
+        wait_for(|| handled.load(Ordering::SeqCst) == sent + 1);
+    }
+
+    for elem in children.elems() {
+        let polls = polls(elem.id());
+        assert_eq!(polls.len(), 1);
+        let (generation, polls) = polls[0];
+        assert_eq!(generation, 0);
+        // At least once for each message.
+        assert!(polls >= 3, "Polled {} times.", polls);
+    }
+
+    let infos = POLLS.lock().unwrap();
+    let info = &infos
+        .iter()
+        .find(|(info, _)| info.id() == children.elems()[0].id())
+        .unwrap()
+        .0;
+    assert_eq!(info.group(), Some("counted"));
+}
+
+#[test]
+fn generations() {
+    init_start();
+
+    let (children, handled) = counting("restarted", 1);
+    let elem = &children.elems()[0];
+    elem.tell_anonymously("error").unwrap();
+
+    // The restarted element's future is wrapped again.
+    wait_for(|| polls(elem.id()).len() == 2);
+    let generations: Vec<_> = polls(elem.id()).iter().map(|(gen, _)| *gen).collect();
+    assert_eq!(generations, vec![0, 1]);
+    assert_eq!(handled.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for_within;
+use futures::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const FAULTS: usize = 1000;
+const WINDOW: Duration = Duration::from_millis(200);
+
+#[test]
+fn coalesced_faults() {
+    Bastion::init();
+    let mut events = Bastion::events();
+
+    let starts = Arc::new(AtomicUsize::new(0));
+    let counter = starts.clone();
+    Bastion::supervisor(move |sp| {
+        let restart_strategy = RestartStrategy::new(
+            RestartPolicy::Tries(FAULTS),
+            ActorRestartStrategy::Immediate,
+        );
+        sp.with_restart_strategy(restart_strategy)
+            .with_fault_reports(WINDOW, 2)
+            .children(move |children| {
+                let counter = counter.clone();
+                children.with_exec(move |_: BastionContext| {
+                    let counter = counter.clone();
+                    async move {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                        Err(())
+                    }
+                })
+            })
+    })
+    .unwrap();
+
+    Bastion::start();
+
+    // The element faults once more after its last restart.
+    wait_for_within(Duration::from_secs(10), || {
+        starts.load(Ordering::SeqCst) == FAULTS + 1
+    });
+    thread::sleep(WINDOW * 2);
+    assert_eq!(starts.load(Ordering::SeqCst), FAULTS + 1);
+
+    let mut faults = 0;
+    let mut reports = 0;
+    while faults < FAULTS + 1 {
+        match run!(events.next()) {
+            Some(Event::ElemsFaulted { reason, count, .. }) => {
+                assert_eq!(reason, FaultReason::Errored);
+                faults += count;
+                reports += 1;
+            }
+            Some(_) => (),
+            None => panic!("The events stream ended."),
+        }
+    }
+
+    assert_eq!(faults, FAULTS + 1);
+    // At most two faults reported as they happen and a summary
+    // per window.
+    assert!(reports < 50, "{} fault reports.", reports);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,14 @@
+use std::path::Path;
+use std::process::Command;
+
+// Checks the crate with every combination of its optional features,
+// which takes a few minutes, so it only runs when asked to (with
+// `cargo test --test features -- --ignored`, as the CI's
+// `check_features` job does).
+#[test]
+#[ignore]
+fn feature_combinations() {
+    let script = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../tools/check_features.sh");
+    let status = Command::new("bash").arg(script).status().unwrap();
+    assert!(status.success());
+}
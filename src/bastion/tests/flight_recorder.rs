@@ -0,0 +1,88 @@
+mod common;
+
+use bastion::prelude::*;
+use common::init_start;
+use futures::prelude::*;
+
+#[derive(Debug)]
+struct A;
+#[derive(Debug)]
+struct B;
+#[derive(Debug)]
+struct C;
+// Makes the element panic, or return an error if it carries
+// `false`.
+#[derive(Debug)]
+struct Fault(bool);
+
+// Tells the messages to a group whose elements keep this number of
+// records and returns the records reported once it faulted.
+fn records(capacity: usize, panic: bool) -> Vec<(&'static str, RecordOutcome)> {
+    let mut events = Bastion::events();
+    let children = Bastion::children(|children| {
+        children
+            .with_flight_recorder(capacity)
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        msg: Fault => {
+                            if msg.0 {
+                                panic!("Panicking as asked.");
+                            }
+
+                            return Err(());
+                        };
+                        _: _ => ();
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    let elem = &children.elems()[0];
+    elem.tell_anonymously(A).unwrap();
+    elem.tell_anonymously(B).unwrap();
+    elem.tell_anonymously(C).unwrap();
+    elem.tell_anonymously(Fault(panic)).unwrap();
+
+    loop {
+        match run!(events.next()) {
+            Some(Event::ElemsFaulted { group, records, .. }) if &group == children.id() => {
+                return records
+                    .iter()
+                    .map(|record| (record.type_name(), record.outcome()))
+                    .collect();
+            }
+            Some(_) => (),
+            None => panic!("The events stream ended."),
+        }
+    }
+}
+
+#[test]
+fn reported_in_order() {
+    init_start();
+
+    assert_eq!(
+        records(8, true),
+        vec![
+            ("flight_recorder::A", RecordOutcome::Handled),
+            ("flight_recorder::B", RecordOutcome::Handled),
+            ("flight_recorder::C", RecordOutcome::Handled),
+            ("flight_recorder::Fault", RecordOutcome::Faulted),
+        ]
+    );
+}
+
+#[test]
+fn bounded() {
+    init_start();
+
+    assert_eq!(
+        records(2, false),
+        vec![
+            ("flight_recorder::C", RecordOutcome::Handled),
+            ("flight_recorder::Fault", RecordOutcome::Faulted),
+        ]
+    );
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "patterns")]
+use bastion::patterns;
+use bastion::prelude::*;
+use futures::prelude::*;
+use std::time::Duration;
+
+// Answers the double of every number except `0`, which
+// makes the element stop without answering.
+fn doubling() -> ChildrenRef {
+    Bastion::children(|children| {
+        children
+            .with_redundancy(2)
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        n: u64 =!> {
+                            if n == 0 {
+                                return Ok(());
+                            }
+
+                            answer!(ctx, n * 2).unwrap();
+                        };
+                        _: _ => ();
+                    }
+                }
+            })
+    })
+    .unwrap()
+}
+
+// Never receives its messages.
+fn idle() -> ChildrenRef {
+    Bastion::children(|children| {
+        children.with_exec(|_: BastionContext| future::pending::<Result<(), ()>>())
+    })
+    .unwrap()
+}
+
+fn doubled(result: Result<SignedMessage, AnswerError>) -> Option<u64> {
+    msg! { result.ok()?,
+        n: u64 => Some(n);
+        _: _ => None;
+    }
+}
+
+#[test]
+fn gather_answers() {
+    Bastion::init();
+    Bastion::start();
+
+    let doubling = doubling();
+    let doubling = doubling.elems();
+    let idle = idle();
+    let idle = idle.elems();
+    let stopped = {
+        let children = Bastion::children(|children| children).unwrap();
+        let elem = children.elems()[0].clone();
+        children.kill().unwrap();
+        elem
+    };
+    let requests = || {
+        vec![
+            ("idle", idle[0].clone(), 1u64),
+            ("stops", doubling[1].clone(), 0),
+            ("first", doubling[0].clone(), 21),
+            ("stopped", stopped.clone(), 1),
+            ("second", doubling[0].clone(), 2),
+        ]
+    };
+
+    let results = run!(patterns::gather(requests()).with_timeout(Duration::from_millis(200)));
+    let keys = results.iter().map(|(key, _)| *key).collect::<Vec<_>>();
+    assert_eq!(keys, ["idle", "stops", "first", "stopped", "second"]);
+
+    let mut results = results.into_iter();
+    let (_, result) = results.next().unwrap();
+    assert_eq!(result.unwrap_err(), AnswerError::TimedOut);
+    let (_, result) = results.next().unwrap();
+    assert_eq!(result.unwrap_err(), AnswerError::Unanswered);
+    let (_, result) = results.next().unwrap();
+    assert_eq!(doubled(result), Some(42));
+    let (_, result) = results.next().unwrap();
+    assert_eq!(result.unwrap_err(), AnswerError::Unanswered);
+    let (_, result) = results.next().unwrap();
+    assert_eq!(doubled(result), Some(4));
+
+    // The idle element's key is yielded last, once the timeout
+    // expired.
+    let results = patterns::gather_unordered(requests()).with_timeout(Duration::from_millis(200));
+    let results = run!(results.collect::<Vec<_>>());
+    let keys = results.iter().map(|(key, _)| *key).collect::<Vec<_>>();
+    assert_eq!(keys.len(), 5);
+    assert_eq!(keys.last(), Some(&"idle"));
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use bastion::children_ref::GroupState;
+use bastion::prelude::*;
+use common::wait_for;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const MAX_AGE: Duration = Duration::from_secs(3);
+
+// Creates a group of elements receiving messages, which fault
+// once (returning an error) if `faulting` is set.
+fn spawn_group(name: &str, redundancy: usize, faulting: bool) -> ChildrenRef {
+    let faulted = Arc::new(AtomicBool::new(!faulting));
+    Bastion::children(move |children| {
+        let faulted = faulted.clone();
+        children
+            .with_name(name)
+            .with_redundancy(redundancy)
+            .with_exec(move |ctx: BastionContext| {
+                let faulted = faulted.clone();
+                async move {
+                    if !faulted.swap(true, Ordering::SeqCst) {
+                        return Err(());
+                    }
+
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+    })
+    .unwrap()
+}
+
+fn names() -> Vec<String> {
+    Bastion::terminated_groups()
+        .iter()
+        .map(|group| group.name().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn terminated_groups() {
+    let config = Config::new().with_group_history(3, MAX_AGE);
+    Bastion::init_with(config);
+    Bastion::start();
+
+    let started = Instant::now();
+    let stopped = spawn_group("stopped", 2, false);
+    let elems = stopped
+        .elems()
+        .iter()
+        .map(|elem| elem.id().clone())
+        .collect::<Vec<_>>();
+    for n in 0..3u64 {
+        stopped.elems()[0].tell_anonymously(n).unwrap();
+    }
+    wait_for(|| stopped.stats().processed() == 3);
+    assert_eq!(run!(stopped.stop_and_wait()).unwrap(), GroupState::Stopped);
+
+    let faulted = spawn_group("faulted", 1, true);
+    wait_for(|| faulted.stats().restarts() == 1);
+    wait_for(|| faulted.warm_elems() == 1);
+    let restarted = faulted.elems()[0].id().clone();
+    faulted.kill().unwrap();
+    wait_for(|| Bastion::terminated_groups().len() == 2);
+
+    // The groups can't be retrieved anymore, but are summarized in
+    // the history in the order they terminated...
+    assert!(Bastion::children_of("stopped").is_none());
+    assert!(Bastion::children_of("faulted").is_none());
+    let history = Bastion::terminated_groups();
+    assert_eq!(names(), vec!["stopped", "faulted"]);
+
+    let mut expected = elems.clone();
+    expected.sort_by_key(|elem| elem.to_string());
+    assert_eq!(history[0].id(), stopped.id());
+    assert_eq!(history[0].elems(), &expected[..]);
+    assert_eq!(history[0].state(), GroupState::Stopped);
+    assert_eq!(history[0].faults(), &[]);
+    assert_eq!(history[0].restarts(), 0);
+    assert_eq!(history[0].processed(), 3);
+    assert_eq!(history[0].rejected(), 0);
+    assert!(history[0].lifetime() <= started.elapsed());
+
+    // The group's elements are killed along with it, but it
+    // itself stopped.
+    assert_eq!(history[1].id(), faulted.id());
+    assert_eq!(history[1].elems(), &[restarted]);
+    assert_eq!(history[1].state(), GroupState::Stopped);
+    assert_eq!(history[1].faults(), &[(FaultReason::Errored, 1)]);
+    assert_eq!(history[1].restarts(), 1);
+    assert!(history[0].terminated_at() <= history[1].terminated_at());
+
+    // ...which is part of the tree snapshots.
+    #[cfg(feature = "introspection")]
+    {
+        let tree = Bastion::tree();
+        assert_eq!(tree.history(), Some(&history[..]));
+        let json = tree.to_json();
+        let exported = format!(
+            "\"history\":[{{\"id\":\"{}\",\"name\":\"stopped\",\"state\":\"stopped\",\"elems\":[\"{}\",\"{}\"],\"faults\":[],\"restarts\":0,\"processed\":3,\"rejected\":0,",
+            stopped.id(),
+            expected[0],
+            expected[1]
+        );
+        assert!(json.contains(&exported), "{}", json);
+        assert!(json.contains("\"faults\":[{\"reason\":\"errored\",\"count\":1}]"));
+    }
+
+    // The oldest groups are dropped once there are too many...
+    for name in &["third", "fourth"] {
+        let children = spawn_group(name, 1, false);
+        children.stop().unwrap();
+        wait_for(|| names().last().map(String::as_str) == Some(name));
+    }
+    assert_eq!(names(), vec!["faulted", "third", "fourth"]);
+
+    // ...and once they were kept for too long.
+    thread::sleep(MAX_AGE);
+    assert!(Bastion::terminated_groups().is_empty());
+    #[cfg(feature = "introspection")]
+    assert_eq!(Bastion::tree().history(), Some(&[][..]));
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use bastion::children_ref::GroupState;
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use futures::StreamExt;
+
+fn panicking_on_demand(children: Children) -> Children {
+    children.with_exec(|ctx: BastionContext| async move {
+        loop {
+            msg! { ctx.recv().await?,
+                ref msg: &'static str => {
+                    if *msg == "panic" {
+                        panic!("Element panicked.");
+                    }
+                };
+                _: _ => ();
+            }
+        }
+    })
+}
+
+#[test]
+fn stop_transitions() {
+    init_start();
+
+    let children = Bastion::children(panicking_on_demand).unwrap();
+    wait_for(|| children.state() == GroupState::Running);
+
+    let changes = children.state_changes();
+    children.stop().unwrap();
+
+    let observed: Vec<_> = run!(changes.collect());
+    assert_eq!(
+        observed,
+        vec![
+            GroupState::Running,
+            GroupState::Stopping,
+            GroupState::Stopped
+        ]
+    );
+    assert_eq!(children.state(), GroupState::Stopped);
+
+    // Subscribing once the group stopped only yields its state.
+    let observed: Vec<_> = run!(children.state_changes().collect());
+    assert_eq!(observed, vec![GroupState::Stopped]);
+}
+
+#[test]
+fn fault_and_restart_transitions() {
+    init_start();
+
+    let mut children = None;
+    Bastion::supervisor(|sp| {
+        let sp = sp.with_restart_strategy(
+            RestartStrategy::default().with_restart_policy(RestartPolicy::Tries(1)),
+        );
+        children = Some(sp.children_ref(panicking_on_demand).unwrap());
+        sp
+    })
+    .unwrap();
+    let children = children.unwrap();
+    wait_for(|| children.state() == GroupState::Running);
+
+    let mut changes = children.state_changes();
+    assert_eq!(run!(changes.next()), Some(GroupState::Running));
+
+    // The element is restarted once...
+    children.broadcast("panic").unwrap();
+    assert_eq!(
+        run!(changes.next()),
+        Some(GroupState::Restarting { attempt: 1 })
+    );
+    assert_eq!(run!(changes.next()), Some(GroupState::Running));
+
+    // ...but not twice.
+    children.broadcast("panic").unwrap();
+    assert_eq!(
+        run!(changes.next()),
+        Some(GroupState::Restarting { attempt: 2 })
+    );
+    assert_eq!(run!(changes.next()), Some(GroupState::Faulted));
+    assert_eq!(run!(changes.next()), None);
+}
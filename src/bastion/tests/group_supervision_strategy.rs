@@ -0,0 +1,129 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const REDUNDANCY: usize = 3;
+
+// Configures a group counting the starts of its elements, which
+// fault when they receive "fault".
+fn counting(children: Children, starts: &Arc<AtomicUsize>) -> Children {
+    let starts = starts.clone();
+    children
+        .with_redundancy(REDUNDANCY)
+        .with_exec(move |ctx: BastionContext| {
+            let starts = starts.clone();
+            async move {
+                starts.fetch_add(1, Ordering::SeqCst);
+
+                loop {
+                    msg! { ctx.recv().await?,
+                        msg: &'static str => {
+                            if msg == "fault" {
+                                return Err(());
+                            }
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+}
+
+// Creates a supervisor using `strategy`, supervising a group
+// using `group_strategy` (set once it runs if `switched` is set)
+// and a sibling group, and makes an element of the former fault.
+// Returns how many elements of both groups were started once the
+// restarts settled.
+fn restarted(
+    strategy: SupervisionStrategy,
+    group_strategy: SupervisionStrategy,
+    switched: bool,
+) -> (usize, usize) {
+    let faulting = Arc::new(AtomicUsize::new(0));
+    let sibling = Arc::new(AtomicUsize::new(0));
+
+    let mut children = None;
+    let built = group_strategy.clone();
+    Bastion::supervisor(|sp| {
+        let sp = sp.with_strategy(strategy);
+        children = Some(
+            sp.children_ref(|children| {
+                let children = counting(children, &faulting);
+                if switched {
+                    children
+                } else {
+                    children.with_supervision_strategy(built)
+                }
+            })
+            .unwrap(),
+        );
+        sp.children(|children| counting(children, &sibling))
+    })
+    .unwrap();
+    let children = children.unwrap();
+
+    wait_for(|| faulting.load(Ordering::SeqCst) == REDUNDANCY);
+    wait_for(|| sibling.load(Ordering::SeqCst) == REDUNDANCY);
+    if switched {
+        children.strategy(group_strategy).unwrap();
+    }
+
+    children.elems()[1].tell_anonymously("fault").unwrap();
+    wait_for(|| faulting.load(Ordering::SeqCst) > REDUNDANCY);
+    thread::sleep(Duration::from_millis(200));
+
+    (
+        faulting.load(Ordering::SeqCst),
+        sibling.load(Ordering::SeqCst),
+    )
+}
+
+#[test]
+fn group_supervision_strategy() {
+    Bastion::init();
+    Bastion::start();
+
+    // Only the faulted element is restarted, even though the
+    // supervisor would restart everything...
+    let starts = restarted(
+        SupervisionStrategy::OneForAll,
+        SupervisionStrategy::OneForOne,
+        false,
+    );
+    assert_eq!(starts, (REDUNDANCY + 1, REDUNDANCY));
+
+    // ...all the elements of the group are restarted, but not the
+    // sibling group's...
+    let starts = restarted(
+        SupervisionStrategy::OneForOne,
+        SupervisionStrategy::OneForAll,
+        false,
+    );
+    assert_eq!(starts, (2 * REDUNDANCY, REDUNDANCY));
+
+    // ...or only the faulted element and the ones launched after
+    // it (in an order which `elems` doesn't follow).
+    let (faulting, sibling) = restarted(
+        SupervisionStrategy::OneForAll,
+        SupervisionStrategy::RestForOne,
+        false,
+    );
+    assert!(faulting > REDUNDANCY && faulting <= 2 * REDUNDANCY);
+    assert_eq!(sibling, REDUNDANCY);
+
+    // The strategy can also be set once the group runs.
+    let starts = restarted(
+        SupervisionStrategy::OneForAll,
+        SupervisionStrategy::OneForOne,
+        true,
+    );
+    assert_eq!(starts, (REDUNDANCY + 1, REDUNDANCY));
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
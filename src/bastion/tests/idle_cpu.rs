@@ -0,0 +1,54 @@
+#![cfg(target_os = "linux")]
+
+use bastion::prelude::*;
+use std::fs;
+use std::thread;
+use std::time::Duration;
+
+const GROUPS: usize = 100;
+const REDUNDANCY: usize = 100;
+
+// Returns the CPU time used by this process so far, in clock
+// ticks (usually hundredths of a second).
+fn cpu_ticks() -> u64 {
+    let stat = fs::read_to_string("/proc/self/stat").unwrap();
+    // The fields following the process' name, which is wrapped
+    // in parentheses.
+    let fields = stat.rsplit(')').next().unwrap();
+    let fields: Vec<&str> = fields.split_whitespace().collect();
+    // `utime` and `stime`.
+    fields[11].parse::<u64>().unwrap() + fields[12].parse::<u64>().unwrap()
+}
+
+#[test]
+fn idle_children() {
+    Bastion::init();
+    Bastion::start();
+
+    for _ in 0..GROUPS {
+        Bastion::children(|children| {
+            children
+                .with_redundancy(REDUNDANCY)
+                .with_exec(|ctx: BastionContext| async move {
+                    loop {
+                        ctx.recv().await?;
+                    }
+                })
+        })
+        .unwrap();
+    }
+
+    // Lets the elements start.
+    thread::sleep(Duration::from_secs(2));
+
+    let before = cpu_ticks();
+    thread::sleep(Duration::from_secs(2));
+    let used = cpu_ticks() - before;
+
+    // Less than 10% of a core, while busy-polling would use at
+    // least one.
+    assert!(used < 20, "{} ticks were used while idle.", used);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,203 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// Counts the allocations made by each thread.
+struct Counting;
+
+thread_local! {
+    static ALLOCS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCS.try_with(|allocs| allocs.set(allocs.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOC: Counting = Counting;
+
+fn allocs() -> usize {
+    ALLOCS.with(Cell::get)
+}
+
+#[derive(Debug)]
+// As small as a `u64`, but not inlined as it needs to be dropped.
+struct Boxed(u64);
+
+impl Drop for Boxed {
+    fn drop(&mut self) {}
+}
+
+#[derive(Debug)]
+// Small enough and not dropped either, but not inlined as it doesn't
+// derive `InlineMessage`.
+struct Unvetted(u64);
+
+#[derive(Debug, Clone, Copy)]
+// Inlined like a `u64`, once registered.
+struct Derived(u64);
+
+inline_message!(Derived);
+
+#[test]
+fn fewer_allocations() {
+    init_start();
+    Derived::register();
+
+    const TELLS: usize = 1_000;
+
+    let received = Arc::new(AtomicU64::new(0));
+    let children = Bastion::children(|children| {
+        let received = received.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let received = received.clone();
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        n: u64 => {
+                            received.fetch_add(n, Ordering::SeqCst);
+                        };
+                        boxed: Boxed => {
+                            received.fetch_add(boxed.0, Ordering::SeqCst);
+                        };
+                        unvetted: Unvetted => {
+                            received.fetch_add(unvetted.0, Ordering::SeqCst);
+                        };
+                        derived: Derived => {
+                            received.fetch_add(derived.0, Ordering::SeqCst);
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .unwrap();
+    let elem = children.elems()[0].clone();
+
+    let before = allocs();
+    for _ in 0..TELLS {
+        elem.tell_anonymously(1u64).unwrap();
+    }
+    let inline = allocs() - before;
+
+    let before = allocs();
+    for _ in 0..TELLS {
+        elem.tell_anonymously(Boxed(1)).unwrap();
+    }
+    let boxed = allocs() - before;
+
+    let before = allocs();
+    for _ in 0..TELLS {
+        elem.tell_anonymously(Unvetted(1)).unwrap();
+    }
+    let unvetted = allocs() - before;
+
+    let before = allocs();
+    for _ in 0..TELLS {
+        elem.tell_anonymously(Derived(1)).unwrap();
+    }
+    let derived = allocs() - before;
+
+    // Each boxed message takes (at least) one more allocation...
+    assert!(
+        boxed >= inline + TELLS,
+        "{} < {} + {}",
+        boxed,
+        inline,
+        TELLS
+    );
+    // ...even if it would fit...
+    assert!(
+        unvetted >= inline + TELLS,
+        "{} < {} + {}",
+        unvetted,
+        inline,
+        TELLS
+    );
+    // ...unlike the messages of the types deriving `InlineMessage`...
+    assert!(
+        boxed >= derived + TELLS,
+        "{} < {} + {}",
+        boxed,
+        derived,
+        TELLS
+    );
+    // ...while they are all received alike.
+    wait_for(|| received.load(Ordering::SeqCst) == 4 * TELLS as u64);
+}
+
+#[test]
+fn transparent() {
+    init_start();
+
+    // The messages received by the element, as retrieved in
+    // different ways.
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let children = Bastion::children(|children| {
+        let received = received.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let received = received.clone();
+            async move {
+                let msg = ctx
+                    .recv_where(|msg| msg.downcast_ref::<u64>() == Some(&42))
+                    .await?;
+                received
+                    .lock()
+                    .unwrap()
+                    .push(format!("peeked {:?}", msg.msg().downcast_ref::<u64>()));
+
+                let (msg, _) = ctx.recv().await?.extract();
+                assert!(msg.is_tell());
+                let msg = msg.downcast::<u64>().unwrap_err();
+                let msg = msg.downcast::<&'static str>().unwrap();
+                received.lock().unwrap().push(format!("downcasted {}", msg));
+
+                let (msg, _) = ctx.recv().await?.extract();
+                if let Ok(TypedMsg::Tell(msg)) = msg.downcast_typed::<char>() {
+                    received.lock().unwrap().push(format!("typed {}", msg));
+                }
+
+                loop {
+                    msg! { ctx.recv().await?,
+                        duration: Duration => {
+                            received.lock().unwrap().push(format!("matched {:?}", duration));
+                        };
+                        n: u64 =!> {
+                            answer!(ctx, n * 2).unwrap();
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .unwrap();
+    let elem = &children.elems()[0];
+
+    elem.tell_anonymously("str").unwrap();
+    elem.tell_anonymously('c').unwrap();
+    elem.tell_anonymously(Duration::from_secs(1)).unwrap();
+    elem.tell_anonymously(42u64).unwrap();
+    let answer = elem.ask_anonymously(21u64).unwrap();
+
+    let (answer, _) = run!(answer).unwrap().extract();
+    assert_eq!(answer.downcast::<u64>().unwrap(), 42);
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec!["peeked Some(42)", "downcasted str", "typed c", "matched 1s"]
+    );
+}
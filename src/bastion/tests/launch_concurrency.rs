@@ -0,0 +1,165 @@
+mod common;
+
+#[cfg(feature = "introspection")]
+use bastion::children_ref::GroupState;
+use bastion::prelude::*;
+#[cfg(feature = "introspection")]
+use bastion::tree::GroupNode;
+use common::{init_start, wait_for_within};
+#[cfg(feature = "introspection")]
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+#[cfg(feature = "introspection")]
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "introspection")]
+// The group's node in the current tree snapshot.
+fn node(children: &ChildrenRef) -> GroupNode {
+    Bastion::tree().supervisors()[0]
+        .groups()
+        .iter()
+        .find(|group| group.id() == children.id())
+        .cloned()
+        .unwrap()
+}
+
+#[test]
+fn ceiling() {
+    init_start();
+
+    let current = Arc::new(AtomicUsize::new(0));
+    let max = Arc::new(AtomicUsize::new(0));
+    let initialized = Arc::new(AtomicUsize::new(0));
+    let (counted, maxed, done) = (current.clone(), max.clone(), initialized.clone());
+    Bastion::children(move |children| {
+        let (current, max, initialized) = (counted.clone(), maxed.clone(), done.clone());
+        children
+            .with_redundancy(500)
+            .with_launch_concurrency(10)
+            .with_async_init(move |ctx: BastionContext| {
+                let (current, max, initialized) =
+                    (current.clone(), max.clone(), initialized.clone());
+                async move {
+                    let initializing = current.fetch_add(1, Ordering::SeqCst) + 1;
+                    max.fetch_max(initializing, Ordering::SeqCst);
+                    ctx.sleep(Duration::from_millis(2)).await;
+                    current.fetch_sub(1, Ordering::SeqCst);
+                    initialized.fetch_add(1, Ordering::SeqCst);
+
+                    Ok::<_, ()>(())
+                }
+            })
+            .with_exec(|_: (), ctx: BastionContext| async move {
+                loop {
+                    ctx.recv().await?;
+                }
+            })
+    })
+    .unwrap();
+
+    wait_for_within(Duration::from_secs(10), || {
+        initialized.load(Ordering::SeqCst) == 500
+    });
+    let max = max.load(Ordering::SeqCst);
+    assert!(max <= 10, "{}", max);
+    assert!(max > 1, "{}", max);
+}
+
+#[test]
+#[cfg(feature = "introspection")]
+fn progress() {
+    init_start();
+
+    let open = Arc::new(AtomicBool::new(false));
+    let handled = Arc::new(AtomicUsize::new(0));
+    let (gate, counted) = (open.clone(), handled.clone());
+    let children = Bastion::children(move |children| {
+        let (gate, counted) = (gate.clone(), counted.clone());
+        children
+            .with_redundancy(20)
+            .with_launch_concurrency(5)
+            .with_async_init(move |ctx: BastionContext| {
+                let gate = gate.clone();
+                async move {
+                    while !gate.load(Ordering::SeqCst) {
+                        ctx.sleep(Duration::from_millis(1)).await;
+                    }
+
+                    Ok::<_, ()>(())
+                }
+            })
+            .with_exec(move |_: (), ctx: BastionContext| {
+                let counted = counted.clone();
+                async move {
+                    loop {
+                        msg! { ctx.recv().await?,
+                            _msg: &'static str => {
+                                counted.fetch_add(1, Ordering::SeqCst);
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    // The elements that aren't launched yet can already be sent
+    // messages.
+    assert_eq!(children.elems().len(), 20);
+    for elem in children.elems() {
+        elem.tell_anonymously("hello").unwrap();
+    }
+
+    thread::sleep(Duration::from_millis(50));
+    let group = node(&children);
+    assert_eq!(group.elems().len(), 20);
+    assert_eq!(group.launched(), 5);
+    assert_eq!(group.initialized(), 0);
+    assert!(group.state() == GroupState::Running);
+
+    open.store(true, Ordering::SeqCst);
+    wait_for_within(Duration::from_secs(10), || {
+        node(&children).initialized() == 20
+    });
+    assert_eq!(node(&children).launched(), 20);
+    wait_for_within(Duration::from_secs(10), || {
+        handled.load(Ordering::SeqCst) == 20
+    });
+}
+
+#[test]
+#[cfg(feature = "introspection")]
+fn killed_while_launching() {
+    init_start();
+
+    let launched = Arc::new(AtomicUsize::new(0));
+    let counted = launched.clone();
+    let children = Bastion::children(move |children| {
+        let counted = counted.clone();
+        children
+            .with_redundancy(50)
+            .with_launch_concurrency(2)
+            .with_async_init(move |ctx: BastionContext| {
+                counted.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    // The elements don't get initialized in time.
+                    ctx.sleep(Duration::from_secs(60)).await;
+                    Ok::<_, ()>(())
+                }
+            })
+            .with_exec(|_: (), _: BastionContext| async { Ok(()) })
+    })
+    .unwrap();
+
+    // The group handles the messages it receives between waves.
+    wait_for_within(Duration::from_secs(10), || node(&children).launched() == 2);
+    children.kill().unwrap();
+    wait_for_within(Duration::from_secs(10), || {
+        children.state() == GroupState::Stopped
+    });
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(launched.load(Ordering::SeqCst), 2);
+}
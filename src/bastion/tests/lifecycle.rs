@@ -0,0 +1,64 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+// Creates a group named "workers" counting the messages it
+// receives.
+fn workers(received: &Arc<AtomicUsize>) -> Result<ChildrenRef, BuilderError> {
+    let received = received.clone();
+    Bastion::children(|children| {
+        children
+            .with_name("workers")
+            .with_exec(move |ctx: BastionContext| {
+                let received = received.clone();
+                async move {
+                    loop {
+                        ctx.recv().await?;
+                        received.fetch_add(1, Ordering::SeqCst);
+                    }
+                }
+            })
+    })
+}
+
+#[test]
+fn init_start_stop_cycles() {
+    let received = Arc::new(AtomicUsize::new(0));
+    let err = workers(&received).unwrap_err();
+    assert_eq!(err, BuilderError::NotInitialized);
+    assert_eq!(BastionError::from(err), BastionError::NotInitialized);
+
+    Bastion::init();
+    Bastion::start();
+    let first = workers(&received).unwrap();
+    first.broadcast("Hello").unwrap();
+    wait_for(|| received.load(Ordering::SeqCst) == 1);
+
+    Bastion::stop();
+    let err = workers(&received).unwrap_err();
+    assert_eq!(err, BuilderError::ShuttingDown);
+    assert_eq!(BastionError::from(err), BastionError::ShuttingDown);
+    Bastion::block_until_stopped();
+    assert_eq!(workers(&received).unwrap_err(), BuilderError::ShuttingDown);
+
+    // The second system doesn't know about the first one's groups.
+    Bastion::init();
+    assert!(Bastion::children_of("workers").is_none());
+    let second = workers(&received).unwrap();
+    assert_ne!(second.id(), first.id());
+    assert!(first.broadcast("Hello").is_err());
+
+    Bastion::start();
+    Bastion::broadcast("Hello").unwrap();
+    wait_for(|| received.load(Ordering::SeqCst) == 2);
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(received.load(Ordering::SeqCst), 2);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,153 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[derive(Clone, Default)]
+struct Gate {
+    open: Arc<AtomicBool>,
+    received: Arc<Mutex<Vec<u64>>>,
+}
+
+// Creates an element with a mailbox holding two messages, which
+// doesn't retrieve them until `gate` is opened and then records
+// those it received.
+fn gated(policy: OverflowPolicy, gate: &Gate) -> ChildRef {
+    let gate = gate.clone();
+    let children = Bastion::children(move |children| {
+        let gate = gate.clone();
+        children
+            .with_mailbox_capacity(2)
+            .with_overflow_policy(policy)
+            .with_exec(move |ctx: BastionContext| {
+                let gate = gate.clone();
+                async move {
+                    loop {
+                        if gate.open.load(Ordering::SeqCst) {
+                            while let Some(msg) = ctx.try_recv().await {
+                                msg! { msg,
+                                    n: u64 => gate.received.lock().unwrap().push(n);
+                                    n: u64 =!> gate.received.lock().unwrap().push(n);
+                                    _: _ => ();
+                                }
+                            }
+                        }
+
+                        Delay::new(Duration::from_millis(10)).await;
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    children.elems()[0].clone()
+}
+
+fn received(gate: &Gate, expected: Vec<u64>) {
+    gate.open.store(true, Ordering::SeqCst);
+    wait_for(|| gate.received.lock().unwrap().len() == expected.len());
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(*gate.received.lock().unwrap(), expected);
+}
+
+#[test]
+fn fail() {
+    init_start();
+
+    let gate = Gate::default();
+    let elem = gated(OverflowPolicy::Fail, &gate);
+
+    elem.tell_anonymously(1u64).unwrap();
+    elem.tell_anonymously(2u64).unwrap();
+    let err = elem.tell_anonymously(3u64).unwrap_err();
+    assert_eq!(err.kind(), SendErrorKind::MailboxFull);
+    assert_eq!(err.into_inner_msg(), 3);
+    let err = elem.ask_anonymously(4u64).unwrap_err();
+    assert_eq!(err.kind(), SendErrorKind::MailboxFull);
+
+    received(&gate, vec![1, 2]);
+
+    // The retrieved messages released their places.
+    elem.tell_anonymously(5u64).unwrap();
+    wait_for(|| gate.received.lock().unwrap().len() == 3);
+}
+
+#[test]
+fn drop_newest() {
+    init_start();
+
+    let gate = Gate::default();
+    let elem = gated(OverflowPolicy::DropNewest, &gate);
+
+    for n in 1..=4u64 {
+        elem.tell_anonymously(n).unwrap();
+    }
+
+    received(&gate, vec![1, 2]);
+}
+
+#[test]
+fn drop_oldest() {
+    init_start();
+
+    let gate = Gate::default();
+    let elem = gated(OverflowPolicy::DropOldest, &gate);
+
+    for n in 1..=4u64 {
+        elem.tell_anonymously(n).unwrap();
+    }
+
+    received(&gate, vec![3, 4]);
+}
+
+#[test]
+fn drop_oldest_keeps_high_priority() {
+    init_start();
+
+    let gate = Gate::default();
+    let elem = gated(OverflowPolicy::DropOldest, &gate);
+
+    elem.tell_anonymously(1u64).unwrap();
+    elem.tell_anonymously(2u64).unwrap();
+    wait_for(|| elem.mailbox_len() == 2);
+    // The question lands first, which doesn't make it the oldest
+    // message to drop.
+    elem.ask_with_priority(3u64, Priority::High).unwrap();
+
+    received(&gate, vec![3, 2]);
+}
+
+#[test]
+fn block() {
+    init_start();
+
+    let gate = Gate::default();
+    let elem = gated(OverflowPolicy::Block, &gate);
+
+    elem.tell_anonymously(1u64).unwrap();
+    elem.tell_anonymously(2u64).unwrap();
+    let err = elem.tell_anonymously(3u64).unwrap_err();
+    assert_eq!(err.kind(), SendErrorKind::MailboxFull);
+
+    let sent = Arc::new(AtomicBool::new(false));
+    let blocked = {
+        let sent = sent.clone();
+        let elem = elem.clone();
+        thread::spawn(move || {
+            run!(elem.tell_async(3u64)).unwrap();
+            sent.store(true, Ordering::SeqCst);
+        })
+    };
+
+    thread::sleep(Duration::from_millis(100));
+    assert!(!sent.load(Ordering::SeqCst));
+
+    received(&gate, vec![1, 2, 3]);
+    blocked.join().unwrap();
+    assert!(sent.load(Ordering::SeqCst));
+}
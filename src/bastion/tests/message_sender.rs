@@ -0,0 +1,95 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::sync::{Arc, Mutex};
+
+// An element replying "pong" to whoever told it "ping", which
+// records whether each "ping" had a sender and whether its
+// signature identified it.
+fn ponging(identified: &Arc<Mutex<Vec<(bool, bool)>>>) -> ChildRef {
+    let identified = identified.clone();
+    let children = Bastion::children(|children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let identified = identified.clone();
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        _msg: &'static str => {
+                            let sender: Option<ChildRef> = sender!();
+                            let signed = signature!().is_sender_identified();
+                            identified.lock().unwrap().push((sender.is_some(), signed));
+                            if let Some(sender) = sender {
+                                ctx.tell(&sender.addr(), "pong").unwrap();
+                            }
+                        };
+                        _msg: &'static str =!> {
+                            answer!(ctx, "pong").unwrap();
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    children.elems()[0].clone()
+}
+
+#[test]
+fn reply_to_sender() {
+    init_start();
+
+    let identified = Arc::new(Mutex::new(Vec::new()));
+    let ponger = ponging(&identified);
+
+    // The "pong" received by the pinging element and its sender.
+    let ponged = Arc::new(Mutex::new(None));
+    let pinger = Bastion::children(|children| {
+        let ponged = ponged.clone();
+        let ponger = ponger.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let ponged = ponged.clone();
+            let ponger = ponger.clone();
+            async move {
+                ctx.tell(&ponger.addr(), "ping").unwrap();
+                let msg = ctx.recv().await?;
+                let sender = msg.sender().map(|sender| sender.id().clone());
+                let pong = msg.msg().downcast_ref::<&'static str>().copied();
+                *ponged.lock().unwrap() = Some((pong, sender));
+
+                loop {
+                    ctx.recv().await?;
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    wait_for(|| ponged.lock().unwrap().is_some());
+    let (pong, sender) = ponged.lock().unwrap().take().unwrap();
+    assert_eq!(pong, Some("pong"));
+    assert_eq!(sender.as_ref(), Some(ponger.id()));
+    assert_eq!(*identified.lock().unwrap(), vec![(true, true)]);
+    assert_ne!(pinger.elems()[0].id(), ponger.id());
+}
+
+#[test]
+fn anonymous_senders() {
+    init_start();
+
+    let identified = Arc::new(Mutex::new(Vec::new()));
+    let ponger = ponging(&identified);
+
+    // Messages sent from outside of any element have no sender...
+    ponger.tell_anonymously("ping").unwrap();
+    wait_for(|| identified.lock().unwrap().len() == 1);
+    assert_eq!(*identified.lock().unwrap(), vec![(false, false)]);
+
+    // ...while answers are signed by the element answering.
+    let answer = ponger.ask_anonymously("ping").unwrap();
+    let (msg, sign) = run!(answer).unwrap().extract();
+    assert_eq!(msg.downcast::<&'static str>().unwrap(), "pong");
+    assert_eq!(sign.origin().map(ChildRef::id), Some(ponger.id()));
+}
@@ -0,0 +1,170 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Once};
+use std::time::Duration;
+
+static START: Once = Once::new();
+
+// The dead letters received, with their recipient and reason.
+static LETTERS: Mutex<Vec<(Option<BastionId>, DeadLetterReason)>> = Mutex::new(Vec::new());
+
+fn init_start() {
+    START.call_once(|| {
+        let config = Config::new().with_dead_letter_handler(|letter: DeadLetter| {
+            let recipient = letter.recipient().cloned();
+            LETTERS.lock().unwrap().push((recipient, letter.reason()));
+        });
+        Bastion::init_with(config);
+        Bastion::start();
+    });
+}
+
+fn reasons_for(id: &BastionId) -> Vec<DeadLetterReason> {
+    LETTERS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(recipient, _)| recipient.as_ref() == Some(id))
+        .map(|(_, reason)| *reason)
+        .collect()
+}
+
+#[derive(Default)]
+struct Counts {
+    started: AtomicUsize,
+    stalled: AtomicUsize,
+    handled: AtomicUsize,
+}
+
+// Never finishes handling the `0`s and the `&str`s it is told
+// (taking the latter out of their message), and counts the other
+// `u64`s.
+struct Staller(Arc<Counts>);
+
+impl Actor for Staller {
+    async fn started(&mut self, _: &BastionContext) -> Result<(), ActorError> {
+        self.0.started.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn handle(
+        &mut self,
+        _: &BastionContext,
+        msg: SignedMessage,
+    ) -> Result<Reply, ActorError> {
+        let n = msg.msg().downcast_ref::<u64>().copied();
+        match n {
+            Some(0) => {
+                self.0.stalled.fetch_add(1, Ordering::SeqCst);
+                std::future::pending::<()>().await;
+            }
+            Some(_) => {
+                self.0.handled.fetch_add(1, Ordering::SeqCst);
+            }
+            None => {
+                let (msg, _) = msg.extract();
+                if let Ok(_taken) = msg.downcast::<&'static str>() {
+                    self.0.stalled.fetch_add(1, Ordering::SeqCst);
+                    std::future::pending::<()>().await;
+                }
+            }
+        }
+
+        Ok(Reply::none())
+    }
+}
+
+fn staller(counts: Arc<Counts>, on_timeout: MessageTimeoutAction) -> ChildrenRef {
+    Bastion::children(move |children| {
+        let counts = counts.clone();
+        children
+            .with_actor(move || Staller(counts.clone()))
+            .with_message_timeout(Duration::from_millis(50), on_timeout)
+    })
+    .unwrap()
+}
+
+fn is_timed_out(reason: &DeadLetterReason) -> bool {
+    match reason {
+        DeadLetterReason::TimedOut {
+            type_name,
+            correlation_id: None,
+            elapsed,
+        } => {
+            (type_name.contains("u64") || type_name.contains("str"))
+                && *elapsed >= Duration::from_millis(50)
+        }
+        _ => false,
+    }
+}
+
+#[test]
+fn skipped() {
+    init_start();
+
+    let counts = Arc::new(Counts::default());
+    let children = staller(counts.clone(), MessageTimeoutAction::SkipAndDeadLetter);
+    let child = &children.elems()[0];
+    child.tell_anonymously(0u64).unwrap();
+    child.tell_anonymously(1u64).unwrap();
+
+    // The element goes on with the next message, without
+    // restarting.
+    wait_for(|| counts.handled.load(Ordering::SeqCst) == 1);
+    wait_for(|| reasons_for(child.id()).len() == 1);
+    assert!(is_timed_out(&reasons_for(child.id())[0]));
+    assert_eq!(counts.started.load(Ordering::SeqCst), 1);
+    assert_eq!(counts.stalled.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn faulted() {
+    init_start();
+
+    let counts = Arc::new(Counts::default());
+    let children = staller(counts.clone(), MessageTimeoutAction::FaultElement);
+    let child = &children.elems()[0];
+    let id = child.id().clone();
+    child.tell_anonymously(0u64).unwrap();
+
+    // The element is restarted once the message is skipped.
+    wait_for(|| counts.started.load(Ordering::SeqCst) == 2);
+    let reasons = reasons_for(&id);
+    assert_eq!(reasons.len(), 1);
+    assert!(is_timed_out(&reasons[0]));
+
+    child.tell_anonymously(1u64).unwrap();
+    wait_for(|| counts.handled.load(Ordering::SeqCst) == 1);
+}
+
+#[test]
+fn retried() {
+    init_start();
+
+    let counts = Arc::new(Counts::default());
+    let children = staller(counts.clone(), MessageTimeoutAction::Retry { attempts: 2 });
+    let child = &children.elems()[0];
+
+    // The messages are handled again whether they were
+    // broadcasted or told...
+    children.broadcast(0u64).unwrap();
+    wait_for(|| reasons_for(child.id()).len() == 1);
+    assert_eq!(counts.stalled.load(Ordering::SeqCst), 3);
+
+    child.tell_anonymously(0u64).unwrap();
+    wait_for(|| reasons_for(child.id()).len() == 2);
+    assert_eq!(counts.stalled.load(Ordering::SeqCst), 6);
+
+    // ...unless the actor took them out of their message.
+    child.tell_anonymously("stall").unwrap();
+    wait_for(|| reasons_for(child.id()).len() == 3);
+    assert_eq!(counts.stalled.load(Ordering::SeqCst), 7);
+    assert!(reasons_for(child.id()).iter().all(is_timed_out));
+
+    child.tell_anonymously(1u64).unwrap();
+    wait_for(|| counts.handled.load(Ordering::SeqCst) == 1);
+    assert_eq!(counts.started.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+
+const ELEMS: usize = 2;
+
+#[test]
+fn group_stats() {
+    Bastion::init();
+
+    let children = Bastion::children(|children| {
+        children
+            .with_redundancy(ELEMS)
+            .with_exec(|ctx: BastionContext| async move {
+                let jobs_ok = ctx.metrics().counter("jobs_ok")?;
+                let in_flight = ctx.metrics().gauge("in_flight")?;
+                let duration = ctx.metrics().histogram("job_duration_seconds")?;
+
+                // Registering a metric fails without poisoning the
+                // group's metrics.
+                assert_eq!(
+                    ctx.metrics().gauge("jobs_ok").unwrap_err(),
+                    MetricError::Registered {
+                        name: "jobs_ok".to_string(),
+                        kind: "counter",
+                    }
+                );
+                assert_eq!(
+                    ctx.metrics().counter("jobs ok").unwrap_err(),
+                    MetricError::InvalidName("jobs ok".to_string())
+                );
+
+                loop {
+                    msg! { ctx.recv().await?,
+                        ref _job: &'static str => {
+                            in_flight.inc();
+                            jobs_ok.inc();
+                            duration.observe(0.2);
+                            in_flight.dec();
+                        };
+                        _: _ => ();
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    Bastion::start();
+
+    children.broadcast("job").unwrap();
+    children.broadcast("job").unwrap();
+    wait_for(|| children.stats().counter("jobs_ok") == 2 * ELEMS as u64);
+
+    let stats = children.stats();
+    assert_eq!(stats.group(), children.id());
+    assert_eq!(stats.elems().len(), ELEMS);
+    assert_eq!(stats.gauge("in_flight"), 0);
+    let duration = stats.histogram("job_duration_seconds").unwrap();
+    assert_eq!(duration.count(), 2 * ELEMS as u64);
+    assert!((duration.sum() - 0.4 * ELEMS as f64).abs() < 1e-9);
+    assert!(stats.histogram("unknown").is_none());
+
+    let exposed = stats.to_prometheus();
+    assert!(exposed.contains("# TYPE jobs_ok counter\n"));
+    assert!(exposed.contains("# TYPE job_duration_seconds histogram\n"));
+    let elem = &children.elems()[0];
+    assert!(exposed.contains(&format!(
+        "jobs_ok{{group=\"{}\",element=\"{}\"}} 2\n",
+        children.id(),
+        elem.id()
+    )));
+
+    // The metrics of a pruned element are kept until they are
+    // reset...
+    children.prune_elem(elem.id()).unwrap();
+    wait_for(|| {
+        children
+            .stats()
+            .elems()
+            .iter()
+            .any(|stats| stats.id() == elem.id() && stats.is_terminal())
+    });
+    let stats = children.stats();
+    assert_eq!(stats.elems().len(), ELEMS);
+    assert_eq!(stats.counter("jobs_ok"), 2 * ELEMS as u64);
+    assert_eq!(children.stats(), stats);
+
+    // ...which drops them and sets the other counters back to zero.
+    children.reset_stats();
+    let stats = children.stats();
+    assert_eq!(stats.elems().len(), ELEMS - 1);
+    assert_eq!(stats.counter("jobs_ok"), 0);
+    assert_eq!(stats.histogram("job_duration_seconds").unwrap().count(), 0);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
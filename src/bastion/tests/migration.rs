@@ -0,0 +1,91 @@
+mod common;
+
+use bastion::children_ref::MigrationError;
+use bastion::prelude::*;
+use common::wait_for;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const MESSAGES: u64 = 1_000;
+
+#[test]
+fn migrate_elem_keeps_every_message() {
+    Bastion::init();
+    Bastion::start();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let recorded = received.clone();
+    let children = Bastion::children(move |children| {
+        let recorded = recorded.clone();
+        children
+            .with_redundancy(2)
+            .with_exec(move |ctx: BastionContext| {
+                let recorded = recorded.clone();
+                async move {
+                    loop {
+                        msg! { ctx.recv().await?,
+                            n: u64 => {
+                                recorded.lock().unwrap().push(n);
+                                // The source element's mailbox fills
+                                // up while it is busy.
+                                if n == 0 {
+                                    ctx.sleep(Duration::from_millis(50)).await;
+                                }
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    let elems = children.elems();
+    let source = elems[0].clone();
+    let target = elems[1].clone();
+
+    assert_eq!(
+        run!(children.migrate_elem(source.id(), source.id())),
+        Err(MigrationError::SameElement)
+    );
+
+    // The messages given back once the source element's mailbox
+    // got closed are sent to the target element once the migration
+    // is over, after the ones it transferred.
+    let sender = {
+        let source = source.clone();
+        thread::spawn(move || {
+            let mut given_back = Vec::new();
+            for n in 0..MESSAGES {
+                if let Err(err) = source.tell_anonymously(n) {
+                    given_back.push(err.into_inner_msg());
+                }
+            }
+
+            given_back
+        })
+    };
+
+    thread::sleep(Duration::from_millis(1));
+    let report = run!(children.migrate_elem(source.id(), target.id()))
+        .expect("Couldn't migrate the element.");
+    assert!(report.transferred() > 0);
+    assert_eq!(report.dead_lettered(), 0);
+    assert_eq!(report.dropped(), 0);
+
+    for n in sender.join().unwrap() {
+        target.tell_anonymously(n).unwrap();
+    }
+
+    wait_for(|| received.lock().unwrap().len() >= MESSAGES as usize);
+    // Leaves some time for duplicated messages to show up.
+    thread::sleep(Duration::from_millis(50));
+
+    // The messages were received in the order they were sent, first
+    // by the source element and then by the target element.
+    assert_eq!(*received.lock().unwrap(), (0..MESSAGES).collect::<Vec<_>>());
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::sync::{Arc, Mutex};
+
+// What an element saw of a message it didn't match: its type
+// name, whether it is a `u64`, a copy of it if it is one, and
+// whether it could still be downcasted afterwards.
+type Seen = Arc<Mutex<Vec<(&'static str, bool, Option<u64>, bool)>>>;
+
+#[test]
+fn inspected_without_consuming() {
+    init_start();
+
+    let seen = Seen::default();
+    let recorded = seen.clone();
+    let children = Bastion::children(move |children| {
+        let recorded = recorded.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let recorded = recorded.clone();
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        unknown: _ => {
+                            let type_name = unknown.type_name();
+                            let is = unknown.is::<u64>();
+                            let n = unknown.downcast_ref::<u64>().copied();
+                            let downcasted = unknown.downcast_typed::<u64>().is_ok();
+
+                            recorded.lock().unwrap().push((type_name, is, n, downcasted));
+                        };
+                    }
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    let elem = &children.elems()[0];
+    elem.tell_anonymously(1u64).unwrap();
+    elem.tell_anonymously("A message of another type.").unwrap();
+    // The asked message isn't answered, as the fallback case can't.
+    let _answer = elem.ask_anonymously(2u64).unwrap();
+    children.broadcast(3u64).unwrap();
+
+    wait_for(|| seen.lock().unwrap().len() == 4);
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen[0], ("u64", true, Some(1), true));
+    assert_eq!(seen[1], ("&str", false, None, false));
+    assert_eq!(seen[2], ("u64", true, Some(2), true));
+    assert_eq!(seen[3], ("u64", true, Some(3), true));
+}
@@ -0,0 +1,128 @@
+// Cancels the groups' tasks using `ChildrenRef::cancel_task`, so it
+// only runs with the `testing` feature (e.g. with `cargo test
+// --features testing --test orphans`, as the CI does).
+#![cfg(feature = "testing")]
+
+mod common;
+
+use bastion::prelude::*;
+use bastion_executor::pool;
+use common::wait_for_within;
+use std::env;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// The seed picking when the groups' tasks are cancelled, which is
+// random unless it is set (e.g. to replay a failed run).
+const SEED: &str = "BASTION_ORPHANS_SEED";
+
+// A xorshift generator, picking when the groups' tasks are
+// cancelled.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self, bound: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % bound
+    }
+}
+
+fn group(handled: Arc<AtomicUsize>) -> ChildrenRef {
+    Bastion::children(move |children| {
+        let handled = handled.clone();
+        children
+            .with_redundancy(4)
+            .with_exec(move |ctx: BastionContext| {
+                let handled = handled.clone();
+                async move {
+                    loop {
+                        msg! { ctx.recv().await?,
+                            n: u64 => {
+                                // Lets the tasks be cancelled while
+                                // the elements handle a message.
+                                ctx.sleep(Duration::from_millis(n % 3)).await;
+                                handled.fetch_add(1, Ordering::SeqCst);
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .unwrap()
+}
+
+#[test]
+fn no_leaked_elements() {
+    Bastion::init();
+    Bastion::start();
+    // Lets the system's own groups launch their elements.
+    thread::sleep(Duration::from_millis(100));
+    let live = pool::get().live_procs();
+
+    let handled = Arc::new(AtomicUsize::new(0));
+    let groups = (0..8).map(|_| group(handled.clone())).collect::<Vec<_>>();
+    wait_for_within(Duration::from_secs(5), || {
+        pool::get().live_procs() >= live + 8 * 5
+    });
+
+    // Keeps sending messages to every element, even once their
+    // group's task was cancelled.
+    let sending = Arc::new(AtomicBool::new(true));
+    let traffic = {
+        let sending = sending.clone();
+        let elems = groups
+            .iter()
+            .flat_map(|group| group.elems().to_vec())
+            .collect::<Vec<_>>();
+        thread::spawn(move || {
+            let mut n = 0u64;
+            while sending.load(Ordering::SeqCst) {
+                for elem in &elems {
+                    elem.tell_anonymously(n).ok();
+                    n += 1;
+                }
+            }
+        })
+    };
+
+    let seed = match env::var(SEED) {
+        Ok(seed) => seed.parse().unwrap(),
+        Err(_) => u64::from(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .subsec_nanos(),
+        ),
+    };
+    // Shown if the test fails.
+    println!("{}={}", SEED, seed);
+    let mut rng = Rng(seed | 1);
+    for group in &groups {
+        thread::sleep(Duration::from_millis(rng.next(50)));
+        group.cancel_task().unwrap();
+    }
+
+    thread::sleep(Duration::from_millis(100));
+    sending.store(false, Ordering::SeqCst);
+    traffic.join().unwrap();
+    assert!(handled.load(Ordering::SeqCst) > 0);
+
+    // The elements stop on their own or are cancelled by the
+    // supervisor, along with the groups' procs.
+    wait_for_within(Duration::from_secs(5), || pool::get().live_procs() <= live);
+
+    // The supervisor keeps supervising the other groups.
+    let handled = Arc::new(AtomicUsize::new(0));
+    let group = group(handled.clone());
+    for elem in group.elems() {
+        elem.tell_anonymously(0u64).unwrap();
+    }
+    wait_for_within(Duration::from_secs(5), || {
+        handled.load(Ordering::SeqCst) == 4
+    });
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+type Log = Arc<Mutex<Vec<String>>>;
+
+// Installs a hook recording the messages of the panics it's called
+// for.
+fn recording(log: &Log) {
+    let log = log.clone();
+    panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let msg = match payload.downcast_ref::<&'static str>() {
+            Some(msg) => msg.to_string(),
+            None => payload
+                .downcast_ref::<String>()
+                .cloned()
+                .unwrap_or_default(),
+        };
+        log.lock().unwrap().push(msg);
+    }));
+}
+
+fn panic_thread(msg: &'static str) {
+    thread::spawn(move || panic!("{}", msg)).join().unwrap_err();
+}
+
+#[test]
+fn chained_with_user_hook() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    recording(&log);
+
+    Bastion::init();
+    Bastion::start();
+
+    let starts = Arc::new(AtomicUsize::new(0));
+    let counter = starts.clone();
+    Bastion::children(|children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let counter = counter.clone();
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+
+                loop {
+                    msg! { ctx.recv().await?,
+                        ref _msg: &'static str => {
+                            panic!("elem");
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .unwrap();
+    wait_for(|| starts.load(Ordering::SeqCst) == 1);
+
+    // The user's hook is still called for the panics of elements,
+    // which are restarted...
+    Bastion::broadcast("panic").unwrap();
+    wait_for(|| starts.load(Ordering::SeqCst) == 2);
+    assert_eq!(*log.lock().unwrap(), vec!["elem"]);
+
+    // ...and the panics of other threads reach it untouched.
+    panic_thread("thread");
+    assert_eq!(*log.lock().unwrap(), vec!["elem", "thread"]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+
+    // The user's hook is restored once the system stopped.
+    log.lock().unwrap().clear();
+    panic_thread("stopped");
+    assert_eq!(*log.lock().unwrap(), vec!["stopped"]);
+
+    // A hook installed in the meantime is kept...
+    let replaced = Arc::new(Mutex::new(Vec::new()));
+    Bastion::init();
+    recording(&replaced);
+    Bastion::start();
+    Bastion::stop();
+    Bastion::block_until_stopped();
+    panic_thread("replaced");
+    assert_eq!(*replaced.lock().unwrap(), vec!["replaced"]);
+
+    // ...and chained the next time the system is initialized.
+    Bastion::init();
+    Bastion::start();
+    panic_thread("chained");
+    assert_eq!(*replaced.lock().unwrap(), vec!["replaced", "chained"]);
+    Bastion::stop();
+    Bastion::block_until_stopped();
+    assert_eq!(*log.lock().unwrap(), vec!["stopped"]);
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+#[test]
+fn exclusive_chains_other_threads() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let recorded = log.clone();
+    panic::set_hook(Box::new(move |info| {
+        let msg = info.payload().downcast_ref::<&'static str>().copied();
+        recorded.lock().unwrap().push(msg.unwrap_or_default());
+    }));
+
+    let config = Config::new().panic_hook_order(PanicHookOrder::Exclusive);
+    Bastion::init_with(config);
+    Bastion::start();
+
+    let starts = Arc::new(AtomicUsize::new(0));
+    let counter = starts.clone();
+    Bastion::children(|children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let counter = counter.clone();
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+
+                loop {
+                    msg! { ctx.recv().await?,
+                        ref _msg: &'static str => {
+                            panic!("elem");
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .unwrap();
+    wait_for(|| starts.load(Ordering::SeqCst) == 1);
+
+    // The user's hook isn't called for the panics of elements...
+    Bastion::broadcast("panic").unwrap();
+    wait_for(|| starts.load(Ordering::SeqCst) == 2);
+    assert!(log.lock().unwrap().is_empty());
+
+    // ...but still is for the panics of plain threads.
+    thread::spawn(|| panic!("thread")).join().unwrap_err();
+    assert_eq!(*log.lock().unwrap(), vec!["thread"]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use bastion::children_ref::GroupState;
+use bastion::prelude::*;
+use common::wait_for;
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[derive(Debug)]
+struct CustomPayload;
+
+// Runs a children group whose element panics by calling
+// `do_panic` the first time it is started, and checks that it
+// gets restarted.
+fn restarted_after(do_panic: fn()) {
+    let starts = Arc::new(AtomicUsize::new(0));
+    let counter = starts.clone();
+
+    let children = Bastion::children(move |children| {
+        let counter = counter.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let counter = counter.clone();
+            async move {
+                if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                    do_panic();
+                }
+
+                loop {
+                    ctx.recv().await?;
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    wait_for(|| starts.load(Ordering::SeqCst) == 2);
+    wait_for(|| children.state() == GroupState::Running);
+}
+
+#[test]
+fn panic_payloads() {
+    Bastion::init();
+    Bastion::start();
+
+    restarted_after(|| panic!("Element panicked."));
+    restarted_after(|| panic::panic_any(CustomPayload));
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use bastion::prelude::*;
+use common::init_start;
+use futures::prelude::*;
+use std::sync::{Arc, Mutex};
+
+// Makes the element panic with a `String` if it carries `true`,
+// or with a `&'static str` otherwise.
+#[derive(Debug)]
+struct Panic(bool);
+
+// Makes a group's element panic and returns the report sent along
+// with its fault, and the callbacks called (in order).
+fn report(formatted: bool) -> (Option<PanicReport>, Vec<String>) {
+    let mut events = Bastion::events();
+    let called = Arc::new(Mutex::new(Vec::new()));
+    let after_panic = called.clone();
+    let before_restart = called.clone();
+    let callbacks = Callbacks::new()
+        .with_after_panic(move |panic: &PanicReport| {
+            let message = panic.message().unwrap_or_default().to_string();
+            after_panic.lock().unwrap().push(message);
+        })
+        .with_before_restart(move || before_restart.lock().unwrap().push("restart".to_string()));
+
+    let children = Bastion::children(|children| {
+        children
+            .with_callbacks(callbacks)
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        msg: Panic => {
+                            if msg.0 {
+                                panic!("Panicking as asked ({}).", 42);
+                            }
+
+                            panic!("Panicking as asked.");
+                        };
+                        _: _ => ();
+                    }
+                }
+            })
+    })
+    .unwrap();
+    children.elems()[0]
+        .tell_anonymously(Panic(formatted))
+        .unwrap();
+
+    loop {
+        match run!(events.next()) {
+            Some(Event::ElemsFaulted { group, panic, .. }) if &group == children.id() => {
+                let called = called.lock().unwrap().clone();
+                return (panic, called);
+            }
+            Some(_) => (),
+            None => panic!("The events stream ended."),
+        }
+    }
+}
+
+#[test]
+fn str_payload() {
+    init_start();
+
+    let (panic, called) = report(false);
+    let panic = panic.unwrap();
+    assert_eq!(panic.message(), Some("Panicking as asked."));
+    assert!(panic.location().unwrap().contains("panic_report.rs"));
+    assert_eq!(called, vec!["Panicking as asked.", "restart"]);
+}
+
+#[test]
+fn string_payload() {
+    init_start();
+
+    let (panic, called) = report(true);
+    let panic = panic.unwrap();
+    assert_eq!(panic.message(), Some("Panicking as asked (42)."));
+    assert_eq!(
+        panic.to_string(),
+        format!(
+            "panicked at {}: Panicking as asked (42).",
+            panic.location().unwrap()
+        )
+    );
+    assert_eq!(called, vec!["Panicking as asked (42).", "restart"]);
+}
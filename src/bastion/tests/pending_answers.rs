@@ -0,0 +1,161 @@
+mod common;
+
+use bastion::errors::SendErrorKind;
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const MAX_PENDING: usize = 100;
+
+// A group whose element answers the questions it is asked with their
+// double, but only once `answering` is set.
+fn slow(answering: Arc<AtomicBool>) -> ChildrenRef {
+    Bastion::children(move |children| {
+        let answering = answering.clone();
+        children
+            .with_max_pending_answers(MAX_PENDING)
+            .with_exec(move |ctx: BastionContext| {
+                let answering = answering.clone();
+                async move {
+                    loop {
+                        while !answering.load(Ordering::SeqCst) {
+                            ctx.sleep(Duration::from_millis(1)).await;
+                        }
+
+                        msg! { ctx.recv().await?,
+                            n: u64 =!> {
+                                answer!(ctx, n * 2).ok();
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .unwrap()
+}
+
+#[test]
+fn too_many_pending() {
+    init_start();
+
+    let answering = Arc::new(AtomicBool::new(false));
+    let children = slow(answering.clone());
+    let elem = &children.elems()[0];
+
+    let mut answers = Vec::new();
+    let mut rejected = 0;
+    for n in 0..10_000u64 {
+        match elem.ask_anonymously(n) {
+            Ok(answer) => answers.push(answer),
+            Err(err) => {
+                assert_eq!(err.kind(), SendErrorKind::TooManyPending);
+                assert_eq!(err.into_inner_msg(), n);
+                rejected += 1;
+            }
+        }
+    }
+
+    assert_eq!(answers.len(), MAX_PENDING);
+    assert_eq!(rejected, 10_000 - MAX_PENDING);
+    let stats = elem.stats();
+    assert_eq!(stats.pending_answers(), MAX_PENDING);
+    assert!(stats.mailbox_len() <= MAX_PENDING);
+
+    // The questions that were accepted are all answered once the
+    // element catches up...
+    answering.store(true, Ordering::SeqCst);
+    for (n, answer) in answers.into_iter().enumerate() {
+        msg! { run!(answer).unwrap(),
+            answer: u64 => assert_eq!(answer, n as u64 * 2);
+            _: _ => panic!("Unexpected answer.");
+        }
+    }
+    wait_for(|| elem.stats().pending_answers() == 0);
+
+    // ...and the element can be asked again.
+    let answer = elem.ask_anonymously(21u64).unwrap();
+    msg! { run!(answer).unwrap(),
+        answer: u64 => assert_eq!(answer, 42);
+        _: _ => panic!("Unexpected answer.");
+    }
+    assert_eq!(children.stats().pending_answers(), 0);
+}
+
+#[test]
+fn ask_async() {
+    init_start();
+
+    let answering = Arc::new(AtomicBool::new(false));
+    let children = slow(answering.clone());
+    let elem = children.elems()[0].clone();
+
+    let asking = thread::spawn(move || {
+        run!(async {
+            let mut answers = Vec::new();
+            for n in 0..(2 * MAX_PENDING as u64) {
+                answers.push(elem.ask_async(n).await.unwrap());
+            }
+
+            answers
+        })
+    });
+
+    // The sender waits for the element to answer...
+    wait_for(|| children.stats().pending_answers() == MAX_PENDING);
+    thread::sleep(Duration::from_millis(50));
+    assert!(!asking.is_finished());
+
+    // ...and goes on once it does.
+    answering.store(true, Ordering::SeqCst);
+    let answers = asking.join().unwrap();
+    assert_eq!(answers.len(), 2 * MAX_PENDING);
+    for (n, answer) in answers.into_iter().enumerate() {
+        msg! { run!(answer).unwrap(),
+            answer: u64 => assert_eq!(answer, n as u64 * 2);
+            _: _ => panic!("Unexpected answer.");
+        }
+    }
+}
+
+#[test]
+fn asked_from_context() {
+    init_start();
+
+    let answering = Arc::new(AtomicBool::new(false));
+    let children = slow(answering.clone());
+    let addr = children.elems()[0].addr();
+
+    // An element asking the questions is bound by the same limit.
+    let rejected = Arc::new(AtomicBool::new(false));
+    let recorded = rejected.clone();
+    Bastion::spawn(move |ctx: BastionContext| {
+        let addr = addr.clone();
+        let rejected = recorded.clone();
+        async move {
+            let mut answers = Vec::new();
+            for n in 0..MAX_PENDING as u64 {
+                answers.push(ctx.ask(&addr, n).unwrap());
+            }
+
+            let err = ctx.ask(&addr, 0u64).unwrap_err();
+            assert_eq!(err.kind(), SendErrorKind::TooManyPending);
+            rejected.store(true, Ordering::SeqCst);
+
+            for answer in answers {
+                answer.await?;
+            }
+
+            Ok(())
+        }
+    })
+    .unwrap();
+
+    wait_for(|| rejected.load(Ordering::SeqCst));
+    assert_eq!(children.stats().pending_answers(), MAX_PENDING);
+    answering.store(true, Ordering::SeqCst);
+    wait_for(|| children.stats().pending_answers() == 0);
+}
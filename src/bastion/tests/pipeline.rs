@@ -0,0 +1,137 @@
+#![cfg(feature = "patterns")]
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const ITEMS: u64 = 100;
+
+fn forwarding_stage(children: Children, f: fn(u64) -> u64) -> Children {
+    children.with_exec(move |ctx: BastionContext| async move {
+        loop {
+            msg! { ctx.recv().await?,
+                n: u64 => {
+                    ctx.next_stage().unwrap().tell_one(f(n)).unwrap();
+                };
+                ref msg: &'static str => {
+                    if *msg == "panic" {
+                        panic!("Stage panicked.");
+                    }
+                };
+                _: _ => ();
+            }
+        }
+    })
+}
+
+#[test]
+fn items_flow_through_stages_in_order() {
+    init_start();
+
+    let collected = Arc::new(Mutex::new(Vec::new()));
+    let sink = collected.clone();
+
+    let pipeline = Pipeline::new()
+        .stage("parse", |children| forwarding_stage(children, |n| n * 10))
+        .stage("enrich", |children| forwarding_stage(children, |n| n + 1))
+        .stage("sink", move |children| {
+            children.with_exec(move |ctx: BastionContext| {
+                let sink = sink.clone();
+                async move {
+                    assert!(ctx.next_stage().is_none());
+                    loop {
+                        msg! { ctx.recv().await?,
+                            n: u64 => {
+                                sink.lock().unwrap().push(n);
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+        })
+        .build()
+        .unwrap();
+
+    assert_eq!(pipeline.stages().len(), 3);
+    assert_eq!(
+        pipeline.stages()[2].id(),
+        pipeline.stage("sink").unwrap().id()
+    );
+
+    for n in 0..ITEMS / 2 {
+        pipeline.push(n).unwrap();
+    }
+    wait_for(|| collected.lock().unwrap().len() >= ITEMS as usize / 2);
+
+    // Restarting a stage mustn't break the pipeline.
+    pipeline
+        .stage("enrich")
+        .unwrap()
+        .broadcast("panic")
+        .unwrap();
+    thread::sleep(Duration::from_millis(100));
+
+    for n in ITEMS / 2..ITEMS {
+        pipeline.push(n).unwrap();
+    }
+    wait_for(|| collected.lock().unwrap().len() >= ITEMS as usize);
+
+    let expected: Vec<u64> = (0..ITEMS).map(|n| n * 10 + 1).collect();
+    assert_eq!(*collected.lock().unwrap(), expected);
+
+    // The stages created before one failed to be are stopped.
+    Bastion::children(|children| children.with_name("taken")).unwrap();
+    let built = Pipeline::new()
+        .stage("taken", |children| children.with_name("taken"))
+        .stage("leaked", |children| children.with_name("leaked"))
+        .build();
+    assert_eq!(built.unwrap_err(), BuilderError::NameTaken);
+    wait_for(|| Bastion::children_of("leaked").is_none());
+}
+
+#[test]
+fn items_are_pushed_once() {
+    init_start();
+
+    let collected = Arc::new(Mutex::new(Vec::new()));
+    let sink = collected.clone();
+
+    let pipeline = Pipeline::new()
+        .stage("double", |children| {
+            forwarding_stage(children.with_redundancy(2), |n| n * 2)
+        })
+        .stage("collect", move |children| {
+            children.with_exec(move |ctx: BastionContext| {
+                let sink = sink.clone();
+                async move {
+                    loop {
+                        msg! { ctx.recv().await?,
+                            n: u64 => {
+                                sink.lock().unwrap().push(n);
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+        })
+        .build()
+        .unwrap();
+
+    for n in 0..ITEMS {
+        pipeline.push(n).unwrap();
+    }
+    wait_for(|| collected.lock().unwrap().len() >= ITEMS as usize);
+    // The items which would have been processed twice.
+    thread::sleep(Duration::from_millis(100));
+
+    // Each item was processed by one of the first stage's elements.
+    let mut collected = collected.lock().unwrap().clone();
+    collected.sort_unstable();
+    let expected: Vec<u64> = (0..ITEMS).map(|n| n * 2).collect();
+    assert_eq!(collected, expected);
+}
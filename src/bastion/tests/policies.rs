@@ -0,0 +1,140 @@
+#![cfg(feature = "supervision")]
+mod common;
+
+use bastion::children_ref::GroupPolicies;
+use bastion::prelude::*;
+use common::wait_for;
+use std::time::Duration;
+
+fn idle(children: Children) -> Children {
+    children.with_exec(|ctx: BastionContext| async move {
+        loop {
+            ctx.recv().await?;
+        }
+    })
+}
+
+fn resolved_to(
+    children: &ChildrenRef,
+    restart_strategy: &RestartStrategy,
+    restart_window: Option<(usize, Duration)>,
+    pre_start_watermark: usize,
+) {
+    wait_for(|| {
+        let policies = children.policies();
+        policies.restart_strategy() == restart_strategy
+            && policies.restart_window() == restart_window
+            && policies.pre_start_watermark() == pre_start_watermark
+    });
+}
+
+#[test]
+fn inherited_policies() {
+    Bastion::init();
+
+    let top_strategy = RestartStrategy::default().with_restart_policy(RestartPolicy::Tries(3));
+    let nested_strategy = RestartStrategy::default().with_restart_policy(RestartPolicy::Never);
+    let window = Some((3, Duration::from_secs(1)));
+
+    let mut groups = Vec::new();
+    let strategy = top_strategy.clone();
+    let nested = nested_strategy.clone();
+    Bastion::supervisor(|top| {
+        let top = top
+            .with_restart_strategy(strategy)
+            .with_default_pre_start_watermark(10);
+
+        groups.push(top.children_ref(idle).unwrap());
+        groups.push(
+            top.children_ref(|children| idle(children).with_pre_start_watermark(20))
+                .unwrap(),
+        );
+
+        // Overrides the restart window only...
+        let top = top.supervisor(|first| {
+            groups.push(first.children_ref(idle).unwrap());
+            first.with_restart_window(3, Duration::from_secs(1))
+        });
+
+        // ...and the restart strategy and the default watermark.
+        top.supervisor(|second| {
+            let second = second
+                .with_restart_strategy(nested)
+                .with_default_pre_start_watermark(30);
+
+            groups.push(second.children_ref(idle).unwrap());
+            groups.push(
+                second
+                    .children_ref(|children| idle(children).with_pre_start_watermark(5))
+                    .unwrap(),
+            );
+            second
+        })
+    })
+    .unwrap();
+
+    // The groups' mailboxes are inherited the same way.
+    let mut mailboxes = Vec::new();
+    Bastion::supervisor(|top| {
+        let top = top.with_default_mailbox(8, OverflowPolicy::DropOldest);
+
+        mailboxes.push(top.children_ref(idle).unwrap());
+        mailboxes.push(
+            top.children_ref(|children| idle(children).with_mailbox_capacity(4))
+                .unwrap(),
+        );
+        mailboxes.push(
+            top.children_ref(|children| idle(children).with_overflow_policy(OverflowPolicy::Fail))
+                .unwrap(),
+        );
+
+        // Inherits the default mailbox...
+        let top = top.supervisor(|first| {
+            mailboxes.push(first.children_ref(idle).unwrap());
+            first
+        });
+
+        // ...or overrides it.
+        top.supervisor(|second| {
+            let second = second.with_default_mailbox(16, OverflowPolicy::Block);
+            mailboxes.push(second.children_ref(idle).unwrap());
+            second
+        })
+    })
+    .unwrap();
+
+    let plain = Bastion::children(idle).unwrap();
+
+    Bastion::start();
+
+    resolved_to(&groups[0], &top_strategy, None, 10);
+    resolved_to(&groups[1], &top_strategy, None, 20);
+    resolved_to(&groups[2], &top_strategy, window, 10);
+    resolved_to(&groups[3], &nested_strategy, None, 30);
+    resolved_to(&groups[4], &nested_strategy, None, 5);
+    let defaults = GroupPolicies::default();
+    resolved_to(
+        &plain,
+        defaults.restart_strategy(),
+        None,
+        defaults.pre_start_watermark(),
+    );
+
+    let expected = [
+        (Some(8), OverflowPolicy::DropOldest),
+        (Some(4), OverflowPolicy::DropOldest),
+        (Some(8), OverflowPolicy::Fail),
+        (Some(8), OverflowPolicy::DropOldest),
+        (Some(16), OverflowPolicy::Block),
+        (None, OverflowPolicy::default()),
+    ];
+    for (children, (capacity, policy)) in mailboxes.iter().chain(Some(&plain)).zip(&expected) {
+        wait_for(|| {
+            let policies = children.policies();
+            policies.mailbox_capacity() == *capacity && policies.overflow_policy() == *policy
+        });
+    }
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
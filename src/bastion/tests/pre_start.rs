@@ -0,0 +1,69 @@
+mod common;
+
+use bastion::children_ref::{GroupState, SendOutcome};
+use bastion::prelude::*;
+use common::wait_for;
+use futures::{FutureExt, StreamExt};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+const WATERMARK: usize = 3;
+const BUFFERED: usize = 10;
+
+#[test]
+fn buffered_until_started() {
+    Bastion::init();
+
+    let mut events = Bastion::events();
+    let received = Arc::new(AtomicUsize::new(0));
+    let counter = received.clone();
+
+    let children = Bastion::children(move |children| {
+        let counter = counter.clone();
+        children
+            .with_pre_start_watermark(WATERMARK)
+            .with_exec(move |ctx: BastionContext| {
+                let counter = counter.clone();
+                async move {
+                    loop {
+                        msg! { ctx.recv().await?,
+                            ref _msg: usize => {
+                                counter.fetch_add(1, Ordering::SeqCst);
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    for n in 0..BUFFERED {
+        assert_eq!(children.tell_outcome(n).unwrap(), SendOutcome::Buffered);
+    }
+
+    Bastion::start();
+    wait_for(|| children.state() == GroupState::Running);
+    assert_eq!(
+        children.tell_outcome(BUFFERED).unwrap(),
+        SendOutcome::Delivered
+    );
+
+    // The buffered messages are delivered too.
+    wait_for(|| received.load(Ordering::SeqCst) == BUFFERED + 1);
+
+    // The event is only emitted once, when the watermark is first exceeded.
+    let event = run!(events.next()).unwrap();
+    assert_eq!(
+        event,
+        Event::PreStartBufferExceeded {
+            group: children.id().clone(),
+            buffered: WATERMARK + 1,
+            watermark: WATERMARK,
+        }
+    );
+    assert!(events.next().now_or_never().is_none());
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
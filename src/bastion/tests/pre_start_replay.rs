@@ -0,0 +1,52 @@
+mod common;
+
+use bastion::children_ref::GroupState;
+use bastion::prelude::*;
+use common::wait_for;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+const BUFFERED: usize = 100_000;
+// The number of buffered messages that are replayed at once.
+const REPLAY_CHUNK: usize = 1_000;
+
+#[test]
+fn killed_while_replaying() {
+    Bastion::init();
+
+    let received = Arc::new(AtomicUsize::new(0));
+    let counter = received.clone();
+
+    let children = Bastion::children(move |children| {
+        let counter = counter.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let counter = counter.clone();
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        ref _msg: usize => {
+                            // The group is killed as soon as it
+                            // starts replaying its backlog.
+                            if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                                ctx.parent().kill().unwrap();
+                            }
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    for n in 0..BUFFERED {
+        children.broadcast(n).unwrap();
+    }
+
+    Bastion::start();
+    wait_for(|| children.state() == GroupState::Stopped);
+    assert!(received.load(Ordering::SeqCst) <= 10 * REPLAY_CHUNK);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
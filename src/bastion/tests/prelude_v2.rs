@@ -0,0 +1,138 @@
+// These tests only use the functions and types exported by
+// `prelude_v2`, as the `send_recv` and `fibonacci` examples do.
+mod common;
+
+use bastion::prelude_v2::*;
+use common::{init_start, wait_for};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+fn fib(n: u64) -> u64 {
+    if n < 2 {
+        n
+    } else {
+        fib(n - 1) + fib(n - 2)
+    }
+}
+
+#[test]
+fn send_recv() {
+    init_start();
+
+    let answered = Arc::new(AtomicUsize::new(0));
+    let counted = answered.clone();
+    Bastion::children(move |children| {
+        let counted = counted.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let counted = counted.clone();
+            async move {
+                assert!(ctx.try_recv().await.is_none());
+
+                // The element asks itself, answers...
+                let answer = ctx.ask(&ctx.current().addr(), "Hello World!").unwrap();
+                match ctx.recv_msg::<&'static str>().await {
+                    (TypedMsg::Ask { msg, sender }, _) => {
+                        assert_eq!(msg, "Hello World!");
+                        sender.answer(&ctx, "Goodbye!").unwrap();
+                    }
+                    _ => panic!("Unexpected message."),
+                }
+
+                // ...and receives its answer.
+                let answer: &'static str = answer.await_typed().await?;
+                assert_eq!(answer, "Goodbye!");
+                counted.fetch_add(1, Ordering::SeqCst);
+
+                Ok(())
+            }
+        })
+    })
+    .unwrap();
+
+    wait_for(|| answered.load(Ordering::SeqCst) == 1);
+}
+
+#[test]
+fn fibonacci() {
+    init_start();
+
+    let told = Arc::new(AtomicUsize::new(0));
+    let counted = told.clone();
+    let children = Bastion::children(move |children| {
+        let counted = counted.clone();
+        children
+            .with_redundancy(2)
+            .with_exec(move |ctx: BastionContext| {
+                let counted = counted.clone();
+                async move {
+                    loop {
+                        match ctx.recv_typed::<u64>().await {
+                            Ok((TypedMsg::Ask { msg, sender }, _)) => {
+                                sender.answer(&ctx, fib(msg)).unwrap();
+                            }
+                            Ok((TypedMsg::Broadcast(_), _)) | Ok((TypedMsg::Tell(_), _)) => {
+                                counted.fetch_add(1, Ordering::SeqCst);
+                            }
+                            // The messages of other types are answered
+                            // with their type's name.
+                            Err(unknown) => {
+                                let (mut unknown, _) = unknown.extract();
+                                let type_name = unknown.type_name();
+                                if let Some(sender) = unknown.take_sender() {
+                                    sender.answer(&ctx, type_name).unwrap();
+                                }
+                            }
+                        }
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    children.broadcast(0u64).unwrap();
+    for (n, child) in children.elems().iter().enumerate() {
+        child.tell_anonymously(0u64).unwrap();
+
+        let answer = child.ask_anonymously(10 + n as u64).unwrap();
+        let answer: u64 = run(answer.await_typed()).unwrap();
+        assert_eq!(answer, fib(10 + n as u64));
+    }
+    wait_for(|| told.load(Ordering::SeqCst) == 4);
+
+    // The answers of other types are reported as such.
+    let child = &children.elems()[0];
+    let answer = child.ask_anonymously("fib 10").unwrap();
+    assert_eq!(
+        run(answer.await_typed::<u64>()),
+        Err(AnswerError::UnexpectedType {
+            expected: "u64",
+            got: "&str",
+        })
+    );
+
+    // The requests can also be sent in parallel.
+    let requests = children
+        .elems()
+        .iter()
+        .map(|child| {
+            let answer = child.ask_anonymously(20u64).unwrap();
+            spawn(answer.await_typed::<u64>())
+        })
+        .collect::<Vec<_>>();
+    for request in requests {
+        assert_eq!(run(request), Some(Ok(fib(20))));
+    }
+}
+
+#[test]
+fn blocking_tasks() {
+    init_start();
+
+    let handle = spawn_blocking(async {
+        thread::sleep(Duration::from_millis(10));
+        fib(10)
+    });
+    assert_eq!(run(handle), Some(55));
+}
@@ -0,0 +1,177 @@
+#![cfg(feature = "supervision")]
+mod common;
+
+use bastion::children_ref::GroupPolicies;
+use bastion::prelude::*;
+use common::wait_for;
+use std::time::Duration;
+
+fn idle(children: Children) -> Children {
+    children.with_exec(|ctx: BastionContext| async move {
+        loop {
+            ctx.recv().await?;
+        }
+    })
+}
+
+// A row of the presets' documented table.
+struct Row {
+    restart_policy: RestartPolicy,
+    backoff: ActorRestartStrategy,
+    restart_window: Option<(usize, Duration)>,
+    restart_backoff: Option<Backoff>,
+    pre_start_watermark: usize,
+    mailbox_capacity: Option<usize>,
+    overflow_policy: OverflowPolicy,
+    drain_timeout: Duration,
+    requeue_on_restart: bool,
+    preset: Option<&'static str>,
+}
+
+fn matches(policies: &GroupPolicies, row: &Row) -> bool {
+    policies.restart_strategy().restart_policy() == row.restart_policy
+        && policies.restart_strategy().strategy() == row.backoff
+        && policies.restart_window() == row.restart_window
+        && policies.restart_backoff() == row.restart_backoff.as_ref()
+        && policies.pre_start_watermark() == row.pre_start_watermark
+        && policies.mailbox_capacity() == row.mailbox_capacity
+        && policies.overflow_policy() == row.overflow_policy
+        && policies.drain_timeout() == row.drain_timeout
+        && policies.requeues_on_restart() == row.requeue_on_restart
+        && policies.preset() == row.preset
+}
+
+fn resolved_to(children: &ChildrenRef, row: Row) {
+    wait_for(|| matches(&children.policies(), &row));
+}
+
+#[test]
+fn presets() {
+    Bastion::init();
+
+    let mut groups = Vec::new();
+    for preset in [
+        SupervisionPreset::web_service(),
+        SupervisionPreset::batch_pipeline(),
+        SupervisionPreset::best_effort_telemetry(),
+    ] {
+        Bastion::supervisor(|sp| {
+            let sp = sp.apply_preset(preset.clone());
+            groups.push(
+                sp.children_ref(|children| idle(children).apply_preset(preset))
+                    .unwrap(),
+            );
+            sp
+        })
+        .unwrap();
+    }
+
+    let overridden = Bastion::supervisor(|sp| {
+        sp.apply_preset(SupervisionPreset::web_service())
+            .with_restart_window(3, Duration::from_secs(1))
+    })
+    .unwrap();
+    // Only the supervisor's preset (including its default mailbox)
+    // applies to this group...
+    let inherited = overridden.children(idle).unwrap();
+    // ...while this one overrides its own preset's capacity.
+    let partial = overridden
+        .children(|children| {
+            idle(children)
+                .apply_preset(SupervisionPreset::best_effort_telemetry())
+                .with_mailbox_capacity(10)
+        })
+        .unwrap();
+
+    Bastion::start();
+
+    resolved_to(
+        &groups[0],
+        Row {
+            restart_policy: RestartPolicy::Always,
+            backoff: ActorRestartStrategy::Immediate,
+            restart_window: Some((10, Duration::from_secs(10))),
+            restart_backoff: None,
+            pre_start_watermark: 1_000,
+            mailbox_capacity: Some(1_024),
+            overflow_policy: OverflowPolicy::Fail,
+            drain_timeout: Duration::from_secs(1),
+            requeue_on_restart: false,
+            preset: Some("web_service"),
+        },
+    );
+    resolved_to(
+        &groups[1],
+        Row {
+            restart_policy: RestartPolicy::Always,
+            backoff: ActorRestartStrategy::Immediate,
+            restart_window: None,
+            restart_backoff: Some(Backoff::exponential(
+                Duration::from_millis(100),
+                Duration::from_secs(10),
+                0.1,
+            )),
+            pre_start_watermark: 10_000,
+            mailbox_capacity: Some(10_000),
+            overflow_policy: OverflowPolicy::Block,
+            drain_timeout: Duration::from_secs(60),
+            requeue_on_restart: true,
+            preset: Some("batch_pipeline"),
+        },
+    );
+    resolved_to(
+        &groups[2],
+        Row {
+            restart_policy: RestartPolicy::Never,
+            backoff: ActorRestartStrategy::Immediate,
+            restart_window: None,
+            restart_backoff: None,
+            pre_start_watermark: 100,
+            mailbox_capacity: Some(256),
+            overflow_policy: OverflowPolicy::DropOldest,
+            drain_timeout: Duration::from_secs(1),
+            requeue_on_restart: false,
+            preset: Some("best_effort_telemetry"),
+        },
+    );
+    resolved_to(
+        &inherited,
+        Row {
+            restart_policy: RestartPolicy::Always,
+            backoff: ActorRestartStrategy::Immediate,
+            restart_window: Some((3, Duration::from_secs(1))),
+            restart_backoff: None,
+            pre_start_watermark: 1_000,
+            mailbox_capacity: Some(1_024),
+            overflow_policy: OverflowPolicy::Fail,
+            drain_timeout: Duration::from_secs(5),
+            requeue_on_restart: true,
+            preset: Some("web_service"),
+        },
+    );
+    resolved_to(
+        &partial,
+        Row {
+            restart_policy: RestartPolicy::Always,
+            backoff: ActorRestartStrategy::Immediate,
+            restart_window: Some((3, Duration::from_secs(1))),
+            restart_backoff: None,
+            pre_start_watermark: 100,
+            mailbox_capacity: Some(10),
+            overflow_policy: OverflowPolicy::DropOldest,
+            drain_timeout: Duration::from_secs(1),
+            requeue_on_restart: false,
+            preset: Some("best_effort_telemetry"),
+        },
+    );
+
+    // The presets' names are exported along with the policies.
+    #[cfg(feature = "introspection")]
+    {
+        let json = Bastion::tree().to_json();
+        assert!(json.contains("\"preset\":\"batch_pipeline\""), "{}", json);
+    }
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -1,4 +1,7 @@
+mod common;
+
 use bastion::prelude::*;
+use common::seeded_config;
 use proptest::prelude::*;
 use std::sync::Once;
 
@@ -9,7 +12,7 @@ proptest! {
     #[test]
     fn proptest_bcast_message(message in "\\PC*") {
         START.call_once(|| {
-            Bastion::init();
+            Bastion::init_with(seeded_config());
         });
         Bastion::start();
 
@@ -0,0 +1,162 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use futures::prelude::*;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug)]
+struct A;
+#[derive(Debug, Default)]
+struct B;
+#[derive(Debug, Default)]
+struct C;
+
+// The type names and correlation ids of a chain's entries.
+type Entries = Vec<(&'static str, Option<CorrelationId>)>;
+type Chain = Arc<Mutex<Option<(Entries, bool)>>>;
+
+// Sends an `M` to `to` every time it receives a message.
+fn forwarding<M: Message + Default>(to: ChildRef, depth: usize) -> ChildrenRef {
+    Bastion::children(move |children| {
+        let to = to.clone();
+        children
+            .with_provenance_depth(depth)
+            .with_exec(move |ctx: BastionContext| {
+                let to = to.clone();
+                async move {
+                    loop {
+                        ctx.recv().await?;
+                        to.tell_anonymously(M::default()).unwrap();
+                    }
+                }
+            })
+    })
+    .unwrap()
+}
+
+// Records the provenance of the first message it receives.
+fn recording(chain: Chain) -> ChildrenRef {
+    Bastion::children(move |children| {
+        let chain = chain.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let chain = chain.clone();
+            async move {
+                let (msg, _) = ctx.recv().await?.extract();
+                let entries = msg
+                    .provenance()
+                    .iter()
+                    .map(|entry| (entry.type_name(), entry.correlation_id().cloned()))
+                    .collect();
+                *chain.lock().unwrap() = Some((entries, msg.is_provenance_truncated()));
+
+                loop {
+                    ctx.recv().await?;
+                }
+            }
+        })
+    })
+    .unwrap()
+}
+
+// Sends an `A` to a group sending a `B` to a group sending a `C`
+// to a group recording its provenance, with chains of at most
+// `depth` entries.
+fn three_hops(depth: usize) -> (CorrelationId, Entries, bool) {
+    let chain = Arc::new(Mutex::new(None));
+    let last = recording(chain.clone());
+    let second = forwarding::<C>(last.elems()[0].clone(), depth);
+    let first = forwarding::<B>(second.elems()[0].clone(), depth);
+
+    let id = first.elems()[0].tell_correlated(A).unwrap();
+    wait_for(|| chain.lock().unwrap().is_some());
+
+    let (entries, truncated) = chain.lock().unwrap().take().unwrap();
+    (id, entries, truncated)
+}
+
+#[test]
+fn three_hops_chain() {
+    init_start();
+
+    let (id, entries, truncated) = three_hops(8);
+    assert_eq!(
+        entries,
+        vec![("provenance::B", None), ("provenance::A", Some(id))]
+    );
+    assert!(!truncated);
+}
+
+#[test]
+fn truncated() {
+    init_start();
+
+    let (_, entries, truncated) = three_hops(1);
+    assert_eq!(entries, vec![("provenance::B", None)]);
+    assert!(truncated);
+}
+
+#[test]
+fn not_recorded() {
+    init_start();
+
+    let chain = Arc::new(Mutex::new(None));
+    let last = recording(chain.clone());
+    Bastion::children(move |children| {
+        let to = last.elems()[0].clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let to = to.clone();
+            async move {
+                ctx.recv().await?;
+                to.tell_anonymously(C).unwrap();
+
+                loop {
+                    ctx.recv().await?;
+                }
+            }
+        })
+    })
+    .unwrap()
+    .broadcast(B)
+    .unwrap();
+
+    wait_for(|| chain.lock().unwrap().is_some());
+    assert_eq!(chain.lock().unwrap().take().unwrap(), (vec![], false));
+}
+
+#[test]
+fn fault_report() {
+    init_start();
+    let mut events = Bastion::events();
+
+    let supervisor = Bastion::supervisor(|sp| sp).unwrap();
+    let faulting = supervisor
+        .children(|children| {
+            children
+                .with_provenance()
+                .with_exec(|ctx: BastionContext| async move {
+                    ctx.recv().await?;
+                    Err(())
+                })
+        })
+        .unwrap();
+    faulting.elems()[0].tell_anonymously(A).unwrap();
+
+    loop {
+        match run!(events.next()) {
+            Some(Event::ElemsFaulted {
+                group, provenance, ..
+            }) if &group == faulting.id() => {
+                let entries = provenance
+                    .entries()
+                    .iter()
+                    .map(|entry| entry.type_name())
+                    .collect::<Vec<_>>();
+                assert_eq!(entries, vec!["provenance::A"]);
+                break;
+            }
+            Some(_) => (),
+            None => panic!("The events stream ended."),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use bastion::children_ref::GroupState;
+use bastion::prelude::*;
+use common::wait_for;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const ELEMS: usize = 3;
+
+// Decrements the count of running elements when the element's
+// future is dropped.
+struct Running(Arc<AtomicUsize>);
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn prune_elems() {
+    Bastion::init();
+
+    let running = Arc::new(AtomicUsize::new(0));
+    let counter = running.clone();
+
+    let children = Bastion::children(move |children| {
+        let counter = counter.clone();
+        children
+            .with_redundancy(ELEMS)
+            .with_exec(move |ctx: BastionContext| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                let running = Running(counter.clone());
+
+                async move {
+                    let _running = running;
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    Bastion::start();
+    wait_for(|| running.load(Ordering::SeqCst) == ELEMS);
+
+    let pruned = children.elems()[0].clone();
+    children.prune_elem(pruned.id()).unwrap();
+    wait_for(|| running.load(Ordering::SeqCst) == ELEMS - 1);
+    wait_for(|| pruned.tell_anonymously("message").is_err());
+
+    // The other elements are still running and the pruned one
+    // isn't restarted.
+    children.prune_elem(pruned.id()).unwrap();
+    children.broadcast("message").unwrap();
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(running.load(Ordering::SeqCst), ELEMS - 1);
+    assert_eq!(children.state(), GroupState::Running);
+    assert!(children.elems()[1].tell_anonymously("message").is_ok());
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
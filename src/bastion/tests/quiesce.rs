@@ -0,0 +1,132 @@
+mod common;
+
+use bastion::children_ref::GroupState;
+use bastion::prelude::*;
+use common::init_start;
+use futures::prelude::*;
+use futures::{pin_mut, select};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// Removes the states the group was in before being quiesced.
+fn quiescing(states: Vec<GroupState>) -> Vec<GroupState> {
+    states
+        .into_iter()
+        .skip_while(|state| *state != GroupState::Quiescing)
+        .collect()
+}
+
+#[test]
+fn quiesced() {
+    init_start();
+
+    // Serves its messages forever, until it is quiesced.
+    let closed = Arc::new(AtomicUsize::new(0));
+    let closing = closed.clone();
+    let children = Bastion::children(move |children| {
+        let closing = closing.clone();
+        children
+            .with_redundancy(3)
+            .with_exec(move |ctx: BastionContext| {
+                let closing = closing.clone();
+                async move {
+                    loop {
+                        let event = ctx.next_event().fuse();
+                        let msg = ctx.recv().fuse();
+                        pin_mut!(event, msg);
+                        select! {
+                            event = event => {
+                                assert!(matches!(event, ContextEvent::Quiesce { .. }));
+                                closing.fetch_add(1, Ordering::SeqCst);
+                                return Ok(());
+                            },
+                            msg = msg => {
+                                msg?;
+                            },
+                        }
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    let states = children.state_changes();
+    let state = run!(children.quiesce(Duration::from_secs(5))).unwrap();
+    assert_eq!(state, GroupState::Stopped);
+    assert_eq!(closed.load(Ordering::SeqCst), 3);
+
+    let states = run!(states.collect::<Vec<_>>());
+    let states = quiescing(states);
+    assert_eq!(states, vec![GroupState::Quiescing, GroupState::Stopped]);
+}
+
+#[test]
+fn deadline_expired() {
+    init_start();
+
+    // Ignores the quiesce event, and keeps handling its messages.
+    let deadlines = Arc::new(Mutex::new(Vec::new()));
+    let recorded = deadlines.clone();
+    let children = Bastion::children(move |children| {
+        let recorded = recorded.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let recorded = recorded.clone();
+            async move {
+                if let ContextEvent::Quiesce { deadline } = ctx.next_event().await {
+                    recorded.lock().unwrap().push(deadline);
+                }
+
+                loop {
+                    ctx.recv().await?;
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    // The element is stopped once the deadline expired, as it is
+    // waiting for a message.
+    let quiesced = Instant::now();
+    let state = run!(children.quiesce(Duration::from_millis(100))).unwrap();
+    assert_eq!(state, GroupState::Stopped);
+    assert!(quiesced.elapsed() >= Duration::from_millis(100));
+
+    let deadlines = deadlines.lock().unwrap();
+    assert_eq!(deadlines.len(), 1);
+    assert!(deadlines[0] > quiesced);
+}
+
+#[test]
+fn killed_after_deadline() {
+    init_start();
+
+    // Never completes nor waits for a message.
+    let children = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                ctx.sleep(Duration::from_millis(1)).await;
+            }
+        })
+    })
+    .unwrap();
+
+    // The element is killed once the deadline expired, rather than
+    // being given as long again to stop.
+    let states = children.state_changes();
+    let quiesced = Instant::now();
+    let state = run!(children.quiesce(Duration::from_millis(500))).unwrap();
+    assert_eq!(state, GroupState::Killed);
+    assert!(quiesced.elapsed() < Duration::from_millis(900));
+
+    let states = run!(states.collect::<Vec<_>>());
+    let states = quiescing(states);
+    assert_eq!(
+        states,
+        vec![
+            GroupState::Quiescing,
+            GroupState::Stopping,
+            GroupState::Killed
+        ]
+    );
+}
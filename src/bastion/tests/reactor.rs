@@ -0,0 +1,65 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use futures::channel::oneshot;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// Spawns an element sleeping for this duration, and returns the
+// receiver of how long it slept for.
+fn sleeping(duration: Duration) -> oneshot::Receiver<Duration> {
+    let (sender, slept) = oneshot::channel();
+    let sender = Arc::new(Mutex::new(Some(sender)));
+    Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let sender = sender.clone();
+            async move {
+                let started = Instant::now();
+                ctx.sleep(duration).await;
+                if let Some(sender) = sender.lock().unwrap().take() {
+                    sender.send(started.elapsed()).ok();
+                }
+
+                ctx.recv().await?;
+                Ok(())
+            }
+        })
+    })
+    .unwrap();
+
+    slept
+}
+
+#[test]
+fn sleep() {
+    init_start();
+
+    let slept = run!(sleeping(Duration::from_millis(50))).unwrap();
+    assert!(slept >= Duration::from_millis(50));
+    assert!(Bastion::health().reactor().is_running());
+}
+
+#[test]
+fn timers_outlive_restarts() {
+    init_start();
+
+    let slept = sleeping(Duration::from_millis(200));
+    wait_for(|| Bastion::health().reactor().timers() > 0);
+
+    let restarts = Bastion::health().reactor().restarts();
+    Bastion::fault_reactor();
+    wait_for(|| {
+        let health = Bastion::health();
+        health.reactor().restarts() > restarts && health.reactor().is_running()
+    });
+
+    // The timer registered before the restart still wakes the
+    // element...
+    let slept = run!(slept).unwrap();
+    assert!(slept >= Duration::from_millis(200));
+
+    // ...and the ones registered after it too.
+    let slept = run!(sleeping(Duration::from_millis(50))).unwrap();
+    assert!(slept >= Duration::from_millis(50));
+}
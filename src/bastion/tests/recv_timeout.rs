@@ -0,0 +1,56 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const TIMEOUT: Duration = Duration::from_millis(50);
+
+#[test]
+fn recv_timeout() {
+    Bastion::init();
+
+    let received = Arc::new(AtomicUsize::new(0));
+    let timeouts = Arc::new(AtomicUsize::new(0));
+    let (received_inner, timeouts_inner) = (received.clone(), timeouts.clone());
+    let children = Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let received = received_inner.clone();
+            let timeouts = timeouts_inner.clone();
+            async move {
+                loop {
+                    let started = Instant::now();
+                    match ctx.recv_timeout(TIMEOUT).await {
+                        Ok(_) => {
+                            received.fetch_add(1, Ordering::SeqCst);
+                        }
+                        Err(ReceiveError::Timeout(timeout)) => {
+                            assert_eq!(timeout, TIMEOUT);
+                            assert!(started.elapsed() >= TIMEOUT);
+                            timeouts.fetch_add(1, Ordering::SeqCst);
+                        }
+                    }
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    Bastion::start();
+
+    // The element keeps timing out while idle...
+    wait_for(|| timeouts.load(Ordering::SeqCst) >= 3);
+    assert_eq!(received.load(Ordering::SeqCst), 0);
+
+    // ...and still receives the messages sent to it.
+    children.broadcast("ping").unwrap();
+    wait_for(|| received.load(Ordering::SeqCst) == 1);
+
+    let timed_out = timeouts.load(Ordering::SeqCst);
+    wait_for(|| timeouts.load(Ordering::SeqCst) > timed_out);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
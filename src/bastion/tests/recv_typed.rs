@@ -0,0 +1,87 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::sync::{Arc, Mutex};
+
+// How a message was received by an element.
+#[derive(Debug, Clone, PartialEq)]
+enum Received {
+    Broadcast(u64),
+    Tell(u64),
+    Ask(u64),
+    Other(bool),
+}
+
+// A `u64` receiving group doubling the `u64`s it's asked, and
+// recording every message it receives.
+fn typed(received: Arc<Mutex<Vec<Received>>>) -> ChildrenRef {
+    Bastion::children(move |children| {
+        let received = received.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let received = received.clone();
+            async move {
+                loop {
+                    let recorded = match ctx.recv_typed::<u64>().await {
+                        Ok((TypedMsg::Broadcast(msg), _)) => Received::Broadcast(*msg),
+                        Ok((TypedMsg::Tell(msg), _)) => Received::Tell(msg),
+                        Ok((TypedMsg::Ask { msg, sender }, _)) => {
+                            // Recorded before answering, for the asker
+                            // to find it once answered.
+                            received.lock().unwrap().push(Received::Ask(msg));
+                            sender.answer(&ctx, msg * 2).unwrap();
+                            continue;
+                        }
+                        Err(signed) => {
+                            let (msg, _) = signed.extract();
+                            Received::Other(msg.downcast::<bool>().unwrap())
+                        }
+                    };
+                    received.lock().unwrap().push(recorded);
+                }
+            }
+        })
+    })
+    .unwrap()
+}
+
+#[test]
+fn tell_and_mismatch() {
+    init_start();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let children = typed(received.clone());
+    let child = &children.elems()[0];
+    child.tell_anonymously(1u64).unwrap();
+    child.tell_anonymously(true).unwrap();
+    children.broadcast(2u64).unwrap();
+
+    wait_for(|| received.lock().unwrap().len() == 3);
+    // The broadcast isn't sent to the element directly, so it can
+    // be received before the other messages.
+    let received = received.lock().unwrap();
+    for expected in &[
+        Received::Tell(1),
+        Received::Other(true),
+        Received::Broadcast(2),
+    ] {
+        assert!(
+            received.contains(expected),
+            "{:?} wasn't received.",
+            expected
+        );
+    }
+}
+
+#[test]
+fn ask() {
+    init_start();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let children = typed(received.clone());
+    let answer = children.elems()[0].ask_anonymously(21u64).unwrap();
+
+    let (msg, _) = run!(answer).unwrap().extract();
+    assert_eq!(msg.downcast::<u64>().unwrap(), 42);
+    assert_eq!(*received.lock().unwrap(), vec![Received::Ask(21)]);
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+static LETTERS: Mutex<Vec<u64>> = Mutex::new(Vec::new());
+
+fn is_reply(msg: &Msg) -> bool {
+    msg.downcast_ref::<&'static str>() == Some(&"reply")
+}
+
+// A group whose element waits for the "reply" before retrieving the
+// other messages, recording them in the order it retrieves them.
+fn selective() -> (ChildrenRef, Arc<Mutex<Vec<String>>>) {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let recorded = received.clone();
+    let children = Bastion::children(move |children| {
+        let recorded = recorded.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let recorded = recorded.clone();
+            async move {
+                let reply = ctx.recv_where(is_reply).await?;
+                msg! { reply,
+                    reply: &'static str => recorded.lock().unwrap().push(reply.to_string());
+                    _: _ => unreachable!();
+                }
+
+                loop {
+                    msg! { ctx.recv().await?,
+                        n: u64 => recorded.lock().unwrap().push(n.to_string());
+                        msg: &'static str => recorded.lock().unwrap().push(msg.to_string());
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    (children, received)
+}
+
+#[test]
+fn recv_where() {
+    let config = Config::new().with_dead_letter_handler(|letter: DeadLetter| {
+        if let DeadLetterPayload::Full(msg) = letter.into_payload() {
+            if let Ok(n) = msg.downcast::<u64>() {
+                LETTERS.lock().unwrap().push(n);
+            }
+        }
+    });
+    Bastion::init_with(config);
+
+    // The messages received before the group is started are replayed
+    // in order...
+    let (children, received) = selective();
+    let elem = &children.elems()[0];
+    elem.tell_anonymously(1u64).unwrap();
+    elem.tell_anonymously(2u64).unwrap();
+    elem.tell_anonymously("reply").unwrap();
+    Bastion::start();
+    elem.tell_anonymously(3u64).unwrap();
+    elem.tell_anonymously("later").unwrap();
+
+    // ...and the ones that don't match are retrieved afterwards, in
+    // the order they were received.
+    wait_for(|| received.lock().unwrap().len() == 5);
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec!["reply", "1", "2", "3", "later"]
+    );
+
+    // The element waits for a matching message...
+    let (children, received) = selective();
+    let elem = &children.elems()[0];
+    for n in 0..3u64 {
+        elem.tell_anonymously(n).unwrap();
+    }
+    wait_for(|| elem.mailbox_len() == 3);
+    thread::sleep(Duration::from_millis(20));
+    assert!(received.lock().unwrap().is_empty());
+
+    elem.tell_anonymously("reply").unwrap();
+    wait_for(|| received.lock().unwrap().len() == 4);
+    assert_eq!(*received.lock().unwrap(), vec!["reply", "0", "1", "2"]);
+
+    // ...and the messages it left aren't lost if it is stopped while
+    // waiting for one.
+    let (children, received) = selective();
+    let elem = &children.elems()[0];
+    for n in 10..13u64 {
+        elem.tell_anonymously(n).unwrap();
+    }
+    wait_for(|| elem.mailbox_len() == 3);
+    elem.stop().unwrap();
+    wait_for(|| LETTERS.lock().unwrap().len() == 3);
+    assert_eq!(*LETTERS.lock().unwrap(), vec![10, 11, 12]);
+    assert!(received.lock().unwrap().is_empty());
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
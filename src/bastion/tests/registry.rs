@@ -0,0 +1,53 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+
+fn named(name: &str) -> Result<ChildrenRef, BuilderError> {
+    let name = name.to_string();
+    Bastion::children(|children| children.with_name(name))
+}
+
+#[test]
+fn named_children() {
+    Bastion::init();
+    Bastion::start();
+
+    assert!(Bastion::children_of("workers").is_none());
+
+    let workers = named("workers").unwrap();
+    let found = Bastion::children_of("workers").unwrap();
+    assert_eq!(found.id(), workers.id());
+    assert_eq!(found.elems().len(), 1);
+    assert_eq!(named("workers").unwrap_err(), BuilderError::NameTaken);
+
+    // A supervisor doesn't deploy a group whose name is taken...
+    let mut duplicate = None;
+    Bastion::supervisor(|sp| {
+        duplicate = Some(sp.children_ref(|children| children.with_name("workers")));
+        sp
+    })
+    .unwrap();
+    assert_eq!(duplicate.unwrap().unwrap_err(), BuilderError::NameTaken);
+    assert_eq!(Bastion::children_of("workers").unwrap().id(), workers.id());
+
+    // ...and isn't created if it was added to it.
+    let err = Bastion::supervisor(|sp| sp.children(|children| children.with_name("workers")))
+        .unwrap_err();
+    assert_eq!(err, BuilderError::NameTaken);
+    assert_eq!(Bastion::children_of("workers").unwrap().id(), workers.id());
+
+    // The name is released once the group stopped...
+    workers.stop().unwrap();
+    wait_for(|| Bastion::children_of("workers").is_none());
+    let workers = named("workers").unwrap();
+    assert_eq!(Bastion::children_of("workers").unwrap().id(), workers.id());
+
+    // ...or was killed.
+    workers.kill().unwrap();
+    wait_for(|| Bastion::children_of("workers").is_none());
+    named("workers").unwrap();
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,251 @@
+mod common;
+
+use bastion::prelude::*;
+use bastion::reject::MAX_REJECT_HOPS;
+use common::wait_for;
+use std::sync::{Arc, Mutex, Once};
+
+static START: Once = Once::new();
+
+// The dead letters received, with their reason.
+static LETTERS: Mutex<Vec<(DeadLetterReason, &'static str)>> = Mutex::new(Vec::new());
+
+fn init_start() {
+    START.call_once(|| {
+        let config = Config::new().with_dead_letter_handler(|letter: DeadLetter| {
+            let reason = letter.reason();
+            if let DeadLetterPayload::Full(msg) = letter.into_payload() {
+                if let Ok(msg) = msg.downcast() {
+                    LETTERS.lock().unwrap().push((reason, msg));
+                }
+            }
+        });
+        Bastion::init_with(config);
+        Bastion::start();
+    });
+}
+
+fn letters(msg: &'static str) -> Vec<DeadLetterReason> {
+    LETTERS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(_, letter)| *letter == msg)
+        .map(|(reason, _)| *reason)
+        .collect()
+}
+
+// A group of `redundancy` elements rejecting the `&'static str`s
+// they receive as `disposition` says.
+fn rejecting(redundancy: usize, disposition: RejectDisposition) -> ChildrenRef {
+    Bastion::children(|children| {
+        children
+            .with_redundancy(redundancy)
+            .with_exec(move |ctx: BastionContext| async move {
+                loop {
+                    let msg = ctx.recv().await?;
+                    if msg.msg().downcast_ref::<&'static str>().is_some() {
+                        ctx.reject_message(msg, disposition);
+                    }
+                }
+            })
+    })
+    .unwrap()
+}
+
+#[test]
+fn shard_resize() {
+    init_start();
+
+    const KEYS: u64 = 200;
+
+    // The keys received and rejected by each element, which owns
+    // the keys equal to its index modulo 4.
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let rejected = Arc::new(Mutex::new(Vec::new()));
+    let children = Bastion::children(|children| {
+        let received = received.clone();
+        let rejected = rejected.clone();
+        children
+            .with_redundancy(4)
+            .with_exec(move |ctx: BastionContext| {
+                let received = received.clone();
+                let rejected = rejected.clone();
+                async move {
+                    loop {
+                        let msg = ctx.recv().await?;
+                        match msg.msg().downcast_ref::<u64>() {
+                            Some(key) if *key as usize % 4 != ctx.elem_index() => {
+                                rejected.lock().unwrap().push(*key);
+                                ctx.reject_message(msg, RejectDisposition::Reroute);
+                            }
+                            Some(key) => received.lock().unwrap().push((*key, ctx.elem_index())),
+                            None => (),
+                        }
+                    }
+                }
+            })
+    })
+    .unwrap();
+    wait_for(|| children.warm_elems() == 4);
+
+    // The keys are sent using the sharding of the group before it
+    // was resized, when it had 3 elements.
+    let elems = children.elems();
+    for key in 0..KEYS {
+        elems[key as usize % 3].tell_anonymously(key).unwrap();
+    }
+
+    wait_for(|| received.lock().unwrap().len() == KEYS as usize);
+    let mut received = received.lock().unwrap().clone();
+    received.sort();
+    let expected = (0..KEYS)
+        .map(|key| (key, key as usize % 4))
+        .collect::<Vec<_>>();
+    assert_eq!(received, expected);
+
+    // Some keys were misrouted, and each of them was rejected at
+    // most once by each of the elements that don't own it.
+    let rejected = rejected.lock().unwrap().clone();
+    assert!(!rejected.is_empty());
+    for key in 0..KEYS {
+        let hops = rejected.iter().filter(|rejected| **rejected == key).count();
+        assert!(hops <= 3, "{} was rejected {} times.", key, hops);
+    }
+    assert_eq!(children.stats().rejected(), rejected.len() as u64);
+}
+
+#[test]
+fn hop_cap() {
+    init_start();
+
+    let children = rejecting(2, RejectDisposition::Reroute);
+    wait_for(|| children.warm_elems() == 2);
+    children.elems()[0].tell_anonymously("ping-pong").unwrap();
+
+    // The message is rerouted back and forth until it reaches the
+    // hop cap, and is then sent to the dead letters.
+    wait_for(|| !letters("ping-pong").is_empty());
+    assert_eq!(letters("ping-pong"), vec![DeadLetterReason::Rejected]);
+    assert_eq!(children.stats().rejected(), MAX_REJECT_HOPS as u64 + 1);
+}
+
+#[test]
+fn pinned_reroute() {
+    init_start();
+
+    // The elements reject the messages unless they are the one the
+    // group is pinned to, which rejects "unpinned" and records the
+    // other messages it handles.
+    let pinned = Arc::new(Mutex::new(None));
+    let handled = Arc::new(Mutex::new(Vec::new()));
+    let children = Bastion::children(|children| {
+        let (pinned, handled) = (pinned.clone(), handled.clone());
+        children
+            .with_redundancy(3)
+            .with_exec(move |ctx: BastionContext| {
+                let (pinned, handled) = (pinned.clone(), handled.clone());
+                async move {
+                    loop {
+                        let msg = ctx.recv().await?;
+                        let is_pinned = pinned.lock().unwrap().as_ref() == Some(ctx.current().id());
+                        match msg.msg().downcast_ref::<&'static str>() {
+                            Some(&"unpinned") if is_pinned => {
+                                ctx.reject_message(msg, RejectDisposition::Reroute)
+                            }
+                            Some(_) if !is_pinned => {
+                                ctx.reject_message(msg, RejectDisposition::Reroute)
+                            }
+                            Some(msg) => handled.lock().unwrap().push(*msg),
+                            None => (),
+                        }
+                    }
+                }
+            })
+    })
+    .unwrap();
+    wait_for(|| children.warm_elems() == 3);
+
+    let elems = children.elems();
+    *pinned.lock().unwrap() = Some(elems[1].id().clone());
+    run!(children.set_routing(Routing::Pinned(elems[1].id().clone()))).unwrap();
+
+    // The messages rejected by the other elements are rerouted
+    // straight to the pinned one...
+    elems[0].tell_anonymously("pinned").unwrap();
+    elems[2].tell_anonymously("pinned").unwrap();
+    wait_for(|| handled.lock().unwrap().len() == 2);
+    assert_eq!(children.stats().rejected(), 2);
+    assert!(letters("pinned").is_empty());
+
+    // ...which can't reroute them to any other.
+    elems[1].tell_anonymously("unpinned").unwrap();
+    wait_for(|| !letters("unpinned").is_empty());
+    assert_eq!(letters("unpinned"), vec![DeadLetterReason::Rejected]);
+    assert_eq!(children.stats().rejected(), 3);
+}
+
+#[test]
+fn single_elem_reroute() {
+    init_start();
+
+    let children = rejecting(1, RejectDisposition::Reroute);
+    children.elems()[0].tell_anonymously("nowhere").unwrap();
+
+    // There is no other element to reroute the message to.
+    wait_for(|| !letters("nowhere").is_empty());
+    assert_eq!(letters("nowhere"), vec![DeadLetterReason::Rejected]);
+    assert_eq!(children.stats().rejected(), 1);
+}
+
+#[test]
+fn dead_letter() {
+    init_start();
+
+    let children = rejecting(1, RejectDisposition::DeadLetter);
+    children.elems()[0].tell_anonymously("dead").unwrap();
+
+    wait_for(|| !letters("dead").is_empty());
+    assert_eq!(letters("dead"), vec![DeadLetterReason::Rejected]);
+}
+
+#[test]
+fn return_to_sender() {
+    init_start();
+
+    let rejecting = rejecting(1, RejectDisposition::ReturnToSender);
+    let target = rejecting.elems()[0].addr();
+
+    // Sends "returned" to the rejecting element and records the
+    // path of the element it is sent back by.
+    let returned = Arc::new(Mutex::new(None));
+    Bastion::children(|children| {
+        let returned = returned.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let returned = returned.clone();
+            let target = target.clone();
+            async move {
+                ctx.tell(&target, "returned").unwrap();
+                let msg = ctx.recv().await?;
+                assert_eq!(msg.msg().downcast_ref::<&'static str>(), Some(&"returned"));
+                *returned.lock().unwrap() = Some(msg.signature().path().to_string());
+                Ok(())
+            }
+        })
+    })
+    .unwrap();
+
+    wait_for(|| returned.lock().unwrap().is_some());
+    let path = returned.lock().unwrap().take().unwrap();
+    assert!(
+        path.ends_with(&rejecting.elems()[0].id().to_string()),
+        "{}",
+        path
+    );
+
+    // The messages told anonymously can't be sent back.
+    rejecting.elems()[0].tell_anonymously("anonymous").unwrap();
+    wait_for(|| !letters("anonymous").is_empty());
+    assert_eq!(letters("anonymous"), vec![DeadLetterReason::Rejected]);
+    assert_eq!(rejecting.stats().rejected(), 2);
+}
@@ -0,0 +1,50 @@
+use bastion::correlation::ReplyError;
+use bastion::errors::SendErrorKind;
+use bastion::prelude::*;
+use std::thread;
+use std::time::Duration;
+
+const TTL: Duration = Duration::from_millis(200);
+
+#[test]
+fn configured_limits() {
+    Bastion::init_with(Config::new().with_reply_broker_limits(2, TTL));
+    Bastion::start();
+
+    // Never replies.
+    let children = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                ctx.recv().await?;
+            }
+        })
+    })
+    .unwrap();
+    let child = &children.elems()[0];
+    let broker = Bastion::reply_broker();
+
+    // At most two correlation ids wait for a reply...
+    let first = child.tell_correlated(1u64).unwrap();
+    child.tell_correlated(2u64).unwrap();
+    let err = child.tell_correlated(3u64).unwrap_err();
+    assert_eq!(err.kind(), SendErrorKind::Full);
+
+    // ...until they expire, which is checked whenever a new one is
+    // registered.
+    thread::sleep(TTL);
+    let third = child.tell_correlated(3u64).unwrap();
+    assert_eq!(
+        run!(broker.wait(&first, Duration::from_millis(10))).unwrap_err(),
+        ReplyError::Unknown(first)
+    );
+
+    // The ids waited for aren't expired while they are.
+    let waiting = thread::spawn(move || run!(broker.wait(&third, 2 * TTL)));
+    thread::sleep(TTL + TTL / 2);
+    child.tell_correlated(4u64).unwrap();
+    child.tell_correlated(5u64).unwrap_err();
+    assert_eq!(waiting.join().unwrap().unwrap_err(), ReplyError::Timeout);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
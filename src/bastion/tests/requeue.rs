@@ -0,0 +1,85 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+static LETTERS: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+type Handled = Arc<Mutex<Vec<&'static str>>>;
+
+// Creates a group whose element faults once, after the messages
+// told after "fault" were queued in its mailbox.
+fn spawn_group(requeue: bool, handled: Handled) -> ChildrenRef {
+    let faulted = Arc::new(AtomicBool::new(false));
+    Bastion::children(move |children| {
+        let (faulted, handled) = (faulted.clone(), handled.clone());
+        children
+            .with_requeue_on_restart(requeue)
+            .with_exec(move |ctx: BastionContext| {
+                let (faulted, handled) = (faulted.clone(), handled.clone());
+                async move {
+                    loop {
+                        let mut faulting = false;
+                        msg! { ctx.recv().await?,
+                            msg: &'static str => {
+                                if msg == "fault" {
+                                    faulting = !faulted.swap(true, Ordering::SeqCst);
+                                } else {
+                                    handled.lock().unwrap().push(msg);
+                                }
+                            };
+                            _: _ => ();
+                        }
+
+                        if faulting {
+                            ctx.sleep(Duration::from_millis(100)).await;
+                            return Err(());
+                        }
+                    }
+                }
+            })
+    })
+    .unwrap()
+}
+
+#[test]
+fn requeue() {
+    let config = Config::new().with_dead_letter_handler(|letter: DeadLetter| {
+        if let DeadLetterPayload::Full(msg) = letter.into_payload() {
+            if let Ok(msg) = msg.downcast::<&'static str>() {
+                LETTERS.lock().unwrap().push(msg);
+            }
+        }
+    });
+    Bastion::init_with(config);
+    Bastion::start();
+
+    let requeued = Handled::default();
+    let dropped = Handled::default();
+    let groups = [
+        spawn_group(true, requeued.clone()),
+        spawn_group(false, dropped.clone()),
+    ];
+    for children in &groups {
+        let elem = &children.elems()[0];
+        elem.tell_anonymously("fault").unwrap();
+        elem.tell_anonymously("queued").unwrap();
+    }
+
+    // The restarted element handles the message its previous
+    // incarnation didn't...
+    wait_for(|| *requeued.lock().unwrap() == ["queued"]);
+
+    // ...unless its group doesn't requeue them, in which case it is
+    // a dead letter.
+    wait_for(|| groups[1].stats().restarts() == 1);
+    wait_for(|| *LETTERS.lock().unwrap() == ["queued"]);
+    groups[1].elems()[0].tell_anonymously("after").unwrap();
+    wait_for(|| *dropped.lock().unwrap() == ["after"]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,154 @@
+#![cfg(feature = "autoscaling")]
+
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+// Counts the running elements, and the most elements that ran at
+// once.
+#[derive(Default)]
+struct Running {
+    now: AtomicUsize,
+    max: AtomicUsize,
+}
+
+struct Guard(Arc<Running>);
+
+impl Guard {
+    fn new(running: Arc<Running>) -> Self {
+        let now = running.now.fetch_add(1, Ordering::SeqCst) + 1;
+        running.max.fetch_max(now, Ordering::SeqCst);
+        Guard(running)
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        self.0.now.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+// Creates a group whose elements take `delay` to handle each
+// message, counting them in `handled`.
+fn group(
+    redundancy: usize,
+    resizer: Resizer,
+    delay: Duration,
+    running: Arc<Running>,
+    handled: Arc<AtomicUsize>,
+) -> ChildrenRef {
+    Bastion::children(move |children| {
+        let running = running.clone();
+        let handled = handled.clone();
+        children
+            .with_redundancy(redundancy)
+            .with_resizer(resizer.clone())
+            .with_exec(move |ctx: BastionContext| {
+                let guard = Guard::new(running.clone());
+                let handled = handled.clone();
+                async move {
+                    let _guard = guard;
+                    loop {
+                        msg! { ctx.recv().await?,
+                            _n: u64 => {
+                                ctx.sleep(delay).await;
+                                handled.fetch_add(1, Ordering::SeqCst);
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .unwrap()
+}
+
+#[test]
+fn scaled_up_within_bounds() {
+    init_start();
+
+    let running = Arc::new(Running::default());
+    let handled = Arc::new(AtomicUsize::new(0));
+    let resizer = Resizer::new()
+        .lower(1)
+        .upper(4)
+        .sample_every(Duration::from_millis(10))
+        .scale_up_when(|pressure| pressure.avg_mailbox > 5);
+    let children = group(
+        1,
+        resizer,
+        Duration::from_millis(5),
+        running.clone(),
+        handled.clone(),
+    );
+
+    let child = children.elems()[0].clone();
+    for n in 0..1_000u64 {
+        child.tell_anonymously(n).unwrap();
+    }
+
+    // The group is scaled up while the element is under pressure,
+    // but never above its upper bound.
+    wait_for(|| running.now.load(Ordering::SeqCst) == 4);
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(running.max.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn scaled_down_once_idle() {
+    init_start();
+
+    let running = Arc::new(Running::default());
+    let handled = Arc::new(AtomicUsize::new(0));
+    let resizer = Resizer::default().sample_every(Duration::from_millis(10));
+    let children = group(
+        4,
+        resizer,
+        Duration::from_millis(1),
+        running.clone(),
+        handled.clone(),
+    );
+    wait_for(|| running.now.load(Ordering::SeqCst) == 4);
+
+    // Each element handles the messages it was told before being
+    // stopped.
+    for child in children.elems() {
+        for n in 0..50u64 {
+            child.tell_anonymously(n).unwrap();
+        }
+    }
+
+    wait_for(|| running.now.load(Ordering::SeqCst) == 1);
+    wait_for(|| handled.load(Ordering::SeqCst) == 200);
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(running.now.load(Ordering::SeqCst), 1);
+    assert_eq!(handled.load(Ordering::SeqCst), 200);
+}
+
+#[test]
+fn scaled_up_to_lower_bound() {
+    init_start();
+
+    let running = Arc::new(Running::default());
+    let handled = Arc::new(AtomicUsize::new(0));
+    let resizer = Resizer::new()
+        .lower(3)
+        .upper(5)
+        .sample_every(Duration::from_millis(10));
+    group(
+        1,
+        resizer,
+        Duration::from_millis(1),
+        running.clone(),
+        handled,
+    );
+
+    wait_for(|| running.now.load(Ordering::SeqCst) == 3);
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(running.max.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const GROUPS: usize = 3;
+
+// Broadcasted to make the elements of the group with this index
+// panic.
+#[derive(Debug)]
+struct Panic(usize);
+
+// Configures the group with this index, counting the starts of
+// its element.
+fn counting(children: Children, index: usize, starts: &Arc<AtomicUsize>) -> Children {
+    let starts = starts.clone();
+    children.with_exec(move |ctx: BastionContext| {
+        let starts = starts.clone();
+        async move {
+            starts.fetch_add(1, Ordering::SeqCst);
+
+            loop {
+                msg! { ctx.recv().await?,
+                    ref msg: Panic => {
+                        if msg.0 == index {
+                            panic!("Panicking as asked.");
+                        }
+                    };
+                    _: _ => ();
+                }
+            }
+        }
+    })
+}
+
+// Makes the group with this index panic and returns how many times
+// the element of each group was started once the restarts settled.
+fn panic(group: usize, starts: &[Arc<AtomicUsize>]) -> Vec<usize> {
+    let restarted = starts[group].load(Ordering::SeqCst) + 1;
+    Bastion::broadcast(Panic(group)).unwrap();
+    wait_for(|| starts[group].load(Ordering::SeqCst) == restarted);
+    thread::sleep(Duration::from_millis(200));
+
+    starts
+        .iter()
+        .map(|starts| starts.load(Ordering::SeqCst))
+        .collect()
+}
+
+#[test]
+fn rest_for_one() {
+    Bastion::init();
+    Bastion::start();
+
+    let starts = (0..GROUPS)
+        .map(|_| Arc::new(AtomicUsize::new(0)))
+        .collect::<Vec<_>>();
+    let counters = starts.clone();
+    Bastion::supervisor(move |sp| {
+        let sp = sp.with_strategy(SupervisionStrategy::RestForOne);
+        counters.iter().enumerate().fold(sp, |sp, (index, starts)| {
+            sp.children(|children| counting(children, index, starts))
+        })
+    })
+    .unwrap();
+    wait_for(|| {
+        starts
+            .iter()
+            .all(|starts| starts.load(Ordering::SeqCst) == 1)
+    });
+
+    // The middle group and the one launched after it are restarted...
+    assert_eq!(panic(1, &starts), vec![1, 2, 2]);
+    // ...keeping their position once restarted...
+    assert_eq!(panic(1, &starts), vec![1, 3, 3]);
+    // ...so that only the last one is restarted when it panics...
+    assert_eq!(panic(2, &starts), vec![1, 3, 4]);
+    // ...and all of them when the first one does.
+    assert_eq!(panic(0, &starts), vec![2, 4, 5]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
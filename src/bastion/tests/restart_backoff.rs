@@ -0,0 +1,174 @@
+#![cfg(feature = "supervision")]
+mod common;
+
+use bastion::children_ref::GroupState;
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+// Configures a group recording when its element starts, which runs
+// for `runs[n]` before faulting the `n`-th time it is started, and
+// keeps on running once it was started `runs.len()` times.
+fn faulting(
+    children: Children,
+    runs: Vec<Duration>,
+    starts: &Arc<Mutex<Vec<Instant>>>,
+) -> Children {
+    let starts = starts.clone();
+    children.with_exec(move |ctx: BastionContext| {
+        let starts = starts.clone();
+        let runs = runs.clone();
+        async move {
+            let started = {
+                let mut starts = starts.lock().unwrap();
+                starts.push(Instant::now());
+                starts.len()
+            };
+
+            match runs.get(started - 1) {
+                Some(run) => {
+                    ctx.sleep(*run).await;
+                    Err(())
+                }
+                None => loop {
+                    ctx.recv().await?;
+                },
+            }
+        }
+    })
+}
+
+// Returns the delays between the consecutive starts, minus how long
+// the element ran for before faulting.
+fn delays(starts: &Arc<Mutex<Vec<Instant>>>, runs: &[Duration]) -> Vec<Duration> {
+    let starts = starts.lock().unwrap();
+    starts
+        .windows(2)
+        .zip(runs)
+        .map(|(starts, run)| starts[1].duration_since(starts[0]) - *run)
+        .collect()
+}
+
+#[test]
+fn grows_up_to_max() {
+    init_start();
+
+    let backoff = Backoff::exponential(ms(50), ms(200), 0.0).with_reset_after(ms(1_000));
+    let runs = vec![Duration::default(); 4];
+    let starts = Arc::new(Mutex::new(Vec::new()));
+    let children = Bastion::children(|children| {
+        faulting(children, runs.clone(), &starts).with_restart_backoff(backoff.clone())
+    })
+    .unwrap();
+    wait_for(|| children.policies().restart_backoff() == Some(&backoff));
+
+    wait_for(|| starts.lock().unwrap().len() == 5);
+    let delays = delays(&starts, &runs);
+
+    // Each restart waits twice as long as the previous one...
+    for (delay, expected) in delays.iter().zip(&[50, 100, 200, 200]) {
+        assert!(*delay >= ms(*expected), "{:?}", delays);
+    }
+    assert!(delays[0] < ms(200), "{:?}", delays);
+    // ...up to the back-off's maximum.
+    assert!(delays[3] < ms(400), "{:?}", delays);
+}
+
+#[test]
+fn resets_after_long_run() {
+    init_start();
+
+    let backoff = Backoff::exponential(ms(50), ms(400), 0.0).with_reset_after(ms(300));
+    // The third run lasts longer than the reset threshold.
+    let runs = vec![ms(0), ms(0), ms(500), ms(0)];
+    let starts = Arc::new(Mutex::new(Vec::new()));
+    let children = Bastion::children(|children| {
+        faulting(children, runs.clone(), &starts).with_restart_backoff(backoff)
+    })
+    .unwrap();
+
+    wait_for(|| starts.lock().unwrap().len() == 5);
+    let delays = delays(&starts, &runs);
+
+    assert!(delays[0] >= ms(50), "{:?}", delays);
+    assert!(delays[1] >= ms(100), "{:?}", delays);
+    // The restart following the long run isn't delayed by 200ms...
+    assert!(delays[2] >= ms(50), "{:?}", delays);
+    assert!(delays[2] < ms(200), "{:?}", delays);
+    // ...and the next one is consecutive to it again.
+    assert!(delays[3] >= ms(100), "{:?}", delays);
+    assert_eq!(children.stats().restarts(), 4);
+}
+
+#[test]
+fn inherited_from_supervisor() {
+    init_start();
+
+    let backoff = Backoff::exponential(ms(100), ms(100), 0.0);
+    let starts = Arc::new(Mutex::new(Vec::new()));
+    let mut children = None;
+    Bastion::supervisor(|sp| {
+        let sp = sp.with_restart_backoff(backoff.clone());
+        children = Some(
+            sp.children_ref(|children| faulting(children, vec![ms(0)], &starts))
+                .unwrap(),
+        );
+        sp
+    })
+    .unwrap();
+    let children = children.unwrap();
+
+    wait_for(|| starts.lock().unwrap().len() == 2);
+    assert!(delays(&starts, &[ms(0)])[0] >= ms(100));
+    wait_for(|| children.policies().restart_backoff() == Some(&backoff));
+}
+
+#[test]
+fn replaces_restart_strategy() {
+    init_start();
+
+    let backoff = Backoff::exponential(ms(50), ms(50), 0.0);
+    let starts = Arc::new(Mutex::new(Vec::new()));
+    Bastion::supervisor(|sp| {
+        let strategy = ActorRestartStrategy::LinearBackOff {
+            timeout: Duration::from_secs(1),
+        };
+        sp.with_restart_strategy(RestartStrategy::new(RestartPolicy::Always, strategy))
+            .children(|children| {
+                faulting(children, vec![ms(0)], &starts).with_restart_backoff(backoff)
+            })
+    })
+    .unwrap();
+
+    // The restart is only delayed by the group's back-off.
+    wait_for(|| starts.lock().unwrap().len() == 2);
+    let delay = delays(&starts, &[ms(0)])[0];
+    assert!(delay >= ms(50), "{:?}", delay);
+    assert!(delay < ms(1_000), "{:?}", delay);
+}
+
+#[test]
+fn cancelled_on_stop() {
+    init_start();
+
+    let backoff = Backoff::exponential(ms(200), ms(200), 0.0);
+    let starts = Arc::new(Mutex::new(Vec::new()));
+    let children = Bastion::children(|children| {
+        faulting(children, vec![ms(0)], &starts).with_restart_backoff(backoff)
+    })
+    .unwrap();
+    wait_for(|| matches!(children.state(), GroupState::Restarting { .. }));
+
+    // The group stops while its element waits to be restarted, and
+    // it isn't restarted afterwards.
+    children.stop().unwrap();
+    wait_for(|| children.state() == GroupState::Stopped);
+    thread::sleep(ms(400));
+    assert_eq!(starts.lock().unwrap().len(), 1);
+}
@@ -0,0 +1,115 @@
+#![cfg(feature = "supervision")]
+mod common;
+
+use bastion::children_ref::GroupState;
+use bastion::prelude::*;
+use common::wait_for;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const MAX_RESTARTS: usize = 3;
+
+// Creates a supervisor supervised by another one and allowing
+// `MAX_RESTARTS` restarts `within`, which supervises a group
+// whose element counts its starts and faults when `faulting`
+// returns true. Returns the group and whether the supervisor
+// stopped.
+fn supervised(
+    within: Duration,
+    starts: &Arc<AtomicUsize>,
+    faulting: fn(&'static str) -> bool,
+) -> (ChildrenRef, Arc<AtomicBool>) {
+    let stopped = Arc::new(AtomicBool::new(false));
+    let mut children = None;
+
+    let (starts, stopped_inner) = (starts.clone(), stopped.clone());
+    Bastion::supervisor(|parent| {
+        parent.supervisor(|sp| {
+            let callbacks = Callbacks::new().with_after_stop(move || {
+                stopped_inner.store(true, Ordering::SeqCst);
+            });
+            let sp = sp
+                .with_restart_window(MAX_RESTARTS, within)
+                .with_callbacks(callbacks);
+
+            children = Some(
+                sp.children_ref(move |children| {
+                    children.with_exec(move |ctx: BastionContext| {
+                        let starts = starts.clone();
+                        async move {
+                            starts.fetch_add(1, Ordering::SeqCst);
+                            if faulting("start") {
+                                return Err(());
+                            }
+
+                            loop {
+                                msg! { ctx.recv().await?,
+                                    ref msg: &'static str => {
+                                        if faulting(msg) {
+                                            return Err(());
+                                        }
+                                    };
+                                    _: _ => ();
+                                }
+                            }
+                        }
+                    })
+                })
+                .unwrap(),
+            );
+            sp
+        })
+    })
+    .unwrap();
+
+    (children.unwrap(), stopped)
+}
+
+#[test]
+fn restart_window() {
+    Bastion::init();
+    Bastion::start();
+
+    // An element always faulting is restarted exactly
+    // `MAX_RESTARTS` times...
+    let starts = Arc::new(AtomicUsize::new(0));
+    let (children, stopped) = supervised(Duration::from_secs(60), &starts, |_| true);
+
+    // ...before its group gets killed and its supervisor faults.
+    wait_for(|| stopped.load(Ordering::SeqCst));
+    wait_for(|| children.state() == GroupState::Stopped);
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(starts.load(Ordering::SeqCst), MAX_RESTARTS + 1);
+
+    // The restarts stop being counted once the window elapsed...
+    let within = Duration::from_millis(300);
+    let starts = Arc::new(AtomicUsize::new(0));
+    let (children, stopped) = supervised(within, &starts, |msg| msg == "fault");
+    wait_for(|| starts.load(Ordering::SeqCst) == 1);
+
+    for restarts in 1..=2 * MAX_RESTARTS {
+        children.broadcast("fault").unwrap();
+        wait_for(|| starts.load(Ordering::SeqCst) == restarts + 1);
+
+        if restarts % MAX_RESTARTS == 0 {
+            thread::sleep(within * 2);
+        }
+    }
+    assert!(!stopped.load(Ordering::SeqCst));
+
+    // ...but too many restarts in a row still exceed it.
+    for restarts in 1..=MAX_RESTARTS {
+        children.broadcast("fault").unwrap();
+        let starts_before = 2 * MAX_RESTARTS + 1;
+        wait_for(|| starts.load(Ordering::SeqCst) == starts_before + restarts);
+    }
+    children.broadcast("fault").unwrap();
+    wait_for(|| stopped.load(Ordering::SeqCst));
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(starts.load(Ordering::SeqCst), 3 * MAX_RESTARTS + 1);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
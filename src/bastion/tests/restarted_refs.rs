@@ -0,0 +1,67 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+// A group whose elements count the messages they handle, and
+// return an error on "error".
+fn counting(children: Children, handled: Arc<AtomicUsize>) -> Children {
+    children.with_exec(move |ctx: BastionContext| {
+        let handled = handled.clone();
+        async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    msg: &'static str => {
+                        handled.fetch_add(1, Ordering::SeqCst);
+                        if msg == "error" {
+                            return Err(());
+                        }
+                    };
+                    _: _ => ();
+                }
+            }
+        }
+    })
+}
+
+#[test]
+fn restarted_elem() {
+    init_start();
+
+    let handled = Arc::new(AtomicUsize::new(0));
+    let counted = handled.clone();
+    let children = Bastion::children(move |children| counting(children, counted.clone())).unwrap();
+    let elem = children.elems()[0].clone();
+
+    elem.tell_anonymously("error").unwrap();
+    wait_for(|| elem.stats().restarts() == 1);
+
+    // The reference retrieved before the restart reaches the
+    // restarted element.
+    elem.tell_anonymously("hello").unwrap();
+    wait_for(|| handled.load(Ordering::SeqCst) == 2);
+}
+
+#[test]
+fn restarted_group() {
+    init_start();
+
+    let handled = Arc::new(AtomicUsize::new(0));
+    let counted = handled.clone();
+    let supervisor =
+        Bastion::supervisor(|sp| sp.with_strategy(SupervisionStrategy::OneForAll)).unwrap();
+    let children = supervisor
+        .children(move |children| counting(children.with_redundancy(3), counted.clone()))
+        .unwrap();
+    let elems = children.elems().to_vec();
+
+    elems[0].tell_anonymously("error").unwrap();
+    wait_for(|| elems.iter().all(|elem| elem.stats().restarts() == 1));
+
+    for elem in &elems {
+        elem.tell_anonymously("hello").unwrap();
+    }
+    wait_for(|| handled.load(Ordering::SeqCst) == 4);
+}
@@ -0,0 +1,202 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use futures::channel::oneshot;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+// The `u64`s handled by the elements of a group, along with the
+// id of the element that handled them.
+type Handled = Arc<Mutex<Vec<(BastionId, u64)>>>;
+
+// Stalls the element told `"stall"` until its sender is dropped.
+type Stall = Arc<Mutex<Option<oneshot::Receiver<()>>>>;
+
+// Creates a group of elements recording the `u64`s they handle,
+// which stall once told `"stall"`.
+fn recording(
+    redundancy: usize,
+    stalled: Stall,
+    configure: impl Fn(Children) -> Children + Send + Sync + 'static,
+) -> (ChildrenRef, Handled) {
+    let handled = Handled::default();
+    let recorded = handled.clone();
+    let children = Bastion::children(move |children| {
+        let recorded = recorded.clone();
+        let stalled = stalled.clone();
+        configure(
+            children
+                .with_redundancy(redundancy)
+                .with_exec(move |ctx: BastionContext| {
+                    let recorded = recorded.clone();
+                    let stalled = stalled.clone();
+                    async move {
+                        let id = ctx.current().id().clone();
+                        loop {
+                            msg! { ctx.recv().await?,
+                                n: u64 => {
+                                    recorded.lock().unwrap().push((id.clone(), n));
+                                };
+                                _stall: &'static str => {
+                                    let stall = stalled.lock().unwrap().take();
+                                    if let Some(stall) = stall {
+                                        stall.await.ok();
+                                    }
+                                };
+                                _: _ => ();
+                            }
+                        }
+                    }
+                }),
+        )
+    })
+    .unwrap();
+
+    wait_for(|| children.warm_elems() == redundancy);
+    (children, handled)
+}
+
+fn count(handled: &Handled, id: &BastionId) -> usize {
+    handled
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(handler, _)| handler == id)
+        .count()
+}
+
+#[test]
+fn pinned() {
+    init_start();
+
+    let (children, handled) = recording(3, Arc::default(), |children| children);
+    let ids = children
+        .elems()
+        .iter()
+        .map(|elem| elem.id().clone())
+        .collect::<Vec<_>>();
+    assert_eq!(children.routing(), Routing::RoundRobin);
+
+    for n in 0..30u64 {
+        children.tell_one(n).unwrap();
+    }
+    wait_for(|| handled.lock().unwrap().len() == 30);
+    assert!(ids.iter().all(|id| count(&handled, id) == 10));
+
+    // The routing is changed for the group's other references too.
+    let other = children.clone();
+    run!(children.set_routing(Routing::Pinned(ids[1].clone()))).unwrap();
+    assert_eq!(other.routing(), Routing::Pinned(ids[1].clone()));
+    for n in 0..30u64 {
+        other.tell_one(n).unwrap();
+    }
+    wait_for(|| handled.lock().unwrap().len() == 60);
+    assert_eq!(count(&handled, &ids[0]), 10);
+    assert_eq!(count(&handled, &ids[1]), 40);
+    assert_eq!(count(&handled, &ids[2]), 10);
+
+    #[cfg(feature = "introspection")]
+    {
+        let tree = Bastion::tree();
+        let group = tree.supervisors()[0]
+            .groups()
+            .iter()
+            .find(|group| group.id() == children.id())
+            .unwrap();
+        assert_eq!(group.routing(), &Routing::Pinned(ids[1].clone()));
+    }
+
+    // The group doesn't change its routing if it can't.
+    let (unknown, _) = recording(1, Arc::default(), |children| children);
+    let unknown = unknown.elems()[0].id().clone();
+    assert_eq!(
+        run!(children.set_routing(Routing::Pinned(unknown.clone()))),
+        Err(RoutingError::UnknownElement(unknown))
+    );
+    assert_eq!(
+        run!(children.set_routing(Routing::HashBy)),
+        Err(RoutingError::MissingKey)
+    );
+    assert_eq!(children.routing(), Routing::Pinned(ids[1].clone()));
+}
+
+#[test]
+fn least_loaded() {
+    init_start();
+
+    let (release, stall) = oneshot::channel::<()>();
+    let stalled = Arc::new(Mutex::new(Some(stall)));
+    let (children, handled) = recording(2, stalled, |children| children);
+    let busy = children.elems()[0].clone();
+    let idle = children.elems()[1].id().clone();
+    busy.tell_anonymously("stall").unwrap();
+    wait_for(|| busy.mailbox_len() == 0);
+
+    // Half of the messages are kept in the stalled element's
+    // mailbox...
+    for n in 0..20u64 {
+        children.tell_one(n).unwrap();
+    }
+    wait_for(|| count(&handled, &idle) == 10);
+    assert_eq!(busy.mailbox_len(), 10);
+
+    // ...while the other element gets all of them once the least
+    // loaded element is picked.
+    run!(children.set_routing(Routing::LeastLoaded)).unwrap();
+    for n in 0..5u64 {
+        children.tell_one(n).unwrap();
+    }
+    wait_for(|| count(&handled, &idle) == 15);
+    assert_eq!(busy.mailbox_len(), 10);
+
+    drop(release);
+    wait_for(|| handled.lock().unwrap().len() == 25);
+    assert_eq!(count(&handled, busy.id()), 10);
+}
+
+#[test]
+fn hashed_by_key() {
+    init_start();
+
+    let (children, handled) = recording(3, Arc::default(), |children| {
+        children.with_routing_key(|n: &u64| *n)
+    });
+
+    // Returns the number of elements that handled each key.
+    let spread = |handled: &Handled| {
+        (0..3u64)
+            .map(|key| {
+                let handled = handled.lock().unwrap();
+                handled
+                    .iter()
+                    .filter(|(_, n)| *n == key)
+                    .map(|(id, _)| id.clone())
+                    .collect::<HashSet<_>>()
+                    .len()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    run!(children.set_routing(Routing::HashBy)).unwrap();
+    assert_eq!(children.routing(), Routing::HashBy);
+    for _ in 0..6 {
+        for key in 0..3u64 {
+            children.tell_one(key).unwrap();
+        }
+    }
+    wait_for(|| handled.lock().unwrap().len() == 18);
+    assert_eq!(spread(&handled), vec![1, 1, 1]);
+
+    // Each key is handled by every element once the messages are
+    // sent to each of them in turn again.
+    handled.lock().unwrap().clear();
+    run!(children.set_routing(Routing::RoundRobin)).unwrap();
+    for _ in 0..3 {
+        for key in 0..4u64 {
+            children.tell_one(key).unwrap();
+        }
+    }
+    wait_for(|| handled.lock().unwrap().len() == 12);
+    assert_eq!(spread(&handled), vec![3, 3, 3]);
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use bastion::children_ref::GroupState;
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+type Running = Arc<Mutex<BTreeSet<usize>>>;
+
+// Held by the future of the element with this index while it runs.
+struct Alive(usize, Running);
+
+impl Drop for Alive {
+    fn drop(&mut self) {
+        self.1.lock().unwrap().remove(&self.0);
+    }
+}
+
+// A group of elements recording the indexes of the running ones
+// and counting the messages they receive.
+fn running(name: &str, redundancy: usize, received: &Arc<AtomicUsize>) -> (ChildrenRef, Running) {
+    let running = Running::default();
+    let elems = running.clone();
+    let received = received.clone();
+    let children = Bastion::children(move |children| {
+        children
+            .with_name(name)
+            .with_redundancy(redundancy)
+            .with_exec(move |ctx: BastionContext| {
+                let elems = elems.clone();
+                let received = received.clone();
+                async move {
+                    let index = ctx.elem_index();
+                    elems.lock().unwrap().insert(index);
+                    let _alive = Alive(index, elems);
+
+                    loop {
+                        ctx.recv().await?;
+                        received.fetch_add(1, Ordering::SeqCst);
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    (children, running)
+}
+
+fn indexes(running: &Running) -> Vec<usize> {
+    running.lock().unwrap().iter().copied().collect()
+}
+
+#[test]
+fn grows_and_shrinks() {
+    init_start();
+
+    let received = Arc::new(AtomicUsize::new(0));
+    let (children, running) = running("scaled", 2, &received);
+    wait_for(|| indexes(&running) == vec![0, 1]);
+
+    let scaled = run!(children.scale(4)).unwrap();
+    assert_eq!(scaled.elems().len(), 4);
+    wait_for(|| indexes(&running) == vec![0, 1, 2, 3]);
+
+    // The newest elements are stopped first.
+    let scaled = run!(children.scale(1)).unwrap();
+    assert_eq!(scaled.elems().len(), 1);
+    wait_for(|| indexes(&running) == vec![0]);
+
+    let registered = Bastion::children_of("scaled").unwrap();
+    assert_eq!(registered.elems().len(), 1);
+    assert_eq!(registered.elems()[0].id(), scaled.elems()[0].id());
+}
+
+#[test]
+fn paused() {
+    init_start();
+
+    let received = Arc::new(AtomicUsize::new(0));
+    let (children, running) = running("paused", 1, &received);
+    wait_for(|| indexes(&running) == vec![0]);
+
+    let scaled = run!(children.scale(0)).unwrap();
+    assert!(scaled.elems().is_empty());
+    wait_for(|| indexes(&running).is_empty());
+    assert_eq!(scaled.state(), GroupState::Running);
+
+    // The routed messages are kept until the group is scaled up.
+    scaled.tell_one(1u8).unwrap();
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(received.load(Ordering::SeqCst), 0);
+
+    run!(scaled.scale(1)).unwrap();
+    wait_for(|| received.load(Ordering::SeqCst) == 1);
+}
@@ -0,0 +1,178 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Once};
+use std::thread;
+use std::time::{Duration, Instant};
+
+static START: Once = Once::new();
+
+// The payloads of the dead letters of type `&'static str`.
+static DEAD_LETTERS: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+fn init_start() {
+    START.call_once(|| {
+        let config = Config::new().with_dead_letter_handler(|letter: DeadLetter| {
+            if letter.reason() != DeadLetterReason::RecipientStopped {
+                return;
+            }
+            if let DeadLetterPayload::Full(msg) = letter.into_payload() {
+                if let Ok(msg) = msg.downcast::<&'static str>() {
+                    DEAD_LETTERS.lock().unwrap().push(msg);
+                }
+            }
+        });
+        Bastion::init_with(config);
+        Bastion::start();
+    });
+}
+
+type Received = Arc<Mutex<Vec<(&'static str, Instant)>>>;
+
+// A group whose element records the `&'static str`s it receives
+// along with when it received them, stops on "stop" and faults on
+// "fault".
+fn recording() -> (ChildRef, Received) {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let recorded = received.clone();
+    let children = Bastion::children(move |children| {
+        let recorded = recorded.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let recorded = recorded.clone();
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        msg: &'static str => {
+                            recorded.lock().unwrap().push((msg, Instant::now()));
+                            match msg {
+                                "stop" => return Ok(()),
+                                "fault" => return Err(()),
+                                _ => (),
+                            }
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    (children.elems()[0].clone(), received)
+}
+
+#[test]
+fn tell_after() {
+    init_start();
+
+    let (child, received) = recording();
+    let sent = Instant::now();
+    let handle = child
+        .tell_after("delayed", Duration::from_millis(100))
+        .unwrap();
+    child.tell_anonymously("now").unwrap();
+
+    wait_for(|| received.lock().unwrap().len() == 2);
+    let received = received.lock().unwrap();
+    assert_eq!(received[0].0, "now");
+    assert_eq!(received[1].0, "delayed");
+    assert!(received[1].1 - sent >= Duration::from_millis(100));
+    assert!(!handle.is_cancelled());
+}
+
+#[test]
+fn cancel() {
+    init_start();
+
+    let (child, received) = recording();
+    let handle = child
+        .tell_after("cancelled", Duration::from_millis(50))
+        .unwrap();
+    handle.cancel();
+    assert!(handle.is_cancelled());
+
+    thread::sleep(Duration::from_millis(150));
+    child.tell_anonymously("after").unwrap();
+    wait_for(|| !received.lock().unwrap().is_empty());
+    thread::sleep(Duration::from_millis(10));
+    let received: Vec<_> = received
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(msg, _)| *msg)
+        .collect();
+    assert_eq!(received, vec!["after"]);
+}
+
+#[test]
+fn dead_recipient() {
+    init_start();
+
+    let (child, received) = recording();
+    let handle = child
+        .tell_after("too late", Duration::from_secs(3600))
+        .unwrap();
+    child.tell_anonymously("stop").unwrap();
+
+    // The timer is dropped once its recipient terminated, the
+    // message being sent to the dead letters.
+    wait_for(|| handle.is_cancelled());
+    wait_for(|| DEAD_LETTERS.lock().unwrap().contains(&"too late"));
+    assert_eq!(received.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn restarted_recipient() {
+    init_start();
+
+    let (child, received) = recording();
+    child
+        .tell_after("restarted", Duration::from_millis(200))
+        .unwrap();
+    child.tell_anonymously("fault").unwrap();
+
+    // The message is received by the restarted element.
+    wait_for(|| received.lock().unwrap().len() == 2);
+    assert_eq!(received.lock().unwrap()[1].0, "restarted");
+    assert!(!DEAD_LETTERS.lock().unwrap().contains(&"restarted"));
+}
+
+#[derive(Debug)]
+struct Tick;
+
+#[test]
+fn periodic() {
+    init_start();
+
+    let ticks = Arc::new(AtomicUsize::new(0));
+    let counted = ticks.clone();
+    Bastion::children(move |children| {
+        let counted = counted.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let counted = counted.clone();
+            async move {
+                let handle = ctx.schedule_periodic(|| Tick, Duration::from_millis(10));
+                loop {
+                    msg! { ctx.recv().await?,
+                        _tick: Tick => {
+                            if counted.fetch_add(1, Ordering::SeqCst) + 1 == 3 {
+                                handle.cancel();
+                            }
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    wait_for(|| ticks.load(Ordering::SeqCst) >= 3);
+    // A tick might have been sent before the handle was cancelled.
+    thread::sleep(Duration::from_millis(50));
+    let cancelled = ticks.load(Ordering::SeqCst);
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(ticks.load(Ordering::SeqCst), cancelled);
+}
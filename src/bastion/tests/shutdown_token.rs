@@ -0,0 +1,78 @@
+mod common;
+
+use bastion::children_ref::GroupState;
+use bastion::prelude::*;
+use common::{init_start, wait_for, wait_for_value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn compute_loop_exits_on_stop() {
+    init_start();
+
+    let started = Arc::new(AtomicBool::new(false));
+    let exited = Arc::new(AtomicBool::new(false));
+    let recorded = (started.clone(), exited.clone());
+    let children = Bastion::children(move |children| {
+        let recorded = recorded.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let (started, exited) = recorded.clone();
+            async move {
+                started.store(true, Ordering::SeqCst);
+
+                // The element is busy computing until it is asked
+                // to stop (as it doesn't wait for messages, it would
+                // otherwise be killed once the timeout expired).
+                let token = ctx.shutdown_token();
+                blocking!(while !token.is_triggered() {
+                    thread::sleep(Duration::from_millis(1));
+                });
+
+                exited.store(true, Ordering::SeqCst);
+                Ok(())
+            }
+        })
+    })
+    .unwrap();
+    wait_for(|| started.load(Ordering::SeqCst));
+
+    let state = run!(children.stop_with_timeout(Duration::from_secs(10))).unwrap();
+    assert_eq!(state, GroupState::Stopped);
+    assert!(exited.load(Ordering::SeqCst));
+}
+
+#[test]
+fn triggered_before_cancellation() {
+    init_start();
+
+    let slot = Arc::new(Mutex::new(None));
+    let slot_inner = slot.clone();
+    let children = Bastion::children(move |children| {
+        let slot = slot_inner.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let slot = slot.clone();
+            async move {
+                let tokens = (ctx.shutdown_token(), ctx.cancellation_token());
+                slot.lock().unwrap().get_or_insert(tokens);
+
+                loop {
+                    ctx.recv().await?;
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    let (shutdown, cancellation) = wait_for_value(|| slot.lock().unwrap().clone());
+    assert!(!shutdown.is_triggered());
+
+    let waiting = shutdown.clone();
+    let awaited = thread::spawn(move || run!(waiting));
+
+    children.kill().unwrap();
+    awaited.join().unwrap();
+    wait_for(|| cancellation.is_cancelled());
+    assert!(shutdown.is_triggered());
+}
@@ -0,0 +1,50 @@
+#![cfg(all(unix, feature = "signals"))]
+use bastion::prelude::*;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[test]
+fn drains_on_sigterm() {
+    Bastion::init();
+    Bastion::start();
+    Bastion::handle_signals(ShutdownConfig::new().with_deadline(Duration::from_secs(5)));
+
+    let handled = Arc::new(AtomicUsize::new(0));
+    let counted = handled.clone();
+    let children = Bastion::children(move |children| {
+        let counted = counted.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let counted = counted.clone();
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        _msg: u32 => {
+                            ctx.sleep(Duration::from_millis(20)).await;
+                            counted.fetch_add(1, Ordering::SeqCst);
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    for n in 0..10u32 {
+        children.elems()[0].tell_anonymously(n).unwrap();
+    }
+
+    let status = Command::new("kill")
+        .arg("-TERM")
+        .arg(std::process::id().to_string())
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    // The element handles the messages left in its mailbox before
+    // the system stops.
+    Bastion::block_until_stopped();
+    assert_eq!(handled.load(Ordering::SeqCst), 10);
+}
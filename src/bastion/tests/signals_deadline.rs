@@ -0,0 +1,34 @@
+#![cfg(all(unix, feature = "signals"))]
+use bastion::prelude::*;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+#[test]
+fn killed_after_deadline() {
+    Bastion::init();
+    Bastion::start();
+    Bastion::handle_signals(ShutdownConfig::new().with_deadline(Duration::from_millis(200)));
+
+    // An element which never stops by itself.
+    Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                ctx.sleep(Duration::from_secs(1)).await;
+            }
+        })
+    })
+    .unwrap();
+
+    let started = Instant::now();
+    let status = Command::new("kill")
+        .arg("-INT")
+        .arg(std::process::id().to_string())
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    Bastion::block_until_stopped();
+    let elapsed = started.elapsed();
+    assert!(elapsed >= Duration::from_millis(200), "{:?}", elapsed);
+    assert!(elapsed < Duration::from_secs(5), "{:?}", elapsed);
+}
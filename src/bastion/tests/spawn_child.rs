@@ -0,0 +1,105 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+struct Workers {
+    // The connections whose worker was started, once per start.
+    started: Mutex<Vec<u32>>,
+    spawned: Mutex<Vec<ChildRef>>,
+}
+
+// A group whose element spawns a worker for each connection it is
+// told, which faults the first time it is started if `faulting`.
+fn acceptor(workers: &Arc<Workers>, faulting: bool) -> ChildrenRef {
+    let workers = workers.clone();
+    Bastion::children(|children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let workers = workers.clone();
+            async move {
+                loop {
+                    let conn = msg! { ctx.recv().await?,
+                        conn: u32 => conn;
+                        _: _ => continue;
+                    };
+
+                    let spawning = workers.clone();
+                    let worker = ctx
+                        .spawn_child(move |ctx: BastionContext| {
+                            let workers = spawning.clone();
+                            async move {
+                                let restarted = {
+                                    let mut started = workers.started.lock().unwrap();
+                                    let restarted = started.contains(&conn);
+                                    started.push(conn);
+                                    restarted
+                                };
+                                if faulting && !restarted {
+                                    return Err(());
+                                }
+
+                                loop {
+                                    ctx.recv().await?;
+                                }
+                            }
+                        })
+                        .await
+                        .unwrap();
+                    workers.spawned.lock().unwrap().push(worker);
+                }
+            }
+        })
+    })
+    .unwrap()
+}
+
+#[test]
+fn worker_per_connection() {
+    init_start();
+
+    let workers = Arc::new(Workers::default());
+    let children = acceptor(&workers, true);
+    for conn in 0..3u32 {
+        children.elems()[0].tell_anonymously(conn).unwrap();
+    }
+
+    // Each worker is restarted by running its own future again.
+    wait_for(|| workers.started.lock().unwrap().len() == 6);
+    let mut started = workers.started.lock().unwrap().clone();
+    started.sort_unstable();
+    assert_eq!(started, vec![0, 0, 1, 1, 2, 2]);
+
+    let spawned = workers.spawned.lock().unwrap().clone();
+    assert_eq!(spawned.len(), 3);
+    for (i, worker) in spawned.iter().enumerate() {
+        assert!(spawned[i + 1..]
+            .iter()
+            .all(|other| other.id() != worker.id()));
+        assert_ne!(worker.id(), children.elems()[0].id());
+    }
+}
+
+#[test]
+fn stopped_with_group() {
+    init_start();
+
+    let workers = Arc::new(Workers::default());
+    let children = acceptor(&workers, false);
+    for conn in 0..2u32 {
+        children.elems()[0].tell_anonymously(conn).unwrap();
+    }
+    wait_for(|| workers.spawned.lock().unwrap().len() == 2);
+
+    // The spawned workers terminate along with the acceptor, and
+    // like it.
+    let spawned = workers.spawned.lock().unwrap().clone();
+    let watchers = spawned.iter().map(ChildRef::watch).collect::<Vec<_>>();
+    let acceptor = children.elems()[0].watch();
+    children.stop().unwrap();
+    let termination = run!(acceptor);
+    for watcher in watchers {
+        assert_eq!(run!(watcher), termination);
+    }
+}
@@ -0,0 +1,114 @@
+#![cfg(feature = "supervision")]
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+// Counts the `u64`s its elements are told, faulting once told `0`.
+fn counting(name: &'static str, stable: bool, told: Arc<AtomicUsize>) -> ChildrenRef {
+    Bastion::children(move |children| {
+        let told = told.clone();
+        children
+            .with_name(name)
+            .with_redundancy(2)
+            .with_stable_ids(stable)
+            .with_exec(move |ctx: BastionContext| {
+                let told = told.clone();
+                async move {
+                    loop {
+                        msg! { ctx.recv().await?,
+                            n: u64 => {
+                                if n == 0 {
+                                    return Err(());
+                                }
+
+                                told.fetch_add(1, Ordering::SeqCst);
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .unwrap()
+}
+
+fn ids(children: &ChildrenRef) -> Vec<BastionId> {
+    children
+        .elems()
+        .iter()
+        .map(|elem| elem.id().clone())
+        .collect()
+}
+
+#[test]
+fn kept_when_deployed_again() {
+    let config = Config::new()
+        .with_root_restart_window(3, Duration::from_secs(60))
+        .on_root_exhaustion(RootAction::Degrade { keep: vec![] });
+    Bastion::init_with(config);
+    Bastion::start();
+
+    let told = Arc::new(AtomicUsize::new(0));
+    let stable = counting("stable", true, told.clone());
+    let plain = counting("plain", false, Arc::default());
+    let stable_ids = ids(&stable);
+
+    // The elements restarted by their supervisor keep their ids
+    // either way, which doesn't add elements to their group.
+    let elem = stable.elems()[0].clone();
+    elem.tell_anonymously(0u64).unwrap();
+    wait_for(|| elem.stats().restarts() == 1);
+    let restarted = Bastion::children_of("stable").unwrap();
+    assert_eq!(ids(&restarted), stable_ids);
+
+    // Faults as soon as it starts, until told otherwise, degrading
+    // the system.
+    let faulting = Arc::new(AtomicBool::new(true));
+    let hot = faulting.clone();
+    Bastion::children(move |children| {
+        let hot = hot.clone();
+        children
+            .with_name("hot")
+            .with_exec(move |_: BastionContext| {
+                let hot = hot.clone();
+                async move {
+                    if hot.load(Ordering::SeqCst) {
+                        return Err(());
+                    }
+
+                    Ok(())
+                }
+            })
+    })
+    .unwrap();
+
+    wait_for(Bastion::is_degraded);
+    wait_for(|| Bastion::children_of("stable").is_none());
+    faulting.store(false, Ordering::SeqCst);
+    Bastion::recover();
+    wait_for(|| Bastion::children_of("stable").is_some());
+    wait_for(|| Bastion::children_of("plain").is_some());
+
+    // Each index takes the id it had before, while the group keeps
+    // its redundancy...
+    let deployed = Bastion::children_of("stable").unwrap();
+    assert_eq!(ids(&deployed), stable_ids);
+    let plain_ids = ids(&Bastion::children_of("plain").unwrap());
+    assert_eq!(plain_ids.len(), 2);
+    assert!(plain_ids.iter().all(|id| !ids(&plain).contains(id)));
+
+    // ...and the `ChildRef`s retrieved before reach the elements
+    // replacing the ones they referenced.
+    for elem in stable.elems() {
+        elem.tell_anonymously(1u64).unwrap();
+    }
+    wait_for(|| told.load(Ordering::SeqCst) == 2);
+    assert!(plain.elems()[0].tell_anonymously(1u64).is_err());
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
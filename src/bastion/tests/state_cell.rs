@@ -0,0 +1,65 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+use std::sync::{Arc, Mutex};
+
+type Log = Arc<Mutex<Vec<&'static str>>>;
+
+// A state recording when it is dropped.
+#[derive(Debug)]
+struct Flushed(Log);
+
+impl Drop for Flushed {
+    fn drop(&mut self) {
+        self.0.lock().unwrap().push("state dropped");
+    }
+}
+
+#[test]
+fn dropped_after_groups() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+
+    Bastion::init();
+    let dropped = log.clone();
+    let cell = Bastion::state_cell(move || Flushed(dropped));
+    assert!(log.lock().unwrap().is_empty());
+
+    let unknown = Arc::new(Mutex::new(None));
+    let stopped = log.clone();
+    let callbacks = Callbacks::new().with_after_stop(move || {
+        stopped.lock().unwrap().push("group stopped");
+    });
+    let accessed = unknown.clone();
+    Bastion::children(|children| {
+        children
+            .with_callbacks(callbacks)
+            .with_exec(move |ctx: BastionContext| {
+                let accessed = accessed.clone();
+                async move {
+                    // The element keeps the state until it stops.
+                    let _state = ctx.global::<Flushed>()?;
+                    *accessed.lock().unwrap() = Some(ctx.global::<Vec<u8>>().map(|_| ()));
+
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    Bastion::start();
+    wait_for(|| unknown.lock().unwrap().is_some());
+    assert_eq!(
+        unknown.lock().unwrap().take(),
+        Some(Err(StateError::Unknown))
+    );
+    assert!(cell.get().is_ok());
+    assert!(log.lock().unwrap().is_empty());
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+    assert_eq!(*log.lock().unwrap(), vec!["group stopped", "state dropped"]);
+    assert_eq!(cell.get().unwrap_err(), StateError::Dropped);
+}
@@ -0,0 +1,38 @@
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+// A state counting how many instances were constructed.
+#[derive(Debug)]
+struct Instance(usize);
+
+#[test]
+fn fresh_state_per_cycle() {
+    let constructed = Arc::new(AtomicUsize::new(0));
+    let construct = |constructed: &Arc<AtomicUsize>| {
+        let constructed = constructed.clone();
+        Bastion::state_cell(move || Instance(constructed.fetch_add(1, Ordering::SeqCst)))
+    };
+
+    Bastion::init();
+    let first = construct(&constructed);
+    // Creating it again returns the same cell.
+    assert_eq!(construct(&constructed).get().unwrap().0, 0);
+    assert_eq!(first.get().unwrap().0, 0);
+    Bastion::start();
+    Bastion::stop();
+    Bastion::block_until_stopped();
+    assert_eq!(first.get().unwrap_err(), StateError::Dropped);
+
+    // The second system constructs a new state, and the first
+    // system's cell doesn't give access to it.
+    Bastion::init();
+    let second = construct(&constructed);
+    assert_eq!(second.get().unwrap().0, 1);
+    assert_eq!(first.get().unwrap_err(), StateError::Dropped);
+    assert_eq!(constructed.load(Ordering::SeqCst), 2);
+    Bastion::start();
+    Bastion::stop();
+    Bastion::block_until_stopped();
+    assert_eq!(second.get().unwrap_err(), StateError::Dropped);
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use bastion::children_ref::GroupState;
+use bastion::prelude::*;
+use common::init_start;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+// Counts the elements whose future was dropped.
+struct Guard(Arc<AtomicUsize>);
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        // Makes the cleanup slow enough for `stop_and_wait` to
+        // return before it finished if it didn't wait for it.
+        thread::sleep(Duration::from_millis(50));
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn guarded(dropped: Arc<AtomicUsize>) -> ChildrenRef {
+    Bastion::children(move |children| {
+        let dropped = dropped.clone();
+        children
+            .with_redundancy(3)
+            .with_exec(move |ctx: BastionContext| {
+                let guard = Guard(dropped.clone());
+                async move {
+                    let _guard = guard;
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+    })
+    .unwrap()
+}
+
+#[test]
+fn waits_for_elements() {
+    init_start();
+
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let children = guarded(dropped.clone());
+
+    let state = run!(children.stop_and_wait()).unwrap();
+    assert_eq!(state, GroupState::Stopped);
+    assert_eq!(dropped.load(Ordering::SeqCst), 3);
+    assert_eq!(children.state(), GroupState::Stopped);
+}
+
+#[test]
+fn already_stopped() {
+    init_start();
+
+    let children = guarded(Arc::new(AtomicUsize::new(0)));
+    run!(children.stop_and_wait()).unwrap();
+
+    let err = run!(children.stop_and_wait()).unwrap_err();
+    assert_eq!(err, ShutdownError::AlreadyStopped);
+}
+
+#[test]
+fn concurrent_waits() {
+    init_start();
+
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let children = guarded(dropped.clone());
+
+    // Both futures resolve once the group terminated.
+    let first = children.stop_and_wait();
+    let second = children.stop_and_wait();
+    let waiting = thread::spawn(move || run!(second));
+    assert_eq!(run!(first).unwrap(), GroupState::Stopped);
+    assert_eq!(waiting.join().unwrap().unwrap(), GroupState::Stopped);
+    assert_eq!(dropped.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,68 @@
+use bastion::prelude::*;
+use futures::future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const MESSAGES: usize = 20;
+
+// A group handling each `usize` it receives for a while before
+// sending it to the next stage, if there is one.
+fn stage(next: Option<ChildrenRef>, handled: &Arc<AtomicUsize>) -> ChildrenRef {
+    let handled = handled.clone();
+    Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let next = next.clone();
+            let handled = handled.clone();
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        ref n: usize => {
+                            thread::sleep(Duration::from_millis(10));
+                            if let Some(next) = &next {
+                                next.broadcast(*n).unwrap();
+                            }
+                            handled.fetch_add(1, Ordering::SeqCst);
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .unwrap()
+}
+
+#[test]
+fn stops_once_idle() {
+    Bastion::init();
+    Bastion::start();
+
+    let handled = Arc::new(AtomicUsize::new(0));
+    let sink = stage(None, &handled);
+    let source = stage(Some(sink), &handled);
+    // A group that is never idle, whose activity is ignored.
+    Bastion::children(|children| {
+        children
+            .with_name("always-on")
+            .with_exec(|_| future::pending::<Result<(), ()>>())
+    })
+    .unwrap();
+
+    for n in 0..MESSAGES {
+        source.broadcast(n).unwrap();
+    }
+
+    let started = Instant::now();
+    Bastion::stop_when_idle(IdleCriteria {
+        quiescent_for: Duration::from_millis(100),
+        ignore_groups: vec!["always-on".to_string()],
+    });
+    Bastion::block_until_stopped();
+
+    // Both stages handled every message, which takes longer than
+    // the quiescence window.
+    assert_eq!(handled.load(Ordering::SeqCst), 2 * MESSAGES);
+    assert!(started.elapsed() >= Duration::from_millis(MESSAGES as u64 * 10));
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use bastion::children_ref::GroupState;
+use bastion::prelude::*;
+use common::init_start;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// A group whose element takes some time to handle the `u64`s it is
+// told, and never finishes handling a `&str`.
+fn slow(handled: &Arc<AtomicUsize>) -> ChildrenRef {
+    let handled = handled.clone();
+    Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let handled = handled.clone();
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        _msg: u64 => {
+                            thread::sleep(Duration::from_millis(20));
+                            handled.fetch_add(1, Ordering::SeqCst);
+                        };
+                        _msg: &'static str => {
+                            futures::future::pending::<()>().await;
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .unwrap()
+}
+
+#[test]
+fn stops_once_done() {
+    init_start();
+
+    let handled = Arc::new(AtomicUsize::new(0));
+    let children = slow(&handled);
+    let elem = &children.elems()[0];
+    for n in 0..10u64 {
+        elem.tell_anonymously(n).unwrap();
+    }
+
+    let stopping = children.stop_with_timeout(Duration::from_secs(5));
+    assert_eq!(run!(stopping), Ok(GroupState::Stopped));
+    // The element handled all its messages before stopping.
+    assert_eq!(handled.load(Ordering::SeqCst), 10);
+}
+
+#[test]
+fn kills_once_timed_out() {
+    init_start();
+
+    let handled = Arc::new(AtomicUsize::new(0));
+    let children = slow(&handled);
+    children.elems()[0].tell_anonymously("stuck").unwrap();
+
+    let started = Instant::now();
+    let stopping = children.stop_with_timeout(Duration::from_millis(200));
+    assert_eq!(run!(stopping), Ok(GroupState::Killed));
+    assert!(started.elapsed() >= Duration::from_millis(200));
+
+    let stopping = children.stop_with_timeout(Duration::from_millis(200));
+    assert_eq!(run!(stopping), Err(ShutdownError::AlreadyStopped));
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const ELEMS: usize = 3;
+
+#[test]
+fn switch_to_one_for_all() {
+    Bastion::init();
+
+    let starts = Arc::new(AtomicUsize::new(0));
+    let counter = starts.clone();
+
+    let mut children = None;
+    let supervisor = Bastion::supervisor(|sp| {
+        let sp = sp.with_strategy(SupervisionStrategy::OneForOne);
+        children = Some(
+            sp.children_ref(move |children| {
+                children
+                    .with_redundancy(ELEMS)
+                    .with_exec(move |ctx: BastionContext| {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                        async move {
+                            loop {
+                                msg! { ctx.recv().await?,
+                                    msg: &'static str => {
+                                        if msg == "panic" {
+                                            panic!("Element panicked.");
+                                        }
+                                    };
+                                    _: _ => ();
+                                }
+                            }
+                        }
+                    })
+            })
+            .unwrap(),
+        );
+        sp
+    })
+    .unwrap();
+    let children = children.unwrap();
+
+    Bastion::start();
+    wait_for(|| starts.load(Ordering::SeqCst) == ELEMS);
+
+    // Only the faulted element is restarted...
+    children.elems()[0].tell_anonymously("panic").unwrap();
+    wait_for(|| starts.load(Ordering::SeqCst) == ELEMS + 1);
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(starts.load(Ordering::SeqCst), ELEMS + 1);
+
+    // ...until the strategy is changed.
+    supervisor.strategy(SupervisionStrategy::OneForAll).unwrap();
+    children.elems()[1].tell_anonymously("panic").unwrap();
+    wait_for(|| starts.load(Ordering::SeqCst) == 2 * ELEMS + 1);
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(starts.load(Ordering::SeqCst), 2 * ELEMS + 1);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+// Configures a group counting the starts of its element, which
+// faults when it receives "fault".
+fn counting(children: Children, starts: &Arc<AtomicUsize>) -> Children {
+    let starts = starts.clone();
+    children.with_exec(move |ctx: BastionContext| {
+        let starts = starts.clone();
+        async move {
+            starts.fetch_add(1, Ordering::SeqCst);
+
+            loop {
+                msg! { ctx.recv().await?,
+                    msg: &'static str => {
+                        if msg == "fault" {
+                            return Err(());
+                        }
+                    };
+                    _: _ => ();
+                }
+            }
+        }
+    })
+}
+
+#[test]
+fn added_once_started() {
+    Bastion::init();
+
+    let before = Arc::new(AtomicUsize::new(0));
+    let after = Arc::new(AtomicUsize::new(0));
+    let supervisor =
+        Bastion::supervisor(|sp| sp.with_strategy(SupervisionStrategy::OneForAll)).unwrap();
+    supervisor
+        .children(|children| counting(children, &before))
+        .unwrap();
+
+    Bastion::start();
+    wait_for(|| before.load(Ordering::SeqCst) == 1);
+
+    // The group added to the running supervisor is started right
+    // away...
+    let late = supervisor
+        .children(|children| counting(children, &after))
+        .unwrap();
+    wait_for(|| after.load(Ordering::SeqCst) == 1);
+
+    // ...and its faults are handled using the supervisor's
+    // strategy, restarting its sibling too.
+    late.elems()[0].tell_anonymously("fault").unwrap();
+    wait_for(|| after.load(Ordering::SeqCst) == 2);
+    wait_for(|| before.load(Ordering::SeqCst) == 2);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
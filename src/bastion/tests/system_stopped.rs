@@ -0,0 +1,83 @@
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+// Counts the elements whose future was dropped.
+struct Guard(Arc<AtomicUsize>);
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        // Makes the cleanup slow enough for the system to be
+        // reported as stopped before it finished if it didn't wait
+        // for it.
+        thread::sleep(Duration::from_millis(50));
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn stopped() {
+    Bastion::init();
+
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let guarded = dropped.clone();
+    Bastion::children(move |children| {
+        let guarded = guarded.clone();
+        children
+            .with_redundancy(3)
+            .with_exec(move |ctx: BastionContext| {
+                let guard = Guard(guarded.clone());
+                async move {
+                    let _guard = guard;
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    // Faults continuously once the system starts stopping.
+    let stopping = Arc::new(AtomicBool::new(false));
+    let faulting = stopping.clone();
+    Bastion::children(move |children| {
+        let faulting = faulting.clone();
+        children
+            .with_redundancy(2)
+            .with_exec(move |ctx: BastionContext| {
+                let faulting = faulting.clone();
+                async move {
+                    while !faulting.load(Ordering::SeqCst) {
+                        ctx.sleep(Duration::from_millis(1)).await;
+                    }
+
+                    Err(())
+                }
+            })
+    })
+    .unwrap();
+
+    let (sender, recver) = mpsc::channel();
+    let waiting = thread::spawn(move || {
+        run!(Bastion::stopped());
+        sender.send(()).unwrap();
+    });
+
+    Bastion::start();
+    assert!(recver.recv_timeout(Duration::from_millis(100)).is_err());
+
+    stopping.store(true, Ordering::SeqCst);
+    thread::sleep(Duration::from_millis(10));
+    Bastion::stop();
+
+    // The future resolves once every group stopped running.
+    recver.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(dropped.load(Ordering::SeqCst), 3);
+    waiting.join().unwrap();
+
+    // It resolves right away once the system stopped.
+    run!(Bastion::stopped());
+    Bastion::block_until_stopped();
+}
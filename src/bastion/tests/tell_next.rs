@@ -0,0 +1,98 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::sync::{Arc, Mutex};
+
+type Received = Arc<Mutex<Vec<BastionId>>>;
+
+// A group of `redundancy` elements recording their id when they
+// receive a `&str`.
+fn recording(redundancy: usize) -> (ChildrenRef, Received) {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let recorded = received.clone();
+    let children = Bastion::children(move |children| {
+        let received = recorded.clone();
+        children
+            .with_redundancy(redundancy)
+            .with_exec(move |ctx: BastionContext| {
+                let received = received.clone();
+                async move {
+                    loop {
+                        msg! { ctx.recv().await?,
+                            _msg: &'static str => {
+                                received.lock().unwrap().push(ctx.current().id().clone());
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .unwrap();
+
+    (children, received)
+}
+
+fn count(received: &Received, elem: &ChildRef) -> usize {
+    let received = received.lock().unwrap();
+    received.iter().filter(|id| *id == elem.id()).count()
+}
+
+#[test]
+fn rotates() {
+    init_start();
+
+    let (children, received) = recording(4);
+    // The messages are only sent to each element in turn once
+    // they are all warm.
+    wait_for(|| children.warm_elems() == 4);
+    for _ in 0..8 {
+        children.tell_next("rotated").unwrap();
+    }
+    wait_for(|| received.lock().unwrap().len() == 8);
+
+    for elem in children.elems() {
+        assert_eq!(count(&received, elem), 2);
+    }
+}
+
+#[test]
+fn skips_stopped_elems() {
+    init_start();
+
+    let (children, received) = recording(3);
+    let stopped = children.elems()[0].clone();
+    wait_for(|| children.warm_elems() == 3);
+    stopped.stop().unwrap();
+    wait_for(|| children.warm_elems() == 2);
+
+    for _ in 0..6 {
+        children.tell_next("rotated").unwrap();
+    }
+    wait_for(|| received.lock().unwrap().len() == 6);
+
+    assert_eq!(count(&received, &stopped), 0);
+    for elem in &children.elems()[1..] {
+        assert_eq!(count(&received, elem), 3);
+    }
+}
+
+#[test]
+fn ignores_routing_mode() {
+    init_start();
+
+    let (children, received) = recording(3);
+    wait_for(|| children.warm_elems() == 3);
+    let pinned = children.elems()[0].id().clone();
+    run!(children.set_routing(Routing::Pinned(pinned))).unwrap();
+
+    for _ in 0..6 {
+        children.tell_next("rotated").unwrap();
+    }
+    wait_for(|| received.lock().unwrap().len() == 6);
+
+    for elem in children.elems() {
+        assert_eq!(count(&received, elem), 2);
+    }
+}
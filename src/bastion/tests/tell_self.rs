@@ -0,0 +1,146 @@
+mod common;
+
+use bastion::children_ref::GroupState;
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// A group whose element tells itself `0..n` when it is told `n`,
+// taking `delay` to record each of them.
+fn looping(delay: Duration) -> (ChildrenRef, Arc<Mutex<Vec<u32>>>) {
+    let handled = Arc::new(Mutex::new(Vec::new()));
+    let recorded = handled.clone();
+    let children = Bastion::children(move |children| {
+        let recorded = recorded.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let recorded = recorded.clone();
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        n: u64 => {
+                            for step in 0..n as u32 {
+                                ctx.tell_self(step).unwrap();
+                            }
+                        };
+                        step: u32 => {
+                            ctx.sleep(delay).await;
+                            recorded.lock().unwrap().push(step);
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    (children, handled)
+}
+
+#[test]
+fn in_order() {
+    init_start();
+
+    let (children, handled) = looping(Duration::from_millis(0));
+    children.elems()[0].tell_anonymously(50u64).unwrap();
+    wait_for(|| handled.lock().unwrap().len() == 50);
+    assert_eq!(*handled.lock().unwrap(), (0..50).collect::<Vec<_>>());
+}
+
+#[test]
+fn survives_drain() {
+    init_start();
+
+    let (children, handled) = looping(Duration::from_millis(20));
+    children.elems()[0].tell_anonymously(5u64).unwrap();
+    wait_for(|| !handled.lock().unwrap().is_empty());
+
+    // The element handles the messages it told itself before
+    // stopping.
+    let stopping = children.stop_with_timeout(Duration::from_secs(5));
+    assert_eq!(run!(stopping), Ok(GroupState::Stopped));
+    assert_eq!(*handled.lock().unwrap(), vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn dropped_on_kill() {
+    init_start();
+
+    let (children, handled) = looping(Duration::from_millis(50));
+    children.elems()[0].tell_anonymously(5u64).unwrap();
+    wait_for(|| !handled.lock().unwrap().is_empty());
+
+    children.kill().unwrap();
+    thread::sleep(Duration::from_millis(400));
+    assert!(handled.lock().unwrap().len() < 5);
+}
+
+#[test]
+fn after_delay() {
+    init_start();
+
+    let received = Arc::new(AtomicUsize::new(0));
+    let counted = received.clone();
+    let started = Instant::now();
+    Bastion::children(move |children| {
+        let counted = counted.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let counted = counted.clone();
+            async move {
+                ctx.tell_self_after("later", Duration::from_millis(100))
+                    .unwrap();
+                msg! { ctx.recv().await?,
+                    _msg: &'static str => {
+                        counted.fetch_add(1, Ordering::SeqCst);
+                    };
+                    _: _ => ();
+                }
+
+                Ok(())
+            }
+        })
+    })
+    .unwrap();
+
+    wait_for(|| received.load(Ordering::SeqCst) == 1);
+    assert!(started.elapsed() >= Duration::from_millis(100));
+}
+
+#[test]
+fn wakes_waiting() {
+    init_start();
+
+    let received = Arc::new(Mutex::new(None));
+    let recorded = received.clone();
+    Bastion::children(move |children| {
+        let recorded = recorded.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let recorded = recorded.clone();
+            async move {
+                // The message is told by another task while the
+                // element is waiting for one.
+                let ctx = Arc::new(ctx);
+                let telling = ctx.clone();
+                spawn! {
+                    telling.sleep(Duration::from_millis(50)).await;
+                    telling.tell_self(42u32).unwrap();
+                };
+                let msg = ctx.recv().await;
+                msg! { msg?,
+                    n: u32 => {
+                        *recorded.lock().unwrap() = Some(n);
+                    };
+                    _: _ => ();
+                }
+
+                Ok(())
+            }
+        })
+    })
+    .unwrap();
+
+    wait_for(|| *received.lock().unwrap() == Some(42));
+}
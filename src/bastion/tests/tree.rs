@@ -0,0 +1,210 @@
+#![cfg(feature = "introspection")]
+mod common;
+
+use bastion::prelude::*;
+use bastion::tree::{SupervisorNode, TreeSnapshot, SCHEMA_VERSION};
+use common::wait_for;
+use futures::StreamExt;
+use std::sync::Once;
+use std::time::{Duration, Instant};
+
+static START: Once = Once::new();
+
+// Creates a supervisor supervising the "alpha" group, along with
+// the top-level "beta" group.
+fn init_start() {
+    START.call_once(|| {
+        Bastion::init();
+        Bastion::start();
+
+        let supervisor =
+            Bastion::supervisor(|sp| sp.with_strategy(SupervisionStrategy::OneForAll)).unwrap();
+        supervisor
+            .children(|children| children.with_name("alpha").with_redundancy(2))
+            .unwrap();
+        Bastion::children(|children| children.with_name("beta")).unwrap();
+
+        wait_for(|| {
+            let tree = Bastion::tree();
+            let groups = tree
+                .supervisors()
+                .iter()
+                .flat_map(|supervisor| supervisor.groups())
+                .collect::<Vec<_>>();
+            groups.len() == 2
+                && groups
+                    .iter()
+                    .all(|group| group.initialized() == group.elems().len())
+        });
+    });
+}
+
+// The identifiers of the supervisor's subtree, in the order they
+// are exported in.
+fn ids(supervisor: &SupervisorNode, ids: &mut Vec<String>) {
+    ids.push(supervisor.id().to_string());
+    for supervisor in supervisor.supervisors() {
+        self::ids(supervisor, ids);
+    }
+
+    for group in supervisor.groups() {
+        ids.push(group.id().to_string());
+        ids.extend(group.elems().iter().map(|elem| elem.id().to_string()));
+    }
+}
+
+// Replaces the identifiers of the tree by their index, for the
+// exports not to depend on them.
+fn normalize(export: &str, tree: &TreeSnapshot) -> String {
+    let mut all = Vec::new();
+    for supervisor in tree.supervisors().iter().chain(tree.system()) {
+        ids(supervisor, &mut all);
+    }
+
+    let mut export = export.to_string();
+    for (index, id) in all.iter().enumerate() {
+        export = export.replace(id.as_str(), &format!("#{}", index));
+    }
+
+    export
+}
+
+const GROUP_POLICIES: &str = "{\"restart_policy\":\"always\",\"max_tries\":null,\
+                              \"backoff\":\"immediate\",\"backoff_ms\":null,\
+                              \"backoff_multiplier\":null,\"restart_window\":null,\
+                              \"restart_backoff\":null,\
+                              \"pre_start_watermark\":1000,\"mailbox_capacity\":null,\
+                              \"overflow_policy\":\"fail\",\"drain_timeout_ms\":5000,\
+                              \"requeue_on_restart\":true,\"preset\":null}";
+
+#[test]
+fn json() {
+    init_start();
+
+    let tree = Bastion::tree();
+    let json = normalize(&tree.to_json(), &tree);
+    // The root supervisor supervises "beta", and the system
+    // "alpha"'s supervisor.
+    let expected = format!(
+        "{{\"version\":3,\"supervisors\":[\
+         {{\"id\":\"#0\",\"strategy\":\"one_for_one\",\"supervisors\":[],\"groups\":[\
+         {{\"id\":\"#1\",\"name\":\"beta\",\"state\":\"running\",\"restart_attempt\":null,\
+         \"policies\":{p},\"routing\":\"round_robin\",\"pinned_to\":null,\
+         \"elem_count\":1,\"launched\":1,\"initialized\":1,\"elems\":[{{\"id\":\"#2\",\"generation\":0}}]}}]}},\
+         {{\"id\":\"#3\",\"strategy\":\"one_for_all\",\"supervisors\":[],\"groups\":[\
+         {{\"id\":\"#4\",\"name\":\"alpha\",\"state\":\"running\",\"restart_attempt\":null,\
+         \"policies\":{p},\"routing\":\"round_robin\",\"pinned_to\":null,\
+         \"elem_count\":2,\"launched\":2,\"initialized\":2,\"elems\":[\
+         {{\"id\":\"#5\",\"generation\":0}},{{\"id\":\"#6\",\"generation\":0}}]}}]}}],\
+         \"system\":{{\"id\":\"#7\",",
+        p = GROUP_POLICIES
+    );
+    assert!(json.starts_with(&expected), "{}", json);
+}
+
+// Returns the keys of the document's objects, in the order they
+// first appear in.
+fn keys(json: &str) -> Vec<&str> {
+    let parts = json.split('"').collect::<Vec<_>>();
+    let mut keys = Vec::new();
+    for index in (1..parts.len()).step_by(2) {
+        let key = parts[index];
+        let is_key = parts
+            .get(index + 1)
+            .is_some_and(|next| next.starts_with(':'));
+        if is_key && !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+
+    keys
+}
+
+#[test]
+fn schema() {
+    init_start();
+
+    // The version has to be increased whenever the keys change.
+    assert_eq!(SCHEMA_VERSION, 3);
+    let json = Bastion::tree().to_json();
+    let expected = vec![
+        "version",
+        "supervisors",
+        "id",
+        "strategy",
+        "groups",
+        "name",
+        "state",
+        "restart_attempt",
+        "policies",
+        "restart_policy",
+        "max_tries",
+        "backoff",
+        "backoff_ms",
+        "backoff_multiplier",
+        "restart_window",
+        "restart_backoff",
+        "pre_start_watermark",
+        "mailbox_capacity",
+        "overflow_policy",
+        "drain_timeout_ms",
+        "requeue_on_restart",
+        "preset",
+        "routing",
+        "pinned_to",
+        "elem_count",
+        "launched",
+        "initialized",
+        "elems",
+        "generation",
+        "system",
+    ];
+    assert_eq!(keys(&json), expected, "{}", json);
+}
+
+#[test]
+fn dot() {
+    init_start();
+
+    let tree = Bastion::tree();
+    let dot = tree.to_dot();
+    // The output doesn't depend on the order the nodes are stored
+    // in.
+    assert_eq!(dot, Bastion::tree().to_dot());
+
+    let dot = normalize(&dot, &tree);
+    let expected = "digraph bastion {
+    \"#0\" [label=\"supervisor\\none_for_one\", shape=box];
+    \"#1\" [label=\"beta\\nrunning\", shape=ellipse, style=filled, fillcolor=palegreen];
+    \"#2\" [label=\"0\", shape=circle];
+    \"#1\" -> \"#2\";
+    \"#0\" -> \"#1\";
+    \"#3\" [label=\"supervisor\\none_for_all\", shape=box];
+    \"#4\" [label=\"alpha\\nrunning\", shape=ellipse, style=filled, fillcolor=palegreen];
+    \"#5\" [label=\"0\", shape=circle];
+    \"#4\" -> \"#5\";
+    \"#6\" [label=\"0\", shape=circle];
+    \"#4\" -> \"#6\";
+    \"#3\" -> \"#4\";
+    subgraph cluster_system {
+        label=\"system\";
+        \"#7\" [label=\"supervisor\\none_for_one\", shape=box];
+";
+    assert!(dot.starts_with(expected), "{}", dot);
+    assert!(dot.ends_with("    }\n}\n"), "{}", dot);
+}
+
+#[test]
+fn watch() {
+    init_start();
+
+    let started = Instant::now();
+    let snapshots = Bastion::tree_watch(Duration::from_millis(50)).take(3);
+    let snapshots = run!(snapshots.collect::<Vec<_>>());
+
+    assert_eq!(snapshots.len(), 3);
+    assert!(started.elapsed() >= Duration::from_millis(100));
+    assert!(snapshots
+        .iter()
+        .all(|tree| tree.supervisors()[0].groups()[0].name() == Some("beta")));
+}
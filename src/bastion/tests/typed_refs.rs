@@ -0,0 +1,113 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+// Creates a group of typed elements doubling the `u64`s they are
+// asked, and counting the ones they are told in `told`.
+fn doubling(told: Arc<AtomicUsize>) -> TypedChildrenRef<u64> {
+    Bastion::typed_children(move |children| {
+        let told = told.clone();
+        children
+            .with_redundancy(2)
+            .with_exec(move |ctx: BastionContext| {
+                let told = told.clone();
+                async move {
+                    loop {
+                        match ctx.recv_msg::<u64>().await {
+                            (TypedMsg::Ask { msg, sender }, _) => {
+                                sender.answer(&ctx, msg * 2).unwrap();
+                            }
+                            (TypedMsg::Tell(_), _) | (TypedMsg::Broadcast(_), _) => {
+                                told.fetch_add(1, Ordering::SeqCst);
+                            }
+                        }
+                    }
+                }
+            })
+    })
+    .unwrap()
+}
+
+#[test]
+fn typed_refs() {
+    init_start();
+
+    let told = Arc::new(AtomicUsize::new(0));
+    let typed = doubling(told.clone());
+    let children = typed.as_untyped().clone();
+    assert!(children.elems()[0].typed::<u32>().is_none());
+    assert!(children.elems()[0].typed::<u64>().is_some());
+    assert_eq!(typed.id(), children.id());
+    assert_eq!(typed.elems().len(), 2);
+
+    let answer = typed.ask_one(21).unwrap();
+    let answer = run!(answer).unwrap();
+    msg! { answer,
+        n: u64 => assert_eq!(n, 42);
+        _: _ => panic!("Unexpected answer.");
+    }
+
+    for elem in typed.elems() {
+        elem.tell(1).unwrap();
+        let answer = run!(elem.ask(2).unwrap()).unwrap();
+        msg! { answer,
+            n: u64 => assert_eq!(n, 4);
+            _: _ => panic!("Unexpected answer.");
+        }
+    }
+    typed.broadcast(1).unwrap();
+    wait_for(|| told.load(Ordering::SeqCst) == 4);
+
+    // The typed references convert back into the untyped ones.
+    let elem: ChildRef = typed.elems()[0].clone().into();
+    assert_eq!(elem.id(), children.elems()[0].id());
+    let untyped: ChildrenRef = typed.into();
+    assert_eq!(untyped.id(), children.id());
+}
+
+#[test]
+fn other_types_rejected() {
+    init_start();
+
+    let told = Arc::new(AtomicUsize::new(0));
+    let children = doubling(told.clone());
+    let elem = children.elems()[0].clone();
+
+    // The messages of other types skip the type checks, without
+    // being handled by the element.
+    elem.as_untyped()
+        .tell_unchecked("A message of another type.")
+        .unwrap();
+    elem.tell(1).unwrap();
+    wait_for(|| told.load(Ordering::SeqCst) == 1);
+    wait_for(|| elem.as_untyped().stats().rejected() == 1);
+
+    let untyped = children.as_untyped().elems()[0].clone();
+    assert!(untyped
+        .tell_anonymously("A message of another type.")
+        .is_err());
+}
+
+#[test]
+fn accepted_types_overridden() {
+    init_start();
+
+    // The group only accepts `u64`s, whatever it was configured to
+    // accept.
+    let typed: TypedChildrenRef<u64> = Bastion::typed_children(|children| {
+        children
+            .with_accepted_types::<(u64, &'static str)>()
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    ctx.recv_msg::<u64>().await;
+                }
+            })
+    })
+    .unwrap();
+    let elem = typed.as_untyped().elems()[0].clone();
+    assert!(elem.typed::<u64>().is_some());
+    assert!(elem.tell_anonymously("A message of another type.").is_err());
+}
@@ -0,0 +1,170 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use futures::future;
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// The elements of a group warm up right away, except the ones
+// after the first `immediate` ones, which wait for the gate to be
+// opened. Every element records its id when it receives a message.
+struct Warming {
+    immediate: usize,
+    open: Arc<AtomicBool>,
+    received: Arc<Mutex<Vec<BastionId>>>,
+}
+
+impl Warming {
+    fn new(immediate: usize) -> Self {
+        Warming {
+            immediate,
+            open: Arc::new(AtomicBool::new(false)),
+            received: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn children(&self, children: Children) -> Children {
+        let immediate = self.immediate;
+        let open = self.open.clone();
+        let received = self.received.clone();
+        let warming = Arc::new(AtomicUsize::new(0));
+
+        children
+            .with_warmup(
+                move |_: BastionContext| {
+                    let open = open.clone();
+                    let wait = warming.fetch_add(1, Ordering::SeqCst) >= immediate;
+                    async move {
+                        while wait && !open.load(Ordering::SeqCst) {
+                            Delay::new(Duration::from_millis(10)).await;
+                        }
+                    }
+                },
+                WarmupGate::UntilReady,
+            )
+            .with_exec(move |ctx: BastionContext| {
+                let received = received.clone();
+                async move {
+                    loop {
+                        ctx.recv().await?;
+                        received.lock().unwrap().push(ctx.current().id().clone());
+                    }
+                }
+            })
+    }
+
+    fn open(&self) {
+        self.open.store(true, Ordering::SeqCst);
+    }
+
+    fn received(&self) -> Vec<BastionId> {
+        self.received.lock().unwrap().clone()
+    }
+}
+
+#[test]
+fn avoids_warming_elems() {
+    init_start();
+
+    let warming = Warming::new(1);
+    let children =
+        Bastion::children(|children| warming.children(children).with_redundancy(3)).unwrap();
+    wait_for(|| children.warm_elems() == 1);
+    assert!(!children.is_ready());
+
+    for _ in 0..10 {
+        children.tell_one("routed").unwrap();
+    }
+    wait_for(|| warming.received().len() == 10);
+
+    // Only the warm element received them.
+    let received = warming.received();
+    assert!(received.iter().all(|id| id == &received[0]));
+
+    warming.open();
+    wait_for(|| children.warm_elems() == 3);
+    assert!(children.is_ready());
+
+    for _ in 0..30 {
+        children.tell_one("routed").unwrap();
+    }
+    wait_for(|| warming.received().len() == 40);
+
+    // The messages are now routed to every element.
+    let received = warming.received();
+    for elem in children.elems() {
+        let count = received.iter().filter(|id| *id == elem.id()).count();
+        assert!(count >= 10, "{} received {} messages.", elem.id(), count);
+    }
+}
+
+#[test]
+fn queued_until_warm() {
+    init_start();
+
+    let warming = Warming::new(0);
+    let children = Bastion::children(|children| {
+        warming
+            .children(children)
+            .with_mailbox_capacity(2)
+            .with_overflow_policy(OverflowPolicy::Fail)
+    })
+    .unwrap();
+
+    children.tell_one("queued").unwrap();
+    let answer = children.ask_one("queued").unwrap();
+    let err = children.tell_one("rejected").unwrap_err();
+    assert_eq!(err.kind(), SendErrorKind::MailboxFull);
+
+    thread::sleep(Duration::from_millis(100));
+    assert!(warming.received().is_empty());
+
+    warming.open();
+    wait_for(|| warming.received().len() == 2);
+    // The question was delivered (and dropped without an answer).
+    assert_eq!(run!(answer).unwrap_err(), AnswerError::Unanswered);
+}
+
+#[test]
+fn duration_gate() {
+    init_start();
+
+    let children = Bastion::children(|children| {
+        children
+            .with_warmup(
+                |_: BastionContext| future::pending(),
+                WarmupGate::Duration(Duration::from_millis(100)),
+            )
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    ctx.recv().await?;
+                }
+            })
+    })
+    .unwrap();
+
+    assert!(!children.is_ready());
+    wait_for(|| children.is_ready());
+    assert_eq!(children.warm_elems(), 1);
+}
+
+#[test]
+fn ready_fraction() {
+    init_start();
+
+    let warming = Warming::new(1);
+    let children = Bastion::children(|children| {
+        warming
+            .children(children)
+            .with_redundancy(2)
+            .with_ready_fraction(0.5)
+    })
+    .unwrap();
+
+    wait_for(|| children.is_ready());
+    assert_eq!(children.warm_elems(), 1);
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use futures::future::{self, Either};
+use futures_timer::Delay;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// A group whose element handles `&'static str`s: stopping on
+// "stop", returning an error on "error" and panicking on "panic".
+// The element records the references to itself each time it
+// starts.
+fn watched() -> (ChildrenRef, Arc<Mutex<Vec<ChildRef>>>) {
+    let started = Arc::new(Mutex::new(Vec::new()));
+    let recorded = started.clone();
+    let children = Bastion::children(move |children| {
+        let recorded = recorded.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            recorded.lock().unwrap().push(ctx.current().clone());
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        msg: &'static str => {
+                            match msg {
+                                "stop" => return Ok(()),
+                                "error" => return Err(()),
+                                "panic" => panic!("Asked to panic."),
+                                _ => (),
+                            }
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .unwrap();
+
+    wait_for(|| started.lock().unwrap().len() == 1);
+    (children, started)
+}
+
+fn first(started: &Mutex<Vec<ChildRef>>) -> ChildRef {
+    started.lock().unwrap()[0].clone()
+}
+
+fn watch(child: &ChildRef) -> Termination {
+    let timeout = Delay::new(Duration::from_secs(5));
+    match run!(future::select(child.watch(), timeout)) {
+        Either::Left((termination, _)) => termination,
+        Either::Right(_) => panic!("Timed out."),
+    }
+}
+
+#[test]
+fn stopped() {
+    init_start();
+
+    let (_, started) = watched();
+    let child = first(&started);
+    let watcher = child.watch();
+    child.tell_anonymously("stop").unwrap();
+    assert_eq!(watch(&child), Termination::Stopped);
+    assert_eq!(run!(watcher), Termination::Stopped);
+
+    // Watching an element that already terminated resolves right
+    // away.
+    assert_eq!(run!(child.watch()), Termination::Stopped);
+}
+
+#[test]
+fn faulted() {
+    init_start();
+
+    let (_, started) = watched();
+    let child = first(&started);
+    child.tell_anonymously("error").unwrap();
+    assert_eq!(watch(&child), Termination::Faulted(FaultReason::Errored));
+
+    // The restarted element is watched using the new references.
+    wait_for(|| started.lock().unwrap().len() == 2);
+    let restarted = started.lock().unwrap()[1].clone();
+    assert_eq!(restarted.id(), child.id());
+    restarted.tell_anonymously("panic").unwrap();
+    assert_eq!(
+        watch(&restarted),
+        Termination::Faulted(FaultReason::Panicked)
+    );
+}
+
+#[test]
+fn killed() {
+    init_start();
+
+    let (_, started) = watched();
+    let child = first(&started);
+    child.kill().unwrap();
+    assert_eq!(watch(&child), Termination::Killed);
+
+    // The elements dropped along with their group are killed too.
+    let (children, started) = watched();
+    let child = first(&started);
+    children.kill().unwrap();
+    assert_eq!(watch(&child), Termination::Killed);
+}
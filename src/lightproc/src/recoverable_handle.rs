@@ -12,6 +12,8 @@ use std::thread;
 /// Recoverable handle which encapsulates a standard Proc Handle and contain all panics inside.
 ///
 /// Execution of `after_panic` will be immediate on polling the [RecoverableHandle]'s future.
+/// Whatever the panic's payload is, it is dropped without being given to `after_panic`.
+/// Panics can only be contained if they unwind (i.e. not with `panic = "abort"`).
 pub struct RecoverableHandle<R>(pub(crate) ProcHandle<thread::Result<R>>);
 
 impl<R> RecoverableHandle<R> {
@@ -0,0 +1,24 @@
+#!/usr/bin/env bash
+# Checks that bastion compiles (along with its tests and examples)
+# with every combination of its optional features.
+set -eo pipefail
+
+cd "$(dirname "$0")/../src/bastion"
+
+FEATURES=(patterns introspection autoscaling supervision signals testing)
+# The combinations are checked in their own target directory, so
+# that the check doesn't wait for (or invalidate) the usual one.
+export CARGO_TARGET_DIR="${CARGO_TARGET_DIR:-../../target/features}"
+
+for ((mask = 0; mask < 1 << ${#FEATURES[@]}; mask++)); do
+  enabled=()
+  for i in "${!FEATURES[@]}"; do
+    if ((mask & 1 << i)); then
+      enabled+=("${FEATURES[$i]}")
+    fi
+  done
+
+  features="${enabled[*]}"
+  echo "Checking with features: [${features}]"
+  cargo check --lib --tests --examples --no-default-features --features "${features}"
+done